@@ -0,0 +1,145 @@
+# German UI strings. Messages missing here fall back to en.ftl.
+
+## Slash commands (suggestions)
+
+command-app = Anwendungen suchen und starten
+command-launch = Die am besten passende Anwendung starten
+command-apps = Apps in der Suche ausblenden oder wieder anzeigen
+command-file = Dateien suchen
+command-hash = Prüfsumme einer Datei oder eines Textes
+command-b64 = Base64 eines Textes oder der Zwischenablage
+command-url = Text oder Zwischenablage URL-kodieren
+command-json = JSON formatieren oder verkleinern
+command-uuid = Eine UUID erzeugen
+command-lorem = Platzhaltertext
+command-regex = Einen regulären Ausdruck an der Zwischenablage testen
+command-extract-to = Das mit „Entpacken nach…“ gewählte Archiv entpacken
+command-devices = Bluetooth- und Audiogeräte
+command-contacts = E-Mail-Adressen und Telefonnummern
+command-git = Aktionen für Git-Repositorys
+command-context = Lokalen Dateikontext verwalten
+command-clip = Verlauf der Zwischenablage, z. B. nur URLs
+command-ocr = Text aus dem kopierten Bild erkennen
+command-pad = Notizfenster öffnen oder schließen
+command-qr = QR-Code anzeigen oder vom Bildschirm scannen
+command-ask-clip = Die KI zum Inhalt der Zwischenablage fragen
+command-tpl = Die KI mit einer Vorlage fragen
+command-flow = Einen Ablauf aus der Konfiguration ausführen
+command-permissions = Prüfen und widerrufen, was Abläufe und Hooks dürfen
+command-memory = Gespeicherte Erinnerungen durchsuchen, kopieren und löschen
+command-fav = Favoriten anzeigen oder einen Quicklink markieren
+command-timer = Einen Countdown starten
+command-dnd = Nicht stören, auf Wunsch für eine Fokuszeit
+command-agenda = Anstehende Termine
+command-timeline = Zuletzt gestartete Apps, geöffnete Dateien, Kopien und Chats
+command-audit = Was gestartet, geöffnet und ausgeführt wurde, auch die Werkzeuge der KI
+command-cache = Zwischengespeicherte Symbole und Vorschaubilder anzeigen oder löschen
+command-clear = Gesprächsverlauf löschen
+command-providers = Verfügbare Anbieter anzeigen
+command-alias = Aliase verwalten
+command-saved = Gespeicherte Suchen ausführen, auflisten oder verwalten
+command-bm = Ordner mit Kürzel als Lesezeichen speichern
+command-doctor = Einrichtung prüfen und Lösungen vorschlagen
+command-debug = Letzte Anfragen an das Backend anzeigen
+command-settings = Einstellungen öffnen
+command-help = Hilfe anzeigen
+
+## /help
+
+commands-help =
+    Verfügbare Befehle:
+    /app <query>     - Anwendungen suchen und starten (sonst: KI); Enter wechselt zu einem offenen Fenster; Strg+O für eine neue Instanz oder Startoptionen
+    /launch <app>    - Die am besten passende Anwendung starten
+    /apps [name]     - Apps auflisten; Enter blendet eine in der Suche aus (oder wieder ein)
+    /file <query>    - Dateien suchen (Strg+O auf einer Datei: entpacken, Öffnen mit…; auf einem Ordner: Terminal, Editor)
+    /devices [name]  - Bluetooth-Geräte verbinden, Audioausgang/-eingang wechseln
+    /contacts [name] - E-Mail-Adresse oder Nummer kopieren oder eine E-Mail schreiben (Namen passen auch beim Tippen)
+    /git <repo>      - Öffnen, Branches auschecken, Remote kopieren, Status anzeigen
+    /hash [algo] <x> - MD5/SHA-1/SHA-256/BLAKE3 einer Datei oder eines Textes
+    /b64, /url       - encode|decode für Text (oder die Zwischenablage)
+    /json fmt|min    - JSON formatieren oder verkleinern (oder die Zwischenablage)
+    /uuid            - Eine zufällige UUID erzeugen
+    /lorem [n]       - n Absätze Platzhaltertext
+    /regex <pattern> - Einen regulären Ausdruck live an der Zwischenablage testen (bearbeitbar)
+    /context <path>  - Lokale Dateien als Kontext laden (zusätzlich zu den geladenen)
+    /context list    - Geladenen Kontext anzeigen; remove <n> entlädt einen
+    /clip [type] [x] - Verlauf der Zwischenablage, auf Wunsch nur URLs/E-Mails/Pfade/Farben/JSON/Code; Strg+O für Aktionen
+    /ocr             - Text aus dem kopierten Bild erkennen (auch in /clip; [clipboard] ocr_language)
+    /pad             - Notizfenster öffnen oder schließen, ein kleines Fenster im Vordergrund (speichert beim Tippen)
+    /qr [text]       - QR-Code zum Kopieren oder Speichern anzeigen; /qr allein scannt einen vom Bildschirm
+    /ask-clip <q>    - Die KI zum neuesten (oder gewählten) Eintrag der Zwischenablage fragen
+    /tpl <name> [x]  - Die KI mit einer [templates]-Vorlage fragen ({"{"}input{"}"}, {"{"}clipboard{"}"}, {"{"}selection{"}"})
+    /flow <name>     - Einen [flows]-Ablauf ausführen: Schritte query, pick-first, launch, copy, shell und ai-prompt
+    /permissions     - Was Abläufe und Hooks dürfen (Shell, Netzwerk, Dateien, Zwischenablage); Enter widerruft
+    /memory [query]  - Gespeicherte Erinnerungen durchsuchen; Enter zum Kopieren oder Löschen
+    /fav [quicklink] - Favoriten anzeigen oder einen Quicklink markieren/entfernen
+    /timer <t> [x]   - Countdown (90s, 25m, 1h30m); laufende Timer erscheinen bei leerer Eingabe
+    /dnd [on|off|t]  - Nicht stören (GNOME, KDE); mit Dauer kommen Benachrichtigungen danach zurück
+    /agenda [days]   - Anstehende Termine aus [calendar]-ics-Dateien und Evolution (auch "today"); Strg+O für den Meeting-Link
+    /timeline        - Gestartete Apps, geöffnete Dateien, Kopien und KI-Chats nach Tag; Enter springt zu einem zurück
+    /audit [since]   - Protokoll gestarteter Apps, geöffneter Dateien, Aktionen, Abläufe, Hooks und KI-Werkzeuge (today, yesterday, 3d, 2026-10-01)
+    /clear           - Gesprächsverlauf löschen
+    /providers       - Verfügbare Anbieter anzeigen
+    /alias           - Aliase verwalten (add <name> <expansion> | list | rm <name>)
+    /saved [name]    - Gespeicherte Suche ausführen oder alle auflisten (add <name> <suche> | rm <name>; auch bei leerer Eingabe)
+    /bm [add|rm]     - Ordner-Lesezeichen (add <Ordner> [,w] | rm <Ordner>); stehen bei kurzen Suchen oben, ,w öffnet eins zum Durchsuchen
+    /settings        - Einstellungen öffnen
+    /help            - Diese Hilfe anzeigen
+    > [action]       - Befehlspalette (Strg+Umschalt+P)
+    Ctrl+.           - Fenster geöffnet halten, wenn es den Fokus verliert
+    Ctrl+D           - Gewählte App oder Datei als Favorit markieren (Strg+Hoch/Runter sortiert Favoriten)
+    Ctrl+Space       - Einträge der Zwischenablage zum Zusammenfügen markieren oder einzeln einfügen (Strg+O)
+    Ctrl+Delete      - Gewählten Eintrag der Zwischenablage löschen oder die Datei in den Papierkorb verschieben (rückgängig machbar)
+    Right / Tab      - Gewählten Ordner durchsuchen, Tippen filtert (Links geht hoch, Enter öffnet)
+
+    Tipp: Einfach die Frage eintippen, um mit der KI zu chatten!
+
+## Empty states
+
+empty-no-matches = { $category ->
+    [applications] Keine App passt zu „{ $query }“
+    [files] Keine Datei passt zu „{ $query }“
+   *[other] Keine Treffer für „{ $query }“
+}
+empty-hint-app = Enter fragt die KI
+empty-hint-file = Einen Teil des Namens versuchen oder [files] search_dirs in config.toml erweitern
+empty-hint-git = Repositorys werden unter [projects] dirs in config.toml gesucht
+empty-hint-device = Zeigt gekoppelte Bluetooth-Geräte und Audioausgänge
+empty-hint-contact = Liest das Adressbuch von Evolution und die vCards unter [contacts] vcf
+empty-hint-other = Eine andere Suche versuchen
+empty-clipboard = Keine passenden Einträge in der Zwischenablage
+empty-clipboard-hint = Typen: url, email, path, color, json, code, text
+empty-favorites = Noch keine Favoriten
+empty-favorites-hint = Apps oder Dateien mit Strg+D markieren, Quicklinks mit /fav <keyword>
+empty-agenda = Keine anstehenden Termine
+empty-agenda-hint = Liest den lokalen Kalender von Evolution und die Dateien unter [calendar] ics
+empty-templates = Noch keine Vorlagen
+empty-templates-filtered = Keine Vorlage beginnt mit „{ $filter }“
+empty-templates-hint = Unter [templates] in config.toml hinzufügen
+empty-flows = Noch keine Abläufe
+empty-flows-filtered = Kein Ablauf beginnt mit „{ $filter }“
+empty-flows-hint = Unter [flows] in config.toml hinzufügen
+empty-memories = Keine Erinnerungen gespeichert
+empty-memories-hint = Die KI bitten, sich etwas zu merken, z. B. „merke dir, dass…“
+empty-apps = Keine Apps passen zu „{ $query }“
+empty-apps-hint = /apps ohne Namen zeigt alle
+empty-browse = Nichts in diesem Ordner passt zu „{ $query }“
+empty-browse-hint = Links geht einen Ordner hoch; mit . beginnen, um versteckte Dateien einzubeziehen
+empty-timeline = Noch nichts in der Zeitleiste
+empty-timeline-hint = Gestartete Apps, geöffnete Dateien, Kopien und KI-Chats erscheinen hier
+
+## Settings
+
+settings-title = Einstellungen
+settings-coming-soon = Einstellungen - demnächst
+settings-model = Modell
+settings-loading-providers = Anbieter werden geladen...
+settings-groq-key = Groq-API-Schlüssel
+settings-groq-key-help = Für den Chat erforderlich.
+settings-supermemory-key = Supermemory-API-Schlüssel
+settings-supermemory-key-help = Für Erinnerungen erforderlich.
+settings-key-placeholder = Schlüssel eingeben...
+settings-autostart = Ruty bei der Anmeldung starten
+settings-tools = Werkzeuge
+settings-cancel = Abbrechen
+settings-save = Einstellungen speichern
@@ -0,0 +1,142 @@
+# English UI strings, and the fallback for every other language.
+# Every message id used in the code must be defined here.
+
+## Slash commands (suggestions)
+
+command-app = Search and launch applications
+command-launch = Launch the best matching application
+command-apps = Hide apps from search, or show them again
+command-file = Search files
+command-hash = Checksum a file or text
+command-b64 = Base64 of text or the clipboard
+command-url = URL-encode text or the clipboard
+command-json = Format or minify JSON
+command-uuid = Generate a UUID
+command-lorem = Placeholder text
+command-regex = Test a regex against the clipboard
+command-extract-to = Extract the archive picked with Extract to…
+command-devices = Bluetooth and audio devices
+command-contacts = Email addresses and phone numbers
+command-git = Git repository actions
+command-context = Manage local file context
+command-clip = Clipboard history, e.g. only URLs
+command-ocr = Extract text from the copied image
+command-pad = Open or close the scratchpad window
+command-qr = Show a QR code, or scan one from the screen
+command-ask-clip = Ask AI about clipboard content
+command-tpl = Ask AI with a prompt template
+command-flow = Run a workflow from config
+command-permissions = Review and revoke what flows and hooks may do
+command-memory = Browse, copy and delete stored memories
+command-fav = List favorites, or star a quicklink
+command-timer = Start a countdown timer
+command-dnd = Do Not Disturb, optionally for a focus session
+command-agenda = Upcoming calendar events
+command-timeline = Recently launched apps, opened files, copies and chats
+command-audit = What was launched, opened and run, including the AI's tools
+command-cache = Show or clear cached icons and thumbnails
+command-clear = Clear conversation history
+command-providers = Show available providers
+command-alias = Manage aliases
+command-saved = Run, list or manage saved searches
+command-bm = Bookmark folders to browse with a shortcut
+command-doctor = Check the setup and suggest fixes
+command-debug = Show the last backend requests
+command-settings = Open settings
+command-help = Show help
+
+## /help
+
+commands-help =
+    Available Commands:
+    /app <query>     - Search and launch applications (default: AI); Enter switches to an open window; Ctrl+O for a new instance or launch options
+    /launch <app>    - Launch the best matching application
+    /apps [name]     - List apps; Enter hides one from search (or shows it again)
+    /file <query>    - Search files (Ctrl+O on a file: extract, Open With…; on a folder: terminal, editor)
+    /devices [name]  - Connect Bluetooth devices, switch audio output/input
+    /contacts [name] - Copy an email address or number, or compose an email (names also match as you type)
+    /git <repo>      - Open, checkout branches, copy remote, show status
+    /hash [algo] <x> - MD5/SHA-1/SHA-256/BLAKE3 of a file or text
+    /b64, /url       - encode|decode text (or the clipboard)
+    /json fmt|min    - Format or minify JSON (or the clipboard)
+    /uuid            - Generate a random UUID
+    /lorem [n]       - n paragraphs of placeholder text
+    /regex <pattern> - Test a regex live against the clipboard (editable)
+    /context <path>  - Load local files as context (adds to loaded ones)
+    /context list    - Show loaded context; remove <n> unloads one
+    /clip [type] [x] - Clipboard history, only URLs/emails/paths/colors/json/code if given; Ctrl+O for actions
+    /ocr             - Extract text from the copied image (also offered in /clip; [clipboard] ocr_language)
+    /pad             - Open or close the scratchpad, a small always-on-top notes window (saved as you type)
+    /qr [text]       - Show a QR code to copy or save; bare /qr scans one off the screen
+    /ask-clip <q>    - Ask AI about the latest (or selected) clipboard entry
+    /tpl <name> [x]  - Ask AI with a [templates] prompt ({"{"}input{"}"}, {"{"}clipboard{"}"}, {"{"}selection{"}"})
+    /flow <name>     - Run a [flows] workflow: query, pick-first, launch, copy, shell and ai-prompt steps
+    /permissions     - What flows and hooks were allowed to do (shell, network, files, clipboard); Enter revokes
+    /memory [query]  - Browse stored memories; Enter to copy or delete one
+    /fav [quicklink] - List favorites, or star/unstar a quicklink
+    /timer <t> [x]   - Countdown (90s, 25m, 1h30m); pending timers show on an empty query
+    /dnd [on|off|t]  - Do Not Disturb (GNOME, KDE); with a duration, notifications come back when it's up
+    /agenda [days]   - Upcoming events from [calendar] ics files and Evolution (also "today"); Ctrl+O for the meeting link
+    /timeline        - Launched apps, opened files, clipboard copies and AI chats by day; Enter goes back to one
+    /audit [since]   - Log of launched apps, opened files, actions, flows, hooks and AI tool calls (today, yesterday, 3d, 2026-10-01)
+    /clear           - Clear conversation history
+    /providers       - Show available providers
+    /alias           - Manage aliases (add <name> <expansion> | list | rm <name>)
+    /saved [name]    - Run a saved search, or list them (add <name> <search> | rm <name>; also on an empty query)
+    /bm [add|rm]     - Bookmark folders (add <folder> [,w] | rm <folder>); they top short queries and ,w browses one
+    /settings        - Open settings
+    /help            - Show this help
+    > [action]       - Command palette (Ctrl+Shift+P)
+    Ctrl+.           - Pin the window open when it loses focus
+    Ctrl+D           - Star the selected app or file (Ctrl+Up/Down reorders favorites)
+    Ctrl+Space       - Mark clipboard entries to merge & copy or paste one at a time (Ctrl+O)
+    Ctrl+Delete      - Delete the selected clipboard entry or move the file to trash (undoable)
+    Right / Tab      - Browse the selected folder, typing filters it (Left goes up, Enter opens)
+
+    Tip: Just type your question to chat with AI!
+
+## Empty states
+
+empty-no-matches = No { $category } match '{ $query }'
+empty-hint-app = Press Enter to ask AI
+empty-hint-file = Try part of the name, or widen [files] search_dirs in config.toml
+empty-hint-git = Repositories are looked up under [projects] dirs in config.toml
+empty-hint-device = Lists paired Bluetooth devices and audio outputs
+empty-hint-contact = Reads Evolution's address book and the vCards under [contacts] vcf
+empty-hint-other = Try a different query
+empty-clipboard = No matching clipboard entries
+empty-clipboard-hint = Types: url, email, path, color, json, code, text
+empty-favorites = No favorites yet
+empty-favorites-hint = Star an app or file with Ctrl+D, or a quicklink with /fav <keyword>
+empty-agenda = No upcoming events
+empty-agenda-hint = Reads Evolution's local calendar and the files under [calendar] ics
+empty-templates = No prompt templates yet
+empty-templates-filtered = No template starts with '{ $filter }'
+empty-templates-hint = Add them under [templates] in config.toml
+empty-flows = No flows yet
+empty-flows-filtered = No flow starts with '{ $filter }'
+empty-flows-hint = Add them under [flows] in config.toml
+empty-memories = No memories stored
+empty-memories-hint = Ask the AI to remember something, e.g. "remember that…"
+empty-apps = No apps match '{ $query }'
+empty-apps-hint = Try /apps without a name to list them all
+empty-browse = Nothing in this folder matches '{ $query }'
+empty-browse-hint = Left goes up a folder; start with . to include hidden files
+empty-timeline = Nothing in the timeline yet
+empty-timeline-hint = Launched apps, opened files, clipboard copies and AI chats show up here
+
+## Settings
+
+settings-title = Settings
+settings-coming-soon = Settings - Coming Soon
+settings-model = Model
+settings-loading-providers = Loading providers...
+settings-groq-key = Groq API Key
+settings-groq-key-help = Required for chat functionality.
+settings-supermemory-key = Supermemory API Key
+settings-supermemory-key-help = Required for memory features.
+settings-key-placeholder = Enter key...
+settings-autostart = Start Ruty at login
+settings-tools = Tools
+settings-cancel = Cancel
+settings-save = Save Settings
@@ -0,0 +1,138 @@
+//! Built-in actions
+//!
+//! Registry of internal actions offered by the command palette (`>` in the
+//! launcher) and the settings page. Each frontend runs them its own way.
+
+use std::cmp::Reverse;
+
+use serde::Serialize;
+
+use crate::fuzzy;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    RefreshApps,
+    ClearClipboard,
+    SwitchTheme,
+    RestartBackend,
+    OpenConfig,
+    EmptyTrash,
+}
+
+/// Action metadata as sent to web frontends
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionInfo {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    /// Question to confirm before running it, for ones that can't be undone
+    pub confirm: Option<&'static str>,
+}
+
+impl Action {
+    pub const ALL: &'static [Action] = &[
+        Action::RefreshApps,
+        Action::ClearClipboard,
+        Action::SwitchTheme,
+        Action::RestartBackend,
+        Action::OpenConfig,
+        Action::EmptyTrash,
+    ];
+
+    /// Stable identifier used by frontends
+    pub fn id(&self) -> &'static str {
+        match self {
+            Action::RefreshApps => "refresh-apps",
+            Action::ClearClipboard => "clear-clipboard",
+            Action::SwitchTheme => "switch-theme",
+            Action::RestartBackend => "restart-backend",
+            Action::OpenConfig => "open-config",
+            Action::EmptyTrash => "empty-trash",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Action::RefreshApps => "Refresh App Index",
+            Action::ClearClipboard => "Clear Clipboard History",
+            Action::SwitchTheme => "Switch Theme",
+            Action::RestartBackend => "Restart Backend",
+            Action::OpenConfig => "Open Config File",
+            Action::EmptyTrash => "Empty Trash",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::RefreshApps => "Rescan installed applications",
+            Action::ClearClipboard => "Forget all clipboard history entries",
+            Action::SwitchTheme => "Toggle between dark and light theme",
+            Action::RestartBackend => "Restart the AI backend process",
+            Action::OpenConfig => "Open config.toml in the default editor",
+            Action::EmptyTrash => "Permanently delete everything in the trash",
+        }
+    }
+
+    /// What to ask before running an action that can't be undone
+    pub fn confirmation(&self) -> Option<&'static str> {
+        match self {
+            Action::EmptyTrash => Some("Permanently delete everything in the trash?"),
+            _ => None,
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|a| a.id() == id)
+    }
+
+    pub fn info(&self) -> ActionInfo {
+        ActionInfo {
+            id: self.id(),
+            title: self.title(),
+            description: self.description(),
+            confirm: self.confirmation(),
+        }
+    }
+
+    /// Actions matching `query`, best first (all actions for an empty query)
+    pub fn search(query: &str) -> Vec<Action> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Self::ALL.to_vec();
+        }
+        let mut matches: Vec<(Action, i32)> = Self::ALL
+            .iter()
+            .map(|a| (*a, fuzzy::score(a.title(), &query)))
+            .filter(|(_, score)| *score > 0)
+            .collect();
+        matches.sort_by_key(|(_, score)| Reverse(*score));
+        matches.into_iter().map(|(a, _)| a).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_roundtrip() {
+        for action in Action::ALL {
+            assert_eq!(Action::from_id(action.id()), Some(*action));
+        }
+        assert_eq!(Action::from_id("nope"), None);
+    }
+
+    #[test]
+    fn test_search() {
+        assert_eq!(Action::search("").len(), Action::ALL.len());
+        assert_eq!(Action::search("clip"), vec![Action::ClearClipboard]);
+        assert_eq!(Action::search("restart")[0], Action::RestartBackend);
+    }
+
+    #[test]
+    fn test_confirmation() {
+        assert!(Action::EmptyTrash.info().confirm.is_some());
+        // Clearing the clipboard can be undone from its toast instead
+        assert_eq!(Action::ClearClipboard.confirmation(), None);
+    }
+}
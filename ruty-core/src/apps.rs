@@ -0,0 +1,961 @@
+//! Linux Application Launcher
+//!
+//! Parses .desktop files from standard XDG locations and provides
+//! application search functionality for the Ruty launcher.
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::time::Instant;
+
+use crate::config::expand_home;
+use crate::fuzzy;
+use crate::paths;
+
+/// How an application is installed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AppSource {
+    #[default]
+    Native,
+    Flatpak,
+    Snap,
+}
+
+impl AppSource {
+    /// Tell from where the desktop file lives and how it launches
+    fn detect(desktop_file: &Path, exec: &str) -> Self {
+        let path = desktop_file.to_string_lossy();
+        if path.contains("/flatpak/") || exec.starts_with("flatpak ") || exec.contains("/flatpak run ") {
+            Self::Flatpak
+        } else if path.contains("/snapd/") || exec.contains("/snap/bin/") {
+            Self::Snap
+        } else {
+            Self::Native
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Native => "Native",
+            Self::Flatpak => "Flatpak",
+            Self::Snap => "Snap",
+        }
+    }
+}
+
+/// How to launch an app differently from its desktop file, configured as
+/// `[apps.overrides."<desktop id>"]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchOverride {
+    /// Appended to the `Exec` arguments
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Set on top of the inherited environment (and the backend's)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Directory to start in (`~` expands)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    /// Force the app onto X11 (XWayland) or native Wayland
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend: Option<DisplayBackend>,
+}
+
+/// Display server an app is told to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayBackend {
+    X11,
+    Wayland,
+}
+
+impl DisplayBackend {
+    /// Variables GTK, Qt, SDL, Firefox and Electron pick the backend from
+    fn env(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::X11 => &[
+                ("GDK_BACKEND", "x11"),
+                ("QT_QPA_PLATFORM", "xcb"),
+                ("SDL_VIDEODRIVER", "x11"),
+                ("MOZ_ENABLE_WAYLAND", "0"),
+                ("ELECTRON_OZONE_PLATFORM_HINT", "x11"),
+            ],
+            Self::Wayland => &[
+                ("GDK_BACKEND", "wayland"),
+                ("QT_QPA_PLATFORM", "wayland"),
+                ("SDL_VIDEODRIVER", "wayland"),
+                ("MOZ_ENABLE_WAYLAND", "1"),
+                ("ELECTRON_OZONE_PLATFORM_HINT", "wayland"),
+            ],
+        }
+    }
+}
+
+impl LaunchOverride {
+    fn apply(&self, command: &mut Command) {
+        command.args(&self.args);
+        if let Some(backend) = self.backend {
+            command.envs(backend.env().iter().copied());
+        }
+        command.envs(&self.env);
+        if let Some(dir) = &self.working_dir {
+            command.current_dir(expand_home(dir));
+        }
+    }
+}
+
+/// Represents a desktop application
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Application {
+    pub id: String,
+    pub name: String,
+    pub generic_name: Option<String>,
+    pub comment: Option<String>,
+    pub exec: String,
+    pub icon: Option<String>,
+    pub categories: Vec<String>,
+    pub keywords: Vec<String>,
+    pub terminal: bool,
+    pub no_display: bool,
+    pub desktop_file: PathBuf,
+    /// MIME types the app opens (desktop entry `MimeType` key)
+    #[serde(default)]
+    pub mime_types: Vec<String>,
+    #[serde(default)]
+    pub startup_wm_class: Option<String>,
+    #[serde(default)]
+    pub source: AppSource,
+    /// Ids of other installs of the same app, hidden from search (see `AppIndexer::dedupe`)
+    #[serde(default)]
+    pub alternates: Vec<String>,
+    /// Id of the preferred install when this one is a hidden duplicate
+    #[serde(default)]
+    pub duplicate_of: Option<String>,
+    /// Program from `Exec` that wasn't found on PATH at scan time
+    #[serde(default)]
+    pub missing_binary: Option<String>,
+}
+
+impl Application {
+    /// Key shared by installs of the same app from different sources: the
+    /// WM class when declared, otherwise the desktop id stripped of its
+    /// reverse-DNS prefix ("org.mozilla.firefox") or snap suffix
+    /// ("firefox_firefox")
+    pub fn identity(&self) -> String {
+        if let Some(class) = self.startup_wm_class.as_deref().filter(|c| !c.is_empty()) {
+            return class.to_lowercase();
+        }
+        let id = self.id.split('_').next().unwrap_or(&self.id);
+        let id = match id.matches('.').count() {
+            0 | 1 => id,
+            _ => id.rsplit('.').next().unwrap_or(id),
+        };
+        id.to_lowercase()
+    }
+
+    /// Launch the application
+    pub fn launch(&self) -> Result<(), String> {
+        self.launch_with(None)
+    }
+
+    /// Launch the application with its launch override, if any
+    pub fn launch_with(&self, launch: Option<&LaunchOverride>) -> Result<(), String> {
+        self.spawn_with(launch).map(drop)
+    }
+
+    /// Like `launch_with`, keeping the process to watch whether it starts
+    pub fn spawn_with(&self, launch: Option<&LaunchOverride>) -> Result<Child, String> {
+        self.command(launch)?
+            .spawn()
+            .map_err(|e| format!("Failed to launch {}: {}", self.name, e))
+    }
+
+    /// The command `spawn_with` spawns
+    fn command(&self, launch: Option<&LaunchOverride>) -> Result<Command, String> {
+        // Parse the Exec field - remove field codes like %f, %u, etc.
+        let exec = self.exec
+            .replace("%f", "")
+            .replace("%F", "")
+            .replace("%u", "")
+            .replace("%U", "")
+            .replace("%d", "")
+            .replace("%D", "")
+            .replace("%n", "")
+            .replace("%N", "")
+            .replace("%i", "")
+            .replace("%c", "")
+            .replace("%k", "")
+            .trim()
+            .to_string();
+
+        // Split into command and args
+        let parts: Vec<&str> = exec.split_whitespace().collect();
+        if parts.is_empty() {
+            return Err("Empty exec command".to_string());
+        }
+
+        let mut command = Command::new(parts[0]);
+        command.args(&parts[1..]);
+        if let Some(launch) = launch {
+            launch.apply(&mut command);
+        }
+        Ok(command)
+    }
+    
+    /// Launch the application with a file, substituting the Exec field codes
+    /// (%f/%F/%u/%U) or appending the path if there are none
+    pub fn open_file(&self, path: &str, launch: Option<&LaunchOverride>) -> Result<(), String> {
+        let mut used = false;
+        let args: Vec<String> = self
+            .exec
+            .split_whitespace()
+            .filter_map(|arg| match arg {
+                "%f" | "%F" | "%u" | "%U" => {
+                    used = true;
+                    Some(path.to_string())
+                }
+                "%d" | "%D" | "%n" | "%N" | "%i" | "%c" | "%k" => None,
+                _ => Some(arg.to_string()),
+            })
+            .collect();
+        let Some((cmd, rest)) = args.split_first() else {
+            return Err("Empty exec command".to_string());
+        };
+
+        let mut command = Command::new(cmd);
+        command.args(rest);
+        if let Some(launch) = launch {
+            launch.apply(&mut command);
+        }
+        if !used {
+            command.arg(path);
+        }
+        command
+            .spawn()
+            .map_err(|e| format!("Failed to launch {}: {}", self.name, e))?;
+        Ok(())
+    }
+
+    /// Get the full path to the application's icon file
+    pub fn icon_path(&self) -> Option<PathBuf> {
+        let icon = self.icon.as_ref()?;
+        
+        // If it's already a full path, return it
+        if icon.starts_with('/') {
+            let path = PathBuf::from(icon);
+            if path.exists() {
+                return Some(path);
+            }
+        }
+        
+        // Search in standard icon directories
+        let icon_dirs = [
+            "/usr/share/icons/hicolor/256x256/apps",
+            "/usr/share/icons/hicolor/128x128/apps",
+            "/usr/share/icons/hicolor/64x64/apps",
+            "/usr/share/icons/hicolor/48x48/apps",
+            "/usr/share/icons/hicolor/scalable/apps",
+            "/usr/share/pixmaps",
+            "/usr/share/icons/Adwaita/256x256/apps",
+            "/usr/share/icons/Adwaita/scalable/apps",
+        ];
+        
+        let extensions = ["png", "svg", "xpm"];
+        
+        for dir in icon_dirs {
+            for ext in &extensions {
+                let path = PathBuf::from(format!("{}/{}.{}", dir, icon, ext));
+                if path.exists() {
+                    return Some(path);
+                }
+            }
+            // Try without extension
+            let path = PathBuf::from(format!("{}/{}", dir, icon));
+            if path.exists() {
+                return Some(path);
+            }
+        }
+        
+        None
+    }
+}
+
+/// Application indexer - scans and caches desktop applications
+pub struct AppIndexer {
+    apps: Vec<Application>,
+    /// Launch overrides by desktop id (see `set_overrides`)
+    overrides: BTreeMap<String, LaunchOverride>,
+    /// Desktop ids the user hid from search (see `set_hidden`)
+    hidden: HashSet<String>,
+}
+
+impl AppIndexer {
+    /// Create a new indexer and scan the standard XDG locations
+    pub fn new() -> Self {
+        Self::with_dirs(Self::desktop_dirs())
+    }
+
+    /// Create an indexer that only scans the given directories, in
+    /// precedence order (see `scan`)
+    pub fn with_dirs(dirs: Vec<PathBuf>) -> Self {
+        Self::from_apps(Self::scan(&dirs))
+    }
+
+    /// Create an indexer from a previously scanned app list (no scan)
+    pub fn from_apps(apps: Vec<Application>) -> Self {
+        Self { apps, overrides: BTreeMap::new(), hidden: HashSet::new() }
+    }
+
+    /// Get all applications
+    pub fn all(&self) -> &[Application] {
+        &self.apps
+    }
+
+    /// Search applications by query (fuzzy matching)
+    pub fn search(&self, query: &str) -> Vec<&Application> {
+        if query.is_empty() {
+            // Return all visible apps sorted by name
+            return self.apps.iter()
+                .filter(|app| self.is_listed(app))
+                .take(20)
+                .collect();
+        }
+        self.search_scored(query).into_iter().map(|(app, _)| app).collect()
+    }
+
+    /// Search with each match's score, best first (empty for an empty query)
+    pub fn search_scored(&self, query: &str) -> Vec<(&Application, i32)> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let query_lower = query.to_lowercase();
+        let mut results: Vec<(&Application, i32)> = self.apps.iter()
+            .filter(|app| self.is_listed(app))
+            .filter_map(|app| {
+                let score = self.calculate_score(app, &query_lower);
+                if score > 0 {
+                    Some((app, score))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        // Sort by score (highest first), entries that can't launch last
+        results.sort_by_key(|(app, score)| (app.missing_binary.is_some(), -score));
+        results.truncate(10);
+        results
+    }
+
+    /// Whether search shows an app: it isn't NoDisplay, another install's
+    /// duplicate or hidden by the user
+    pub fn is_listed(&self, app: &Application) -> bool {
+        !app.no_display && app.duplicate_of.is_none() && !self.hidden.contains(&app.id)
+    }
+
+    /// Hide these apps (`[apps] hidden`) from search from now on
+    pub fn set_hidden(&mut self, hidden: impl IntoIterator<Item = String>) {
+        self.hidden = hidden.into_iter().collect();
+    }
+
+    pub fn is_hidden(&self, id: &str) -> bool {
+        self.hidden.contains(id)
+    }
+
+    /// Collapse installs of the same app from different sources (native,
+    /// Flatpak, Snap) into the one whose source comes first in `prefer`; the
+    /// others stay indexed, listed in its `alternates`, but drop out of
+    /// search. Same-source entries sharing an identity are left alone: they
+    /// were installed side by side on purpose.
+    pub fn dedupe(&mut self, prefer: &[AppSource]) {
+        let rank = |source: AppSource| prefer.iter().position(|p| *p == source).unwrap_or(prefer.len());
+        let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+        for (idx, app) in self.apps.iter_mut().enumerate() {
+            app.alternates.clear();
+            app.duplicate_of = None;
+            if !app.no_display {
+                groups.entry(app.identity()).or_default().push(idx);
+            }
+        }
+        for members in groups.into_values() {
+            let sources: HashSet<AppSource> = members.iter().map(|&i| self.apps[i].source).collect();
+            if sources.len() < 2 {
+                continue;
+            }
+            let Some(&keep) = members.iter().min_by_key(|&&i| rank(self.apps[i].source)) else {
+                continue;
+            };
+            let keep_id = self.apps[keep].id.clone();
+            for &idx in members.iter().filter(|&&i| i != keep) {
+                let id = self.apps[idx].id.clone();
+                self.apps[keep].alternates.push(id);
+                self.apps[idx].duplicate_of = Some(keep_id.clone());
+            }
+        }
+    }
+
+    /// An app followed by its alternate installs
+    pub fn installs(&self, id: &str) -> Vec<&Application> {
+        let Some(app) = self.apps.iter().find(|app| app.id == id) else {
+            return Vec::new();
+        };
+        let mut installs = vec![app];
+        installs.extend(app.alternates.iter().filter_map(|alt| self.apps.iter().find(|a| &a.id == alt)));
+        installs
+    }
+
+    /// Use these launch overrides (from `[apps.overrides]`) from now on
+    pub fn set_overrides(&mut self, overrides: BTreeMap<String, LaunchOverride>) {
+        self.overrides = overrides;
+    }
+
+    /// The launch override configured for an app
+    pub fn launch_override(&self, id: &str) -> Option<&LaunchOverride> {
+        self.overrides.get(id)
+    }
+
+    /// Launch an application by ID, merging in its launch override
+    pub fn launch(&self, id: &str) -> Result<(), String> {
+        self.spawn(id).map(drop)
+    }
+
+    /// Like `launch`, keeping the process to watch whether it starts
+    pub fn spawn(&self, id: &str) -> Result<Child, String> {
+        self.apps
+            .iter()
+            .find(|app| app.id == id)
+            .ok_or_else(|| format!("Application not found: {}", id))?
+            .spawn_with(self.overrides.get(id))
+    }
+
+    /// Calculate match score for an app
+    fn calculate_score(&self, app: &Application, query: &str) -> i32 {
+        // Exact, prefix and substring name matches rank highest
+        let name_score = fuzzy::score(&app.name, query);
+        if name_score >= fuzzy::CONTAINS {
+            return name_score;
+        }
+        
+        // Check generic name
+        if let Some(ref generic) = app.generic_name {
+            let generic_lower = generic.to_lowercase();
+            if generic_lower.contains(query) {
+                return 150;
+            }
+        }
+        
+        // Check keywords
+        for keyword in &app.keywords {
+            if keyword.to_lowercase().contains(query) {
+                return 100;
+            }
+        }
+        
+        // Check categories
+        for category in &app.categories {
+            if category.to_lowercase().contains(query) {
+                return 50;
+            }
+        }
+        
+        // Subsequence name match as a last resort
+        name_score
+    }
+
+    /// Scan directories for .desktop files, sorted by name. Directories are
+    /// listed and files parsed in parallel (slow NFS homes); a desktop file
+    /// id found in several directories is taken from the first, as XDG
+    /// precedence says.
+    fn scan(dirs: &[PathBuf]) -> Vec<Application> {
+        let start = Instant::now();
+        let listed: Vec<Vec<(String, PathBuf)>> = dirs.par_iter().map(|dir| desktop_files(dir)).collect();
+
+        let mut seen = HashSet::new();
+        let entries: Vec<(String, PathBuf)> = listed
+            .into_iter()
+            .flatten()
+            .filter(|(id, _)| seen.insert(id.clone()))
+            .collect();
+        let listed_in = start.elapsed();
+
+        let parsed: Vec<(Application, Option<String>)> = entries
+            .par_iter()
+            .filter_map(|(id, path)| Self::parse_desktop_file(id, path))
+            .collect();
+
+        // TryExec names a binary the entry needs: without it, the entry
+        // doesn't exist (per the spec). A missing Exec binary is flagged.
+        let executables = Executables::from_env();
+        let mut apps: Vec<Application> = parsed
+            .into_iter()
+            .filter(|(_, try_exec)| try_exec.as_deref().is_none_or(|bin| executables.exists(bin)))
+            .map(|(mut app, _)| {
+                app.missing_binary = program(&app.exec).filter(|bin| !executables.exists(bin));
+                app
+            })
+            .collect();
+        apps.sort_by_cached_key(|app| app.name.to_lowercase());
+        tracing::info!(
+            "Scanned {} desktop files in {} dirs: {} apps in {:?} (listing {:?})",
+            entries.len(),
+            dirs.len(),
+            apps.len(),
+            start.elapsed(),
+            listed_in
+        );
+        apps
+    }
+
+    /// Get standard XDG desktop file directories, highest precedence first
+    pub fn desktop_dirs() -> Vec<PathBuf> {
+        let home = paths::home();
+        // User applications: XDG_DATA_HOME, ~/.local/share by default
+        let mut data_dirs = vec![paths::data_home()];
+
+        // System applications: XDG_DATA_DIRS
+        let xdg_dirs = std::env::var("XDG_DATA_DIRS")
+            .ok()
+            .filter(|d| !d.is_empty())
+            .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+        data_dirs.extend(xdg_dirs.split(':').filter(|d| !d.is_empty()).map(PathBuf::from));
+        data_dirs.push(PathBuf::from("/usr/local/share"));
+        data_dirs.push(PathBuf::from("/usr/share"));
+
+        // Flatpak (usually in XDG_DATA_DIRS already when installed)
+        data_dirs.extend(home.iter().map(|h| h.join(".local/share/flatpak/exports/share")));
+        data_dirs.push(PathBuf::from("/var/lib/flatpak/exports/share"));
+
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        for dir in data_dirs.into_iter().map(|d| d.join("applications")) {
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+
+        // Snap
+        dirs.push(PathBuf::from("/var/lib/snapd/desktop/applications"));
+
+        dirs
+    }
+
+    /// Parse a .desktop file with the given desktop file id, returning the
+    /// app and its `TryExec` binary
+    fn parse_desktop_file(id: &str, path: &Path) -> Option<(Application, Option<String>)> {
+        let content = fs::read_to_string(path).ok()?;
+        
+        let mut in_desktop_entry = false;
+        let mut fields: HashMap<String, String> = HashMap::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+            
+            // Skip comments and empty lines
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            // Check for section headers
+            if line.starts_with('[') {
+                in_desktop_entry = line == "[Desktop Entry]";
+                continue;
+            }
+
+            // Only parse [Desktop Entry] section
+            if !in_desktop_entry {
+                continue;
+            }
+
+            // Parse key=value
+            if let Some(pos) = line.find('=') {
+                let key = line[..pos].trim().to_string();
+                let value = line[pos + 1..].trim().to_string();
+                fields.insert(key, value);
+            }
+        }
+
+        // Required fields
+        let name = fields.get("Name")?.clone();
+        let exec = fields.get("Exec")?.clone();
+        
+        // Check if it's an application (not Link or Directory)
+        let entry_type = fields.get("Type").map(|s| s.as_str()).unwrap_or("Application");
+        if entry_type != "Application" {
+            return None;
+        }
+        // Hidden=true means deleted: it's how a user entry masks a system one
+        if fields.get("Hidden").is_some_and(|v| v == "true") {
+            return None;
+        }
+
+        // Parse categories
+        let categories: Vec<String> = fields.get("Categories")
+            .map(|c| c.split(';').filter(|s| !s.is_empty()).map(String::from).collect())
+            .unwrap_or_default();
+
+        // Parse keywords
+        let keywords: Vec<String> = fields.get("Keywords")
+            .map(|k| k.split(';').filter(|s| !s.is_empty()).map(String::from).collect())
+            .unwrap_or_default();
+
+        let mime_types: Vec<String> = fields.get("MimeType")
+            .map(|m| m.split(';').filter(|s| !s.is_empty()).map(String::from).collect())
+            .unwrap_or_default();
+
+        let app = Application {
+            source: AppSource::detect(path, &exec),
+            startup_wm_class: fields.get("StartupWMClass").cloned(),
+            alternates: Vec::new(),
+            duplicate_of: None,
+            missing_binary: None,
+            id: id.to_string(),
+            name,
+            generic_name: fields.get("GenericName").cloned(),
+            comment: fields.get("Comment").cloned(),
+            exec,
+            icon: fields.get("Icon").cloned(),
+            categories,
+            keywords,
+            terminal: fields.get("Terminal").map(|v| v == "true").unwrap_or(false),
+            no_display: fields.get("NoDisplay").map(|v| v == "true").unwrap_or(false),
+            desktop_file: path.to_path_buf(),
+            mime_types,
+        };
+        Some((app, fields.get("TryExec").cloned()))
+    }
+}
+
+/// Program an `Exec` line runs, skipping an `env VAR=value` prefix
+fn program(exec: &str) -> Option<String> {
+    let mut words = exec.split_whitespace().map(|w| w.trim_matches('"'));
+    let mut word = words.next()?;
+    if word == "env" {
+        word = words.find(|w| !w.contains('=') && !w.starts_with('-'))?;
+    }
+    (!word.starts_with('%')).then(|| word.to_string())
+}
+
+/// Programs on PATH, listed once per scan so checking an entry is a lookup
+struct Executables {
+    on_path: HashSet<String>,
+}
+
+impl Executables {
+    fn from_env() -> Self {
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        let on_path = std::env::split_paths(&path)
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.flatten().map(|e| e.file_name().to_string_lossy().to_string()))
+            .collect();
+        Self { on_path }
+    }
+
+    /// Whether a program (a name looked up on PATH, or a path) exists
+    fn exists(&self, program: &str) -> bool {
+        if program.contains('/') {
+            Path::new(program).is_file()
+        } else {
+            self.on_path.contains(program)
+        }
+    }
+}
+
+/// `.desktop` files under an applications dir with their desktop file ids:
+/// the path relative to the dir, `/` replaced by `-` ("kde4/foo.desktop" is
+/// "kde4-foo"). Missing dirs yield nothing.
+fn desktop_files(dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut found = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                pending.push(path);
+            } else if path.extension().is_some_and(|e| e == "desktop") {
+                let Ok(relative) = path.with_extension("").strip_prefix(dir).map(Path::to_path_buf) else {
+                    continue;
+                };
+                let id = relative.to_string_lossy().replace('/', "-");
+                found.push((id, path));
+            }
+        }
+    }
+    found
+}
+
+impl Default for AppIndexer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Apps that came and went between two scans, by desktop id
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AppChanges {
+    /// Names of the new apps
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl AppChanges {
+    pub fn between(old: &[Application], new: &[Application]) -> Self {
+        let old_ids: HashSet<&str> = old.iter().map(|app| app.id.as_str()).collect();
+        let new_ids: HashSet<&str> = new.iter().map(|app| app.id.as_str()).collect();
+        Self {
+            added: new.iter().filter(|app| !old_ids.contains(app.id.as_str())).map(|app| app.name.clone()).collect(),
+            removed: old.iter().filter(|app| !new_ids.contains(app.id.as_str())).map(|app| app.name.clone()).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /// Counts for a notice, e.g. "3 new" or "1 new, 2 removed"
+    pub fn summary(&self) -> String {
+        match (self.added.len(), self.removed.len()) {
+            (added, 0) => format!("{} new", added),
+            (0, removed) => format!("{} removed", removed),
+            (added, removed) => format!("{} new, {} removed", added, removed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_indexer() -> AppIndexer {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/applications");
+        AppIndexer::with_dirs(vec![dir])
+    }
+
+    fn find<'a>(indexer: &'a AppIndexer, id: &str) -> &'a Application {
+        indexer.all().iter().find(|app| app.id == id).unwrap()
+    }
+
+    fn ids(results: Vec<&Application>) -> Vec<&str> {
+        results.into_iter().map(|app| app.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_indexer_creation() {
+        let indexer = fixture_indexer();
+        let mut ids: Vec<&str> = indexer.all().iter().map(|app| app.id.as_str()).collect();
+        ids.sort();
+        // Type=Link, Hidden=true, missing Exec or TryExec binary and
+        // non-.desktop files are skipped
+        assert_eq!(
+            ids,
+            vec![
+                "org.example.Broken",
+                "org.example.Missing",
+                "org.example.Settings",
+                "org.example.Terminal",
+                "org.example.Writer",
+                "org.example.WriterPreview",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_xdg_precedence_and_subdir_ids() {
+        let root = std::env::temp_dir().join(format!("ruty-apps-{}", std::process::id()));
+        let (user, system) = (root.join("user"), root.join("system"));
+        fs::create_dir_all(&user).unwrap();
+        fs::create_dir_all(system.join("kde4")).unwrap();
+        let entry = |name: &str| format!("[Desktop Entry]\nType=Application\nName={}\nExec=true\n", name);
+        fs::write(user.join("editor.desktop"), entry("User Editor")).unwrap();
+        fs::write(system.join("editor.desktop"), entry("System Editor")).unwrap();
+        fs::write(system.join("kde4/viewer.desktop"), entry("Viewer")).unwrap();
+
+        let indexer = AppIndexer::with_dirs(vec![user, system]);
+        fs::remove_dir_all(&root).unwrap();
+
+        // The higher-precedence dir shadows the same desktop file id
+        assert_eq!(find(&indexer, "editor").name, "User Editor");
+        assert_eq!(find(&indexer, "kde4-viewer").name, "Viewer");
+        assert_eq!(indexer.all().len(), 2);
+    }
+
+    #[test]
+    fn test_launch_override() {
+        let indexer = fixture_indexer();
+        let writer = find(&indexer, "org.example.Writer");
+        let launch = LaunchOverride {
+            args: vec!["--safe-mode".to_string()],
+            env: BTreeMap::from([("GDK_BACKEND".to_string(), "x11,wayland".to_string())]),
+            working_dir: Some("/tmp".to_string()),
+            backend: Some(DisplayBackend::Wayland),
+        };
+        let plain = writer.command(None).unwrap();
+        let command = writer.command(Some(&launch)).unwrap();
+
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args.len(), plain.get_args().count() + 1);
+        assert_eq!(args.last().copied(), Some(std::ffi::OsStr::new("--safe-mode")));
+        let envs: HashMap<_, _> = command.get_envs().collect();
+        // Explicit env wins over the backend's
+        assert_eq!(envs[std::ffi::OsStr::new("GDK_BACKEND")], Some(std::ffi::OsStr::new("x11,wayland")));
+        assert_eq!(envs[std::ffi::OsStr::new("QT_QPA_PLATFORM")], Some(std::ffi::OsStr::new("wayland")));
+        assert_eq!(command.get_current_dir(), Some(Path::new("/tmp")));
+    }
+
+    #[test]
+    fn test_dedupe_across_sources() {
+        let root = std::env::temp_dir().join(format!("ruty-dedupe-{}", std::process::id()));
+        let native = root.join("native");
+        let flatpak = root.join("flatpak/exports/share/applications");
+        fs::create_dir_all(&native).unwrap();
+        fs::create_dir_all(&flatpak).unwrap();
+        let entry = |name: &str, exec: &str, class: &str| {
+            format!("[Desktop Entry]\nName={}\nExec={}\nStartupWMClass={}\n", name, exec, class)
+        };
+        fs::write(native.join("browser.desktop"), entry("Browser", "browser", "Browser")).unwrap();
+        fs::write(native.join("browser-nightly.desktop"), entry("Browser Nightly", "browser-nightly", "browser"))
+            .unwrap();
+        let flatpak_exec = "flatpak run org.example.Browser";
+        fs::write(flatpak.join("org.example.Browser.desktop"), entry("Browser", flatpak_exec, "browser")).unwrap();
+
+        let mut indexer = AppIndexer::with_dirs(vec![native, flatpak]);
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(find(&indexer, "org.example.Browser").source, AppSource::Flatpak);
+
+        indexer.dedupe(&[AppSource::Flatpak, AppSource::Native]);
+        // Both native entries lose to the preferred Flatpak, which lists them
+        assert_eq!(ids(indexer.search("browser")), vec!["org.example.Browser"]);
+        let installs = ids(indexer.installs("org.example.Browser"));
+        assert_eq!(installs.len(), 3);
+        assert!(installs.contains(&"browser") && installs.contains(&"browser-nightly"));
+
+        // Among equally preferred installs the first by name is kept
+        indexer.dedupe(&[AppSource::Native]);
+        assert_eq!(find(&indexer, "org.example.Browser").duplicate_of.as_deref(), Some("browser"));
+    }
+
+    #[test]
+    fn test_identity() {
+        let indexer = fixture_indexer();
+        let mut app = find(&indexer, "org.example.Writer").clone();
+        assert_eq!(app.identity(), "writer");
+        app.id = "firefox_firefox".to_string();
+        assert_eq!(app.identity(), "firefox");
+        app.startup_wm_class = Some("LibreOffice-Writer".to_string());
+        assert_eq!(app.identity(), "libreoffice-writer");
+    }
+
+    #[test]
+    fn test_missing_binary() {
+        let indexer = fixture_indexer();
+        let missing = find(&indexer, "org.example.Missing");
+        assert_eq!(missing.missing_binary.as_deref(), Some("/nonexistent/ruty-fixture-binary"));
+        assert_eq!(find(&indexer, "org.example.Broken").missing_binary, None);
+
+        assert_eq!(program("env GDK_BACKEND=x11 gimp %U").as_deref(), Some("gimp"));
+        assert_eq!(program("\"/usr/bin/app\" --flag").as_deref(), Some("/usr/bin/app"));
+        assert_eq!(program("  %f %U"), None);
+    }
+
+    #[test]
+    fn test_app_changes() {
+        let root = std::env::temp_dir().join(format!("ruty-changes-{}", std::process::id()));
+        let dir = root.join("applications");
+        fs::create_dir_all(dir.join("kde4")).unwrap();
+        let entry = |name: &str| format!("[Desktop Entry]\nName={}\nExec=true\n", name);
+        fs::write(dir.join("editor.desktop"), entry("Editor")).unwrap();
+        fs::write(dir.join("viewer.desktop"), entry("Viewer")).unwrap();
+        let before = AppIndexer::with_dirs(vec![dir.clone()]);
+
+        fs::remove_file(dir.join("viewer.desktop")).unwrap();
+        fs::write(dir.join("kde4/player.desktop"), entry("Player")).unwrap();
+        fs::write(dir.join("shell.desktop"), entry("Shell")).unwrap();
+        let after = AppIndexer::with_dirs(vec![dir]);
+        fs::remove_dir_all(&root).unwrap();
+
+        let changes = AppChanges::between(before.all(), after.all());
+        assert_eq!(changes, AppChanges { added: vec!["Player".into(), "Shell".into()], removed: vec!["Viewer".into()] });
+        assert_eq!(changes.summary(), "2 new, 1 removed");
+        assert!(AppChanges::between(after.all(), after.all()).is_empty());
+    }
+
+    #[test]
+    fn test_missing_dirs_are_ignored() {
+        let indexer = AppIndexer::with_dirs(vec![PathBuf::from("/nonexistent/ruty-fixtures")]);
+        assert!(indexer.all().is_empty());
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        let indexer = fixture_indexer();
+        let writer = find(&indexer, "org.example.Writer");
+
+        // Unlocalized name wins, and action sections don't leak in
+        assert_eq!(writer.name, "Writer");
+        assert_eq!(writer.exec, "writer %U");
+        assert_eq!(writer.generic_name.as_deref(), Some("Word Processor"));
+        assert_eq!(writer.categories, vec!["Office", "WordProcessor"]);
+        assert_eq!(writer.keywords, vec!["text", "document"]);
+        assert_eq!(writer.mime_types, vec!["application/vnd.oasis.opendocument.text", "text/plain"]);
+        assert!(!writer.terminal);
+
+        let terminal = find(&indexer, "org.example.Terminal");
+        assert_eq!(terminal.name, "Terminal");
+        assert_eq!(terminal.exec, "term");
+        assert!(terminal.terminal);
+
+        assert!(find(&indexer, "org.example.Settings").no_display);
+    }
+
+    #[test]
+    fn test_search() {
+        let indexer = fixture_indexer();
+
+        // Exact beats prefix; shorter prefix matches rank first
+        assert_eq!(
+            ids(indexer.search("writer")),
+            vec!["org.example.Writer", "org.example.WriterPreview"]
+        );
+        // Generic name, keyword and category matches
+        assert_eq!(ids(indexer.search("processor")), vec!["org.example.Writer"]);
+        assert_eq!(ids(indexer.search("shell")), vec!["org.example.Terminal"]);
+        assert_eq!(ids(indexer.search("emulator")), vec!["org.example.Terminal"]);
+        // Subsequence fallback
+        assert_eq!(ids(indexer.search("trml")), vec!["org.example.Terminal"]);
+        assert!(indexer.search("zzz").is_empty());
+    }
+
+    #[test]
+    fn test_search_hides_no_display() {
+        let indexer = fixture_indexer();
+        assert!(indexer.search("settings").is_empty());
+        assert!(!ids(indexer.search("")).contains(&"org.example.Settings"));
+    }
+
+    #[test]
+    fn test_search_hides_user_hidden() {
+        let mut indexer = fixture_indexer();
+        indexer.set_hidden(["org.example.WriterPreview".to_string()]);
+        assert!(indexer.is_hidden("org.example.WriterPreview"));
+        assert_eq!(ids(indexer.search("writer")), vec!["org.example.Writer"]);
+        assert!(!ids(indexer.search("")).contains(&"org.example.WriterPreview"));
+
+        indexer.set_hidden(Vec::new());
+        assert_eq!(indexer.search("writer").len(), 2);
+    }
+
+    #[test]
+    fn test_launch_bad_exec() {
+        let indexer = fixture_indexer();
+        assert_eq!(indexer.launch("org.example.Broken"), Err("Empty exec command".to_string()));
+        assert!(indexer.launch("org.example.Missing").is_err());
+        assert!(indexer.launch("org.example.Nope").is_err());
+    }
+}
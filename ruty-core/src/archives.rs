@@ -0,0 +1,519 @@
+//! Archive listing and extraction
+//!
+//! Zip files go through the zip crate and plain or gzipped tarballs through
+//! the tar crate. Other compressions (.tar.xz, .tar.bz2, .tar.zst) and 7z
+//! archives are handed to the `tar` and `7z` tools.
+//! Entries that would land outside the destination (absolute paths, `..`,
+//! writing through a symlink) are skipped or stop the extraction, and so
+//! are devices and pipes; skipped entries are reported back.
+
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use flate2::read::GzDecoder;
+
+use crate::progress::Progress;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+    /// Tarball with a compression only the `tar` tool handles
+    TarOther,
+    SevenZip,
+}
+
+impl ArchiveKind {
+    /// Detect an archive from its file name
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        let kind = if name.ends_with(".zip") {
+            Self::Zip
+        } else if name.ends_with(".tar") {
+            Self::Tar
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Self::TarGz
+        } else if [".tar.xz", ".txz", ".tar.bz2", ".tbz2", ".tar.zst", ".tzst"]
+            .iter()
+            .any(|ext| name.ends_with(ext))
+        {
+            Self::TarOther
+        } else if name.ends_with(".7z") {
+            Self::SevenZip
+        } else {
+            return None;
+        };
+        Some(kind)
+    }
+}
+
+/// A file or directory inside an archive
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub path: String,
+    /// Uncompressed size (0 when the listing tool doesn't report it)
+    pub size: u64,
+    pub is_dir: bool,
+}
+
+/// What an extraction wrote
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Extracted {
+    /// Files and links written
+    pub files: usize,
+    /// Members left out, each with why
+    pub skipped: Vec<(String, &'static str)>,
+}
+
+impl Extracted {
+    fn skip(&mut self, name: &str, reason: &'static str) {
+        self.skipped.push((name.to_string(), reason));
+    }
+}
+
+/// Progress for extracting `path`, measured in archive bytes read (unknown
+/// when an external tool does the work)
+pub fn progress_for(path: &Path) -> Progress {
+    let total = match ArchiveKind::from_path(path) {
+        Some(ArchiveKind::TarOther | ArchiveKind::SevenZip) | None => 0,
+        Some(_) => fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+    };
+    Progress::new(total)
+}
+
+fn kind_of(path: &Path) -> Result<ArchiveKind, String> {
+    ArchiveKind::from_path(path).ok_or_else(|| format!("Not a supported archive: {}", path.display()))
+}
+
+fn open(path: &Path) -> Result<File, String> {
+    File::open(path).map_err(|e| format!("Cannot open {}: {}", path.display(), e))
+}
+
+/// Entries of an archive, in archive order (blocking)
+pub fn list(path: &Path) -> Result<Vec<Entry>, String> {
+    match kind_of(path)? {
+        ArchiveKind::Zip => {
+            let mut archive = zip::ZipArchive::new(open(path)?).map_err(|e| format!("Invalid zip: {}", e))?;
+            (0..archive.len())
+                .map(|i| {
+                    let file = archive.by_index_raw(i).map_err(|e| e.to_string())?;
+                    Ok(Entry {
+                        path: file.name().to_string(),
+                        size: file.size(),
+                        is_dir: file.is_dir(),
+                    })
+                })
+                .collect()
+        }
+        ArchiveKind::Tar => tar_entries(open(path)?),
+        ArchiveKind::TarGz => tar_entries(GzDecoder::new(open(path)?)),
+        ArchiveKind::TarOther => {
+            let output = run("tar", &[OsStr::new("-tf"), path.as_os_str()])?;
+            Ok(output
+                .lines()
+                .map(|line| Entry {
+                    path: line.to_string(),
+                    size: 0,
+                    is_dir: line.ends_with('/'),
+                })
+                .collect())
+        }
+        ArchiveKind::SevenZip => {
+            let output = run("7z", &[OsStr::new("l"), OsStr::new("-slt"), OsStr::new("-ba"), path.as_os_str()])?;
+            Ok(parse_7z_listing(&output))
+        }
+    }
+}
+
+/// Extract into `dest` (created if needed), returning what was written and
+/// left out. `progress.done` advances as the archive is read (blocking).
+pub fn extract(path: &Path, dest: &Path, progress: &Progress) -> Result<Extracted, String> {
+    fs::create_dir_all(dest).map_err(|e| format!("Cannot create {}: {}", dest.display(), e))?;
+    let written = match kind_of(path)? {
+        ArchiveKind::Zip => extract_zip(path, dest, progress),
+        ArchiveKind::Tar => extract_tar(Counting::new(open(path)?, progress), dest),
+        ArchiveKind::TarGz => extract_tar(GzDecoder::new(Counting::new(open(path)?, progress)), dest),
+        ArchiveKind::TarOther => {
+            // GNU/bsdtar refuse absolute and `..` member names by default
+            run("tar", &[OsStr::new("-xf"), path.as_os_str(), OsStr::new("-C"), dest.as_os_str()])?;
+            Ok(Extracted { files: list(path)?.iter().filter(|e| !e.is_dir).count(), skipped: Vec::new() })
+        }
+        ArchiveKind::SevenZip => {
+            let out_dir = format!("-o{}", dest.display());
+            run("7z", &[OsStr::new("x"), OsStr::new("-y"), OsStr::new(&out_dir), path.as_os_str()])?;
+            Ok(Extracted { files: list(path)?.iter().filter(|e| !e.is_dir).count(), skipped: Vec::new() })
+        }
+    }?;
+    // Trailing padding isn't read, so finish the bar explicitly
+    progress.finish();
+    Ok(written)
+}
+
+/// Directory "Extract here" uses: next to the archive, named after it, with
+/// a numeric suffix if that name is taken
+pub fn default_destination(path: &Path) -> PathBuf {
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let lower = name.to_lowercase();
+    let suffix = [".tar.gz", ".tar.xz", ".tar.bz2", ".tar.zst", ".tgz", ".txz", ".tbz2", ".tzst", ".tar", ".zip", ".7z"]
+        .iter()
+        .find(|ext| lower.ends_with(*ext))
+        .map_or(0, |ext| ext.len());
+    let stem = &name[..name.len() - suffix];
+    let stem = if stem.is_empty() { "archive" } else { stem };
+
+    let mut dest = parent.join(stem);
+    let mut n = 2;
+    while dest.exists() {
+        dest = parent.join(format!("{} ({})", stem, n));
+        n += 1;
+    }
+    dest
+}
+
+fn extract_zip(path: &Path, dest: &Path, progress: &Progress) -> Result<Extracted, String> {
+    let mut archive = zip::ZipArchive::new(open(path)?).map_err(|e| format!("Invalid zip: {}", e))?;
+    let mut extracted = Extracted::default();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
+        progress.add(file.compressed_size());
+        let Some(relative) = file.enclosed_name() else {
+            tracing::warn!("Skipping unsafe zip entry {}", file.name());
+            extracted.skip(file.name(), "outside the destination");
+            continue;
+        };
+        let target = dest.join(relative);
+        if file.is_dir() {
+            check_inside(dest, &target)?;
+            fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+            continue;
+        }
+        prepare(dest, &target)?;
+        if file.is_symlink() {
+            let mut link = String::new();
+            file.read_to_string(&mut link).map_err(|e| format!("Invalid zip: {}", e))?;
+            std::os::unix::fs::symlink(&link, &target)
+                .map_err(|e| format!("Cannot write {}: {}", target.display(), e))?;
+        } else {
+            let mode = file.unix_mode();
+            write_file(&target, &mut file, mode)?;
+        }
+        extracted.files += 1;
+    }
+    Ok(extracted)
+}
+
+fn extract_tar<R: Read>(reader: R, dest: &Path) -> Result<Extracted, String> {
+    let mut archive = tar::Archive::new(reader);
+    let mut extracted = Extracted::default();
+    for entry in archive.entries().map_err(invalid_tar)? {
+        let mut entry = entry.map_err(invalid_tar)?;
+        let name = entry.path().map_err(invalid_tar)?.to_string_lossy().to_string();
+        let kind = entry.header().entry_type();
+        if !(kind.is_file() || kind.is_dir() || kind.is_symlink() || kind.is_hard_link()) {
+            tracing::warn!("Skipping tar entry {} of type {:?}", name, kind);
+            extracted.skip(&name, unsupported(kind));
+            continue;
+        }
+        // Refuses members and links that would land outside `dest` (`..`,
+        // through a symlink), and hard links to files outside it
+        let unpacked = entry.unpack_in(dest).map_err(|e| format!("Cannot extract {}: {}", name, e))?;
+        if !unpacked {
+            tracing::warn!("Skipping unsafe tar entry {}", name);
+            extracted.skip(&name, "outside the destination");
+        } else if !kind.is_dir() {
+            extracted.files += 1;
+        }
+    }
+    Ok(extracted)
+}
+
+fn invalid_tar(e: io::Error) -> String {
+    format!("Invalid tar archive: {}", e)
+}
+
+/// Why a tar member of this type isn't extracted
+fn unsupported(kind: tar::EntryType) -> &'static str {
+    match kind {
+        tar::EntryType::Char => "character device",
+        tar::EntryType::Block => "block device",
+        tar::EntryType::Fifo => "named pipe",
+        tar::EntryType::GNUSparse => "sparse file",
+        _ => "unsupported entry type",
+    }
+}
+
+/// Get `target` ready to be written: its directory created, and a symlink
+/// already there removed so it isn't written through
+fn prepare(dest: &Path, target: &Path) -> Result<(), String> {
+    let parent = target.parent().unwrap_or(dest);
+    check_inside(dest, parent)?;
+    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    if fs::symlink_metadata(target).is_ok_and(|m| m.file_type().is_symlink()) {
+        fs::remove_file(target).map_err(|e| format!("Cannot replace {}: {}", target.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Refuse `path` if the part of it that exists resolves outside `dest`,
+/// i.e. a symlink extracted earlier would redirect it
+fn check_inside(dest: &Path, path: &Path) -> Result<(), String> {
+    let Some(existing) = path.ancestors().find(|ancestor| ancestor.exists()) else {
+        return Ok(());
+    };
+    let canonical = |path: &Path| path.canonicalize().map_err(|e| format!("Cannot resolve {}: {}", path.display(), e));
+    if !canonical(existing)?.starts_with(canonical(dest)?) {
+        return Err(format!("{} would be written outside {}", path.display(), dest.display()));
+    }
+    Ok(())
+}
+
+fn write_file(target: &Path, data: &mut dyn Read, mode: Option<u32>) -> Result<(), String> {
+    let mut out = File::create(target).map_err(|e| format!("Cannot write {}: {}", target.display(), e))?;
+    io::copy(data, &mut out).map_err(|e| format!("Cannot write {}: {}", target.display(), e))?;
+    if let Some(mode) = mode {
+        let _ = fs::set_permissions(target, fs::Permissions::from_mode(mode & 0o777));
+    }
+    Ok(())
+}
+
+fn tar_entries<R: Read>(reader: R) -> Result<Vec<Entry>, String> {
+    let mut archive = tar::Archive::new(reader);
+    archive
+        .entries()
+        .map_err(invalid_tar)?
+        .map(|entry| {
+            let entry = entry.map_err(invalid_tar)?;
+            Ok(Entry {
+                path: entry.path().map_err(invalid_tar)?.to_string_lossy().to_string(),
+                size: entry.size(),
+                is_dir: entry.header().entry_type().is_dir(),
+            })
+        })
+        .collect()
+}
+
+fn parse_7z_listing(output: &str) -> Vec<Entry> {
+    output
+        .split("\n\n")
+        .filter_map(|block| {
+            let field = |key: &str| {
+                block
+                    .lines()
+                    .find_map(|line| line.strip_prefix(key)?.strip_prefix(" = ").map(str::to_string))
+            };
+            Some(Entry {
+                path: field("Path")?,
+                size: field("Size").and_then(|s| s.parse().ok()).unwrap_or(0),
+                is_dir: field("Folder").as_deref() == Some("+"),
+            })
+        })
+        .collect()
+}
+
+fn run(program: &str, args: &[&OsStr]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("{} unavailable: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Reader that reports bytes read to a `Progress`
+struct Counting<'a, R> {
+    inner: R,
+    progress: &'a Progress,
+}
+
+impl<'a, R> Counting<'a, R> {
+    fn new(inner: R, progress: &'a Progress) -> Self {
+        Self { inner, progress }
+    }
+}
+
+impl<R: Read> Read for Counting<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.progress.add(n as u64);
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ruty-archive-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    const BLOCK: usize = 512;
+
+    /// Minimal ustar member: header block plus padded data
+    fn tar_member(name: &str, flag: u8, data: &[u8]) -> Vec<u8> {
+        tar_link(name, flag, "", data)
+    }
+
+    /// Ustar member naming `link` as its target
+    fn tar_link(name: &str, flag: u8, link: &str, data: &[u8]) -> Vec<u8> {
+        let mut header = [0u8; BLOCK];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[100..107].copy_from_slice(b"0000755");
+        header[124..135].copy_from_slice(format!("{:011o}", data.len()).as_bytes());
+        header[156] = flag;
+        header[157..157 + link.len()].copy_from_slice(link.as_bytes());
+        header[257..265].copy_from_slice(b"ustar\x0000");
+        // Checksum: the header's byte sum, counting its own field as spaces
+        header[148..156].fill(b' ');
+        let sum: u32 = header.iter().map(|b| *b as u32).sum();
+        header[148..155].copy_from_slice(format!("{:06o}\0", sum).as_bytes());
+        let mut member = header.to_vec();
+        member.extend_from_slice(data);
+        member.resize(member.len().div_ceil(BLOCK) * BLOCK, 0);
+        member
+    }
+
+    #[test]
+    fn test_kind_from_path() {
+        assert_eq!(ArchiveKind::from_path(Path::new("a/B.TAR.GZ")), Some(ArchiveKind::TarGz));
+        assert_eq!(ArchiveKind::from_path(Path::new("x.tar.zst")), Some(ArchiveKind::TarOther));
+        assert_eq!(ArchiveKind::from_path(Path::new("x.7z")), Some(ArchiveKind::SevenZip));
+        assert_eq!(ArchiveKind::from_path(Path::new("x.gz")), None);
+    }
+
+    #[test]
+    fn test_check_inside() {
+        let dir = temp_dir("inside");
+        let dest = dir.join("dest");
+        fs::create_dir_all(&dest).unwrap();
+        std::os::unix::fs::symlink(&dir, dest.join("up")).unwrap();
+        assert!(check_inside(&dest, &dest.join("a/b.txt")).is_ok());
+        assert!(check_inside(&dest, &dest.join("up")).is_err());
+        assert!(check_inside(&dest, &dest.join("up/b.txt")).is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_tar_list_and_extract() {
+        let dir = temp_dir("tar");
+        let long = format!("{}/file.txt", "d".repeat(120));
+        let mut tar = Vec::new();
+        tar.extend(tar_member("src/", b'5', b""));
+        tar.extend(tar_member("src/run.sh", b'0', b"#!/bin/sh\n"));
+        tar.extend(tar_member("../evil", b'0', b"x"));
+        tar.extend(tar_member("././@LongLink", b'L', format!("{}\0", long).as_bytes()));
+        tar.extend(tar_member("truncated", b'0', b"long"));
+        tar.extend(tar_link("src/latest", b'2', "run.sh", b""));
+        tar.extend(tar_member("src/pipe", b'6', b""));
+        tar.extend([0u8; BLOCK * 2]);
+        let archive = dir.join("test.tar");
+        fs::write(&archive, &tar).unwrap();
+
+        let entries = list(&archive).unwrap();
+        let paths: Vec<&str> = entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/", "src/run.sh", "../evil", long.as_str(), "src/latest", "src/pipe"]);
+        assert!(entries[0].is_dir);
+        assert_eq!(entries[1].size, 10);
+
+        let dest = default_destination(&archive);
+        assert_eq!(dest, dir.join("test"));
+        let progress = progress_for(&archive);
+        let extracted = extract(&archive, &dest, &progress).unwrap();
+        assert_eq!(extracted.files, 3);
+        assert_eq!(
+            extracted.skipped,
+            vec![("../evil".to_string(), "outside the destination"), ("src/pipe".to_string(), "named pipe")]
+        );
+        assert_eq!(fs::read_to_string(dest.join("src/run.sh")).unwrap(), "#!/bin/sh\n");
+        assert_eq!(fs::read_to_string(dest.join(&long)).unwrap(), "long");
+        assert_eq!(fs::read_link(dest.join("src/latest")).unwrap(), Path::new("run.sh"));
+        assert!(!dest.join("src/pipe").exists());
+        assert!(!dir.join("evil").exists());
+        assert_eq!(progress.fraction(), Some(1.0));
+        assert_eq!(default_destination(&archive), dir.join("test (2)"));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_tar_through_symlink() {
+        let dir = temp_dir("tar-link");
+        let mut tar = Vec::new();
+        tar.extend(tar_link("up", b'2', "..", b""));
+        tar.extend(tar_member("up/escaped.txt", b'0', b"x"));
+        tar.extend([0u8; BLOCK * 2]);
+        let archive = dir.join("link.tar");
+        fs::write(&archive, &tar).unwrap();
+
+        let dest = dir.join("dest");
+        assert!(extract(&archive, &dest, &progress_for(&archive)).is_err());
+        assert!(!dir.join("escaped.txt").exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_zip_extract() {
+        let dir = temp_dir("zip");
+        let archive = dir.join("bundle.zip");
+        {
+            let mut zip = zip::ZipWriter::new(File::create(&archive).unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            zip.start_file("docs/readme.txt", options).unwrap();
+            zip.write_all(b"hello").unwrap();
+            zip.start_file("../escape.txt", options).unwrap();
+            zip.write_all(b"x").unwrap();
+            zip.add_symlink("docs/latest", "readme.txt", options).unwrap();
+            zip.finish().unwrap();
+        }
+        assert_eq!(list(&archive).unwrap().len(), 3);
+        let dest = default_destination(&archive);
+        let extracted = extract(&archive, &dest, &progress_for(&archive)).unwrap();
+        assert_eq!(extracted.files, 2);
+        assert_eq!(extracted.skipped, vec![("../escape.txt".to_string(), "outside the destination")]);
+        assert_eq!(fs::read_to_string(dest.join("docs/readme.txt")).unwrap(), "hello");
+        assert_eq!(fs::read_link(dest.join("docs/latest")).unwrap(), Path::new("readme.txt"));
+        assert!(!dir.join("escape.txt").exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_zip_through_symlink() {
+        let dir = temp_dir("zip-link");
+        let archive = dir.join("link.zip");
+        {
+            let mut zip = zip::ZipWriter::new(File::create(&archive).unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            zip.add_symlink("up", "..", options).unwrap();
+            zip.start_file("up/escaped.txt", options).unwrap();
+            zip.write_all(b"x").unwrap();
+            zip.finish().unwrap();
+        }
+        let dest = dir.join("dest");
+        assert!(extract(&archive, &dest, &progress_for(&archive)).is_err());
+        assert!(!dir.join("escaped.txt").exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_parse_7z_listing() {
+        let output = "Path = docs\nSize = 0\nFolder = +\n\nPath = docs/a.txt\nSize = 12\nFolder = -\n";
+        assert_eq!(
+            parse_7z_listing(output),
+            vec![
+                Entry { path: "docs".into(), size: 0, is_dir: true },
+                Entry { path: "docs/a.txt".into(), size: 12, is_dir: false },
+            ]
+        );
+    }
+}
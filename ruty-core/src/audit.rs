@@ -0,0 +1,196 @@
+//! Audit log of executed actions
+//!
+//! Everything Ruty carries out on the user's behalf (apps launched, files
+//! and links opened, palette actions, flows and their shell commands, hooks,
+//! the AI's tool calls) is appended to `audit.jsonl` in the data dir, one
+//! JSON object per line. Nothing here rewrites or trims the file; `/audit`
+//! and `ruty audit --since yesterday` read it back.
+
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use jiff::civil::Date;
+use jiff::tz::TimeZone;
+use jiff::{Timestamp, Zoned};
+use serde::{Deserialize, Serialize};
+
+use crate::paths;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Kind {
+    Launch,
+    Open,
+    /// A command palette action, e.g. emptying the trash
+    Action,
+    Flow,
+    /// A flow's shell step
+    Shell,
+    Hook,
+    /// A tool the AI called while answering
+    Tool,
+}
+
+impl Kind {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::Launch => "Launched",
+            Self::Open => "Opened",
+            Self::Action => "Ran action",
+            Self::Flow => "Ran flow",
+            Self::Shell => "Ran shell command",
+            Self::Hook => "Ran hook",
+            Self::Tool => "AI used tool",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Unix seconds
+    pub time: u64,
+    pub kind: Kind,
+    /// What ran or was opened: app name, path, URL, command line, tool name
+    pub target: String,
+    /// What it ran for, e.g. the flow or hook event, or the prompt
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub detail: String,
+}
+
+pub fn path() -> PathBuf {
+    paths::data_dir().join("audit.jsonl")
+}
+
+/// Append an entry for now; a log that can't be written is only logged
+pub fn record(kind: Kind, target: &str, detail: &str) {
+    let time = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs());
+    let entry = Entry { time, kind, target: target.to_string(), detail: detail.to_string() };
+    if let Err(e) = append(&path(), &entry) {
+        tracing::warn!("Audit log not written: {}", e);
+    }
+}
+
+fn append(path: &Path, entry: &Entry) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let mut line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    line.push('\n');
+    // One write per line, so entries from several threads don't interleave
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Entries of the log at `path` from `since` (Unix seconds) on, oldest
+/// first; lines that don't parse are skipped
+pub fn read(path: &Path, since: u64) -> Result<Vec<Entry>, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    Ok(text
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Entry>(line).ok())
+        .filter(|entry| entry.time >= since)
+        .collect())
+}
+
+/// Entries of the audit log from `since` on, oldest first
+pub fn since(since: u64) -> Result<Vec<Entry>, String> {
+    read(&path(), since)
+}
+
+/// Start of the period `text` names, in Unix seconds: "today", "yesterday",
+/// a date ("2026-10-01") or a span back from now ("30m", "12h", "3d", "2w")
+pub fn parse_since(text: &str) -> Result<u64, String> {
+    parse_since_at(text, &Zoned::now())
+}
+
+fn parse_since_at(text: &str, now: &Zoned) -> Result<u64, String> {
+    let text = text.trim().to_lowercase();
+    let start_of = |date: Date| date.to_zoned(now.time_zone().clone()).map(|zoned| zoned.timestamp());
+    let start = match text.as_str() {
+        "today" => start_of(now.date()),
+        "yesterday" => now.date().yesterday().and_then(start_of),
+        _ if text.contains('-') => text.parse::<Date>().and_then(start_of),
+        _ => {
+            let unit = text.chars().last().filter(char::is_ascii_alphabetic);
+            let seconds = match unit {
+                Some('m') => 60,
+                Some('h') => 3600,
+                Some('d') => 86_400,
+                Some('w') => 7 * 86_400,
+                _ => return Err(bad_since(&text)),
+            };
+            let count: i64 = text[..text.len() - 1].parse().map_err(|_| bad_since(&text))?;
+            return Ok(now.timestamp().as_second().saturating_sub(count.saturating_mul(seconds)).max(0) as u64);
+        }
+    };
+    start.map(|start| start.as_second().max(0) as u64).map_err(|_| bad_since(&text))
+}
+
+fn bad_since(text: &str) -> String {
+    format!("Can't tell when '{}' is (try today, yesterday, 2026-10-01 or 3d)", text)
+}
+
+/// What happened and what for, e.g. "Ran hook notify-send hi (window_shown)"
+pub fn summary(entry: &Entry) -> String {
+    match entry.detail.as_str() {
+        "" => format!("{} {}", entry.kind.describe(), entry.target),
+        detail => format!("{} {} ({})", entry.kind.describe(), entry.target, detail),
+    }
+}
+
+/// One entry as `ruty audit` prints it, after its local date and time
+pub fn line(entry: &Entry) -> String {
+    let when = i64::try_from(entry.time)
+        .ok()
+        .and_then(|seconds| Timestamp::from_second(seconds).ok())
+        .map_or_else(String::new, |at| at.to_zoned(TimeZone::system()).strftime("%Y-%m-%d %H:%M:%S").to_string());
+    format!("{}  {}", when, summary(entry))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_read() {
+        let root = std::env::temp_dir().join(format!("ruty-audit-{}", std::process::id()));
+        let path = root.join("audit.jsonl");
+        assert_eq!(read(&path, 0).unwrap(), Vec::new());
+
+        let launched = Entry { time: 100, kind: Kind::Launch, target: "Firefox".into(), detail: String::new() };
+        let hook = Entry { time: 200, kind: Kind::Hook, target: "notify-send hi".into(), detail: "window_shown".into() };
+        append(&path, &launched).unwrap();
+        fs::write(&path, fs::read_to_string(&path).unwrap() + "not json\n").unwrap();
+        append(&path, &hook).unwrap();
+        assert_eq!(read(&path, 0).unwrap(), [launched.clone(), hook.clone()]);
+        assert_eq!(read(&path, 150).unwrap(), [hook]);
+        // The detail is left out when there is none
+        let first = fs::read_to_string(&path).unwrap().lines().next().map(String::from);
+        assert_eq!(first.as_deref(), Some(r#"{"time":100,"kind":"launch","target":"Firefox"}"#));
+        assert_eq!(summary(&read(&path, 150).unwrap()[0]), "Ran hook notify-send hi (window_shown)");
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now: Zoned = "2026-10-16T15:00[UTC]".parse().unwrap();
+        let at = |stamp: &str| stamp.parse::<Zoned>().unwrap().timestamp().as_second() as u64;
+        assert_eq!(parse_since_at("today", &now), Ok(at("2026-10-16T00:00[UTC]")));
+        assert_eq!(parse_since_at("Yesterday", &now), Ok(at("2026-10-15T00:00[UTC]")));
+        assert_eq!(parse_since_at("2026-10-01", &now), Ok(at("2026-10-01T00:00[UTC]")));
+        assert_eq!(parse_since_at("3h", &now), Ok(at("2026-10-16T12:00[UTC]")));
+        assert_eq!(parse_since_at("2w", &now), Ok(at("2026-10-02T15:00[UTC]")));
+        assert!(parse_since_at("lately", &now).is_err());
+        assert!(parse_since_at("h", &now).is_err());
+    }
+}
@@ -0,0 +1,276 @@
+//! Autostart management
+//!
+//! Installs the daemon either as an XDG autostart entry
+//! (`~/.config/autostart/ruty.desktop`) or as a systemd user service
+//! (`~/.config/systemd/user/ruty.service`), optionally socket-activated
+//! through `ruty.socket` so the first client connection starts it.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::paths;
+
+/// systemd unit name for the daemon
+pub const SERVICE_NAME: &str = "ruty.service";
+
+/// systemd socket unit name for on-demand activation
+pub const SOCKET_NAME: &str = "ruty.socket";
+
+/// How the daemon gets started at login
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Method {
+    /// XDG autostart .desktop entry (works on any desktop)
+    Desktop,
+    /// systemd user service bound to the graphical session
+    Systemd,
+}
+
+/// Which autostart mechanisms are currently installed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Status {
+    pub desktop: bool,
+    pub systemd: bool,
+    pub socket: bool,
+}
+
+impl Status {
+    pub fn enabled(&self) -> bool {
+        self.desktop || self.systemd || self.socket
+    }
+}
+
+/// Path of the XDG autostart entry
+pub fn desktop_entry_path() -> PathBuf {
+    config_dir().join("autostart").join("ruty.desktop")
+}
+
+/// Path of the systemd user unit
+pub fn service_path() -> PathBuf {
+    config_dir().join("systemd").join("user").join(SERVICE_NAME)
+}
+
+/// Path of the systemd user socket unit
+pub fn socket_path() -> PathBuf {
+    config_dir().join("systemd").join("user").join(SOCKET_NAME)
+}
+
+/// Install autostart for the daemon binary at `exe`
+pub fn enable(method: Method, exe: &Path) -> Result<PathBuf, String> {
+    match method {
+        Method::Desktop => {
+            let path = desktop_entry_path();
+            write_file(&path, &desktop_entry(exe))?;
+            Ok(path)
+        }
+        Method::Systemd => {
+            let path = service_path();
+            write_file(&path, &service_unit(exe))?;
+            systemctl(&["daemon-reload"])?;
+            systemctl(&["enable", SERVICE_NAME])?;
+            Ok(path)
+        }
+    }
+}
+
+/// Install the service plus a socket unit listening on `listen`
+/// (e.g. `127.0.0.1:42321`), so the daemon starts on first connection
+pub fn enable_socket(exe: &Path, listen: &str) -> Result<Vec<PathBuf>, String> {
+    let service = service_path();
+    let socket = socket_path();
+    write_file(&service, &service_unit(exe))?;
+    write_file(&socket, &socket_unit(listen))?;
+    systemctl(&["daemon-reload"])?;
+    systemctl(&["enable", "--now", SOCKET_NAME])?;
+    Ok(vec![service, socket])
+}
+
+/// Write the service and socket units into `dir` without installing them
+pub fn generate(dir: &Path, exe: &Path, listen: &str) -> Result<Vec<PathBuf>, String> {
+    let service = dir.join(SERVICE_NAME);
+    let socket = dir.join(SOCKET_NAME);
+    write_file(&service, &service_unit(exe))?;
+    write_file(&socket, &socket_unit(listen))?;
+    Ok(vec![service, socket])
+}
+
+/// Remove every installed autostart mechanism
+pub fn disable() -> Result<(), String> {
+    let desktop = desktop_entry_path();
+    if desktop.exists() {
+        fs::remove_file(&desktop).map_err(|e| format!("Failed to remove {:?}: {}", desktop, e))?;
+    }
+
+    let socket = socket_path();
+    if socket.exists() {
+        let _ = systemctl(&["disable", "--now", SOCKET_NAME]);
+        fs::remove_file(&socket).map_err(|e| format!("Failed to remove {:?}: {}", socket, e))?;
+    }
+
+    let service = service_path();
+    if service.exists() {
+        // Ignore failure: the unit may never have been enabled
+        let _ = systemctl(&["disable", SERVICE_NAME]);
+        fs::remove_file(&service).map_err(|e| format!("Failed to remove {:?}: {}", service, e))?;
+        let _ = systemctl(&["daemon-reload"]);
+    }
+
+    Ok(())
+}
+
+/// Report which autostart mechanisms are installed
+pub fn status() -> Status {
+    Status {
+        desktop: desktop_entry_path().exists(),
+        systemd: service_path().exists(),
+        socket: socket_path().exists(),
+    }
+}
+
+/// XDG autostart entry contents
+pub fn desktop_entry(exe: &Path) -> String {
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Ruty\n\
+         Comment=AI-powered productivity launcher\n\
+         Exec={}\n\
+         Icon=ruty\n\
+         NoDisplay=true\n\
+         X-GNOME-Autostart-enabled=true\n",
+        desktop_exec(exe)
+    )
+}
+
+/// systemd user unit contents
+pub fn service_unit(exe: &Path) -> String {
+    format!(
+        "[Unit]\n\
+         Description=Ruty launcher daemon\n\
+         PartOf=graphical-session.target\n\
+         After=graphical-session.target\n\
+         \n\
+         [Service]\n\
+         ExecStart={}\n\
+         Restart=on-failure\n\
+         \n\
+         [Install]\n\
+         WantedBy=graphical-session.target\n",
+        systemd_exec(exe)
+    )
+}
+
+/// systemd socket unit contents
+pub fn socket_unit(listen: &str) -> String {
+    format!(
+        "[Unit]\n\
+         Description=Ruty launcher daemon socket\n\
+         \n\
+         [Socket]\n\
+         ListenStream={}\n\
+         \n\
+         [Install]\n\
+         WantedBy=sockets.target\n",
+        listen
+    )
+}
+
+/// `exe` as the program of a desktop entry's `Exec`: quoted, with `"`, `` ` ``,
+/// `$` and `\` escaped inside the quotes, then escaped again as a string
+/// value (so a backslash ends up as four) and `%` doubled, per the Desktop
+/// Entry spec
+fn desktop_exec(exe: &Path) -> String {
+    let mut argument = String::from("\"");
+    for c in exe.to_string_lossy().chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            argument.push('\\');
+        }
+        argument.push(c);
+    }
+    argument.push('"');
+
+    let mut value = String::new();
+    for c in argument.chars() {
+        match c {
+            '\\' => value.push_str("\\\\"),
+            '\n' => value.push_str("\\n"),
+            '\t' => value.push_str("\\t"),
+            '\r' => value.push_str("\\r"),
+            '%' => value.push_str("%%"),
+            c => value.push(c),
+        }
+    }
+    value
+}
+
+/// `exe` as the program of a systemd `ExecStart`: quoted with C-style
+/// escapes, and `%` and `$` doubled so they're neither specifiers nor
+/// variables
+fn systemd_exec(exe: &Path) -> String {
+    let mut quoted = String::from("\"");
+    for c in exe.to_string_lossy().chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            '%' => quoted.push_str("%%"),
+            '$' => quoted.push_str("$$"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn config_dir() -> PathBuf {
+    paths::config_home()
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+    }
+    fs::write(path, content).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+fn systemctl(args: &[&str]) -> Result<(), String> {
+    let status = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .status()
+        .map_err(|e| format!("Failed to run systemctl: {}", e))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("systemctl --user {} failed: {}", args.join(" "), status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_files_use_exe() {
+        let exe = Path::new("/opt/ruty/ruty");
+        assert!(desktop_entry(exe).contains("Exec=\"/opt/ruty/ruty\"\n"));
+        assert!(service_unit(exe).contains("ExecStart=\"/opt/ruty/ruty\"\n"));
+        assert!(socket_unit("127.0.0.1:42321").contains("ListenStream=127.0.0.1:42321\n"));
+    }
+
+    #[test]
+    fn test_exe_paths_are_quoted() {
+        let exe = Path::new("/home/me/My Apps/ruty");
+        assert!(desktop_entry(exe).contains("Exec=\"/home/me/My Apps/ruty\"\n"));
+        assert!(service_unit(exe).contains("ExecStart=\"/home/me/My Apps/ruty\"\n"));
+
+        let exe = Path::new(r#"/opt/50% off/$x "y"\z"#);
+        assert_eq!(desktop_exec(exe), r#""/opt/50%% off/\\$x \\"y\\"\\\\z""#);
+        assert_eq!(systemd_exec(exe), r#""/opt/50%% off/$$x \"y\"\\z""#);
+    }
+}
@@ -0,0 +1,274 @@
+//! Size-budgeted LRU caches
+//!
+//! Long-running daemons keep icons, thumbnails and the like around between
+//! searches. Each cache gets a byte budget; every entry is inserted with its
+//! approximate size, and the least recently used entries are evicted once
+//! the total goes over budget. Caches stay small (hundreds of entries), so
+//! eviction just scans for the oldest entry.
+//!
+//! `ResponseCache` builds on it for answers that are the same for the same
+//! query for a while, so typing a query again (or a frontend asking on every
+//! keystroke) doesn't redo the work.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// Bytes in a megabyte, for budgets configured in MB
+pub const MB: usize = 1024 * 1024;
+
+/// Counters reported by `/cache stats`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub bytes: usize,
+    pub budget: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    /// One-line summary, e.g. "12 entries, 1.5 MB / 32.0 MB, 90% hits, 3 evicted"
+    pub fn summary(&self) -> String {
+        let mut summary = format!("{} entries, {} / {}", self.entries, format_mb(self.bytes), format_mb(self.budget));
+        if let Some(rate) = (self.hits * 100).checked_div(self.hits + self.misses) {
+            summary.push_str(&format!(", {}% hits", rate));
+        }
+        if self.evictions > 0 {
+            summary.push_str(&format!(", {} evicted", self.evictions));
+        }
+        summary
+    }
+}
+
+/// "1.5 MB"
+pub fn format_mb(bytes: usize) -> String {
+    format!("{:.1} MB", bytes as f64 / MB as f64)
+}
+
+/// Resident memory of this process, from /proc (Linux only)
+pub fn resident_bytes() -> Option<usize> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kb = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse::<usize>()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+#[derive(Debug, Clone)]
+struct Entry<V> {
+    value: V,
+    size: usize,
+    /// Value of the cache's clock at the last access
+    used: u64,
+}
+
+/// Map with a byte budget that evicts least recently used entries
+#[derive(Debug, Clone)]
+pub struct LruCache<K, V> {
+    entries: HashMap<K, Entry<V>>,
+    budget: usize,
+    bytes: usize,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl<K: Eq + Hash + Clone, V> LruCache<K, V> {
+    /// Empty cache holding at most `budget` bytes
+    pub fn new(budget: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            budget,
+            bytes: 0,
+            clock: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    /// Look up an entry, marking it as recently used
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let now = self.tick();
+        match self.entries.get_mut(key) {
+            Some(entry) => {
+                self.hits += 1;
+                entry.used = now;
+                Some(&entry.value)
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Look up an entry without touching its recency or the counters
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).map(|entry| &entry.value)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    /// Insert (or replace) an entry of `size` bytes, then evict down to the
+    /// budget. An entry larger than the whole budget is not kept (and
+    /// evicts nothing).
+    pub fn insert(&mut self, key: K, value: V, size: usize) {
+        if size > self.budget {
+            self.remove(&key);
+            return;
+        }
+        let used = self.tick();
+        if let Some(old) = self.entries.insert(key.clone(), Entry { value, size, used }) {
+            self.bytes -= old.size;
+        }
+        self.bytes += size;
+        while self.bytes > self.budget {
+            let Some(oldest) = self.entries.iter().min_by_key(|(_, e)| e.used).map(|(k, _)| k.clone()) else {
+                break;
+            };
+            self.remove(&oldest);
+            self.evictions += 1;
+        }
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let entry = self.entries.remove(key)?;
+        self.bytes -= entry.size;
+        Some(entry.value)
+    }
+
+    /// Drop every entry (the counters are kept)
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.bytes = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.len(),
+            bytes: self.bytes,
+            budget: self.budget,
+            hits: self.hits,
+            misses: self.misses,
+            evictions: self.evictions,
+        }
+    }
+}
+
+/// Answers of deterministic providers, keyed by provider and query. Each
+/// lookup says how long its provider's answers stay good; older ones are
+/// dropped rather than returned.
+#[derive(Debug, Clone)]
+pub struct ResponseCache<V> {
+    entries: LruCache<(&'static str, String), (Instant, V)>,
+}
+
+impl<V: Clone> ResponseCache<V> {
+    pub fn new(budget: usize) -> Self {
+        Self { entries: LruCache::new(budget) }
+    }
+
+    /// What `provider` answered to `query` less than `ttl` ago
+    pub fn get(&mut self, provider: &'static str, query: &str, ttl: Duration) -> Option<V> {
+        let key = (provider, query.to_string());
+        if self.entries.peek(&key).is_some_and(|(answered, _)| answered.elapsed() >= ttl) {
+            self.entries.remove(&key);
+        }
+        self.entries.get(&key).map(|(_, value)| value.clone())
+    }
+
+    /// Keep an answer of about `size` bytes
+    pub fn insert(&mut self, provider: &'static str, query: &str, value: V, size: usize) {
+        self.entries.insert((provider, query.to_string()), (Instant::now(), value), size + query.len());
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.entries.stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = LruCache::new(30);
+        cache.insert("a", 1, 10);
+        cache.insert("b", 2, 10);
+        cache.insert("c", 3, 10);
+        // Touch "a" so "b" is the oldest
+        assert_eq!(cache.get(&"a"), Some(&1));
+        cache.insert("d", 4, 10);
+
+        assert!(!cache.contains_key(&"b"));
+        assert!(cache.contains_key(&"a") && cache.contains_key(&"c") && cache.contains_key(&"d"));
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.bytes, stats.evictions), (3, 30, 1));
+    }
+
+    #[test]
+    fn test_replace_and_oversized() {
+        let mut cache = LruCache::new(100);
+        cache.insert("a", 1, 40);
+        cache.insert("a", 2, 60);
+        assert_eq!(cache.stats().bytes, 60);
+        assert_eq!(cache.peek(&"a"), Some(&2));
+
+        cache.insert("huge", 3, 500);
+        assert!(!cache.contains_key(&"huge"));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut cache = LruCache::new(2 * MB);
+        cache.insert(1, (), MB / 2);
+        cache.get(&1);
+        cache.get(&2);
+        assert_eq!(cache.stats().summary(), "1 entries, 0.5 MB / 2.0 MB, 50% hits");
+    }
+
+    #[test]
+    fn test_response_cache() {
+        let mut cache = ResponseCache::new(MB);
+        let ttl = Duration::from_secs(60);
+        cache.insert("files", "report", vec!["report.pdf"], 10);
+        assert_eq!(cache.get("files", "report", ttl), Some(vec!["report.pdf"]));
+        // Same query, other provider
+        assert_eq!(cache.get("repos", "report", ttl), None);
+        // Too old for a provider that wants fresher answers
+        assert_eq!(cache.get("files", "report", Duration::ZERO), None);
+        assert_eq!(cache.get("files", "report", ttl), None);
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.hits, stats.misses), (0, 1, 3));
+    }
+}
@@ -0,0 +1,383 @@
+//! Calculator for plain prompts
+//!
+//! `2^10`, `sqrt(2) * 3` or `(ans + 1) / 2` evaluate as they're typed. The
+//! `Calculator` keeps what the session calculated: `ans` is the last result,
+//! `x = 5*3` sets a variable later expressions can use, and the most recent
+//! calculations are kept for the detail pane. Only input that reads as math
+//! (an operator or function, every name known) counts, so words still go to
+//! search and AI.
+
+use std::collections::{BTreeMap, VecDeque};
+
+/// Calculations kept in the history
+pub const HISTORY_LEN: usize = 20;
+
+/// Function of one argument, e.g. `sqrt`
+type Function = fn(f64) -> f64;
+
+// Neither constants nor functions can be assigned
+const CONSTANTS: &[(&str, f64)] =
+    &[("pi", std::f64::consts::PI), ("e", std::f64::consts::E), ("tau", std::f64::consts::TAU)];
+const FUNCTIONS: &[(&str, Function)] = &[
+    ("sqrt", f64::sqrt),
+    ("cbrt", f64::cbrt),
+    ("abs", f64::abs),
+    ("ln", f64::ln),
+    ("log", f64::log10),
+    ("log2", f64::log2),
+    ("exp", f64::exp),
+    ("sin", f64::sin),
+    ("cos", f64::cos),
+    ("tan", f64::tan),
+    ("asin", f64::asin),
+    ("acos", f64::acos),
+    ("atan", f64::atan),
+    ("floor", f64::floor),
+    ("ceil", f64::ceil),
+    ("round", f64::round),
+];
+/// Name of the last result
+const ANS: &str = "ans";
+
+/// One evaluated input
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calculation {
+    /// The expression as typed (right of the `=` for an assignment)
+    pub expression: String,
+    /// Variable the input assigns, e.g. `x` for `x = 5*3`
+    pub variable: Option<String>,
+    pub value: f64,
+}
+
+impl Calculation {
+    /// The input it came from, e.g. "x = 5*3"
+    pub fn input(&self) -> String {
+        match &self.variable {
+            Some(name) => format!("{} = {}", name, self.expression),
+            None => self.expression.clone(),
+        }
+    }
+}
+
+/// Variables, `ans` and recent calculations of a session
+#[derive(Debug, Clone, Default)]
+pub struct Calculator {
+    variables: BTreeMap<String, f64>,
+    ans: Option<f64>,
+    /// Newest first
+    history: VecDeque<Calculation>,
+}
+
+impl Calculator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Evaluate `input`, or `None` if it doesn't read as math. Nothing is
+    /// kept until the result is `record`ed.
+    pub fn calculate(&self, input: &str) -> Option<Calculation> {
+        let input = input.trim();
+        let (variable, expression) = match input.split_once('=') {
+            Some((name, expression)) if is_assignable(name.trim()) => {
+                (Some(name.trim().to_string()), expression.trim())
+            }
+            Some(_) => return None,
+            None => (None, input),
+        };
+        let tokens = tokenize(expression)?;
+        let mut parser = Parser { tokens: &tokens, position: 0, calculator: self, operators: 0 };
+        let value = parser.expression().ok()?;
+        if parser.position != tokens.len() || !value.is_finite() {
+            return None;
+        }
+        // A lone number or name isn't a calculation, unless it's being assigned
+        if parser.operators == 0 && variable.is_none() {
+            return None;
+        }
+        Some(Calculation { expression: expression.to_string(), variable, value })
+    }
+
+    /// Keep a result: it becomes `ans`, sets its variable and heads the history
+    pub fn record(&mut self, calculation: Calculation) {
+        self.ans = Some(calculation.value);
+        if let Some(name) = &calculation.variable {
+            self.variables.insert(name.clone(), calculation.value);
+        }
+        self.history.push_front(calculation);
+        self.history.truncate(HISTORY_LEN);
+    }
+
+    /// Recent calculations, newest first
+    pub fn history(&self) -> impl Iterator<Item = &Calculation> {
+        self.history.iter()
+    }
+
+    pub fn variables(&self) -> &BTreeMap<String, f64> {
+        &self.variables
+    }
+
+    pub fn ans(&self) -> Option<f64> {
+        self.ans
+    }
+
+    fn lookup(&self, name: &str) -> Option<f64> {
+        if name == ANS {
+            return self.ans;
+        }
+        self.variables
+            .get(name)
+            .copied()
+            .or_else(|| CONSTANTS.iter().find(|(constant, _)| *constant == name).map(|(_, value)| *value))
+    }
+}
+
+/// A value as shown: whole numbers without decimals, others to at most 10
+/// places so float noise (0.1 + 0.2) doesn't show
+pub fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        return format!("{}", value as i64);
+    }
+    if value.abs() >= 1e15 || value.abs() < 1e-6 {
+        return format!("{:e}", value);
+    }
+    let text = format!("{:.10}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_assignable(name: &str) -> bool {
+    is_identifier(name)
+        && name != ANS
+        && !CONSTANTS.iter().any(|(constant, _)| *constant == name)
+        && !FUNCTIONS.iter().any(|(function, _)| *function == name)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Operator(char),
+    Open,
+    Close,
+}
+
+/// `None` on a character that has no place in math
+fn tokenize(text: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '0'..='9' | '.' => {
+                let mut end = start;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_ascii_digit() || c == '.') {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                tokens.push(Token::Number(text[start..end].parse().ok()?));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut end = start;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                tokens.push(Token::Name(text[start..end].to_string()));
+            }
+            '+' | '-' | '*' | '/' | '%' | '^' => {
+                tokens.push(Token::Operator(c));
+                chars.next();
+            }
+            '×' => {
+                tokens.push(Token::Operator('*'));
+                chars.next();
+            }
+            '÷' => {
+                tokens.push(Token::Operator('/'));
+                chars.next();
+            }
+            '(' => {
+                tokens.push(Token::Open);
+                chars.next();
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                chars.next();
+            }
+            _ => return None,
+        }
+    }
+    Some(tokens)
+}
+
+/// Recursive descent over the usual precedence: `+ -`, then `* / %`, then
+/// unary minus, then `^` (right-associative)
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    calculator: &'a Calculator,
+    /// Operators and function calls seen
+    operators: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        self.position += 1;
+        self.tokens.get(self.position - 1)
+    }
+
+    /// Consume the next token if it is one of `ops`
+    fn operator(&mut self, ops: &[char]) -> Option<char> {
+        match self.peek() {
+            Some(Token::Operator(op)) if ops.contains(op) => {
+                let op = *op;
+                self.position += 1;
+                self.operators += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn expression(&mut self) -> Result<f64, String> {
+        let mut value = self.term()?;
+        while let Some(op) = self.operator(&['+', '-']) {
+            let rhs = self.term()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<f64, String> {
+        let mut value = self.unary()?;
+        while let Some(op) = self.operator(&['*', '/', '%']) {
+            let rhs = self.unary()?;
+            value = match op {
+                '*' => value * rhs,
+                '/' => value / rhs,
+                _ => value % rhs,
+            };
+        }
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<f64, String> {
+        match self.operator(&['-', '+']) {
+            Some('-') => Ok(-self.unary()?),
+            Some(_) => self.unary(),
+            None => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<f64, String> {
+        let base = self.atom()?;
+        match self.operator(&['^']) {
+            Some(_) => Ok(base.powf(self.unary()?)),
+            None => Ok(base),
+        }
+    }
+
+    fn atom(&mut self) -> Result<f64, String> {
+        match self.next().cloned() {
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::Open) => {
+                let value = self.expression()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(value),
+                    _ => Err("Missing )".to_string()),
+                }
+            }
+            Some(Token::Name(name)) => {
+                if self.peek() == Some(&Token::Open) {
+                    let function = FUNCTIONS
+                        .iter()
+                        .find(|(function, _)| *function == name)
+                        .map(|(_, function)| *function)
+                        .ok_or_else(|| format!("Unknown function: {}", name))?;
+                    self.operators += 1;
+                    return Ok(function(self.atom()?));
+                }
+                self.calculator.lookup(&name).ok_or_else(|| format!("Unknown name: {}", name))
+            }
+            _ => Err("Expected a number".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(calculator: &Calculator, input: &str) -> Option<f64> {
+        calculator.calculate(input).map(|calculation| calculation.value)
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let calculator = Calculator::new();
+        assert_eq!(value(&calculator, "1 + 2 * 3"), Some(7.0));
+        assert_eq!(value(&calculator, "(1 + 2) * 3"), Some(9.0));
+        assert_eq!(value(&calculator, "2^3^2"), Some(512.0));
+        assert_eq!(value(&calculator, "-2^2"), Some(-4.0));
+        assert_eq!(value(&calculator, "10 % 4 - -1"), Some(3.0));
+        assert_eq!(value(&calculator, "sqrt(16) + abs(-2)"), Some(6.0));
+        assert_eq!(value(&calculator, "6 × 7 ÷ 2"), Some(21.0));
+        assert!((value(&calculator, "2 * pi").unwrap() - std::f64::consts::TAU).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_not_math() {
+        let calculator = Calculator::new();
+        for input in ["42", "pi", "what is rust", "x-ray", "e-mail", "1 / 0", "(1 + 2", "sqrt 4", "a = b = 1", "ans + 1"] {
+            assert_eq!(calculator.calculate(input), None, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_ans_and_variables() {
+        let mut calculator = Calculator::new();
+        let assignment = calculator.calculate("x = 5*3").unwrap();
+        assert_eq!((assignment.variable.as_deref(), assignment.value), (Some("x"), 15.0));
+        assert_eq!(assignment.input(), "x = 5*3");
+        // Not kept until recorded
+        assert_eq!(calculator.calculate("x + 1"), None);
+        calculator.record(assignment);
+        assert_eq!(value(&calculator, "x + 1"), Some(16.0));
+        assert_eq!(value(&calculator, "ans * 2"), Some(30.0));
+        calculator.record(calculator.calculate("ans * 2").unwrap());
+        assert_eq!(calculator.ans(), Some(30.0));
+        assert_eq!(calculator.history().map(Calculation::input).collect::<Vec<_>>(), ["ans * 2", "x = 5*3"]);
+        assert_eq!(calculator.calculate("pi = 3"), None);
+        assert_eq!(calculator.calculate("y = 2").map(|c| c.value), Some(2.0));
+
+        for _ in 0..HISTORY_LEN + 5 {
+            calculator.record(calculator.calculate("ans + 1").unwrap());
+        }
+        assert_eq!(calculator.history().count(), HISTORY_LEN);
+    }
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!(format_value(15.0), "15");
+        assert_eq!(format_value(0.1 + 0.2), "0.3");
+        assert_eq!(format_value(-2.5), "-2.5");
+        assert_eq!(format_value(1e20), "1e20");
+    }
+}
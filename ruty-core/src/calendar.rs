@@ -0,0 +1,597 @@
+//! Calendar agenda
+//!
+//! "today" and `/agenda` list upcoming events read from iCalendar files: the
+//! ones under `[calendar] ics` plus Evolution's local calendar (which GNOME
+//! Calendar writes its on-this-computer events to) when there is one. The
+//! evolution-data-server D-Bus API isn't used: it needs a view per calendar
+//! and a running factory, while the file covers the local calendar offline.
+//!
+//! Recurring events are expanded for the common rules (daily, weekly on given
+//! days, monthly and yearly, with an interval, count or end date) minus their
+//! exceptions; rarer parts such as BYMONTHDAY or BYSETPOS are ignored.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use jiff::civil::{Date, DateTime, Weekday};
+use jiff::tz::TimeZone;
+use jiff::{Span, Timestamp, ToSpan, Zoned};
+
+use crate::paths;
+
+/// Hosts of video call services, for spotting meeting links
+const MEETING_HOSTS: &[&str] = &[
+    "meet.google.com",
+    "zoom.us",
+    "teams.microsoft.com",
+    "teams.live.com",
+    "whereby.com",
+    "meet.jit.si",
+    "webex.com",
+];
+/// Recurrences expanded per event before giving up on reaching the window
+const MAX_STEPS: usize = 10_000;
+
+/// One occurrence of an event
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub summary: String,
+    pub start: Zoned,
+    pub end: Zoned,
+    pub all_day: bool,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    /// Video call link from the event's URL, location or description
+    pub meeting_link: Option<String>,
+}
+
+impl Event {
+    /// When it happens: "Today 09:00–09:30", "Tomorrow · All day", "Now
+    /// until 11:00", "Fri 17 Oct 14:00"
+    pub fn when(&self) -> String {
+        self.when_at(&Zoned::now())
+    }
+
+    /// Its day as YYYY-MM-DD, for opening a calendar app on it
+    pub fn day(&self) -> String {
+        self.start.date().to_string()
+    }
+
+    fn when_at(&self, now: &Zoned) -> String {
+        if !self.all_day && self.start <= *now && *now < self.end {
+            return format!("Now until {}", self.end.strftime("%H:%M"));
+        }
+        let date = self.start.date();
+        let day = if date == now.date() {
+            "Today".to_string()
+        } else if now.date().tomorrow().is_ok_and(|tomorrow| tomorrow == date) {
+            "Tomorrow".to_string()
+        } else {
+            format!("{} {} {}", self.start.strftime("%a"), date.day(), self.start.strftime("%b"))
+        };
+        if self.all_day {
+            format!("{} · All day", day)
+        } else if self.end > self.start && self.end.date() == date {
+            format!("{} {}–{}", day, self.start.strftime("%H:%M"), self.end.strftime("%H:%M"))
+        } else {
+            format!("{} {}", day, self.start.strftime("%H:%M"))
+        }
+    }
+}
+
+/// Configured calendar files plus Evolution's local calendar, if it exists
+pub fn sources(configured: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut sources = configured;
+    let evolution = paths::data_home().join("evolution/calendar/system/calendar.ics");
+    if evolution.is_file() && !sources.contains(&evolution) {
+        sources.push(evolution);
+    }
+    sources
+}
+
+/// Events from `files` that haven't ended yet and start within `days` days
+/// (today being the first), soonest first
+pub fn agenda(files: &[PathBuf], days: u32) -> Vec<Event> {
+    let now = &Zoned::now();
+    let until = now
+        .start_of_day()
+        .and_then(|today| today.checked_add(i64::from(days.max(1)).days()))
+        .unwrap_or_else(|_| now.clone());
+    let mut events: Vec<Event> = files
+        .iter()
+        .filter_map(|file| match fs::read_to_string(file) {
+            Ok(text) => Some(text),
+            Err(e) => {
+                tracing::warn!("Cannot read calendar {}: {}", file.display(), e);
+                None
+            }
+        })
+        .flat_map(|text| occurrences(&text, now, &until))
+        .collect();
+    events.sort_by(|a, b| a.start.cmp(&b.start).then_with(|| a.summary.cmp(&b.summary)));
+    events
+}
+
+/// A content line: `NAME;PARAM=value:value` (vCards share the format)
+#[derive(Debug, Clone)]
+pub(crate) struct Property {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    pub value: String,
+}
+
+impl Property {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// A VEVENT's properties (those of nested components such as VALARM left out)
+type Component = Vec<Property>;
+
+/// Occurrences in `text` overlapping `from`..`until`, in `from`'s time zone
+fn occurrences(text: &str, from: &Zoned, until: &Zoned) -> Vec<Event> {
+    let local = from.time_zone();
+    let events = components(text);
+    // Moved or edited occurrences are events of their own with a
+    // RECURRENCE-ID, replacing that occurrence of the series
+    let mut overridden: HashMap<String, Vec<Timestamp>> = HashMap::new();
+    for event in &events {
+        if let (Some(uid), Some(id)) = (get(event, "UID"), find(event, "RECURRENCE-ID")) {
+            if let Some((moment, _)) = parse_time(id, local) {
+                overridden.entry(uid.value.clone()).or_default().push(moment.timestamp());
+            }
+        }
+    }
+    events
+        .iter()
+        .filter(|event| get(event, "STATUS").is_none_or(|status| !status.value.eq_ignore_ascii_case("CANCELLED")))
+        .flat_map(|event| {
+            let skip = match (get(event, "UID"), find(event, "RECURRENCE-ID")) {
+                (Some(uid), None) => overridden.get(&uid.value).cloned().unwrap_or_default(),
+                _ => Vec::new(),
+            };
+            expand(event, local, &skip, from, until)
+        })
+        .collect()
+}
+
+/// The VEVENTs of an iCalendar file
+fn components(text: &str) -> Vec<Component> {
+    let mut events = Vec::new();
+    let mut stack: Vec<String> = Vec::new();
+    let mut current: Component = Vec::new();
+    for line in unfold(text) {
+        let Some(property) = parse_line(&line) else { continue };
+        match property.name.as_str() {
+            "BEGIN" => {
+                if property.value.eq_ignore_ascii_case("VEVENT") {
+                    current.clear();
+                }
+                stack.push(property.value.to_uppercase());
+            }
+            "END" => {
+                let ended = stack.pop();
+                if ended.as_deref() == Some("VEVENT") {
+                    events.push(std::mem::take(&mut current));
+                }
+            }
+            _ if stack.last().map(String::as_str) == Some("VEVENT") => current.push(property),
+            _ => {}
+        }
+    }
+    events
+}
+
+/// Join folded lines (continuations start with a space or tab)
+pub(crate) fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+pub(crate) fn parse_line(line: &str) -> Option<Property> {
+    // The value starts at the first colon outside a quoted parameter
+    let mut quoted = false;
+    let split = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(i),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..split], &line[split + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_uppercase();
+    let params = parts
+        .filter_map(|param| param.split_once('='))
+        .map(|(key, value)| (key.to_uppercase(), value.trim_matches('"').to_string()))
+        .collect();
+    Some(Property { name, params, value: value.to_string() })
+}
+
+fn find<'a>(event: &'a Component, name: &str) -> Option<&'a Property> {
+    event.iter().find(|property| property.name == name)
+}
+
+/// A property whose value isn't empty
+fn get<'a>(event: &'a Component, name: &str) -> Option<&'a Property> {
+    find(event, name).filter(|property| !property.value.trim().is_empty())
+}
+
+/// A text value with its escapes undone
+fn text(event: &Component, name: &str) -> Option<String> {
+    Some(unescape(&get(event, name)?.value).trim().to_string())
+}
+
+/// Undo the escaping of commas, semicolons, backslashes and newlines in a
+/// text value (vCards escape them the same way)
+pub(crate) fn unescape(value: &str) -> String {
+    let mut text = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => text.push('\n'),
+            Some(other) => text.push(other),
+            None => {}
+        }
+    }
+    text
+}
+
+/// A DATE or DATE-TIME value as a civil time in its zone: UTC for a `Z`
+/// suffix, the TZID if the system knows it, else `local` (floating times
+/// and all-day dates), plus whether it's a whole day
+fn parse_time(property: &Property, local: &TimeZone) -> Option<(Zoned, bool)> {
+    let value = property.value.trim();
+    let (civil, all_day) = match DateTime::strptime("%Y%m%dT%H%M%S", value.trim_end_matches('Z')) {
+        Ok(civil) => (civil, false),
+        Err(_) => (Date::strptime("%Y%m%d", value).ok()?.to_datetime(jiff::civil::Time::midnight()), true),
+    };
+    let zone = if !all_day && value.ends_with('Z') {
+        TimeZone::UTC
+    } else {
+        property
+            .param("TZID")
+            .filter(|_| !all_day)
+            .and_then(|tzid| TimeZone::get(tzid.trim_start_matches('/')).ok())
+            .unwrap_or_else(|| local.clone())
+    };
+    Some((civil.to_zoned(zone).ok()?, all_day))
+}
+
+/// `P1W`, `PT1H30M`, `P1DT12H` (sign ignored)
+fn parse_duration(value: &str) -> Option<Span> {
+    let value = value.trim().trim_start_matches(['+', '-']).strip_prefix('P')?;
+    let (date, time) = value.split_once('T').unwrap_or((value, ""));
+    let mut span = Span::new();
+    let mut number = String::new();
+    for (c, in_time) in date.chars().map(|c| (c, false)).chain(time.chars().map(|c| (c, true))) {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let n: i64 = number.parse().ok()?;
+        number.clear();
+        span = match (c, in_time) {
+            ('W', false) => span.weeks(n),
+            ('D', false) => span.days(n),
+            ('H', true) => span.hours(n),
+            ('M', true) => span.minutes(n),
+            ('S', true) => span.seconds(n),
+            _ => return None,
+        };
+    }
+    Some(span)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// The parts of an RRULE that are followed
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    frequency: Frequency,
+    interval: i64,
+    count: Option<usize>,
+    until: Option<Timestamp>,
+    /// Weekly rules only
+    weekdays: Vec<Weekday>,
+}
+
+fn parse_rule(value: &str, local: &TimeZone) -> Option<Rule> {
+    let parts: HashMap<String, &str> = value
+        .split(';')
+        .filter_map(|part| part.split_once('='))
+        .map(|(key, value)| (key.to_uppercase(), value))
+        .collect();
+    let frequency = match parts.get("FREQ")?.to_uppercase().as_str() {
+        "DAILY" => Frequency::Daily,
+        "WEEKLY" => Frequency::Weekly,
+        "MONTHLY" => Frequency::Monthly,
+        "YEARLY" => Frequency::Yearly,
+        _ => return None,
+    };
+    let until = parts.get("UNTIL").and_then(|until| {
+        let property = Property { name: "UNTIL".to_string(), params: Vec::new(), value: until.to_string() };
+        let (moment, all_day) = parse_time(&property, local)?;
+        // A date includes the whole day
+        Some(if all_day { moment.checked_add(1.day()).ok()?.timestamp() } else { moment.timestamp() })
+    });
+    let weekdays = match (frequency, parts.get("BYDAY")) {
+        (Frequency::Weekly, Some(days)) => days
+            .split(',')
+            .filter_map(|day| match day.trim().to_uppercase().as_str() {
+                "MO" => Some(Weekday::Monday),
+                "TU" => Some(Weekday::Tuesday),
+                "WE" => Some(Weekday::Wednesday),
+                "TH" => Some(Weekday::Thursday),
+                "FR" => Some(Weekday::Friday),
+                "SA" => Some(Weekday::Saturday),
+                "SU" => Some(Weekday::Sunday),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    Some(Rule {
+        frequency,
+        interval: parts.get("INTERVAL").and_then(|n| n.parse().ok()).filter(|n| *n > 0).unwrap_or(1),
+        count: parts.get("COUNT").and_then(|n| n.parse().ok()),
+        until,
+        weekdays,
+    })
+}
+
+/// Start times of a rule's occurrences in its zone, from `start` on, until
+/// `stop` returns true for one or the rule runs out
+fn recur(start: &Zoned, rule: &Rule, mut stop: impl FnMut(&Zoned) -> bool) {
+    let zone = start.time_zone().clone();
+    let civil = start.datetime();
+    let mut emitted = 0;
+    for step in 0..MAX_STEPS as i64 {
+        let n = step * rule.interval;
+        let base = match rule.frequency {
+            Frequency::Daily => civil.checked_add(n.days()),
+            Frequency::Weekly => civil.checked_add(n.weeks()),
+            Frequency::Monthly => civil.checked_add(n.months()),
+            Frequency::Yearly => civil.checked_add(n.years()),
+        };
+        let Ok(base) = base else { return };
+        // Weekly BYDAY: every listed day of the week `base` falls in
+        let candidates: Vec<DateTime> = if rule.weekdays.is_empty() {
+            vec![base]
+        } else {
+            let monday = base.checked_sub(i64::from(base.weekday().to_monday_zero_offset()).days());
+            let Ok(monday) = monday else { return };
+            let mut days: Vec<DateTime> = rule
+                .weekdays
+                .iter()
+                .filter_map(|day| monday.checked_add(i64::from(day.to_monday_zero_offset()).days()).ok())
+                .filter(|day| *day >= civil)
+                .collect();
+            days.sort();
+            days
+        };
+        for candidate in candidates {
+            let Ok(occurrence) = candidate.to_zoned(zone.clone()) else { continue };
+            if rule.count.is_some_and(|count| emitted >= count)
+                || rule.until.is_some_and(|until| occurrence.timestamp() > until)
+            {
+                return;
+            }
+            emitted += 1;
+            if stop(&occurrence) {
+                return;
+            }
+        }
+    }
+}
+
+/// An event's occurrences overlapping `from`..`until`, minus those in `skip`
+fn expand(event: &Component, local: &TimeZone, skip: &[Timestamp], from: &Zoned, until: &Zoned) -> Vec<Event> {
+    let Some((start, all_day)) = get(event, "DTSTART").and_then(|property| parse_time(property, local)) else {
+        return Vec::new();
+    };
+    let length = match get(event, "DTEND").and_then(|property| parse_time(property, local)) {
+        Some((end, _)) => start.until(&end).unwrap_or_default(),
+        None => match get(event, "DURATION").and_then(|property| parse_duration(&property.value)) {
+            Some(span) => span,
+            None if all_day => 1.day(),
+            None => Span::new(),
+        },
+    };
+    let exceptions: Vec<Timestamp> = event
+        .iter()
+        .filter(|property| property.name == "EXDATE")
+        .flat_map(|property| {
+            property.value.split(',').filter_map(|value| {
+                let single = Property { value: value.to_string(), ..property.clone() };
+                parse_time(&single, local).map(|(moment, _)| moment.timestamp())
+            })
+        })
+        .chain(skip.iter().copied())
+        .collect();
+    let summary = text(event, "SUMMARY").unwrap_or_else(|| "(No title)".to_string());
+    let location = text(event, "LOCATION");
+    let description = text(event, "DESCRIPTION");
+    let meeting_link = meeting_link(event);
+
+    let mut events = Vec::new();
+    let mut add = |occurrence: &Zoned| {
+        if occurrence >= until {
+            return true;
+        }
+        let Ok(end) = occurrence.checked_add(length) else { return false };
+        let ongoing = if end > *occurrence { end > *from } else { occurrence >= from };
+        if ongoing && !exceptions.contains(&occurrence.timestamp()) {
+            events.push(Event {
+                summary: summary.clone(),
+                start: occurrence.with_time_zone(from.time_zone().clone()),
+                end: end.with_time_zone(from.time_zone().clone()),
+                all_day,
+                location: location.clone(),
+                description: description.clone(),
+                meeting_link: meeting_link.clone(),
+            });
+        }
+        false
+    };
+    match get(event, "RRULE").and_then(|rule| parse_rule(&rule.value, local)) {
+        Some(rule) => recur(&start, &rule, add),
+        None => {
+            add(&start);
+        }
+    }
+    events
+}
+
+/// A link to a known video call service in the conference, URL, location or
+/// description, else the event's URL
+fn meeting_link(event: &Component) -> Option<String> {
+    let url = text(event, "URL");
+    ["X-GOOGLE-CONFERENCE", "URL", "LOCATION", "DESCRIPTION"]
+        .iter()
+        .filter_map(|name| text(event, name))
+        .find_map(|value| links(&value).into_iter().find(|link| is_meeting(link)))
+        .or(url.filter(|url| url.starts_with("https://")))
+}
+
+fn links(text: &str) -> Vec<String> {
+    text.split(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\'' | '(' | ')' | ','))
+        .filter(|token| token.starts_with("https://"))
+        .map(|token| token.trim_end_matches(['.', ';']).to_string())
+        .collect()
+}
+
+fn is_meeting(link: &str) -> bool {
+    let host = link.trim_start_matches("https://").split(['/', '?', '#']).next().unwrap_or_default();
+    MEETING_HOSTS
+        .iter()
+        .any(|known| host == *known || host.strip_suffix(known).is_some_and(|sub| sub.ends_with('.')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ICS: &str = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\n\
+UID:standup\r\n\
+SUMMARY:Standup\r\n\
+DTSTART;TZID=Europe/Berlin:20261012T093000\r\n\
+DTEND;TZID=Europe/Berlin:20261012T094500\r\n\
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR\r\n\
+EXDATE;TZID=Europe/Berlin:20261016T093000\r\n\
+DESCRIPTION:Join: https://meet.google.com/abc-defg-hij\\nor dial in\r\n\
+BEGIN:VALARM\r\n\
+DESCRIPTION:Reminder\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:standup\r\n\
+RECURRENCE-ID;TZID=Europe/Berlin:20261019T093000\r\n\
+SUMMARY:Standup (moved)\r\n\
+DTSTART;TZID=Europe/Berlin:20261019T110000\r\n\
+DTEND;TZID=Europe/Berlin:20261019T111500\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:offsite\r\n\
+SUMMARY:Team offsite\\, day one\r\n\
+DTSTART;VALUE=DATE:20261017\r\n\
+LOCATION:Lisbon\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:review\r\n\
+SUMMARY:Design review with a very long title that goes on and\r\n\
+\x20 on\r\n\
+DTSTART:20261016T130000Z\r\n\
+DURATION:PT1H\r\n\
+URL:https://example.com/agenda\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:gone\r\n\
+SUMMARY:Cancelled sync\r\n\
+STATUS:CANCELLED\r\n\
+DTSTART:20261016T150000Z\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+    fn at(text: &str) -> Zoned {
+        DateTime::strptime("%Y-%m-%d %H:%M", text).unwrap().to_zoned(TimeZone::UTC).unwrap()
+    }
+
+    #[test]
+    fn test_occurrences() {
+        let now = at("2026-10-14 07:00");
+        let until = at("2026-10-20 00:00");
+        let events = occurrences(ICS, &now, &until);
+        let mut summary: Vec<(String, String)> =
+            events.iter().map(|e| (e.start.strftime("%a %H:%M").to_string(), e.summary.clone())).collect();
+        summary.sort();
+        assert_eq!(
+            summary,
+            vec![
+                ("Fri 13:00".to_string(), "Design review with a very long title that goes on and on".to_string()),
+                ("Mon 09:00".to_string(), "Standup (moved)".to_string()),
+                ("Sat 00:00".to_string(), "Team offsite, day one".to_string()),
+                // 09:30 in Berlin (CEST) is 07:30 UTC; Friday's is an exception
+                ("Wed 07:30".to_string(), "Standup".to_string()),
+            ]
+        );
+        let standup = events.iter().find(|e| e.summary == "Standup").unwrap();
+        assert_eq!(standup.meeting_link.as_deref(), Some("https://meet.google.com/abc-defg-hij"));
+        assert_eq!(standup.description.as_deref(), Some("Join: https://meet.google.com/abc-defg-hij\nor dial in"));
+        let review = events.iter().find(|e| e.summary.starts_with("Design")).unwrap();
+        assert_eq!(review.meeting_link.as_deref(), Some("https://example.com/agenda"));
+        assert_eq!(review.end, at("2026-10-16 14:00"));
+    }
+
+    #[test]
+    fn test_when() {
+        let now = at("2026-10-16 12:00");
+        let event = |start: &str, end: &str, all_day| Event {
+            summary: String::new(),
+            start: at(start),
+            end: at(end),
+            all_day,
+            location: None,
+            description: None,
+            meeting_link: None,
+        };
+        assert_eq!(event("2026-10-16 13:00", "2026-10-16 14:00", false).when_at(&now), "Today 13:00–14:00");
+        assert_eq!(event("2026-10-16 11:30", "2026-10-16 12:30", false).when_at(&now), "Now until 12:30");
+        assert_eq!(event("2026-10-17 00:00", "2026-10-18 00:00", true).when_at(&now), "Tomorrow · All day");
+        assert_eq!(event("2026-10-20 09:00", "2026-10-20 09:00", false).when_at(&now), "Tue 20 Oct 09:00");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("PT1H30M").unwrap().fieldwise(), 1.hour().minutes(30).fieldwise());
+        assert_eq!(parse_duration("P1W").unwrap().fieldwise(), 1.week().fieldwise());
+        assert!(parse_duration("1H").is_none());
+    }
+
+    #[test]
+    fn test_is_meeting() {
+        assert!(is_meeting("https://us02web.zoom.us/j/123"));
+        assert!(is_meeting("https://teams.microsoft.com/l/meetup-join/x"));
+        assert!(!is_meeting("https://notzoom.us/j/123"));
+    }
+}
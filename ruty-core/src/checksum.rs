@@ -0,0 +1,149 @@
+//! Checksums of files and text for `/hash`
+//!
+//! Files are streamed in fixed-size reads so multi-gigabyte downloads can be
+//! checked without loading them, reporting bytes read to a `Progress`.
+
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::progress::Progress;
+
+const READ_SIZE: usize = 256 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Blake3,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 4] = [Self::Md5, Self::Sha1, Self::Sha256, Self::Blake3];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace('-', "").as_str() {
+            "md5" => Some(Self::Md5),
+            "sha1" => Some(Self::Sha1),
+            "sha256" => Some(Self::Sha256),
+            "blake3" | "b3" => Some(Self::Blake3),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Md5 => "MD5",
+            Self::Sha1 => "SHA-1",
+            Self::Sha256 => "SHA-256",
+            Self::Blake3 => "BLAKE3",
+        }
+    }
+}
+
+/// One of the supported hash states
+enum State {
+    Md5(Md5),
+    Sha1(Sha1),
+    Sha256(Sha256),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl State {
+    fn new(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Md5 => Self::Md5(Md5::new()),
+            Algorithm::Sha1 => Self::Sha1(Sha1::new()),
+            Algorithm::Sha256 => Self::Sha256(Sha256::new()),
+            Algorithm::Blake3 => Self::Blake3(Box::<blake3::Hasher>::default()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Md5(h) => h.update(data),
+            Self::Sha1(h) => h.update(data),
+            Self::Sha256(h) => h.update(data),
+            Self::Blake3(h) => {
+                h.update(data);
+            }
+        }
+    }
+
+    fn hex(self) -> String {
+        let bytes = match self {
+            Self::Md5(h) => h.finalize().to_vec(),
+            Self::Sha1(h) => h.finalize().to_vec(),
+            Self::Sha256(h) => h.finalize().to_vec(),
+            Self::Blake3(h) => h.finalize().as_bytes().to_vec(),
+        };
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+/// Lowercase hex digest of `text` (UTF-8 bytes, no trailing newline)
+pub fn hash_text(algorithm: Algorithm, text: &str) -> String {
+    let mut state = State::new(algorithm);
+    state.update(text.as_bytes());
+    state.hex()
+}
+
+/// Lowercase hex digest of a file (blocking), advancing `progress` by bytes read
+pub fn hash_file(algorithm: Algorithm, path: &Path, progress: &Progress) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+    let mut state = State::new(algorithm);
+    let mut buf = vec![0u8; READ_SIZE];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        if n == 0 {
+            break;
+        }
+        state.update(&buf[..n]);
+        progress.add(n as u64);
+    }
+    progress.finish();
+    Ok(state.hex())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Algorithm::parse("SHA-256"), Some(Algorithm::Sha256));
+        assert_eq!(Algorithm::parse("b3"), Some(Algorithm::Blake3));
+        assert_eq!(Algorithm::parse("crc32"), None);
+    }
+
+    #[test]
+    fn test_hash_text() {
+        assert_eq!(hash_text(Algorithm::Md5, "abc"), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(hash_text(Algorithm::Sha1, "abc"), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(
+            hash_text(Algorithm::Sha256, "abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_hash_file_matches_text() {
+        let path = std::env::temp_dir().join(format!("ruty-hash-{}.bin", std::process::id()));
+        let content = "x".repeat(READ_SIZE + 100);
+        std::fs::write(&path, &content).unwrap();
+        let progress = Progress::new(content.len() as u64);
+        for algorithm in Algorithm::ALL {
+            progress.done.store(0, std::sync::atomic::Ordering::Relaxed);
+            assert_eq!(hash_file(algorithm, &path, &progress).unwrap(), hash_text(algorithm, &content));
+        }
+        std::fs::remove_file(&path).ok();
+        assert_eq!(progress.fraction(), Some(1.0));
+        assert!(hash_file(Algorithm::Md5, Path::new("/nonexistent/ruty"), &progress).is_err());
+    }
+}
@@ -0,0 +1,577 @@
+//! Clipboard Manager Module
+//!
+//! polls system clipboard and maintains a history of copied text.
+//! Supports Wayland (wl-clipboard) and X11 (xclip).
+//!
+//! Copies a password manager flags as secret are never recorded, nor are
+//! copies from ignored apps. Polling can't see which window a copy came
+//! from, so the window focused when the copy is noticed stands in for it.
+//! Each entry remembers that app, so what's written to disk can leave out
+//! password managers and apps ignored since.
+//!
+//! Polling can be paused while something else owns the clipboard and needs
+//! to see every read of it, such as an entry offered for a single paste.
+
+use serde::{Deserialize, Serialize};
+
+use crate::content::{self, ContentKind};
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Entries kept unless `set_limit` says otherwise
+const DEFAULT_LIMIT: usize = 50;
+/// Total size of history text kept unless `set_budget` says otherwise
+const DEFAULT_BUDGET: usize = 8 * 1024 * 1024;
+/// Time between clipboard checks unless `set_poll_interval` says otherwise
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Checking more often than this just burns CPU on spawning wl-paste
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// MIME type password managers (KeePassXC, KWallet) offer with a secret
+const PASSWORD_HINT: &str = "x-kde-passwordManagerHint";
+/// Window classes of password managers, whose copies are kept in memory
+/// but never written to disk (see `try_history_to_save`)
+const SENSITIVE_APPS: &[&str] = &[
+    "org.keepassxc.keepassxc",
+    "keepassxc",
+    "bitwarden",
+    "1password",
+    "org.gnome.seahorse.application",
+    "kwalletmanager5",
+];
+
+/// Id of the next entry
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Finds the window class of the app a copy came from
+pub type SourceProbe = fn() -> Option<String>;
+
+/// Told about each copy recorded by the polling thread
+pub type CaptureListener = fn(&ClipboardItem);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClipboardItem {
+    /// Unique for this run and larger for later entries; rows and marks
+    /// name entries by it, since copies within a second share a timestamp
+    #[serde(skip)]
+    pub id: u64,
+    pub content: String,
+    pub timestamp: u64,
+    /// What the content looks like, tagged when it's copied
+    #[serde(default)]
+    pub kind: ContentKind,
+    /// Guessed language of a code snippet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Lowercased window class of the app focused when it was copied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl ClipboardItem {
+    /// An entry for `content`, tagged with its content type
+    pub fn new(content: String, timestamp: u64) -> Self {
+        let (kind, language) = content::classify(&content);
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        Self { id, content, timestamp, kind, language: language.map(str::to_string), source: None }
+    }
+
+    /// Badge for the entry: its type, with the language for code
+    pub fn badge(&self) -> String {
+        match &self.language {
+            Some(language) => format!("{} code", language),
+            None => self.kind.label().to_string(),
+        }
+    }
+}
+
+pub struct ClipboardManager {
+    history: Arc<Mutex<VecDeque<ClipboardItem>>>,
+    last_content: Arc<Mutex<String>>,
+    running: Arc<Mutex<bool>>,
+    /// Bytes of history text kept; older entries go first
+    budget: Arc<AtomicUsize>,
+    /// Entries kept; older entries go first
+    limit: Arc<AtomicUsize>,
+    /// Milliseconds between clipboard checks
+    poll_ms: Arc<AtomicU64>,
+    ignore: Arc<Mutex<Ignore>>,
+    /// Clipboard checks are skipped while set
+    paused: Arc<AtomicBool>,
+    listener: Arc<Mutex<Option<CaptureListener>>>,
+}
+
+/// Apps whose copies aren't recorded
+#[derive(Debug, Default)]
+struct Ignore {
+    /// Lowercased window classes / app_ids
+    classes: Vec<String>,
+    probe: Option<SourceProbe>,
+}
+
+impl Ignore {
+    fn matches(&self, class: &str) -> bool {
+        self.classes.iter().any(|ignored| *ignored == class.to_lowercase())
+    }
+
+    /// The app the copy on the clipboard now came from, if known, or why it
+    /// shouldn't be recorded
+    fn check(&self) -> Result<Option<String>, String> {
+        if is_secret() {
+            return Err("marked secret".to_string());
+        }
+        match self.probe.and_then(|probe| probe()).map(|class| class.to_lowercase()) {
+            Some(class) if self.matches(&class) => Err(format!("copied from {}", class)),
+            source => Ok(source),
+        }
+    }
+
+    /// Whether `item` came from an ignored app or a password manager
+    fn keeps_off_disk(&self, item: &ClipboardItem) -> bool {
+        item.source.as_deref().is_some_and(|class| self.matches(class) || SENSITIVE_APPS.contains(&class))
+    }
+}
+
+impl ClipboardManager {
+    pub fn new() -> Self {
+        Self {
+            history: Arc::new(Mutex::new(VecDeque::new())),
+            last_content: Arc::new(Mutex::new(String::new())),
+            running: Arc::new(Mutex::new(false)),
+            budget: Arc::new(AtomicUsize::new(DEFAULT_BUDGET)),
+            limit: Arc::new(AtomicUsize::new(DEFAULT_LIMIT)),
+            poll_ms: Arc::new(AtomicU64::new(DEFAULT_POLL_INTERVAL.as_millis() as u64)),
+            ignore: Arc::new(Mutex::new(Ignore::default())),
+            paused: Arc::new(AtomicBool::new(false)),
+            listener: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Keep at most `entries` (at least one), dropping the oldest now and as
+    /// new ones arrive
+    pub fn set_limit(&self, entries: usize) {
+        let entries = entries.max(1);
+        self.limit.store(entries, Ordering::SeqCst);
+        trim(&mut self.history.lock().unwrap(), entries, self.budget.load(Ordering::SeqCst));
+    }
+
+    /// Check the clipboard this often (no more than every 100ms)
+    pub fn set_poll_interval(&self, interval: Duration) {
+        let interval = interval.max(MIN_POLL_INTERVAL);
+        self.poll_ms.store(interval.as_millis() as u64, Ordering::SeqCst);
+    }
+
+    /// Don't record copies made while a window of one of `classes` is
+    /// focused; `probe` tells which window that is
+    pub fn set_ignored(&self, classes: &[String], probe: SourceProbe) {
+        let mut ignore = self.ignore.lock().unwrap();
+        ignore.classes = classes.iter().map(|class| class.to_lowercase()).collect();
+        ignore.probe = Some(probe);
+    }
+
+    /// Stop (or resume) checking the clipboard; reading it counts as a paste
+    /// for entries offered with `copy_once`
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+    }
+
+    /// Call `listener` with each copy the polling thread records (not
+    /// entries added with `add`)
+    pub fn on_capture(&self, listener: CaptureListener) {
+        *self.listener.lock().unwrap() = Some(listener);
+    }
+
+    /// Limit history to `bytes` of text, dropping the oldest entries now and
+    /// as new ones arrive
+    pub fn set_budget(&self, bytes: usize) {
+        self.budget.store(bytes, Ordering::SeqCst);
+        trim(&mut self.history.lock().unwrap(), self.limit.load(Ordering::SeqCst), bytes);
+    }
+
+    /// Bytes of text currently held in history
+    pub fn bytes(&self) -> usize {
+        history_bytes(&self.history.lock().unwrap())
+    }
+
+    /// Start the background polling thread
+    pub fn start(&self) {
+        let mut running = self.running.lock().unwrap();
+        if *running {
+            return;
+        }
+        *running = true;
+
+        let history = self.history.clone();
+        let last_content = self.last_content.clone();
+        let running_clone = self.running.clone();
+        let budget = self.budget.clone();
+        let limit = self.limit.clone();
+        let poll_ms = self.poll_ms.clone();
+        let ignore = self.ignore.clone();
+        let paused = self.paused.clone();
+        let listener = self.listener.clone();
+
+        thread::spawn(move || {
+            loop {
+                if !*running_clone.lock().unwrap() {
+                    break;
+                }
+
+                let content = if paused.load(Ordering::SeqCst) { None } else { Self::get_system_clipboard() };
+                if let Some(content) = content {
+                    let mut last = last_content.lock().unwrap();
+                    if *last != content && !content.trim().is_empty() {
+                        *last = content.clone();
+                        let checked = ignore.lock().unwrap().check();
+                        match checked {
+                            Err(reason) => tracing::debug!("Not recording clipboard copy: {}", reason),
+                            Ok(source) => {
+                                let (limit, budget) = (limit.load(Ordering::SeqCst), budget.load(Ordering::SeqCst));
+                                let item = record(&history, content, source, limit, budget);
+                                if let Some(listener) = *listener.lock().unwrap() {
+                                    listener(&item);
+                                }
+                            }
+                        }
+                    }
+                }
+
+                thread::sleep(Duration::from_millis(poll_ms.load(Ordering::SeqCst)));
+            }
+        });
+    }
+
+    /// Stop the polling thread
+    pub fn stop(&self) {
+        let mut running = self.running.lock().unwrap();
+        *running = false;
+    }
+
+    /// Get current history
+    pub fn get_history(&self) -> Vec<ClipboardItem> {
+        let hist = self.history.lock().unwrap();
+        hist.iter().cloned().collect()
+    }
+
+    /// Forget all history (the current clipboard isn't re-added)
+    pub fn clear(&self) {
+        self.history.lock().unwrap().clear();
+    }
+
+    /// Forget one entry, returning it
+    pub fn remove(&self, id: u64) -> Option<ClipboardItem> {
+        let mut hist = self.history.lock().unwrap();
+        let pos = hist.iter().position(|x| x.id == id)?;
+        hist.remove(pos)
+    }
+
+    /// Put removed entries back where they were (by copy time); entries
+    /// copied again meanwhile stay where they are now
+    pub fn reinsert(&self, items: Vec<ClipboardItem>) {
+        let mut hist = self.history.lock().unwrap();
+        for item in items {
+            if !hist.iter().any(|x| x.content == item.content) {
+                hist.push_back(item);
+            }
+        }
+        hist.make_contiguous().sort_by_key(|item| Reverse((item.timestamp, item.id)));
+        trim(&mut hist, self.limit.load(Ordering::SeqCst), self.budget.load(Ordering::SeqCst));
+    }
+
+    /// Get current history without blocking (for panic hooks)
+    pub fn try_history(&self) -> Option<Vec<ClipboardItem>> {
+        let hist = self.history.try_lock().ok()?;
+        Some(hist.iter().cloned().collect())
+    }
+
+    /// History to write to disk, without blocking (for panic hooks): copies
+    /// from ignored apps and password managers are left out
+    pub fn try_history_to_save(&self) -> Option<Vec<ClipboardItem>> {
+        let ignore = self.ignore.try_lock().ok()?;
+        let hist = self.history.try_lock().ok()?;
+        Some(hist.iter().filter(|item| !ignore.keeps_off_disk(item)).cloned().collect())
+    }
+
+    /// Add text that didn't come from a copy (e.g. recognized in an image)
+    /// as the newest entry
+    pub fn add(&self, content: String) {
+        *self.last_content.lock().unwrap() = content.clone();
+        record(&self.history, content, None, self.limit.load(Ordering::SeqCst), self.budget.load(Ordering::SeqCst));
+    }
+
+    /// Seed history from a previous session (newest first)
+    pub fn restore(&self, items: Vec<ClipboardItem>) {
+        let mut hist = self.history.lock().unwrap();
+        let limit = self.limit.load(Ordering::SeqCst);
+        let mut kept: Vec<ClipboardItem> = Vec::new();
+        for item in items {
+            if hist.len() + kept.len() >= limit {
+                break;
+            }
+            if !hist.iter().chain(&kept).any(|x| x.content == item.content) {
+                kept.push(item);
+            }
+        }
+        // Oldest first, so later entries get larger ids; sessions from
+        // before tagging have every entry as Text
+        let mut restored: Vec<ClipboardItem> = kept
+            .into_iter()
+            .rev()
+            .map(|item| ClipboardItem { source: item.source, ..ClipboardItem::new(item.content, item.timestamp) })
+            .collect();
+        restored.reverse();
+        hist.extend(restored);
+        trim(&mut hist, limit, self.budget.load(Ordering::SeqCst));
+        if let Some(latest) = hist.front() {
+            *self.last_content.lock().unwrap() = latest.content.clone();
+        }
+    }
+
+    /// Read system clipboard
+    fn get_system_clipboard() -> Option<String> {
+        paste("clipboard")
+    }
+}
+
+/// Text of the primary selection (whatever is highlighted), for `{selection}`
+/// in prompt templates
+pub fn selection() -> Option<String> {
+    paste("primary")
+}
+
+/// The image on the clipboard as PNG, if a PNG was copied (screenshots are)
+pub fn image() -> Option<Vec<u8>> {
+    let x11: &[&str] = &["-selection", "clipboard", "-t", "image/png", "-o"];
+    for (tool, args) in [("wl-paste", &["--type", "image/png"][..]), ("xclip", x11)] {
+        if let Ok(output) = Command::new(tool).args(args).output() {
+            if output.status.success() {
+                return output.stdout.starts_with(b"\x89PNG").then_some(output.stdout);
+            }
+        }
+    }
+    None
+}
+
+/// Read the "clipboard" or "primary" selection with wl-paste (Wayland) or
+/// xclip (X11)
+fn paste(selection: &str) -> Option<String> {
+    let wayland: &[&str] = if selection == "primary" { &["--no-newline", "--primary"] } else { &["--no-newline"] };
+    for (tool, args) in [("wl-paste", wayland), ("xclip", &["-selection", selection, "-o"][..])] {
+        if let Ok(output) = Command::new(tool).args(args).output() {
+            // Ensure valid UTF-8
+            if output.status.success() {
+                if let Ok(text) = String::from_utf8(output.stdout) {
+                    return Some(text);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Whether the clipboard owner flagged the copy as a secret, as KeePassXC
+/// does with `PASSWORD_HINT`
+fn is_secret() -> bool {
+    offers(PASSWORD_HINT)
+}
+
+/// Whether an image was copied, without reading it
+pub fn has_image() -> bool {
+    offers("image/png")
+}
+
+/// Whether the clipboard owner offers the copy as MIME type `mime`
+fn offers(mime: &str) -> bool {
+    let x11: &[&str] = &["-selection", "clipboard", "-t", "TARGETS", "-o"];
+    for (tool, args) in [("wl-paste", &["--list-types"][..]), ("xclip", x11)] {
+        if let Ok(output) = Command::new(tool).args(args).output() {
+            if output.status.success() {
+                return String::from_utf8_lossy(&output.stdout).lines().any(|t| t.trim() == mime);
+            }
+        }
+    }
+    false
+}
+
+/// Put a new copy at the front of history, moving it up if it's there
+fn record(
+    history: &Mutex<VecDeque<ClipboardItem>>,
+    content: String,
+    source: Option<String>,
+    limit: usize,
+    budget: usize,
+) -> ClipboardItem {
+    let mut hist = history.lock().unwrap();
+
+    // Remove if exists (to move to top)
+    if let Some(pos) = hist.iter().position(|x| x.content == content) {
+        hist.remove(pos);
+    }
+
+    // Add to front
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let item = ClipboardItem { source, ..ClipboardItem::new(content, timestamp) };
+    hist.push_front(item.clone());
+
+    trim(&mut hist, limit, budget);
+    item
+}
+
+fn history_bytes(hist: &VecDeque<ClipboardItem>) -> usize {
+    hist.iter().map(|item| item.content.len()).sum()
+}
+
+/// Drop the oldest entries beyond `limit` entries or `budget` bytes; the
+/// newest entry always stays
+fn trim(hist: &mut VecDeque<ClipboardItem>, limit: usize, budget: usize) {
+    let mut bytes = history_bytes(hist);
+    while hist.len() > 1 && (hist.len() > limit || bytes > budget) {
+        if let Some(oldest) = hist.pop_back() {
+            bytes -= oldest.content.len();
+        }
+    }
+}
+
+/// Put text on the system clipboard, returning the tool that was used
+pub fn copy(content: &str) -> Result<&'static str, String> {
+    for (tool, args) in [("wl-copy", &[][..]), ("xclip", &["-selection", "clipboard", "-i"][..])] {
+        if let Ok(mut child) = Command::new(tool).args(args).stdin(Stdio::piped()).spawn() {
+            if let Some(mut stdin) = child.stdin.take() {
+                let _ = stdin.write_all(content.as_bytes());
+            }
+            let _ = child.wait();
+            return Ok(tool);
+        }
+    }
+    Err("Failed to copy: no clipboard tool found".to_string())
+}
+
+/// Put a PNG on the system clipboard
+pub fn copy_image(png: &[u8]) -> Result<(), String> {
+    let x11: &[&str] = &["-selection", "clipboard", "-t", "image/png", "-i"];
+    for (tool, args) in [("wl-copy", &["--type", "image/png"][..]), ("xclip", x11)] {
+        if let Ok(mut child) = Command::new(tool).args(args).stdin(Stdio::piped()).spawn() {
+            if let Some(mut stdin) = child.stdin.take() {
+                let _ = stdin.write_all(png);
+            }
+            let _ = child.wait();
+            return Ok(());
+        }
+    }
+    Err("Failed to copy: no clipboard tool found".to_string())
+}
+
+/// Offer text on the clipboard for a single paste: the returned `wl-copy`
+/// process exits once an app has pasted it, leaving the clipboard empty.
+/// Wayland only, since X11 tools can't tell a paste from a type query.
+pub fn copy_once(content: &str) -> Result<Child, String> {
+    let mut child = Command::new("wl-copy")
+        .args(["--foreground", "--paste-once"])
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Pasting one at a time needs wl-copy (Wayland): {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(content.as_bytes()).map_err(|e| format!("Failed to copy: {}", e))?;
+    }
+    Ok(child)
+}
+
+impl Default for ClipboardManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(content: &str, timestamp: u64) -> ClipboardItem {
+        ClipboardItem::new(content.to_string(), timestamp)
+    }
+
+    #[test]
+    fn test_budget_drops_oldest() {
+        let manager = ClipboardManager::new();
+        manager.restore(vec![item("newest", 3), item("middle", 2), item("oldest", 1)]);
+        assert_eq!(manager.bytes(), 18);
+
+        manager.set_budget(12);
+        let history: Vec<_> = manager.get_history().into_iter().map(|i| i.content).collect();
+        assert_eq!(history, vec!["newest", "middle"]);
+
+        // The latest copy is kept even when it alone is over budget
+        manager.set_budget(1);
+        assert_eq!(manager.get_history().len(), 1);
+    }
+
+    #[test]
+    fn test_tagged_at_capture() {
+        let manager = ClipboardManager::new();
+        let old: ClipboardItem = serde_json::from_str(r#"{"content": "https://example.com", "timestamp": 1}"#).unwrap();
+        assert_eq!(old.kind, ContentKind::Text);
+        manager.restore(vec![old]);
+        assert_eq!(manager.get_history()[0].kind, ContentKind::Url);
+        assert_eq!(item("fn main() {\n    println!(\"hi\");\n}", 2).badge(), "Rust code");
+    }
+
+    #[test]
+    fn test_limit_and_ignore() {
+        let manager = ClipboardManager::new();
+        manager.restore(vec![item("c", 3), item("b", 2), item("a", 1)]);
+        manager.set_limit(2);
+        let history: Vec<_> = manager.get_history().into_iter().map(|i| i.content).collect();
+        assert_eq!(history, vec!["c", "b"]);
+
+        manager.set_ignored(&["org.keepassxc.KeePassXC".to_string()], || None);
+        let ignore = manager.ignore.lock().unwrap();
+        assert!(ignore.matches("org.keepassxc.keepassxc"));
+        assert!(!ignore.matches("firefox"));
+    }
+
+    #[test]
+    fn test_history_to_save() {
+        let manager = ClipboardManager::new();
+        manager.set_ignored(&["Slack".to_string()], || None);
+        let from = |content: &str, source: &str| ClipboardItem { source: Some(source.to_string()), ..item(content, 1) };
+        manager.restore(vec![item("notes", 4), from("hunter2", "bitwarden"), from("dm", "slack"), from("link", "firefox")]);
+        let saved: Vec<_> = manager.try_history_to_save().unwrap().into_iter().map(|i| i.content).collect();
+        assert_eq!(saved, vec!["notes", "link"]);
+        assert_eq!(manager.get_history().len(), 4);
+    }
+
+    #[test]
+    fn test_remove_and_reinsert() {
+        let manager = ClipboardManager::new();
+        manager.restore(vec![item("c", 3), item("b", 2), item("a", 1)]);
+        let ids: Vec<_> = manager.get_history().into_iter().map(|i| i.id).collect();
+        assert!(ids[0] > ids[1] && ids[1] > ids[2]);
+        let removed = manager.remove(ids[1]).unwrap();
+        assert_eq!(removed.content, "b");
+        assert!(manager.remove(ids[1]).is_none());
+
+        manager.reinsert(vec![removed]);
+        let history: Vec<_> = manager.get_history().into_iter().map(|i| i.content).collect();
+        assert_eq!(history, vec!["c", "b", "a"]);
+    }
+
+    #[test]
+    fn test_copies_within_a_second_stay_apart() {
+        let manager = ClipboardManager::new();
+        manager.restore(vec![item("second", 5), item("first", 5)]);
+        manager.add("third".to_string());
+        let history = manager.get_history();
+        let removed = manager.remove(history[1].id).unwrap();
+        assert_eq!(removed.content, "second");
+        assert_eq!(manager.get_history().len(), 2);
+
+        manager.reinsert(vec![removed]);
+        let history: Vec<_> = manager.get_history().into_iter().map(|i| i.content).collect();
+        assert_eq!(history, vec!["third", "second", "first"]);
+    }
+}
@@ -0,0 +1,1093 @@
+//! User configuration
+//!
+//! Loaded from `~/.config/ruty/config.toml`. Every field has a default so a
+//! missing or partial file still yields a usable config.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table, Value};
+
+use crate::apps::{AppSource, LaunchOverride};
+use crate::flows::Flow;
+use crate::hooks::HookEvent;
+use crate::paths;
+use crate::permissions::Capability;
+use crate::sandbox::Sandbox;
+use crate::validation;
+
+/// What `Config::write` last wrote, to tell our own writes from edits
+static LAST_SAVED: Mutex<Option<String>> = Mutex::new(None);
+
+/// Top-level configuration shared by all frontends
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub files: FilesConfig,
+    pub projects: ProjectsConfig,
+    pub folders: FoldersConfig,
+    pub hotkeys: HotkeysConfig,
+    pub ui: UiConfig,
+    pub search: SearchConfig,
+    pub fonts: FontsConfig,
+    pub apps: AppsConfig,
+    pub clipboard: ClipboardConfig,
+    pub cache: CacheConfig,
+    pub memory: MemoryConfig,
+    pub calendar: CalendarConfig,
+    pub contacts: ContactsConfig,
+    pub api: ApiConfig,
+    pub hooks: HooksConfig,
+    /// How flow shell steps and hooks run (see `sandbox`)
+    pub sandbox: Sandbox,
+    /// Provider ("hooks", "flow:<name>") -> what it was allowed to do (see
+    /// `permissions`)
+    pub permissions: BTreeMap<String, BTreeSet<Capability>>,
+    /// Alias -> prompt text it expands to, e.g. `ff = "/launch firefox"`
+    pub aliases: BTreeMap<String, String>,
+    /// Keyword -> URL template, e.g. `gh = "https://github.com/search?q={query}"`
+    pub quicklinks: BTreeMap<String, String>,
+    /// Abbreviation -> text it expands to as it's typed, e.g. `"@@" = "me@example.com"`
+    pub abbreviations: BTreeMap<String, String>,
+    /// Prompt template name -> prompt for `/tpl`, e.g.
+    /// `translate = "Translate to English:\n{input}"` (see `templates`)
+    pub templates: BTreeMap<String, String>,
+    /// Flow name -> steps that `/flow <name>` runs (see `flows`)
+    pub flows: BTreeMap<String, Flow>,
+    /// Starred apps, files and quicklinks, listed in this order while the
+    /// query is empty
+    pub favorites: Vec<Favorite>,
+    /// Name -> search run by `/saved <name>`, also listed while the query is
+    /// empty
+    pub saved_searches: BTreeMap<String, SavedSearch>,
+    /// Folders added with `/bm add`, listed first for short queries
+    pub bookmarks: Vec<Bookmark>,
+    pub icons: IconsConfig,
+}
+
+/// A starred result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Favorite {
+    pub kind: FavoriteKind,
+    /// App id, file path or quicklink keyword
+    pub target: String,
+    /// Name shown in the list
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FavoriteKind {
+    App,
+    File,
+    Quicklink,
+}
+
+/// A bookmarked folder
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bookmark {
+    /// Folder path, `~` allowed
+    pub path: String,
+    /// Typed after a comma (`,w`) to browse the folder straight away
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shortcut: Option<String>,
+}
+
+impl Bookmark {
+    /// The folder's own name, e.g. "src" for `~/work/src`
+    pub fn name(&self) -> &str {
+        let trimmed = self.path.trim_end_matches('/');
+        trimmed.rsplit('/').next().filter(|name| !name.is_empty()).unwrap_or(&self.path)
+    }
+
+    /// Whether `target` names this bookmark: its path (as typed or expanded)
+    /// or its shortcut, with or without the comma
+    pub fn is(&self, target: &str) -> bool {
+        let shortcut = target.strip_prefix(',').unwrap_or(target);
+        self.path == target
+            || expand_home(&self.path) == expand_home(target)
+            || self.shortcut.as_deref() == Some(shortcut)
+    }
+}
+
+/// A search kept under a name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    /// Text and any filters, e.g. "kind:pdf modified:week invoice"
+    pub query: String,
+    #[serde(default)]
+    pub category: SearchCategory,
+}
+
+/// What a saved search looks through
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchCategory {
+    /// Everything a plain prompt searches
+    #[default]
+    All,
+    Apps,
+    Files,
+    Repos,
+    Clipboard,
+    Contacts,
+}
+
+impl SearchCategory {
+    /// Command that searches the category, `None` for `All`
+    pub fn command(self) -> Option<&'static str> {
+        match self {
+            Self::All => None,
+            Self::Apps => Some("/app"),
+            Self::Files => Some("/file"),
+            Self::Repos => Some("/git"),
+            Self::Clipboard => Some("/clip"),
+            Self::Contacts => Some("/contacts"),
+        }
+    }
+}
+
+impl SavedSearch {
+    /// The search a prompt makes, e.g. `/file kind:pdf invoice` searches
+    /// files for "kind:pdf invoice"; `None` for other commands
+    pub fn from_prompt(prompt: &str) -> Option<Self> {
+        let prompt = prompt.trim();
+        let (command, rest) = prompt.split_once(' ').unwrap_or((prompt, ""));
+        let category = match command {
+            "/app" | "/a" => SearchCategory::Apps,
+            "/file" | "/files" | "/f" => SearchCategory::Files,
+            "/git" => SearchCategory::Repos,
+            "/clip" | "/clipboard" => SearchCategory::Clipboard,
+            "/contacts" => SearchCategory::Contacts,
+            _ if command.starts_with('/') => return None,
+            _ => return Some(Self { query: prompt.to_string(), category: SearchCategory::All }),
+        };
+        Some(Self { query: rest.trim().to_string(), category })
+    }
+
+    /// The prompt that runs it
+    pub fn prompt(&self) -> String {
+        match self.category.command() {
+            Some(command) => format!("{} {}", command, self.query),
+            None => self.query.clone(),
+        }
+    }
+}
+
+/// Appearance settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    pub theme: Theme,
+    /// Register float/center rules with Hyprland or Sway at startup
+    pub window_rules: bool,
+    /// When losing focus hides the window (Ctrl+. pins it open regardless)
+    pub auto_hide: AutoHide,
+    /// UI language, e.g. "de"; empty follows the locale (`LANG` and co.)
+    pub language: String,
+    /// Zoom for the whole UI, window included, on top of the desktop's own
+    /// scaling (e.g. 1.5 on a HiDPI screen the desktop doesn't scale)
+    pub scale: f64,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            window_rules: true,
+            auto_hide: AutoHide::default(),
+            language: String::new(),
+            scale: 1.0,
+        }
+    }
+}
+
+impl UiConfig {
+    /// `scale`, kept to something usable
+    pub fn scale_factor(&self) -> f64 {
+        if self.scale.is_finite() { self.scale.clamp(0.5, 4.0) } else { 1.0 }
+    }
+}
+
+/// Searching as the prompt is typed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchConfig {
+    /// Search apps, files, repos, devices and contacts as plain text is
+    /// typed. Off, plain text only offers a device or contact it names, and
+    /// the rest waits for a command.
+    pub live: bool,
+    /// Pause in typing before a live search runs
+    pub debounce_ms: u64,
+    /// Run plain-language requests like "set a 5 minute timer" or "open
+    /// spotify" directly instead of asking AI (Tab asks AI anyway)
+    pub intents: bool,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self { live: true, debounce_ms: 150, intents: true }
+    }
+}
+
+/// Fonts for UI text (see `fonts`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FontsConfig {
+    /// Family for UI text; empty uses the desktop's sans-serif
+    pub family: String,
+    /// Family for code, regexes and the like; empty uses the desktop's
+    /// monospace
+    pub monospace: String,
+    /// Families to try, in order, for characters the UI font lacks, ahead
+    /// of the built-in CJK and emoji fallbacks
+    pub fallback: Vec<String>,
+    /// Text size multiplier, e.g. 1.2 for larger text in a window of the
+    /// same size
+    pub size: f32,
+}
+
+impl Default for FontsConfig {
+    fn default() -> Self {
+        Self {
+            family: String::new(),
+            monospace: String::new(),
+            fallback: Vec::new(),
+            size: 1.0,
+        }
+    }
+}
+
+impl FontsConfig {
+    /// `size`, kept to something readable
+    pub fn size_factor(&self) -> f32 {
+        if self.size.is_finite() { self.size.clamp(0.5, 3.0) } else { 1.0 }
+    }
+}
+
+/// When the window hides itself on losing focus
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoHide {
+    Never,
+    #[default]
+    Always,
+    /// Only while searching, so a chat stays up to copy things into it
+    Search,
+}
+
+/// Application index settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppsConfig {
+    /// Which install of an app search shows when it's installed several
+    /// ways; the others are listed under Ctrl+O
+    pub prefer: Vec<AppSource>,
+    /// Extra args, env vars, working dir or display backend by desktop id,
+    /// e.g. `[apps.overrides."firefox.desktop"] backend = "wayland"`
+    pub overrides: BTreeMap<String, LaunchOverride>,
+    /// Desktop ids never shown in search (toggled from `/apps`)
+    pub hidden: Vec<String>,
+}
+
+impl Default for AppsConfig {
+    fn default() -> Self {
+        Self {
+            prefer: vec![AppSource::Native, AppSource::Flatpak, AppSource::Snap],
+            overrides: BTreeMap::new(),
+            hidden: Vec::new(),
+        }
+    }
+}
+
+impl AppsConfig {
+    /// Hide an app from search, or show it again if it's hidden; returns
+    /// whether it's hidden now
+    pub fn toggle_hidden(&mut self, id: &str) -> bool {
+        if let Some(index) = self.hidden.iter().position(|hidden| hidden == id) {
+            self.hidden.remove(index);
+            return false;
+        }
+        self.hidden.push(id.to_string());
+        true
+    }
+}
+
+/// Clipboard history recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    /// Entries kept (the `[cache] clipboard_mb` budget may keep fewer)
+    pub history_limit: usize,
+    /// How often the clipboard is checked for a new copy
+    pub poll_interval_ms: u64,
+    /// Window classes / Wayland app_ids whose copies are never recorded,
+    /// e.g. `["org.keepassxc.KeePassXC"]` (case-insensitive)
+    pub ignore: Vec<String>,
+    /// Put between entries by "Merge & copy"
+    pub merge_separator: String,
+    /// Tesseract language(s) for reading text in copied images, e.g. "eng+deu"
+    pub ocr_language: String,
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            history_limit: 50,
+            poll_interval_ms: 1000,
+            ignore: Vec::new(),
+            merge_separator: "\n".to_string(),
+            ocr_language: "eng".to_string(),
+        }
+    }
+}
+
+/// Memory budgets of the daemon's caches, in MB (see `/cache stats`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// Thumbnails of image/video file results
+    pub thumbnails_mb: usize,
+    /// Resolved application icon paths
+    pub icons_mb: usize,
+    /// Clipboard history text
+    pub clipboard_mb: usize,
+    /// Recent answers to repeated queries (file searches and the like)
+    pub responses_mb: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            thumbnails_mb: 32,
+            icons_mb: 1,
+            clipboard_mb: 8,
+            responses_mb: 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Theme {
+    /// The other theme
+    pub fn toggled(self) -> Self {
+        match self {
+            Theme::Dark => Theme::Light,
+            Theme::Light => Theme::Dark,
+        }
+    }
+}
+
+/// Global shortcut bindings: a combo ("Super+Space"), a chord of combos
+/// ("Super+Space, C") or a modifier double-tap ("Ctrl, Ctrl"); empty disables
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HotkeysConfig {
+    /// Toggle the launcher window
+    pub toggle: String,
+    /// Open the window straight into clipboard history
+    pub clipboard: String,
+    /// Push-to-talk: record while held, then put the transcript in the prompt
+    pub voice: String,
+}
+
+impl Default for HotkeysConfig {
+    fn default() -> Self {
+        Self {
+            toggle: "Super+Space".to_string(),
+            clipboard: "Super+V".to_string(),
+            voice: "Super+Alt+V".to_string(),
+        }
+    }
+}
+
+/// File search settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilesConfig {
+    /// Directories searched for files (`~` is expanded)
+    pub search_dirs: Vec<String>,
+    /// Maximum directory depth below each search dir
+    pub max_depth: usize,
+}
+
+impl Default for FilesConfig {
+    fn default() -> Self {
+        Self {
+            search_dirs: vec![
+                "~".to_string(),
+                "~/Documents".to_string(),
+                "~/Downloads".to_string(),
+                "~/Desktop".to_string(),
+                "~/Projects".to_string(),
+            ],
+            max_depth: 4,
+        }
+    }
+}
+
+impl FilesConfig {
+    /// Search dirs with `~` expanded to the home directory
+    pub fn resolved_dirs(&self) -> Vec<PathBuf> {
+        self.search_dirs.iter().map(|d| expand_home(d)).collect()
+    }
+}
+
+/// Where git repositories are looked for, and how they're opened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectsConfig {
+    /// Directories searched for repositories (`~` is expanded)
+    pub dirs: Vec<String>,
+    /// Maximum directory depth below each dir
+    pub max_depth: usize,
+    /// Editor command used to open a repository
+    pub editor: String,
+}
+
+impl Default for ProjectsConfig {
+    fn default() -> Self {
+        Self {
+            dirs: vec!["~/Projects".to_string(), "~/src".to_string(), "~/code".to_string()],
+            max_depth: 3,
+            editor: "code".to_string(),
+        }
+    }
+}
+
+impl ProjectsConfig {
+    /// Project dirs with `~` expanded to the home directory
+    pub fn resolved_dirs(&self) -> Vec<PathBuf> {
+        self.dirs.iter().map(|d| expand_home(d)).collect()
+    }
+}
+
+/// What "Open terminal here" and "Open in editor" start for a folder (see
+/// `folders`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FoldersConfig {
+    /// Terminal command; empty uses `$TERMINAL` or the first one installed
+    pub terminal: String,
+    /// Editor command; empty uses `[projects] editor`
+    pub editor: String,
+    /// Editor -> how it opens a folder, where `{dir}` is the folder and
+    /// `{terminal}` a terminal started in it, e.g. `nvim = "{terminal} nvim ."`
+    pub editor_commands: BTreeMap<String, String>,
+}
+
+/// Icons used instead of the system theme's or the bundled ones. A value is
+/// an image or SVG file (`~` is expanded) or an emoji.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IconsConfig {
+    /// Result type -> icon, e.g. `file = "📄"` (`app`, `file`, `command`,
+    /// `clipboard`, `bookmark`, ...)
+    pub types: BTreeMap<String, String>,
+    /// App id -> icon, e.g. `"firefox.desktop" = "~/.icons/firefox.svg"`
+    pub apps: BTreeMap<String, String>,
+    /// Quicklink keyword -> icon, e.g. `gh = "🐙"`
+    pub quicklinks: BTreeMap<String, String>,
+}
+
+/// Where "today" and `/agenda` find events (see `calendar`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    /// iCalendar files read besides Evolution's local calendar (`~` is expanded)
+    pub ics: Vec<String>,
+    /// Days `/agenda` looks ahead, today included
+    pub days: u32,
+    /// Opens the calendar app on an event's day (`{date}` is YYYY-MM-DD)
+    pub open_command: String,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            ics: Vec::new(),
+            days: 7,
+            open_command: "gnome-calendar --date {date}".to_string(),
+        }
+    }
+}
+
+impl CalendarConfig {
+    /// Configured files with `~` expanded
+    pub fn resolved_files(&self) -> Vec<PathBuf> {
+        self.ics.iter().map(|file| expand_home(file)).collect()
+    }
+}
+
+/// Where contacts are read from besides Evolution's address book (see `contacts`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContactsConfig {
+    /// vCard files, or folders of them (`~` is expanded)
+    pub vcf: Vec<String>,
+}
+
+impl ContactsConfig {
+    /// Configured files and folders with `~` expanded
+    pub fn resolved_paths(&self) -> Vec<PathBuf> {
+        self.vcf.iter().map(|path| expand_home(path)).collect()
+    }
+}
+
+/// Localhost HTTP API for other frontends (rofi scripts, browser extensions)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    /// Off unless asked for: anything local can search and launch through it
+    pub enabled: bool,
+    /// Port on 127.0.0.1 (the gRPC port plus one by default)
+    pub port: u16,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self { enabled: false, port: 42322 }
+    }
+}
+
+/// Shell commands run on events, fed the event as JSON (see `hooks`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    pub window_shown: Vec<String>,
+    pub app_launched: Vec<String>,
+    pub clipboard_captured: Vec<String>,
+    pub ai_response_done: Vec<String>,
+    /// Sandbox for hooks instead of `[sandbox]`
+    pub sandbox: Option<Sandbox>,
+}
+
+impl HooksConfig {
+    /// Whether any hook is set
+    pub fn any(&self) -> bool {
+        [&self.window_shown, &self.app_launched, &self.clipboard_captured, &self.ai_response_done]
+            .iter()
+            .any(|commands| !commands.is_empty())
+    }
+
+    /// The commands to run for `event`
+    pub fn commands(&self, event: &HookEvent) -> &[String] {
+        match event {
+            HookEvent::WindowShown => &self.window_shown,
+            HookEvent::AppLaunched { .. } => &self.app_launched,
+            HookEvent::ClipboardCaptured { .. } => &self.clipboard_captured,
+            HookEvent::AiResponseDone { .. } => &self.ai_response_done,
+        }
+    }
+}
+
+/// Notes and documents chat prompts draw on (see `memory`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryConfig {
+    /// Folders indexed (`~` is expanded); none turns local memory off
+    pub folders: Vec<String>,
+    /// Maximum directory depth below each folder
+    pub max_depth: usize,
+    /// Passages added to each chat prompt
+    pub passages: usize,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            folders: Vec::new(),
+            max_depth: 4,
+            passages: 3,
+        }
+    }
+}
+
+impl MemoryConfig {
+    /// Folders with `~` expanded to the home directory
+    pub fn resolved_dirs(&self) -> Vec<PathBuf> {
+        self.folders.iter().map(|d| expand_home(d)).collect()
+    }
+}
+
+impl Config {
+    /// Path of the config file
+    pub fn path() -> PathBuf {
+        paths::config_dir().join("config.toml")
+    }
+
+    /// Load config from disk, falling back to defaults on any error
+    pub fn load() -> Self {
+        Self::try_load().unwrap_or_else(|e| {
+            tracing::warn!("{} (using defaults)", e);
+            Self::default()
+        })
+    }
+
+    /// Load config from disk: defaults if there's no file, an error saying
+    /// what's wrong if it can't be read or parsed
+    pub fn try_load() -> Result<Self, String> {
+        Self::read().map(|(_, config)| config)
+    }
+
+    /// config.toml's text ("" if there's none) and the config it holds
+    fn read() -> Result<(String, Self), String> {
+        let path = Self::path();
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok((String::new(), Self::default())),
+            Err(e) => return Err(format!("Cannot read {}: {}", path.display(), e)),
+        };
+        let validated =
+            validation::validate(&content).map_err(|e| format!("Invalid config at {}: {}", path.display(), e))?;
+        for warning in &validated.warnings {
+            tracing::warn!("{}: {}", path.display(), warning);
+        }
+        Ok((content, validated.config))
+    }
+
+    /// Change the config on disk with `change`, rewriting only the settings
+    /// it changes so the rest of config.toml (comments included) stays as
+    /// written. A config.toml that can't be read or parsed is an error, never
+    /// replaced with defaults.
+    pub fn update<T>(change: impl FnOnce(&mut Config) -> T) -> Result<T, String> {
+        let (content, old) = Self::read()?;
+        let mut new = old.clone();
+        let changed = change(&mut new);
+        if new != old {
+            new.write(&patch(&content, &old, &new)?)?;
+        }
+        Ok(changed)
+    }
+
+    /// Whether config.toml is just as this process last saved it, so a
+    /// change to it was our own rather than an edit
+    pub fn is_own_save() -> bool {
+        let saved = LAST_SAVED.lock().unwrap();
+        saved.is_some() && fs::read_to_string(Self::path()).ok() == *saved
+    }
+
+    /// Settings that differ in `new` but only take effect when the daemon
+    /// restarts; everything else applies live
+    pub fn restart_needed(&self, new: &Config) -> Vec<&'static str> {
+        [
+            ("[api]", self.api != new.api),
+            ("[fonts]", self.fonts != new.fonts),
+            ("[ui] scale", self.ui.scale != new.ui.scale),
+            ("[ui] window_rules", self.ui.window_rules != new.ui.window_rules),
+        ]
+        .into_iter()
+        .filter(|(_, changed)| *changed)
+        .map(|(setting, _)| setting)
+        .collect()
+    }
+
+    /// Parse config from TOML text; errors say where the problem is (see
+    /// `validation` for the warnings too)
+    pub fn from_toml(content: &str) -> Result<Self, String> {
+        validation::validate(content).map(|validated| validated.config).map_err(|e| e.to_string())
+    }
+
+    /// The config as TOML, every setting included
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+
+    /// Expand `keyword rest...` into a quicklink URL if the keyword is configured
+    pub fn quicklink_url(&self, input: &str) -> Option<String> {
+        let input = input.trim();
+        let (keyword, query) = input.split_once(' ').unwrap_or((input, ""));
+        let template = self.quicklinks.get(keyword)?;
+        Some(template.replace("{query}", &encode_query(query.trim())))
+    }
+
+    /// Star `favorite` (appended last), or unstar it if it's already
+    /// starred; returns whether it's starred now
+    pub fn toggle_favorite(&mut self, favorite: Favorite) -> bool {
+        let starred = |f: &Favorite| f.kind == favorite.kind && f.target == favorite.target;
+        if let Some(index) = self.favorites.iter().position(starred) {
+            self.favorites.remove(index);
+            return false;
+        }
+        self.favorites.push(favorite);
+        true
+    }
+
+    /// Move the favorite at `index` up (negative `by`) or down the list;
+    /// returns its new index, or `None` if it can't move that way
+    pub fn move_favorite(&mut self, index: usize, by: isize) -> Option<usize> {
+        let target = index.checked_add_signed(by).filter(|target| *target < self.favorites.len())?;
+        if index >= self.favorites.len() {
+            return None;
+        }
+        let favorite = self.favorites.remove(index);
+        self.favorites.insert(target, favorite);
+        Some(target)
+    }
+
+    /// Create the config file with defaults if it doesn't exist yet
+    pub fn ensure_exists() -> Result<PathBuf, String> {
+        let path = Self::path();
+        if !path.exists() {
+            let config = Self::default();
+            config.write(&config.to_toml()?)?;
+        }
+        Ok(path)
+    }
+
+    /// Write `content` (this config as TOML) to config.toml
+    fn write(&self, content: &str) -> Result<(), String> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {}", e))?;
+        }
+        fs::write(&path, content).map_err(|e| format!("Failed to write config: {}", e))?;
+        *LAST_SAVED.lock().unwrap() = Some(content.to_string());
+        Ok(())
+    }
+}
+
+/// `content`, the TOML `old` was loaded from, with what differs in `new`
+/// rewritten and everything else left as it is
+fn patch(content: &str, old: &Config, new: &Config) -> Result<String, String> {
+    let mut document: DocumentMut = content.parse().map_err(|e| format!("Invalid config: {}", e))?;
+    let table = |config: &Config| toml::Table::try_from(config).map_err(|e| e.to_string());
+    patch_table(document.as_table_mut(), &table(old)?, &table(new)?)?;
+    Ok(document.to_string())
+}
+
+/// Bring `section` from `old` to `new`, descending into tables so that only
+/// the keys that changed are touched
+fn patch_table(section: &mut Table, old: &toml::Table, new: &toml::Table) -> Result<(), String> {
+    for (key, value) in new {
+        let before = old.get(key);
+        if before == Some(value) {
+            continue;
+        }
+        match (value.as_table(), section.get(key).is_none_or(Item::is_table)) {
+            (Some(table), true) => {
+                let mut added = Table::new();
+                added.set_implicit(true);
+                let Item::Table(inner) = section.entry(key).or_insert(Item::Table(added)) else {
+                    unreachable!("only tables are descended into");
+                };
+                let empty = toml::Table::new();
+                patch_table(inner, before.and_then(toml::Value::as_table).unwrap_or(&empty), table)?;
+            }
+            _ => {
+                let mut item = to_item(value)?;
+                match section.get_mut(key) {
+                    // Replaced in place, keeping the comments around it
+                    Some(existing) => {
+                        if let (Item::Value(old), Item::Value(new)) = (&*existing, &mut item) {
+                            *new.decor_mut() = old.decor().clone();
+                        }
+                        *existing = item;
+                    }
+                    None => {
+                        section.insert(key, item);
+                    }
+                }
+            }
+        }
+    }
+    for key in old.keys().filter(|key| !new.contains_key(*key)) {
+        section.remove(key);
+    }
+    Ok(())
+}
+
+/// `value` as written to config.toml: tables as `[sections]`, arrays of
+/// tables as `[[sections]]`, anything else inline
+fn to_item(value: &toml::Value) -> Result<Item, String> {
+    match value {
+        toml::Value::Table(table) => {
+            let mut section = Table::new();
+            for (key, value) in table {
+                section.insert(key, to_item(value)?);
+            }
+            Ok(Item::Table(section))
+        }
+        toml::Value::Array(array) if !array.is_empty() && array.iter().all(toml::Value::is_table) => {
+            let mut sections = ArrayOfTables::new();
+            for table in array {
+                if let Item::Table(section) = to_item(table)? {
+                    sections.push(section);
+                }
+            }
+            Ok(Item::ArrayOfTables(sections))
+        }
+        value => value.to_string().parse::<Value>().map(Item::Value).map_err(|e| e.to_string()),
+    }
+}
+
+/// Expand a leading `~` to the user's home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    if path == "~" {
+        return paths::home().unwrap_or_else(|| PathBuf::from(path));
+    }
+    if let Some(rest) = path.strip_prefix("~/") {
+        if let Some(home) = paths::home() {
+            return home.join(rest);
+        }
+    }
+    PathBuf::from(path)
+}
+
+/// Percent-encode a string for use in a URL query
+fn encode_query(query: &str) -> String {
+    let mut encoded = String::with_capacity(query.len());
+    for byte in query.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b' ' => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flows::Step;
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config = Config::from_toml("[files]\nmax_depth = 2\n").unwrap();
+        assert_eq!(config.files.max_depth, 2);
+        assert_eq!(config.files.search_dirs, FilesConfig::default().search_dirs);
+    }
+
+    #[test]
+    fn test_quicklink_url() {
+        let config = Config::from_toml(
+            "[quicklinks]\ngh = \"https://github.com/search?q={query}\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.quicklink_url("gh rust & c++").as_deref(),
+            Some("https://github.com/search?q=rust+%26+c%2B%2B")
+        );
+        assert_eq!(config.quicklink_url("gh").as_deref(), Some("https://github.com/search?q="));
+        assert_eq!(config.quicklink_url("ghx rust"), None);
+    }
+
+    #[test]
+    fn test_app_preference() {
+        let config = Config::from_toml("[apps]\nprefer = [\"flatpak\", \"native\"]\n").unwrap();
+        assert_eq!(config.apps.prefer, vec![AppSource::Flatpak, AppSource::Native]);
+        assert_eq!(Config::default().apps.prefer[0], AppSource::Native);
+    }
+
+    #[test]
+    fn test_launch_overrides() {
+        let config = Config::from_toml(
+            "[apps.overrides.\"code.desktop\"]\nargs = [\"--disable-gpu\"]\nbackend = \"x11\"\n\
+             env = { LANG = \"C\" }\n",
+        )
+        .unwrap();
+        let launch = &config.apps.overrides["code.desktop"];
+        assert_eq!(launch.args, vec!["--disable-gpu"]);
+        assert_eq!(launch.backend, Some(crate::apps::DisplayBackend::X11));
+        assert_eq!(launch.env.get("LANG").map(String::as_str), Some("C"));
+        assert!(Config::from_toml("[apps.overrides.\"x.desktop\"]\nbackend = \"mir\"\n").is_err());
+    }
+
+    #[test]
+    fn test_clipboard() {
+        let config = Config::from_toml("[clipboard]\nhistory_limit = 200\nignore = [\"KeePassXC\"]\n").unwrap();
+        assert_eq!(config.clipboard.history_limit, 200);
+        assert_eq!(config.clipboard.poll_interval_ms, 1000);
+        assert_eq!(config.clipboard.ignore, vec!["KeePassXC"]);
+        assert_eq!(config.clipboard.merge_separator, "\n");
+        assert_eq!(config.clipboard.ocr_language, "eng");
+    }
+
+    #[test]
+    fn test_api() {
+        assert!(!Config::default().api.enabled);
+        let config = Config::from_toml("[api]\nenabled = true\n").unwrap();
+        assert_eq!((config.api.enabled, config.api.port), (true, 42322));
+    }
+
+    #[test]
+    fn test_calendar() {
+        let config = Config::from_toml("[calendar]\nics = [\"~/cal/work.ics\"]\n").unwrap();
+        assert_eq!(config.calendar.days, 7);
+        assert!(config.calendar.resolved_files()[0].ends_with("cal/work.ics"));
+    }
+
+    #[test]
+    fn test_hidden_apps() {
+        let mut config = Config::from_toml("[apps]\nhidden = [\"org.kde.kwalletd6\"]\n").unwrap();
+        assert!(config.apps.toggle_hidden("nm-applet"));
+        assert!(!config.apps.toggle_hidden("org.kde.kwalletd6"));
+        assert_eq!(config.apps.hidden, vec!["nm-applet"]);
+    }
+
+    #[test]
+    fn test_templates() {
+        let config = Config::from_toml("[templates]\nsummarize = \"Summarize:\\n{clipboard}\"\n").unwrap();
+        assert_eq!(config.templates.get("summarize").map(String::as_str), Some("Summarize:\n{clipboard}"));
+    }
+
+    #[test]
+    fn test_hooks() {
+        let config = Config::from_toml("[hooks]\napp_launched = [\"~/bin/log-launch\"]\n").unwrap();
+        let launched = HookEvent::AppLaunched { id: "firefox".into(), name: "Firefox".into() };
+        assert_eq!(config.hooks.commands(&launched), ["~/bin/log-launch"]);
+        assert!(config.hooks.commands(&HookEvent::WindowShown).is_empty());
+    }
+
+    #[test]
+    fn test_flows() {
+        let config = Config::from_toml("[flows.standup]\nsteps = [{ launch = \"zoom\" }, \"copy\"]\n").unwrap();
+        assert_eq!(config.flows["standup"].steps, vec![Step::Launch(Some("zoom".into())), Step::Copy(None)]);
+        assert!(Config::from_toml("[flows.standup]\nsteps = [\"teleport\"]\n").is_err());
+    }
+
+    #[test]
+    fn test_auto_hide() {
+        let config = Config::from_toml("[ui]\nauto_hide = \"search\"\n").unwrap();
+        assert_eq!(config.ui.auto_hide, AutoHide::Search);
+        assert_eq!(Config::default().ui.auto_hide, AutoHide::Always);
+        assert!(Config::from_toml("[ui]\nauto_hide = \"sometimes\"\n").is_err());
+    }
+
+    #[test]
+    fn test_fonts() {
+        let config = Config::from_toml("[ui]\nscale = 1.5\n[fonts]\nfamily = \"Inter\"\nfallback = [\"Twemoji\"]\nsize = 9\n").unwrap();
+        assert_eq!(config.ui.scale_factor(), 1.5);
+        assert_eq!(config.fonts.family, "Inter");
+        assert_eq!(config.fonts.fallback, vec!["Twemoji".to_string()]);
+        assert_eq!(config.fonts.size_factor(), 3.0);
+        assert_eq!(Config::default().fonts.size_factor(), 1.0);
+        assert_eq!(Config::default().ui.scale_factor(), 1.0);
+    }
+
+    #[test]
+    fn test_restart_needed() {
+        let old = Config::default();
+        let new = Config::from_toml("[ui]\ntheme = \"light\"\nscale = 2.0\n[fonts]\nfamily = \"Inter\"\n").unwrap();
+        assert_eq!(old.restart_needed(&new), vec!["[fonts]", "[ui] scale"]);
+        assert!(old.restart_needed(&Config::from_toml("[ui]\ntheme = \"light\"\n").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_language() {
+        assert_eq!(Config::from_toml("[ui]\nlanguage = \"de\"\n").unwrap().ui.language, "de");
+        assert!(Config::default().ui.language.is_empty());
+    }
+
+    #[test]
+    fn test_memory() {
+        let config = Config::from_toml("[memory]\nfolders = [\"~/notes\"]\n").unwrap();
+        assert_eq!(config.memory.resolved_dirs(), vec![expand_home("~/notes")]);
+        assert_eq!(config.memory.passages, 3);
+        assert!(Config::default().memory.folders.is_empty());
+    }
+
+    #[test]
+    fn test_abbreviations() {
+        let config = Config::from_toml("[abbreviations]\n\"@@\" = \"me@example.com\"\n").unwrap();
+        assert_eq!(config.abbreviations.get("@@").map(String::as_str), Some("me@example.com"));
+        assert!(Config::default().abbreviations.is_empty());
+    }
+
+    #[test]
+    fn test_favorites() {
+        let mut config = Config::from_toml(
+            "[[favorites]]\nkind = \"app\"\ntarget = \"firefox.desktop\"\ntitle = \"Firefox\"\n",
+        )
+        .unwrap();
+        let favorite = |kind, target: &str| Favorite { kind, target: target.to_string(), title: target.to_string() };
+        assert!(config.toggle_favorite(favorite(FavoriteKind::File, "/home/me/todo.md")));
+        assert!(config.toggle_favorite(favorite(FavoriteKind::Quicklink, "gh")));
+
+        assert_eq!(config.move_favorite(2, -2), Some(0));
+        assert_eq!(config.move_favorite(0, -1), None);
+        assert_eq!(config.move_favorite(2, 1), None);
+        let targets: Vec<&str> = config.favorites.iter().map(|f| f.target.as_str()).collect();
+        assert_eq!(targets, vec!["gh", "firefox.desktop", "/home/me/todo.md"]);
+
+        // Starring again unstars, whatever the title
+        assert!(!config.toggle_favorite(favorite(FavoriteKind::App, "firefox.desktop")));
+        assert_eq!(config.favorites.len(), 2);
+        let text = toml::to_string_pretty(&config).unwrap();
+        assert_eq!(Config::from_toml(&text).unwrap(), config);
+    }
+
+    #[test]
+    fn test_saved_searches() {
+        let config = Config::from_toml(
+            "[saved_searches.invoices]\nquery = \"kind:pdf invoice\"\ncategory = \"files\"\n\n[saved_searches.music]\nquery = \"spotify\"\n",
+        )
+        .unwrap();
+        let invoices = &config.saved_searches["invoices"];
+        assert_eq!(invoices.category, SearchCategory::Files);
+        assert_eq!(invoices.prompt(), "/file kind:pdf invoice");
+        assert_eq!(config.saved_searches["music"].prompt(), "spotify");
+
+        assert_eq!(SavedSearch::from_prompt("/files  kind:pdf invoice").as_ref(), Some(invoices));
+        assert_eq!(SavedSearch::from_prompt("/git ruty").map(|saved| saved.category), Some(SearchCategory::Repos));
+        assert_eq!(SavedSearch::from_prompt("/timer 5m"), None);
+        let text = toml::to_string_pretty(&config).unwrap();
+        assert_eq!(Config::from_toml(&text).unwrap(), config);
+    }
+
+    #[test]
+    fn test_bookmarks() {
+        let config = Config::from_toml(
+            "[[bookmarks]]\npath = \"~/work/src/\"\nshortcut = \"w\"\n\n[[bookmarks]]\npath = \"/tmp\"\n",
+        )
+        .unwrap();
+        let [work, tmp] = config.bookmarks.as_slice() else {
+            panic!("Expected two bookmarks");
+        };
+        assert_eq!((work.name(), tmp.name(), tmp.shortcut.as_deref()), ("src", "tmp", None));
+        assert!(work.is(",w") && work.is("w") && work.is("~/work/src/"));
+        assert!(!tmp.is(",w"));
+        let text = toml::to_string_pretty(&config).unwrap();
+        assert_eq!(text.matches("shortcut").count(), 1);
+        assert_eq!(Config::from_toml(&text).unwrap(), config);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let config = Config::default();
+        let text = toml::to_string_pretty(&config).unwrap();
+        assert_eq!(Config::from_toml(&text).unwrap(), config);
+    }
+
+    #[test]
+    fn test_patch() {
+        let content = "# My launcher\n[ui]\ntheme = \"light\" # easier on the eyes\n\n[aliases]\ngh = \"github.com\"\n";
+        let old = Config::from_toml(content).unwrap();
+        let mut new = old.clone();
+        new.ui.theme = Theme::Dark;
+        new.aliases.remove("gh");
+        new.aliases.insert("yt".into(), "youtube.com".into());
+        new.permissions.insert("flow:backup".into(), BTreeSet::from([Capability::Shell]));
+        new.apps.overrides.insert("firefox".into(), LaunchOverride { args: vec!["--private".into()], ..Default::default() });
+
+        let patched = patch(content, &old, &new).unwrap();
+        assert_eq!(Config::from_toml(&patched).unwrap(), new);
+        // Comments survive, and settings left at their defaults aren't written out
+        assert!(patched.starts_with("# My launcher\n[ui]\ntheme = \"dark\" # easier on the eyes\n"));
+        assert!(!patched.contains("gh") && !patched.contains("[apps]") && !patched.contains("[search]"));
+        assert!(patched.contains("[permissions]\n\"flow:backup\" = [\"shell\"]\n"));
+    }
+}
@@ -0,0 +1,209 @@
+//! Address book
+//!
+//! Contacts are read from vCard files (those under `[contacts] vcf`, each a
+//! file or a folder of them such as a vdirsyncer collection) and from
+//! Evolution's local address book, which GNOME Contacts writes to. Evolution
+//! keeps its cards in SQLite, read with the `sqlite3` CLI; without it only
+//! the files are used.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::calendar::{parse_line, unescape, unfold, Property};
+use crate::paths;
+
+/// Table Evolution's file backend keeps the local address book's cards in
+const EVOLUTION_QUERY: &str = "SELECT vcard FROM folder_id";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contact {
+    /// The card's UID, or its name when it has none
+    pub uid: String,
+    pub name: String,
+    pub emails: Vec<String>,
+    pub phones: Vec<String>,
+    pub organization: Option<String>,
+}
+
+impl Contact {
+    /// First email and phone number, and the organization: "ada@example.com
+    /// · +44 20 7946 0000 · Analytical Engines"
+    pub fn summary(&self) -> String {
+        [self.emails.first(), self.phones.first(), self.organization.as_ref()]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+/// Configured vCard files, with folders expanded to the `.vcf` files in them
+pub fn sources(configured: Vec<PathBuf>) -> Vec<PathBuf> {
+    configured
+        .into_iter()
+        .flat_map(|path| {
+            if !path.is_dir() {
+                return vec![path];
+            }
+            let mut files: Vec<PathBuf> = fs::read_dir(&path)
+                .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+                .unwrap_or_default();
+            files.retain(|file| file.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("vcf")));
+            files.sort();
+            files
+        })
+        .collect()
+}
+
+/// Contacts from `files` and Evolution's address book, sorted by name (a
+/// contact in several of them is kept once)
+pub fn load(files: &[PathBuf]) -> Vec<Contact> {
+    let mut contacts: Vec<Contact> = files
+        .iter()
+        .filter_map(|file| match fs::read_to_string(file) {
+            Ok(text) => Some(text),
+            Err(e) => {
+                tracing::warn!("Cannot read contacts {}: {}", file.display(), e);
+                None
+            }
+        })
+        .chain(evolution_cards())
+        .flat_map(|text| parse(&text))
+        .collect();
+    let mut seen = HashSet::new();
+    contacts.retain(|contact| seen.insert(contact.uid.clone()));
+    contacts.sort_by_key(|contact| contact.name.to_lowercase());
+    contacts
+}
+
+/// The cards in Evolution's local address book, if there is one
+fn evolution_cards() -> Option<String> {
+    let db = paths::data_home().join("evolution/addressbook/system/contacts.db");
+    if !db.is_file() {
+        return None;
+    }
+    query_sqlite(&db)
+        .map_err(|e| tracing::debug!("Skipping Evolution's address book: {}", e))
+        .ok()
+}
+
+fn query_sqlite(db: &Path) -> Result<String, String> {
+    let output = Command::new("sqlite3")
+        .arg("-readonly")
+        .arg(db)
+        .arg(EVOLUTION_QUERY)
+        .output()
+        .map_err(|e| format!("Cannot run sqlite3 (is it installed?): {}", e))?;
+    if !output.status.success() {
+        return Err(format!("sqlite3 failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The contacts in vCard text (3.0 and 4.0; 2.1's quoted-printable values
+/// are left as they are)
+pub fn parse(text: &str) -> Vec<Contact> {
+    let mut contacts = Vec::new();
+    let mut card: Option<Vec<Property>> = None;
+    for line in unfold(text) {
+        let Some(mut property) = parse_line(&line) else { continue };
+        // Apple's grouped properties: `item1.EMAIL`
+        if let Some((_, name)) = property.name.rsplit_once('.') {
+            property.name = name.to_string();
+        }
+        match (property.name.as_str(), card.as_mut()) {
+            ("BEGIN", _) if property.value.eq_ignore_ascii_case("VCARD") => card = Some(Vec::new()),
+            ("END", Some(_)) if property.value.eq_ignore_ascii_case("VCARD") => {
+                contacts.extend(card.take().and_then(|properties| contact(&properties)));
+            }
+            (_, Some(properties)) => properties.push(property),
+            _ => {}
+        }
+    }
+    contacts
+}
+
+fn contact(properties: &[Property]) -> Option<Contact> {
+    let values = |name: &str| -> Vec<String> {
+        properties
+            .iter()
+            .filter(|property| property.name == name)
+            .map(|property| unescape(&property.value).trim().to_string())
+            .filter(|value| !value.is_empty())
+            .collect()
+    };
+    let emails: Vec<String> =
+        values("EMAIL").into_iter().map(|email| email.trim_start_matches("mailto:").to_string()).collect();
+    let phones: Vec<String> = values("TEL").into_iter().map(|tel| tel.trim_start_matches("tel:").to_string()).collect();
+    let organization = values("ORG").into_iter().next().map(|org| {
+        // Organization and units are `;`-separated
+        org.split(';').map(str::trim).filter(|part| !part.is_empty()).collect::<Vec<_>>().join(", ")
+    });
+    let name = values("FN")
+        .into_iter()
+        .next()
+        .or_else(|| values("N").into_iter().next().map(|n| structured_name(&n)))
+        .filter(|name| !name.is_empty())
+        .or_else(|| organization.clone())
+        .or_else(|| emails.first().cloned())?;
+    let uid = values("UID").into_iter().next().unwrap_or_else(|| name.clone());
+    Some(Contact { uid, name, emails, phones, organization: organization.filter(|org| !org.is_empty()) })
+}
+
+/// `Family;Given;Additional;Prefix;Suffix` as "Given Family"
+fn structured_name(n: &str) -> String {
+    let parts: Vec<&str> = n.split(';').map(str::trim).collect();
+    [parts.get(1), parts.first()]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let vcf = "BEGIN:VCARD\r\n\
+VERSION:3.0\r\n\
+UID:ada\r\n\
+FN:Ada Lovelace\r\n\
+N:Lovelace;Ada;;;\r\n\
+EMAIL;TYPE=WORK:ada@example.com\r\n\
+item1.EMAIL;type=INTERNET:ada@home.example\r\n\
+TEL;TYPE=CELL:+44 20 7946\r\n\
+\x200000\r\n\
+ORG:Analytical Engines\\, Ltd;Research\r\n\
+END:VCARD\r\n\
+BEGIN:VCARD\r\n\
+VERSION:4.0\r\n\
+N:Hopper;Grace;;;\r\n\
+TEL;VALUE=uri:tel:+1-555-0100\r\n\
+END:VCARD\r\n\
+BEGIN:VCARD\r\n\
+VERSION:4.0\r\n\
+END:VCARD\r\n";
+        let contacts = parse(vcf);
+        assert_eq!(contacts.len(), 2);
+        assert_eq!(
+            contacts[0],
+            Contact {
+                uid: "ada".to_string(),
+                name: "Ada Lovelace".to_string(),
+                emails: vec!["ada@example.com".to_string(), "ada@home.example".to_string()],
+                phones: vec!["+44 20 79460000".to_string()],
+                organization: Some("Analytical Engines, Ltd, Research".to_string()),
+            }
+        );
+        assert_eq!(contacts[0].summary(), "ada@example.com · +44 20 79460000 · Analytical Engines, Ltd, Research");
+        assert_eq!((contacts[1].name.as_str(), contacts[1].uid.as_str()), ("Grace Hopper", "Grace Hopper"));
+        assert_eq!(contacts[1].phones, vec!["+1-555-0100".to_string()]);
+    }
+}
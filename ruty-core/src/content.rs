@@ -0,0 +1,265 @@
+//! Content type detection
+//!
+//! Clipboard entries are tagged with what they look like (a URL, an email
+//! address, a path, a color, JSON, code or plain text) when they're copied,
+//! so history can show a badge per entry, filter by type and offer actions
+//! that fit ("Open in browser" for a URL). Detection is a cheap syntactic
+//! guess: nothing is fetched or checked on disk.
+
+use serde::{Deserialize, Serialize};
+
+/// What a piece of text looks like
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentKind {
+    Url,
+    Email,
+    Path,
+    Color,
+    Json,
+    Code,
+    #[default]
+    Text,
+}
+
+impl ContentKind {
+    pub const ALL: &'static [ContentKind] = &[
+        ContentKind::Url,
+        ContentKind::Email,
+        ContentKind::Path,
+        ContentKind::Color,
+        ContentKind::Json,
+        ContentKind::Code,
+        ContentKind::Text,
+    ];
+
+    /// Stable identifier, as serialized
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Url => "url",
+            Self::Email => "email",
+            Self::Path => "path",
+            Self::Color => "color",
+            Self::Json => "json",
+            Self::Code => "code",
+            Self::Text => "text",
+        }
+    }
+
+    /// Badge shown on clipboard entries
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Url => "URL",
+            Self::Email => "Email",
+            Self::Path => "Path",
+            Self::Color => "Color",
+            Self::Json => "JSON",
+            Self::Code => "Code",
+            Self::Text => "Text",
+        }
+    }
+
+    /// Parse a filter name: singular or plural, any case ("urls", "JSON")
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        match name.strip_suffix('s').unwrap_or(&name) {
+            "url" | "link" => Some(Self::Url),
+            "email" | "mail" => Some(Self::Email),
+            "path" | "file" => Some(Self::Path),
+            "color" | "colour" => Some(Self::Color),
+            "json" => Some(Self::Json),
+            "code" | "snippet" => Some(Self::Code),
+            "text" => Some(Self::Text),
+            _ => None,
+        }
+    }
+}
+
+/// Classify text, with a guess at the language when it's code
+pub fn classify(text: &str) -> (ContentKind, Option<&'static str>) {
+    let trimmed = text.trim();
+    let single_token = !trimmed.is_empty() && !trimmed.contains(char::is_whitespace);
+    if single_token {
+        if is_url(trimmed) {
+            return (ContentKind::Url, None);
+        }
+        if is_email(trimmed) {
+            return (ContentKind::Email, None);
+        }
+        if is_path(trimmed) {
+            return (ContentKind::Path, None);
+        }
+    }
+    if is_color(trimmed) {
+        return (ContentKind::Color, None);
+    }
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(trimmed).is_ok_and(|v| v.is_object() || v.is_array())
+    {
+        return (ContentKind::Json, None);
+    }
+    match code_language(trimmed) {
+        Some(language) => (ContentKind::Code, (!language.is_empty()).then_some(language)),
+        None => (ContentKind::Text, None),
+    }
+}
+
+fn is_url(text: &str) -> bool {
+    ["http://", "https://", "ftp://", "file://"]
+        .iter()
+        .any(|scheme| text.len() > scheme.len() && text.to_lowercase().starts_with(scheme))
+        || (text.starts_with("www.") && text[4..].contains('.'))
+}
+
+fn is_email(text: &str) -> bool {
+    let text = text.strip_prefix("mailto:").unwrap_or(text);
+    let Some((local, domain)) = text.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && domain.split('.').count() >= 2
+        && domain.split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '-'))
+}
+
+fn is_path(text: &str) -> bool {
+    (text.starts_with('/') || text.starts_with("~/")) && text.len() > 1 && !text.contains("//")
+}
+
+fn is_color(text: &str) -> bool {
+    if let Some(hex) = text.strip_prefix('#') {
+        return matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit());
+    }
+    let lower = text.to_lowercase();
+    ["rgb(", "rgba(", "hsl(", "hsla("].iter().any(|f| lower.starts_with(f)) && lower.ends_with(')') && !lower.contains('\n')
+}
+
+/// A color in the other common notation: `rgb()` for hex, hex for
+/// `rgb()`/`rgba()` (`None` for hsl or malformed colors)
+pub fn convert_color(color: &str) -> Option<String> {
+    let color = color.trim().to_lowercase();
+    if let Some(hex) = color.strip_prefix('#') {
+        let digits: Vec<u8> = match hex.len() {
+            3 | 4 => hex.chars().map(|c| u8::from_str_radix(&c.to_string().repeat(2), 16).ok()).collect::<Option<_>>()?,
+            6 | 8 => (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+                .collect::<Option<_>>()?,
+            _ => return None,
+        };
+        return Some(match digits[..] {
+            [r, g, b] => format!("rgb({}, {}, {})", r, g, b),
+            [r, g, b, a] => format!("rgba({}, {}, {}, {})", r, g, b, (a as f32 / 255.0 * 100.0).round() / 100.0),
+            _ => return None,
+        });
+    }
+    let inner = color.strip_prefix("rgba(").or_else(|| color.strip_prefix("rgb("))?.strip_suffix(')')?;
+    let parts: Vec<&str> = inner.split([',', ' ', '/']).filter(|p| !p.is_empty()).collect();
+    let channel = |part: &str| part.parse::<u8>().ok();
+    match parts[..] {
+        [r, g, b] => Some(format!("#{:02x}{:02x}{:02x}", channel(r)?, channel(g)?, channel(b)?)),
+        [r, g, b, a] => {
+            let alpha = a.parse::<f32>().ok().filter(|a| (0.0..=1.0).contains(a))?;
+            Some(format!(
+                "#{:02x}{:02x}{:02x}{:02x}",
+                channel(r)?,
+                channel(g)?,
+                channel(b)?,
+                (alpha * 255.0).round() as u8
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Language of a code snippet ("" if it's code but the language is unclear),
+/// or `None` for prose
+fn code_language(text: &str) -> Option<&'static str> {
+    let lines: Vec<&str> = text.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    let has = |needles: &[&str]| lines.iter().any(|line| needles.iter().any(|n| line.starts_with(n)));
+    let contains = |needle: &str| text.contains(needle);
+
+    if text.starts_with("#!") {
+        return Some(if contains("python") { "Python" } else { "Shell" });
+    }
+    if has(&["fn ", "pub fn ", "impl ", "use std::", "let mut "]) || contains("println!(") {
+        return Some("Rust");
+    }
+    if lines.iter().any(|l| (l.starts_with("def ") || l.starts_with("class ")) && l.ends_with(':'))
+        || (has(&["from "]) && contains(" import "))
+    {
+        return Some("Python");
+    }
+    if has(&["package ", "func "]) && contains("func ") {
+        return Some("Go");
+    }
+    if has(&["#include"]) {
+        return Some("C");
+    }
+    if has(&["function ", "const ", "export "])
+        || (has(&["import "]) && contains(";"))
+        || contains("=> {")
+        || contains("console.log(")
+    {
+        return Some("JavaScript");
+    }
+    if has(&["<!doctype", "<!DOCTYPE", "<html", "<div", "<?xml"]) && contains("</") {
+        return Some("HTML");
+    }
+    let upper = text.to_uppercase();
+    if ["SELECT ", "INSERT ", "UPDATE ", "DELETE ", "CREATE "].iter().any(|k| upper.starts_with(k))
+        && [" FROM ", " INTO ", " SET ", " TABLE "].iter().any(|k| upper.replace('\n', " ").contains(k))
+    {
+        return Some("SQL");
+    }
+    if has(&["$ ", "sudo ", "cd ", "export "]) && lines.len() <= 20 {
+        return Some("Shell");
+    }
+    // Unclear language: most lines end like statements or blocks do
+    let structured = lines
+        .iter()
+        .filter(|line| line.ends_with(';') || line.ends_with('{') || line.ends_with('}') || line.ends_with(");"))
+        .count();
+    (lines.len() >= 2 && structured * 2 >= lines.len()).then_some("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify("https://example.com/a?b=c").0, ContentKind::Url);
+        assert_eq!(classify("www.example.com").0, ContentKind::Url);
+        assert_eq!(classify("me@example.com").0, ContentKind::Email);
+        assert_eq!(classify("~/notes/todo.md").0, ContentKind::Path);
+        assert_eq!(classify("#1e1e2e").0, ContentKind::Color);
+        assert_eq!(classify("rgb(30, 30, 46)").0, ContentKind::Color);
+        assert_eq!(classify("{\"a\": [1, 2]}").0, ContentKind::Json);
+        assert_eq!(classify("fn main() {\n    println!(\"hi\");\n}"), (ContentKind::Code, Some("Rust")));
+        assert_eq!(classify("def f(x):\n    return x"), (ContentKind::Code, Some("Python")));
+        assert_eq!(classify("SELECT id\nFROM users;"), (ContentKind::Code, Some("SQL")));
+        assert_eq!(classify("a = 1;\nb = 2;"), (ContentKind::Code, None));
+        assert_eq!(classify("Meet at 5, bring #2 pencils").0, ContentKind::Text);
+        assert_eq!(classify("see https://example.com").0, ContentKind::Text);
+        assert_eq!(classify("[not json").0, ContentKind::Text);
+    }
+
+    #[test]
+    fn test_convert_color() {
+        assert_eq!(convert_color("#1E1E2E").as_deref(), Some("rgb(30, 30, 46)"));
+        assert_eq!(convert_color("#fff8").as_deref(), Some("rgba(255, 255, 255, 0.53)"));
+        assert_eq!(convert_color("rgb(30, 30, 46)").as_deref(), Some("#1e1e2e"));
+        assert_eq!(convert_color("rgba(255 0 0 / 0.5)").as_deref(), Some("#ff000080"));
+        assert_eq!(convert_color("rgb(300, 0, 0)"), None);
+        assert_eq!(convert_color("hsl(0, 100%, 50%)"), None);
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        assert_eq!(ContentKind::parse("URLs"), Some(ContentKind::Url));
+        assert_eq!(ContentKind::parse("paths"), Some(ContentKind::Path));
+        assert_eq!(ContentKind::parse("colour"), Some(ContentKind::Color));
+        assert_eq!(ContentKind::parse("pictures"), None);
+    }
+}
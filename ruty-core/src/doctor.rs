@@ -0,0 +1,307 @@
+//! Setup diagnostics for `ruty doctor` and `/doctor`
+//!
+//! Each check looks at one thing the launcher depends on and says what it
+//! found; one that failed or looks doubtful also says what to do about it.
+//! The checks here only need the environment and the filesystem. Frontends
+//! add the ones that need their own pieces (the backend, hotkeys, the
+//! daemon's ports) and print the lot with `report`.
+
+use std::env;
+use std::net::TcpListener;
+use std::os::unix::fs::PermissionsExt;
+
+use crate::apps::Application;
+use crate::config::Config;
+use crate::sandbox;
+use crate::validation;
+
+/// Share of app icons that must resolve before the icon theme looks fine
+const ICONS_FOUND_OK: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Ok => "✓",
+            Self::Warn => "!",
+            Self::Fail => "✗",
+        }
+    }
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    /// What was found
+    pub detail: String,
+    /// What to do about it, unless it's fine
+    pub fix: Option<String>,
+}
+
+impl Check {
+    pub fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, status: Status::Ok, detail: detail.into(), fix: None }
+    }
+
+    pub fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name, status: Status::Warn, detail: detail.into(), fix: Some(fix.into()) }
+    }
+
+    pub fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name, status: Status::Fail, detail: detail.into(), fix: Some(fix.into()) }
+    }
+}
+
+/// Display server of the session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Session {
+    Wayland,
+    X11,
+    /// Neither, e.g. over SSH
+    None,
+}
+
+impl Session {
+    pub fn detect() -> Self {
+        Self::from_env(|name| env::var(name).ok().filter(|value| !value.is_empty()))
+    }
+
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        if var("WAYLAND_DISPLAY").is_some() {
+            Self::Wayland
+        } else if var("DISPLAY").is_some() {
+            Self::X11
+        } else {
+            Self::None
+        }
+    }
+}
+
+/// The session type and desktop (`XDG_CURRENT_DESKTOP` or the compositor)
+pub fn session_check(session: Session, desktop: &str) -> Check {
+    let desktop = if desktop.is_empty() { "unknown desktop" } else { desktop };
+    match session {
+        Session::Wayland => Check::ok("Session", format!("Wayland ({})", desktop)),
+        Session::X11 => Check::ok("Session", format!("X11 ({})", desktop)),
+        Session::None => Check::fail(
+            "Session",
+            "No display: neither WAYLAND_DISPLAY nor DISPLAY is set",
+            "Start Ruty from the graphical session, or use `ruty --headless` without a window",
+        ),
+    }
+}
+
+/// Whether an executable of this name is on `PATH`
+pub fn has_program(name: &str) -> bool {
+    env::var_os("PATH").is_some_and(|path| {
+        env::split_paths(&path).any(|dir| {
+            dir.join(name)
+                .metadata()
+                .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        })
+    })
+}
+
+/// The external tools file search and the clipboard rely on; `has` tells
+/// whether a program is installed (see `has_program`)
+pub fn tools(session: Session, has: impl Fn(&str) -> bool) -> Vec<Check> {
+    let files = if has("fd") {
+        Check::ok("File search", "fd")
+    } else {
+        Check::warn(
+            "File search",
+            "fd isn't installed; searching with find, which is slower",
+            "Install fd (on Debian and Ubuntu the package is fd-find; link its fdfind binary as fd)",
+        )
+    };
+    let wayland = has("wl-copy") && has("wl-paste");
+    let x11 = has("xclip");
+    let clipboard = match session {
+        Session::Wayland if wayland => Check::ok("Clipboard", "wl-clipboard"),
+        Session::Wayland if x11 => Check::warn(
+            "Clipboard",
+            "Only xclip, which sees just the XWayland apps' clipboard",
+            "Install wl-clipboard",
+        ),
+        Session::Wayland => {
+            Check::fail("Clipboard", "Neither wl-clipboard nor xclip is installed", "Install wl-clipboard")
+        }
+        _ if x11 => Check::ok("Clipboard", "xclip"),
+        _ if wayland => Check::warn("Clipboard", "Only wl-clipboard, which needs Wayland", "Install xclip"),
+        _ => Check::fail("Clipboard", "Neither xclip nor wl-clipboard is installed", "Install xclip"),
+    };
+    vec![files, clipboard]
+}
+
+/// config.toml's text, `None` if there's no file
+pub fn config_check(content: Option<&str>) -> Check {
+    let Some(content) = content else {
+        return Check::ok("Config", "No config.toml; using the defaults");
+    };
+    match validation::validate(content) {
+        Ok(validated) => match validated.warnings.first() {
+            None => Check::ok("Config", "config.toml is valid"),
+            Some(first) => Check::warn(
+                "Config",
+                format!("{} warning(s), first {}", validated.warnings.len(), first),
+                "Run `ruty config validate` for the full list",
+            ),
+        },
+        Err(e) => Check::fail(
+            "Config",
+            format!("config.toml doesn't load: {}", e),
+            "Fix it (`ruty config validate` rechecks); the defaults apply until then",
+        ),
+    }
+}
+
+/// Whether a local port is free; `owner` names who holds it when it's known
+/// to be Ruty's own, and `fix` is what to do if a stranger holds it
+pub fn port_check(name: &'static str, addr: &str, owner: Option<&str>, fix: &str) -> Check {
+    if TcpListener::bind(addr).is_ok() {
+        return Check::ok(name, format!("{} is free", addr));
+    }
+    match owner {
+        Some(owner) => Check::ok(name, format!("{} is in use by {}", addr, owner)),
+        None => Check::fail(name, format!("{} is taken by another program", addr), fix),
+    }
+}
+
+/// How many of the apps' icons resolve to a file
+pub fn icons_check(apps: &[Application]) -> Check {
+    let named: Vec<&Application> = apps.iter().filter(|app| !app.no_display && app.icon.is_some()).collect();
+    if named.is_empty() {
+        return Check::warn("Icons", "No apps with icons are indexed", "Run `ruty doctor` again once apps are installed");
+    }
+    let found = named.iter().filter(|app| app.icon_path().is_some()).count();
+    let detail = format!("{} of {} app icons found", found, named.len());
+    if found as f32 >= named.len() as f32 * ICONS_FOUND_OK {
+        Check::ok("Icons", detail)
+    } else {
+        Check::warn("Icons", detail, "Install the hicolor and Adwaita icon themes (hicolor-icon-theme, adwaita-icon-theme)")
+    }
+}
+
+/// Whether the shell commands from config (flow steps, hooks) run
+/// sandboxed; `has` tells which programs are installed
+pub fn sandbox_check(config: &Config, has: impl Fn(&str) -> bool) -> Check {
+    let mut providers: Vec<(String, &sandbox::Sandbox)> = config
+        .flows
+        .iter()
+        .filter(|(_, flow)| flow.runs_shell())
+        .map(|(name, flow)| (format!("flow {}", name), flow.sandbox(&config.sandbox)))
+        .collect();
+    if config.hooks.any() {
+        providers.push(("hooks".to_string(), config.hooks.sandbox.as_ref().unwrap_or(&config.sandbox)));
+    }
+    if providers.is_empty() {
+        return Check::ok("Sandbox", "No flow shell steps or hooks configured");
+    }
+    let exposed: Vec<String> = providers
+        .into_iter()
+        .filter_map(|(name, sandbox)| {
+            let warning = sandbox::warning(sandbox, &has)?;
+            Some(format!("{} ({})", name, warning.trim_start_matches("⚠ ")))
+        })
+        .collect();
+    if exposed.is_empty() {
+        Check::ok("Sandbox", "Flow shell steps and hooks run sandboxed")
+    } else {
+        Check::warn(
+            "Sandbox",
+            exposed.join(", "),
+            "Set `enabled = true` under [sandbox] and install bubblewrap (bwrap) or firejail",
+        )
+    }
+}
+
+/// Checks as text, one line each, with fixes indented below
+pub fn report(checks: &[Check]) -> String {
+    let mut text = String::new();
+    for check in checks {
+        text.push_str(&format!("{} {}: {}\n", check.status.symbol(), check.name, check.detail));
+        if let Some(fix) = &check.fix {
+            text.push_str(&format!("    → {}\n", fix));
+        }
+    }
+    let problems = checks.iter().filter(|check| check.status != Status::Ok).count();
+    match problems {
+        0 => text.push_str("\nEverything looks fine."),
+        n => text.push_str(&format!("\n{} thing(s) to look at.", n)),
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session() {
+        let env = |vars: &'static [&'static str]| move |name: &str| vars.contains(&name).then(|| "1".to_string());
+        assert_eq!(Session::from_env(env(&["WAYLAND_DISPLAY", "DISPLAY"])), Session::Wayland);
+        assert_eq!(Session::from_env(env(&["DISPLAY"])), Session::X11);
+        assert_eq!(Session::from_env(env(&[])), Session::None);
+        assert_eq!(session_check(Session::None, "").status, Status::Fail);
+    }
+
+    #[test]
+    fn test_tools() {
+        let statuses = |session, installed: &'static [&'static str]| -> Vec<Status> {
+            tools(session, |name| installed.contains(&name)).iter().map(|check| check.status).collect()
+        };
+        assert_eq!(statuses(Session::Wayland, &["fd", "wl-copy", "wl-paste"]), [Status::Ok, Status::Ok]);
+        assert_eq!(statuses(Session::Wayland, &["xclip"]), [Status::Warn, Status::Warn]);
+        assert_eq!(statuses(Session::X11, &["wl-copy", "wl-paste"]), [Status::Warn, Status::Warn]);
+        assert_eq!(statuses(Session::X11, &[]), [Status::Warn, Status::Fail]);
+    }
+
+    #[test]
+    fn test_config_check() {
+        assert_eq!(config_check(None).status, Status::Ok);
+        assert_eq!(config_check(Some("[ui]\ntheme = \"light\"\n")).status, Status::Ok);
+        let typo = config_check(Some("[hotkye]\ntoggle = \"Super+Space\"\n"));
+        assert_eq!(typo.status, Status::Warn);
+        assert!(typo.detail.contains("did you mean `hotkeys`"), "{}", typo.detail);
+        assert_eq!(config_check(Some("[ui\n")).status, Status::Fail);
+    }
+
+    #[test]
+    fn test_sandbox_check() {
+        let mut config = Config::from_toml("[flows.stamp]\nsteps = [{ shell = \"date\" }]\n").unwrap();
+        let check = sandbox_check(&config, |_| true);
+        assert_eq!((check.status, check.detail.as_str()), (Status::Warn, "flow stamp (unsandboxed)"));
+        config.sandbox.enabled = true;
+        assert_eq!(sandbox_check(&config, |_| true).status, Status::Ok);
+        config.hooks.window_shown.push("notify-send shown".to_string());
+        assert_eq!(sandbox_check(&config, |_| false).detail, "flow stamp (environment-only sandbox), hooks (environment-only sandbox)");
+        assert_eq!(sandbox_check(&Config::default(), |_| false).status, Status::Ok);
+    }
+
+    #[test]
+    fn test_port_check() {
+        let held = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = held.local_addr().unwrap().to_string();
+        assert_eq!(port_check("Port", &addr, None, "Stop it").status, Status::Fail);
+        assert_eq!(port_check("Port", &addr, Some("Ruty"), "Stop it").status, Status::Ok);
+        drop(held);
+        assert_eq!(port_check("Port", &addr, None, "Stop it").status, Status::Ok);
+    }
+
+    #[test]
+    fn test_report() {
+        let checks = [Check::ok("Session", "X11"), Check::fail("Clipboard", "No xclip", "Install xclip")];
+        assert_eq!(
+            report(&checks),
+            "✓ Session: X11\n✗ Clipboard: No xclip\n    → Install xclip\n\n1 thing(s) to look at."
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! Document text extraction
+//!
+//! Converts PDFs and office documents (docx, odt) to plain text so they can be
+//! loaded as AI context; the backend only reads plain text files.
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// Largest document accepted for extraction
+pub const MAX_DOCUMENT_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Extracted text is truncated to this many characters
+pub const MAX_TEXT_CHARS: usize = 100_000;
+
+/// Largest uncompressed XML part read from an office document
+const MAX_XML_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    Pdf,
+    Docx,
+    Odt,
+}
+
+impl DocumentKind {
+    /// Detect a supported document from its extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "pdf" => Some(Self::Pdf),
+            "docx" => Some(Self::Docx),
+            "odt" => Some(Self::Odt),
+            _ => None,
+        }
+    }
+}
+
+/// Plain text pulled out of a document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Extracted {
+    pub text: String,
+    /// Text was cut at `MAX_TEXT_CHARS`
+    pub truncated: bool,
+}
+
+/// Extract plain text from a PDF, docx or odt file (blocking)
+pub fn extract(path: &Path) -> Result<Extracted, String> {
+    let kind = DocumentKind::from_path(path)
+        .ok_or_else(|| format!("Unsupported document: {}", path.display()))?;
+
+    let size = fs::metadata(path)
+        .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?
+        .len();
+    if size > MAX_DOCUMENT_BYTES {
+        return Err(format!(
+            "{} is too large ({} MB, limit {} MB)",
+            path.display(),
+            size / (1024 * 1024),
+            MAX_DOCUMENT_BYTES / (1024 * 1024)
+        ));
+    }
+
+    let text = match kind {
+        DocumentKind::Pdf => pdf_extract::extract_text(path)
+            .map_err(|e| format!("Failed to read PDF: {}", e))?,
+        DocumentKind::Docx => zip_part_text(path, "word/document.xml")?,
+        DocumentKind::Odt => zip_part_text(path, "content.xml")?,
+    };
+
+    Ok(truncate(tidy(&text)))
+}
+
+/// Text of one XML part inside a zip container
+fn zip_part_text(path: &Path, part: &str) -> Result<String, String> {
+    let file = File::open(path).map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Not a valid document archive: {}", e))?;
+    let entry = archive
+        .by_name(part)
+        .map_err(|_| format!("Document has no {}", part))?;
+    xml_text(BufReader::new(entry.take(MAX_XML_BYTES)))
+}
+
+/// Text content of WordprocessingML / OpenDocument XML, one line per paragraph
+fn xml_text<R: std::io::BufRead>(source: R) -> Result<String, String> {
+    let mut reader = Reader::from_reader(source);
+    let mut buf = Vec::new();
+    let mut text = String::new();
+    // Open paragraphs; text outside them is markup whitespace
+    let mut depth = 0usize;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Text(t)) if depth > 0 => {
+                let unescaped = t.unescape().map_err(|e| format!("Invalid document XML: {}", e))?;
+                text.push_str(&unescaped);
+            }
+            // Paragraphs and headings (w:p, text:p, text:h)
+            Ok(Event::Start(e)) if matches!(e.local_name().as_ref(), b"p" | b"h") => depth += 1,
+            Ok(Event::End(e)) if matches!(e.local_name().as_ref(), b"p" | b"h") => {
+                depth = depth.saturating_sub(1);
+                text.push('\n');
+            }
+            Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                b"tab" => text.push('\t'),
+                b"br" | b"cr" | b"line-break" => text.push('\n'),
+                b"s" => text.push(' '),
+                b"p" | b"h" => text.push('\n'),
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => return Err(format!("Invalid document XML: {}", e)),
+        }
+        buf.clear();
+    }
+    Ok(text)
+}
+
+/// Trim lines and collapse runs of blank lines
+fn tidy(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank = 0;
+    for line in text.lines().map(str::trim_end) {
+        if line.trim().is_empty() {
+            blank += 1;
+            if blank > 1 {
+                continue;
+            }
+        } else {
+            blank = 0;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.trim().to_string()
+}
+
+fn truncate(text: String) -> Extracted {
+    match text.char_indices().nth(MAX_TEXT_CHARS) {
+        Some((end, _)) => Extracted {
+            text: text[..end].to_string(),
+            truncated: true,
+        },
+        None => Extracted {
+            text,
+            truncated: false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_kind_from_path() {
+        assert_eq!(DocumentKind::from_path(Path::new("a/Report.PDF")), Some(DocumentKind::Pdf));
+        assert_eq!(DocumentKind::from_path(Path::new("notes.odt")), Some(DocumentKind::Odt));
+        assert_eq!(DocumentKind::from_path(Path::new("notes.txt")), None);
+    }
+
+    #[test]
+    fn test_xml_text() {
+        let docx = r#"<w:document xmlns:w="x"><w:body>
+            <w:p><w:r><w:t>Hello</w:t></w:r><w:r><w:tab/><w:t>world &amp; co</w:t></w:r></w:p>
+            <w:p><w:r><w:t>Second</w:t></w:r></w:p>
+        </w:body></w:document>"#;
+        assert_eq!(tidy(&xml_text(docx.as_bytes()).unwrap()), "Hello\tworld & co\nSecond");
+
+        let odt = r#"<office:text xmlns:text="x"><text:h>Title</text:h><text:p>a<text:s/>b</text:p></office:text>"#;
+        assert_eq!(tidy(&xml_text(odt.as_bytes()).unwrap()), "Title\na b");
+    }
+
+    #[test]
+    fn test_extract_docx() {
+        let path = std::env::temp_dir().join(format!("ruty-test-{}.docx", std::process::id()));
+        {
+            let mut zip = zip::ZipWriter::new(File::create(&path).unwrap());
+            zip.start_file("word/document.xml", zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(b"<w:document><w:body><w:p><w:r><w:t>From docx</w:t></w:r></w:p></w:body></w:document>")
+                .unwrap();
+            zip.finish().unwrap();
+        }
+        let extracted = extract(&path);
+        fs::remove_file(&path).ok();
+        assert_eq!(
+            extracted.unwrap(),
+            Extracted { text: "From docx".to_string(), truncated: false }
+        );
+    }
+
+    #[test]
+    fn test_truncate() {
+        let long = "é".repeat(MAX_TEXT_CHARS + 10);
+        let extracted = truncate(long);
+        assert!(extracted.truncated);
+        assert_eq!(extracted.text.chars().count(), MAX_TEXT_CHARS);
+    }
+}
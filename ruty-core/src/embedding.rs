@@ -0,0 +1,113 @@
+//! Sentence embeddings for local memory
+//!
+//! Runs all-MiniLM-L6-v2, a small BERT model, on the CPU with candle. Token
+//! states are averaged over the attention mask and scaled to unit length, so
+//! the dot product of two embeddings is their cosine similarity. The model
+//! files aren't shipped: the launcher asks before downloading them into the
+//! data dir (see `model_dir`).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use candle_core::{Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::bert::{BertModel, Config, DTYPE};
+use tokenizers::{PaddingParams, Tokenizer, TruncationParams};
+
+use crate::paths;
+
+/// Model name, also its directory under `models`
+pub const MODEL: &str = "all-MiniLM-L6-v2";
+
+/// Files of the model, as named in its Hugging Face repository
+pub const WEIGHTS: &str = "model.safetensors";
+pub const TOKENIZER: &str = "tokenizer.json";
+
+/// Length of an embedding
+pub const DIMENSIONS: usize = 384;
+
+/// Text past this many tokens doesn't count towards the embedding
+const MAX_TOKENS: usize = 256;
+
+/// Texts run through the model at once
+const BATCH: usize = 32;
+
+/// Something that turns text into unit vectors of the same length
+pub trait Embed: Send + Sync {
+    /// Embeddings of `texts`, in order
+    fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String>;
+}
+
+/// The loaded embedding model
+pub struct Model {
+    bert: BertModel,
+    tokenizer: Tokenizer,
+    device: Device,
+}
+
+impl Model {
+    /// Load the downloaded model
+    pub fn load() -> Result<Self, String> {
+        Self::load_from(&model_dir())
+    }
+
+    pub fn load_from(dir: &Path) -> Result<Self, String> {
+        let mut tokenizer = Tokenizer::from_file(dir.join(TOKENIZER))
+            .map_err(|e| format!("Cannot load the embedding tokenizer: {}", e))?;
+        // Pad each batch to its longest text
+        tokenizer.with_padding(Some(PaddingParams::default()));
+        tokenizer
+            .with_truncation(Some(TruncationParams { max_length: MAX_TOKENS, ..Default::default() }))
+            .map_err(|e| format!("Cannot load the embedding tokenizer: {}", e))?;
+
+        let device = Device::Cpu;
+        let weights = fs::read(dir.join(WEIGHTS)).map_err(|e| format!("Cannot read the embedding model: {}", e))?;
+        let bert = VarBuilder::from_buffered_safetensors(weights, DTYPE, &device)
+            .and_then(|weights| BertModel::load(weights, &config()))
+            .map_err(|e| format!("Cannot load the embedding model: {}", e))?;
+        Ok(Self { bert, tokenizer, device })
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> candle_core::Result<Vec<Vec<f32>>> {
+        let encodings = self.tokenizer.encode_batch(texts.to_vec(), true).map_err(candle_core::Error::msg)?;
+        let length = encodings.first().map_or(0, |encoding| encoding.len());
+        let shape = (encodings.len(), length);
+        let ids: Vec<u32> = encodings.iter().flat_map(|encoding| encoding.get_ids().to_vec()).collect();
+        let mask: Vec<u32> = encodings.iter().flat_map(|encoding| encoding.get_attention_mask().to_vec()).collect();
+        let ids = Tensor::from_vec(ids, shape, &self.device)?;
+        let mask = Tensor::from_vec(mask, shape, &self.device)?;
+
+        let states = self.bert.forward(&ids, &ids.zeros_like()?, Some(&mask))?;
+        // Mean of the states of the real (unpadded) tokens
+        let mask = mask.to_dtype(DTYPE)?.unsqueeze(2)?;
+        let mean = states.broadcast_mul(&mask)?.sum(1)?.broadcast_div(&mask.sum(1)?)?;
+        let norm = mean.sqr()?.sum_keepdim(1)?.sqrt()?;
+        mean.broadcast_div(&norm)?.to_vec2()
+    }
+}
+
+impl Embed for Model {
+    fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(BATCH) {
+            embeddings.extend(self.embed_batch(batch).map_err(|e| format!("Embedding failed: {}", e))?);
+        }
+        Ok(embeddings)
+    }
+}
+
+/// all-MiniLM-L6-v2's shape: a six-layer BERT with 384-wide states
+fn config() -> Config {
+    Config { hidden_size: DIMENSIONS, num_hidden_layers: 6, intermediate_size: 4 * DIMENSIONS, ..Config::default() }
+}
+
+/// Where the model files live
+pub fn model_dir() -> PathBuf {
+    paths::data_dir().join("models").join(MODEL)
+}
+
+/// Whether the model has to be downloaded before memory can be indexed
+pub fn needs_model() -> bool {
+    let dir = model_dir();
+    !dir.join(WEIGHTS).exists() || !dir.join(TOKENIZER).exists()
+}
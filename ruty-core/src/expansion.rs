@@ -0,0 +1,136 @@
+//! Text expansion of user abbreviations (`[abbreviations]` in config.toml)
+//!
+//! An abbreviation such as `@@ = "me@example.com"` expands as soon as it's
+//! typed, wherever the cursor is, as long as it starts a word. The same
+//! engine expands whole texts for paths that insert text rather than have it
+//! typed (e.g. pasting a snippet).
+
+use std::collections::BTreeMap;
+
+/// Abbreviations from config, matched longest first
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Expander {
+    abbreviations: Vec<(String, String)>,
+}
+
+impl Expander {
+    pub fn new(abbreviations: &BTreeMap<String, String>) -> Self {
+        let mut abbreviations: Vec<(String, String)> = abbreviations
+            .iter()
+            .filter(|(abbreviation, _)| !abbreviation.is_empty() && !abbreviation.contains(char::is_whitespace))
+            .map(|(abbreviation, expansion)| (abbreviation.clone(), expansion.clone()))
+            .collect();
+        abbreviations.sort_by_key(|(abbreviation, _)| std::cmp::Reverse(abbreviation.len()));
+        Self { abbreviations }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.abbreviations.is_empty()
+    }
+
+    /// Expand the abbreviation ending at byte `end` of `text`, if any.
+    /// Returns the new text and the byte offset just after the expansion.
+    pub fn expand_at(&self, text: &str, end: usize) -> Option<(String, usize)> {
+        let before = text.get(..end)?;
+        let (abbreviation, expansion) = self.abbreviations.iter().find(|(abbreviation, _)| {
+            before
+                .strip_suffix(abbreviation.as_str())
+                .is_some_and(|prefix| prefix.is_empty() || prefix.ends_with(char::is_whitespace))
+        })?;
+        let start = end - abbreviation.len();
+        let expanded = format!("{}{}{}", &text[..start], expansion, &text[end..]);
+        Some((expanded, start + expansion.len()))
+    }
+
+    /// Expand every whitespace-separated word of `text` that is an abbreviation
+    pub fn expand_all(&self, text: &str) -> String {
+        if self.is_empty() {
+            return text.to_string();
+        }
+        let mut expanded = String::with_capacity(text.len());
+        let mut word_start = None;
+        for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+            if c.is_whitespace() {
+                if let Some(start) = word_start.take() {
+                    let word = &text[start..i];
+                    match self.abbreviations.iter().find(|(abbreviation, _)| abbreviation == word) {
+                        Some((_, expansion)) => expanded.push_str(expansion),
+                        None => expanded.push_str(word),
+                    }
+                }
+                if i < text.len() {
+                    expanded.push(c);
+                }
+            } else if word_start.is_none() {
+                word_start = Some(i);
+            }
+        }
+        expanded
+    }
+}
+
+/// Where `new` differs from `old` by text inserted at one place (typing or a
+/// paste), the byte offset in `new` just after the inserted text
+pub fn insertion_end(old: &str, new: &str) -> Option<usize> {
+    if new.len() <= old.len() {
+        return None;
+    }
+    let prefix = old
+        .char_indices()
+        .zip(new.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(old.len(), |((i, _), _)| i);
+    let suffix = old.len() - prefix;
+    let end = new.len() - suffix;
+    (new.get(..prefix) == old.get(..prefix) && new.get(end..) == old.get(prefix..)).then_some(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expander() -> Expander {
+        Expander::new(&BTreeMap::from([
+            ("@@".to_string(), "me@example.com".to_string()),
+            ("@@w".to_string(), "me@work.example".to_string()),
+            (";sig".to_string(), "Best,\nSam".to_string()),
+            ("bad key".to_string(), "ignored".to_string()),
+        ]))
+    }
+
+    #[test]
+    fn test_expand_at() {
+        let expander = expander();
+        assert_eq!(
+            expander.expand_at("mail @@", 7),
+            Some(("mail me@example.com".to_string(), 19))
+        );
+        // Typed in the middle of the prompt
+        assert_eq!(
+            expander.expand_at("to @@ please", 5),
+            Some(("to me@example.com please".to_string(), 17))
+        );
+        assert_eq!(expander.expand_at("@@w", 3), Some(("me@work.example".to_string(), 15)));
+        // Only at the start of a word
+        assert_eq!(expander.expand_at("a@@", 3), None);
+        assert_eq!(expander.expand_at("bad key", 7), None);
+    }
+
+    #[test]
+    fn test_expand_all() {
+        assert_eq!(
+            expander().expand_all("reach @@ or @@w;  x@@ ;sig"),
+            "reach me@example.com or @@w;  x@@ Best,\nSam"
+        );
+        assert_eq!(Expander::default().expand_all("@@"), "@@");
+    }
+
+    #[test]
+    fn test_insertion_end() {
+        assert_eq!(insertion_end("mail @", "mail @@"), Some(7));
+        assert_eq!(insertion_end("to  please", "to @@ please"), Some(5));
+        assert_eq!(insertion_end("é", "éé"), Some(4));
+        assert_eq!(insertion_end("abc", "ab"), None);
+        assert_eq!(insertion_end("abc", "xbcd"), None);
+    }
+}
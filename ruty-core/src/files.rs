@@ -0,0 +1,335 @@
+//! File Search Module
+//!
+//! Provides fast file searching using fd (or find as fallback).
+//! Searches common user directories and returns results with paths,
+//! narrowed by any filters in the query (see `query`). `list_dir` lists
+//! one folder's children for browsing it from the launcher.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use jiff::Zoned;
+
+use crate::config::FilesConfig;
+use crate::fuzzy;
+use crate::query::{FileQuery, SizeFilter};
+
+/// File search result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileResult {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+    pub extension: Option<String>,
+}
+
+/// File searcher - uses fd for fast searching
+pub struct FileSearcher {
+    /// Use fd if available, otherwise fall back to find
+    use_fd: bool,
+    /// Directories to search
+    search_dirs: Vec<PathBuf>,
+    /// Maximum depth below each search dir
+    max_depth: usize,
+}
+
+impl FileSearcher {
+    pub fn new() -> Self {
+        Self::with_config(&FilesConfig::default())
+    }
+
+    /// Create a searcher using the given file search settings
+    pub fn with_config(config: &FilesConfig) -> Self {
+        // Check if fd is available
+        let use_fd = Command::new("fd")
+            .arg("--version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false);
+        
+        Self {
+            use_fd,
+            search_dirs: config.resolved_dirs(),
+            max_depth: config.max_depth,
+        }
+    }
+
+    /// Search for files matching query
+    pub fn search(&self, query: &str, max_results: usize, folders_only: bool) -> Vec<FileResult> {
+        let query = FileQuery { folders_only, ..FileQuery::name(query) };
+        self.search_query(&query, max_results)
+    }
+
+    /// Search for files matching a parsed query (see `query`)
+    pub fn search_query(&self, query: &FileQuery, max_results: usize) -> Vec<FileResult> {
+        // Filters alone list every file they let through
+        if query.text.is_empty() && !query.has_filters() {
+            return Vec::new();
+        }
+
+        if self.use_fd {
+            self.search_fd(query, max_results)
+        } else {
+            self.search_find(query, max_results)
+        }
+    }
+
+    /// Search using fd (fast, respects .gitignore)
+    fn search_fd(&self, query: &FileQuery, max_results: usize) -> Vec<FileResult> {
+        let mut results = Vec::new();
+        
+        let mut fd_args = vec![
+            "--hidden".to_string(),
+            "--no-ignore".to_string(),
+            "--max-depth".to_string(), self.max_depth.to_string(),
+            "--max-results".to_string(), max_results.to_string(),
+            "-i".to_string(),
+        ];
+        
+        if query.folders_only {
+            fd_args.push("--type".to_string());
+            fd_args.push("d".to_string());
+        } else if !query.extensions.is_empty() {
+            fd_args.push("--type".to_string());
+            fd_args.push("f".to_string());
+            for ext in &query.extensions {
+                fd_args.push("--extension".to_string());
+                fd_args.push(ext.clone());
+            }
+        } else {
+            fd_args.push("--type".to_string());
+            fd_args.push("f".to_string());
+            fd_args.push("--type".to_string());
+            fd_args.push("d".to_string());
+        }
+        if let Some(after) = &query.modified_after {
+            fd_args.push("--changed-within".to_string());
+            fd_args.push(local_time(after));
+        }
+        if let Some(before) = &query.modified_before {
+            fd_args.push("--changed-before".to_string());
+            fd_args.push(local_time(before));
+        }
+        match query.size {
+            Some(SizeFilter::AtLeast(bytes)) => fd_args.push(format!("--size=+{}b", bytes)),
+            Some(SizeFilter::Below(bytes)) => fd_args.push(format!("--size=-{}b", bytes)),
+            None => {}
+        }
+        
+        if !query.text.is_empty() {
+            fd_args.push(query.text.clone());
+        }
+
+        for dir in &self.search_dirs {
+            if !dir.exists() {
+                continue;
+            }
+
+            let output = Command::new("fd")
+                .args(&fd_args)
+                .current_dir(dir)
+                .output();
+
+            if let Ok(output) = output {
+                if output.status.success() {
+                    let stdout = String::from_utf8_lossy(&output.stdout);
+                    for line in stdout.lines().take(max_results - results.len()) {
+                        let path = if line.starts_with('/') {
+                            PathBuf::from(line)
+                        } else {
+                            dir.join(line)
+                        };
+                        
+                        if let Some(result) = path_to_result(&path) {
+                            results.push(result);
+                        }
+                    }
+                }
+            }
+
+            if results.len() >= max_results {
+                break;
+            }
+        }
+
+        results.truncate(max_results);
+        results
+    }
+
+    /// Search using find (fallback, slower)
+    fn search_find(&self, query: &FileQuery, max_results: usize) -> Vec<FileResult> {
+        // Only the first dir: the defaults nest inside it and find would repeat hits
+        let root = match self.search_dirs.first() {
+            Some(dir) => dir.to_string_lossy().to_string(),
+            None => return Vec::new(),
+        };
+        
+        let mut find_args = vec![
+            root,
+            "-maxdepth".to_string(), self.max_depth.to_string(),
+        ];
+        
+        if query.folders_only {
+            find_args.extend(["-type".to_string(), "d".to_string()]);
+        } else if !query.extensions.is_empty() {
+            find_args.extend(["-type".to_string(), "f".to_string(), "(".to_string()]);
+            for (i, ext) in query.extensions.iter().enumerate() {
+                if i > 0 {
+                    find_args.push("-o".to_string());
+                }
+                find_args.extend(["-iname".to_string(), format!("*.{}", ext)]);
+            }
+            find_args.push(")".to_string());
+        }
+        if let Some(after) = &query.modified_after {
+            find_args.extend(["-newermt".to_string(), local_time(after)]);
+        }
+        if let Some(before) = &query.modified_before {
+            find_args.extend(["!".to_string(), "-newermt".to_string(), local_time(before)]);
+        }
+        // `c` counts bytes; find's `+`/`-` are strictly greater/less
+        match query.size {
+            Some(SizeFilter::AtLeast(bytes)) => {
+                find_args.extend(["-size".to_string(), format!("+{}c", bytes.saturating_sub(1))]);
+            }
+            Some(SizeFilter::Below(bytes)) => find_args.extend(["-size".to_string(), format!("-{}c", bytes)]),
+            None => {}
+        }
+        
+        find_args.extend(["-iname".to_string(), format!("*{}*", query.text), "-print".to_string()]);
+
+        let output = Command::new("find")
+            .args(&find_args)
+            .output();
+
+        let mut results = Vec::new();
+
+        if let Ok(output) = output {
+            if output.status.success() {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                for line in stdout.lines().take(max_results) {
+                    let path = PathBuf::from(line);
+                    if let Some(result) = path_to_result(&path) {
+                        results.push(result);
+                    }
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Open file with default application
+    pub fn open(&self, path: &str) -> Result<(), String> {
+        // Use xdg-open on Linux
+        Command::new("xdg-open")
+            .arg(path)
+            .spawn()
+            .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+        Ok(())
+    }
+
+    /// Open file's containing folder
+    pub fn reveal(&self, path: &str) -> Result<(), String> {
+        let path = PathBuf::from(path);
+        let folder = path.parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string_lossy().to_string());
+        
+        Command::new("xdg-open")
+            .arg(&folder)
+            .spawn()
+            .map_err(|e| format!("Failed to open folder: {}", e))?;
+        Ok(())
+    }
+}
+
+/// A path as a search result
+fn path_to_result(path: &Path) -> Option<FileResult> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+    let is_dir = path.is_dir();
+    let extension = if is_dir {
+        None
+    } else {
+        path.extension().map(|e| e.to_string_lossy().to_string())
+    };
+
+    Some(FileResult {
+        name,
+        path: path.to_string_lossy().to_string(),
+        is_dir,
+        extension,
+    })
+}
+
+/// The children of `dir` whose names fuzzy-match `filter`: best match first,
+/// or folders first and then by name with no filter. Hidden entries only
+/// show once the filter starts with a dot.
+pub fn list_dir(dir: &Path, filter: &str) -> Result<Vec<FileResult>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("Can't open {}: {}", dir.display(), e))?;
+    let query = filter.trim().to_lowercase();
+    let mut scored: Vec<(i32, FileResult)> = entries
+        .filter_map(|entry| path_to_result(&entry.ok()?.path()))
+        .filter(|file| query.starts_with('.') || !file.name.starts_with('.'))
+        .filter_map(|file| match query.as_str() {
+            "" => Some((0, file)),
+            query => Some((fuzzy::score(&file.name, query), file)).filter(|(score, _)| *score > 0),
+        })
+        .collect();
+    scored.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then(b.is_dir.cmp(&a.is_dir))
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    Ok(scored.into_iter().map(|(_, file)| file).collect())
+}
+
+/// A time as fd and find take it: local wall-clock time
+fn local_time(time: &Zoned) -> String {
+    time.timestamp()
+        .to_zoned(jiff::tz::TimeZone::system())
+        .strftime("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+impl Default for FileSearcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_search() {
+        let searcher = FileSearcher::new();
+        println!("Using fd: {}", searcher.use_fd);
+        
+        let results = searcher.search("rust", 10, false);
+        for r in &results {
+            println!("{}: {}", if r.is_dir { "DIR" } else { "FILE" }, r.path);
+        }
+    }
+
+    #[test]
+    fn test_list_dir() {
+        let root = std::env::temp_dir().join(format!("ruty-browse-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("Projects")).unwrap();
+        for name in ["notes.txt", "photo.png", ".hidden"] {
+            std::fs::write(root.join(name), "").unwrap();
+        }
+        let names = |filter: &str| -> Vec<String> {
+            list_dir(&root, filter).unwrap().into_iter().map(|file| file.name).collect()
+        };
+        assert_eq!(names(""), ["Projects", "notes.txt", "photo.png"]);
+        assert_eq!(names("pho"), ["photo.png"]);
+        assert_eq!(names("nt"), ["notes.txt"]);
+        assert_eq!(names(".h"), [".hidden"]);
+        assert!(list_dir(&root.join("missing"), "").is_err());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,217 @@
+//! Workflows
+//!
+//! `[flows.<name>]` in config.toml names a sequence of steps that `/flow
+//! <name>` (or `ruty flow run <name>`) runs in order, stopping at the first
+//! that fails:
+//!
+//! ```toml
+//! [flows.standup]
+//! description = "Join standup with the notes template copied"
+//! steps = [
+//!     { launch = "zoom" },
+//!     { copy = "Yesterday:\nToday:\nBlockers:\n" },
+//! ]
+//! ```
+//!
+//! Steps:
+//! - `{ query = "text" }` searches apps and files; its output is the titles
+//! - `"pick-first"` picks the first result of the last query; its output is
+//!   the result's id (the path, for files)
+//! - `{ launch = "app" }` launches the best matching app; bare `"launch"`
+//!   opens the picked result
+//! - `{ copy = "text" }` copies text; bare `"copy"` copies the last output
+//! - `{ shell = "command" }` runs `sh -c`; its output is what it prints
+//! - `{ ai-prompt = "text" }` asks the AI; its output is the reply
+//!
+//! `{output}` in a step's text is replaced with the previous step's output,
+//! except in shell commands, which get it as `$RUTY_OUTPUT` so it's never
+//! parsed as shell. Shell commands run in `[sandbox]`, or the flow's own
+//! `sandbox` table (see `sandbox`).
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::sandbox::Sandbox;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Flow {
+    /// Shown next to the name in the `/flow` picker
+    pub description: String,
+    pub steps: Vec<Step>,
+    /// Sandbox for its shell steps instead of `[sandbox]`
+    pub sandbox: Option<Sandbox>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawStep", into = "RawStep")]
+pub enum Step {
+    Query(String),
+    PickFirst,
+    /// App to launch, or the picked result if `None`
+    Launch(Option<String>),
+    /// Text to copy, or the last output if `None`
+    Copy(Option<String>),
+    Shell(String),
+    AiPrompt(String),
+}
+
+/// A step as written: a bare name, or a one-entry table of name and text
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RawStep {
+    Bare(String),
+    Table(BTreeMap<String, String>),
+}
+
+impl TryFrom<RawStep> for Step {
+    type Error = String;
+
+    fn try_from(raw: RawStep) -> Result<Self, String> {
+        match raw {
+            RawStep::Bare(name) => match name.as_str() {
+                "pick-first" => Ok(Self::PickFirst),
+                "launch" => Ok(Self::Launch(None)),
+                "copy" => Ok(Self::Copy(None)),
+                "query" | "shell" | "ai-prompt" => Err(format!("step '{}' needs text: {{ {} = \"...\" }}", name, name)),
+                _ => Err(format!("unknown step '{}'", name)),
+            },
+            RawStep::Table(table) => {
+                let mut entries = table.into_iter();
+                let (Some((name, text)), None) = (entries.next(), entries.next()) else {
+                    return Err("each step is one of query, pick-first, launch, copy, shell or ai-prompt".to_string());
+                };
+                match name.as_str() {
+                    "query" => Ok(Self::Query(text)),
+                    "launch" => Ok(Self::Launch(Some(text))),
+                    "copy" => Ok(Self::Copy(Some(text))),
+                    "shell" => Ok(Self::Shell(text)),
+                    "ai-prompt" => Ok(Self::AiPrompt(text)),
+                    "pick-first" => Err("write pick-first as a plain \"pick-first\" step".to_string()),
+                    _ => Err(format!("unknown step '{}'", name)),
+                }
+            }
+        }
+    }
+}
+
+impl From<Step> for RawStep {
+    fn from(step: Step) -> Self {
+        let table = |name: &str, text: String| Self::Table(BTreeMap::from([(name.to_string(), text)]));
+        match step {
+            Step::Query(text) => table("query", text),
+            Step::PickFirst => Self::Bare("pick-first".to_string()),
+            Step::Launch(Some(app)) => table("launch", app),
+            Step::Launch(None) => Self::Bare("launch".to_string()),
+            Step::Copy(Some(text)) => table("copy", text),
+            Step::Copy(None) => Self::Bare("copy".to_string()),
+            Step::Shell(command) => table("shell", command),
+            Step::AiPrompt(prompt) => table("ai-prompt", prompt),
+        }
+    }
+}
+
+impl Flow {
+    /// Whether any step runs a shell command
+    pub fn runs_shell(&self) -> bool {
+        self.steps.iter().any(|step| matches!(step, Step::Shell(_)))
+    }
+
+    /// The sandbox its shell steps run in: its own, else `default`
+    pub fn sandbox<'a>(&'a self, default: &'a Sandbox) -> &'a Sandbox {
+        self.sandbox.as_ref().unwrap_or(default)
+    }
+}
+
+impl Step {
+    /// Short description for progress and errors, e.g. "launch zoom"
+    pub fn describe(&self) -> String {
+        let first_line = |text: &str| text.lines().next().unwrap_or("").to_string();
+        match self {
+            Self::Query(query) => format!("query {}", query),
+            Self::PickFirst => "pick-first".to_string(),
+            Self::Launch(Some(app)) => format!("launch {}", app),
+            Self::Launch(None) => "launch the picked result".to_string(),
+            Self::Copy(Some(text)) => format!("copy {}", first_line(text)),
+            Self::Copy(None) => "copy the output".to_string(),
+            Self::Shell(command) => format!("shell {}", first_line(command)),
+            Self::AiPrompt(prompt) => format!("ai-prompt {}", first_line(prompt)),
+        }
+    }
+
+    /// Whether the step's output is worth showing when it's the last one
+    pub fn shows_output(&self) -> bool {
+        matches!(self, Self::Query(_) | Self::Shell(_) | Self::AiPrompt(_))
+    }
+}
+
+/// Replace `{output}` in a step's text with the previous step's output
+pub fn fill(text: &str, output: &str) -> String {
+    text.replace("{output}", output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Flows {
+        flows: BTreeMap<String, Flow>,
+    }
+
+    #[test]
+    fn test_parse_steps() {
+        let parsed: Flows = toml::from_str(
+            r#"
+            [flows.standup]
+            description = "Join standup"
+            steps = [
+                { launch = "zoom" },
+                { query = "notes.md" },
+                "pick-first",
+                "launch",
+                { shell = "date +%F" },
+                { ai-prompt = "Agenda for {output}" },
+                "copy",
+            ]
+            sandbox = { enabled = true, network = true }
+            "#,
+        )
+        .unwrap();
+        let flow = &parsed.flows["standup"];
+        assert_eq!(flow.description, "Join standup");
+        assert_eq!(
+            flow.steps,
+            vec![
+                Step::Launch(Some("zoom".into())),
+                Step::Query("notes.md".into()),
+                Step::PickFirst,
+                Step::Launch(None),
+                Step::Shell("date +%F".into()),
+                Step::AiPrompt("Agenda for {output}".into()),
+                Step::Copy(None),
+            ]
+        );
+
+        assert!(flow.runs_shell());
+        let default = Sandbox::default();
+        let own = flow.sandbox(&default);
+        assert!(own.enabled && own.network && own.env.contains(&"PATH".to_string()));
+
+        // Round-trips through config.save()
+        let saved = toml::to_string(&flow).unwrap();
+        assert_eq!(&toml::from_str::<Flow>(&saved).unwrap(), flow);
+
+        for bad in [r#"steps = ["teleport"]"#, r#"steps = ["shell"]"#, r#"steps = [{ copy = "a", shell = "b" }]"#] {
+            assert!(toml::from_str::<Flow>(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(fill("Summarize {output}", "notes"), "Summarize notes");
+        assert_eq!(fill("no placeholder", "notes"), "no placeholder");
+        assert_eq!(Step::Copy(Some("Yesterday:\nToday:".into())).describe(), "copy Yesterday:");
+    }
+}
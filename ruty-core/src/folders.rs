@@ -0,0 +1,195 @@
+//! Opening a folder in a terminal or an editor
+//!
+//! "Open terminal here" starts `[folders] terminal` (or `$TERMINAL`, or the
+//! first known terminal installed) in the folder. Terminals that keep one
+//! server process ignore the working directory they're started in, so the
+//! known ones also get their own directory flag.
+//!
+//! "Open in editor" runs `[folders] editor` (`[projects] editor` when
+//! empty) with the folder as its argument, unless `[folders]
+//! editor_commands` says how that editor opens a folder: `{dir}` is the
+//! folder and `{terminal}` a terminal started in it, which is how terminal
+//! editors get a window.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::config::Config;
+use crate::doctor;
+
+/// Terminals tried in this order when none is configured
+pub const TERMINALS: &[&str] = &[
+    "kgx",
+    "gnome-terminal",
+    "konsole",
+    "xfce4-terminal",
+    "kitty",
+    "alacritty",
+    "wezterm",
+    "foot",
+    "xterm",
+];
+
+/// How terminal editors open a folder when `editor_commands` doesn't say
+pub fn default_editor_commands() -> BTreeMap<String, String> {
+    [("nvim", "{terminal} nvim ."), ("vim", "{terminal} vim ."), ("hx", "{terminal} hx .")]
+        .into_iter()
+        .map(|(editor, command)| (editor.to_string(), command.to_string()))
+        .collect()
+}
+
+/// The terminal to use: the configured one, `$TERMINAL`, or the first of
+/// `TERMINALS` that `has` finds
+pub fn pick_terminal(configured: &str, env: Option<String>, has: impl Fn(&str) -> bool) -> Option<String> {
+    if !configured.trim().is_empty() {
+        return Some(configured.trim().to_string());
+    }
+    env.filter(|terminal| !terminal.trim().is_empty())
+        .or_else(|| TERMINALS.iter().find(|name| has(name)).map(|name| name.to_string()))
+}
+
+/// Arguments that start `terminal` (a command line) in `dir`, running `run`
+/// if it isn't empty
+pub fn terminal_command(terminal: &str, dir: &Path, run: &[String]) -> Vec<String> {
+    let mut args: Vec<String> = terminal.split_whitespace().map(String::from).collect();
+    let Some(program) = args.first().cloned() else {
+        return Vec::new();
+    };
+    let dir = dir.display().to_string();
+    let program = program.rsplit('/').next().unwrap_or(&program);
+    let (dir_args, exec): (Vec<String>, &[&str]) = match program {
+        "gnome-terminal" | "kgx" => (vec![format!("--working-directory={}", dir)], &["--"]),
+        "xfce4-terminal" => (vec![format!("--working-directory={}", dir)], &["-x"]),
+        "konsole" => (vec!["--workdir".into(), dir], &["-e"]),
+        "kitty" => (vec!["--directory".into(), dir], &[]),
+        "alacritty" => (vec!["--working-directory".into(), dir], &["-e"]),
+        "wezterm" => (vec!["start".into(), "--cwd".into(), dir], &["--"]),
+        "foot" => (vec![format!("--working-directory={}", dir)], &[]),
+        // Anything else starts in the working directory it's given
+        _ => (Vec::new(), &["-e"]),
+    };
+    args.extend(dir_args);
+    if !run.is_empty() {
+        args.extend(exec.iter().map(|arg| arg.to_string()));
+        args.extend(run.iter().cloned());
+    }
+    args
+}
+
+/// Arguments that open `dir` in `editor` (a command line), through its
+/// entry in `commands` if it has one
+pub fn editor_command(editor: &str, commands: &BTreeMap<String, String>, dir: &Path, terminal: Option<&str>) -> Vec<String> {
+    let mut parts = editor.split_whitespace();
+    let Some(program) = parts.next() else {
+        return Vec::new();
+    };
+    let name = program.rsplit('/').next().unwrap_or(program);
+    let Some(template) = commands.get(name) else {
+        let mut args: Vec<String> = editor.split_whitespace().map(String::from).collect();
+        args.push(dir.display().to_string());
+        return args;
+    };
+    let mut args = Vec::new();
+    let mut tokens = template.split_whitespace();
+    while let Some(token) = tokens.next() {
+        if token == "{terminal}" {
+            // The terminal runs the rest of the template
+            let run: Vec<String> = tokens.by_ref().map(|token| token.replace("{dir}", &dir.display().to_string())).collect();
+            return match terminal {
+                Some(terminal) => {
+                    args.extend(terminal_command(terminal, dir, &run));
+                    args
+                }
+                None => Vec::new(),
+            };
+        }
+        args.push(token.replace("{dir}", &dir.display().to_string()));
+    }
+    args
+}
+
+/// Start a terminal in `dir`
+pub fn open_terminal(config: &Config, dir: &Path) -> Result<(), String> {
+    let terminal = terminal(config)?;
+    spawn(&terminal_command(&terminal, dir, &[]), dir)
+}
+
+/// Open `dir` in the configured editor
+pub fn open_editor(config: &Config, dir: &Path) -> Result<(), String> {
+    let editor = match config.folders.editor.trim() {
+        "" => config.projects.editor.as_str(),
+        editor => editor,
+    };
+    if editor.trim().is_empty() {
+        return Err("No editor configured; set [folders] editor in config.toml".to_string());
+    }
+    let mut commands = default_editor_commands();
+    commands.extend(config.folders.editor_commands.clone());
+    let terminal = terminal(config).ok();
+    let args = editor_command(editor, &commands, dir, terminal.as_deref());
+    if args.is_empty() {
+        return Err(format!("{} needs a terminal; set [folders] terminal in config.toml", editor));
+    }
+    spawn(&args, dir)
+}
+
+fn terminal(config: &Config) -> Result<String, String> {
+    pick_terminal(&config.folders.terminal, std::env::var("TERMINAL").ok(), doctor::has_program)
+        .ok_or_else(|| "No terminal found; set [folders] terminal in config.toml".to_string())
+}
+
+/// Run `args` in `dir`, without waiting
+fn spawn(args: &[String], dir: &Path) -> Result<(), String> {
+    let (program, rest) = args.split_first().ok_or("Nothing to run")?;
+    Command::new(program)
+        .args(rest)
+        .current_dir(dir)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to start {}: {}", program, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_terminal_command() {
+        let dir = Path::new("/home/me/My Projects");
+        assert_eq!(
+            terminal_command("gnome-terminal", dir, &[]),
+            strings(&["gnome-terminal", "--working-directory=/home/me/My Projects"])
+        );
+        assert_eq!(
+            terminal_command("/usr/bin/konsole", dir, &strings(&["vim", "."])),
+            strings(&["/usr/bin/konsole", "--workdir", "/home/me/My Projects", "-e", "vim", "."])
+        );
+        assert_eq!(terminal_command("st -f mono", dir, &[]), strings(&["st", "-f", "mono"]));
+        assert!(terminal_command("  ", dir, &[]).is_empty());
+
+        assert_eq!(pick_terminal("", None, |name| name == "kitty").as_deref(), Some("kitty"));
+        assert_eq!(pick_terminal("", Some("foot".into()), |_| true).as_deref(), Some("foot"));
+        assert_eq!(pick_terminal("alacritty", Some("foot".into()), |_| true).as_deref(), Some("alacritty"));
+        assert_eq!(pick_terminal("", None, |_| false), None);
+    }
+
+    #[test]
+    fn test_editor_command() {
+        let dir = Path::new("/src/ruty");
+        let mut commands = default_editor_commands();
+        commands.insert("subl".into(), "subl --new-window {dir}".into());
+        assert_eq!(editor_command("code", &commands, dir, None), strings(&["code", "/src/ruty"]));
+        assert_eq!(editor_command("subl", &commands, dir, None), strings(&["subl", "--new-window", "/src/ruty"]));
+        assert_eq!(
+            editor_command("nvim", &commands, dir, Some("kitty")),
+            strings(&["kitty", "--directory", "/src/ruty", "nvim", "."])
+        );
+        // A terminal editor can't open without a terminal
+        assert!(editor_command("nvim", &commands, dir, None).is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+//! Font selection
+//!
+//! The UI font is `[fonts] family`, or the desktop's sans-serif as
+//! fontconfig resolves it. Characters it lacks (CJK, emoji) fall back along
+//! a chain: the `[fonts] fallback` families, then well-known CJK and emoji
+//! families, whichever of them are installed. The frontend decides how to
+//! apply the chain; this module only works out what it is.
+
+use std::process::Command;
+
+use crate::config::FontsConfig;
+
+/// CJK families, most complete first
+pub const CJK_FALLBACK: &[&str] = &[
+    "Noto Sans CJK SC",
+    "Noto Sans CJK JP",
+    "Source Han Sans SC",
+    "Source Han Sans",
+    "Sarasa Gothic SC",
+    "WenQuanYi Micro Hei",
+    "WenQuanYi Zen Hei",
+    "Droid Sans Fallback",
+];
+
+/// Emoji families, color ones first
+pub const EMOJI_FALLBACK: &[&str] = &["Noto Color Emoji", "Twemoji", "JoyPixels", "Apple Color Emoji", "Symbola"];
+
+/// The desktop's family for a generic name like `sans-serif` or
+/// `monospace`, if fontconfig is around
+pub fn system_family(generic: &str) -> Option<String> {
+    let output = Command::new("fc-match").args(["-f", "%{family}", generic]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_fc_family(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// First family of an `fc-match -f %{family}` answer, which lists the
+/// family's names in several languages, comma separated
+fn parse_fc_family(output: &str) -> Option<String> {
+    let family = output.split(',').next()?.trim();
+    (!family.is_empty()).then(|| family.to_string())
+}
+
+/// Families to try for text, in order: `primary`, the configured fallbacks,
+/// then the built-in CJK and emoji ones (each family once)
+pub fn chain(config: &FontsConfig, primary: &str) -> Vec<String> {
+    let mut chain: Vec<String> = Vec::new();
+    let families = std::iter::once(primary)
+        .chain(config.fallback.iter().map(String::as_str))
+        .chain(CJK_FALLBACK.iter().copied())
+        .chain(EMOJI_FALLBACK.iter().copied());
+    for family in families.map(str::trim).filter(|f| !f.is_empty()) {
+        if !chain.iter().any(|known| known.eq_ignore_ascii_case(family)) {
+            chain.push(family.to_string());
+        }
+    }
+    chain
+}
+
+/// Kinds of text no installed font covers ("CJK", "emoji"), going by the
+/// built-in fallback families; `installed` tells whether a family is
+pub fn missing_coverage(installed: impl Fn(&str) -> bool) -> Vec<&'static str> {
+    [("CJK", CJK_FALLBACK), ("emoji", EMOJI_FALLBACK)]
+        .into_iter()
+        .filter(|(_, families)| !families.iter().any(|family| installed(family)))
+        .map(|(kind, _)| kind)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain() {
+        let config = FontsConfig {
+            fallback: vec!["Twemoji".into(), "inter".into(), " ".into()],
+            ..Default::default()
+        };
+        let chain = chain(&config, "Inter");
+        assert_eq!(chain[..3], ["Inter", "Twemoji", "Noto Sans CJK SC"]);
+        assert_eq!(chain.iter().filter(|f| f.eq_ignore_ascii_case("twemoji")).count(), 1);
+        assert_eq!(chain.last().unwrap(), "Symbola");
+    }
+
+    #[test]
+    fn test_parse_fc_family() {
+        assert_eq!(parse_fc_family("DejaVu Sans"), Some("DejaVu Sans".to_string()));
+        assert_eq!(parse_fc_family("Noto Sans CJK JP,Noto Sans CJK JP Regular"), Some("Noto Sans CJK JP".to_string()));
+        assert_eq!(parse_fc_family(""), None);
+    }
+
+    #[test]
+    fn test_missing_coverage() {
+        assert_eq!(missing_coverage(|family| family == "Twemoji"), vec!["CJK"]);
+        assert!(missing_coverage(|family| family.starts_with("Noto")).is_empty());
+    }
+}
@@ -0,0 +1,124 @@
+//! Fuzzy string matching
+//!
+//! Tiered scoring shared by the app indexer and file searcher:
+//! exact > prefix > substring > subsequence.
+
+/// Score for an exact (case-insensitive) match
+pub const EXACT: i32 = 1000;
+/// Base score for a prefix match (shorter candidates get a bonus)
+pub const PREFIX: i32 = 500;
+/// Score for a substring match
+pub const CONTAINS: i32 = 200;
+/// Score for a subsequence match ("ffx" in "firefox")
+pub const SUBSEQUENCE: i32 = 10;
+/// Lead over the runner-up that makes a strong match the clear best one
+pub const CLEAR_LEAD: i32 = 100;
+
+/// Score `candidate` against a lowercased `query`. Returns 0 for no match.
+pub fn score(candidate: &str, query: &str) -> i32 {
+    if query.is_empty() {
+        return 0;
+    }
+
+    let candidate = candidate.to_lowercase();
+
+    if candidate == query {
+        return EXACT;
+    }
+
+    if candidate.starts_with(query) {
+        return PREFIX + (100 - candidate.len() as i32).max(0);
+    }
+
+    if candidate.contains(query) {
+        return CONTAINS;
+    }
+
+    if is_subsequence(&candidate, query) {
+        return SUBSEQUENCE;
+    }
+
+    0
+}
+
+/// Whether the top score is confidently the best match: the only match at
+/// substring level or better, or a prefix/exact match well ahead of the next.
+/// Two prefix matches only differ by length, so they never qualify.
+pub fn is_clear_winner(best: i32, runner_up: Option<i32>) -> bool {
+    match runner_up {
+        None => best >= CONTAINS,
+        Some(next) => best >= PREFIX && best - next >= CLEAR_LEAD,
+    }
+}
+
+/// Check whether all chars of `query` appear in `candidate` in order
+pub fn is_subsequence(candidate: &str, query: &str) -> bool {
+    let mut chars = candidate.chars();
+    query.chars().all(|q| chars.any(|c| c == q))
+}
+
+/// Char positions in `candidate` that a lowercased `query` matched, for
+/// highlighting: the first occurrence for prefix/substring matches, the
+/// leftmost chars for a subsequence match. Empty when it doesn't match.
+pub fn matched_chars(candidate: &str, query: &str) -> Vec<usize> {
+    let query: Vec<char> = query.chars().collect();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    // Lowercase char by char so positions line up with `candidate`
+    let lower: Vec<char> = candidate
+        .chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect();
+
+    if let Some(start) = lower.windows(query.len()).position(|window| window == query.as_slice()) {
+        return (start..start + query.len()).collect();
+    }
+
+    let mut positions = Vec::with_capacity(query.len());
+    let mut chars = lower.iter().enumerate();
+    for q in &query {
+        match chars.find(|(_, c)| *c == q) {
+            Some((i, _)) => positions.push(i),
+            None => return Vec::new(),
+        }
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiers() {
+        assert_eq!(score("Firefox", "firefox"), EXACT);
+        assert!(score("Firefox", "fire") > CONTAINS);
+        assert_eq!(score("LibreOffice Writer", "writer"), CONTAINS);
+        assert_eq!(score("Firefox", "ffx"), SUBSEQUENCE);
+        assert_eq!(score("Firefox", "xyz"), 0);
+    }
+
+    #[test]
+    fn test_shorter_prefix_ranks_higher() {
+        assert!(score("Code", "co") > score("Color Picker", "co"));
+    }
+
+    #[test]
+    fn test_clear_winner() {
+        assert!(is_clear_winner(score("Firefox", "firefox"), Some(score("Firefox Nightly", "firefox"))));
+        assert!(is_clear_winner(score("Firefox", "fire"), Some(score("Campfire", "fire"))));
+        assert!(!is_clear_winner(score("Code", "co"), Some(score("Color Picker", "co"))));
+        assert!(is_clear_winner(CONTAINS, None));
+        assert!(!is_clear_winner(SUBSEQUENCE, None));
+    }
+
+    #[test]
+    fn test_matched_chars() {
+        assert_eq!(matched_chars("LibreOffice Writer", "writer"), (12..18).collect::<Vec<_>>());
+        assert_eq!(matched_chars("Firefox", "ffx"), vec![0, 4, 6]);
+        assert_eq!(matched_chars("ÜNÏcode", "ünï"), vec![0, 1, 2]);
+        assert!(matched_chars("Firefox", "xyz").is_empty());
+        assert!(matched_chars("Firefox", "").is_empty());
+    }
+}
@@ -0,0 +1,340 @@
+//! Launcher window geometry
+//!
+//! The window's size and anchor (centered, or near the top like Alfred and
+//! Spotlight) are kept per monitor, so a script can give the launcher a
+//! different shape on a laptop panel and on an external display. The monitor
+//! it opens on is the one last asked for over RPC while that's connected,
+//! and the focused one otherwise.
+//!
+//! Monitor layouts come from the compositor (`hyprctl monitors -j`, `swaymsg
+//! -t get_outputs`) or `xrandr` on X11; the parsers for each live here.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::paths;
+
+/// Window size when nothing is saved
+pub const DEFAULT_WIDTH: u32 = 700;
+pub const DEFAULT_HEIGHT: u32 = 400;
+
+/// Smallest size accepted, so a typo can't make the window vanish
+pub const MIN_WIDTH: u32 = 200;
+pub const MIN_HEIGHT: u32 = 100;
+
+/// Gap above a top-anchored window, as a fraction of the monitor height
+const TOP_OFFSET: f64 = 0.2;
+
+/// Key under which geometry is saved when monitors can't be told apart
+const ANY_MONITOR: &str = "*";
+
+/// Where on the monitor the window sits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Anchor {
+    #[default]
+    Center,
+    /// Horizontally centered, a fifth of the way down
+    Top,
+}
+
+impl Anchor {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "center" | "centre" => Some(Self::Center),
+            "top" => Some(Self::Top),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Center => "center",
+            Self::Top => "top",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Geometry {
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub anchor: Anchor,
+}
+
+impl Default for Geometry {
+    fn default() -> Self {
+        Self { width: DEFAULT_WIDTH, height: DEFAULT_HEIGHT, anchor: Anchor::Center }
+    }
+}
+
+impl Geometry {
+    /// Top-left corner of the window on `monitor`, in global coordinates
+    pub fn position(&self, monitor: &Monitor) -> (i32, i32) {
+        let x = monitor.x + (monitor.width as i32 - self.width as i32) / 2;
+        let y = match self.anchor {
+            Anchor::Center => monitor.y + (monitor.height as i32 - self.height as i32) / 2,
+            Anchor::Top => monitor.y + (monitor.height as f64 * TOP_OFFSET) as i32,
+        };
+        (x.max(monitor.x), y.max(monitor.y))
+    }
+
+    /// The window's size under a UI zoom of `factor` (`[ui] scale`), so
+    /// zoomed content still fits
+    pub fn scaled(self, factor: f64) -> Self {
+        let scale = |size: u32| (size as f64 * factor).round() as u32;
+        Self { width: scale(self.width), height: scale(self.height), ..self }
+    }
+}
+
+/// A connected output, in logical pixels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monitor {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// Has keyboard focus (or is the primary output, on X11)
+    pub focused: bool,
+}
+
+/// Saved geometry per monitor, and the monitor last asked for
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Placements {
+    monitor: Option<String>,
+    geometries: BTreeMap<String, Geometry>,
+}
+
+impl Placements {
+    /// Path of the geometry file
+    pub fn path() -> PathBuf {
+        paths::state_dir().join("geometry.json")
+    }
+
+    /// Load placements from disk (defaults if missing or unreadable)
+    pub fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write placements to disk
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create state dir: {}", e))?;
+        }
+        let content = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write geometry: {}", e))
+    }
+
+    /// The monitor to open on among `monitors`: the one last asked for if
+    /// it's connected, else the focused one. `None` if monitors are unknown.
+    pub fn monitor<'a>(&self, monitors: &'a [Monitor]) -> Option<&'a Monitor> {
+        let pinned = self.monitor.as_deref().and_then(|name| monitors.iter().find(|m| m.name == name));
+        pinned.or_else(|| monitors.iter().find(|m| m.focused)).or(monitors.first())
+    }
+
+    /// Geometry saved for `monitor` (`None` when monitors are unknown)
+    pub fn geometry(&self, monitor: Option<&Monitor>) -> Geometry {
+        let key = monitor.map_or(ANY_MONITOR, |m| m.name.as_str());
+        self.geometries.get(key).copied().unwrap_or_default()
+    }
+
+    /// Save `geometry` for `monitor`; `pin` makes it the monitor to open on
+    pub fn set(&mut self, monitor: Option<&Monitor>, geometry: Geometry, pin: bool) {
+        let key = monitor.map_or(ANY_MONITOR, |m| m.name.as_str()).to_string();
+        if pin {
+            self.monitor = monitor.map(|m| m.name.clone());
+        }
+        self.geometries.insert(key, geometry);
+    }
+}
+
+#[derive(Deserialize)]
+struct HyprMonitor {
+    name: String,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    #[serde(default = "one")]
+    scale: f64,
+    #[serde(default)]
+    transform: u32,
+    #[serde(default)]
+    focused: bool,
+}
+
+fn one() -> f64 {
+    1.0
+}
+
+/// Monitors from `hyprctl monitors -j`, whose sizes are in physical pixels
+pub fn parse_hyprctl_monitors(json: &str) -> Result<Vec<Monitor>, String> {
+    let monitors: Vec<HyprMonitor> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    Ok(monitors
+        .into_iter()
+        .map(|m| {
+            let scale = if m.scale > 0.0 { m.scale } else { 1.0 };
+            let (width, height) = (m.width as f64 / scale, m.height as f64 / scale);
+            // Odd transforms rotate by 90 or 270 degrees
+            let (width, height) = if m.transform % 2 == 1 { (height, width) } else { (width, height) };
+            Monitor { name: m.name, x: m.x, y: m.y, width: width as u32, height: height as u32, focused: m.focused }
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct SwayOutput {
+    name: String,
+    #[serde(default = "yes")]
+    active: bool,
+    #[serde(default)]
+    focused: bool,
+    rect: SwayRect,
+}
+
+#[derive(Deserialize)]
+struct SwayRect {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+fn yes() -> bool {
+    true
+}
+
+/// Active outputs from `swaymsg -t get_outputs -r`
+pub fn parse_sway_outputs(json: &str) -> Result<Vec<Monitor>, String> {
+    let outputs: Vec<SwayOutput> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    Ok(outputs
+        .into_iter()
+        .filter(|o| o.active)
+        .map(|o| Monitor {
+            name: o.name,
+            x: o.rect.x,
+            y: o.rect.y,
+            width: o.rect.width,
+            height: o.rect.height,
+            focused: o.focused,
+        })
+        .collect())
+}
+
+/// Connected, enabled outputs from `xrandr --query`, e.g.
+/// `DP-1 connected primary 2560x1440+1920+0 (normal left ...) 597mm x 336mm`
+pub fn parse_xrandr(output: &str) -> Vec<Monitor> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            let name = words.next()?;
+            if words.next()? != "connected" {
+                return None;
+            }
+            let mut primary = false;
+            // Disabled outputs have no WxH+X+Y
+            let rect = words.find(|word| {
+                primary |= *word == "primary";
+                word.contains('x') && word.contains('+')
+            })?;
+            let (size, offset) = rect.split_once('+')?;
+            let (width, height) = size.split_once('x')?;
+            let (x, y) = offset.split_once('+')?;
+            Some(Monitor {
+                name: name.to_string(),
+                x: x.parse().ok()?,
+                y: y.parse().ok()?,
+                width: width.parse().ok()?,
+                height: height.parse().ok()?,
+                focused: primary,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: i32, focused: bool) -> Monitor {
+        Monitor { name: name.into(), x, y: 0, width: 1920, height: 1080, focused }
+    }
+
+    #[test]
+    fn test_position() {
+        let laptop = monitor("eDP-1", 2560, false);
+        let centered = Geometry::default();
+        assert_eq!(centered.position(&laptop), (2560 + 610, 340));
+        let top = Geometry { anchor: Anchor::Top, ..centered };
+        assert_eq!(top.position(&laptop), (2560 + 610, 216));
+        // Bigger than the monitor: pinned to its corner
+        let huge = Geometry { width: 4000, height: 2000, anchor: Anchor::Center };
+        assert_eq!(huge.position(&laptop), (2560, 0));
+        assert_eq!(top.scaled(1.5), Geometry { width: 1050, height: 600, anchor: Anchor::Top });
+        assert_eq!(Anchor::parse(" Top"), Some(Anchor::Top));
+        assert_eq!(Anchor::parse("left"), None);
+    }
+
+    #[test]
+    fn test_placements_per_monitor() {
+        let monitors = [monitor("DP-1", 0, false), monitor("eDP-1", 1920, true)];
+        let mut placements = Placements::default();
+        assert_eq!(placements.monitor(&monitors).unwrap().name, "eDP-1");
+        assert_eq!(placements.geometry(placements.monitor(&monitors)), Geometry::default());
+
+        let wide = Geometry { width: 1000, height: 500, anchor: Anchor::Top };
+        placements.set(Some(&monitors[0]), wide, true);
+        assert_eq!(placements.monitor(&monitors).unwrap().name, "DP-1");
+        assert_eq!(placements.geometry(Some(&monitors[0])), wide);
+        assert_eq!(placements.geometry(Some(&monitors[1])), Geometry::default());
+
+        // The pinned monitor unplugged: back to the focused one
+        assert_eq!(placements.monitor(&monitors[1..]).unwrap().name, "eDP-1");
+        assert_eq!(placements.monitor(&[]), None);
+
+        let saved = serde_json::to_string(&placements).unwrap();
+        assert_eq!(serde_json::from_str::<Placements>(&saved).unwrap(), placements);
+    }
+
+    #[test]
+    fn test_parse_monitors() {
+        let hyprland = r#"[
+            {"id": 0, "name": "DP-1", "width": 3840, "height": 2160, "x": 0, "y": 0, "scale": 2.0, "transform": 0, "focused": false},
+            {"id": 1, "name": "HDMI-A-1", "width": 1920, "height": 1080, "x": 1920, "y": 0, "scale": 1.0, "transform": 1, "focused": true}
+        ]"#;
+        let monitors = parse_hyprctl_monitors(hyprland).unwrap();
+        assert_eq!(monitors[0], Monitor { name: "DP-1".into(), x: 0, y: 0, width: 1920, height: 1080, focused: false });
+        assert_eq!((monitors[1].width, monitors[1].height, monitors[1].focused), (1080, 1920, true));
+
+        let sway = r#"[
+            {"name": "eDP-1", "active": true, "focused": true, "rect": {"x": 0, "y": 0, "width": 1280, "height": 800}},
+            {"name": "HDMI-A-1", "active": false, "focused": false, "rect": {"x": 0, "y": 0, "width": 0, "height": 0}}
+        ]"#;
+        assert_eq!(
+            parse_sway_outputs(sway).unwrap(),
+            vec![Monitor { name: "eDP-1".into(), x: 0, y: 0, width: 1280, height: 800, focused: true }]
+        );
+
+        let xrandr = "Screen 0: minimum 8 x 8, current 4480 x 1440, maximum 32767 x 32767\n\
+            DP-1 connected primary 2560x1440+1920+0 (normal left inverted right x axis y axis) 597mm x 336mm\n   \
+            2560x1440     59.95*+\n\
+            eDP-1 connected 1920x1080+0+180 (normal left inverted right x axis y axis) 344mm x 194mm\n\
+            HDMI-1 disconnected (normal left inverted right x axis y axis)\n\
+            DP-2 connected (normal left inverted right x axis y axis)\n";
+        let monitors = parse_xrandr(xrandr);
+        assert_eq!(monitors.len(), 2);
+        assert_eq!(monitors[0], Monitor { name: "DP-1".into(), x: 1920, y: 0, width: 2560, height: 1440, focused: true });
+        assert_eq!((monitors[1].y, monitors[1].focused), (180, false));
+    }
+}
@@ -0,0 +1,266 @@
+//! Git repositories in the project directories
+//!
+//! Finds repos under `[projects] dirs` and offers quick actions on them:
+//! open in editor, copy the remote URL, check out a recent branch and show
+//! what's changed.
+
+use git2::{BranchType, Repository, StatusOptions};
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::fuzzy;
+
+/// Recent branches offered for checkout
+const RECENT_BRANCHES: usize = 5;
+
+/// Snapshot of a repository's state
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepoInfo {
+    pub path: PathBuf,
+    pub name: String,
+    /// Current branch, `None` when HEAD is detached or unborn
+    pub branch: Option<String>,
+    /// Files with staged, unstaged or untracked changes
+    pub changed: usize,
+    pub remote_url: Option<String>,
+    /// Other local branches, most recently committed first
+    pub recent_branches: Vec<String>,
+}
+
+/// Something to do with a repository
+#[derive(Debug, Clone, PartialEq)]
+pub enum RepoAction {
+    OpenInEditor,
+    CopyRemoteUrl,
+    Checkout(String),
+    ShowStatus,
+}
+
+impl RepoAction {
+    /// Result id for this action on `repo`
+    pub fn encode(&self, repo: &Path) -> String {
+        let repo = repo.display();
+        match self {
+            Self::OpenInEditor => format!("open\t{}", repo),
+            Self::CopyRemoteUrl => format!("remote\t{}", repo),
+            Self::Checkout(branch) => format!("checkout\t{}\t{}", repo, branch),
+            Self::ShowStatus => format!("status\t{}", repo),
+        }
+    }
+
+    /// Inverse of `encode`
+    pub fn decode(id: &str) -> Option<(PathBuf, Self)> {
+        let mut parts = id.split('\t');
+        let kind = parts.next()?;
+        let repo = PathBuf::from(parts.next()?);
+        let action = match kind {
+            "open" => Self::OpenInEditor,
+            "remote" => Self::CopyRemoteUrl,
+            "checkout" => Self::Checkout(parts.next()?.to_string()),
+            "status" => Self::ShowStatus,
+            _ => return None,
+        };
+        Some((repo, action))
+    }
+}
+
+/// Find git repositories below `dirs` (repos aren't searched for nested repos)
+pub fn discover(dirs: &[PathBuf], max_depth: usize) -> Vec<PathBuf> {
+    let mut repos = Vec::new();
+    for dir in dirs {
+        walk(dir, max_depth, &mut repos);
+    }
+    repos.sort();
+    repos.dedup();
+    repos
+}
+
+fn walk(dir: &Path, depth: usize, repos: &mut Vec<PathBuf>) {
+    if dir.join(".git").exists() {
+        repos.push(dir.to_path_buf());
+        return;
+    }
+    if depth == 0 {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !hidden && entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            walk(&entry.path(), depth - 1, repos);
+        }
+    }
+}
+
+/// Repos whose directory name matches `query`, best first
+pub fn search<'a>(repos: &'a [PathBuf], query: &str) -> Vec<&'a PathBuf> {
+    let query = query.to_lowercase();
+    let mut matches: Vec<(&PathBuf, i32)> = repos
+        .iter()
+        .map(|repo| (repo, fuzzy::score(&repo_name(repo), &query)))
+        .filter(|(_, score)| *score > 0)
+        .collect();
+    matches.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    matches.into_iter().map(|(repo, _)| repo).collect()
+}
+
+/// Display name of a repo (its directory name)
+pub fn repo_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+fn open(path: &Path) -> Result<Repository, String> {
+    Repository::open(path).map_err(|e| format!("Not a git repository: {}", e.message()))
+}
+
+/// Read branch, dirty state, remote and recent branches
+pub fn inspect(path: &Path) -> Result<RepoInfo, String> {
+    let repo = open(path)?;
+
+    let branch = repo
+        .head()
+        .ok()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand().map(str::to_string));
+
+    let remote_url = repo
+        .find_remote("origin")
+        .ok()
+        .and_then(|remote| remote.url().map(str::to_string));
+
+    let mut branches: Vec<(String, i64)> = repo
+        .branches(Some(BranchType::Local))
+        .map_err(|e| e.message().to_string())?
+        .flatten()
+        .filter_map(|(branch, _)| {
+            let name = branch.name().ok()??.to_string();
+            let time = branch.get().peel_to_commit().ok()?.time().seconds();
+            Some((name, time))
+        })
+        .filter(|(name, _)| Some(name) != branch.as_ref())
+        .collect();
+    branches.sort_by_key(|(_, time)| Reverse(*time));
+
+    Ok(RepoInfo {
+        path: path.to_path_buf(),
+        name: repo_name(path),
+        branch,
+        changed: status_lines(&repo)?.len(),
+        remote_url,
+        recent_branches: branches.into_iter().take(RECENT_BRANCHES).map(|(n, _)| n).collect(),
+    })
+}
+
+/// `git status --short` style lines
+pub fn status(path: &Path) -> Result<Vec<String>, String> {
+    status_lines(&open(path)?)
+}
+
+fn status_lines(repo: &Repository) -> Result<Vec<String>, String> {
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).include_ignored(false);
+    let statuses = repo.statuses(Some(&mut options)).map_err(|e| e.message().to_string())?;
+
+    Ok(statuses
+        .iter()
+        .map(|entry| {
+            let s = entry.status();
+            let code = if s.is_wt_new() {
+                "??"
+            } else if s.is_index_new() {
+                "A "
+            } else if s.is_index_deleted() || s.is_wt_deleted() {
+                " D"
+            } else if s.is_index_renamed() || s.is_wt_renamed() {
+                " R"
+            } else if s.is_conflicted() {
+                "UU"
+            } else {
+                " M"
+            };
+            format!("{} {}", code, entry.path().unwrap_or("?"))
+        })
+        .collect())
+}
+
+/// Switch to a local branch; refuses if local changes would be overwritten
+pub fn checkout(path: &Path, branch: &str) -> Result<(), String> {
+    let repo = open(path)?;
+    let refname = format!("refs/heads/{}", branch);
+    let target = repo
+        .revparse_single(&refname)
+        .map_err(|_| format!("No branch named {}", branch))?;
+    repo.checkout_tree(&target, Some(git2::build::CheckoutBuilder::new().safe()))
+        .map_err(|e| format!("Checkout failed: {}", e.message()))?;
+    repo.set_head(&refname).map_err(|e| e.message().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ruty-git-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn commit_file(repo: &Repository, name: &str) {
+        fs::write(repo.workdir().unwrap().join(name), name).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, name, &tree, &parents).unwrap();
+    }
+
+    #[test]
+    fn test_action_roundtrip() {
+        let repo = Path::new("/home/me/Projects/ruty");
+        for action in [RepoAction::OpenInEditor, RepoAction::Checkout("feat/x".into())] {
+            assert_eq!(RepoAction::decode(&action.encode(repo)), Some((repo.to_path_buf(), action)));
+        }
+        assert_eq!(RepoAction::decode("/home/me/Projects/ruty"), None);
+    }
+
+    #[test]
+    fn test_discover_and_search() {
+        let root = temp_dir("discover");
+        Repository::init(root.join("ruty")).unwrap();
+        Repository::init(root.join("work/api-server")).unwrap();
+        fs::create_dir_all(root.join("ruty/vendor/.git")).unwrap();
+
+        let repos = discover(std::slice::from_ref(&root), 3);
+        assert_eq!(repos, vec![root.join("ruty"), root.join("work/api-server")]);
+        assert_eq!(search(&repos, "api"), vec![&root.join("work/api-server")]);
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_inspect_and_checkout() {
+        let root = temp_dir("inspect");
+        let repo = Repository::init(&root).unwrap();
+        commit_file(&repo, "a.txt");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &head, false).unwrap();
+        fs::write(root.join("untracked.txt"), "x").unwrap();
+
+        let info = inspect(&root).unwrap();
+        assert_eq!(info.changed, 1);
+        assert_eq!(info.recent_branches, vec!["feature"]);
+        assert_eq!(status(&root).unwrap(), vec!["?? untracked.txt"]);
+
+        checkout(&root, "feature").unwrap();
+        assert_eq!(inspect(&root).unwrap().branch.as_deref(), Some("feature"));
+        assert!(checkout(&root, "missing").is_err());
+        fs::remove_dir_all(&root).ok();
+    }
+}
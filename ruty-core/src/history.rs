@@ -0,0 +1,150 @@
+//! Prompt history
+//!
+//! Remembers submitted search queries and chat prompts separately so the
+//! launcher can recall them shell-style (Up/Down, Ctrl+R).
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::fuzzy;
+use crate::paths;
+
+/// Entries kept per kind
+const HISTORY_LIMIT: usize = 200;
+
+/// Which prompt an entry came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Search,
+    Chat,
+}
+
+/// Submitted prompts, oldest first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct History {
+    search: Vec<String>,
+    chat: Vec<String>,
+}
+
+impl History {
+    /// Path of the history file
+    pub fn path() -> PathBuf {
+        paths::state_dir().join("history.json")
+    }
+
+    /// Load history from disk (empty if missing or unreadable)
+    pub fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write history to disk
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create state dir: {}", e))?;
+        }
+        let content = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write history: {}", e))
+    }
+
+    /// Entries of one kind, oldest first
+    pub fn entries(&self, kind: Kind) -> &[String] {
+        match kind {
+            Kind::Search => &self.search,
+            Kind::Chat => &self.chat,
+        }
+    }
+
+    /// Entry `offset` steps back from the newest (0 = newest)
+    pub fn recall(&self, kind: Kind, offset: usize) -> Option<&str> {
+        let entries = self.entries(kind);
+        entries.len().checked_sub(offset + 1).map(|i| entries[i].as_str())
+    }
+
+    /// Record a submitted prompt; repeats move to the front
+    pub fn push(&mut self, kind: Kind, entry: &str) {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            return;
+        }
+        let entries = match kind {
+            Kind::Search => &mut self.search,
+            Kind::Chat => &mut self.chat,
+        };
+        entries.retain(|e| e != entry);
+        entries.push(entry.to_string());
+        if entries.len() > HISTORY_LIMIT {
+            let excess = entries.len() - HISTORY_LIMIT;
+            entries.drain(..excess);
+        }
+    }
+
+    /// Fuzzy search across all kinds, best match first (newest on ties).
+    /// An empty query lists everything newest first.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&str> {
+        let query = query.to_lowercase();
+        let mut matches: Vec<(&str, i32, usize)> = Vec::new();
+
+        for entries in [&self.search, &self.chat] {
+            for (age, entry) in entries.iter().rev().enumerate() {
+                if matches.iter().any(|(e, _, _)| *e == entry.as_str()) {
+                    continue;
+                }
+                let score = if query.is_empty() { 1 } else { fuzzy::score(entry, &query) };
+                if score > 0 {
+                    matches.push((entry, score, age));
+                }
+            }
+        }
+
+        matches.sort_by(|a, b| b.1.cmp(&a.1).then(a.2.cmp(&b.2)));
+        matches.into_iter().take(limit).map(|(entry, _, _)| entry).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recall_and_dedupe() {
+        let mut history = History::default();
+        history.push(Kind::Search, "/app firefox");
+        history.push(Kind::Search, "/app code");
+        history.push(Kind::Search, "/app firefox");
+        history.push(Kind::Chat, "hello");
+        history.push(Kind::Chat, "  ");
+
+        assert_eq!(history.recall(Kind::Search, 0), Some("/app firefox"));
+        assert_eq!(history.recall(Kind::Search, 1), Some("/app code"));
+        assert_eq!(history.recall(Kind::Search, 2), None);
+        assert_eq!(history.entries(Kind::Chat), ["hello"]);
+    }
+
+    #[test]
+    fn test_limit() {
+        let mut history = History::default();
+        for i in 0..HISTORY_LIMIT + 5 {
+            history.push(Kind::Chat, &i.to_string());
+        }
+        assert_eq!(history.entries(Kind::Chat).len(), HISTORY_LIMIT);
+        assert_eq!(history.entries(Kind::Chat)[0], "5");
+    }
+
+    #[test]
+    fn test_search() {
+        let mut history = History::default();
+        history.push(Kind::Chat, "what is rust");
+        history.push(Kind::Search, "/app rustrover");
+        history.push(Kind::Chat, "rust lifetimes");
+
+        assert_eq!(history.search("rust", 10), vec!["rust lifetimes", "/app rustrover", "what is rust"]);
+        assert_eq!(history.search("rlt", 10), vec!["rust lifetimes"]);
+        assert_eq!(history.search("", 2).len(), 2);
+    }
+}
@@ -0,0 +1,273 @@
+//! Approximate nearest neighbour search over unit vectors
+//!
+//! A hierarchical navigable small world graph (Malkov & Yashunin): every
+//! vector is a node on layer 0, and on each layer above that with shrinking
+//! probability. A search walks greedily from the single node on the top
+//! layer down to layer 0, where it explores the `ef` best nodes it has seen,
+//! so it looks at a few hundred vectors instead of all of them. Similarity is
+//! the dot product, which is cosine similarity for the unit vectors the
+//! embedding model gives.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+
+/// Neighbours kept per node on the upper layers
+const M: usize = 16;
+
+/// Neighbours kept per node on layer 0, which carries the most nodes
+const M0: usize = 2 * M;
+
+/// Candidates considered when linking a new node
+const EF_CONSTRUCTION: usize = 100;
+
+/// A node id with its similarity to the vector being searched for, ordered
+/// by similarity
+#[derive(Debug, Clone, Copy)]
+struct Scored(f32, usize);
+
+impl PartialEq for Scored {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Hnsw {
+    vectors: Vec<Vec<f32>>,
+    /// Neighbours of each node on each of its layers, layer 0 first
+    links: Vec<Vec<Vec<usize>>>,
+    /// The node searches start from, on the top layer
+    entry: Option<usize>,
+    top: usize,
+    /// xorshift state for picking layers
+    seed: u64,
+}
+
+impl Default for Hnsw {
+    fn default() -> Self {
+        Self { vectors: Vec::new(), links: Vec::new(), entry: None, top: 0, seed: 0x2545_f491_4f6c_dd1d }
+    }
+}
+
+impl Hnsw {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vectors.is_empty()
+    }
+
+    /// Add a vector; returns its id, which counts up from 0
+    pub fn insert(&mut self, vector: Vec<f32>) -> usize {
+        let id = self.vectors.len();
+        let level = self.random_level();
+
+        // Find the new node's neighbours before it's part of the graph
+        let mut neighbours = vec![Vec::new(); level + 1];
+        if let Some(mut entry) = self.entry {
+            for layer in (level + 1..=self.top).rev() {
+                entry = self.greedy(&vector, entry, layer);
+            }
+            let mut entries = vec![entry];
+            for layer in (0..=level.min(self.top)).rev() {
+                let found = self.search_layer(&vector, &entries, EF_CONSTRUCTION, layer);
+                neighbours[layer] = found.iter().take(M).map(|scored| scored.1).collect();
+                entries = found.into_iter().map(|scored| scored.1).collect();
+            }
+        }
+
+        self.vectors.push(vector);
+        self.links.push(neighbours.clone());
+        for (layer, neighbours) in neighbours.into_iter().enumerate() {
+            let most = if layer == 0 { M0 } else { M };
+            for neighbour in neighbours {
+                self.links[neighbour][layer].push(id);
+                if self.links[neighbour][layer].len() > most {
+                    self.prune(neighbour, layer, most);
+                }
+            }
+        }
+        if self.entry.is_none() || level > self.top {
+            self.entry = Some(id);
+            self.top = level;
+        }
+        id
+    }
+
+    /// Ids of about the `k` vectors most similar to `query` with their
+    /// similarity, best first; a larger `ef` finds them more reliably
+    pub fn search(&self, query: &[f32], k: usize, ef: usize) -> Vec<(usize, f32)> {
+        let Some(mut entry) = self.entry else { return Vec::new() };
+        for layer in (1..=self.top).rev() {
+            entry = self.greedy(query, entry, layer);
+        }
+        self.search_layer(query, &[entry], ef.max(k), 0)
+            .into_iter()
+            .take(k)
+            .map(|Scored(score, id)| (id, score))
+            .collect()
+    }
+
+    fn similarity(&self, query: &[f32], id: usize) -> f32 {
+        self.vectors[id].iter().zip(query).map(|(a, b)| a * b).sum()
+    }
+
+    /// Follow the most similar neighbour on `layer` until none is closer
+    fn greedy(&self, query: &[f32], mut node: usize, layer: usize) -> usize {
+        let mut best = self.similarity(query, node);
+        loop {
+            let closer = self.links[node][layer]
+                .iter()
+                .map(|&neighbour| Scored(self.similarity(query, neighbour), neighbour))
+                .max()
+                .filter(|scored| scored.0 > best);
+            match closer {
+                Some(Scored(score, neighbour)) => (best, node) = (score, neighbour),
+                None => return node,
+            }
+        }
+    }
+
+    /// The `ef` most similar nodes reachable on `layer` from `entries`, best
+    /// first
+    fn search_layer(&self, query: &[f32], entries: &[usize], ef: usize, layer: usize) -> Vec<Scored> {
+        let mut visited: HashSet<usize> = entries.iter().copied().collect();
+        let mut candidates: BinaryHeap<Scored> = BinaryHeap::new();
+        // Worst result on top, to drop it when a better one turns up
+        let mut results: BinaryHeap<Reverse<Scored>> = BinaryHeap::new();
+        for &entry in entries {
+            let scored = Scored(self.similarity(query, entry), entry);
+            candidates.push(scored);
+            results.push(Reverse(scored));
+        }
+        while results.len() > ef {
+            results.pop();
+        }
+
+        while let Some(candidate) = candidates.pop() {
+            let worst = results.peek().map_or(f32::NEG_INFINITY, |Reverse(worst)| worst.0);
+            if candidate.0 < worst && results.len() >= ef {
+                break;
+            }
+            for &neighbour in &self.links[candidate.1][layer] {
+                if !visited.insert(neighbour) {
+                    continue;
+                }
+                let scored = Scored(self.similarity(query, neighbour), neighbour);
+                let worst = results.peek().map_or(f32::NEG_INFINITY, |Reverse(worst)| worst.0);
+                if results.len() < ef || scored.0 > worst {
+                    candidates.push(scored);
+                    results.push(Reverse(scored));
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+        let mut results: Vec<Scored> = results.into_iter().map(|Reverse(scored)| scored).collect();
+        results.sort_by(|a, b| b.cmp(a));
+        results
+    }
+
+    /// Keep only `node`'s `most` most similar neighbours on `layer`
+    fn prune(&mut self, node: usize, layer: usize, most: usize) {
+        let vector = &self.vectors[node];
+        let mut neighbours: Vec<Scored> = self.links[node][layer]
+            .iter()
+            .map(|&neighbour| Scored(self.similarity(vector, neighbour), neighbour))
+            .collect();
+        neighbours.sort_by(|a, b| b.cmp(a));
+        self.links[node][layer] = neighbours.into_iter().take(most).map(|scored| scored.1).collect();
+    }
+
+    /// Top layer for a new node: each layer up is `M` times less likely
+    fn random_level(&mut self) -> usize {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        // Uniform in (0, 1]
+        let uniform = ((self.seed >> 11) + 1) as f64 / (1u64 << 53) as f64;
+        (-uniform.ln() / (M as f64).ln()) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic unit vectors
+    fn vectors(count: usize, dimensions: usize, mut seed: u64) -> Vec<Vec<f32>> {
+        (0..count)
+            .map(|_| {
+                let vector: Vec<f32> = (0..dimensions)
+                    .map(|_| {
+                        seed ^= seed << 13;
+                        seed ^= seed >> 7;
+                        seed ^= seed << 17;
+                        (seed >> 40) as f32 / (1u64 << 24) as f32 - 0.5
+                    })
+                    .collect();
+                let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+                vector.into_iter().map(|x| x / norm).collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_search_finds_nearest_neighbours() {
+        let data = vectors(2000, 24, 7);
+        let mut index = Hnsw::new();
+        for vector in &data {
+            index.insert(vector.clone());
+        }
+        assert_eq!(index.len(), 2000);
+
+        let mut found = 0;
+        let queries = vectors(50, 24, 99);
+        for query in &queries {
+            let mut exact: Vec<Scored> = data
+                .iter()
+                .enumerate()
+                .map(|(id, vector)| Scored(vector.iter().zip(query).map(|(a, b)| a * b).sum(), id))
+                .collect();
+            exact.sort_by(|a, b| b.cmp(a));
+            let hits = index.search(query, 10, 50);
+            assert_eq!(hits.len(), 10);
+            assert!(hits.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+            found += exact[..10].iter().filter(|scored| hits.iter().any(|hit| hit.0 == scored.1)).count();
+        }
+        // Approximate, but it should rarely miss
+        assert!(found * 100 >= queries.len() * 10 * 90, "recall {}/{}", found, queries.len() * 10);
+    }
+
+    #[test]
+    fn test_search_small_and_empty() {
+        assert!(Hnsw::new().search(&[1.0, 0.0], 3, 10).is_empty());
+
+        let mut index = Hnsw::new();
+        index.insert(vec![1.0, 0.0]);
+        index.insert(vec![0.0, 1.0]);
+        index.insert(vec![0.6, 0.8]);
+        let hits = index.search(&[1.0, 0.0], 5, 10);
+        assert_eq!(hits.iter().map(|hit| hit.0).collect::<Vec<_>>(), vec![0, 2, 1]);
+    }
+}
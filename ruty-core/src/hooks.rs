@@ -0,0 +1,148 @@
+//! User hook scripts
+//!
+//! `[hooks]` in config.toml lists shell commands to run when something
+//! happens, so integrations don't need patches to Ruty:
+//!
+//! ```toml
+//! [hooks]
+//! app_launched = ["~/bin/log-launch"]
+//! clipboard_captured = ["jq -r .content >> ~/copies.txt"]
+//! ```
+//!
+//! Each command runs with `sh -c` and reads the event as one JSON object on
+//! stdin: `event` names it, `timestamp` is Unix seconds, and the rest are
+//! the event's fields (see `HookEvent`). Hooks run in the background, so a
+//! slow one never holds Ruty up, and one still running after
+//! `HOOK_TIMEOUT` is killed. They run in `[hooks.sandbox]`, or else
+//! `[sandbox]` (see `sandbox`).
+
+use std::io::Write;
+use std::process::Stdio;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::audit;
+use crate::content::ContentKind;
+use crate::sandbox::{self, Sandbox};
+
+/// How long a hook may run before it's killed
+pub const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Something hooks can run on
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HookEvent {
+    /// The launcher window was brought up
+    WindowShown,
+    AppLaunched { id: String, name: String },
+    /// A copy was added to clipboard history
+    ClipboardCaptured {
+        content: String,
+        kind: ContentKind,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        language: Option<String>,
+    },
+    /// The AI finished answering a prompt
+    AiResponseDone { session_id: String, prompt: String, response: String, tools_used: Vec<String> },
+}
+
+impl HookEvent {
+    /// The `[hooks]` key for this event
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::WindowShown => "window_shown",
+            Self::AppLaunched { .. } => "app_launched",
+            Self::ClipboardCaptured { .. } => "clipboard_captured",
+            Self::AiResponseDone { .. } => "ai_response_done",
+        }
+    }
+
+    /// The JSON hooks read on stdin
+    pub fn payload(&self) -> String {
+        let mut payload = serde_json::to_value(self).unwrap_or_default();
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        payload["timestamp"] = timestamp.into();
+        payload.to_string()
+    }
+}
+
+/// Run `commands` for `event` in the background, in `sandbox`
+pub fn run(commands: &[String], event: &HookEvent, sandbox: &Sandbox) {
+    if commands.is_empty() {
+        return;
+    }
+    let payload = event.payload();
+    for command in commands {
+        audit::record(audit::Kind::Hook, command, event.name());
+        let (command, payload, name, sandbox) = (command.clone(), payload.clone(), event.name(), sandbox.clone());
+        thread::spawn(move || {
+            if let Err(e) = run_one(&command, &payload, HOOK_TIMEOUT, &sandbox) {
+                tracing::warn!("{} hook `{}` failed: {}", name, command, e);
+            }
+        });
+    }
+}
+
+/// Run one hook with `payload` on stdin and wait for it, up to `timeout`
+fn run_one(command: &str, payload: &str, timeout: Duration, sandbox: &Sandbox) -> Result<(), String> {
+    let mut command = sandbox::shell_command(sandbox, command)?;
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Cannot run {}: {}", command.get_program().to_string_lossy(), e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that doesn't read its input closes the pipe early; that's fine
+        let _ = stdin.write_all(payload.as_bytes());
+    }
+
+    let started = Instant::now();
+    loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) if status.success() => return Ok(()),
+            Some(status) => return Err(format!("exited with {}", status)),
+            None if started.elapsed() > timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("still running after {}s, killed", timeout.as_secs()));
+            }
+            None => thread::sleep(Duration::from_millis(50)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload() {
+        let event = HookEvent::AppLaunched { id: "org.gnome.Calculator".into(), name: "Calculator".into() };
+        let payload: serde_json::Value = serde_json::from_str(&event.payload()).unwrap();
+        assert_eq!(payload["event"], "app_launched");
+        assert_eq!(payload["id"], "org.gnome.Calculator");
+        assert!(payload["timestamp"].as_u64().unwrap() > 0);
+
+        let copied = HookEvent::ClipboardCaptured { content: "https://example.com".into(), kind: ContentKind::Url, language: None };
+        let payload: serde_json::Value = serde_json::from_str(&copied.payload()).unwrap();
+        assert_eq!((payload["event"].as_str(), payload["kind"].as_str()), (Some("clipboard_captured"), Some("url")));
+        assert!(payload.get("language").is_none());
+    }
+
+    #[test]
+    fn test_run_one() {
+        let dir = std::env::temp_dir().join(format!("ruty-hooks-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let out = dir.join("event.json");
+
+        let sandbox = Sandbox::default();
+        run_one(&format!("cat > '{}'", out.display()), r#"{"event":"window_shown"}"#, HOOK_TIMEOUT, &sandbox).unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), r#"{"event":"window_shown"}"#);
+
+        assert!(run_one("exit 3", "{}", HOOK_TIMEOUT, &sandbox).unwrap_err().contains("exit status: 3"));
+        assert!(run_one("sleep 5", "{}", Duration::from_millis(100), &sandbox).unwrap_err().contains("killed"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
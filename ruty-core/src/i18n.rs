@@ -0,0 +1,284 @@
+//! UI string localization
+//!
+//! User-facing text (command help, empty states, settings labels) is looked
+//! up by message id in Fluent files bundled with the binary, one per
+//! language under `locales/`. The language is `[ui] language` in
+//! config.toml, or the one the locale environment asks for when that's
+//! empty. A message missing from a translation falls back to English, and
+//! one missing from English to its id, so a stale translation never blanks
+//! out the UI.
+//!
+//! The files are Fluent proper (fluent-bundle), so translations can use
+//! selectors and plurals, e.g. `{ $count -> [one] …  *[other] … }` with a
+//! number argument. Languages are matched with fluent-langneg, so `de-AT`
+//! or `de_DE.UTF-8` get German and `en-US` English.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::sync::{LazyLock, RwLock};
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use fluent_langneg::negotiate::filter_matches;
+use fluent_langneg::NegotiationStrategy;
+use fluent_syntax::ast;
+use unic_langid::LanguageIdentifier;
+
+pub use fluent_bundle::FluentValue;
+
+/// The language every message must exist in
+pub const FALLBACK: &str = "en";
+
+/// Bundled languages and their Fluent sources
+const LOCALES: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+];
+
+static BUNDLES: LazyLock<BTreeMap<&'static str, Bundle>> = LazyLock::new(|| {
+    LOCALES
+        .iter()
+        .map(|(language, source)| {
+            let bundle = Bundle::parse(language, source).unwrap_or_else(|e| panic!("locales/{}.ftl: {}", language, e));
+            (*language, bundle)
+        })
+        .collect()
+});
+
+static AVAILABLE: LazyLock<Vec<LanguageIdentifier>> = LazyLock::new(|| {
+    LOCALES.iter().map(|(language, _)| language.parse().expect("bundled language tag")).collect()
+});
+
+static LANGUAGE: RwLock<&str> = RwLock::new(FALLBACK);
+
+/// The messages of one language
+pub struct Bundle {
+    bundle: FluentBundle<FluentResource>,
+    ids: Vec<String>,
+}
+
+impl Bundle {
+    /// Parse the Fluent source of `language`
+    pub fn parse(language: &str, source: &str) -> Result<Self, String> {
+        let langid: LanguageIdentifier = language.parse().map_err(|e| format!("language '{}': {}", language, e))?;
+        let resource = FluentResource::try_new(source.to_string()).map_err(|(_, errors)| {
+            let error = &errors[0];
+            let line = source[..error.pos.start.min(source.len())].lines().count().max(1);
+            format!("line {}: {}", line, error)
+        })?;
+        let ids = resource
+            .entries()
+            .filter_map(|entry| match entry {
+                ast::Entry::Message(message) => Some(message.id.name.to_string()),
+                _ => None,
+            })
+            .collect();
+
+        let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+        // Arguments would otherwise be wrapped in Unicode isolation marks,
+        // which the UI font draws as boxes
+        bundle.set_use_isolating(false);
+        bundle.add_resource(resource).map_err(|errors| {
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+        })?;
+        Ok(Self { bundle, ids })
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.bundle.has_message(id)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.ids.iter().map(String::as_str)
+    }
+
+    /// Message `id` with its variables filled in from `args`; a variable
+    /// that isn't given is left as `{$name}`
+    pub fn format(&self, id: &str, args: &[(&str, FluentValue)]) -> Option<String> {
+        let pattern = self.bundle.get_message(id)?.value()?;
+        let args: FluentArgs = args.iter().map(|(name, value)| (*name, value.clone())).collect();
+        let mut errors = Vec::new();
+        let text = self.bundle.format_pattern(pattern, Some(&args), &mut errors);
+        if !errors.is_empty() {
+            tracing::debug!("Formatting message {}: {:?}", id, errors);
+        }
+        Some(text.into_owned())
+    }
+}
+
+/// The bundled language closest to a locale like `de_DE.UTF-8` or `pt-BR`
+pub fn negotiate(locale: &str) -> Option<&'static str> {
+    // POSIX locales put a codeset and modifier after the tag, and `_`
+    // where BCP 47 has `-`
+    let tag = locale.split(['.', '@']).next().unwrap_or("").replace('_', "-");
+    let requested: LanguageIdentifier = tag.parse().ok()?;
+    let matched = filter_matches(&[requested], &AVAILABLE, NegotiationStrategy::Lookup);
+    let index = AVAILABLE.iter().position(|available| Some(&available) == matched.first())?;
+    Some(LOCALES[index].0)
+}
+
+/// The language the locale environment asks for. The first of `LANGUAGE`
+/// (a `:` separated preference list), `LC_ALL`, `LC_MESSAGES` and `LANG`
+/// that's set decides, as with gettext.
+pub fn detect() -> &'static str {
+    for var in ["LANGUAGE", "LC_ALL", "LC_MESSAGES", "LANG"] {
+        let value = env::var(var).unwrap_or_default();
+        if !value.is_empty() {
+            return value.split(':').find_map(negotiate).unwrap_or(FALLBACK);
+        }
+    }
+    FALLBACK
+}
+
+/// The language for `[ui] language`: the configured one if it's bundled,
+/// the detected one if it's empty
+pub fn resolve(configured: &str) -> &'static str {
+    if configured.trim().is_empty() {
+        return detect();
+    }
+    negotiate(configured).unwrap_or_else(|| {
+        tracing::warn!("No translation for language '{}', using {}", configured, FALLBACK);
+        FALLBACK
+    })
+}
+
+/// Pick the UI language for `[ui] language`, returning it
+pub fn init(configured: &str) -> &'static str {
+    let language = resolve(configured);
+    *LANGUAGE.write().unwrap() = language;
+    tracing::info!("UI language: {}", language);
+    language
+}
+
+/// The current UI language
+pub fn language() -> &'static str {
+    *LANGUAGE.read().unwrap()
+}
+
+/// Message `id` in the current language
+pub fn tr(id: &str) -> String {
+    tr_args(id, &[])
+}
+
+/// Message `id` in the current language with `{ $name }` variables filled
+/// in from `args`; pass numbers as numbers for plurals to pick the right form
+pub fn tr_args(id: &str, args: &[(&str, FluentValue)]) -> String {
+    [language(), FALLBACK]
+        .iter()
+        .find_map(|language| BUNDLES.get(language)?.format(id, args))
+        .unwrap_or_else(|| {
+            tracing::debug!("Missing message: {}", id);
+            id.to_string()
+        })
+}
+
+/// Every message of `language` (English where it has no translation), for
+/// frontends that render their own UI
+pub fn messages(language: &str) -> BTreeMap<String, String> {
+    let mut messages = BTreeMap::new();
+    for language in [FALLBACK, language] {
+        if let Some(bundle) = BUNDLES.get(language) {
+            for id in bundle.ids() {
+                messages.insert(id.to_string(), bundle.format(id, &[]).unwrap_or_default());
+            }
+        }
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let bundle = Bundle::parse(
+            "en",
+            concat!(
+                "# Comment\n",
+                "greeting = Hello, { $name }!\n",
+                "braces = Use {\"{\"}input{ \"}\" } here\n",
+                "help =\n",
+                "    First line\n",
+                "      indented\n",
+                "\n",
+                "    After a blank line\n",
+                "\n",
+                "next = Done\n",
+            ),
+        )
+        .unwrap();
+
+        let name = [("name", FluentValue::from("Ada"))];
+        assert_eq!(bundle.format("greeting", &name).unwrap(), "Hello, Ada!");
+        assert_eq!(bundle.format("greeting", &[]).unwrap(), "Hello, {$name}!");
+        assert_eq!(bundle.format("braces", &[]).unwrap(), "Use {input} here");
+        assert_eq!(bundle.format("help", &[]).unwrap(), "First line\n  indented\n\nAfter a blank line");
+        assert_eq!(bundle.format("next", &[]).unwrap(), "Done");
+        assert_eq!(bundle.format("missing", &[]), None);
+        assert_eq!(bundle.ids().collect::<Vec<_>>(), ["greeting", "braces", "help", "next"]);
+
+        assert!(Bundle::parse("en", "no value here").is_err());
+        assert!(Bundle::parse("en", "empty =").is_err());
+        assert!(Bundle::parse("en", "open = { $name").is_err());
+        assert!(Bundle::parse("en", "twice = One\ntwice = Two").is_err());
+    }
+
+    #[test]
+    fn test_selectors() {
+        let source = concat!(
+            "files = { $count ->\n",
+            "    [0] No files\n",
+            "    [one] One file\n",
+            "   *[other] { $count } files\n",
+            "}\n",
+            "kind = { $kind ->\n",
+            "    [app] Keine Apps\n",
+            "   *[other] Keine Treffer\n",
+            "}\n",
+        );
+        let english = Bundle::parse("en", source).unwrap();
+        let count = |n: i64| [("count", FluentValue::from(n))];
+        assert_eq!(english.format("files", &count(0)).unwrap(), "No files");
+        assert_eq!(english.format("files", &count(1)).unwrap(), "One file");
+        assert_eq!(english.format("files", &count(3)).unwrap(), "3 files");
+        assert_eq!(english.format("kind", &[("kind", "app".into())]).unwrap(), "Keine Apps");
+        assert_eq!(english.format("kind", &[("kind", "file".into())]).unwrap(), "Keine Treffer");
+
+        // Plural categories are the language's: Polish has a `few` form
+        let polish = Bundle::parse("pl", "files = { $count ->\n    [one] plik\n    [few] pliki\n   *[other] plików\n}").unwrap();
+        assert_eq!(polish.format("files", &count(3)).unwrap(), "pliki");
+        assert_eq!(polish.format("files", &count(5)).unwrap(), "plików");
+    }
+
+    #[test]
+    fn test_bundled_locales() {
+        let english = &BUNDLES[FALLBACK];
+        for (language, _) in LOCALES {
+            let bundle = &BUNDLES[language];
+            for id in bundle.ids() {
+                assert!(english.contains(id), "{}.ftl has `{}`, which en.ftl doesn't", language, id);
+            }
+        }
+        assert!(messages("de")["settings-title"] != messages("en")["settings-title"]);
+
+        let no_matches = |category: &str| {
+            let args = [("category", category.into()), ("query", "xy".into())];
+            BUNDLES["de"].format("empty-no-matches", &args).unwrap()
+        };
+        assert_eq!(no_matches("files"), "Keine Datei passt zu „xy“");
+        assert_eq!(no_matches("devices"), "Keine Treffer für „xy“");
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(negotiate("de_DE.UTF-8"), Some("de"));
+        assert_eq!(negotiate("de-AT"), Some("de"));
+        assert_eq!(negotiate("en-Latn-US"), Some("en"));
+        assert_eq!(negotiate("en_US.utf8@euro"), Some("en"));
+        assert_eq!(negotiate("C"), None);
+        assert_eq!(negotiate("fr_FR"), None);
+        assert_eq!(resolve("DE"), "de");
+        assert_eq!(resolve("klingon"), FALLBACK);
+    }
+}
@@ -0,0 +1,61 @@
+//! Ruty core: platform logic shared by the iced daemon and the Tauri app
+//!
+//! Anything that isn't tied to a particular frontend (app indexing, file
+//! search, clipboard and prompt history, content type detection, launch
+//! usage, caches, the calculator, matching, configuration and its validation,
+//! fonts, window geometry, workflows, opening folders in a terminal or
+//! editor, hook scripts, sandboxing shell commands, provider permissions,
+//! abbreviation expansion, document text extraction, text recognition, QR
+//! codes, the calendar agenda, contacts, local note retrieval, archives,
+//! checksums, text transforms, thumbnails, the activity timeline, the trash,
+//! scratchpad notes, the audit log, the local API token, UI translations,
+//! autostart, setup diagnostics) lives here so both builds pick up changes
+//! automatically.
+
+pub mod actions;
+pub mod apps;
+pub mod archives;
+pub mod audit;
+pub mod autostart;
+pub mod cache;
+pub mod calc;
+pub mod calendar;
+pub mod checksum;
+pub mod clipboard;
+pub mod config;
+pub mod contacts;
+pub mod content;
+pub mod documents;
+pub mod doctor;
+pub mod embedding;
+pub mod expansion;
+pub mod files;
+pub mod flows;
+pub mod folders;
+pub mod fonts;
+pub mod fuzzy;
+pub mod geometry;
+pub mod git;
+pub mod history;
+pub mod hnsw;
+pub mod hooks;
+pub mod i18n;
+pub mod memory;
+pub mod mime;
+pub mod paths;
+pub mod permissions;
+pub mod ocr;
+pub mod progress;
+pub mod query;
+pub mod qr;
+pub mod sandbox;
+pub mod scratchpad;
+pub mod snapshot;
+pub mod templates;
+pub mod thumbnails;
+pub mod timeline;
+pub mod token;
+pub mod transforms;
+pub mod trash;
+pub mod usage;
+pub mod validation;
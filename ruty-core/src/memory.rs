@@ -0,0 +1,415 @@
+//! Local memory: retrieval over notes and documents
+//!
+//! Indexes the folders in `[memory] folders` so chat prompts can carry the
+//! most relevant passages as local context, with no Python backend involved.
+//! Files are split into paragraph-sized passages, each passage is embedded
+//! with the local sentence model (see `embedding`), and the embeddings go
+//! into an HNSW graph (see `hnsw`). A query is embedded the same way and the
+//! graph finds the passages closest in meaning, so notes match on what they
+//! say rather than the words they use. Embeddings are cached by passage text
+//! between runs, so a restart only embeds what's new or changed.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::documents::{self, DocumentKind};
+use crate::embedding::{self, Embed};
+use crate::hnsw::Hnsw;
+use crate::paths;
+
+/// Plain text files indexed as-is
+const TEXT_EXTENSIONS: &[&str] = &["md", "markdown", "txt", "org", "rst", "adoc"];
+
+/// Largest plain text file indexed
+const MAX_TEXT_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Passages are cut at about this many characters
+const PASSAGE_CHARS: usize = 1200;
+
+/// Stop indexing after this many passages
+const MAX_PASSAGES: usize = 50_000;
+
+/// Passages less similar to the query than this are about something else
+const MIN_SCORE: f32 = 0.3;
+
+/// Candidates the graph search keeps, at least; more finds the best passages
+/// more reliably
+const SEARCH_EF: usize = 64;
+
+/// Start of the embedding cache file, before the embedding length
+const CACHE_MAGIC: &[u8; 8] = b"RUTYEMB1";
+
+/// A stretch of one file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Passage {
+    pub path: PathBuf,
+    pub text: String,
+}
+
+/// Embedded passages of the memory folders
+pub struct MemoryIndex {
+    /// Passages by their id in `graph`
+    passages: Vec<Passage>,
+    graph: Hnsw,
+    embedder: Box<dyn Embed>,
+    /// Number of files the passages came from
+    files: usize,
+}
+
+impl MemoryIndex {
+    /// Index the notes and documents under `dirs` (blocking; documents are
+    /// extracted to text, and new passages embedded, which can take a while).
+    /// Embeddings are reused from and saved to `cache`, if given.
+    pub fn build(
+        embedder: Box<dyn Embed>,
+        dirs: &[PathBuf],
+        max_depth: usize,
+        cache: Option<&Path>,
+    ) -> Result<Self, String> {
+        let mut paths = Vec::new();
+        for dir in dirs {
+            walk(dir, max_depth, &mut paths);
+        }
+        paths.sort();
+        paths.dedup();
+
+        let mut passages = Vec::new();
+        let mut files = 0;
+        for path in paths {
+            if passages.len() >= MAX_PASSAGES {
+                tracing::warn!("Memory index is full at {} passages; skipping the rest", MAX_PASSAGES);
+                break;
+            }
+            match read_text(&path) {
+                Ok(text) => {
+                    files += 1;
+                    passages.extend(split(&text).into_iter().map(|text| Passage { path: path.clone(), text }));
+                }
+                Err(e) => tracing::debug!("Not indexing {}: {}", path.display(), e),
+            }
+        }
+        let vectors = embed_cached(embedder.as_ref(), &passages, cache)?;
+        let mut index = Self::from_vectors(embedder, passages, vectors);
+        index.files = files;
+        Ok(index)
+    }
+
+    /// Index passages that are already in memory
+    pub fn from_passages(embedder: Box<dyn Embed>, passages: Vec<Passage>) -> Result<Self, String> {
+        let vectors = embed_cached(embedder.as_ref(), &passages, None)?;
+        Ok(Self::from_vectors(embedder, passages, vectors))
+    }
+
+    fn from_vectors(embedder: Box<dyn Embed>, passages: Vec<Passage>, vectors: Vec<Vec<f32>>) -> Self {
+        let mut graph = Hnsw::new();
+        for vector in vectors {
+            graph.insert(vector);
+        }
+        Self { passages, graph, embedder, files: 0 }
+    }
+
+    /// Number of passages
+    pub fn len(&self) -> usize {
+        self.passages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passages.is_empty()
+    }
+
+    /// Number of files indexed
+    pub fn files(&self) -> usize {
+        self.files
+    }
+
+    /// The `k` passages most similar to `query`, best first
+    pub fn search(&self, query: &str, k: usize) -> Vec<(&Passage, f32)> {
+        let query = match self.embedder.embed(&[query]) {
+            Ok(mut vectors) if !vectors.is_empty() => vectors.swap_remove(0),
+            Ok(_) => return Vec::new(),
+            Err(e) => {
+                tracing::warn!("Cannot search memory: {}", e);
+                return Vec::new();
+            }
+        };
+        self.graph
+            .search(&query, k, SEARCH_EF)
+            .into_iter()
+            .filter(|(_, score)| *score >= MIN_SCORE)
+            .map(|(id, score)| (&self.passages[id], score))
+            .collect()
+    }
+}
+
+/// Where `build` keeps the embedding model's passage embeddings
+pub fn cache_path() -> PathBuf {
+    paths::cache_dir().join(format!("memory-{}.bin", embedding::MODEL))
+}
+
+/// Local context for a chat prompt quoting the passages, or `None` if there
+/// are none
+pub fn context(hits: &[(&Passage, f32)]) -> Option<String> {
+    if hits.is_empty() {
+        return None;
+    }
+    let mut context = String::from("### From your notes");
+    for (passage, _) in hits {
+        context.push_str(&format!("\n\n#### {}\n{}", passage.path.display(), passage.text));
+    }
+    Some(context)
+}
+
+fn walk(dir: &Path, depth: usize, paths: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(kind) if kind.is_dir() && depth > 0 => walk(&path, depth - 1, paths),
+            Ok(kind) if kind.is_file() && is_indexable(&path) => paths.push(path),
+            _ => {}
+        }
+    }
+}
+
+fn is_indexable(path: &Path) -> bool {
+    let is_text = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| TEXT_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+    is_text || DocumentKind::from_path(path).is_some()
+}
+
+fn read_text(path: &Path) -> Result<String, String> {
+    if DocumentKind::from_path(path).is_some() {
+        return documents::extract(path).map(|extracted| extracted.text);
+    }
+    let size = fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if size > MAX_TEXT_BYTES {
+        return Err(format!("too large ({} KB)", size / 1024));
+    }
+    fs::read_to_string(path).map_err(|e| e.to_string())
+}
+
+/// Cut text into passages of at most `PASSAGE_CHARS` bytes, keeping
+/// paragraphs whole where they fit
+fn split(text: &str) -> Vec<String> {
+    let mut passages = Vec::new();
+    let mut current = String::new();
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        if !current.is_empty() && current.len() + 2 + paragraph.len() > PASSAGE_CHARS {
+            passages.push(std::mem::take(&mut current));
+        }
+        if paragraph.len() > PASSAGE_CHARS {
+            // One long paragraph: cut between words, and within words too
+            // long for a passage of their own (a base64 blob, say)
+            for piece in paragraph.split_whitespace().flat_map(|word| pieces(word, PASSAGE_CHARS)) {
+                if !current.is_empty() && current.len() + 1 + piece.len() > PASSAGE_CHARS {
+                    passages.push(std::mem::take(&mut current));
+                }
+                if !current.is_empty() {
+                    current.push(' ');
+                }
+                current.push_str(piece);
+            }
+            continue;
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(paragraph);
+    }
+    if !current.is_empty() {
+        passages.push(current);
+    }
+    passages
+}
+
+/// `word` cut at character boundaries into pieces of at most `max` bytes
+fn pieces(word: &str, max: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut rest = word;
+    while rest.len() > max {
+        let mut cut = max;
+        while !rest.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        let (piece, tail) = rest.split_at(cut);
+        pieces.push(piece);
+        rest = tail;
+    }
+    pieces.push(rest);
+    pieces
+}
+
+/// Embeddings of the passages, in order: those in `cache` are reused, the
+/// rest are embedded, and the cache is rewritten with just these passages
+fn embed_cached(embedder: &dyn Embed, passages: &[Passage], cache: Option<&Path>) -> Result<Vec<Vec<f32>>, String> {
+    let mut known = cache.map(read_cache).unwrap_or_default();
+    let keys: Vec<[u8; 32]> = passages.iter().map(|passage| *blake3::hash(passage.text.as_bytes()).as_bytes()).collect();
+
+    let (new_keys, new_texts): (Vec<[u8; 32]>, Vec<&str>) = keys
+        .iter()
+        .zip(passages)
+        .filter(|(key, _)| !known.contains_key(*key))
+        .map(|(key, passage)| (*key, passage.text.as_str()))
+        .unzip();
+    if !new_texts.is_empty() {
+        tracing::info!("Embedding {} new passages", new_texts.len());
+        let vectors = embedder.embed(&new_texts)?;
+        known.extend(new_keys.into_iter().zip(vectors));
+    }
+
+    let vectors: Vec<Vec<f32>> = keys.iter().filter_map(|key| known.get(key).cloned()).collect();
+    if vectors.len() != passages.len() {
+        return Err("The embedding model skipped passages".to_string());
+    }
+    if let Some(cache) = cache {
+        if let Err(e) = write_cache(cache, keys.iter().zip(&vectors)) {
+            tracing::warn!("Cannot save memory embeddings to {}: {}", cache.display(), e);
+        }
+    }
+    Ok(vectors)
+}
+
+/// Cached embeddings by passage hash; empty if there's no usable cache
+fn read_cache(path: &Path) -> HashMap<[u8; 32], Vec<f32>> {
+    let Ok(bytes) = fs::read(path) else { return HashMap::new() };
+    let Some(dimensions) = bytes
+        .strip_prefix(CACHE_MAGIC.as_slice())
+        .and_then(|rest| rest.get(..4))
+        .map(|length| u32::from_le_bytes([length[0], length[1], length[2], length[3]]) as usize)
+    else {
+        return HashMap::new();
+    };
+    let header = CACHE_MAGIC.len() + 4;
+    bytes[header..]
+        .chunks_exact(32 + dimensions * 4)
+        .map(|record| {
+            let (key, vector) = record.split_at(32);
+            let vector = vector.chunks_exact(4).map(|x| f32::from_le_bytes([x[0], x[1], x[2], x[3]])).collect();
+            (key.try_into().unwrap_or([0; 32]), vector)
+        })
+        .collect()
+}
+
+fn write_cache<'a>(
+    path: &Path,
+    entries: impl Iterator<Item = (&'a [u8; 32], &'a Vec<f32>)>,
+) -> Result<(), String> {
+    let mut entries = entries.peekable();
+    let dimensions = entries.peek().map_or(0, |(_, vector)| vector.len());
+    let mut bytes = CACHE_MAGIC.to_vec();
+    bytes.extend((dimensions as u32).to_le_bytes());
+    for (key, vector) in entries {
+        bytes.extend(key);
+        bytes.extend(vector.iter().flat_map(|x| x.to_le_bytes()));
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    fs::write(path, bytes).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Stand-in for the model: hashed bags of words, which is enough to tell
+    /// these notes apart
+    #[derive(Default)]
+    struct Words {
+        embedded: Arc<AtomicUsize>,
+    }
+
+    impl Embed for Words {
+        fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String> {
+            self.embedded.fetch_add(texts.len(), Ordering::SeqCst);
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let mut vector = vec![0.0f32; 64];
+                    for word in text.split(|c: char| !c.is_alphanumeric()).filter(|word| word.len() > 3) {
+                        let hash = word.to_lowercase().bytes().fold(0u32, |hash, byte| hash.wrapping_mul(31) + byte as u32);
+                        vector[hash as usize % 64] += 1.0;
+                    }
+                    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt().max(f32::EPSILON);
+                    vector.into_iter().map(|x| x / norm).collect()
+                })
+                .collect())
+        }
+    }
+
+    fn passage(path: &str, text: &str) -> Passage {
+        Passage { path: PathBuf::from(path), text: text.to_string() }
+    }
+
+    #[test]
+    fn test_search_ranks_relevant_passages() {
+        let index = MemoryIndex::from_passages(
+            Box::new(Words::default()),
+            vec![
+                passage("wifi.md", "The office wifi password is on the fridge. Router admin is at 192.168.1.1."),
+                passage("recipes.md", "Pancakes: flour, milk, eggs. Whisk and fry in butter."),
+                passage("trips.md", "Booked the train to Lisbon for March; the hotel has wifi."),
+            ],
+        )
+        .unwrap();
+        let hits = index.search("what is the wifi password?", 2);
+        assert_eq!(hits[0].0.path, PathBuf::from("wifi.md"));
+        assert!(hits.iter().all(|(p, _)| p.path != Path::new("recipes.md")));
+
+        assert!(index.search("quantum chromodynamics", 3).is_empty());
+        let context = context(&hits[..1]).unwrap();
+        assert!(context.starts_with("### From your notes\n\n#### wifi.md\nThe office wifi"));
+    }
+
+    #[test]
+    fn test_split() {
+        assert_eq!(split("one\n\n\n\ntwo"), vec!["one\n\ntwo"]);
+        let long = "word ".repeat(PASSAGE_CHARS / 2);
+        let passages = split(&format!("intro\n\n{}", long));
+        assert!(passages.len() >= 2);
+        assert!(passages.iter().all(|p| p.len() <= PASSAGE_CHARS));
+    }
+
+    #[test]
+    fn test_split_hard_splits_long_words() {
+        let blob = "é".repeat(PASSAGE_CHARS);
+        let passages = split(&format!("short note\n\nsee {} end", blob));
+        assert!(passages.iter().all(|p| !p.is_empty() && p.len() <= PASSAGE_CHARS));
+        assert_eq!(passages[0], "short note");
+        assert_eq!(passages.concat().matches('é').count(), PASSAGE_CHARS);
+        assert_eq!(passages.last().unwrap(), "end");
+
+        assert_eq!(split(&"x".repeat(PASSAGE_CHARS + 1)).len(), 2);
+    }
+
+    #[test]
+    fn test_build_reads_notes() {
+        let dir = std::env::temp_dir().join(format!("ruty-memory-{}", std::process::id()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub/note.md"), "Dentist appointment moved to Friday").unwrap();
+        fs::write(dir.join("image.png"), [0u8; 4]).unwrap();
+        let cache = dir.join("cache.bin");
+
+        let embedder = Words::default();
+        let embedded = embedder.embedded.clone();
+        let index = MemoryIndex::build(Box::new(embedder), std::slice::from_ref(&dir), 2, Some(&cache)).unwrap();
+        assert_eq!((index.files(), index.len()), (1, 1));
+        assert_eq!(index.search("when is the dentist appointment", 1).len(), 1);
+
+        // Unchanged passages come from the cache the second time
+        embedded.store(0, Ordering::SeqCst);
+        let embedder = Words { embedded: embedded.clone() };
+        let index = MemoryIndex::build(Box::new(embedder), std::slice::from_ref(&dir), 2, Some(&cache)).unwrap();
+        fs::remove_dir_all(&dir).ok();
+        assert_eq!(embedded.load(Ordering::SeqCst), 0);
+        assert_eq!(index.search("when is the dentist appointment", 1).len(), 1);
+    }
+}
@@ -0,0 +1,242 @@
+//! XDG MIME associations for "Open With…"
+//!
+//! Finds the applications that can open a file: desktop entries listing its
+//! MIME type, plus associations added in mimeapps.list (minus removed ones),
+//! with the default handler first. Setting a default edits the user's
+//! `~/.config/mimeapps.list`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::apps::{AppIndexer, Application};
+use crate::paths;
+
+const DEFAULTS: &str = "Default Applications";
+const ADDED: &str = "Added Associations";
+const REMOVED: &str = "Removed Associations";
+
+/// MIME type of a file, from shared-mime-info (`xdg-mime`) or `file`
+pub fn mime_type(path: &Path) -> Option<String> {
+    let queries: [(&str, &[&str]); 2] = [
+        ("xdg-mime", &["query", "filetype"]),
+        ("file", &["--brief", "--mime-type"]),
+    ];
+    queries.iter().find_map(|(program, args)| {
+        let output = Command::new(program).args(*args).arg(path).output().ok()?;
+        let mime = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && mime.contains('/')).then_some(mime)
+    })
+}
+
+/// Merged mimeapps.list associations
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MimeApps {
+    /// MIME type -> preferred desktop ids, highest priority file first
+    defaults: HashMap<String, Vec<String>>,
+    added: HashMap<String, Vec<String>>,
+    removed: HashMap<String, Vec<String>>,
+}
+
+impl MimeApps {
+    /// Load mimeapps.list files in XDG precedence order
+    pub fn load() -> Self {
+        let mut apps = Self::default();
+        for path in mimeapps_paths() {
+            if let Ok(content) = fs::read_to_string(&path) {
+                apps.merge(&content);
+            }
+        }
+        apps
+    }
+
+    /// Add a lower-precedence mimeapps.list
+    pub fn merge(&mut self, content: &str) {
+        for (section, mime, ids) in entries(content) {
+            let target = match section.as_str() {
+                DEFAULTS => &mut self.defaults,
+                ADDED => &mut self.added,
+                REMOVED => &mut self.removed,
+                _ => continue,
+            };
+            let list = target.entry(mime).or_default();
+            for id in ids {
+                if !list.contains(&id) {
+                    list.push(id);
+                }
+            }
+        }
+    }
+
+    /// Applications able to open `mime`, the default first
+    pub fn handlers<'a>(&self, mime: &str, apps: &'a [Application]) -> Vec<&'a Application> {
+        let listed = |map: &HashMap<String, Vec<String>>, app: &Application| {
+            map.get(mime)
+                .is_some_and(|ids| ids.contains(&desktop_id(app)))
+        };
+
+        let mut handlers: Vec<&Application> = apps
+            .iter()
+            .filter(|app| app.mime_types.iter().any(|m| m == mime) || listed(&self.added, app))
+            .filter(|app| !listed(&self.removed, app))
+            .collect();
+
+        let default = self.default_id(mime, &handlers);
+        handlers.sort_by_key(|app| Some(desktop_id(app)) != default);
+        handlers
+    }
+
+    /// Desktop id of the default handler among `handlers`
+    pub fn default_id(&self, mime: &str, handlers: &[&Application]) -> Option<String> {
+        let preferred = self.defaults.get(mime)?;
+        preferred
+            .iter()
+            .find(|id| handlers.iter().any(|app| desktop_id(app) == **id))
+            .cloned()
+    }
+}
+
+/// Desktop file id as used in mimeapps.list (e.g. org.gnome.Loupe.desktop)
+pub fn desktop_id(app: &Application) -> String {
+    format!("{}.desktop", app.id)
+}
+
+/// (section, mime, desktop ids) for every association line
+fn entries(content: &str) -> Vec<(String, String, Vec<String>)> {
+    let mut section = String::new();
+    let mut entries = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.to_string();
+            continue;
+        }
+        if let Some((mime, ids)) = line.split_once('=') {
+            let ids = ids.split(';').map(str::trim).filter(|id| !id.is_empty()).map(String::from);
+            entries.push((section.clone(), mime.trim().to_string(), ids.collect()));
+        }
+    }
+    entries
+}
+
+/// mimeapps.list locations, highest precedence first
+fn mimeapps_paths() -> Vec<PathBuf> {
+    let mut paths = vec![user_mimeapps_path(), PathBuf::from("/etc/xdg/mimeapps.list")];
+    paths.push(paths::data_home().join("applications").join("mimeapps.list"));
+    paths.extend(
+        AppIndexer::desktop_dirs()
+            .into_iter()
+            .map(|dir| dir.join("mimeapps.list")),
+    );
+    paths.dedup();
+    paths
+}
+
+/// The user's mimeapps.list, where defaults are written
+pub fn user_mimeapps_path() -> PathBuf {
+    paths::config_home().join("mimeapps.list")
+}
+
+/// Make `app` the default for `mime` in the user's mimeapps.list
+pub fn set_default(mime: &str, app: &Application) -> Result<(), String> {
+    let path = user_mimeapps_path();
+    let content = fs::read_to_string(&path).unwrap_or_default();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&path, with_default(&content, mime, &desktop_id(app)))
+        .map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+}
+
+/// `content` with `mime=id` set under [Default Applications], other lines kept
+fn with_default(content: &str, mime: &str, id: &str) -> String {
+    let entry = format!("{}={};", mime, id);
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+
+    let header = format!("[{}]", DEFAULTS);
+    let Some(start) = lines.iter().position(|l| l.trim() == header) else {
+        if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.extend([header, entry]);
+        return lines.join("\n") + "\n";
+    };
+
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| l.trim_start().starts_with('['))
+        .map_or(lines.len(), |i| start + 1 + i);
+    let existing = (start + 1..end).find(|&i| {
+        lines[i].split_once('=').is_some_and(|(key, _)| key.trim() == mime)
+    });
+    match existing {
+        Some(i) => lines[i] = entry,
+        None => lines.insert(start + 1, entry),
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(id: &str, mime_types: &[&str]) -> Application {
+        Application {
+            id: id.to_string(),
+            name: id.to_string(),
+            generic_name: None,
+            comment: None,
+            exec: id.to_string(),
+            icon: None,
+            categories: Vec::new(),
+            keywords: Vec::new(),
+            terminal: false,
+            no_display: false,
+            desktop_file: PathBuf::from(format!("/usr/share/applications/{}.desktop", id)),
+            mime_types: mime_types.iter().map(|m| m.to_string()).collect(),
+            startup_wm_class: None,
+            source: Default::default(),
+            alternates: Vec::new(),
+            duplicate_of: None,
+            missing_binary: None,
+        }
+    }
+
+    #[test]
+    fn test_handlers_default_first() {
+        let apps = vec![
+            app("eog", &["image/png"]),
+            app("gimp", &["image/png", "image/jpeg"]),
+            app("writer", &["text/plain"]),
+            app("krita", &[]),
+        ];
+        let mut mimeapps = MimeApps::default();
+        mimeapps.merge(
+            "[Default Applications]\nimage/png=missing.desktop;gimp.desktop;\n\n\
+             [Added Associations]\nimage/png=krita.desktop;\n\n\
+             [Removed Associations]\nimage/png=eog.desktop;\n",
+        );
+        // Lower-precedence defaults don't override
+        mimeapps.merge("[Default Applications]\nimage/png=eog.desktop\n");
+
+        let ids: Vec<&str> = mimeapps.handlers("image/png", &apps).iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["gimp", "krita"]);
+        assert!(mimeapps.handlers("video/mp4", &apps).is_empty());
+    }
+
+    #[test]
+    fn test_with_default() {
+        assert_eq!(with_default("", "image/png", "gimp.desktop"), "[Default Applications]\nimage/png=gimp.desktop;\n");
+
+        let existing = "[Added Associations]\ntext/plain=writer.desktop;\n\n[Default Applications]\nimage/png=eog.desktop;\ntext/plain=writer.desktop;\n";
+        let updated = with_default(existing, "image/png", "gimp.desktop");
+        assert!(updated.contains("[Default Applications]\nimage/png=gimp.desktop;\ntext/plain=writer.desktop;"));
+        assert!(updated.starts_with("[Added Associations]\ntext/plain=writer.desktop;"));
+
+        let added = with_default(existing, "video/mp4", "mpv.desktop");
+        assert!(added.contains("[Default Applications]\nvideo/mp4=mpv.desktop;\nimage/png=eog.desktop;"));
+    }
+}
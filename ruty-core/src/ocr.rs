@@ -0,0 +1,68 @@
+//! Text recognition
+//!
+//! Reads the text in an image with the `tesseract` CLI, so a copied
+//! screenshot of an error message or a scanned page can become a text
+//! clipboard entry. Languages are tesseract's codes, joined with `+` for
+//! mixed text ("eng+deu"); each needs its traineddata package installed.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Text in a PNG (or any image tesseract reads), tidied up
+pub fn extract(image: &[u8], language: &str) -> Result<String, String> {
+    let mut child = Command::new("tesseract")
+        .args(["stdin", "stdout", "-l", language])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Cannot run tesseract (is it installed?): {}", e))?;
+    // tesseract reads the whole image before writing anything
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(image).map_err(|e| format!("Cannot pass the image to tesseract: {}", e))?;
+    }
+    let output = child.wait_with_output().map_err(|e| format!("tesseract failed: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().rfind(|line| !line.trim().is_empty()).unwrap_or("unknown error");
+        return Err(format!("tesseract failed: {}", reason.trim()));
+    }
+    let text = tidy(&String::from_utf8_lossy(&output.stdout));
+    if text.is_empty() {
+        return Err("No text found in the image".to_string());
+    }
+    Ok(text)
+}
+
+/// Drop tesseract's page break and trailing spaces, and collapse runs of
+/// blank lines into one
+fn tidy(output: &str) -> String {
+    let mut text = String::new();
+    let mut blank = false;
+    for line in output.replace('\u{c}', "").lines().map(str::trim_end) {
+        if line.is_empty() {
+            blank = !text.is_empty();
+            continue;
+        }
+        if blank {
+            text.push('\n');
+            blank = false;
+        }
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(line);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tidy() {
+        assert_eq!(tidy("\nerror[E0382]: borrow  \nof moved value\n\n\n\nhelp: clone it\n\u{c}"), "error[E0382]: borrow\nof moved value\n\nhelp: clone it");
+        assert_eq!(tidy(" \n\u{c}"), "");
+    }
+}
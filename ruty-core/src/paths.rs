@@ -0,0 +1,171 @@
+//! Where Ruty keeps its files
+//!
+//! Every store asks here rather than working out a location of its own:
+//!
+//! - `config_dir()`, `~/.config/ruty`: config.toml
+//! - `data_dir()`, `~/.local/share/ruty`: the scratchpad, speech models, the
+//!   audit log and the backend's conversations
+//! - `state_dir()`, `~/.local/state/ruty`: prompt history, launch usage,
+//!   window geometry and the crash snapshot
+//! - `cache_dir()`, `~/.cache/ruty`: thumbnails
+//!
+//! The directories come from `directories::ProjectDirs` for "ruty", which
+//! follows the XDG variables (`XDG_CONFIG_HOME` and co.) on Linux and the
+//! platform's conventions elsewhere; the bases other stores share (autostart,
+//! the trash, sockets) from its `BaseDirs`. Without a home directory,
+//! everything goes to a per-user directory under the temp dir instead of a
+//! shared `/tmp`.
+//!
+//! Files older versions kept somewhere else are listed in `legacy()`, and
+//! `migrate()` moves them over at startup.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use directories::{BaseDirs, ProjectDirs};
+
+const APP: &str = "ruty";
+
+/// Ruty's own directories; `None` without a home directory
+fn project() -> Option<ProjectDirs> {
+    ProjectDirs::from("", "", APP)
+}
+
+/// Home directory
+pub fn home() -> Option<PathBuf> {
+    BaseDirs::new().map(|base| base.home_dir().to_path_buf())
+}
+
+/// Base for user config (`$XDG_CONFIG_HOME`), e.g. for autostart entries
+pub fn config_home() -> PathBuf {
+    BaseDirs::new().map(|base| base.config_dir().to_path_buf()).unwrap_or_else(fallback)
+}
+
+/// Base for user data (`$XDG_DATA_HOME`), e.g. for the trash
+pub fn data_home() -> PathBuf {
+    BaseDirs::new().map(|base| base.data_dir().to_path_buf()).unwrap_or_else(fallback)
+}
+
+/// Where sockets go (`$XDG_RUNTIME_DIR`)
+pub fn runtime_dir() -> PathBuf {
+    BaseDirs::new()
+        .and_then(|base| base.runtime_dir().map(Path::to_path_buf))
+        .unwrap_or_else(fallback)
+}
+
+pub fn config_dir() -> PathBuf {
+    project().map(|project| project.config_dir().to_path_buf()).unwrap_or_else(|| fallback().join(APP))
+}
+
+pub fn data_dir() -> PathBuf {
+    project().map(|project| project.data_dir().to_path_buf()).unwrap_or_else(|| fallback().join(APP))
+}
+
+/// State that's kept between runs but not worth backing up
+pub fn state_dir() -> PathBuf {
+    project()
+        .map(|project| project.state_dir().unwrap_or(project.data_local_dir()).to_path_buf())
+        .unwrap_or_else(|| fallback().join(APP))
+}
+
+pub fn cache_dir() -> PathBuf {
+    project().map(|project| project.cache_dir().to_path_buf()).unwrap_or_else(|| fallback().join(APP))
+}
+
+/// Per-user stand-in for the bases when there's no home directory
+fn fallback() -> PathBuf {
+    let user = env::var("USER").unwrap_or_else(|_| "user".to_string());
+    env::temp_dir().join(format!("{}-{}", APP, user))
+}
+
+/// A file an older version kept elsewhere, and where it belongs now
+#[derive(Debug, Clone, PartialEq)]
+pub struct Legacy {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// Files older versions kept in the wrong place
+pub fn legacy() -> Vec<Legacy> {
+    // The backend kept its conversation database with the config, along
+    // with SQLite's journal files while it's open
+    ["conversations.db", "conversations.db-wal", "conversations.db-shm"]
+        .into_iter()
+        .map(|name| Legacy { from: config_dir().join(name), to: data_dir().join(name) })
+        .collect()
+}
+
+/// Move legacy files to their current places, returning the ones moved. A
+/// file that's already in its new place is left alone, so this only does
+/// anything on the first run after an upgrade.
+pub fn migrate() -> Vec<Legacy> {
+    migrate_files(legacy())
+}
+
+fn migrate_files(files: Vec<Legacy>) -> Vec<Legacy> {
+    files
+        .into_iter()
+        .filter(|file| file.from.exists() && !file.to.exists())
+        .filter(|file| match move_file(&file.from, &file.to) {
+            Ok(()) => {
+                tracing::info!("Moved {} to {}", file.from.display(), file.to.display());
+                true
+            }
+            Err(e) => {
+                tracing::warn!("Failed to move {}: {}", file.from.display(), e);
+                false
+            }
+        })
+        .collect()
+}
+
+/// Rename, or copy and delete across filesystems
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to).map_err(|e| e.to_string())?;
+    fs::remove_file(from).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dirs() {
+        for dir in [config_dir(), data_dir(), state_dir(), cache_dir()] {
+            assert!(dir.is_absolute() && dir.ends_with(APP), "{}", dir.display());
+        }
+        assert!(legacy().iter().all(|file| file.from.starts_with(config_dir()) && file.to.starts_with(data_dir())));
+    }
+
+    #[test]
+    fn test_migrate_files() {
+        let root = env::temp_dir().join(format!("ruty-paths-{}", std::process::id()));
+        let old = root.join("config");
+        let new = root.join("data").join("ruty");
+        fs::create_dir_all(&old).unwrap();
+        fs::write(old.join("a.db"), "a").unwrap();
+        fs::write(old.join("b.db"), "stale").unwrap();
+        fs::create_dir_all(&new).unwrap();
+        fs::write(new.join("b.db"), "current").unwrap();
+
+        let files: Vec<Legacy> = ["a.db", "b.db", "missing.db"]
+            .into_iter()
+            .map(|name| Legacy { from: old.join(name), to: new.join(name) })
+            .collect();
+        let moved = migrate_files(files.clone());
+        assert_eq!(moved, files[..1]);
+        assert_eq!(fs::read_to_string(new.join("a.db")).unwrap(), "a");
+        assert!(!old.join("a.db").exists());
+        // Never overwrites what's already in the new place
+        assert_eq!(fs::read_to_string(new.join("b.db")).unwrap(), "current");
+        assert!(migrate_files(files).is_empty());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,148 @@
+//! Capabilities of flows and hooks
+//!
+//! Flows and hooks act on the user's behalf, so what they can do is asked
+//! for before they first do it. Each provider needs the capabilities its
+//! config implies: a flow's shell steps run shell commands, its `ai-prompt`
+//! steps use the network and its queries read files; hooks run shell
+//! commands, and `clipboard_captured` hooks read every copy. A shell command
+//! also uses the network unless its sandbox cuts it off (see `sandbox`).
+//!
+//! The first time a provider needs a capability it hasn't been granted, the
+//! launcher asks; "Allow" saves the grant under `[permissions]`, and
+//! `/permissions` lists and revokes them:
+//!
+//! ```toml
+//! [permissions]
+//! "flow:backup" = ["shell", "network"]
+//! hooks = ["shell", "clipboard-read"]
+//! ```
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::flows::Step;
+use crate::sandbox::Sandbox;
+
+/// Provider name of the `[hooks]` commands
+pub const HOOKS: &str = "hooks";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    ClipboardRead,
+    FileRead,
+    Network,
+    Shell,
+}
+
+impl Capability {
+    /// What it lets a provider do, e.g. "run shell commands"
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::ClipboardRead => "read the clipboard",
+            Self::FileRead => "search and open your files",
+            Self::Network => "use the network",
+            Self::Shell => "run shell commands",
+        }
+    }
+}
+
+/// Provider name of flow `name`
+pub fn flow(name: &str) -> String {
+    format!("flow:{}", name)
+}
+
+/// `provider` for people: "flow backup", "hooks"
+pub fn label(provider: &str) -> String {
+    match provider.strip_prefix("flow:") {
+        Some(name) => format!("flow {}", name),
+        None => provider.to_string(),
+    }
+}
+
+/// The capabilities `provider` needs as configured; none for one that isn't
+pub fn needs(config: &Config, provider: &str) -> BTreeSet<Capability> {
+    let mut needs = BTreeSet::new();
+    let shell = |needs: &mut BTreeSet<Capability>, sandbox: &Sandbox| {
+        needs.insert(Capability::Shell);
+        if !sandbox.enabled || sandbox.network {
+            needs.insert(Capability::Network);
+        }
+    };
+    if provider == HOOKS {
+        if config.hooks.any() {
+            shell(&mut needs, config.hooks.sandbox.as_ref().unwrap_or(&config.sandbox));
+        }
+        if !config.hooks.clipboard_captured.is_empty() {
+            needs.insert(Capability::ClipboardRead);
+        }
+    } else if let Some(flow) = provider.strip_prefix("flow:").and_then(|name| config.flows.get(name)) {
+        for step in &flow.steps {
+            match step {
+                Step::Shell(_) => shell(&mut needs, flow.sandbox(&config.sandbox)),
+                Step::AiPrompt(_) => {
+                    needs.insert(Capability::Network);
+                }
+                Step::Query(_) => {
+                    needs.insert(Capability::FileRead);
+                }
+                Step::PickFirst | Step::Launch(_) | Step::Copy(_) => {}
+            }
+        }
+    }
+    needs
+}
+
+/// What `provider` needs that `[permissions]` doesn't grant it
+pub fn missing(config: &Config, provider: &str) -> BTreeSet<Capability> {
+    let granted = config.permissions.get(provider);
+    needs(config, provider)
+        .into_iter()
+        .filter(|capability| !granted.is_some_and(|granted| granted.contains(capability)))
+        .collect()
+}
+
+/// "run shell commands and use the network"
+pub fn describe(capabilities: &BTreeSet<Capability>) -> String {
+    let all: Vec<&str> = capabilities.iter().rev().map(Capability::describe).collect();
+    match all.split_last() {
+        None => String::new(),
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_needs() {
+        let mut config: Config = toml::from_str(concat!(
+            "[flows.stamp]\nsteps = [{ shell = \"date\" }, { ai-prompt = \"Day of {output}\" }]\n\n",
+            "[flows.find]\nsteps = [{ query = \"notes\" }, \"pick-first\", \"launch\"]\n\n",
+            "[hooks]\nclipboard_captured = [\"cat >> ~/copies\"]\n\n",
+            "[permissions]\n\"flow:stamp\" = [\"shell\"]\n",
+        ))
+        .unwrap();
+        let stamp = flow("stamp");
+        assert_eq!(needs(&config, &stamp), BTreeSet::from([Capability::Network, Capability::Shell]));
+        assert_eq!(missing(&config, &stamp), BTreeSet::from([Capability::Network]));
+        assert_eq!(missing(&config, "flow:find"), BTreeSet::from([Capability::FileRead]));
+        assert!(needs(&config, "flow:unknown").is_empty());
+
+        // A sandbox without network takes the network off what it needs
+        config.sandbox.enabled = true;
+        assert_eq!(
+            needs(&config, HOOKS),
+            BTreeSet::from([Capability::ClipboardRead, Capability::Shell])
+        );
+
+        assert_eq!(describe(&needs(&config, &stamp)), "run shell commands and use the network");
+        assert_eq!(label(&stamp), "flow stamp");
+        let text = toml::to_string_pretty(&config).unwrap();
+        assert_eq!(toml::from_str::<Config>(&text).unwrap(), config);
+    }
+}
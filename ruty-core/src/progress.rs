@@ -0,0 +1,33 @@
+//! Progress of long-running background work (extraction, hashing)
+//!
+//! The worker bumps `done` from its thread; the UI polls `fraction` on its
+//! tick to draw a progress bar.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Shared progress counter; `total` is 0 when the amount of work is unknown
+#[derive(Debug, Default)]
+pub struct Progress {
+    pub done: AtomicU64,
+    pub total: u64,
+}
+
+impl Progress {
+    pub fn new(total: u64) -> Self {
+        Self { done: AtomicU64::new(0), total }
+    }
+
+    pub fn add(&self, amount: u64) {
+        self.done.fetch_add(amount, Ordering::Relaxed);
+    }
+
+    /// Mark the work finished
+    pub fn finish(&self) {
+        self.done.store(self.total, Ordering::Relaxed);
+    }
+
+    /// Completed fraction, `None` if it can't be measured
+    pub fn fraction(&self) -> Option<f32> {
+        (self.total > 0).then(|| (self.done.load(Ordering::Relaxed) as f32 / self.total as f32).min(1.0))
+    }
+}
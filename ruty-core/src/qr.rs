@@ -0,0 +1,89 @@
+//! QR codes
+//!
+//! `/qr` renders text or a URL as a QR code PNG (to show, copy or save), and
+//! codes in a screenshot are read back in-process with rqrr.
+
+use std::io::Cursor;
+
+use image::{GrayImage, ImageOutputFormat, Luma};
+use qrcode::{Color, QrCode};
+
+/// Pixels per module (black or white square) of a rendered code
+const SCALE: u32 = 8;
+/// Blank modules around the code, which scanners need to find it
+const QUIET_ZONE: u32 = 4;
+
+/// `text` as a QR code PNG
+pub fn render(text: &str) -> Result<Vec<u8>, String> {
+    let code = QrCode::new(text.as_bytes()).map_err(|e| format!("Can't encode as a QR code: {}", e))?;
+    let width = code.width() as u32;
+    let colors = code.to_colors();
+    let size = (width + 2 * QUIET_ZONE) * SCALE;
+    let image = GrayImage::from_fn(size, size, |x, y| {
+        let (col, row) = ((x / SCALE).checked_sub(QUIET_ZONE), (y / SCALE).checked_sub(QUIET_ZONE));
+        let dark = match (col, row) {
+            (Some(col), Some(row)) if col < width && row < width => colors[(row * width + col) as usize] == Color::Dark,
+            _ => false,
+        };
+        Luma([if dark { 0 } else { 255 }])
+    });
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+        .map_err(|e| format!("Can't render the QR code: {}", e))?;
+    Ok(png)
+}
+
+/// Contents of the QR codes in a PNG, duplicates dropped
+pub fn scan(png: &[u8]) -> Result<Vec<String>, String> {
+    let image = image::load_from_memory(png)
+        .map_err(|e| format!("Cannot read the capture: {}", e))?
+        .to_luma8();
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let mut prepared =
+        rqrr::PreparedImage::prepare_from_greyscale(width, height, |x, y| image.get_pixel(x as u32, y as u32).0[0]);
+    let grids = prepared.detect_grids();
+    if grids.is_empty() {
+        return Err("No QR code found in the selection".to_string());
+    }
+    let mut codes: Vec<String> = Vec::new();
+    for grid in grids {
+        match grid.decode() {
+            Ok((_, content)) if !codes.contains(&content) => codes.push(content),
+            Ok(_) => {}
+            Err(e) => tracing::debug!("Unreadable QR code: {}", e),
+        }
+    }
+    if codes.is_empty() {
+        return Err("Found a QR code but couldn't read it; try a larger selection".to_string());
+    }
+    Ok(codes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let png = render("https://example.com").unwrap();
+        let image = image::load_from_memory(&png).unwrap().to_luma8();
+        let width = QrCode::new("https://example.com").unwrap().width() as u32;
+        assert_eq!(image.width(), (width + 2 * QUIET_ZONE) * SCALE);
+        // Quiet zone, then the top-left finder pattern's dark corner
+        assert_eq!(image.get_pixel(0, 0), &Luma([255]));
+        assert_eq!(image.get_pixel(QUIET_ZONE * SCALE, QUIET_ZONE * SCALE), &Luma([0]));
+    }
+
+    #[test]
+    fn test_scan_reads_rendered_code() {
+        let text = "WIFI:S:home;T:WPA;P:secret;;";
+        assert_eq!(scan(&render(text).unwrap()).unwrap(), vec![text]);
+
+        let mut blank = Vec::new();
+        GrayImage::from_pixel(64, 64, Luma([255]))
+            .write_to(&mut Cursor::new(&mut blank), ImageOutputFormat::Png)
+            .unwrap();
+        assert_eq!(scan(&blank), Err("No QR code found in the selection".to_string()));
+    }
+}
@@ -0,0 +1,289 @@
+//! Query language for file search
+//!
+//! A file search can narrow by metadata with `key:value` words mixed in with
+//! the name to look for, e.g. `kind:pdf modified:today size:>10mb report`:
+//!
+//! - `kind:` a group of file types (`image`, `document`, `archive`, …),
+//!   `folder`, or else an extension (`kind:pdf`)
+//! - `ext:` one or more extensions, comma-separated (`ext:rs,toml`)
+//! - `modified:` `today`, `yesterday`, `week`, `month`, `year`, a span back
+//!   from now (`3d`, `2w`, `12h`), a date (`2024-05-01`), or a date with `>`
+//!   (after) or `<` (before)
+//! - `size:` `>` or `<` a size in bytes, kb, mb or gb (`size:>10mb`); a bare
+//!   size means at least that
+//!
+//! Any other word, including one with an unknown key, is part of the name.
+//! `FileSearcher::search_query` hands the filters to fd or find.
+
+use jiff::civil::Date;
+use jiff::{ToSpan, Zoned};
+
+/// Filter keys with what they take, for completion
+pub const KEYS: &[(&str, &str)] = &[
+    ("kind:", "image, document, folder, pdf…"),
+    ("modified:", "today, week, 3d, >2024-05-01…"),
+    ("size:", ">10mb, <100kb…"),
+    ("ext:", "rs,toml…"),
+];
+
+/// `kind:` groups and their extensions
+const KINDS: &[(&str, &[&str])] = &[
+    ("image", &["png", "jpg", "jpeg", "gif", "webp", "bmp", "svg", "tif", "tiff", "heic"]),
+    ("video", &["mp4", "mkv", "webm", "mov", "avi"]),
+    ("audio", &["mp3", "flac", "ogg", "opus", "wav", "m4a"]),
+    ("document", &["pdf", "doc", "docx", "odt", "rtf", "txt", "md"]),
+    ("spreadsheet", &["xls", "xlsx", "ods", "csv"]),
+    ("presentation", &["ppt", "pptx", "odp"]),
+    ("archive", &["zip", "tar", "gz", "tgz", "xz", "bz2", "7z", "rar", "zst"]),
+    ("code", &["rs", "py", "js", "ts", "c", "h", "cpp", "go", "java", "rb", "sh"]),
+];
+const MODIFIED: &[&str] = &["today", "yesterday", "week", "month", "year"];
+const SIZES: &[&str] = &[">1mb", ">10mb", ">100mb", ">1gb", "<100kb"];
+
+/// A parsed file search
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileQuery {
+    /// What the name must contain (the words that aren't filters)
+    pub text: String,
+    /// Only files with one of these extensions (lowercase, no dot)
+    pub extensions: Vec<String>,
+    /// Only folders (`kind:folder`)
+    pub folders_only: bool,
+    /// Only files modified at or after this
+    pub modified_after: Option<Zoned>,
+    /// Only files modified before this
+    pub modified_before: Option<Zoned>,
+    pub size: Option<SizeFilter>,
+}
+
+/// `size:` bound, in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeFilter {
+    AtLeast(u64),
+    Below(u64),
+}
+
+impl FileQuery {
+    /// A search by name alone
+    pub fn name(text: &str) -> Self {
+        Self { text: text.trim().to_string(), ..Self::default() }
+    }
+
+    /// Parse a query, with `modified:` dates counted from now
+    pub fn parse(query: &str) -> Result<Self, String> {
+        Self::parse_at(query, &Zoned::now())
+    }
+
+    fn parse_at(query: &str, now: &Zoned) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut words = Vec::new();
+        for word in query.split_whitespace() {
+            let Some((key, value)) = word.split_once(':') else {
+                words.push(word);
+                continue;
+            };
+            let value = value.to_lowercase();
+            match key.to_lowercase().as_str() {
+                // Still being typed
+                "kind" | "ext" | "modified" | "size" if value.is_empty() => {}
+                "kind" => parsed.add_kind(&value)?,
+                "ext" => {
+                    for ext in value.split(',').map(|ext| ext.trim_start_matches('.')).filter(|ext| !ext.is_empty()) {
+                        parsed.extensions.push(ext.to_string());
+                    }
+                }
+                "modified" => {
+                    let (after, before) = parse_modified(&value, now)?;
+                    parsed.modified_after = after.or(parsed.modified_after);
+                    parsed.modified_before = before.or(parsed.modified_before);
+                }
+                "size" => parsed.size = Some(parse_size(&value)?),
+                _ => words.push(word),
+            }
+        }
+        parsed.text = words.join(" ");
+        Ok(parsed)
+    }
+
+    /// Whether anything besides the name narrows the search
+    pub fn has_filters(&self) -> bool {
+        !self.extensions.is_empty()
+            || self.folders_only
+            || self.modified_after.is_some()
+            || self.modified_before.is_some()
+            || self.size.is_some()
+    }
+
+    fn add_kind(&mut self, value: &str) -> Result<(), String> {
+        for kind in value.split(',').filter(|kind| !kind.is_empty()) {
+            match kind {
+                "folder" | "folders" | "dir" | "directory" => self.folders_only = true,
+                kind => match KINDS.iter().find(|(name, _)| *name == kind || kind.strip_suffix('s') == Some(*name)) {
+                    Some((_, extensions)) => self.extensions.extend(extensions.iter().map(|ext| ext.to_string())),
+                    None if kind.chars().all(|c| c.is_ascii_alphanumeric()) => self.extensions.push(kind.to_string()),
+                    None => return Err(format!("Unknown kind: {}", kind)),
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bounds of a `modified:` value: (at or after, before)
+fn parse_modified(value: &str, now: &Zoned) -> Result<(Option<Zoned>, Option<Zoned>), String> {
+    let unknown = || format!("Unknown date in modified:{} (try today, week, 3d or 2024-05-01)", value);
+    let today = now.start_of_day().map_err(|e| e.to_string())?;
+    let ago = |days: i64| today.checked_sub(days.days()).map_err(|e| e.to_string());
+    let day = |text: &str| -> Result<Zoned, String> {
+        let date: Date = text.parse().map_err(|_| unknown())?;
+        date.to_zoned(now.time_zone().clone()).map_err(|e| e.to_string())
+    };
+    Ok(match value {
+        "today" => (Some(today), None),
+        "yesterday" => (Some(ago(1)?), Some(today)),
+        "week" => (Some(ago(7)?), None),
+        "month" => (Some(ago(30)?), None),
+        "year" => (Some(ago(365)?), None),
+        _ if value.starts_with('>') => (Some(day(&value[1..])?.tomorrow().map_err(|e| e.to_string())?), None),
+        _ if value.starts_with('<') => (None, Some(day(&value[1..])?)),
+        _ if value.starts_with(|c: char| c.is_ascii_digit()) && value.contains('-') => {
+            let start = day(value)?;
+            let end = start.tomorrow().map_err(|e| e.to_string())?;
+            (Some(start), Some(end))
+        }
+        _ => {
+            let (split, unit) = value.char_indices().last().ok_or_else(unknown)?;
+            let count: i64 = value[..split].parse().map_err(|_| unknown())?;
+            let span = match unit {
+                'h' => count.hours(),
+                'd' => count.days(),
+                'w' => count.weeks(),
+                _ => return Err(unknown()),
+            };
+            (Some(now.checked_sub(span).map_err(|e| e.to_string())?), None)
+        }
+    })
+}
+
+fn parse_size(value: &str) -> Result<SizeFilter, String> {
+    let invalid = || format!("Unknown size in size:{} (try >10mb or <100kb)", value);
+    let (below, amount) = match value.strip_prefix('<') {
+        Some(amount) => (true, amount),
+        None => (false, value.strip_prefix('>').unwrap_or(value)),
+    };
+    let amount = amount.trim_end_matches('b');
+    let (number, unit) = match amount.char_indices().last() {
+        Some((i, unit @ ('k' | 'm' | 'g'))) => (&amount[..i], Some(unit)),
+        _ => (amount, None),
+    };
+    let number: f64 = number.parse().map_err(|_| invalid())?;
+    let scale = match unit {
+        Some('k') => 1024.0,
+        Some('m') => 1024.0 * 1024.0,
+        Some('g') => 1024.0 * 1024.0 * 1024.0,
+        _ => 1.0,
+    };
+    if number < 0.0 {
+        return Err(invalid());
+    }
+    let bytes = (number * scale) as u64;
+    Ok(if below { SizeFilter::Below(bytes) } else { SizeFilter::AtLeast(bytes) })
+}
+
+/// Completions for the word being typed: filter keys it starts, or values
+/// of the key it names, with a hint for each
+pub fn complete(word: &str) -> Vec<(String, &'static str)> {
+    let lower = word.to_lowercase();
+    let Some((key, value)) = lower.split_once(':') else {
+        return KEYS
+            .iter()
+            .filter(|(key, _)| !lower.is_empty() && key.starts_with(&lower))
+            .map(|(key, hint)| (key.to_string(), *hint))
+            .collect();
+    };
+    let values: Vec<&str> = match key {
+        "kind" => std::iter::once("folder").chain(KINDS.iter().map(|(name, _)| *name)).chain(["pdf"]).collect(),
+        "modified" => MODIFIED.to_vec(),
+        "size" => SIZES.to_vec(),
+        _ => Vec::new(),
+    };
+    values
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(value) && *candidate != value)
+        .map(|candidate| (format!("{}:{}", key, candidate), ""))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::tz::TimeZone;
+
+    fn now() -> Zoned {
+        "2024-05-15T14:30:00[UTC]".parse().unwrap()
+    }
+
+    fn at(text: &str) -> Option<Zoned> {
+        Some(format!("{}[UTC]", text).parse().unwrap())
+    }
+
+    #[test]
+    fn test_parse() {
+        let query = FileQuery::parse_at("kind:pdf modified:today size:>10mb quarterly report", &now()).unwrap();
+        assert_eq!(query.text, "quarterly report");
+        assert_eq!(query.extensions, ["pdf"]);
+        assert_eq!(query.modified_after, at("2024-05-15T00:00"));
+        assert_eq!(query.modified_before, None);
+        assert_eq!(query.size, Some(SizeFilter::AtLeast(10 * 1024 * 1024)));
+        assert!(query.has_filters());
+
+        let query = FileQuery::parse_at("Kind:Images ext:.rs,toml notes", &now()).unwrap();
+        assert!(query.extensions.contains(&"png".to_string()));
+        assert!(query.extensions.ends_with(&["rs".to_string(), "toml".to_string()]));
+        assert!(FileQuery::parse_at("kind:folder", &now()).unwrap().folders_only);
+        assert_eq!(FileQuery::parse_at("report size:", &now()), Ok(FileQuery::name("report")));
+
+        // Unknown keys are part of the name
+        let query = FileQuery::parse_at("meeting 10:30 notes", &now()).unwrap();
+        assert_eq!(query, FileQuery::name("meeting 10:30 notes"));
+        assert!(!query.has_filters());
+    }
+
+    #[test]
+    fn test_modified_and_size() {
+        let bounds = |value: &str| {
+            let query = FileQuery::parse_at(&format!("modified:{}", value), &now()).unwrap();
+            (query.modified_after, query.modified_before)
+        };
+        assert_eq!(bounds("yesterday"), (at("2024-05-14T00:00"), at("2024-05-15T00:00")));
+        assert_eq!(bounds("week"), (at("2024-05-08T00:00"), None));
+        assert_eq!(bounds("3d"), (at("2024-05-12T14:30"), None));
+        assert_eq!(bounds("2024-05-01"), (at("2024-05-01T00:00"), at("2024-05-02T00:00")));
+        assert_eq!(bounds(">2024-05-01"), (at("2024-05-02T00:00"), None));
+        assert_eq!(bounds("<2024-05-01"), (None, at("2024-05-01T00:00")));
+        assert!(FileQuery::parse_at("modified:someday", &now()).is_err());
+
+        // Days start at midnight where the user is
+        let local = now().with_time_zone(TimeZone::fixed(jiff::tz::offset(2)));
+        let query = FileQuery::parse_at("modified:today", &local).unwrap();
+        assert_eq!(query.modified_after.unwrap().timestamp(), "2024-05-14T22:00:00Z".parse().unwrap());
+
+        let size = |value: &str| FileQuery::parse_at(&format!("size:{}", value), &now()).map(|query| query.size.unwrap());
+        assert_eq!(size("<100kb"), Ok(SizeFilter::Below(100 * 1024)));
+        assert_eq!(size("1.5g"), Ok(SizeFilter::AtLeast(1024 * 1024 * 1536)));
+        assert_eq!(size(">512"), Ok(SizeFilter::AtLeast(512)));
+        assert!(size("big").is_err());
+    }
+
+    #[test]
+    fn test_complete() {
+        let completions = |word: &str| complete(word).into_iter().map(|(text, _)| text).collect::<Vec<_>>();
+        assert_eq!(completions("k"), ["kind:"]);
+        assert_eq!(completions("Mod"), ["modified:"]);
+        assert_eq!(completions("kind:im"), ["kind:image"]);
+        assert_eq!(completions("modified:y"), ["modified:yesterday", "modified:year"]);
+        assert!(completions("").is_empty());
+        assert!(completions("kind:image").is_empty());
+        assert!(completions("ext:r").is_empty());
+    }
+}
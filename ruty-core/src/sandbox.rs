@@ -0,0 +1,205 @@
+//! Sandboxing shell commands from config
+//!
+//! Flow `shell` steps and hooks run commands from config.toml, which would
+//! otherwise have the user's full privileges. A `[sandbox]` profile wraps
+//! them in bubblewrap or firejail (whichever is installed): the file system
+//! is read-only apart from `writable` and a private `/tmp`, the network is
+//! cut off unless `network` is set, and only the `env` variables are passed
+//! on. Without either tool (or with `tool = "env"`) only the environment is
+//! cut down, which the `/flow` picker and `ruty doctor` point out.
+//!
+//! A flow's own `sandbox` table, or `[hooks.sandbox]` for hooks, replaces
+//! `[sandbox]` for that provider:
+//!
+//! ```toml
+//! [sandbox]
+//! enabled = true
+//!
+//! [flows.backup]
+//! steps = [{ shell = "restic backup ~/Documents" }]
+//! sandbox = { enabled = true, network = true, writable = ["~/.cache/restic"] }
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+/// How shell commands of one provider run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sandbox {
+    /// Wrap the commands at all; off, they run as plain `sh -c`
+    pub enabled: bool,
+    /// `auto` (bwrap, else firejail, else `env`), `bwrap`, `firejail`, or
+    /// `env` for a cut-down environment only
+    pub tool: String,
+    /// Allow network access
+    pub network: bool,
+    /// Environment variables passed on; the rest are dropped
+    pub env: Vec<String>,
+    /// Paths the commands may write to (`~` is expanded)
+    pub writable: Vec<String>,
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tool: "auto".to_string(),
+            network: false,
+            env: ["PATH", "HOME", "USER", "LANG", "LC_ALL", "TERM", "DISPLAY", "WAYLAND_DISPLAY", "XDG_RUNTIME_DIR"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            writable: Vec::new(),
+        }
+    }
+}
+
+/// What a sandbox runs commands in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    Bubblewrap,
+    Firejail,
+    /// No isolation tool: only the environment is cut down
+    Env,
+}
+
+impl Tool {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Bubblewrap => "bwrap",
+            Self::Firejail => "firejail",
+            Self::Env => "env",
+        }
+    }
+}
+
+/// The tool `configured` asks for; `has` tells which programs are installed.
+/// A tool asked for by name must be installed, so a missing one fails the
+/// command instead of running it without isolation.
+pub fn pick_tool(configured: &str, has: impl Fn(&str) -> bool) -> Result<Tool, String> {
+    match configured.trim() {
+        "" | "auto" if has("bwrap") => Ok(Tool::Bubblewrap),
+        "" | "auto" if has("firejail") => Ok(Tool::Firejail),
+        "" | "auto" | "env" => Ok(Tool::Env),
+        "bwrap" | "bubblewrap" if has("bwrap") => Ok(Tool::Bubblewrap),
+        "firejail" if has("firejail") => Ok(Tool::Firejail),
+        tool @ ("bwrap" | "bubblewrap" | "firejail") => {
+            Err(format!("Sandbox tool {} is not installed; install it or set [sandbox] tool = \"auto\"", tool))
+        }
+        other => Err(format!("Unknown sandbox tool '{}' (expected auto, bwrap, firejail or env)", other)),
+    }
+}
+
+/// Why commands under `sandbox` aren't fully isolated, if they aren't:
+/// shown next to the providers that run them
+pub fn warning(sandbox: &Sandbox, has: impl Fn(&str) -> bool) -> Option<String> {
+    if !sandbox.enabled {
+        return Some("⚠ unsandboxed".to_string());
+    }
+    match pick_tool(&sandbox.tool, has) {
+        Ok(Tool::Env) => Some("⚠ environment-only sandbox".to_string()),
+        Ok(_) => None,
+        Err(e) => Some(format!("⚠ {}", e)),
+    }
+}
+
+/// Arguments that run `script` with `sh -c` in `tool`; `writable` are the
+/// expanded paths that exist
+pub fn wrap_args(tool: Tool, sandbox: &Sandbox, writable: &[PathBuf], home: &Path, script: &str) -> Vec<String> {
+    let path = |path: &Path| path.display().to_string();
+    let mut args: Vec<String> = match tool {
+        Tool::Bubblewrap => {
+            let mut args: Vec<String> = ["bwrap", "--ro-bind", "/", "/", "--dev", "/dev", "--proc", "/proc", "--tmpfs", "/tmp"]
+                .into_iter()
+                .map(String::from)
+                .collect();
+            for dir in writable {
+                args.extend(["--bind".to_string(), path(dir), path(dir)]);
+            }
+            if !sandbox.network {
+                args.push("--unshare-net".to_string());
+            }
+            args.extend(["--die-with-parent".to_string(), "--".to_string()]);
+            args
+        }
+        Tool::Firejail => {
+            let mut args = vec!["firejail".to_string(), "--quiet".to_string(), "--private-tmp".to_string()];
+            args.push(format!("--read-only={}", path(home)));
+            args.extend(writable.iter().map(|dir| format!("--read-write={}", path(dir))));
+            if !sandbox.network {
+                args.push("--net=none".to_string());
+            }
+            args
+        }
+        Tool::Env => Vec::new(),
+    };
+    args.extend(["sh".to_string(), "-c".to_string(), script.to_string()]);
+    args
+}
+
+/// A command running `script` with `sh -c` under `sandbox`
+pub fn shell_command(sandbox: &Sandbox, script: &str) -> Result<Command, String> {
+    if !sandbox.enabled {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(script);
+        return Ok(command);
+    }
+    let tool = pick_tool(&sandbox.tool, crate::doctor::has_program)?;
+    let writable: Vec<PathBuf> =
+        sandbox.writable.iter().map(|path| config::expand_home(path)).filter(|path| path.exists()).collect();
+    let home = config::expand_home("~");
+    let args = wrap_args(tool, sandbox, &writable, &home, script);
+    let mut command = Command::new(&args[0]);
+    command.args(&args[1..]).env_clear();
+    for name in &sandbox.env {
+        if let Some(value) = std::env::var_os(name) {
+            command.env(name, value);
+        }
+    }
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_tool() {
+        assert_eq!(pick_tool("auto", |name| name == "firejail"), Ok(Tool::Firejail));
+        assert_eq!(pick_tool("auto", |_| true), Ok(Tool::Bubblewrap));
+        assert_eq!(pick_tool("", |_| false), Ok(Tool::Env));
+        // Asked for by name, a missing tool doesn't fall back to nothing
+        assert!(pick_tool("bwrap", |_| false).is_err());
+        assert!(pick_tool("docker", |_| true).is_err());
+
+        let sandbox = Sandbox { enabled: true, ..Sandbox::default() };
+        assert_eq!(warning(&sandbox, |_| true), None);
+        assert_eq!(warning(&sandbox, |_| false).as_deref(), Some("⚠ environment-only sandbox"));
+        assert_eq!(warning(&Sandbox::default(), |_| true).as_deref(), Some("⚠ unsandboxed"));
+    }
+
+    #[test]
+    fn test_wrap_args() {
+        let sandbox = Sandbox { enabled: true, ..Sandbox::default() };
+        let writable = [PathBuf::from("/home/me/out")];
+        let home = Path::new("/home/me");
+        let bwrap = wrap_args(Tool::Bubblewrap, &sandbox, &writable, home, "ls ~");
+        assert_eq!(&bwrap[..2], ["bwrap", "--ro-bind"]);
+        assert!(bwrap.windows(3).any(|args| args == ["--bind", "/home/me/out", "/home/me/out"]));
+        assert!(bwrap.contains(&"--unshare-net".to_string()));
+        assert_eq!(&bwrap[bwrap.len() - 4..], ["--", "sh", "-c", "ls ~"]);
+
+        let online = Sandbox { network: true, ..sandbox.clone() };
+        let firejail = wrap_args(Tool::Firejail, &online, &writable, home, "ls");
+        assert_eq!(
+            firejail,
+            ["firejail", "--quiet", "--private-tmp", "--read-only=/home/me", "--read-write=/home/me/out", "sh", "-c", "ls"]
+        );
+        assert_eq!(wrap_args(Tool::Env, &sandbox, &[], home, "ls"), ["sh", "-c", "ls"]);
+    }
+}
@@ -0,0 +1,51 @@
+//! Scratchpad notes
+//!
+//! The text of the `/pad` window, kept in one plain text file that's
+//! rewritten on every edit. Writes go through a temp file and a rename, so
+//! a crash mid-write leaves the previous text rather than half of it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+
+/// Path of the scratchpad file (user data, unlike the state files)
+pub fn path() -> PathBuf {
+    paths::data_dir().join("scratchpad.txt")
+}
+
+/// The saved text (empty if there's none yet)
+pub fn load() -> String {
+    fs::read_to_string(path()).unwrap_or_default()
+}
+
+/// Replace the saved text
+pub fn save(text: &str) -> Result<(), String> {
+    write(&path(), text)
+}
+
+fn write(path: &Path, text: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let tmp = path.with_extension("txt.tmp");
+    fs::write(&tmp, text).map_err(|e| format!("Failed to write scratchpad: {}", e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("Failed to write scratchpad: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_replaces_text() {
+        let dir = std::env::temp_dir().join(format!("ruty-pad-{}", std::process::id()));
+        let file = dir.join("nested").join("scratchpad.txt");
+
+        write(&file, "first draft\nwith two lines").unwrap();
+        write(&file, "rewritten").unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "rewritten");
+        assert!(!file.with_extension("txt.tmp").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,82 @@
+//! Volatile state snapshots
+//!
+//! Clipboard history and the app cache only live in memory. A snapshot
+//! captures them so a crashed daemon can pick up where it left off. It
+//! holds clipboard text, so only the user can read it.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::apps::Application;
+use crate::clipboard::ClipboardItem;
+use crate::paths;
+
+/// Point-in-time copy of the daemon's in-memory state
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Snapshot {
+    /// Unix time the snapshot was taken
+    pub timestamp: u64,
+    pub clipboard: Vec<ClipboardItem>,
+    pub apps: Vec<Application>,
+}
+
+impl Snapshot {
+    pub fn new(clipboard: Vec<ClipboardItem>, apps: Vec<Application>) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            clipboard,
+            apps,
+        }
+    }
+
+    /// Path of the snapshot file
+    pub fn path() -> PathBuf {
+        paths::state_dir().join("snapshot.json")
+    }
+
+    /// Load the last snapshot, if any
+    pub fn load() -> Option<Self> {
+        let content = fs::read_to_string(Self::path()).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Write the snapshot atomically (temp file + rename), readable and
+    /// writable only by the user
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create state dir: {}", e))?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        let content = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        let written = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&tmp)
+            .and_then(|mut file| {
+                // A temp file left by an older version may be more open
+                file.set_permissions(fs::Permissions::from_mode(0o600))?;
+                file.write_all(content.as_bytes())
+            });
+        written.map_err(|e| format!("Failed to write snapshot: {}", e))?;
+        fs::rename(&tmp, &path).map_err(|e| format!("Failed to write snapshot: {}", e))
+    }
+
+    /// Delete the snapshot; there being none is fine
+    pub fn remove() -> Result<(), String> {
+        match fs::remove_file(Self::path()) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(format!("Failed to delete snapshot: {}", e)),
+            _ => Ok(()),
+        }
+    }
+}
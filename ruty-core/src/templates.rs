@@ -0,0 +1,70 @@
+//! Prompt templates
+//!
+//! `[templates]` in config.toml maps names to prompts that `/tpl <name>
+//! [text]` expands before asking the AI. Placeholders: `{input}` is the text
+//! typed after the name, `{clipboard}` the clipboard, `{selection}` the
+//! primary selection. Text typed for a template without `{input}` is
+//! appended to it. Other braces are left alone.
+
+/// Expand `template`; `clipboard` and `selection` are only read when the
+/// template uses them
+pub fn expand(
+    template: &str,
+    input: &str,
+    clipboard: impl FnOnce() -> Option<String>,
+    selection: impl FnOnce() -> Option<String>,
+) -> Result<String, String> {
+    let input = input.trim();
+    let mut prompt = template.to_string();
+
+    if prompt.contains("{input}") {
+        if input.is_empty() {
+            return Err("This template needs text: /tpl <name> <text>".to_string());
+        }
+        prompt = prompt.replace("{input}", input);
+    } else if !input.is_empty() {
+        prompt = format!("{}\n\n{}", prompt.trim_end(), input);
+    }
+    if prompt.contains("{clipboard}") {
+        let text = clipboard().filter(|text| !text.trim().is_empty()).ok_or("The clipboard is empty")?;
+        prompt = prompt.replace("{clipboard}", &text);
+    }
+    if prompt.contains("{selection}") {
+        let text = selection().filter(|text| !text.trim().is_empty()).ok_or("Nothing is selected")?;
+        prompt = prompt.replace("{selection}", &text);
+    }
+    Ok(prompt)
+}
+
+/// First line of a template, for the picker
+pub fn preview(template: &str) -> &str {
+    template.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn none() -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_expand() {
+        let clip = || Some("bonjour".to_string());
+        assert_eq!(
+            expand("Translate to English: {input}", "hola", none, none).unwrap(),
+            "Translate to English: hola"
+        );
+        assert_eq!(expand("Translate: {clipboard}", "", clip, none).unwrap(), "Translate: bonjour");
+        assert_eq!(expand("Summarize:\n", "some text", none, none).unwrap(), "Summarize:\n\nsome text");
+        assert_eq!(expand("Keep {braces}", "", none, none).unwrap(), "Keep {braces}");
+    }
+
+    #[test]
+    fn test_missing_values() {
+        assert!(expand("Fix: {input}", " ", none, none).is_err());
+        assert_eq!(expand("Explain {selection}", "", none, none), Err("Nothing is selected".to_string()));
+        assert!(expand("{clipboard}", "", || Some("  ".to_string()), none).is_err());
+    }
+}
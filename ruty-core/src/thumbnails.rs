@@ -0,0 +1,179 @@
+//! Thumbnails for image and video file results
+//!
+//! Images are scaled with the image crate, videos (and images it can't
+//! decode) go through ffmpegthumbnailer. Thumbnails are PNGs cached under
+//! `$XDG_CACHE_HOME/ruty/thumbs`, keyed by path, size and mtime so an edited
+//! file gets a fresh one.
+
+use md5::{Digest, Md5};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::UNIX_EPOCH;
+
+use crate::paths;
+
+/// Longest edge of a generated thumbnail, in pixels
+pub const THUMB_SIZE: u32 = 64;
+
+/// Files larger than this aren't decoded in-process
+const MAX_IMAGE_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKind {
+    Image,
+    Video,
+}
+
+impl MediaKind {
+    /// Detect a thumbnailable file from its extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" | "ico" | "tif" | "tiff" | "heic"
+            | "avif" => Some(Self::Image),
+            "mp4" | "mkv" | "webm" | "mov" | "avi" | "m4v" | "wmv" | "flv" => Some(Self::Video),
+            _ => None,
+        }
+    }
+}
+
+/// Directory holding cached thumbnails
+pub fn cache_dir() -> PathBuf {
+    paths::cache_dir().join("thumbs")
+}
+
+/// Where the thumbnail for `path` is (or would be) cached
+fn cache_path(path: &Path) -> Result<PathBuf, String> {
+    let meta = fs::metadata(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let key = format!("{}\0{}\0{}", path.display(), meta.len(), mtime);
+    let digest = Md5::digest(key.as_bytes());
+    Ok(cache_dir().join(format!("{:x}.png", digest)))
+}
+
+/// Cached thumbnail for `path`, generating it if needed (blocking)
+pub fn thumbnail(path: &Path) -> Result<PathBuf, String> {
+    let kind = MediaKind::from_path(path)
+        .ok_or_else(|| format!("No thumbnail for {}", path.display()))?;
+    let out = cache_path(path)?;
+    if out.exists() {
+        return Ok(out);
+    }
+    fs::create_dir_all(cache_dir()).map_err(|e| format!("Cannot create thumbnail cache: {}", e))?;
+
+    let scaled = match kind {
+        MediaKind::Image => scale_image(path, &out),
+        MediaKind::Video => Err(String::from("video")),
+    };
+    if let Err(e) = scaled {
+        if kind == MediaKind::Image {
+            tracing::debug!("Decoding {} failed ({}), trying ffmpegthumbnailer", path.display(), e);
+        }
+        ffmpegthumbnailer(path, &out)?;
+    }
+    Ok(out)
+}
+
+/// Approximate memory a loaded thumbnail takes: its PNG plus the decoded
+/// RGBA pixels the renderer keeps while it's shown
+pub fn memory_size(png_bytes: usize) -> usize {
+    png_bytes + (THUMB_SIZE * THUMB_SIZE * 4) as usize
+}
+
+/// Total size of the on-disk thumbnail cache
+pub fn disk_usage() -> u64 {
+    fs::read_dir(cache_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| entry.metadata().ok())
+                .map(|meta| meta.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Delete the on-disk thumbnail cache, returning the bytes freed
+pub fn clear_disk_cache() -> Result<u64, String> {
+    let freed = disk_usage();
+    match fs::remove_dir_all(cache_dir()) {
+        Ok(()) => Ok(freed),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(format!("Cannot clear thumbnail cache: {}", e)),
+    }
+}
+
+fn scale_image(path: &Path, out: &Path) -> Result<(), String> {
+    let size = fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if size > MAX_IMAGE_BYTES {
+        return Err(format!("{} MB image", size / (1024 * 1024)));
+    }
+    let img = image::open(path).map_err(|e| e.to_string())?;
+    img.thumbnail(THUMB_SIZE, THUMB_SIZE)
+        .save_with_format(out, image::ImageFormat::Png)
+        .map_err(|e| format!("Cannot write thumbnail: {}", e))
+}
+
+fn ffmpegthumbnailer(path: &Path, out: &Path) -> Result<(), String> {
+    let output = Command::new("ffmpegthumbnailer")
+        .arg("-i")
+        .arg(path)
+        .arg("-o")
+        .arg(out)
+        .args(["-s", &THUMB_SIZE.to_string(), "-c", "png"])
+        .output()
+        .map_err(|e| format!("ffmpegthumbnailer unavailable: {}", e))?;
+    if !output.status.success() || !out.exists() {
+        let _ = fs::remove_file(out);
+        return Err(format!(
+            "ffmpegthumbnailer failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_from_path() {
+        assert_eq!(MediaKind::from_path(Path::new("a/Photo.JPG")), Some(MediaKind::Image));
+        assert_eq!(MediaKind::from_path(Path::new("clip.mkv")), Some(MediaKind::Video));
+        assert_eq!(MediaKind::from_path(Path::new("notes.txt")), None);
+    }
+
+    #[test]
+    fn test_cache_path_changes_with_content() {
+        let path = std::env::temp_dir().join(format!("ruty-thumb-{}.png", std::process::id()));
+        fs::write(&path, b"one").unwrap();
+        let first = cache_path(&path).unwrap();
+        assert_eq!(cache_path(&path).unwrap(), first);
+        fs::write(&path, b"longer").unwrap();
+        let second = cache_path(&path).unwrap();
+        fs::remove_file(&path).ok();
+        assert_ne!(first, second);
+        assert!(first.starts_with(cache_dir()));
+    }
+
+    #[test]
+    fn test_image_thumbnail() {
+        let dir = std::env::temp_dir().join(format!("ruty-thumbs-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("big.png");
+        image::RgbImage::new(200, 100).save(&source).unwrap();
+        let out = dir.join("thumb.png");
+
+        scale_image(&source, &out).unwrap();
+        let thumb = image::open(&out).unwrap();
+        fs::remove_dir_all(&dir).ok();
+        assert_eq!((thumb.width(), thumb.height()), (THUMB_SIZE, THUMB_SIZE / 2));
+    }
+}
@@ -0,0 +1,66 @@
+//! Day grouping for `/timeline`
+//!
+//! The timeline lists launched apps, opened files, clipboard captures and
+//! AI chats newest first, under a header for each day. Times are Unix
+//! seconds (as `usage` and the clipboard history keep them), shown in the
+//! local time zone.
+
+use jiff::tz::TimeZone;
+use jiff::{Timestamp, Zoned};
+
+/// Header of the day `time` falls on: "Today", "Yesterday", a weekday within
+/// the last week, otherwise the date ("Fri 17 Oct", with the year if it
+/// isn't this one)
+pub fn day_label(time: u64) -> String {
+    day_label_at(time, &Zoned::now())
+}
+
+/// Time of day of `time`, e.g. "14:05"
+pub fn clock(time: u64) -> String {
+    local(time, &TimeZone::system()).map_or_else(String::new, |zoned| zoned.strftime("%H:%M").to_string())
+}
+
+fn day_label_at(time: u64, now: &Zoned) -> String {
+    let Some(zoned) = local(time, now.time_zone()) else {
+        return String::new();
+    };
+    let (date, today) = (zoned.date(), now.date());
+    if date == today {
+        "Today".to_string()
+    } else if today.yesterday().is_ok_and(|yesterday| yesterday == date) {
+        "Yesterday".to_string()
+    } else if date < today && today.since(date).is_ok_and(|span| span.get_days() < 7) {
+        zoned.strftime("%A").to_string()
+    } else if date.year() == today.year() {
+        format!("{} {} {}", zoned.strftime("%a"), date.day(), zoned.strftime("%b"))
+    } else {
+        format!("{} {} {} {}", zoned.strftime("%a"), date.day(), zoned.strftime("%b"), date.year())
+    }
+}
+
+fn local(time: u64, tz: &TimeZone) -> Option<Zoned> {
+    let seconds = i64::try_from(time).ok()?;
+    Some(Timestamp::from_second(seconds).ok()?.to_zoned(tz.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_label() {
+        let now: Zoned = "2026-10-16T15:00[UTC]".parse().unwrap();
+        let at = |stamp: &str| {
+            let zoned: Zoned = stamp.parse().unwrap();
+            day_label_at(zoned.timestamp().as_second() as u64, &now)
+        };
+        assert_eq!(at("2026-10-16T00:05[UTC]"), "Today");
+        assert_eq!(at("2026-10-15T23:59[UTC]"), "Yesterday");
+        assert_eq!(at("2026-10-12T09:00[UTC]"), "Monday");
+        assert_eq!(at("2026-10-02T09:00[UTC]"), "Fri 2 Oct");
+        assert_eq!(at("2025-12-31T09:00[UTC]"), "Wed 31 Dec 2025");
+        // Days are local: 23:30 UTC is already the next day two hours east
+        let east = now.with_time_zone(TimeZone::fixed(jiff::tz::offset(2)));
+        assert_eq!(day_label_at(now.timestamp().as_second() as u64 - 15 * 3600 - 1800, &east), "Today");
+    }
+}
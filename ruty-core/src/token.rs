@@ -0,0 +1,110 @@
+//! Per-user token for the daemon's local APIs
+//!
+//! The gRPC and HTTP APIs listen on 127.0.0.1, where any local user (or a
+//! page the browser was tricked into loading) could reach them. Clients
+//! prove they run as the daemon's user by sending the token kept in
+//! `rpc-token` in the state dir, which only that user can read. Whichever
+//! side needs it first creates it, so a socket-activated daemon and its
+//! first client agree on it.
+
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+
+pub fn path() -> PathBuf {
+    paths::state_dir().join("rpc-token")
+}
+
+/// The token, created if there's none yet
+pub fn get() -> Result<String, String> {
+    load_or_create(&path())
+}
+
+/// `Bearer <token>`, as clients send it in `authorization`
+pub fn bearer(token: &str) -> String {
+    format!("Bearer {}", token)
+}
+
+/// Whether a client sent `expected` (a `bearer`); compared in constant time,
+/// so response timing doesn't give the token away a byte at a time
+pub fn matches(sent: Option<&str>, expected: &str) -> bool {
+    let sent = sent.unwrap_or_default();
+    let differing = sent.bytes().zip(expected.bytes()).fold(0, |differing, (a, b)| differing | (a ^ b));
+    sent.len() == expected.len() && differing == 0
+}
+
+fn load_or_create(path: &Path) -> Result<String, String> {
+    if let Some(token) = read(path)? {
+        return Ok(token);
+    }
+    let parent = path.parent().ok_or_else(|| format!("No directory for {}", path.display()))?;
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+
+    // Written in full under a name of its own, then linked into place, so
+    // nobody reads it half-written and a client racing the daemon can't
+    // replace a token the other already uses
+    let token = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    let partial = path.with_extension(format!("{}.tmp", std::process::id()));
+    let written = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&partial)
+        .and_then(|mut file| file.write_all(token.as_bytes()))
+        .and_then(|()| fs::hard_link(&partial, path));
+    fs::remove_file(&partial).ok();
+    match written {
+        Ok(()) => Ok(token),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+            read(path)?.ok_or_else(|| format!("{} is empty", path.display()))
+        }
+        Err(e) => Err(format!("Failed to write {}: {}", path.display(), e)),
+    }
+}
+
+/// The token at `path`; refused if others could read it
+fn read(path: &Path) -> Result<Option<String>, String> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    if metadata.permissions().mode() & 0o077 != 0 {
+        return Err(format!("{} is readable by other users; delete it to get a new token", path.display()));
+    }
+    let token = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(Some(token.trim().to_string()).filter(|token| !token.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_or_create() {
+        let root = std::env::temp_dir().join(format!("ruty-token-{}", std::process::id()));
+        let path = root.join("rpc-token");
+        let token = load_or_create(&path).unwrap();
+        assert_eq!(token.len(), 64);
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(load_or_create(&path).unwrap(), token);
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 1);
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(load_or_create(&path).is_err());
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_matches() {
+        let expected = bearer("secret");
+        assert!(matches(Some("Bearer secret"), &expected));
+        assert!(!matches(Some("Bearer secreT"), &expected));
+        assert!(!matches(Some("Bearer secret2"), &expected));
+        assert!(!matches(Some("Bearer"), &expected));
+        assert!(!matches(None, &expected));
+    }
+}
@@ -0,0 +1,173 @@
+//! Text transforms for `/b64`, `/url`, `/json`, `/uuid` and `/lorem`
+//!
+//! Encoders and formatters take the typed text (or the clipboard, chosen by
+//! the caller); generators ignore their input.
+
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::{DecodePaddingMode, Engine};
+use base64::alphabet;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+/// Characters left alone when URL-encoding (RFC 3986 unreserved)
+const URL_UNRESERVED: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+/// Most paragraphs `/lorem` generates
+pub const MAX_LOREM: usize = 20;
+
+const LOREM: [&str; 5] = [
+    "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat.",
+    "Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit anim id est laborum.",
+    "Curabitur pretium tincidunt lacus. Nulla gravida orci a odio. Nullam varius, turpis et commodo pharetra, est eros bibendum elit, nec luctus magna felis sollicitudin mauris. Integer in mauris eu nibh euismod gravida.",
+    "Praesent dapibus, neque id cursus faucibus, tortor neque egestas augue, eu vulputate magna eros eu erat. Aliquam erat volutpat. Nam dui mi, tincidunt quis, accumsan porttitor, facilisis luctus, metus.",
+    "Phasellus ultrices nulla quis nibh. Quisque a lectus. Donec consectetuer ligula vulputate sem tristique cursus. Nam nulla quam, gravida non, commodo a, sodales sit amet, nisi.",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    Base64Encode,
+    Base64Decode,
+    UrlEncode,
+    UrlDecode,
+    JsonFormat,
+    JsonMinify,
+    Uuid,
+    /// Paragraphs of placeholder text
+    Lorem(usize),
+}
+
+impl Transform {
+    /// Parse the operation for a transform command, e.g. ("/b64", "decode")
+    pub fn parse(command: &str, op: &str) -> Option<Self> {
+        let op = op.to_lowercase();
+        match (command, op.as_str()) {
+            ("/b64" | "/base64", "encode" | "enc" | "e") => Some(Self::Base64Encode),
+            ("/b64" | "/base64", "decode" | "dec" | "d") => Some(Self::Base64Decode),
+            ("/url", "encode" | "enc" | "e") => Some(Self::UrlEncode),
+            ("/url", "decode" | "dec" | "d") => Some(Self::UrlDecode),
+            ("/json", "fmt" | "format" | "pretty") => Some(Self::JsonFormat),
+            ("/json", "min" | "minify" | "compact") => Some(Self::JsonMinify),
+            _ => None,
+        }
+    }
+
+    /// Whether the transform works on input text (rather than generating it)
+    pub fn takes_input(&self) -> bool {
+        !matches!(self, Self::Uuid | Self::Lorem(_))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Base64Encode => "Base64 encoded",
+            Self::Base64Decode => "Base64 decoded",
+            Self::UrlEncode => "URL encoded",
+            Self::UrlDecode => "URL decoded",
+            Self::JsonFormat => "Formatted JSON",
+            Self::JsonMinify => "Minified JSON",
+            Self::Uuid => "UUID v4",
+            Self::Lorem(_) => "Lorem ipsum",
+        }
+    }
+
+    pub fn apply(&self, input: &str) -> Result<String, String> {
+        match self {
+            Self::Base64Encode => Ok(lenient_base64(alphabet::STANDARD).encode(input)),
+            Self::Base64Decode => base64_decode(input),
+            Self::UrlEncode => Ok(utf8_percent_encode(input, URL_UNRESERVED).to_string()),
+            Self::UrlDecode => percent_decode_str(&input.trim().replace('+', " "))
+                .decode_utf8()
+                .map(|s| s.into_owned())
+                .map_err(|_| String::from("Decoded URL isn't valid UTF-8")),
+            Self::JsonFormat => parse_json(input).and_then(|v| serde_json::to_string_pretty(&v).map_err(|e| e.to_string())),
+            Self::JsonMinify => parse_json(input).and_then(|v| serde_json::to_string(&v).map_err(|e| e.to_string())),
+            Self::Uuid => Ok(uuid::Uuid::new_v4().to_string()),
+            Self::Lorem(paragraphs) => Ok(lorem(*paragraphs)),
+        }
+    }
+}
+
+/// Padded on encode, padding optional on decode
+fn lenient_base64(alphabet: alphabet::Alphabet) -> GeneralPurpose {
+    GeneralPurpose::new(
+        &alphabet,
+        GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+    )
+}
+
+/// Decode standard or URL-safe base64, ignoring whitespace and line breaks
+fn base64_decode(input: &str) -> Result<String, String> {
+    let compact: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    let bytes = lenient_base64(alphabet::STANDARD)
+        .decode(&compact)
+        .or_else(|_| lenient_base64(alphabet::URL_SAFE).decode(&compact))
+        .map_err(|e| format!("Invalid base64: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("Decoded data isn't text ({} bytes)", e.as_bytes().len()))
+}
+
+fn parse_json(input: &str) -> Result<serde_json::Value, String> {
+    serde_json::from_str(input).map_err(|e| format!("Invalid JSON: {}", e))
+}
+
+/// `paragraphs` paragraphs (1..=MAX_LOREM) separated by blank lines
+fn lorem(paragraphs: usize) -> String {
+    LOREM
+        .iter()
+        .cycle()
+        .take(paragraphs.clamp(1, MAX_LOREM))
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Transform::parse("/b64", "DECODE"), Some(Transform::Base64Decode));
+        assert_eq!(Transform::parse("/url", "e"), Some(Transform::UrlEncode));
+        assert_eq!(Transform::parse("/json", "min"), Some(Transform::JsonMinify));
+        assert_eq!(Transform::parse("/json", "encode"), None);
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(Transform::Base64Encode.apply("héllo?").unwrap(), "aMOpbGxvPw==");
+        assert_eq!(Transform::Base64Decode.apply("aMOpbGxvPw==").unwrap(), "héllo?");
+        // Unpadded, URL-safe and wrapped input
+        assert_eq!(Transform::Base64Decode.apply("aMOpbGxvPw").unwrap(), "héllo?");
+        assert_eq!(Transform::Base64Decode.apply("aMOpbGxvPw\n==").unwrap(), "héllo?");
+        assert_eq!(Transform::Base64Decode.apply("-_8").unwrap_err(), "Decoded data isn't text (2 bytes)");
+        assert!(Transform::Base64Decode.apply("not base64!").is_err());
+    }
+
+    #[test]
+    fn test_url() {
+        assert_eq!(Transform::UrlEncode.apply("a b&c=d/é~").unwrap(), "a%20b%26c%3Dd%2F%C3%A9~");
+        assert_eq!(Transform::UrlDecode.apply("a%20b+c%C3%A9").unwrap(), "a b cé");
+        assert!(Transform::UrlDecode.apply("%FF").is_err());
+    }
+
+    #[test]
+    fn test_json() {
+        let input = r#"{"a": [1, 2], "b": null}"#;
+        assert_eq!(Transform::JsonMinify.apply(input).unwrap(), r#"{"a":[1,2],"b":null}"#);
+        assert_eq!(
+            Transform::JsonFormat.apply(input).unwrap(),
+            "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": null\n}"
+        );
+        assert!(Transform::JsonFormat.apply("{oops}").unwrap_err().starts_with("Invalid JSON"));
+    }
+
+    #[test]
+    fn test_generators() {
+        let id = Transform::Uuid.apply("").unwrap();
+        assert_eq!(id.len(), 36);
+        assert_eq!(id.chars().nth(14), Some('4'));
+        assert_ne!(Transform::Uuid.apply("").unwrap(), id);
+
+        assert_eq!(Transform::Lorem(3).apply("").unwrap().split("\n\n").count(), 3);
+        assert_eq!(Transform::Lorem(0).apply("").unwrap(), LOREM[0]);
+        assert_eq!(Transform::Lorem(99).apply("").unwrap().split("\n\n").count(), MAX_LOREM);
+    }
+}
@@ -0,0 +1,211 @@
+//! Freedesktop trash
+//!
+//! Files go to the home trash (`$XDG_DATA_HOME/Trash`) with a `.trashinfo`
+//! record, as file managers do, so they show up (and can be restored) there
+//! too. Moving is a rename, so files on other filesystems than the home
+//! directory can't be trashed this way.
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::paths;
+
+/// Path characters kept as-is in a `.trashinfo` (the spec encodes like URLs)
+const PATH_SET: &AsciiSet = &NON_ALPHANUMERIC.remove(b'/').remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+/// A file moved to the trash, and where it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trashed {
+    pub original: PathBuf,
+    /// The file inside the trash
+    pub trashed: PathBuf,
+    info: PathBuf,
+}
+
+/// The home trash directory
+pub fn home_trash() -> PathBuf {
+    paths::data_home().join("Trash")
+}
+
+/// Move a file or directory to the home trash
+pub fn trash(path: &Path) -> Result<Trashed, String> {
+    trash_in(&home_trash(), path)
+}
+
+fn trash_in(trash: &Path, path: &Path) -> Result<Trashed, String> {
+    let original = fs::canonicalize(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let name = original
+        .file_name()
+        .ok_or_else(|| format!("Cannot trash {}", original.display()))?
+        .to_string_lossy()
+        .to_string();
+    let (files, infos) = (trash.join("files"), trash.join("info"));
+    for dir in [&files, &infos] {
+        fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+    }
+
+    // Claim a free name by creating its info file; "name", "name.2", ...
+    let (trashed, info, mut record) = (1..)
+        .map(|n| if n == 1 { name.clone() } else { format!("{}.{}", name, n) })
+        .find_map(|candidate| {
+            let info = infos.join(format!("{}.trashinfo", candidate));
+            let trashed = files.join(&candidate);
+            if trashed.exists() {
+                return None;
+            }
+            match OpenOptions::new().write(true).create_new(true).open(&info) {
+                Ok(file) => Some(Ok((trashed, info, file))),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => None,
+                Err(e) => Some(Err(format!("Cannot write {}: {}", info.display(), e))),
+            }
+        })
+        .expect("unbounded candidates")?;
+
+    let path_text = original.to_string_lossy();
+    let written = write!(
+        record,
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        utf8_percent_encode(&path_text, PATH_SET),
+        deletion_date(SystemTime::now())
+    );
+    let moved = written
+        .map_err(|e| format!("Cannot write {}: {}", info.display(), e))
+        .and_then(|()| {
+            fs::rename(&original, &trashed).map_err(|e| match e.raw_os_error() {
+                Some(18) => format!("{} is on a different filesystem than the trash", original.display()),
+                _ => format!("Cannot move {} to the trash: {}", original.display(), e),
+            })
+        });
+    if let Err(e) = moved {
+        fs::remove_file(&info).ok();
+        return Err(e);
+    }
+    Ok(Trashed { original, trashed, info })
+}
+
+/// Permanently delete everything in the home trash, returning how many
+/// items were in it
+pub fn empty() -> Result<usize, String> {
+    empty_in(&home_trash())
+}
+
+fn empty_in(trash: &Path) -> Result<usize, String> {
+    let files = trash.join("files");
+    let entries = match fs::read_dir(&files) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(format!("Cannot read {}: {}", files.display(), e)),
+    };
+    let mut count = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let removed = match entry.file_type() {
+            Ok(kind) if kind.is_dir() => fs::remove_dir_all(&path),
+            _ => fs::remove_file(&path),
+        };
+        removed.map_err(|e| format!("Cannot delete {}: {}", path.display(), e))?;
+        let info = trash.join("info").join(format!("{}.trashinfo", entry.file_name().to_string_lossy()));
+        fs::remove_file(info).ok();
+        count += 1;
+    }
+    Ok(count)
+}
+
+impl Trashed {
+    /// Put the file back where it was
+    pub fn restore(&self) -> Result<(), String> {
+        if self.original.exists() {
+            return Err(format!("{} exists again; not overwriting it", self.original.display()));
+        }
+        fs::rename(&self.trashed, &self.original)
+            .map_err(|e| format!("Cannot restore {}: {}", self.original.display(), e))?;
+        fs::remove_file(&self.info).ok();
+        Ok(())
+    }
+}
+
+/// `YYYY-MM-DDThh:mm:ss` (UTC; file managers only display it)
+fn deletion_date(at: SystemTime) -> String {
+    let secs = at.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let (days, rest) = (secs / 86_400, secs % 86_400);
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_trash_and_restore() {
+        let root = std::env::temp_dir().join(format!("ruty-trash-{}", std::process::id()));
+        let trash = root.join("Trash");
+        fs::create_dir_all(&root).unwrap();
+        let file = root.join("my notes.txt");
+        fs::write(&file, "hello").unwrap();
+
+        let trashed = trash_in(&trash, &file).unwrap();
+        assert!(!file.exists());
+        assert_eq!(trashed.trashed, trash.join("files/my notes.txt"));
+        let info = fs::read_to_string(trash.join("info/my notes.txt.trashinfo")).unwrap();
+        assert!(info.contains("my%20notes.txt\nDeletionDate="));
+
+        // A second file with the same name gets a numbered slot
+        fs::write(&file, "again").unwrap();
+        let second = trash_in(&trash, &file).unwrap();
+        assert_eq!(second.trashed, trash.join("files/my notes.txt.2"));
+
+        second.restore().unwrap();
+        assert!(trashed.restore().is_err());
+        fs::remove_file(&file).unwrap();
+        trashed.restore().unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "hello");
+        assert!(!trash.join("info/my notes.txt.trashinfo").exists());
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_empty() {
+        let root = std::env::temp_dir().join(format!("ruty-trash-empty-{}", std::process::id()));
+        let trash = root.join("Trash");
+        assert_eq!(empty_in(&trash), Ok(0));
+        fs::create_dir_all(root.join("dir")).unwrap();
+        fs::write(root.join("dir/inner.txt"), "x").unwrap();
+        fs::write(root.join("file.txt"), "y").unwrap();
+        trash_in(&trash, &root.join("dir")).unwrap();
+        trash_in(&trash, &root.join("file.txt")).unwrap();
+
+        assert_eq!(empty_in(&trash), Ok(2));
+        assert_eq!(fs::read_dir(trash.join("files")).unwrap().count(), 0);
+        assert_eq!(fs::read_dir(trash.join("info")).unwrap().count(), 0);
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_deletion_date() {
+        assert_eq!(deletion_date(UNIX_EPOCH), "1970-01-01T00:00:00");
+        let leap_day = UNIX_EPOCH + Duration::from_secs(951_782_400 + 3_723);
+        assert_eq!(deletion_date(leap_day), "2000-02-29T01:02:03");
+    }
+}
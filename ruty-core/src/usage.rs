@@ -0,0 +1,172 @@
+//! Launch usage
+//!
+//! Remembers which apps and files were opened from the launcher, and which
+//! prompts went to AI, how often and when, so the empty-query dashboard can
+//! offer frequent apps and recent files and `/timeline` can list them.
+//! Frequency decays with age, so an app used daily this week outranks one
+//! used a lot last year.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::paths;
+
+/// Entries kept per kind; the least recently used go first
+const USAGE_LIMIT: usize = 200;
+
+/// A launch counts half as much after this long
+const HALF_LIFE_SECS: f64 = 7.0 * 86_400.0;
+
+/// What was launched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    App,
+    File,
+    /// A prompt answered by AI (the id is the prompt)
+    Chat,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub kind: Kind,
+    /// App id or file path
+    pub id: String,
+    /// Name shown on the dashboard
+    pub title: String,
+    pub count: u32,
+    /// Unix time of the last launch
+    pub last_used: u64,
+}
+
+impl Entry {
+    /// Launch count decayed by the time since the last launch
+    fn frecency(&self, now: u64) -> f64 {
+        let age = now.saturating_sub(self.last_used) as f64;
+        self.count as f64 * 0.5f64.powf(age / HALF_LIFE_SECS)
+    }
+}
+
+/// Launched apps and files, in no particular order
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Usage {
+    entries: Vec<Entry>,
+}
+
+impl Usage {
+    /// Path of the usage file
+    pub fn path() -> PathBuf {
+        paths::state_dir().join("usage.json")
+    }
+
+    /// Load usage from disk (empty if missing or unreadable)
+    pub fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write usage to disk
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create state dir: {}", e))?;
+        }
+        let content = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write usage: {}", e))
+    }
+
+    /// Record a launch at `now` (Unix time)
+    pub fn record(&mut self, kind: Kind, id: &str, title: &str, now: u64) {
+        match self.entries.iter_mut().find(|e| e.kind == kind && e.id == id) {
+            Some(entry) => {
+                entry.count += 1;
+                entry.last_used = now;
+                entry.title = title.to_string();
+            }
+            None => self.entries.push(Entry {
+                kind,
+                id: id.to_string(),
+                title: title.to_string(),
+                count: 1,
+                last_used: now,
+            }),
+        }
+        // Chats don't push out apps and files
+        if self.entries.iter().filter(|e| e.kind == kind).count() > USAGE_LIMIT {
+            let oldest = self
+                .entries
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| e.kind == kind)
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(i, _)| i);
+            if let Some(i) = oldest {
+                self.entries.remove(i);
+            }
+        }
+    }
+
+    /// All entries, in no particular order
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Entries of one kind, most used (recently) first
+    pub fn frequent(&self, kind: Kind, now: u64) -> Vec<&Entry> {
+        let mut entries: Vec<&Entry> = self.entries.iter().filter(|e| e.kind == kind).collect();
+        entries.sort_by(|a, b| b.frecency(now).total_cmp(&a.frecency(now)));
+        entries
+    }
+
+    /// Entries of one kind, last used first
+    pub fn recent(&self, kind: Kind) -> Vec<&Entry> {
+        let mut entries: Vec<&Entry> = self.entries.iter().filter(|e| e.kind == kind).collect();
+        entries.sort_by_key(|entry| Reverse(entry.last_used));
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86_400;
+
+    #[test]
+    fn test_frequent_and_recent() {
+        let now = 100 * DAY;
+        let mut usage = Usage::default();
+        // Used a lot, but a month ago
+        for _ in 0..6 {
+            usage.record(Kind::App, "gimp.desktop", "GIMP", now - 30 * DAY);
+        }
+        for _ in 0..3 {
+            usage.record(Kind::App, "firefox.desktop", "Firefox", now - DAY);
+        }
+        usage.record(Kind::App, "foot.desktop", "Foot", now);
+        usage.record(Kind::File, "/home/me/todo.md", "todo.md", now);
+
+        let ids: Vec<&str> = usage.frequent(Kind::App, now).iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["firefox.desktop", "foot.desktop", "gimp.desktop"]);
+        let recent: Vec<&str> = usage.recent(Kind::App).iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(recent, vec!["Foot", "Firefox", "GIMP"]);
+        assert_eq!(usage.recent(Kind::File).len(), 1);
+    }
+
+    #[test]
+    fn test_limit_drops_least_recent() {
+        let mut usage = Usage::default();
+        for i in 0..=USAGE_LIMIT as u64 {
+            usage.record(Kind::File, &format!("/f{}", i), "f", i);
+        }
+        assert_eq!(usage.entries.len(), USAGE_LIMIT);
+        assert!(usage.entries.iter().all(|e| e.id != "/f0"));
+        usage.record(Kind::Chat, "what is rust", "what is rust", 0);
+        assert_eq!(usage.entries.len(), USAGE_LIMIT + 1);
+    }
+}
@@ -0,0 +1,268 @@
+//! Config validation
+//!
+//! The config structs take defaults for anything left out, which also means
+//! serde quietly skips keys it doesn't know: a `[hotkye]` table or a
+//! `them = "light"` leaves the defaults in place with no hint why. `validate`
+//! parses config.toml like `Config::from_toml` and also reports those keys,
+//! with the line and column they're at and the known key they were probably
+//! meant to be. Errors get the same treatment.
+//!
+//! Known keys are the ones the parsed config serializes back to, so the
+//! check follows the structs without a schema of its own to keep in sync.
+
+use std::fmt;
+
+use toml::Value;
+use toml_edit::{ImDocument, Item, TableLike};
+
+use crate::config::Config;
+
+/// A problem in config text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// Line and column, from 1, when known
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.position {
+            Some((line, column)) => write!(f, "line {}, column {}: {}", line, column, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// A config that parsed, and what looked off in it
+#[derive(Debug, Clone)]
+pub struct Validated {
+    pub config: Config,
+    pub warnings: Vec<Diagnostic>,
+}
+
+/// Step from a value to one inside it
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parse config text, warning about keys no setting reads
+pub fn validate(content: &str) -> Result<Validated, Diagnostic> {
+    let config: Config = toml::from_str(content).map_err(|e| Diagnostic {
+        position: e.span().map(|span| position(content, span.start)),
+        message: suggest_variant(e.message().trim()),
+    })?;
+    let input: Value = toml::from_str(content).unwrap_or(Value::Table(Default::default()));
+    let known = Value::try_from(&config).unwrap_or(Value::Table(Default::default()));
+
+    let mut unknown = Vec::new();
+    unknown_keys(&input, &known, &mut Vec::new(), &mut unknown);
+    let document = ImDocument::parse(content).ok();
+    let mut warnings: Vec<Diagnostic> = unknown
+        .into_iter()
+        .map(|(path, suggestion)| Diagnostic {
+            position: document.as_ref().and_then(|document| key_offset(document, &path)).map(|at| position(content, at)),
+            message: match suggestion {
+                Some(key) => format!("unknown key `{}`; did you mean `{}`?", display_path(&path), key),
+                None => format!("unknown key `{}`", display_path(&path)),
+            },
+        })
+        .collect();
+    warnings.sort_by_key(|warning| warning.position);
+    Ok(Validated { config, warnings })
+}
+
+/// Collect keys of `input` missing from `known`, with the closest known
+/// sibling of each
+fn unknown_keys(input: &Value, known: &Value, path: &mut Vec<Segment>, out: &mut Vec<(Vec<Segment>, Option<String>)>) {
+    match (input, known) {
+        (Value::Table(input), Value::Table(known)) => {
+            for (key, value) in input {
+                path.push(Segment::Key(key.clone()));
+                match known.get(key) {
+                    Some(known) => unknown_keys(value, known, path, out),
+                    // Empty lists and tables aren't written back, known or not
+                    None if is_empty(value) => {}
+                    None => out.push((path.clone(), closest(key, known.keys().map(String::as_str)))),
+                }
+                path.pop();
+            }
+        }
+        (Value::Array(input), Value::Array(known)) => {
+            for (i, (value, known)) in input.iter().zip(known).enumerate() {
+                path.push(Segment::Index(i));
+                unknown_keys(value, known, path, out);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Array(array) => array.is_empty(),
+        Value::Table(table) => table.is_empty(),
+        _ => false,
+    }
+}
+
+/// Byte offset of the key at `path`
+fn key_offset(document: &ImDocument<&str>, path: &[Segment]) -> Option<usize> {
+    let mut table: &dyn TableLike = document.as_table();
+    let mut segments = path.iter().peekable();
+    while let Some(segment) = segments.next() {
+        let Segment::Key(key) = segment else {
+            return None;
+        };
+        let (key, item) = table.get_key_value(key)?;
+        table = match segments.peek() {
+            None => return key.span().map(|span| span.start),
+            Some(Segment::Index(i)) => {
+                segments.next();
+                match item {
+                    Item::ArrayOfTables(tables) => tables.get(*i)?,
+                    item => item.as_array()?.get(*i)?.as_inline_table()?,
+                }
+            }
+            Some(Segment::Key(_)) => item.as_table_like()?,
+        };
+    }
+    None
+}
+
+/// `favorites[0].title` style
+fn display_path(path: &[Segment]) -> String {
+    let mut text = String::new();
+    for segment in path {
+        match segment {
+            Segment::Key(key) if text.is_empty() => text.push_str(key),
+            Segment::Key(key) => text.push_str(&format!(".{}", key)),
+            Segment::Index(i) => text.push_str(&format!("[{}]", i)),
+        }
+    }
+    text
+}
+
+/// Line and column of a byte offset
+fn position(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+/// Add a "did you mean" to serde's "unknown variant `drak`, expected `dark`
+/// or `light`"
+fn suggest_variant(message: &str) -> String {
+    let Some(rest) = message.strip_prefix("unknown variant ") else {
+        return message.to_string();
+    };
+    let mut quoted = rest.split('`').skip(1).step_by(2);
+    let Some(given) = quoted.next() else {
+        return message.to_string();
+    };
+    match closest(given, quoted) {
+        Some(variant) => format!("{}; did you mean `{}`?", message, variant),
+        None => message.to_string(),
+    }
+}
+
+/// The candidate `word` is most likely a typo of, if any is close enough
+fn closest<'a>(word: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+    let limit = (word.chars().count() / 3).max(1);
+    candidates
+        .map(|candidate| (distance(&word.to_lowercase(), &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+/// Edits (insert, delete, substitute, swap neighbours) between two words
+fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![(0..=b.len()).collect::<Vec<usize>>()];
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (rows[i - 1][j] + 1).min(row[j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+        rows.push(row);
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_keys() {
+        let content = concat!(
+            "[hotkye]\n",
+            "toggle = \"Super+Space\"\n",
+            "\n",
+            "[ui]\n",
+            "theme = \"light\"\n",
+            "them = \"dark\"\n",
+            "\n",
+            "[aliases]\n",
+            "ff = \"/launch firefox\"\n",
+            "\n",
+            "[[favorites]]\n",
+            "kind = \"app\"\n",
+            "target = \"firefox\"\n",
+            "title = \"Firefox\"\n",
+            "tilte = \"Web\"\n",
+            "\n",
+            "[apps.overrides.firefox]\n",
+            "args = [\"--new-window\"]\n",
+            "arg = [\"--private\"]\n",
+            "env = {}\n",
+            "\n",
+            "[flows.open]\n",
+            "steps = [{ query = \"x\" }, \"launch\"]\n",
+        );
+        let validated = validate(content).unwrap();
+        assert_eq!(validated.config.ui.theme, crate::config::Theme::Light);
+        let warnings: Vec<String> = validated.warnings.iter().map(|w| w.to_string()).collect();
+        assert_eq!(
+            warnings,
+            [
+                "line 1, column 2: unknown key `hotkye`; did you mean `hotkeys`?",
+                "line 6, column 1: unknown key `ui.them`; did you mean `theme`?",
+                "line 15, column 1: unknown key `favorites[0].tilte`; did you mean `title`?",
+                "line 19, column 1: unknown key `apps.overrides.firefox.arg`; did you mean `args`?",
+            ]
+        );
+        assert!(validate("[ui]\ntheme = \"dark\"\n").unwrap().warnings.is_empty());
+        assert_eq!(validate("[zzz]\na = 1\n").unwrap().warnings[0].message, "unknown key `zzz`");
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = validate("[ui]\ntheme = \"drak\"\n").unwrap_err();
+        assert_eq!(error.position, Some((2, 9)));
+        assert!(error.message.ends_with("did you mean `dark`?"), "{}", error.message);
+
+        let error = validate("[files]\nmax_depth = \"deep\"\n").unwrap_err();
+        assert_eq!(error.position, Some((2, 13)));
+        let error = validate("[ui]\n\n[[favorites]]\nkind = \"app\"\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 3, column 1: missing field `target`");
+        assert!(validate("[ui\n").unwrap_err().position.is_some());
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(distance("hotkye", "hotkeys"), 2);
+        assert_eq!(distance("theme", "theme"), 0);
+        assert_eq!(distance("them", "theme"), 1);
+        assert_eq!(closest("xyz", ["theme", "scale"].into_iter()), None);
+    }
+}
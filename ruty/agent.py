@@ -44,8 +44,10 @@ Guidelines:
 8. For system tasks, use run_shell cautiously and explain what you're doing
 """
 
-# SQLite database for conversation persistence
-DB_PATH = Path.home() / ".config" / "ruty" / "conversations.db"
+# SQLite database for conversation persistence, with the daemon's data
+# (it moves the one older versions kept in ~/.config/ruty)
+DATA_DIR = Path(os.getenv("XDG_DATA_HOME") or Path.home() / ".local" / "share") / "ruty"
+DB_PATH = DATA_DIR / "conversations.db"
 
 
 def get_checkpointer(persistent: bool = True):
@@ -0,0 +1,87 @@
+"""Client approval of tool calls that act on the user's system.
+
+run_shell and open_url check here before doing anything. What happens
+depends on the mode in `approval_context` while the agent runs, which a
+chat request picks through its `tool_approval`:
+
+- "deny" (the default): the call is refused. Clients that can't show the
+  user an approval prompt, like the WebSocket chat, get this.
+- "ask": the call is proposed and waits until the client approves or denies
+  it (GET /tool-calls/proposed, POST /tool-calls/{id}, which want the
+  user's token; see `auth`); one left unanswered
+  for APPROVAL_TIMEOUT seconds is denied
+- "prompt": the user is asked on the terminal (the CLI)
+"""
+import threading
+import time
+import uuid
+from contextvars import ContextVar
+from typing import Optional
+
+# How long a proposed call waits for an answer before it's denied
+APPROVAL_TIMEOUT = 120
+
+# (tool_approval, session_id) of the chat request being answered
+approval_context: ContextVar[tuple[str, str]] = ContextVar("tool_approval", default=("deny", ""))
+
+_calls: dict[str, dict] = {}
+_changed = threading.Condition()
+
+
+def approve(tool: str, argument: str) -> bool:
+    """Whether the tool call may go ahead; blocks while the client decides."""
+    mode, session_id = approval_context.get()
+    if mode == "prompt":
+        try:
+            return input(f"  Allow {tool}: {argument}? [y/N] ").strip().lower() in ("y", "yes")
+        except EOFError:
+            return False
+    if mode != "ask":
+        return False
+
+    call = {
+        "id": uuid.uuid4().hex,
+        "session_id": session_id,
+        "tool": tool,
+        "argument": argument,
+        "proposed": False,
+        "approved": None,
+    }
+    with _changed:
+        _calls[call["id"]] = call
+        _changed.notify_all()
+        decided = _changed.wait_for(lambda: call["approved"] is not None, timeout=APPROVAL_TIMEOUT)
+        del _calls[call["id"]]
+    return bool(decided and call["approved"])
+
+
+def proposed(session_id: Optional[str] = None, wait: float = 0.0) -> list[dict]:
+    """Calls not handed to the client yet, waiting up to `wait` seconds for one."""
+    deadline = time.monotonic() + wait
+    with _changed:
+        while True:
+            calls = [
+                call for call in _calls.values()
+                if not call["proposed"] and session_id in (None, call["session_id"])
+            ]
+            remaining = deadline - time.monotonic()
+            if calls or remaining <= 0:
+                break
+            _changed.wait(remaining)
+        for call in calls:
+            call["proposed"] = True
+        return [
+            {"id": call["id"], "tool": call["tool"], "argument": call["argument"]}
+            for call in calls
+        ]
+
+
+def decide(call_id: str, approved: bool) -> bool:
+    """Answer a proposed call; False if it's no longer waiting."""
+    with _changed:
+        call = _calls.get(call_id)
+        if call is None or call["approved"] is not None:
+            return False
+        call["approved"] = approved
+        _changed.notify_all()
+        return True
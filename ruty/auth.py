@@ -0,0 +1,36 @@
+"""The per-user token of the daemon's local APIs (see ruty-core's `token`).
+
+The daemon creates `rpc-token` in Ruty's state dir, readable only by the
+user. Endpoints that could make the agent act on the system want it as
+`Authorization: Bearer <token>`, so other local users and web pages can't
+call them.
+"""
+import hmac
+import os
+import stat
+from pathlib import Path
+from typing import Optional
+
+
+def path() -> Path:
+    state = os.getenv("XDG_STATE_HOME") or Path.home() / ".local" / "state"
+    return Path(state) / "ruty" / "rpc-token"
+
+
+def read() -> Optional[str]:
+    """The token, or None if there's none yet or others could read it."""
+    try:
+        if path().stat().st_mode & (stat.S_IRWXG | stat.S_IRWXO):
+            return None
+        token = path().read_text().strip()
+    except OSError:
+        return None
+    return token or None
+
+
+def check(authorization: Optional[str]) -> bool:
+    """Whether an `Authorization` header carries the token."""
+    token = read()
+    if token is None or authorization is None:
+        return False
+    return hmac.compare_digest(authorization.encode(), f"Bearer {token}".encode())
@@ -6,6 +6,7 @@ from datetime import datetime
 from langchain_core.messages import HumanMessage
 
 from .agent import create_agent
+from .approval import approval_context
 from .memory import read_directory_context, add_memory_to_supermemory
 from .extraction import extract_semantic_memories
 
@@ -61,6 +62,8 @@ def interactive_chat():
     # Create session ID for this session
     session_id = f"session_{datetime.now().strftime('%Y%m%d_%H%M%S')}_{uuid.uuid4().hex[:8]}"
     config = {"configurable": {"thread_id": session_id}}
+    # Shell commands and links the agent wants to run are asked about here
+    approval_context.set(("prompt", session_id))
     
     # Local context state
     local_context = ""
@@ -67,11 +67,12 @@ def delete_document(doc_id: str):
     """Delete a document from Supermemory"""
     response = requests.delete(
         f"https://api.supermemory.ai/v3/documents/{doc_id}",
-        headers={"Authorization": f"Bearer {SUPERMEMORY_API_KEY}"}
+        headers=get_headers()
     )
     if not response.ok:
         return None
-    return response.json()
+    # Deletes answer 204 No Content
+    return response.json() if response.content else {}
 
 
 def add_memory_to_supermemory(content: str, custom_id: str = None, title: str = None):
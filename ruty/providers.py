@@ -120,7 +120,7 @@ class RutyConfig:
 
 
 # Config file path
-CONFIG_DIR = Path.home() / ".config" / "ruty"
+CONFIG_DIR = Path(os.getenv("XDG_CONFIG_HOME") or Path.home() / ".config") / "ruty"
 CONFIG_FILE = CONFIG_DIR / "config.json"
 
 
@@ -5,21 +5,28 @@ Provides:
 - Provider configuration management
 - Session management
 - Local context loading
+- Supermemory browsing (list, search, delete)
+- Approval of tool calls by the client (see `approval`)
 """
 import os
 import uuid
 from datetime import datetime
-from typing import Optional
+from typing import Literal, Optional
 from contextlib import asynccontextmanager
 
-from fastapi import FastAPI, WebSocket, WebSocketDisconnect
+from fastapi import Depends, FastAPI, Header, HTTPException, WebSocket, WebSocketDisconnect
 from fastapi.middleware.cors import CORSMiddleware
 from pydantic import BaseModel
 import uvicorn
 
-from langchain_core.messages import HumanMessage, AIMessage
+from langchain_core.messages import HumanMessage, AIMessage, RemoveMessage
 from .agent import create_agent, get_agent, reset_agent
-from .memory import read_directory_context
+from .memory import (
+    read_directory_context, list_memories, search_supermemory,
+    delete_document, get_supermemory_key,
+)
+from . import auth
+from .approval import approval_context, proposed, decide
 from .config import api_key_context
 from .providers import (
     get_config, update_config, list_providers, 
@@ -46,10 +53,11 @@ app = FastAPI(
     lifespan=lifespan,
 )
 
-# CORS for Tauri frontend
+# CORS for the Tauri frontend's webview only (tauri://localhost on Linux and
+# macOS, http://tauri.localhost on Windows), not for every page in a browser
 app.add_middleware(
     CORSMiddleware,
-    allow_origins=["*"],
+    allow_origins=["tauri://localhost", "http://tauri.localhost", "https://tauri.localhost"],
     allow_credentials=True,
     allow_methods=["*"],
     allow_headers=["*"],
@@ -64,6 +72,11 @@ class ChatRequest(BaseModel):
     session_id: str
     local_context: Optional[str] = None
     api_keys: Optional[dict] = None
+    # Replace the session's last exchange (edited resend, regenerate) instead of appending
+    replace_last: bool = False
+    # "ask" or "deny" for tool calls that act on the system (see `approval`);
+    # only clients that show the user an approval prompt should ask
+    tool_approval: Literal["ask", "deny"] = "deny"
 
 
 class ChatResponse(BaseModel):
@@ -77,6 +90,19 @@ class ContextRequest(BaseModel):
     """Request model for context loading"""
     session_id: str
     path: str
+    # Text extracted by the client (PDF/office documents)
+    content: Optional[str] = None
+
+
+class ContextRemoveRequest(BaseModel):
+    """Request model for unloading one context path"""
+    session_id: str
+    path: str
+
+
+class ToolCallDecision(BaseModel):
+    """The client's answer to a proposed tool call"""
+    approved: bool
 
 
 class ProviderUpdateRequest(BaseModel):
@@ -86,6 +112,36 @@ class ProviderUpdateRequest(BaseModel):
     api_key: Optional[str] = None  # Key for the current provider
 
 
+# ============== Memories ==============
+
+def _memory_entry(item: dict) -> dict:
+    """Normalize a Supermemory document or search hit for the memory browser"""
+    return {
+        # Search hits name their document; deleting works on documents
+        "id": item.get("documentId") or item.get("id") or "",
+        "title": item.get("title") or "",
+        "content": item.get("content") or item.get("memory") or item.get("chunk") or item.get("summary") or "",
+        "created_at": item.get("createdAt") or item.get("updatedAt"),
+    }
+
+
+@app.get("/memories")
+def list_memories_endpoint(q: Optional[str] = None, limit: int = 50):
+    """List stored memories, newest first, or search them when `q` is given"""
+    if not get_supermemory_key():
+        raise HTTPException(status_code=503, detail="No Supermemory API key configured")
+    items = search_supermemory(q, limit=limit) if q else list_memories()[:limit]
+    return {"memories": [_memory_entry(item) for item in items]}
+
+
+@app.delete("/memories/{memory_id}")
+def delete_memory_endpoint(memory_id: str):
+    """Delete a stored memory"""
+    if delete_document(memory_id) is None:
+        raise HTTPException(status_code=502, detail=f"Supermemory refused to delete {memory_id}")
+    return {"success": True}
+
+
 # ============== Session Management ==============
 
 def get_or_create_session(session_id: str) -> dict:
@@ -95,35 +151,56 @@ def get_or_create_session(session_id: str) -> dict:
             "agent": create_agent(),
             "config": {"configurable": {"thread_id": session_id}},
             "local_context": "",
+            "contexts": {},
             "created_at": datetime.now().isoformat(),
         }
     return sessions[session_id]
 
 
+def _message_context(session: dict, extra: Optional[str]) -> str:
+    """Session context plus context sent with a single message (e.g. clipboard)."""
+    parts = [part for part in (session["local_context"], extra) if part]
+    return "\n\n".join(parts)
+
+
+def _drop_last_exchange(agent, config: dict) -> None:
+    """Remove the last human message and everything after it from the thread."""
+    messages = agent.get_state(config).values.get("messages", [])
+    last_human = next(
+        (i for i in range(len(messages) - 1, -1, -1) if isinstance(messages[i], HumanMessage)),
+        None,
+    )
+    if last_human is None:
+        return
+    agent.update_state(config, {"messages": [RemoveMessage(id=m.id) for m in messages[last_human:]]})
+
+
 # ============== Chat Endpoints ==============
 
 @app.post("/chat", response_model=ChatResponse)
-async def chat(request: ChatRequest):
+def chat(request: ChatRequest):
     """
     Process a chat message and return response.
-    This is a synchronous endpoint that returns the full response.
+    This is a synchronous endpoint that returns the full response. It runs
+    in the threadpool, so tool calls can wait for approval while
+    /tool-calls is served.
     """
     # Set API key context for this request
     token = api_key_context.set(request.api_keys or {})
+    approval_token = approval_context.set((request.tool_approval, request.session_id))
     
     try:
         session = get_or_create_session(request.session_id)
         agent = session["agent"]
         config = session["config"]
+        if request.replace_last:
+            _drop_last_exchange(agent, config)
         
-        # Update local context if provided
-        if request.local_context:
-            session["local_context"] = request.local_context
-        
-        # Build input state
+        # Build input state (request context applies to this message only)
         input_state = {"messages": [HumanMessage(content=request.message)]}
-        if session["local_context"]:
-            input_state["local_context"] = session["local_context"]
+        local_context = _message_context(session, request.local_context)
+        if local_context:
+            input_state["local_context"] = local_context
         
         # Process with agent
         tools_used = []
@@ -152,14 +229,37 @@ async def chat(request: ChatRequest):
             session_id=request.session_id
         )
     finally:
+        approval_context.reset(approval_token)
         api_key_context.reset(token)
 
 
+def require_token(authorization: Optional[str] = Header(None)):
+    """Refuse callers that don't send the user's token (see `auth`)"""
+    if not auth.check(authorization):
+        raise HTTPException(status_code=401, detail="Missing or wrong token")
+
+
+@app.get("/tool-calls/proposed", dependencies=[Depends(require_token)])
+def proposed_tool_calls(session_id: Optional[str] = None, wait: float = 0.0):
+    """Tool calls waiting for approval that weren't handed out yet, waiting
+    up to `wait` seconds (at most 30) for one to come in"""
+    return {"calls": proposed(session_id, min(max(wait, 0.0), 30.0))}
+
+
+@app.post("/tool-calls/{call_id}", dependencies=[Depends(require_token)])
+def decide_tool_call(call_id: str, decision: ToolCallDecision):
+    """Approve or deny a proposed tool call"""
+    if not decide(call_id, decision.approved):
+        raise HTTPException(status_code=404, detail=f"Tool call {call_id} is no longer waiting")
+    return {"success": True}
+
+
 @app.websocket("/ws/{session_id}")
 async def websocket_chat(websocket: WebSocket, session_id: str):
     """
     WebSocket endpoint for streaming chat responses.
-    Enables real-time token streaming to the frontend.
+    Enables real-time token streaming to the frontend. Tool calls that act
+    on the system are denied, as nothing here can ask the user about them.
     """
     await websocket.accept()
     session = get_or_create_session(session_id)
@@ -176,13 +276,11 @@ async def websocket_chat(websocket: WebSocket, session_id: str):
             token = api_key_context.set(api_keys)
             
             try:
-                if local_context:
-                    session["local_context"] = local_context
-                
-                # Build input state
+                # Build input state (message context applies to this message only)
                 input_state = {"messages": [HumanMessage(content=message)]}
-                if session["local_context"]:
-                    input_state["local_context"] = session["local_context"]
+                local_context = _message_context(session, local_context)
+                if local_context:
+                    input_state["local_context"] = local_context
                 
                 agent = session["agent"]
                 config = session["config"]
@@ -290,34 +388,96 @@ async def get_current_config():
 
 # ============== Context Management ==============
 
+def _sync_local_context(session: dict) -> list[str]:
+    """Rebuild local_context from the loaded context paths, returning them."""
+    contexts = session.setdefault("contexts", {})
+    session["local_context"] = "\n\n".join(contexts.values())
+    return list(contexts.keys())
+
+
 @app.post("/context/load")
 async def load_context(request: ContextRequest):
-    """Load local files as context for the session"""
+    """Load local files as context for the session (adds to existing context)"""
     from pathlib import Path
     
     session = get_or_create_session(request.session_id)
     path = Path(request.path).expanduser().resolve()
     
+    if request.content is not None:
+        session["contexts"][str(path)] = f"### {path.name}\n```\n{request.content}\n```"
+        return {
+            "success": True,
+            "loaded": path.name,
+            "type": "document",
+            "message": f"Loaded {path.name} ({len(request.content)} chars)",
+            "files_loaded": 1,
+            "contexts": _sync_local_context(session),
+        }
+    
     if not path.exists():
-        return {"success": False, "error": f"Path not found: {path}"}
+        error = f"Path not found: {path}"
+        return {
+            "success": False,
+            "error": error,
+            "message": error,
+            "files_loaded": 0,
+            "contexts": _sync_local_context(session),
+        }
     
     try:
         if path.is_file():
             content = path.read_text(encoding="utf-8")
-            session["local_context"] = f"### {path.name}\n```\n{content[:5000]}\n```"
-            return {"success": True, "loaded": path.name, "type": "file"}
+            session["contexts"][str(path)] = f"### {path.name}\n```\n{content[:5000]}\n```"
+            files_loaded = 1
+            context_type = "file"
         else:
             content = read_directory_context(path)
-            session["local_context"] = content
-            return {"success": True, "loaded": path.name, "type": "directory"}
+            session["contexts"][str(path)] = content
+            files_loaded = content.count("### ")
+            context_type = "directory"
+        return {
+            "success": True,
+            "loaded": path.name,
+            "type": context_type,
+            "message": f"Loaded {path.name} ({files_loaded} files)",
+            "files_loaded": files_loaded,
+            "contexts": _sync_local_context(session),
+        }
     except Exception as e:
-        return {"success": False, "error": str(e)}
+        return {
+            "success": False,
+            "error": str(e),
+            "message": str(e),
+            "files_loaded": 0,
+            "contexts": _sync_local_context(session),
+        }
+
+
+@app.get("/context/list/{session_id}")
+async def list_context(session_id: str):
+    """List context paths loaded for a session"""
+    session = get_or_create_session(session_id)
+    return {"contexts": _sync_local_context(session)}
+
+
+@app.post("/context/remove")
+async def remove_context(request: ContextRemoveRequest):
+    """Unload one context path from a session"""
+    session = get_or_create_session(request.session_id)
+    removed = session.setdefault("contexts", {}).pop(request.path, None) is not None
+    return {
+        "success": removed,
+        "message": f"Removed {request.path}" if removed else f"Not loaded: {request.path}",
+        "files_loaded": 0,
+        "contexts": _sync_local_context(session),
+    }
 
 
 @app.post("/context/clear")
 async def clear_context(session_id: str):
     """Clear local context for a session"""
     session = get_or_create_session(session_id)
+    session["contexts"] = {}
     session["local_context"] = ""
     return {"success": True}
 
@@ -4,11 +4,15 @@ Provides tools for:
 - Document management (list, delete)
 - URL opening
 - Shell command execution (sandboxed)
+
+Opening URLs and running commands need the client's approval when the
+chat request asks for it (see `approval`).
 """
 import os
 import subprocess
 import webbrowser
 from langchain_core.tools import tool
+from ..approval import approve
 from ..memory import list_docs, delete_document as delete_doc_api
 
 
@@ -83,6 +87,9 @@ def open_url(url: str) -> str:
         else:
             return f"✗ Invalid URL: {url}. Must be a valid web address."
     
+    if not approve("open_url", url):
+        return f"✗ The user didn't allow opening {url}"
+    
     try:
         webbrowser.open(url)
         return f"✓ Opened {url} in your browser"
@@ -148,6 +155,9 @@ def run_shell(command: str) -> str:
             if op in [">", ">>"] and cmd_name not in SAFE_COMMANDS:
                 return f"✗ Redirects with '{cmd_name}' are not allowed"
     
+    if not approve("run_shell", command):
+        return f"✗ The user didn't allow running: {command}"
+    
     try:
         result = subprocess.run(
             command,
@@ -104,6 +104,176 @@ pub async fn clear_context(session_id: String) -> Result<bool, String> {
     Ok(response.status().is_success())
 }
 
+// ==================== Provider Management ====================
+
+use std::collections::{BTreeMap, HashMap};
+
+/// Keyring service name for stored API keys
+const KEYRING_SERVICE: &str = "ruty";
+
+/// Services whose keys the settings page edits
+const API_KEY_PROVIDERS: &[&str] = &["groq", "supermemory"];
+
+#[derive(Serialize, Deserialize)]
+pub struct ProviderInfo {
+    name: String,
+    models: Vec<String>,
+    default_model: String,
+    requires_key: bool,
+    /// Whether a key for this provider is stored in the system keyring
+    #[serde(default)]
+    has_api_key: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CurrentProvider {
+    provider: String,
+    model: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProvidersResponse {
+    providers: HashMap<String, ProviderInfo>,
+    current: CurrentProvider,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProviderUpdateRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProviderUpdateResponse {
+    success: bool,
+    provider: Option<String>,
+    model: Option<String>,
+    error: Option<String>,
+}
+
+/// Look up a provider's API key in the system keyring
+fn keyring_get(provider: &str) -> Option<String> {
+    keyring::Entry::new(KEYRING_SERVICE, provider)
+        .and_then(|entry| entry.get_password())
+        .ok()
+}
+
+/// POST a provider update to the backend
+async fn post_provider_update(request: &ProviderUpdateRequest) -> Result<ProviderUpdateResponse, String> {
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/providers/update", API_BASE))
+        .json(request)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("API error: {}", response.status()));
+    }
+
+    response
+        .json::<ProviderUpdateResponse>()
+        .await
+        .map_err(|e| format!("Parse error: {}", e))
+}
+
+/// Fetch the provider list from the backend
+async fn fetch_providers() -> Result<ProvidersResponse, String> {
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/providers", API_BASE))
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    response
+        .json::<ProvidersResponse>()
+        .await
+        .map_err(|e| format!("Parse error: {}", e))
+}
+
+/// List available providers, their models, and which have stored keys
+#[tauri::command]
+pub async fn get_providers() -> Result<ProvidersResponse, String> {
+    let mut providers = fetch_providers().await?;
+
+    for (id, info) in providers.providers.iter_mut() {
+        info.has_api_key = keyring_get(id).is_some();
+    }
+
+    Ok(providers)
+}
+
+/// Switch the active provider and/or model
+#[tauri::command]
+pub async fn set_provider(provider: String, model: Option<String>) -> Result<ProviderUpdateResponse, String> {
+    // Send the stored key along so switching to a keyed provider just works
+    let api_key = keyring_get(&provider);
+
+    post_provider_update(&ProviderUpdateRequest {
+        provider: Some(provider),
+        model,
+        api_key,
+    })
+    .await
+}
+
+/// Store an API key in the system keyring and push it to the backend if
+/// it's the active provider's. The key is kept even when the backend can't
+/// be reached; `false` then says it wasn't updated.
+#[tauri::command]
+pub async fn set_api_key(provider: String, api_key: String) -> Result<bool, String> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, &provider)
+        .map_err(|e| format!("Keyring error: {}", e))?;
+
+    if api_key.is_empty() {
+        match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => return Ok(true),
+            Err(e) => return Err(format!("Failed to remove key: {}", e)),
+        }
+    }
+
+    entry
+        .set_password(&api_key)
+        .map_err(|e| format!("Failed to store key: {}", e))?;
+
+    // Only push to the backend for the active provider; naming another
+    // provider in an update would switch to it. Others get their key
+    // sent by set_provider when selected.
+    let Ok(providers) = fetch_providers().await else {
+        return Ok(false);
+    };
+    if providers.current.provider != provider {
+        return Ok(true);
+    }
+
+    let response = post_provider_update(&ProviderUpdateRequest {
+        provider: None,
+        model: None,
+        api_key: Some(api_key),
+    })
+    .await;
+
+    Ok(response.is_ok_and(|response| response.success))
+}
+
+/// API keys in the system keyring, by provider (settings and chat requests
+/// read them from here, not from settings.json)
+#[tauri::command]
+pub fn get_api_keys() -> HashMap<String, String> {
+    API_KEY_PROVIDERS
+        .iter()
+        .filter_map(|provider| Some((provider.to_string(), keyring_get(provider)?)))
+        .collect()
+}
+
 // ==================== Application Launcher ====================
 
 use super::apps::{AppIndexer, Application};
@@ -174,10 +344,12 @@ pub fn refresh_apps() -> usize {
 // ==================== File Search ====================
 
 use super::files::{FileSearcher, FileResult};
+use ruty_core::config::{self, Config};
+use ruty_core::i18n;
 
-/// Global file searcher (lazily initialized)
+/// Global file searcher (lazily initialized from the shared config)
 static FILE_SEARCHER: Lazy<Mutex<FileSearcher>> = Lazy::new(|| {
-    Mutex::new(FileSearcher::new())
+    Mutex::new(FileSearcher::with_config(&Config::load().files))
 });
 
 /// Search for files
@@ -230,39 +402,88 @@ pub fn get_clipboard_history() -> Vec<ClipboardItem> {
 /// Copy text to clipboard (moves to top of history)
 #[tauri::command]
 pub fn copy_to_clipboard(content: String) -> Result<String, String> {
-    // Only works if arboard is used or we shell out to wl-copy/xclip
-    // Because we are using shell-out for reading, let's use it for writing too
-    // to keep it consistent.
-    
-    // Try wl-copy first
-    use std::process::{Command, Stdio};
-    use std::io::Write;
-    
-    let child = Command::new("wl-copy")
-        .stdin(Stdio::piped())
-        .spawn();
-        
-    if let Ok(mut child) = child {
-        if let Some(mut stdin) = child.stdin.take() {
-            let _ = stdin.write_all(content.as_bytes());
-        }
-        let _ = child.wait();
-        return Ok("Copied via wl-copy".to_string());
+    super::clipboard::copy(&content).map(|tool| format!("Copied via {}", tool))
+}
+
+// ==================== Autostart ====================
+
+use ruty_core::autostart;
+
+/// Get which autostart mechanisms are installed
+#[tauri::command]
+pub fn get_autostart() -> autostart::Status {
+    autostart::status()
+}
+
+/// Enable or disable launching Ruty at login
+#[tauri::command]
+pub fn set_autostart(enabled: bool) -> Result<autostart::Status, String> {
+    if enabled {
+        let exe = std::env::current_exe()
+            .map_err(|e| format!("Could not determine app path: {}", e))?;
+        autostart::enable(autostart::Method::Desktop, &exe)?;
+    } else {
+        autostart::disable()?;
     }
-    
-    // Try xclip
-    let child = Command::new("xclip")
-        .args(["-selection", "clipboard", "-i"])
-        .stdin(Stdio::piped())
-        .spawn();
-        
-    if let Ok(mut child) = child {
-        if let Some(mut stdin) = child.stdin.take() {
-            let _ = stdin.write_all(content.as_bytes());
+    Ok(autostart::status())
+}
+
+// ==================== Actions ====================
+
+use ruty_core::actions::{Action, ActionInfo};
+use tauri::{AppHandle, Emitter};
+
+/// List built-in actions for the command palette and settings page
+#[tauri::command]
+pub fn list_actions() -> Vec<ActionInfo> {
+    Action::ALL.iter().map(Action::info).collect()
+}
+
+/// Get the configured theme ("dark" or "light")
+#[tauri::command]
+pub fn get_theme() -> config::Theme {
+    Config::load().ui.theme
+}
+
+/// UI strings in the configured language, by message id
+#[tauri::command]
+pub fn translations() -> BTreeMap<String, String> {
+    i18n::messages(i18n::resolve(&Config::load().ui.language))
+}
+
+/// Run a built-in action by id, returning a status message
+#[tauri::command]
+pub fn run_action(app: AppHandle, id: String) -> Result<String, String> {
+    let action = Action::from_id(&id).ok_or_else(|| format!("Unknown action: {}", id))?;
+    match action {
+        Action::RefreshApps => Ok(format!("Refreshed app index ({} apps)", refresh_apps())),
+        Action::ClearClipboard => {
+            CLIPBOARD_MANAGER.lock().unwrap().clear();
+            Ok("Clipboard history cleared".to_string())
+        }
+        Action::SwitchTheme => {
+            let theme = Config::update(|config| {
+                config.ui.theme = config.ui.theme.toggled();
+                config.ui.theme
+            })?;
+            app.emit("theme-changed", theme).map_err(|e| e.to_string())?;
+            Ok(format!("Switched to {:?} theme", theme))
         }
-        let _ = child.wait();
-        return Ok("Copied via xclip".to_string());
+        Action::RestartBackend => {
+            // The backend process is owned by main.rs, which listens for this
+            app.emit("restart-backend", ()).map_err(|e| e.to_string())?;
+            Ok("Restarting backend...".to_string())
+        }
+        Action::OpenConfig => {
+            let path = Config::ensure_exists()?;
+            let searcher = FILE_SEARCHER.lock().unwrap();
+            searcher.open(&path.to_string_lossy())?;
+            Ok(format!("Opened {}", path.display()))
+        }
+        // The settings page confirms this first (see `ActionInfo::confirm`)
+        Action::EmptyTrash => ruty_core::trash::empty().map(|count| match count {
+            0 => String::from("The trash is already empty"),
+            count => format!("Emptied the trash ({} items)", count),
+        }),
     }
-
-    Err("Failed to copy: no clipboard tool found".to_string())
 }
@@ -1,8 +1,7 @@
 //! Ruty library entry point for Tauri v2
 
-pub mod apps;
-pub mod clipboard;
 pub mod commands;
-pub mod files;
+
+pub use ruty_core::{apps, clipboard, files};
 
 pub use commands::*;
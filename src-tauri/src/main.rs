@@ -11,10 +11,11 @@ use std::sync::Mutex;
 use tauri::{
     menu::{Menu, MenuItem},
     tray::{TrayIcon, TrayIconBuilder},
-    AppHandle, Manager, WebviewWindow,
+    AppHandle, Emitter, Listener, Manager, WebviewWindow,
 };
 
 use ruty_lib::commands;
+use ruty_core::config::Config;
 
 #[cfg(debug_assertions)]
 struct PythonBackend(Mutex<Option<Child>>);
@@ -32,10 +33,19 @@ fn main() {
             // Start Python backend
             spawn_python_backend(app.handle())?;
 
+            // "Restart Backend" action from the palette/settings
+            let handle = app.handle().clone();
+            app.listen("restart-backend", move |_| {
+                stop_python_backend(&handle);
+                if let Err(e) = spawn_python_backend(&handle) {
+                    eprintln!("⚠️  Backend restart failed: {}", e);
+                }
+            });
+
             // Create system tray
             create_tray(app.handle())?;
 
-            // Register global shortcut: Super+Space (non-fatal if fails)
+            // Register global shortcuts from config (non-fatal if fails)
             if let Err(e) = register_global_shortcut(app.handle()) {
                 eprintln!("⚠️  Hotkey registration failed: {}", e);
                 eprintln!("   Use system tray to open Ruty instead");
@@ -50,7 +60,11 @@ fn main() {
             }
 
             println!("✅ Ruty started!");
-            println!("   Press Super+Space to toggle window (or use tray)");
+            let hotkeys = Config::load().hotkeys;
+            println!("   Press {} to toggle window (or use tray)", hotkeys.toggle);
+            if !hotkeys.clipboard.is_empty() {
+                println!("   Press {} for clipboard history", hotkeys.clipboard);
+            }
 
             Ok(())
         })
@@ -59,6 +73,10 @@ fn main() {
             commands::toggle_window_cmd,
             commands::load_context,
             commands::clear_context,
+            commands::get_providers,
+            commands::set_provider,
+            commands::set_api_key,
+            commands::get_api_keys,
             commands::search_apps,
             commands::launch_app,
             commands::refresh_apps,
@@ -68,6 +86,12 @@ fn main() {
             commands::init_clipboard,
             commands::get_clipboard_history,
             commands::copy_to_clipboard,
+            commands::get_autostart,
+            commands::set_autostart,
+            commands::list_actions,
+            commands::run_action,
+            commands::get_theme,
+            commands::translations,
         ])
         .on_window_event(|window, event| {
             // Center window on first show (WebContentsLoaded)
@@ -102,14 +126,7 @@ fn create_tray(app: &AppHandle) -> Result<TrayIcon, Box<dyn std::error::Error>>
                 }
             }
             "quit" => {
-                // Stop Python backend
-                if let Some(state) = app.try_state::<PythonBackend>() {
-                    if let Ok(mut guard) = state.0.lock() {
-                        if let Some(mut child) = guard.take() {
-                            let _ = child.kill();
-                        }
-                    }
-                }
+                stop_python_backend(app);
                 std::process::exit(0);
             }
             _ => {}
@@ -126,30 +143,60 @@ fn create_tray(app: &AppHandle) -> Result<TrayIcon, Box<dyn std::error::Error>>
     Ok(tray)
 }
 
-/// Register Super+Space global shortcut
+/// Register the configured global shortcuts (toggle + clipboard history)
 fn register_global_shortcut(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let hotkeys = Config::load().hotkeys;
+
+    register_shortcut(app, &hotkeys.toggle, toggle_window)?;
+
+    // The clipboard shortcut is optional; an empty binding disables it
+    if !hotkeys.clipboard.is_empty() {
+        if let Err(e) = register_shortcut(app, &hotkeys.clipboard, show_clipboard) {
+            eprintln!("⚠️ Could not set up clipboard hotkey {}: {}", hotkeys.clipboard, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Bind a single accelerator (e.g. "Super+V") to a window action
+fn register_shortcut(
+    app: &AppHandle,
+    accelerator: &str,
+    action: fn(&WebviewWindow),
+) -> Result<(), Box<dyn std::error::Error>> {
     use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
-    let shortcut: Shortcut = "Super+Space".parse()?;
+    let shortcut: Shortcut = accelerator.parse()?;
 
     let app_handle = app.clone();
     app.global_shortcut().on_shortcut(shortcut, move |_app, _shortcut, event| {
         if event.state == ShortcutState::Pressed {
             if let Some(window) = app_handle.get_webview_window("main") {
-                toggle_window(&window);
+                action(&window);
             }
         }
     })?;
 
     // Try to register, but don't fail if already registered
     match app.global_shortcut().register(shortcut) {
-        Ok(_) => println!("✓ Registered Super+Space hotkey"),
-        Err(e) => eprintln!("⚠️ Could not register Super+Space (may be used by another app): {}", e),
+        Ok(_) => println!("✓ Registered {} hotkey", accelerator),
+        Err(e) => eprintln!("⚠️ Could not register {} (may be used by another app): {}", accelerator, e),
     }
 
     Ok(())
 }
 
+/// Show the window (if hidden) and switch the frontend to clipboard history
+fn show_clipboard(window: &WebviewWindow) {
+    if !window.is_visible().unwrap_or(false) {
+        toggle_window(window);
+    } else {
+        let _ = window.set_focus();
+    }
+    let _ = window.emit("open-clipboard", ());
+}
+
 /// Toggle window visibility with aggressive focus handling for Linux
 fn toggle_window(window: &WebviewWindow) {
     if window.is_visible().unwrap_or(false) {
@@ -176,6 +223,17 @@ fn toggle_window(window: &WebviewWindow) {
     }
 }
 
+/// Kill the Python backend if we started one
+fn stop_python_backend(app: &AppHandle) {
+    if let Some(state) = app.try_state::<PythonBackend>() {
+        if let Ok(mut guard) = state.0.lock() {
+            if let Some(mut child) = guard.take() {
+                let _ = child.kill();
+            }
+        }
+    }
+}
+
 /// Spawn the Python FastAPI backend as a subprocess
 fn spawn_python_backend(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let state = app.state::<PythonBackend>();
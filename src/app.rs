@@ -2,14 +2,73 @@
 //!
 //! Uses Iced 0.13 API with polished visual design inspired by Gauntlet/Raycast.
 
-use iced::widget::{container, text_input, column, row, text, scrollable, Space, image};
-use iced::{Element, Length, Theme, Subscription, keyboard, Event, Task, Border, Background, Color, Padding, window};
+use iced::widget::{button, container, text_input, text_editor, column, row, text, rich_text, span, scrollable, stack, Space, image};
+use iced::{Element, Font, Length, Theme, Subscription, keyboard, Event, Task, Border, Background, Color, Padding, window};
 use iced::keyboard::Key;
 
-use crate::backend::api::{BackendClient, ChatRequest};
-use crate::native::apps::AppIndexer;
+use crate::backend::api::{self, BackendClient, ChatRequest, ContextResponse, Memory, ToolApproval, ToolCall};
+use crate::native::clipboard::{self, ClipboardItem};
+use crate::native::dnd;
+use crate::native::embedding;
+use crate::native::network::{self, NetQuery};
+use crate::native::screenshot;
+use crate::native::voice::{self, Recording};
+use crate::native::windows;
+use crate::services::{self, Services};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use crate::hotkey;
-use crate::commands::Command;
+use crate::chat::{self, Conversation, Role, Turn};
+use crate::commands::{
+    self, AliasCommand, BookmarkCommand, CacheCommand, Command, ContextCommand, DebugCommand, DndCommand,
+    SavedCommand,
+};
+use crate::controls::{self, Control, ControlKind, ControlQuery, Target};
+use crate::details::{self, Detail};
+use crate::events;
+use crate::flows;
+use crate::intents::{self, Intent};
+use crate::redraw;
+use crate::regex_tester;
+use crate::scratchpad::{self, Scratchpad};
+use crate::ui::{self, fonts};
+use crate::ui::toast::Toasts;
+use crate::undo::{self, Inverse, UndoStack};
+use crate::window_commands::{Placement, WindowCommand};
+use crate::suggest::{Suggestion, SuggestionEngine};
+use crate::timers::{self, Timers};
+use crate::launches::Launches;
+use crate::paste_queue::{self, PasteQueue};
+use ruty_core::actions::Action;
+use ruty_core::apps::{AppChanges, LaunchOverride};
+use ruty_core::archives;
+use ruty_core::audit;
+use ruty_core::cache::{self, LruCache, MB};
+use ruty_core::calendar;
+use ruty_core::checksum::{self, Algorithm};
+use ruty_core::transforms::Transform;
+use ruty_core::progress::Progress;
+use ruty_core::config::{self, AutoHide, Bookmark, Config, Favorite, FavoriteKind, SavedSearch, SearchCategory};
+use ruty_core::content::{self, ContentKind};
+use ruty_core::doctor::{self, Check};
+use ruty_core::documents::DocumentKind;
+use ruty_core::expansion::{self, Expander};
+use ruty_core::fuzzy;
+use ruty_core::history::{self, History};
+use ruty_core::i18n::{tr, tr_args};
+use ruty_core::ocr;
+use ruty_core::permissions::{self, Capability};
+use ruty_core::qr;
+use ruty_core::sandbox;
+use ruty_core::query::FileQuery;
+use ruty_core::templates;
+use ruty_core::timeline;
+use ruty_core::thumbnails::{self, MediaKind};
+use ruty_core::trash;
+use ruty_core::usage::{self, Usage};
+use regex::Regex;
 
 // ============================================================================
 // Theme Colors (Raycast/Gauntlet inspired)
@@ -17,16 +76,63 @@ use crate::commands::Command;
 
 mod colors {
     use iced::Color;
-    
-    pub const BACKGROUND: Color = Color::from_rgb(0.09, 0.09, 0.11);
-    pub const SURFACE: Color = Color::from_rgb(0.12, 0.12, 0.14);
-    pub const SURFACE_HIGHLIGHT: Color = Color::from_rgb(0.18, 0.18, 0.22);
-    pub const BORDER: Color = Color::from_rgb(0.25, 0.25, 0.28);
-    pub const PRIMARY: Color = Color::from_rgb(0.4, 0.55, 1.0);
-    pub const TEXT: Color = Color::from_rgb(0.95, 0.95, 0.95);
-    pub const TEXT_MUTED: Color = Color::from_rgb(0.55, 0.55, 0.6);
-    pub const TEXT_PLACEHOLDER: Color = Color::from_rgb(0.4, 0.4, 0.45);
-    pub const SELECTION: Color = Color::from_rgb(0.2, 0.25, 0.35);
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    struct Palette {
+        background: Color,
+        surface: Color,
+        border: Color,
+        primary: Color,
+        text: Color,
+        text_muted: Color,
+        text_placeholder: Color,
+        selection: Color,
+        error: Color,
+    }
+
+    const DARK: Palette = Palette {
+        background: Color::from_rgb(0.09, 0.09, 0.11),
+        surface: Color::from_rgb(0.12, 0.12, 0.14),
+        border: Color::from_rgb(0.25, 0.25, 0.28),
+        primary: Color::from_rgb(0.4, 0.55, 1.0),
+        text: Color::from_rgb(0.95, 0.95, 0.95),
+        text_muted: Color::from_rgb(0.55, 0.55, 0.6),
+        text_placeholder: Color::from_rgb(0.4, 0.4, 0.45),
+        selection: Color::from_rgb(0.2, 0.25, 0.35),
+        error: Color::from_rgb(0.95, 0.45, 0.45),
+    };
+
+    const LIGHT: Palette = Palette {
+        background: Color::from_rgb(0.97, 0.97, 0.98),
+        surface: Color::from_rgb(1.0, 1.0, 1.0),
+        border: Color::from_rgb(0.82, 0.82, 0.86),
+        primary: Color::from_rgb(0.2, 0.4, 0.9),
+        text: Color::from_rgb(0.1, 0.1, 0.12),
+        text_muted: Color::from_rgb(0.42, 0.42, 0.47),
+        text_placeholder: Color::from_rgb(0.6, 0.6, 0.65),
+        selection: Color::from_rgb(0.85, 0.89, 0.98),
+        error: Color::from_rgb(0.8, 0.2, 0.2),
+    };
+
+    static LIGHT_MODE: AtomicBool = AtomicBool::new(false);
+
+    pub fn set_light(light: bool) {
+        LIGHT_MODE.store(light, Ordering::Relaxed);
+    }
+
+    fn palette() -> &'static Palette {
+        if LIGHT_MODE.load(Ordering::Relaxed) { &LIGHT } else { &DARK }
+    }
+
+    pub fn background() -> Color { palette().background }
+    pub fn surface() -> Color { palette().surface }
+    pub fn border() -> Color { palette().border }
+    pub fn primary() -> Color { palette().primary }
+    pub fn text() -> Color { palette().text }
+    pub fn text_muted() -> Color { palette().text_muted }
+    pub fn text_placeholder() -> Color { palette().text_placeholder }
+    pub fn selection() -> Color { palette().selection }
+    pub fn error() -> Color { palette().error }
 }
 
 // ============================================================================
@@ -40,6 +146,8 @@ pub enum UIMode {
     Results,
     Chat,
     Settings,
+    /// /regex: pattern in the prompt, test text and matches below
+    Regex,
 }
 
 #[derive(Debug, Clone)]
@@ -58,6 +166,131 @@ pub enum ResultCategory {
     Command,
     AI,
     Clipboard,
+    History,
+    Action,
+    /// Git repository, or an action on one (see `git::RepoAction`)
+    Git,
+    /// Network quick answer; the id is the value Enter copies
+    Network,
+    /// Bluetooth device or audio sink/source
+    Device,
+    /// Quick control adjusted in place (id is a `controls::Target`)
+    Control,
+    /// Application offered by "Open With…" for a file
+    OpenWith,
+    /// List/extract action on an archive (id is `<action>\t<path>`)
+    Archive,
+    /// Checksum from /hash; the id is the digest Enter copies
+    Hash,
+    /// Output of /b64, /url, /json, /uuid or /lorem; the id is the full text
+    Transform,
+    /// Memory stored by the backend, or an action on one (`<action>\t<id>`)
+    Memory,
+    /// Starred app, file or quicklink (id is its index in the favorites)
+    Favorite,
+    /// Pending countdown from /timer (id is the timer's)
+    Timer,
+    /// Address book entry (id is its UID), or an action on one's email
+    /// address or phone number (`<action>\t<value>`)
+    Contact,
+    /// Upcoming calendar event (id is its index in the agenda), or an action
+    /// on one (`<action>\t<index>`)
+    Event,
+    /// Result of a plain prompt that reads as math (id is the prompt)
+    Calculator,
+    /// Search saved under a name (id is the name)
+    Saved,
+    /// Bookmarked folder (id is its path as configured)
+    Bookmark,
+}
+
+impl ResultCategory {
+    /// Stable name used over RPC
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::App => "app",
+            Self::File => "file",
+            Self::Command => "command",
+            Self::AI => "ai",
+            Self::Clipboard => "clipboard",
+            Self::History => "history",
+            Self::Action => "action",
+            Self::Git => "git",
+            Self::Network => "network",
+            Self::Device => "device",
+            Self::Control => "control",
+            Self::OpenWith => "open_with",
+            Self::Archive => "archive",
+            Self::Hash => "hash",
+            Self::Transform => "transform",
+            Self::Memory => "memory",
+            Self::Favorite => "favorite",
+            Self::Timer => "timer",
+            Self::Contact => "contact",
+            Self::Event => "event",
+            Self::Calculator => "calculator",
+            Self::Saved => "saved",
+            Self::Bookmark => "bookmark",
+        }
+    }
+
+    /// Plural display name, for section headers
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::App => "Applications",
+            Self::File => "Files",
+            Self::Command => "Commands",
+            Self::AI => "AI",
+            Self::Clipboard => "Clipboard",
+            Self::History => "History",
+            Self::Action => "Actions",
+            Self::Git => "Repositories",
+            Self::Network => "Network",
+            Self::Device => "Devices",
+            Self::Control => "Controls",
+            Self::OpenWith => "Open With",
+            Self::Archive => "Archive",
+            Self::Hash => "Checksums",
+            Self::Transform => "Transforms",
+            Self::Memory => "Memories",
+            Self::Favorite => "Favorites",
+            Self::Timer => "Timers",
+            Self::Contact => "Contacts",
+            Self::Event => "Agenda",
+            Self::Calculator => "Calculator",
+            Self::Saved => "Saved Searches",
+            Self::Bookmark => "Bookmarks",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "app" => Some(Self::App),
+            "file" => Some(Self::File),
+            "command" => Some(Self::Command),
+            "ai" => Some(Self::AI),
+            "clipboard" => Some(Self::Clipboard),
+            "history" => Some(Self::History),
+            "action" => Some(Self::Action),
+            "git" => Some(Self::Git),
+            "network" => Some(Self::Network),
+            "device" => Some(Self::Device),
+            "control" => Some(Self::Control),
+            "open_with" => Some(Self::OpenWith),
+            "archive" => Some(Self::Archive),
+            "hash" => Some(Self::Hash),
+            "transform" => Some(Self::Transform),
+            "memory" => Some(Self::Memory),
+            "favorite" => Some(Self::Favorite),
+            "timer" => Some(Self::Timer),
+            "contact" => Some(Self::Contact),
+            "event" => Some(Self::Event),
+            "calculator" => Some(Self::Calculator),
+            "saved" => Some(Self::Saved),
+            "bookmark" => Some(Self::Bookmark),
+            _ => None,
+        }
+    }
 }
 
 // ============================================================================
@@ -74,12 +307,202 @@ pub struct Ruty {
     ai_response: String,
     tools_used: Vec<String>,
     backend: BackendClient,
-    app_indexer: AppIndexer,
+    services: Arc<Services>,
+    /// Whether the launcher is shown; hidden, it's 1px and draws nothing
     visible: bool,
+    /// Whether the first frame is drawn
+    interactive: bool,
+    /// Provider held back for lack of a grant, to point out once shown
+    permission_needed: Option<String>,
     focused: bool,
     session_id: String,
+    history: History,
+    /// Steps back from the newest entry while recalling with Up/Down
+    history_cursor: Option<usize>,
+    /// Ctrl+R: results show history matches for the prompt
+    history_search: bool,
+    /// Folder whose children are listed (Right/Tab on a folder); the prompt
+    /// filters them
+    browsing: Option<PathBuf>,
+    /// Day of each listed `/timeline` row, for its day headers
+    timeline: Option<Vec<String>>,
+    /// Completions shown under the search bar (Tab accepts the first)
+    suggestions: Vec<Suggestion>,
+    theme: config::Theme,
+    /// User aliases (kept in sync with config.toml by /alias)
+    aliases: BTreeMap<String, String>,
+    /// Starred results, in order (kept in sync with config.toml)
+    favorites: Vec<Favorite>,
+    /// Searches kept by name (kept in sync with config.toml by /saved)
+    saved_searches: BTreeMap<String, SavedSearch>,
+    /// Bookmarked folders (kept in sync with config.toml by /bm)
+    bookmarks: Vec<Bookmark>,
+    /// `[icons] types`: icons drawn for result types instead of the bundled ones
+    icon_types: BTreeMap<String, String>,
+    /// Launch counts behind the dashboard's frequent apps and recent files
+    usage: Usage,
+    /// Countdowns started with /timer
+    timers: Timers,
+    /// Timer that ends a `/dnd <duration>` focus session
+    focus_timer: Option<u64>,
+    /// Events last listed by "today" or /agenda (rows point into it)
+    agenda: Vec<calendar::Event>,
+    /// The results are the empty-query dashboard (see `is_dashboard`)
+    dashboard: bool,
+    /// Abbreviations from config, expanded as they're typed
+    expander: Expander,
+    /// Side panel for the selected result (Tab toggles)
+    detail: Option<Detail>,
+    /// Paths loaded as AI context for this session, shown as chips
+    context_paths: Vec<String>,
+    /// Memories last listed by /memory
+    memories: Vec<Memory>,
+    /// Microphone recording while push-to-talk is held
+    voice: Option<Recording>,
+    /// Recent loudness of the recording, for the waveform
+    voice_levels: Vec<f32>,
+    /// Whether a recording is being transcribed
+    transcribing: bool,
+    /// Whether chat has offered to download the embedding model this session
+    memory_model_offered: bool,
+    /// Pinned open: losing focus doesn't hide the window
+    pinned: bool,
+    /// Results (and selection) an action menu replaced; Escape goes back
+    action_menu: Option<(Vec<SearchResult>, usize)>,
+    /// Chat request awaiting its reply; Escape stops it
+    chat_request: Option<iced::task::Handle>,
+    /// Tool calls the AI proposed during that request, waiting for an
+    /// answer; the first is shown
+    tool_calls: Vec<ToolCall>,
+    /// Confirmations floating over the bottom of the window
+    toasts: Toasts<Message>,
+    /// How to reverse recent destructive actions (offered in their toasts)
+    undo: UndoStack,
+    /// Action that can't be undone, run by a second Enter (or its toast's
+    /// button) until the deadline
+    unconfirmed: Option<(Action, Instant)>,
+    /// Apps just launched, watched for failing to start
+    launches: Launches,
+    /// Clipboard entries (by id) marked with Ctrl+Space, in order
+    marked_clips: Vec<u64>,
+    /// Marked entries being pasted one at a time
+    paste_queue: Option<PasteQueue>,
+    /// State of listed quick controls, keyed by result id
+    controls: BTreeMap<String, ControlKind>,
+    /// Thumbnails of image/video file results, keyed by path
+    thumbnails: LruCache<String, Thumbnail>,
+    /// Scroll position and height of the results list, for lazy thumbnails
+    results_viewport: (f32, f32),
+    /// Archive picked with "Extract to…", waiting for `/extract-to <dir>`
+    pending_archive: Option<PathBuf>,
+    /// Running background job (status label, progress), e.g. an extraction
+    job: Option<(String, Arc<Progress>)>,
+    /// /regex: the compiled pattern (or its error) and the editable test text
+    regex: Option<(Result<Regex, String>, text_editor::Content)>,
+    /// /pad: the scratchpad window, while it's open
+    pad: Option<Scratchpad>,
+    /// App search made before the index was built, rerun once it is
+    awaiting_index: Option<String>,
+    /// Query the fuzzy-ranked results came from; its matches are highlighted
+    match_query: String,
+    /// Last submitted prompt, rerun by an error card's Retry
+    submitted: String,
+    /// Shown instead of the hint when a search found nothing
+    empty: Option<EmptyState>,
+    /// Shown instead of the response when a request failed
+    failure: Option<Failure>,
+    /// Categories still being searched in the background, shown as skeletons
+    pending: Vec<ResultCategory>,
+    /// Bumped per background search so late results of an older one are dropped
+    search_generation: u64,
+    /// Bumped per prompt edit, so only the last of a burst of keystrokes
+    /// runs its debounced live search
+    keystrokes: u64,
+    /// The results are a live search of a plain prompt and no row has been
+    /// picked yet: none shows as selected, and Enter asks AI
+    unpicked: bool,
+    /// What the plain prompt asks for, run by Enter instead of asking AI
+    /// (Tab asks AI anyway)
+    intent: Option<Intent>,
+    /// Start of the current background search, for the spinner frame
+    search_started: Instant,
+    /// Sections collapsed from their header; kept across queries
+    collapsed: Vec<ResultCategory>,
+    /// Id of a clear best match, featured above the list while it is the
+    /// first result
+    hero: Option<String>,
+    /// Chat turns of this session
+    conversation: Conversation,
+    /// The prompt holds an edit of the last chat prompt; sending it replaces
+    /// that exchange
+    editing_last: bool,
+}
+
+/// Blocking search of one category, run off the UI thread
+type CategorySearch = fn(&Services, &str) -> Vec<SearchResult>;
+
+/// A search that found nothing: what was searched and what to try next
+#[derive(Debug, Clone, PartialEq)]
+struct EmptyState {
+    message: String,
+    hint: String,
+}
+
+/// A failed request, shown as an error card
+#[derive(Debug, Clone, PartialEq)]
+struct Failure {
+    /// What failed, e.g. "AI request failed"
+    title: &'static str,
+    detail: String,
+    /// Prompt that makes the request again, if retrying makes sense
+    retry: Option<String>,
+}
+
+/// Thumbnail state of a file result
+#[derive(Debug, Clone, PartialEq)]
+enum Thumbnail {
+    Loading,
+    /// Loaded from the PNG bytes, so dropping it frees the image
+    Ready(image::Handle),
+    /// Not decodable; keep the generic icon
+    Failed,
 }
 
+/// Height of a result row plus spacing, used to work out which rows are visible
+const RESULT_ROW_HEIGHT: f32 = 60.0;
+
+/// Skeleton rows shown per category while it is searched
+const SKELETON_ROWS: usize = 3;
+
+/// Spinner frames for categories still searching (one per `JOB_TICK`)
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Refresh interval of the status line while a background job runs, of the
+/// spinners while a search does, and of the waveform while recording
+const JOB_TICK: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Bars in the voice input waveform
+const WAVEFORM_BARS: usize = 32;
+
+/// Frequent apps and recent files on the dashboard
+const DASHBOARD_ROWS: usize = 5;
+
+/// Prompts up to this long list matching bookmarks first
+const BOOKMARK_QUERY_CHARS: usize = 4;
+
+/// Entries listed by /timeline
+const TIMELINE_ROWS: usize = 100;
+
+/// Entries /audit shows without a start
+const AUDIT_SHOWN: usize = 200;
+
+/// Clipboard entries on the dashboard
+const DASHBOARD_CLIPS: usize = 3;
+
+/// How long a question in a toast (confirming an action that can't be
+/// undone, downloading the speech model) waits for an answer
+const CONFIRM_WINDOW: Duration = Duration::from_secs(5);
+
 #[derive(Debug, Clone)]
 pub enum Message {
     PromptChanged(String),
@@ -88,20 +511,136 @@ pub enum Message {
     SelectPrevious,
     ExecuteSelected,
     Escape,
-    SearchComplete(Vec<SearchResult>),
+    /// Typing paused on the prompt this keystroke count left (see `keystrokes`)
+    LiveSearch(u64),
+    /// Tab on a recognized request: send the prompt to AI as typed
+    AskAiInstead,
+    /// Rows of one category from a background search
+    SearchComplete { generation: u64, category: ResultCategory, results: Vec<SearchResult> },
     AIResponseChunk(String),
     AIResponseWithTools { response: String, tools: Vec<String> },
     AIResponseComplete,
     AIError(String),
+    /// The AI wants to run a command or open a link
+    ToolCallProposed(ToolCall),
+    /// The backend got the answer to a tool call
+    ToolCallDecided(Result<(), String>),
+    /// Something to check: a wakeup from hotkeys/RPC, or a job progress tick
     Tick,
     WindowFocusLost,
     HotkeyPressed,
+    HistoryPrevious,
+    HistoryNext,
+    HistorySearch,
+    AcceptSuggestion,
+    CommandPalette,
+    /// Ctrl+O: actions for the selected file (archive actions, Open With…),
+    /// or the other installs of the selected app
+    FileActions,
+    ArchiveExtracted(Result<String, String>),
+    HashComputed { algorithm: Algorithm, source: String, result: Result<String, String> },
+    ToggleDetail,
+    /// List the selected folder's children
+    BrowseInto,
+    /// List the parent of the folder being browsed
+    BrowseUp,
+    FileDropped(PathBuf),
+    /// Unload the context chip at this position
+    RemoveContext(usize),
+    ContextUpdated(Result<ContextResponse, String>),
+    ContextListed(Result<Vec<String>, String>),
+    MemoriesListed(Result<Vec<Memory>, String>),
+    /// `/doctor` ran its checks
+    DoctorFinished(Vec<Check>),
+    /// A memory was deleted (by id)
+    MemoryDeleted(String, Result<(), String>),
+    VoiceTranscribed(Result<String, String>),
+    /// The user agreed to download the speech model
+    DownloadVoiceModel,
+    VoiceModelDownloaded(Result<(), String>),
+    /// The user agreed to download the embedding model `[memory]` needs
+    DownloadMemoryModel,
+    MemoryModelDownloaded(Result<(), String>),
+    /// Ctrl+. or the pin button: keep the window open on focus loss
+    TogglePin,
+    /// Copy a chat turn's text (by index)
+    CopyTurn(usize),
+    /// Ctrl+Delete: delete the selected clipboard entry or trash the file
+    DeleteSelected,
+    /// Ctrl+D: star the selected app or file, or unstar a favorite
+    ToggleFavorite,
+    /// Ctrl+Space: mark the selected clipboard entry to merge or paste in
+    /// sequence, or unmark it
+    MarkClip,
+    /// Give up on pasting the marked entries one at a time
+    StopPasting,
+    /// Text read from the copied image by OCR
+    TextExtracted(Result<String, String>),
+    /// Codes read from a screen region (`None` if the selection was cancelled)
+    QrScanned(Result<Option<Vec<String>>, String>),
+    /// Upcoming events, read for this prompt
+    AgendaListed(String, Vec<calendar::Event>),
+    /// A show/hide/quit command sent over RPC
+    Window(WindowCommand),
+    /// Where the window goes, worked out after it was shown
+    WindowPlaced(Placement),
+    /// Something published on the event bus
+    Bus(events::Event),
+    /// A `/flow` finished: its name and the output worth showing, if any
+    FlowFinished(String, Result<Option<String>, String>),
+    /// Ask for what a provider (see `permissions`) was held back without
+    ReviewPermission(String),
+    /// Ctrl+Up/Down: move the selected favorite up or down the list
+    MoveFavorite(isize),
+    /// Reverse a destructive action (by undo stack id)
+    Undo(u64),
+    /// Run an action that can't be undone, from its confirmation toast
+    ConfirmAction(Action),
+    PublicIpFetched(Result<String, String>),
+    ControlsListed(Vec<Control>),
+    /// Slider dragged to a level (applied on release)
+    ControlChanged(String, u8),
+    ControlReleased(String),
+    /// Left/Right: move the selected slider by this many points (or browse
+    /// folders when no slider is selected)
+    AdjustControl(i8),
+    ControlApplied(Result<(), String>),
+    ResultsScrolled { offset: f32, height: f32 },
+    ThumbnailLoaded(String, Result<image::Handle, String>),
+    RegexTextEdited(text_editor::Action),
+    /// Typing in the scratchpad window
+    PadEdited(text_editor::Action),
+    /// A window was closed (by us, or the window manager)
+    WindowClosed(window::Id),
+    /// The first frame is drawn: startup is done (see `startup`)
+    FirstFrame,
+    /// Error card's Retry: submit the failed prompt again
+    Retry,
+    /// Section header clicked: collapse or expand that category's rows
+    ToggleSection(ResultCategory),
+    /// Chat view scrolled (relative offset, 0.0 top to 1.0 bottom)
+    ChatScrolled(f32),
+    /// Put the last chat prompt back in the input to edit and resend
+    EditLastPrompt,
+    /// Ask again for the last reply, replacing it
+    Regenerate,
     IcedEvent(Event),
 }
 
 impl Default for Ruty {
     fn default() -> Self {
-        Self {
+        let services = crate::get_services().unwrap_or_else(|| Arc::new(Services::new()));
+        let theme = services.config().ui.theme;
+        let aliases = services.config().aliases.clone();
+        let favorites = services.config().favorites.clone();
+        let saved_searches = services.config().saved_searches.clone();
+        let bookmarks = services.config().bookmarks.clone();
+        let icon_types = services.config().icons.types.clone();
+        let expander = Expander::new(&services.config().abbreviations);
+        let thumbnail_budget = services.config().cache.thumbnails_mb * MB;
+        colors::set_light(theme == config::Theme::Light);
+
+        let mut ruty = Self {
             prompt: String::new(),
             results: Vec::new(),
             selected_index: 0,
@@ -111,11 +650,72 @@ impl Default for Ruty {
             ai_response: String::new(),
             tools_used: Vec::new(),
             backend: BackendClient::new(),
-            app_indexer: AppIndexer::new(),
-            visible: true,
+            services,
+            visible: crate::get_window_controller().is_none_or(|controller| controller.is_visible()),
+            interactive: false,
+            permission_needed: None,
             focused: true,
             session_id: uuid::Uuid::new_v4().to_string(),
-        }
+            history: History::load(),
+            history_cursor: None,
+            history_search: false,
+            browsing: None,
+            timeline: None,
+            suggestions: Vec::new(),
+            theme,
+            aliases,
+            favorites,
+            saved_searches,
+            bookmarks,
+            icon_types,
+            usage: Usage::load(),
+            timers: Timers::default(),
+            focus_timer: None,
+            agenda: Vec::new(),
+            dashboard: false,
+            expander,
+            detail: None,
+            context_paths: Vec::new(),
+            memories: Vec::new(),
+            voice: None,
+            voice_levels: Vec::new(),
+            transcribing: false,
+            memory_model_offered: false,
+            pinned: false,
+            action_menu: None,
+            chat_request: None,
+            tool_calls: Vec::new(),
+            toasts: Toasts::default(),
+            undo: UndoStack::default(),
+            unconfirmed: None,
+            launches: Launches::default(),
+            marked_clips: Vec::new(),
+            paste_queue: None,
+            controls: BTreeMap::new(),
+            thumbnails: LruCache::new(thumbnail_budget),
+            results_viewport: (0.0, 480.0),
+            pending_archive: None,
+            job: None,
+            regex: None,
+            pad: None,
+            awaiting_index: None,
+            match_query: String::new(),
+            submitted: String::new(),
+            empty: None,
+            failure: None,
+            pending: Vec::new(),
+            search_generation: 0,
+            keystrokes: 0,
+            unpicked: false,
+            intent: None,
+            search_started: Instant::now(),
+            collapsed: Vec::new(),
+            hero: None,
+            conversation: Conversation::default(),
+            editing_last: false,
+        };
+        ruty.show_dashboard();
+        ruty
     }
 }
 
@@ -124,20 +724,98 @@ impl Ruty {
         Self::default()
     }
 
-    pub fn title(&self) -> String {
-        String::from("Ruty")
+    pub fn title(&self, window: window::Id) -> String {
+        if scratchpad::is_pad(window) {
+            String::from("Ruty Scratchpad")
+        } else {
+            String::from("Ruty")
+        }
     }
 
     pub fn update(&mut self, message: Message) -> Task<Message> {
+        let task = self.handle_message(message);
+        self.sync_detail();
+        Task::batch([task, self.load_visible_thumbnails()])
+    }
+
+    fn handle_message(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::PromptChanged(new_prompt) => {
+                if let Some((expanded, cursor)) = expansion::insertion_end(&self.prompt, &new_prompt)
+                    .and_then(|end| self.expander.expand_at(&new_prompt, end))
+                {
+                    // Recorded first so the expansion itself isn't seen as typing
+                    self.prompt = expanded.clone();
+                    let position = expanded[..cursor].chars().count();
+                    return Task::batch([
+                        self.update(Message::PromptChanged(expanded)),
+                        text_input::move_cursor_to(prompt_input_id(), position),
+                    ]);
+                }
                 self.prompt = new_prompt.clone();
+                self.action_menu = None;
+                self.dashboard = false;
+                self.history_cursor = None;
+                self.empty = None;
+                self.failure = None;
+                self.pending.clear();
+                self.keystrokes += 1;
+                self.unpicked = false;
+                self.intent = None;
+                self.unconfirmed = None;
+                self.timeline = None;
+
+                if self.history_search {
+                    self.show_history_matches();
+                    return Task::none();
+                }
+                if self.browsing.is_some() {
+                    self.show_browse(None);
+                    return Task::none();
+                }
+                // ",w" jumps to the bookmark with that shortcut
+                if let Some(bookmark) = new_prompt
+                    .strip_prefix(',')
+                    .and_then(|shortcut| self.bookmarks.iter().find(|b| b.shortcut.as_deref() == Some(shortcut)))
+                {
+                    let folder = config::expand_home(&bookmark.path);
+                    return self.browse_folder(folder);
+                }
+                if let Some(query) = new_prompt.strip_prefix('>') {
+                    self.suggestions.clear();
+                    self.show_actions(query);
+                    return Task::none();
+                }
+                self.update_suggestions();
                 
-                // Clear results when prompt is empty
-                if new_prompt.is_empty() {
+                if let Some(query) = NetQuery::parse(&new_prompt) {
+                    return self.show_network(query);
+                }
+                if let Some(query) = ControlQuery::parse(&new_prompt) {
+                    return self.show_controls(query);
+                }
+                if is_agenda_query(&new_prompt) {
+                    return self.show_agenda(None);
+                }
+                if self.results.iter().any(|r| r.category == ResultCategory::Event) {
                     self.results.clear();
                     self.mode = UIMode::Search;
                 }
+                if let Some(pattern) = regex_tester::pattern(&new_prompt) {
+                    self.show_regex(pattern);
+                    return Task::none();
+                }
+                if self.mode == UIMode::Regex {
+                    self.regex = None;
+                    self.mode = UIMode::Search;
+                }
+
+                // Clear results when prompt is empty
+                if new_prompt.is_empty() {
+                    self.awaiting_index = None;
+                    self.editing_last = false;
+                    self.show_dashboard();
+                }
                 // Only show results preview for /app command
                 else if new_prompt.starts_with("/app ") {
                     let query = new_prompt.strip_prefix("/app ").unwrap_or("");
@@ -145,6 +823,82 @@ impl Ruty {
                         self.search(query);
                     }
                 }
+                else if let Some(query) = new_prompt.strip_prefix("/git ") {
+                    if !query.trim().is_empty() {
+                        return self.search_repos(query.trim());
+                    }
+                }
+                // File search, hashing and transforms wait for Enter; drop stale rows meanwhile
+                else if matches!(
+                    new_prompt.split_whitespace().next(),
+                    Some(
+                        "/file" | "/files" | "/f" | "/hash" | "/checksum" | "/b64" | "/base64" | "/url"
+                            | "/json" | "/uuid" | "/lorem"
+                    )
+                ) {
+                    if self.results.iter().any(|r| {
+                        matches!(r.category, ResultCategory::File | ResultCategory::Hash | ResultCategory::Transform)
+                    }) {
+                        self.results.clear();
+                        self.mode = UIMode::Search;
+                    }
+                }
+                // /clip filters history as it's typed
+                else if let Some(filter) =
+                    new_prompt.strip_prefix("/clip ").or_else(|| new_prompt.strip_prefix("/clipboard "))
+                {
+                    self.filter_clipboard(filter);
+                }
+                // /ask-clip lists clipboard entries to pick from
+                else if new_prompt.starts_with("/ask-clip ") || new_prompt.starts_with("/ac ") {
+                    if !self.results.iter().any(|r| r.category == ResultCategory::Clipboard) {
+                        self.show_clipboard();
+                    }
+                }
+                // /tpl offers the templates until a name is typed out
+                else if let Some(rest) = new_prompt.strip_prefix("/tpl ").or_else(|| new_prompt.strip_prefix("/template ")) {
+                    if !rest.contains(char::is_whitespace) {
+                        self.show_templates(rest);
+                    } else if self.results.iter().any(|r| r.category == ResultCategory::Command) {
+                        self.results.clear();
+                        self.mode = UIMode::Search;
+                    }
+                }
+                else if let Some(filter) = new_prompt.strip_prefix("/flow ").or_else(|| new_prompt.strip_prefix("/flows ")) {
+                    self.show_flows(filter.trim());
+                }
+                else if let Some(query) = new_prompt.strip_prefix("/devices ") {
+                    self.show_devices(query, &[ResultCategory::Device], false);
+                }
+                else if let Some(query) = new_prompt.strip_prefix("/contacts ") {
+                    self.show_devices(query, &[ResultCategory::Contact], false);
+                }
+                // Plain prompts search everything once typing pauses; during a
+                // chat they're follow-ups, so results don't cover the replies
+                else if !new_prompt.starts_with('/') {
+                    // Math shows its result right away, ready for Enter
+                    if self.mode != UIMode::Chat {
+                        if let Some(row) = self.services.calculate(&new_prompt) {
+                            self.show_calculation(row);
+                            return Task::none();
+                        }
+                    }
+                    let search = self.services.config().search.clone();
+                    if search.intents {
+                        self.intent = intents::classify(&new_prompt, &self.services.app_names());
+                    }
+                    if search.live && self.mode != UIMode::Chat {
+                        // Rows of the text so far aren't for Enter to run
+                        self.unpicked = true;
+                        let keystroke = self.keystrokes;
+                        return Task::perform(
+                            tokio::time::sleep(Duration::from_millis(search.debounce_ms)),
+                            move |_| Message::LiveSearch(keystroke),
+                        );
+                    }
+                    // Otherwise one naming a device or contact offers it ("airpods" + Enter)
+                    self.show_devices(&new_prompt, &[ResultCategory::Device, ResultCategory::Contact], true);
+                }
                 
                 Task::none()
             }
@@ -152,36 +906,352 @@ impl Ruty {
             Message::PromptSubmit => {
                 let prompt = self.prompt.clone();
                 
+                if self.history_search {
+                    return self.accept_history_match();
+                }
+                if self.browsing.is_some() {
+                    return self.execute_selected();
+                }
+
                 if prompt.is_empty() {
+                    // Second Enter on an action waiting for confirmation
+                    if let Some((action, _)) = self.unconfirmed.take().filter(|(_, until)| Instant::now() < *until) {
+                        return self.update(Message::ConfirmAction(action));
+                    }
+                    // Enter on a favorite
+                    if self.mode == UIMode::Results {
+                        return self.execute_selected();
+                    }
+                    return Task::none();
+                }
+                self.suggestions.clear();
+                self.failure = None;
+                self.submitted = prompt.clone();
+                // Only a chat message can stand in for the edited prompt
+                let editing_last = std::mem::take(&mut self.editing_last);
+
+                // Palette actions and file action rows don't depend on the prompt
+                if prompt.starts_with('>')
+                    || self.results.iter().any(|r| {
+                        matches!(r.category, ResultCategory::OpenWith | ResultCategory::Archive | ResultCategory::Event)
+                    })
+                {
+                    return self.execute_selected();
+                }
+
+                // Aliases expand before anything else looks at the prompt
+                let expanded = commands::expand_alias(&prompt, &self.aliases)
+                    .unwrap_or_else(|| prompt.clone());
+
+                // Quicklink keyword: open the expanded URL
+                if let Some(url) = self.services.config().quicklink_url(&expanded) {
+                    self.record_history(history::Kind::Search, &prompt);
+                    if let Err(e) = self.services.open_url(&url) {
+                        tracing::warn!("Quicklink failed: {}", e);
+                    }
+                    return Task::none();
+                }
+
+                let command = Command::parse(&expanded);
+                // A recognized request runs as what it amounts to, unless a row was picked
+                let intent = self
+                    .intent
+                    .take()
+                    .filter(|_| matches!(command, Command::Chat { .. }))
+                    .filter(|_| self.mode != UIMode::Results || self.results.is_empty() || self.unpicked);
+                let command = match &intent {
+                    Some(Intent { target: intents::Target::Prompt(request), .. }) => Command::parse(request),
+                    _ => command,
+                };
+                let kind = match command {
+                    Command::Chat { .. } if self.results.is_empty() && intent.is_none() => history::Kind::Chat,
+                    _ => history::Kind::Search,
+                };
+                self.record_history(kind, &prompt);
+                if let Some(Intent { target: intents::Target::Action(action), .. }) = intent {
+                    self.run_action(action);
                     return Task::none();
                 }
                 
                 // Parse command
-                match Command::parse(&prompt) {
+                match command {
                     Command::App { query } => {
-                        // Search for apps and switch to results mode
+                        // Search for apps and switch to results mode; with no
+                        // match, Enter asks AI instead (as the empty state says)
                         self.search(&query);
+                        if self.results.is_empty() && self.awaiting_index.is_none() && !query.trim().is_empty() {
+                            self.empty = None;
+                            return self.send_chat(query, None);
+                        }
                         self.mode = UIMode::Results;
                         return Task::none();
                     }
-                    Command::Context { path } => {
-                        self.loading = true;
+                    Command::Hash { algorithm, target } => {
+                        // Enter on a shown checksum copies it
+                        if self.results.iter().any(|r| r.category == ResultCategory::Hash) {
+                            return self.execute_selected();
+                        }
+                        return self.hash(algorithm, &target);
+                    }
+                    Command::Regex { pattern } => {
+                        self.show_regex(&pattern);
+                        return Task::none();
+                    }
+                    Command::Transform { transform, text } => {
+                        if self.results.iter().any(|r| r.category == ResultCategory::Transform) {
+                            return self.execute_selected();
+                        }
+                        self.transform(transform, &text);
+                        return Task::none();
+                    }
+                    Command::ExtractTo { dest } => {
+                        let Some(archive) = self.pending_archive.take() else {
+                            self.ai_response = String::from("Pick an archive first: Ctrl+O on it, then \"Extract to…\"");
+                            self.mode = UIMode::Chat;
+                            return Task::none();
+                        };
+                        return self.start_extraction(archive, config::expand_home(&dest));
+                    }
+                    Command::File { query } => {
+                        if self.results.iter().any(|r| r.category == ResultCategory::File) {
+                            return self.execute_selected();
+                        }
+                        return self.search_files(&query);
+                    }
+                    Command::Devices { query } => {
+                        if self.results.iter().any(|r| r.category == ResultCategory::Device) {
+                            return self.execute_selected();
+                        }
+                        self.show_devices(&query, &[ResultCategory::Device], false);
+                        return Task::none();
+                    }
+                    Command::Contacts { query } => {
+                        if self.results.iter().any(|r| r.category == ResultCategory::Contact) {
+                            return self.execute_selected();
+                        }
+                        self.show_devices(&query, &[ResultCategory::Contact], false);
+                        return Task::none();
+                    }
+                    Command::Git { query } => {
+                        // Enter on a listed repo opens its actions
+                        if self.results.iter().any(|r| r.category == ResultCategory::Git) {
+                            return self.execute_selected();
+                        }
+                        return self.search_repos(&query);
+                    }
+                    Command::Launch { query } => {
+                        self.launch_best_match(&query);
+                        return Task::none();
+                    }
+                    Command::Alias(alias) => {
+                        self.ai_response = self.manage_alias(alias);
+                        self.ai_status.clear();
                         self.mode = UIMode::Chat;
-                        let backend = self.backend.clone();
-                        let session_id = self.session_id.clone();
-                        return Task::perform(
-                            async move {
-                                backend.load_context(&session_id, &path).await
-                            },
-                            |result| match result {
-                                Ok(resp) => Message::AIResponseChunk(resp.message),
-                                Err(e) => Message::AIError(e),
+                        return Task::none();
+                    }
+                    // Enter on the listed searches runs the highlighted one
+                    Command::Saved(SavedCommand::List)
+                        if self.results.iter().any(|r| r.category == ResultCategory::Saved) =>
+                    {
+                        return self.execute_selected();
+                    }
+                    Command::Saved(saved) => {
+                        return self.manage_saved(saved);
+                    }
+                    // Enter on the listed bookmarks browses the highlighted one
+                    Command::Bookmark(BookmarkCommand::List)
+                        if self.results.iter().any(|r| r.category == ResultCategory::Bookmark) =>
+                    {
+                        return self.execute_selected();
+                    }
+                    Command::Bookmark(bookmark) => {
+                        self.manage_bookmark(bookmark);
+                        return Task::none();
+                    }
+                    Command::Context(context) => {
+                        return self.manage_context(context);
+                    }
+                    // Enter on the listed entries copies the highlighted one
+                    Command::Clipboard { .. }
+                        if self.results.iter().any(|r| r.category == ResultCategory::Clipboard) =>
+                    {
+                        return self.execute_selected();
+                    }
+                    Command::Clipboard { filter } => {
+                        self.filter_clipboard(&filter);
+                        if self.results.is_empty() {
+                            self.empty = Some(EmptyState {
+                                message: tr("empty-clipboard"),
+                                hint: tr("empty-clipboard-hint"),
+                            });
+                        }
+                        return Task::none();
+                    }
+                    Command::Ocr => {
+                        self.prompt.clear();
+                        return self.extract_image_text();
+                    }
+                    Command::Pad => {
+                        self.prompt.clear();
+                        return self.toggle_pad();
+                    }
+                    // Enter on the listed rows runs the highlighted one
+                    Command::Qr { .. } if self.results.iter().any(|r| r.id.starts_with("qr-")) => {
+                        return self.execute_selected();
+                    }
+                    Command::Qr { text } if text.is_empty() => {
+                        self.show_qr_menu();
+                        return Task::none();
+                    }
+                    Command::Qr { text } => {
+                        self.show_qr(&text);
+                        return Task::none();
+                    }
+                    Command::AskClip { question } => {
+                        let selected = self
+                            .results
+                            .get(self.selected_index)
+                            .filter(|r| r.category == ResultCategory::Clipboard)
+                            .and_then(|r| r.id.parse::<u64>().ok());
+                        let history = self.services.clipboard().get_history();
+                        let item = match selected {
+                            Some(id) => history.into_iter().find(|i| i.id == id),
+                            None => history.into_iter().next(),
+                        };
+                        self.results.clear();
+                        let Some(item) = item else {
+                            self.ai_response = "Clipboard history is empty".to_string();
+                            self.mode = UIMode::Chat;
+                            return Task::none();
+                        };
+                        let context = format!("### Clipboard\n```\n{}\n```", item.content);
+                        return self.send_chat(question, Some(context));
+                    }
+                    // Enter on the picker fills in the highlighted template
+                    Command::Template { .. }
+                        if self.results.iter().any(|r| r.category == ResultCategory::Command) =>
+                    {
+                        return self.execute_selected();
+                    }
+                    // Enter on the picker runs the highlighted flow
+                    Command::Flow { .. } if self.results.iter().any(|r| r.category == ResultCategory::Command) => {
+                        return self.execute_selected();
+                    }
+                    Command::Flow { name: None } => {
+                        self.show_flows("");
+                        return Task::none();
+                    }
+                    Command::Flow { name: Some(name) } => {
+                        self.prompt.clear();
+                        return self.run_flow(name);
+                    }
+                    Command::Permissions => {
+                        self.prompt.clear();
+                        self.show_permissions();
+                        return Task::none();
+                    }
+                    Command::Template { name: None, .. } => {
+                        self.show_templates("");
+                        return Task::none();
+                    }
+                    Command::Template { name: Some(name), input } => {
+                        let Some(template) = self.services.config().templates.get(&name).cloned() else {
+                            self.fail(
+                                "Unknown template",
+                                format!("No template named '{}' under [templates] in config.toml", name),
+                                None,
+                            );
+                            return Task::none();
+                        };
+                        let services = self.services.clone();
+                        let expanded = templates::expand(
+                            &template,
+                            &input,
+                            || services.clipboard().get_history().into_iter().next().map(|item| item.content),
+                            clipboard::selection,
+                        );
+                        return match expanded {
+                            Ok(prompt) => self.send_chat(prompt, None),
+                            Err(e) => {
+                                self.fail("Couldn't fill in the template", e, None);
+                                Task::none()
                             }
-                        ).chain(Task::done(Message::AIResponseComplete));
+                        };
+                    }
+                    Command::Cache(command) => {
+                        self.ai_response = self.manage_cache(command);
+                        self.ai_status.clear();
+                        self.mode = UIMode::Chat;
+                        return Task::none();
+                    }
+                    Command::Memory { query } => {
+                        return self.list_memories(query);
+                    }
+                    Command::Apps { query } => {
+                        self.prompt.clear();
+                        self.show_app_visibility(&query);
+                        return Task::none();
+                    }
+                    // Enter on the listed rows runs the highlighted one
+                    Command::Dnd(DndCommand::Status)
+                        if self.results.iter().any(|r| r.category == ResultCategory::Command && r.id.starts_with("/dnd")) =>
+                    {
+                        return self.execute_selected();
+                    }
+                    Command::Dnd(command) => {
+                        self.prompt.clear();
+                        self.run_dnd(command);
+                        return Task::none();
+                    }
+                    Command::Agenda { days } => {
+                        self.prompt.clear();
+                        return self.show_agenda(days);
+                    }
+                    Command::Timeline => {
+                        self.prompt.clear();
+                        self.show_timeline();
+                        return Task::none();
+                    }
+                    Command::Audit { since } => {
+                        self.prompt.clear();
+                        self.show_audit(&since);
+                        return Task::none();
+                    }
+                    Command::Timer { duration, label } => {
+                        self.timers.start(duration, &label, Instant::now());
+                        self.prompt.clear();
+                        self.toasts.push(
+                            format!("Timer set for {}", timers::format_remaining(duration)),
+                            Instant::now(),
+                        );
+                        self.show_dashboard();
+                        return Task::none();
+                    }
+                    Command::Favorite { keyword: None } => {
+                        self.prompt.clear();
+                        self.show_favorites();
+                        if self.results.is_empty() {
+                            self.empty = Some(EmptyState {
+                                message: tr("empty-favorites"),
+                                hint: tr("empty-favorites-hint"),
+                            });
+                        }
+                        return Task::none();
+                    }
+                    Command::Favorite { keyword: Some(keyword) } => {
+                        if !self.services.config().quicklinks.contains_key(&keyword) {
+                            self.fail("Not a quicklink", format!("No quicklink named '{}' in config.toml", keyword), None);
+                        } else {
+                            self.prompt.clear();
+                            self.star(Favorite { kind: FavoriteKind::Quicklink, target: keyword.clone(), title: keyword });
+                            self.show_favorites();
+                        }
+                        return Task::none();
                     }
                     Command::Clear => {
                         self.prompt.clear();
                         self.ai_response.clear();
+                        self.conversation.clear();
                         self.results.clear();
                         self.mode = UIMode::Search;
                         return Task::none();
@@ -218,93 +1288,206 @@ impl Ruty {
                         return Task::none();
                     }
                     Command::Help => {
-                        self.ai_response = Command::help_text().to_string();
+                        self.ai_response = Command::help_text();
                         self.mode = UIMode::Chat;
                         return Task::none();
                     }
-                    Command::Settings => {
-                        self.ai_response = "Settings not yet implemented".to_string();
+                    Command::Doctor => {
+                        self.loading = true;
+                        self.ai_status = "🩺 Checking the setup...".to_string();
+                        self.ai_response.clear();
+                        self.results.clear();
+                        self.mode = UIMode::Chat;
+                        return Task::perform(crate::doctor::run(), Message::DoctorFinished);
+                    }
+                    Command::Debug(DebugCommand::Net) => {
+                        self.ai_response = crate::backend::trace::report();
+                        self.ai_status.clear();
                         self.mode = UIMode::Chat;
                         return Task::none();
                     }
+                    Command::Settings => {
+                        return self.update(Message::CommandPalette);
+                    }
                     Command::Chat { message } => {
                         // Regular chat - send to AI
-                        if !self.results.is_empty() {
+                        if !self.results.is_empty() && !self.unpicked {
                             // If there are search results, execute selected instead
-                            self.execute_selected();
-                            return Task::none();
+                            return self.execute_selected();
                         }
                         
-                        self.loading = true;
-                        self.ai_status = "🤔 Thinking...".to_string();
-                        self.ai_response.clear();
-                        self.tools_used.clear();
-                        self.mode = UIMode::Chat;
-                        
-                        let backend = self.backend.clone();
-                        let session_id = self.session_id.clone();
-                        return Task::perform(
-                            async move {
-                                let request = ChatRequest {
-                                    message,
-                                    session_id,
-                                    local_context: None,
-                                    api_keys: None,
-                                };
-                                backend.chat(request).await
-                            },
-                            |result| match result {
-                                Ok(resp) => Message::AIResponseWithTools {
-                                    response: resp.response,
-                                    tools: resp.tools_used,
-                                },
-                                Err(e) => Message::AIError(e),
-                            }
-                        ).chain(Task::done(Message::AIResponseComplete));
+                        self.editing_last = editing_last;
+                        return self.send_chat(message, None);
                     }
                 }
             }
             
             Message::SelectNext => {
-                if !self.results.is_empty() {
-                    self.selected_index = (self.selected_index + 1) % self.results.len();
-                }
+                self.step_selection(true);
                 Task::none()
             }
             
             Message::SelectPrevious => {
-                if !self.results.is_empty() {
-                    self.selected_index = if self.selected_index == 0 {
-                        self.results.len() - 1
-                    } else {
-                        self.selected_index - 1
-                    };
-                }
+                self.step_selection(false);
                 Task::none()
             }
-            
-            Message::ExecuteSelected => {
-                self.execute_selected();
+
+            Message::ChatScrolled(relative_y) => {
+                self.conversation.scrolled(relative_y);
+                Task::none()
+            }
+
+            Message::EditLastPrompt => {
+                let Some(index) = self.conversation.last_prompt() else {
+                    return Task::none();
+                };
+                self.prompt = self.conversation.turns()[index].text.clone();
+                self.editing_last = true;
+                Task::batch([
+                    text_input::focus(prompt_input_id()),
+                    text_input::move_cursor_to_end(prompt_input_id()),
+                ])
+            }
+
+            Message::Regenerate => {
+                if self.loading {
+                    return Task::none();
+                }
+                match self.conversation.pop_exchange() {
+                    Some(prompt) => self.request_chat(prompt.text, prompt.context, true),
+                    None => Task::none(),
+                }
+            }
+
+            Message::ToggleSection(category) => {
+                if let Some(position) = self.collapsed.iter().position(|c| *c == category) {
+                    self.collapsed.remove(position);
+                } else {
+                    self.collapsed.push(category);
+                }
+                // Keep the selection on a row that is still shown
+                if self.is_hidden(self.selected_index) {
+                    self.step_selection(true);
+                }
                 Task::none()
             }
             
+            Message::ExecuteSelected => self.execute_selected(),
+            
+            // One layer per press: stop a reply, close the detail panel or an
+            // action menu, leave chat, clear the query, then hide
             Message::Escape => {
-                if self.mode == UIMode::Chat {
+                if let Some(request) = self.chat_request.take() {
+                    // Stopping keeps the conversation; a second press leaves it
+                    request.abort();
+                    self.loading = false;
+                    self.ai_status = "⏹ Stopped".to_string();
+                    return self.deny_tool_calls();
+                }
+                if self.detail.is_some() {
+                    self.detail = None;
+                } else if let Some((results, selected)) =
+                    self.action_menu.take().filter(|_| self.mode == UIMode::Results)
+                {
+                    self.results = results;
+                    self.selected_index = selected;
+                } else if self.history_search {
+                    self.history_search = false;
+                    self.results.clear();
                     self.mode = UIMode::Search;
-                    self.ai_response.clear();
-                } else {
+                } else if self.browsing.is_some() {
                     self.prompt.clear();
-                    self.results.clear();
+                    self.show_dashboard();
+                } else if self.mode == UIMode::Chat {
                     self.mode = UIMode::Search;
+                    self.ai_response.clear();
+                    self.failure = None;
+                    self.editing_last = false;
+                } else if !self.prompt.is_empty()
+                    || (!self.results.is_empty() && !self.is_dashboard())
+                    || self.regex.is_some()
+                {
+                    self.prompt.clear();
+                    self.pending.clear();
+                    self.suggestions.clear();
+                    self.empty = None;
+                    self.regex = None;
+                    self.show_dashboard();
+                } else {
+                    return self.hide_window();
                 }
                 Task::none()
             }
-            
-            Message::SearchComplete(results) => {
-                self.results = results;
-                self.selected_index = 0;
-                self.mode = UIMode::Results;
-                self.loading = false;
+
+            Message::Retry => {
+                let Some(prompt) = self.failure.take().and_then(|failure| failure.retry) else {
+                    return Task::none();
+                };
+                self.prompt = prompt;
+                self.update(Message::PromptSubmit)
+            }
+
+            Message::RegexTextEdited(action) => {
+                if let Some((_, content)) = &mut self.regex {
+                    content.perform(action);
+                }
+                Task::none()
+            }
+
+            Message::PadEdited(action) => {
+                if let Some(pad) = &mut self.pad {
+                    pad.perform(action);
+                }
+                Task::none()
+            }
+
+            Message::WindowClosed(id) => {
+                if self.pad.as_ref().is_some_and(|pad| pad.window() == id) {
+                    scratchpad::closed(id);
+                    self.pad = None;
+                    return Task::none();
+                }
+                // The launcher itself: the daemon goes with it, as it always has
+                tracing::info!("Launcher window closed");
+                crate::crash::mark_clean_shutdown();
+                std::process::exit(0);
+            }
+
+            Message::FirstFrame => {
+                self.interactive = true;
+                if crate::startup::interactive() {
+                    self.services.start_background();
+                }
+                Task::none()
+            }
+            
+            Message::AskAiInstead => {
+                self.intent = None;
+                // Nor does a live result row run
+                self.unpicked = true;
+                self.update(Message::PromptSubmit)
+            }
+
+            Message::LiveSearch(keystroke) => {
+                if keystroke != self.keystrokes {
+                    return Task::none();
+                }
+                let query = self.prompt.clone();
+                self.live_search(&query)
+            }
+
+            Message::SearchComplete { generation, category, results } => {
+                if generation != self.search_generation || !self.pending.contains(&category) {
+                    return Task::none();
+                }
+                self.pending.retain(|c| *c != category);
+                self.results.retain(|r| r.category != category);
+                self.results.extend(results);
+                if self.pending.is_empty() && self.results.is_empty() {
+                    // A plain prompt that matched nothing is for AI, which needs no hint
+                    self.empty = self.prompt.starts_with('/').then(|| no_matches(category, &self.match_query));
+                    self.mode = UIMode::Search;
+                }
                 Task::none()
             }
             
@@ -314,55 +1497,94 @@ impl Ruty {
             }
             
             Message::AIResponseWithTools { response, tools } => {
-                self.ai_response = response;
                 self.tools_used = tools.clone();
-                
-                // Format tools used for status
-                if !tools.is_empty() {
-                    let tool_icons = tools.iter().map(|t| {
-                        match t.as_str() {
-                            "search_memory" | "query_supermemory" => "🔍 Searched memory",
-                            "add_memory" => "💾 Saved to memory",
-                            "open_url" | "open_browser" => "🌐 Opened browser",
-                            "run_shell" | "run_command" => "⚙️ Ran command",
-                            "get_system_info" => "💻 Got system info",
-                            _ => "🔧 Used tool",
-                        }
-                    }).collect::<Vec<_>>().join(", ");
-                    self.ai_status = tool_icons;
-                } else {
-                    self.ai_status.clear();
+                self.ai_status.clear();
+                if let Some(index) = self.conversation.last_prompt() {
+                    let prompt = self.conversation.turns()[index].text.clone();
+                    self.record_usage(ResultCategory::History, &prompt, &prompt);
                 }
-                Task::none()
+                self.conversation.push_assistant(response, tools);
+                self.follow_conversation()
             }
             
             Message::AIResponseComplete => {
                 self.loading = false;
+                self.chat_request = None;
+                self.tool_calls.clear();
                 Task::none()
             }
             
             Message::AIError(err) => {
-                self.ai_response = format!("Error: {}", err);
-                self.loading = false;
+                self.chat_request = None;
+                self.tool_calls.clear();
+                self.fail("AI request failed", err, Some(self.submitted.clone()));
+                Task::none()
+            }
+
+            Message::ToolCallProposed(call) => {
+                self.tool_calls.push(call);
+                if self.tool_calls.len() == 1 {
+                    self.show_tool_call();
+                }
+                Task::none()
+            }
+
+            Message::ToolCallDecided(result) => {
+                if let Err(e) = result {
+                    self.toasts.push(format!("The AI didn't get your answer: {}", e), Instant::now());
+                }
                 Task::none()
             }
             
             Message::IcedEvent(event) => {
                 match event {
                     Event::Keyboard(keyboard::Event::KeyPressed { key, .. }) => {
+                        // Up/Down recall history only while nothing is listed
+                        let recall = self.results.is_empty()
+                            && (self.prompt.is_empty() || self.history_cursor.is_some());
                         match key {
+                            // Up from the top of the dashboard starts recalling too
+                            Key::Named(keyboard::key::Named::ArrowUp)
+                                if self.is_dashboard() && self.selected_index == 0 =>
+                            {
+                                return self.update(Message::HistoryPrevious);
+                            }
+                            Key::Named(keyboard::key::Named::ArrowDown) if recall => {
+                                return self.update(Message::HistoryNext);
+                            }
+                            Key::Named(keyboard::key::Named::ArrowUp) if recall => {
+                                return self.update(Message::HistoryPrevious);
+                            }
                             Key::Named(keyboard::key::Named::ArrowDown) => {
                                 return self.update(Message::SelectNext);
                             }
                             Key::Named(keyboard::key::Named::ArrowUp) => {
                                 return self.update(Message::SelectPrevious);
                             }
+                            Key::Named(keyboard::key::Named::Tab) if self.intent.is_some() => {
+                                return self.update(Message::AskAiInstead);
+                            }
+                            Key::Named(keyboard::key::Named::Tab) if self.selected_folder().is_some() => {
+                                return self.update(Message::BrowseInto);
+                            }
+                            // Tab completes while typing, otherwise shows details
+                            Key::Named(keyboard::key::Named::Tab)
+                                if self.suggestions.is_empty() && self.mode == UIMode::Results =>
+                            {
+                                return self.update(Message::ToggleDetail);
+                            }
+                            Key::Named(keyboard::key::Named::Tab) => {
+                                return self.update(Message::AcceptSuggestion);
+                            }
                             Key::Named(keyboard::key::Named::Escape) => {
                                 return self.update(Message::Escape);
                             }
                             _ => {}
                         }
                     }
+                    Event::Window(window::Event::FileDropped(path)) => {
+                        return self.update(Message::FileDropped(path));
+                    }
                     Event::Window(window::Event::Focused) => {
                         self.focused = true;
                     }
@@ -375,390 +1597,4205 @@ impl Ruty {
                 Task::none()
             }
             Message::Tick => {
-                // Check RPC WindowController for toggle requests
-                if let Some(controller) = crate::get_window_controller() {
-                    use std::sync::atomic::Ordering;
-                    
-                    // Check for quit
-                    if controller.quit_requested.swap(false, Ordering::SeqCst) {
-                        tracing::info!("Quit requested via RPC");
-                        std::process::exit(0);
+                if let Some((label, progress)) = &self.job {
+                    self.ai_status = job_status(label, progress);
+                }
+                if let Some(recording) = &self.voice {
+                    self.voice_levels = recording.levels(WAVEFORM_BARS);
+                }
+                self.toasts.expire(Instant::now());
+                self.undo.expire(Instant::now());
+                self.check_launches();
+                self.check_paste_queue();
+                self.check_timers();
+                let mut stop_voice = false;
+                let mut window = Task::none();
+                // Check if a hotkey binding fired (X11, portal or SIGUSR1)
+                match hotkey::poll() {
+                    Some(hotkey::Action::Toggle) => {
+                        tracing::info!("Hotkey detected - smart toggling window");
+                        // Hide a focused window; show a hidden or unfocused one
+                        window = self.set_visible(!self.focused);
                     }
-                    
-                    // Check for visibility toggle
-                    if controller.toggle_requested.swap(false, Ordering::SeqCst) {
-                        let visible = controller.visible.load(Ordering::SeqCst);
-                        tracing::info!("Window visibility change via RPC: {}", visible);
-                        
-                        // Toggle window visibility using resize (Wayland doesn't support move_to)
-                        return if visible {
-                            // Show: resize to full size and try to bring to front
-                            // Show: resize to full size and try to bring to front
-                            window::get_oldest().and_then(|id| {
-                                Task::batch([
-                                    // Reset level to force WM to re-evaluate
-                                    window::change_level(id, window::Level::Normal), 
-                                    window::resize(id, iced::Size::new(700.0, 400.0)),
-                                    window::gain_focus(id),
-                                    window::request_user_attention(id, Some(window::UserAttention::Critical)),
-                                    // Set AlwaysOnTop LAST (and after a level reset) to be aggressive
-                                    window::change_level(id, window::Level::AlwaysOnTop),
-                                ])
-                            })
-                        } else {
-                            // Hide: shrink to minimal size and set normal level
-                            window::get_oldest().and_then(|id| {
-                                Task::batch([
-                                    window::resize(id, iced::Size::new(1.0, 1.0)),
-                                    window::change_level(id, window::Level::Normal),
-                                ])
-                            })
-                        };
+                    Some(hotkey::Action::Clipboard) => {
+                        tracing::info!("Clipboard hotkey detected - showing history");
+                        window = self.set_visible(true);
+                        self.prompt.clear();
+                        self.show_clipboard();
+                    }
+                    // Pressed again (portal shortcuts report no release): stop
+                    Some(hotkey::Action::Voice) if self.voice.is_some() => stop_voice = true,
+                    Some(hotkey::Action::Voice) => {
+                        tracing::info!("Voice hotkey detected - recording");
+                        window = self.set_visible(true);
+                        self.start_voice();
                     }
+                    None => {}
                 }
-                
-                // Check if hotkey was pressed (X11 or SIGUSR1)
-                // Check if hotkey was pressed (X11 or SIGUSR1)
-                if hotkey::check_hotkey_pressed() {
-                    tracing::info!("Hotkey detected - smart toggling window");
-                    if let Some(controller) = crate::get_window_controller() {
-                        use std::sync::atomic::Ordering;
-                        
-                        // Smart Toggle Logic:
-                        // If window is FOCUSED, then Hide.
-                        // If window is HIDDEN or NOT FOCUSED, then Show.
-                        let should_show = !self.focused;
-                        
-                        controller.visible.store(should_show, Ordering::SeqCst);
-                        
-                        // We set toggle_requested to true to trigger the actual window update in the block above
-                        // But wait, the block above (lines 383+) runs on toggle_requested.
-                        // We need to ensure it runs with the NEW visibility state.
-                        // Since we just set 'visible', we can set toggle_requested=true and it will be picked up
-                        // in the NEXT tick (or we can handle it now if we refactor).
-                        // For simplicity, we'll let the next tick handle it, BUT we need to ensure the logic matches.
-                        
-                        controller.toggle_requested.store(true, Ordering::SeqCst);
+                if hotkey::voice_released() && self.voice.is_some() {
+                    stop_voice = true;
+                }
+                let voice = if stop_voice { self.stop_voice() } else { Task::none() };
+                Task::batch([voice, window])
+            }
+
+            Message::Window(command) => {
+                if command == WindowCommand::Quit {
+                    tracing::info!("Quit requested via RPC");
+                    crate::crash::mark_clean_shutdown();
+                    std::process::exit(0);
+                }
+                let visible = crate::get_window_controller().is_some_and(|controller| controller.is_visible());
+                match command {
+                    WindowCommand::Place if visible => self.place_window(),
+                    WindowCommand::Place => Task::none(),
+                    _ => self.set_visible(command.visible_after(visible)),
+                }
+            }
+
+            Message::WindowPlaced(placement) => {
+                let size = iced::Size::new(placement.geometry.width as f32, placement.geometry.height as f32);
+                window::get_oldest().and_then(move |id| {
+                    let moved = match placement.position {
+                        Some((x, y)) => window::move_to(id, iced::Point::new(x as f32, y as f32)),
+                        None => Task::none(),
+                    };
+                    Task::batch([window::resize(id, size), moved])
+                })
+            }
+
+            Message::HistoryPrevious => {
+                let offset = self.history_cursor.map_or(0, |c| c + 1);
+                self.recall_history(offset)
+            }
+
+            Message::HistoryNext => match self.history_cursor {
+                Some(0) => {
+                    self.history_cursor = None;
+                    self.prompt.clear();
+                    self.show_dashboard();
+                    Task::none()
+                }
+                Some(c) => self.recall_history(c - 1),
+                None => Task::none(),
+            },
+
+            Message::AcceptSuggestion => match self.suggestions.first() {
+                Some(suggestion) => {
+                    let completion = suggestion.completion.clone();
+                    Task::batch([
+                        self.update(Message::PromptChanged(completion)),
+                        text_input::move_cursor_to_end(prompt_input_id()),
+                    ])
+                }
+                None => Task::none(),
+            },
+
+            Message::CommandPalette => {
+                self.history_search = false;
+                self.suggestions.clear();
+                self.prompt = String::from(">");
+                self.show_actions("");
+                Task::batch([
+                    text_input::focus(prompt_input_id()),
+                    text_input::move_cursor_to_end(prompt_input_id()),
+                ])
+            }
+
+            Message::FileActions => {
+                let Some(result) = self.results.get(self.selected_index) else {
+                    return Task::none();
+                };
+                if result.category == ResultCategory::App {
+                    let app_row = |action: &str, title: &str, subtitle: String| SearchResult {
+                        id: format!("{}\t{}", action, result.id),
+                        title: title.to_string(),
+                        subtitle,
+                        icon: None,
+                        category: ResultCategory::Action,
+                    };
+                    // Already open: switching to it is what Enter does, so it comes first
+                    let mut rows = match self.services.open_window(&result.id) {
+                        Some(window) => vec![
+                            app_row("focus-window", "Focus existing window", window.title),
+                            app_row("launch-new", "Launch new instance", result.title.clone()),
+                        ],
+                        None => Vec::new(),
+                    };
+                    let installs = self.services.app_installs(&result.id);
+                    if installs.len() > 1 {
+                        rows.extend(installs);
+                    }
+                    rows.push(SearchResult {
+                        id: format!("launch-options\t{}", result.id),
+                        title: "Edit launch options".to_string(),
+                        subtitle: "Extra args, env vars, working dir or X11/Wayland, in config.toml".to_string(),
+                        icon: None,
+                        category: ResultCategory::Action,
+                    });
+                    self.open_menu(rows);
+                    return Task::none();
+                }
+                if result.category == ResultCategory::Contact && !result.id.contains('\t') {
+                    let rows = self.services.contact_actions(&result.id);
+                    if !rows.is_empty() {
+                        self.match_query.clear();
+                        self.open_menu(rows);
+                    }
+                    return Task::none();
+                }
+                if result.category == ResultCategory::Event {
+                    let rows = self.event_actions(&result.id.clone());
+                    if !rows.is_empty() {
+                        self.open_menu(rows);
+                    }
+                    return Task::none();
+                }
+                if result.category == ResultCategory::Clipboard {
+                    let rows = self.clipboard_actions(&result.id.clone());
+                    if !rows.is_empty() {
+                        self.open_menu(rows);
+                    }
+                    return Task::none();
+                }
+                // Folders open in a terminal or the editor
+                if let Some(folder) = self.selected_folder() {
+                    let folder = folder.display().to_string();
+                    let mut rows = services::folder_actions(&folder);
+                    if result.category == ResultCategory::File {
+                        match self.services.open_with(&folder) {
+                            Ok(open_with) => rows.extend(open_with),
+                            Err(e) => tracing::debug!("No Open With rows: {}", e),
+                        }
+                    }
+                    self.open_menu(rows);
+                    return Task::none();
+                }
+                if result.category != ResultCategory::File {
+                    return Task::none();
+                }
+                let path = result.id.clone();
+                let mut rows = services::archive_actions(&path);
+                match self.services.open_with(&path) {
+                    Ok(open_with) => rows.extend(open_with),
+                    Err(e) if !rows.is_empty() => tracing::debug!("No Open With rows: {}", e),
+                    Err(e) => {
+                        self.fail("No file actions", e, None);
+                        return Task::none();
                     }
                 }
+                self.open_menu(rows);
                 Task::none()
             }
-            
-            Message::HotkeyPressed => {
-                tracing::info!("Global hotkey pressed: Super+Space");
+
+            Message::HashComputed { algorithm, source, result } => {
+                self.job = None;
+                match result {
+                    Ok(digest) => self.show_hash(algorithm, &source, digest),
+                    Err(e) => self.fail("Checksum failed", e, Some(self.submitted.clone())),
+                }
                 Task::none()
             }
-            
-            Message::WindowFocusLost => {
-                if let Some(controller) = crate::get_window_controller() {
-                    use std::sync::atomic::Ordering;
-                    // Update controller state if currently visible
-                    if controller.visible.load(Ordering::SeqCst) {
-                        tracing::info!("Focus lost - auto-hiding window");
-                        controller.visible.store(false, Ordering::SeqCst);
-                        
-                        return window::get_oldest().and_then(|id| {
-                            Task::batch([
-                                window::resize(id, iced::Size::new(1.0, 1.0)),
-                                window::change_level(id, window::Level::Normal),
-                            ])
-                        });
+
+            Message::ArchiveExtracted(result) => {
+                self.job = None;
+                self.ai_status.clear();
+                match result {
+                    Ok(message) => self.ai_response = message,
+                    Err(e) => self.fail("Extraction failed", e, None),
+                }
+                Task::none()
+            }
+
+            Message::ToggleDetail => {
+                self.detail = match (&self.detail, self.results.get(self.selected_index)) {
+                    (None, Some(result)) => Some(details::load(&self.services, result)),
+                    _ => None,
+                };
+                Task::none()
+            }
+
+            Message::BrowseInto => match self.selected_folder() {
+                Some(folder) => self.browse_folder(folder),
+                None => Task::none(),
+            },
+
+            // Only with an empty filter, so Left still moves the cursor in it
+            Message::BrowseUp => {
+                let Some(folder) = self.browsing.take().filter(|_| self.prompt.is_empty()) else {
+                    return Task::none();
+                };
+                let Some(parent) = folder.parent() else {
+                    self.browsing = Some(folder);
+                    return Task::none();
+                };
+                self.browsing = Some(parent.to_path_buf());
+                self.show_browse(Some(&folder));
+                Task::none()
+            }
+
+            Message::FileDropped(path) => {
+                let path = path.to_string_lossy().to_string();
+                tracing::info!("Loading dropped path as context: {}", path);
+                self.manage_context(ContextCommand::Load { path })
+            }
+
+            Message::RemoveContext(index) => {
+                self.manage_context(ContextCommand::Remove { index: index + 1 })
+            }
+
+            Message::ContextUpdated(result) => {
+                self.loading = false;
+                self.ai_status.clear();
+                match result {
+                    Ok(resp) => {
+                        self.context_paths = resp.contexts;
+                        self.ai_response = resp.message;
                     }
+                    Err(e) => self.fail("Context update failed", e, Some(self.submitted.clone())),
                 }
                 Task::none()
             }
-        }
-    }
 
-    pub fn view(&self) -> Element<'_, Message> {
-        // Search bar with styling
-        let search_bar = container(
-            text_input("Ask Ruty anything...", &self.prompt)
-                .on_input(Message::PromptChanged)
-                .on_submit(Message::PromptSubmit)
-                .padding(Padding::new(16.0))
-                .size(20)
-                .style(|_theme, status| {
-                    text_input::Style {
-                        background: Background::Color(Color::TRANSPARENT),
-                        border: Border::default(),
-                        icon: colors::TEXT_MUTED,
-                        placeholder: colors::TEXT_PLACEHOLDER,
-                        value: colors::TEXT,
-                        selection: colors::PRIMARY,
+            Message::ContextListed(result) => {
+                self.loading = false;
+                self.ai_status.clear();
+                match result {
+                    Ok(contexts) => {
+                        self.context_paths = contexts;
+                        self.ai_response = format_context_list(&self.context_paths);
                     }
-                })
-        )
-        .padding(Padding::from([8.0, 16.0]))
-        .width(Length::Fill)
-        .style(|_theme| container::Style {
-            background: Some(Background::Color(colors::SURFACE)),
-            border: Border {
-                color: colors::BORDER,
-                width: 1.0,
-                radius: 12.0.into(),
-            },
-            ..Default::default()
-        });
+                    Err(e) => self.fail("Couldn't list context", e, Some(self.submitted.clone())),
+                }
+                Task::none()
+            }
 
-        // Build content based on mode
-        let content: Element<'_, Message> = match self.mode {
-            UIMode::Search => {
-                // Just the search bar with hint text below
-                column![
-                    search_bar,
-                    Space::with_height(16),
-                    container(
-                        text("Type to search apps, files, or ask AI...")
-                            .size(14)
-                            .color(colors::TEXT_MUTED)
-                    )
-                    .width(Length::Fill)
-                    .center_x(Length::Fill)
-                ]
-                .spacing(0)
-                .into()
+            Message::DoctorFinished(checks) => {
+                self.loading = false;
+                self.ai_status.clear();
+                self.ai_response = doctor::report(&checks);
+                Task::none()
             }
-            UIMode::Results => {
-                let results_list = self.view_results();
-                column![
-                    search_bar,
-                    Space::with_height(12),
-                    results_list
-                ]
-                .spacing(0)
-                .into()
+
+            Message::MemoriesListed(result) => {
+                self.loading = false;
+                self.ai_status.clear();
+                match result {
+                    Ok(memories) => {
+                        self.memories = memories;
+                        self.show_memories();
+                    }
+                    Err(e) => self.fail("Couldn't load memories", e, Some(self.submitted.clone())),
+                }
+                Task::none()
             }
-            UIMode::Chat => {
-                // Status line (thinking, tools used)
-                let status_text = if self.loading {
-                    text(&self.ai_status).size(13).color(colors::TEXT_MUTED)
-                } else if !self.ai_status.is_empty() {
-                    text(&self.ai_status).size(13).color(colors::PRIMARY)
-                } else {
-                    text("").size(13)
-                };
-                
-                let response_view = container(
-                    scrollable(
-                        container(
-                            text(&self.ai_response)
-                                .size(15)
-                                .color(colors::TEXT)
-                        )
-                        .padding(16)
-                    )
-                    .height(Length::Fill)
-                )
-                .width(Length::Fill)
-                .height(Length::Fill)
-                .style(|_theme| container::Style {
-                    background: Some(Background::Color(colors::SURFACE)),
-                    border: Border::default().rounded(8),
-                    ..Default::default()
-                });
-                
-                column![
-                    search_bar,
-                    Space::with_height(8),
-                    status_text,
-                    Space::with_height(4),
-                    response_view
-                ]
-                .spacing(0)
-                .into()
+
+            Message::MemoryDeleted(id, result) => {
+                self.loading = false;
+                self.ai_status.clear();
+                match result {
+                    Ok(()) => {
+                        self.memories.retain(|memory| memory.id != id);
+                        self.show_memories();
+                    }
+                    Err(e) => self.fail("Couldn't delete memory", e, None),
+                }
+                Task::none()
             }
-            UIMode::Settings => {
-                column![
-                    search_bar,
-                    text("Settings - Coming Soon").color(colors::TEXT_MUTED)
-                ]
-                .into()
+
+            Message::VoiceTranscribed(result) => {
+                self.transcribing = false;
+                match result {
+                    Ok(transcript) if transcript.is_empty() => {
+                        self.fail("Didn't catch that", "No speech was recognized".to_string(), None);
+                        Task::none()
+                    }
+                    Ok(transcript) => {
+                        let prompt = match self.prompt.trim_end() {
+                            "" => transcript,
+                            typed => format!("{} {}", typed, transcript),
+                        };
+                        Task::batch([
+                            self.update(Message::PromptChanged(prompt)),
+                            text_input::focus(prompt_input_id()),
+                            text_input::move_cursor_to_end(prompt_input_id()),
+                        ])
+                    }
+                    Err(e) => {
+                        self.fail("Voice input failed", e, None);
+                        Task::none()
+                    }
+                }
             }
-        };
 
-        // Main container with rounded corners and proper background
-        container(
-            container(content)
-                .padding(16)
-                .width(Length::Fill)
-                .height(Length::Fill)
-        )
-        .width(Length::Fill)
-        .height(Length::Fill)
-        .style(|_theme| container::Style {
-            background: Some(Background::Color(colors::BACKGROUND)),
-            border: Border {
-                color: colors::BORDER,
-                width: 1.0,
-                radius: 16.0.into(),
-            },
-            ..Default::default()
-        })
-        .into()
-    }
+            Message::DownloadVoiceModel => {
+                self.toasts.dismiss(|message| matches!(message, Message::DownloadVoiceModel));
+                if self.job.is_some() {
+                    return Task::none();
+                }
+                let progress = Arc::new(Progress::new(voice::MODEL_MB * MB as u64));
+                let label = String::from("🎙 Downloading the speech model");
+                self.ai_status = job_status(&label, &progress);
+                self.job = Some((label, progress.clone()));
+                self.results.clear();
+                self.ai_response.clear();
+                self.mode = UIMode::Chat;
+                Task::perform(voice::download_model(progress), Message::VoiceModelDownloaded)
+            }
 
-    fn view_results(&self) -> Element<'_, Message> {
-        let items: Vec<Element<'_, Message>> = self
-            .results
-            .iter()
-            .enumerate()
-            .map(|(i, result)| {
-                let is_selected = i == self.selected_index;
-                
-                // Render icon: use actual image if available, fallback to text symbol
-                let icon_element: Element<'_, Message> = if let Some(ref icon_path) = result.icon {
-                    container(
-                        image(icon_path.as_str())
-                            .width(24)
-                            .height(24)
-                    )
-                    .width(36)
-                    .center_x(36)
+            Message::VoiceModelDownloaded(result) => {
+                self.job = None;
+                self.ai_status.clear();
+                let outcome = result.map(|()| String::from("Speech model downloaded: hold the voice hotkey and speak"));
+                self.show_outcome("Speech model download failed", outcome);
+                Task::none()
+            }
+
+            Message::DownloadMemoryModel => {
+                self.toasts.dismiss(|message| matches!(message, Message::DownloadMemoryModel));
+                if self.job.is_some() {
+                    return Task::none();
+                }
+                let progress = Arc::new(Progress::new(embedding::MODEL_MB * MB as u64));
+                let label = String::from("🧠 Downloading the embedding model");
+                self.ai_status = job_status(&label, &progress);
+                self.job = Some((label, progress.clone()));
+                self.results.clear();
+                self.ai_response.clear();
+                self.mode = UIMode::Chat;
+                Task::perform(embedding::download_model(progress), Message::MemoryModelDownloaded)
+            }
+
+            Message::MemoryModelDownloaded(result) => {
+                self.job = None;
+                self.ai_status.clear();
+                if result.is_ok() {
+                    let services = self.services.clone();
+                    std::thread::spawn(move || services.index_memory());
+                }
+                let outcome = result.map(|()| String::from("Embedding model downloaded: indexing your notes"));
+                self.show_outcome("Embedding model download failed", outcome);
+                Task::none()
+            }
+
+            Message::PublicIpFetched(result) => {
+                let placeholder = self.results.iter_mut().find(|r| {
+                    r.category == ResultCategory::Network && r.subtitle.starts_with(PUBLIC_IP_LABEL)
+                });
+                if let Some(row) = placeholder {
+                    match result {
+                        Ok(ip) => {
+                            row.title = ip.clone();
+                            row.id = ip;
+                            row.subtitle = String::from(PUBLIC_IP_LABEL);
+                        }
+                        Err(e) => {
+                            row.title = String::from("Public IP unavailable");
+                            row.subtitle = format!("{} · {}", PUBLIC_IP_LABEL, e);
+                        }
+                    }
+                }
+                Task::none()
+            }
+
+            Message::ControlsListed(listed) => {
+                // Typed on past the query while the controls were read
+                if ControlQuery::parse(&self.prompt).is_none() {
+                    return Task::none();
+                }
+                self.controls = listed
+                    .iter()
+                    .map(|c| (c.target.encode(), c.kind))
+                    .collect();
+                self.results = listed
+                    .into_iter()
+                    .map(|c| SearchResult {
+                        id: c.target.encode(),
+                        subtitle: control_hint(c.kind),
+                        title: c.label,
+                        icon: None,
+                        category: ResultCategory::Control,
+                    })
+                    .collect();
+                if self.results.is_empty() {
+                    self.results.push(SearchResult {
+                        id: String::new(),
+                        title: String::from("No controls available"),
+                        subtitle: String::from("Needs wpctl, a backlight, ddcutil or GNOME night light"),
+                        icon: None,
+                        category: ResultCategory::Control,
+                    });
+                }
+                self.selected_index = 0;
+                self.mode = UIMode::Results;
+                Task::none()
+            }
+
+            Message::ControlChanged(id, percent) => {
+                if let Some(index) = self.results.iter().position(|r| r.id == id) {
+                    self.selected_index = index;
+                }
+                self.controls.insert(id, ControlKind::Level(percent));
+                Task::none()
+            }
+
+            Message::ControlReleased(id) => self.apply_control(&id),
+
+            Message::AdjustControl(delta) => {
+                let Some(result) = self.results.get(self.selected_index) else {
+                    return Task::none();
+                };
+                let id = result.id.clone();
+                let Some(ControlKind::Level(percent)) = self.controls.get(&id).copied() else {
+                    return self.update(if delta > 0 { Message::BrowseInto } else { Message::BrowseUp });
+                };
+                self.controls.insert(id.clone(), ControlKind::Level(controls::step(percent, delta)));
+                self.apply_control(&id)
+            }
+
+            Message::ControlApplied(result) => {
+                if let Err(e) = result {
+                    tracing::warn!("Control change failed: {}", e);
+                }
+                Task::none()
+            }
+
+            Message::ResultsScrolled { offset, height } => {
+                self.results_viewport = (offset, height);
+                Task::none()
+            }
+
+            Message::ThumbnailLoaded(path, result) => {
+                let (thumbnail, size) = match result {
+                    Ok(handle) => {
+                        let bytes = match &handle {
+                            image::Handle::Bytes(_, bytes) => bytes.len(),
+                            _ => 0,
+                        };
+                        (Thumbnail::Ready(handle), thumbnails::memory_size(bytes))
+                    }
+                    Err(e) => {
+                        tracing::debug!("No thumbnail for {}: {}", path, e);
+                        (Thumbnail::Failed, path.len())
+                    }
+                };
+                self.thumbnails.insert(path.clone(), thumbnail, size);
+                Task::none()
+            }
+
+            Message::HistorySearch => {
+                self.history_search = true;
+                self.show_history_matches();
+                Task::none()
+            }
+
+            Message::HotkeyPressed => {
+                tracing::info!("Global hotkey pressed: Super+Space");
+                Task::none()
+            }
+            
+            Message::CopyTurn(index) => {
+                if let Some(turn) = self.conversation.turns().get(index) {
+                    let content = turn.text.clone();
+                    self.copy_to_clipboard(&content, "Copied reply".to_string());
+                }
+                Task::none()
+            }
+
+            Message::TogglePin => {
+                self.pinned = !self.pinned;
+                Task::none()
+            }
+
+            Message::DeleteSelected => {
+                if self.mode != UIMode::Results {
+                    return Task::none();
+                }
+                let Some(result) = self.results.get(self.selected_index).cloned() else {
+                    return Task::none();
+                };
+                let deleted = match result.category {
+                    ResultCategory::Clipboard => result
+                        .id
+                        .parse::<u64>()
+                        .ok()
+                        .and_then(|id| self.services.clipboard().remove(id))
+                        .map(|item| Ok(("Deleted clipboard entry".to_string(), Inverse::Clipboard(vec![item])))),
+                    ResultCategory::File => Some(trash::trash(Path::new(&result.id)).map(|trashed| {
+                        (format!("Moved {} to trash", result.title), Inverse::File(trashed))
+                    })),
+                    _ => None,
+                };
+                match deleted {
+                    Some(Ok((toast, inverse))) => {
+                        self.results.remove(self.selected_index);
+                        self.selected_index = self.selected_index.min(self.results.len().saturating_sub(1));
+                        if self.results.is_empty() {
+                            self.mode = UIMode::Search;
+                        }
+                        self.offer_undo(toast, inverse);
+                    }
+                    Some(Err(e)) => self.fail("Couldn't move to trash", e, None),
+                    None => {}
+                }
+                Task::none()
+            }
+
+            Message::MarkClip => {
+                let Some(result) = self.results.get(self.selected_index).filter(|_| self.mode == UIMode::Results) else {
+                    return Task::none();
+                };
+                let Some(id) = result.id.parse::<u64>().ok().filter(|_| result.category == ResultCategory::Clipboard) else {
+                    return Task::none();
+                };
+                match self.marked_clips.iter().position(|marked| *marked == id) {
+                    Some(index) => {
+                        self.marked_clips.remove(index);
+                    }
+                    None => self.marked_clips.push(id),
+                }
+                // On to the next entry, to mark several in a row
+                if self.selected_index + 1 < self.results.len() {
+                    self.selected_index += 1;
+                }
+                Task::none()
+            }
+
+            Message::StopPasting => {
+                if self.paste_queue.take().is_some() {
+                    self.services.clipboard().set_paused(false);
+                    self.toasts.push("Stopped pasting", Instant::now());
+                }
+                Task::none()
+            }
+
+            Message::TextExtracted(result) => {
+                self.loading = false;
+                self.ai_status.clear();
+                match result {
+                    Ok(text) => {
+                        let chars = text.chars().count();
+                        self.services.clipboard().add(text.clone());
+                        self.copy_to_clipboard(&text, format!("Extracted {} chars of text", chars));
+                        self.show_clipboard();
+                    }
+                    Err(e) => self.fail("Couldn't read the image", e, None),
+                }
+                Task::none()
+            }
+
+            Message::Bus(event) => {
+                match event {
+                    // Rerun the query typed while the index was being built
+                    events::Event::IndexRefreshed => {
+                        if let Some(query) = self.awaiting_index.take() {
+                            // A live search is of the prompt itself, and reruns whole
+                            if query == self.prompt {
+                                return self.live_search(&query);
+                            }
+                            self.search(&query);
+                        }
+                    }
+                    events::Event::AppsChanged(changes) => self.apps_changed(&changes),
+                    events::Event::ConfigReloaded(restart) => self.config_reloaded(&restart),
+                    events::Event::ConfigInvalid(error) => {
+                        self.toasts.push(format!("Config not reloaded: {}", error), Instant::now());
+                    }
+                    events::Event::PermissionNeeded(provider) => {
+                        self.permission_needed = Some(provider);
+                        if self.visible {
+                            self.remind_permission();
+                        }
+                    }
+                    _ => {}
+                }
+                Task::none()
+            }
+
+            Message::ReviewPermission(provider) => {
+                self.toasts.dismiss(|message| matches!(message, Message::ReviewPermission(_)));
+                let missing = permissions::missing(&self.services.config(), &provider);
+                if !missing.is_empty() {
+                    self.ask_permission(&provider, &missing);
+                }
+                Task::none()
+            }
+
+            Message::FlowFinished(name, result) => {
+                self.loading = false;
+                self.ai_status.clear();
+                match result {
+                    Ok(Some(output)) => {
+                        self.ai_response = output;
+                        self.mode = UIMode::Chat;
+                    }
+                    Ok(None) => {
+                        self.mode = UIMode::Search;
+                        self.toasts.push(format!("Ran {} ✓", name), Instant::now());
+                    }
+                    Err(e) => self.fail("Flow failed", format!("{}: {}", name, e), None),
+                }
+                Task::none()
+            }
+
+            Message::QrScanned(result) => {
+                match result {
+                    Ok(None) => return Task::none(),
+                    Ok(Some(codes)) => {
+                        self.results = codes
+                            .into_iter()
+                            .map(|code| SearchResult {
+                                title: code.lines().next().unwrap_or_default().chars().take(80).collect(),
+                                subtitle: format!("QR code · {} chars · ↵ to copy", code.chars().count()),
+                                id: code,
+                                icon: None,
+                                category: ResultCategory::Transform,
+                            })
+                            .collect();
+                        self.prompt.clear();
+                        self.dashboard = false;
+                        self.selected_index = 0;
+                        self.mode = UIMode::Results;
+                    }
+                    Err(e) => self.fail("Couldn't scan a QR code", e, None),
+                }
+                self.set_visible(true)
+            }
+
+            Message::AgendaListed(prompt, events) => {
+                // Typed on while the calendars were read
+                if prompt != self.prompt {
+                    return Task::none();
+                }
+                self.results = events
+                    .iter()
+                    .enumerate()
+                    .map(|(index, event)| {
+                        let mut subtitle = event.when();
+                        if let Some(location) = &event.location {
+                            subtitle.push_str(&format!(" · {}", location.lines().next().unwrap_or_default()));
+                        }
+                        if event.meeting_link.is_some() {
+                            subtitle.push_str(" · Ctrl+O for the meeting link");
+                        }
+                        SearchResult {
+                            id: index.to_string(),
+                            title: event.summary.clone(),
+                            subtitle,
+                            icon: None,
+                            category: ResultCategory::Event,
+                        }
+                    })
+                    .collect();
+                self.agenda = events;
+                if self.results.is_empty() {
+                    self.empty = Some(EmptyState {
+                        message: tr("empty-agenda"),
+                        hint: tr("empty-agenda-hint"),
+                    });
+                }
+                self.dashboard = false;
+                self.match_query.clear();
+                self.selected_index = 0;
+                self.mode = UIMode::Results;
+                Task::none()
+            }
+
+            Message::ToggleFavorite => {
+                let Some(result) = self.results.get(self.selected_index) else {
+                    return Task::none();
+                };
+                let favorite = match result.category {
+                    ResultCategory::App | ResultCategory::File => Favorite {
+                        kind: if result.category == ResultCategory::App { FavoriteKind::App } else { FavoriteKind::File },
+                        target: result.id.clone(),
+                        title: result.title.clone(),
+                    },
+                    ResultCategory::Favorite => match self.selected_favorite() {
+                        Some(index) => self.favorites[index].clone(),
+                        None => return Task::none(),
+                    },
+                    _ => return Task::none(),
+                };
+                let unstarring_row = result.category == ResultCategory::Favorite;
+                self.star(favorite);
+                if unstarring_row || self.is_dashboard() {
+                    let selected = self.selected_index;
+                    self.refresh_favorites();
+                    self.selected_index = selected.min(self.results.len().saturating_sub(1));
+                }
+                Task::none()
+            }
+
+            Message::MoveFavorite(by) => {
+                let Some(index) = self.selected_favorite() else {
+                    return Task::none();
+                };
+                let saved = Config::update(|config| {
+                    config.favorites = self.favorites.clone();
+                    config.move_favorite(index, by).map(|moved| (moved, config.favorites.clone()))
+                });
+                match saved {
+                    Ok(Some((moved, favorites))) => {
+                        self.favorites = favorites;
+                        self.refresh_favorites();
+                        self.selected_index = moved;
+                    }
+                    Ok(None) => {}
+                    Err(e) => self.fail("Couldn't save favorites", e, None),
+                }
+                Task::none()
+            }
+
+            Message::ConfirmAction(action) => {
+                self.toasts.dismiss(|message| matches!(message, Message::ConfirmAction(asked) if *asked == action));
+                self.unconfirmed = None;
+                self.perform_action(action);
+                Task::none()
+            }
+
+            Message::Undo(id) => {
+                self.toasts.dismiss(|message| matches!(message, Message::Undo(offered) if *offered == id));
+                let Some(inverse) = self.undo.take(id, Instant::now()) else {
+                    return Task::none();
+                };
+                let restored = match inverse {
+                    Inverse::Clipboard(items) => {
+                        self.services.clipboard().reinsert(items);
+                        if self.is_dashboard() {
+                            self.show_dashboard();
+                        } else if self.mode == UIMode::Results
+                            && self.results.iter().all(|r| r.category == ResultCategory::Clipboard)
+                        {
+                            self.show_clipboard();
+                        }
+                        Ok(())
+                    }
+                    Inverse::File(trashed) => trashed.restore(),
+                    Inverse::Alias { name, expansion } => {
+                        self.aliases.insert(name, expansion);
+                        self.save_aliases()
+                    }
+                    Inverse::SavedSearch { name, search } => {
+                        self.saved_searches.insert(name, search);
+                        let saved = self.save_saved_searches();
+                        if saved.is_ok() && self.is_dashboard() {
+                            self.show_dashboard();
+                        }
+                        saved
+                    }
+                    Inverse::Bookmark { index, bookmark } => {
+                        self.bookmarks.insert(index.min(self.bookmarks.len()), bookmark);
+                        self.save_bookmarks()
+                    }
+                    Inverse::Permission { provider, capabilities } => {
+                        let saved = self.save_permission(&provider, capabilities);
+                        if saved.is_ok() && self.results.iter().any(|r| r.id.starts_with("revoke\t")) {
+                            self.show_permissions();
+                        }
+                        saved
+                    }
+                };
+                match restored {
+                    Ok(()) => self.toasts.push("Restored ✓", Instant::now()),
+                    Err(e) => self.fail("Undo failed", e, None),
+                }
+                Task::none()
+            }
+
+            Message::WindowFocusLost => {
+                if !self.auto_hides() {
+                    return Task::none();
+                }
+                tracing::info!("Focus lost - auto-hiding window");
+                self.hide_window()
+            }
+        }
+    }
+
+    pub fn view(&self, window: window::Id) -> Element<'_, Message> {
+        match &self.pad {
+            Some(pad) if pad.window() == window => self.view_pad(pad),
+            // Nothing to draw in a 1px window (see `redraw`)
+            _ if !self.visible => Space::new(Length::Shrink, Length::Shrink).into(),
+            _ => self.view_launcher(),
+        }
+    }
+
+    /// The scratchpad window: nothing but the editor
+    fn view_pad<'a>(&'a self, pad: &'a Scratchpad) -> Element<'a, Message> {
+        let editor = text_editor(&pad.content)
+            .placeholder("Notes…")
+            .on_action(Message::PadEdited)
+            .height(Length::Fill)
+            .size(fonts::size(14))
+            .padding(12)
+            .style(|_theme, _status| text_editor::Style {
+                background: Background::Color(colors::surface()),
+                border: Border::default(),
+                icon: colors::text_muted(),
+                placeholder: colors::text_placeholder(),
+                value: colors::text(),
+                selection: colors::selection(),
+            });
+        container(editor).width(Length::Fill).height(Length::Fill).into()
+    }
+
+    fn view_launcher(&self) -> Element<'_, Message> {
+        // Search bar with styling
+        let search_bar = container(
+            row![text_input(
+                &match &self.browsing {
+                    Some(folder) => format!("Filter {}...", folder.display()),
+                    None if self.history_search => String::from("Search history..."),
+                    None => String::from("Ask Ruty anything..."),
+                },
+                &self.prompt,
+            )
+                .id(prompt_input_id())
+                .on_input(Message::PromptChanged)
+                .on_submit(Message::PromptSubmit)
+                .padding(Padding::new(16.0))
+                .size(fonts::size(20))
+                .style(|_theme, status| {
+                    text_input::Style {
+                        background: Background::Color(Color::TRANSPARENT),
+                        border: Border::default(),
+                        icon: colors::text_muted(),
+                        placeholder: colors::text_placeholder(),
+                        value: colors::text(),
+                        selection: colors::primary(),
+                    }
+                }),
+                self.view_pin(),
+            ]
+            .align_y(iced::Alignment::Center)
+        )
+        .padding(Padding::from([8.0, 16.0]))
+        .width(Length::Fill)
+        .style(|_theme| container::Style {
+            background: Some(Background::Color(colors::surface())),
+            border: Border {
+                color: colors::border(),
+                width: 1.0,
+                radius: 12.0.into(),
+            },
+            ..Default::default()
+        });
+
+        // Active context chips sit at the start of the search bar
+        let search_bar: Element<'_, Message> = if self.context_paths.is_empty() {
+            search_bar.into()
+        } else {
+            let chips = self.context_paths.iter().enumerate().map(|(i, path)| {
+                let remove = button(text("×").size(fonts::size(12)).color(colors::text_muted()))
+                    .on_press(Message::RemoveContext(i))
+                    .padding(0)
+                    .style(|_theme, _status| button::Style::default());
+                container(
+                    row![
+                        text(format!("📎 {}", chip_label(path))).size(fonts::size(12)).color(colors::text()),
+                        remove,
+                    ]
+                    .spacing(6)
+                    .align_y(iced::Alignment::Center),
+                )
+                    .padding(Padding::from([4.0, 8.0]))
+                    .style(|_theme| container::Style {
+                        background: Some(Background::Color(colors::selection())),
+                        border: Border::default().rounded(6),
+                        ..Default::default()
+                    })
                     .into()
+            });
+            column![row(chips).spacing(6), search_bar].spacing(6).into()
+        };
+
+        // Push-to-talk: the waveform while recording, then a transcribing note
+        let search_bar: Element<'_, Message> = if self.voice.is_some() {
+            let listening = row![
+                ui::waveform::waveform(&self.voice_levels, colors::primary()),
+                text("Listening… release to transcribe").size(fonts::size(12)).color(colors::text_muted()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center);
+            column![search_bar, listening].spacing(6).into()
+        } else if self.transcribing {
+            let note = text("✍ Transcribing…").size(fonts::size(12)).color(colors::text_muted());
+            column![search_bar, note].spacing(6).into()
+        } else if let Some(intent) = &self.intent {
+            let note = text(format!("⚡ {} · ↵ to run it, Tab to ask AI instead", intent.summary))
+                .size(fonts::size(12))
+                .color(colors::primary());
+            column![search_bar, note].spacing(6).into()
+        } else {
+            search_bar
+        };
+
+        // Build content based on mode
+        let content: Element<'_, Message> = match self.mode {
+            UIMode::Search => {
+                // Search bar with suggestions (or empty state, or hint text) below
+                let below: Element<'_, Message> = if !self.suggestions.is_empty() {
+                    self.view_suggestions()
+                } else if let Some(empty) = &self.empty {
+                    view_empty(empty)
                 } else {
-                    // Fallback symbol based on category
-                    let symbol = match result.category {
-                        ResultCategory::App => "●",
-                        ResultCategory::File => "◆",
-                        ResultCategory::Command => "»",
-                        ResultCategory::AI => "◎",
-                        ResultCategory::Clipboard => "▢",
+                    let hint = if self.awaiting_index.is_some() {
+                        "Indexing applications…"
+                    } else {
+                        "Type to search apps, files, or ask AI..."
                     };
                     container(
-                        text(symbol).size(20).color(colors::PRIMARY)
+                        text(hint)
+                            .size(fonts::size(14))
+                            .color(colors::text_muted())
                     )
-                    .width(36)
-                    .center_x(36)
+                    .width(Length::Fill)
+                    .center_x(Length::Fill)
                     .into()
                 };
-                
-                let item_content = row![
-                    // Icon (image or fallback)
-                    icon_element,
-                    
-                    // Title and subtitle
-                    column![
-                        text(&result.title)
-                            .size(15)
-                            .color(if is_selected { colors::TEXT } else { colors::TEXT }),
-                        text(&result.subtitle)
-                            .size(12)
-                            .color(colors::TEXT_MUTED)
-                    ]
-                    .spacing(2),
-                    
-                    // Spacer
-                    Space::with_width(Length::Fill),
-                    
-                    // Keyboard hint for selected item
-                    text(if is_selected { "↵" } else { "" })
-                        .size(12)
-                        .color(colors::TEXT_MUTED)
-                ]
-                .spacing(12)
-                .align_y(iced::Alignment::Center);
+                column![
+                    search_bar,
+                    Space::with_height(16),
+                    below
+                ]
+                .spacing(0)
+                .into()
+            }
+            UIMode::Results => {
+                let results_list: Element<'_, Message> = match &self.detail {
+                    Some(detail) => row![
+                        container(self.view_results()).width(Length::FillPortion(3)),
+                        view_detail(detail),
+                    ]
+                    .spacing(12)
+                    .height(Length::FillPortion(1))
+                    .into(),
+                    None => self.view_results(),
+                };
+                column![
+                    search_bar,
+                    Space::with_height(12),
+                    results_list
+                ]
+                .spacing(0)
+                .into()
+            }
+            UIMode::Chat => {
+                // Status line (thinking, tools used)
+                let status_text = if self.loading {
+                    text(&self.ai_status).size(fonts::size(13)).color(colors::text_muted())
+                } else if !self.ai_status.is_empty() {
+                    text(&self.ai_status).size(fonts::size(13)).color(colors::primary())
+                } else {
+                    text("").size(fonts::size(13))
+                };
+                
+                // Command output, else the conversation; a failure card goes below
+                let response_view: Element<'_, Message> = if self.ai_response.is_empty() && !self.conversation.is_empty() {
+                    view_conversation(&self.conversation, !self.loading)
+                } else if self.ai_response.is_empty() && self.failure.is_some() {
+                    Space::with_height(0).into()
+                } else {
+                    container(
+                        scrollable(
+                            container(
+                                text(&self.ai_response)
+                                    .size(fonts::size(15))
+                                    .color(colors::text())
+                            )
+                            .padding(16)
+                        )
+                        .height(Length::Fill)
+                    )
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(|_theme| container::Style {
+                        background: Some(Background::Color(colors::surface())),
+                        border: Border::default().rounded(8),
+                        ..Default::default()
+                    })
+                    .into()
+                };
+                
+                let mut chat = column![
+                    search_bar,
+                    Space::with_height(8),
+                    status_text,
+                    Space::with_height(4),
+                    response_view
+                ]
+                .spacing(0);
+                if let Some(failure) = &self.failure {
+                    chat = chat.push(Space::with_height(8)).push(view_failure(failure));
+                }
+                chat.into()
+            }
+            UIMode::Regex => column![search_bar, Space::with_height(8), self.view_regex()]
+                .spacing(0)
+                .into(),
+            UIMode::Settings => {
+                column![
+                    search_bar,
+                    text(tr("settings-coming-soon")).color(colors::text_muted())
+                ]
+                .into()
+            }
+        };
+
+        let content = container(content).padding(16).width(Length::Fill).height(Length::Fill);
+        let content: Element<'_, Message> = if self.toasts.is_empty() {
+            content.into()
+        } else {
+            stack![content, self.toasts.view(colors::text(), colors::background())].into()
+        };
+
+        // Main container with rounded corners and proper background
+        container(content)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(|_theme| container::Style {
+            background: Some(Background::Color(colors::background())),
+            border: Border {
+                color: colors::border(),
+                width: 1.0,
+                radius: 16.0.into(),
+            },
+            ..Default::default()
+        })
+        .into()
+    }
+
+    /// Pattern status, test text editor, highlighted preview and capture groups
+    fn view_regex(&self) -> Element<'_, Message> {
+        let Some((compiled, content)) = &self.regex else {
+            return Space::with_height(0).into();
+        };
+
+        let editor = text_editor(content)
+            .placeholder("Test text")
+            .on_action(Message::RegexTextEdited)
+            .height(Length::Fill)
+            .size(fonts::size(13))
+            .font(Font::MONOSPACE)
+            .padding(12)
+            .style(|_theme, _status| text_editor::Style {
+                background: Background::Color(colors::surface()),
+                border: Border::default().rounded(8),
+                icon: colors::text_muted(),
+                placeholder: colors::text_placeholder(),
+                value: colors::text(),
+                selection: colors::selection(),
+            });
+
+        let regex = match compiled {
+            Ok(regex) => regex,
+            Err(e) => {
+                let error = text(e).size(fonts::size(13)).font(Font::MONOSPACE).color(colors::error());
+                return column![error, Space::with_height(8), editor].into();
+            }
+        };
+
+        let test_text = content.text();
+        let found = regex_tester::matches(regex, &test_text);
+        let status = match found.len() {
+            0 => text("No matches").size(fonts::size(13)).color(colors::text_muted()),
+            1 => text("1 match").size(fonts::size(13)).color(colors::primary()),
+            n => text(format!("{} matches", n)).size(fonts::size(13)).color(colors::primary()),
+        };
+
+        let spans: Vec<text::Span<'_, Message, Font>> = regex_tester::segments(&test_text, &found)
+            .into_iter()
+            .map(|(piece, matched)| {
+                let piece = span(piece.to_string()).font(Font::MONOSPACE);
+                if matched {
+                    piece.color(colors::primary()).background(colors::selection())
+                } else {
+                    piece.color(colors::text())
+                }
+            })
+            .collect();
+        let preview = container(scrollable(container(rich_text(spans).size(fonts::size(13))).padding(12)))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(|_theme| container::Style {
+                background: Some(Background::Color(colors::surface())),
+                border: Border::default().rounded(8),
+                ..Default::default()
+            });
+
+        let mut groups = column![].spacing(10);
+        for (i, m) in found.iter().take(regex_tester::MAX_LISTED).enumerate() {
+            let mut item = column![text(format!("{}. {}", i + 1, m.text))
+                .size(fonts::size(13))
+                .font(Font::MONOSPACE)
+                .color(colors::text())]
+            .spacing(2);
+            for (g, (name, value)) in m.groups.iter().enumerate() {
+                let label = match name {
+                    Some(name) => format!("  {} <{}>", g + 1, name),
+                    None => format!("  {}", g + 1),
+                };
+                let value = value.as_deref().unwrap_or("—");
+                item = item.push(
+                    text(format!("{}: {}", label, value))
+                        .size(fonts::size(12))
+                        .font(Font::MONOSPACE)
+                        .color(colors::text_muted()),
+                );
+            }
+            groups = groups.push(item);
+        }
+        if found.len() > regex_tester::MAX_LISTED {
+            groups = groups.push(
+                text(format!("… and {} more", found.len() - regex_tester::MAX_LISTED))
+                    .size(fonts::size(12))
+                    .color(colors::text_muted()),
+            );
+        }
+        let groups = container(scrollable(container(groups).padding(12)).height(Length::Fill))
+            .width(Length::FillPortion(2))
+            .height(Length::Fill)
+            .style(|_theme| container::Style {
+                background: Some(Background::Color(colors::surface())),
+                border: Border::default().rounded(8),
+                ..Default::default()
+            });
+
+        column![
+            status,
+            Space::with_height(8),
+            row![
+                column![editor, preview].spacing(8).width(Length::FillPortion(3)),
+                groups,
+            ]
+            .spacing(12)
+            .height(Length::Fill),
+        ]
+        .into()
+    }
+
+    fn view_results(&self) -> Element<'_, Message> {
+        // Only rows ranked by the fuzzy matcher show what matched
+        let query = |result: &SearchResult| match result.category {
+            ResultCategory::App
+            | ResultCategory::File
+            | ResultCategory::Git
+            | ResultCategory::Device
+            | ResultCategory::Contact => self.match_query.as_str(),
+            _ => "",
+        };
+        let hero = self
+            .results
+            .first()
+            .filter(|first| self.hero.as_deref() == Some(first.id.as_str()));
+        let sectioned = self.is_sectioned();
+        let mut items: Vec<Element<'_, Message>> = Vec::new();
+        for (i, result) in self.results.iter().enumerate() {
+            if hero.is_some() && i == 0 {
+                continue;
+            }
+            let category = result.category;
+            if sectioned && (i == 0 || self.results[i - 1].category != category) {
+                let count = self.results[i..].iter().take_while(|r| r.category == category).count();
+                items.push(view_section_header(
+                    category,
+                    self.section_label(category),
+                    count,
+                    self.collapsed.contains(&category),
+                ));
+            }
+            if let Some(day) = self.timeline_day(i) {
+                items.push(view_day_header(day));
+            }
+            if self.is_hidden(i) {
+                continue;
+            }
+            items.push({
+                let is_selected = i == self.selected_index && !self.unpicked;
+                
+                let thumbnail = match self.thumbnails.peek(&result.id) {
+                    Some(Thumbnail::Ready(handle)) if result.category == ResultCategory::File => Some(handle),
+                    _ => None,
+                };
+
+                // Render icon: thumbnail, else the row's own icon or its type's
+                let icon_element: Element<'_, Message> = if let Some(thumb) = thumbnail {
+                    container(
+                        image(thumb.clone())
+                            .width(32)
+                            .height(32)
+                    )
+                    .width(36)
+                    .center_x(36)
+                    .into()
+                } else {
+                    container(ui::icons::view(
+                        result.icon.as_deref(),
+                        result.category.as_str(),
+                        &self.icon_types,
+                        24,
+                        colors::primary(),
+                    ))
+                    .width(36)
+                    .center_x(36)
+                    .into()
+                };
+                
+                // Level controls show a slider where the Enter hint would be;
+                // marked clipboard entries show their place in the order
+                let mark = (result.category == ResultCategory::Clipboard)
+                    .then(|| self.marked_clips.iter().position(|marked| marked.to_string() == result.id))
+                    .flatten();
+                let trailing: Element<'_, Message> = match self.controls.get(&result.id) {
+                    Some(ControlKind::Level(percent)) if result.category == ResultCategory::Control => {
+                        let id = result.id.clone();
+                        ui::control::level(
+                            *percent,
+                            move |value| Message::ControlChanged(id.clone(), value),
+                            Message::ControlReleased(result.id.clone()),
+                            colors::text_muted(),
+                        )
+                    }
+                    _ => match mark {
+                        Some(index) => text(format!("✓ {}", index + 1)).size(fonts::size(12)).color(colors::primary()).into(),
+                        None => text(if is_selected { "↵" } else { "" }).size(fonts::size(12)).color(colors::text_muted()).into(),
+                    },
+                };
+
+                // Clipboard entries lead with their content type badge
+                let subtitle = ui::highlight::highlighted(&result.subtitle, query(result), 12, colors::text_muted(), colors::primary());
+                let subtitle: Element<'_, Message> = match result.subtitle.split_once(" · ") {
+                    Some((badge, rest)) if result.category == ResultCategory::Clipboard && !result.id.contains('\t') => row![
+                        view_badge(badge),
+                        ui::highlight::highlighted(rest, query(result), 12, colors::text_muted(), colors::primary()),
+                    ]
+                    .spacing(6)
+                    .align_y(iced::Alignment::Center)
+                    .into(),
+                    _ => subtitle,
+                };
+
+                let item_content = row![
+                    // Icon (image or fallback)
+                    icon_element,
+                    
+                    // Title and subtitle
+                    column![
+                        ui::highlight::highlighted(&result.title, query(result), 15, colors::text(), colors::primary()),
+                        subtitle,
+                    ]
+                    .spacing(2),
+                    
+                    // Spacer
+                    Space::with_width(Length::Fill),
+                    
+                    // Keyboard hint for selected item, or the control
+                    trailing
+                ]
+                .spacing(12)
+                .align_y(iced::Alignment::Center);
+
+                container(item_content)
+                    .padding(Padding::from([10.0, 12.0]))
+                    .width(Length::Fill)
+                    .style(move |_theme| container::Style {
+                        background: Some(Background::Color(
+                            if is_selected { colors::selection() } else { Color::TRANSPARENT }
+                        )),
+                        border: Border::default().rounded(8),
+                        ..Default::default()
+                    })
+                    .into()
+            });
+        }
+
+        // Categories still searching: a spinner header over placeholder rows
+        let frame = SPINNER[(self.search_started.elapsed().as_millis() / JOB_TICK.as_millis()) as usize % SPINNER.len()];
+        for category in &self.pending {
+            items.push(
+                row![
+                    text(category.label()).size(fonts::size(12)).color(colors::text_muted()),
+                    text(frame).size(fonts::size(12)).color(colors::primary()),
+                ]
+                .spacing(6)
+                .padding(Padding::from([4.0, 12.0]))
+                .into(),
+            );
+            items.extend((0..SKELETON_ROWS).map(|_| view_skeleton_row()));
+        }
+
+        let list = scrollable(
+            column(items).spacing(4)
+        )
+        .height(Length::Fill)
+        .on_scroll(|viewport| Message::ResultsScrolled {
+            offset: viewport.absolute_offset().y,
+            height: viewport.bounds().height,
+        });
+
+        // The clear best match stays pinned above the scrolling list
+        let content: Element<'_, Message> = match hero {
+            Some(best) => column![view_hero(best, &self.match_query, &self.icon_types, self.selected_index == 0 && !self.unpicked), list].spacing(8).into(),
+            None => list.into(),
+        };
+        container(content)
+            .height(Length::FillPortion(1))
+            .into()
+    }
+
+    /// Show or hide the window (shrunk, since Wayland can't move it away)
+    fn set_visible(&mut self, visible: bool) -> Task<Message> {
+        tracing::info!("Window visible: {}", visible);
+        if let Some(controller) = crate::get_window_controller() {
+            controller.set_visible(visible);
+        }
+        self.visible = visible;
+        if !visible {
+            return window::get_oldest().and_then(|id| {
+                Task::batch([
+                    window::resize(id, iced::Size::new(1.0, 1.0)),
+                    window::change_level(id, window::Level::Normal),
+                ])
+            });
+        }
+        events::publish(events::Event::WindowShown);
+        // Toasts from while it was hidden are stale
+        self.toasts.expire(Instant::now());
+        self.remind_permission();
+        // Opening on an empty prompt shows a fresh dashboard
+        if self.prompt.is_empty() && self.mode != UIMode::Chat && (self.results.is_empty() || self.is_dashboard()) {
+            self.show_dashboard();
+        }
+        let raise = window::get_oldest().and_then(|id| {
+            Task::batch([
+                // Reset level to force WM to re-evaluate
+                window::change_level(id, window::Level::Normal),
+                window::gain_focus(id),
+                window::request_user_attention(id, Some(window::UserAttention::Critical)),
+                // Set AlwaysOnTop LAST (and after a level reset) to be aggressive
+                window::change_level(id, window::Level::AlwaysOnTop),
+            ])
+        });
+        // The prompt is drawn afresh, unfocused
+        Task::batch([raise, self.place_window(), text_input::focus(prompt_input_id())])
+    }
+
+    /// Size and move the window to its saved geometry on the monitor it
+    /// opens on (asking the compositor takes a moment, so off the UI thread)
+    fn place_window(&self) -> Task<Message> {
+        let Some(controller) = crate::get_window_controller() else {
+            return Task::none();
+        };
+        Task::perform(
+            async move { tokio::task::spawn_blocking(move || controller.place()).await.unwrap_or_default() },
+            Message::WindowPlaced,
+        )
+    }
+
+    /// Hide the window if it's showing
+    fn hide_window(&mut self) -> Task<Message> {
+        match crate::get_window_controller() {
+            Some(controller) if controller.is_visible() => self.set_visible(false),
+            _ => Task::none(),
+        }
+    }
+
+    /// Whether losing focus should hide the window right now
+    fn auto_hides(&self) -> bool {
+        if self.pinned {
+            return false;
+        }
+        match self.services.config().ui.auto_hide {
+            AutoHide::Never => false,
+            AutoHide::Always => true,
+            AutoHide::Search => matches!(self.mode, UIMode::Search | UIMode::Results),
+        }
+    }
+
+    /// Pin toggle at the end of the search bar (Ctrl+.)
+    fn view_pin(&self) -> Element<'_, Message> {
+        let color = if self.pinned { colors::primary() } else { colors::text_placeholder() };
+        let label = if self.pinned { "📌 Pinned" } else { "📌" };
+        button(text(label).size(fonts::size(13)).color(color))
+            .on_press(Message::TogglePin)
+            .padding(Padding::from([4.0, 8.0]))
+            .style(|_theme, _status| button::Style::default())
+            .into()
+    }
+
+    fn view_suggestions(&self) -> Element<'_, Message> {
+        let rows: Vec<Element<'_, Message>> = self
+            .suggestions
+            .iter()
+            .enumerate()
+            .map(|(i, suggestion)| {
+                row![
+                    text(&suggestion.completion).size(fonts::size(14)).color(colors::text()),
+                    text(&suggestion.hint).size(fonts::size(12)).color(colors::text_muted()),
+                    Space::with_width(Length::Fill),
+                    text(if i == 0 { "Tab" } else { "" }).size(fonts::size(12)).color(colors::text_muted())
+                ]
+                .spacing(12)
+                .align_y(iced::Alignment::Center)
+                .padding(Padding::from([4.0, 12.0]))
+                .into()
+            })
+            .collect();
+
+        column(rows).spacing(2).into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let mut subscriptions = vec![
+            iced::event::listen_with(handle_event),
+            crate::wake::subscription().map(|_| Message::Tick),
+            events::subscription().map(Message::Bus),
+            crate::window_commands::subscription().map(Message::Window),
+            window::close_events().map(Message::WindowClosed),
+        ];
+        if !self.interactive {
+            subscriptions.push(window::frames().map(|_| Message::FirstFrame));
+        }
+        // Tool calls the AI proposes wait for an answer while it's replying
+        if self.chat_request.is_some() {
+            let calls = api::tool_calls(self.backend.clone(), self.session_id.clone());
+            subscriptions.push(calls.map(Message::ToolCallProposed));
+        }
+        // Idle otherwise, and while hidden only background work ticks
+        let activity = redraw::Activity {
+            busy: self.job.is_some() || !self.pending.is_empty(),
+            toasts: !self.toasts.is_empty(),
+            voice: self.voice.is_some(),
+            launches: !self.launches.is_empty(),
+            pasting: self.paste_queue.is_some(),
+            timers: !self.timers.is_empty(),
+        };
+        match redraw::clock(&activity, self.visible) {
+            Some(redraw::Clock::Fast) => subscriptions.push(iced::time::every(JOB_TICK).map(|_| Message::Tick)),
+            Some(redraw::Clock::Seconds) => {
+                subscriptions.push(iced::time::every(Duration::from_secs(1)).map(|_| Message::Tick));
+            }
+            None => {}
+        }
+        Subscription::batch(subscriptions)
+    }
+
+    pub fn theme(&self, _window: window::Id) -> Theme {
+        match self.theme {
+            config::Theme::Dark => Theme::Dark,
+            config::Theme::Light => Theme::Light,
+        }
+    }
+
+    // ========================================================================
+    // Business Logic
+    // ========================================================================
+
+    fn handle_command(&mut self, input: &str) {
+        let parts: Vec<&str> = input.split_whitespace().collect();
+        let cmd = parts.first().copied().unwrap_or("");
+        let args = parts.get(1..).unwrap_or(&[]).join(" ");
+
+        match cmd {
+            "/app" => self.search_apps(&args),
+            "/file" => {
+                let _ = self.search_files(&args);
+            }
+            "/clip" => self.show_clipboard(),
+            "/quit" => std::process::exit(0),
+            _ => {}
+        }
+    }
+
+    fn search(&mut self, query: &str) {
+        self.awaiting_index = (!self.services.is_ready()).then(|| query.to_string());
+        let (results, clear_winner) = self.services.rank_apps(query, services::DEFAULT_LIMIT);
+        self.hero = results.first().filter(|_| clear_winner).map(|best| best.id.clone());
+        self.results = results;
+        self.match_query = query.to_string();
+        self.empty = (self.results.is_empty() && self.awaiting_index.is_none())
+            .then(|| no_matches(ResultCategory::App, query));
+        self.selected_index = 0;
+        self.mode = if self.results.is_empty() {
+            UIMode::Search
+        } else {
+            UIMode::Results
+        };
+    }
+
+    fn search_apps(&mut self, query: &str) {
+        self.search(query);
+    }
+
+    /// Apps were installed or removed: say so, and rank the app results on
+    /// screen (or the dashboard's frequent apps) again, keeping the
+    /// selection on the same app if it's still there
+    fn apps_changed(&mut self, changes: &AppChanges) {
+        self.toasts.push(format!("App list updated ({})", changes.summary()), Instant::now());
+        if self.is_dashboard() {
+            self.show_dashboard();
+            return;
+        }
+        let Some(query) = self.prompt.strip_prefix("/app ").map(str::to_string) else {
+            return;
+        };
+        if !self.results.iter().all(|r| r.category == ResultCategory::App) {
+            return;
+        }
+        let selected = self.results.get(self.selected_index).map(|r| r.id.clone());
+        self.search(&query);
+        if let Some(index) = selected.and_then(|id| self.results.iter().position(|r| r.id == id)) {
+            self.selected_index = index;
+        }
+    }
+
+    /// Pick up an edited config.toml: theme, aliases, abbreviations,
+    /// favorites, saved searches and bookmarks are read from it again (the
+    /// services did their part)
+    fn config_reloaded(&mut self, restart: &[&str]) {
+        let config = self.services.config();
+        self.theme = config.ui.theme;
+        colors::set_light(self.theme == config::Theme::Light);
+        self.aliases = config.aliases.clone();
+        self.favorites = config.favorites.clone();
+        self.saved_searches = config.saved_searches.clone();
+        self.bookmarks = config.bookmarks.clone();
+        self.icon_types = config.icons.types.clone();
+        self.expander = Expander::new(&config.abbreviations);
+        if self.thumbnails.stats().budget != config.cache.thumbnails_mb * MB {
+            self.thumbnails = LruCache::new(config.cache.thumbnails_mb * MB);
+        }
+        let toast = match restart {
+            [] => String::from("Config reloaded"),
+            settings => format!("Config reloaded; restart Ruty to apply {}", settings.join(", ")),
+        };
+        self.toasts.push(toast, Instant::now());
+        if self.is_dashboard() {
+            self.show_dashboard();
+        }
+    }
+
+    /// File search (spawns fd/find, in the background); image and video rows
+    /// get thumbnails as they come into view. A filter that doesn't parse
+    /// (`size:huge`) shows its error instead.
+    fn search_files(&mut self, query: &str) -> Task<Message> {
+        if let Err(e) = FileQuery::parse(query) {
+            self.fail("Invalid file search", e, None);
+            return Task::none();
+        }
+        self.search_in_background(query, &[(ResultCategory::File, |s, query| {
+            s.search_files(query, services::DEFAULT_LIMIT)
+        })])
+    }
+
+    /// Search a plain prompt as it's typed (`[search] live`): apps, and any
+    /// device or contact it names, right away; files and repos in the
+    /// background. Only a clear best match is selected, so Enter asks AI
+    /// unless a row is picked.
+    fn live_search(&mut self, query: &str) -> Task<Message> {
+        let (apps, clear_winner) = self.services.rank_apps(query, services::DEFAULT_LIMIT);
+        // Bookmarks named like a short prompt come first; a single one is the best match
+        let bookmarks = self.bookmark_rows(query);
+        let (mut rows, clear_winner) = match bookmarks.len() {
+            0 => (Vec::new(), clear_winner),
+            n => (bookmarks, n == 1),
+        };
+        rows.extend(apps);
+        // Short prompts would match nearly every device
+        if query.trim().len() >= 3 {
+            rows.extend(self.services.search_devices(query, services::DEFAULT_LIMIT, true));
+            rows.extend(self.services.search_contacts(query, services::DEFAULT_LIMIT, true));
+        }
+        let task = self.search_in_background(query, &[
+            (ResultCategory::File, |s, query| s.search_files(query, services::DEFAULT_LIMIT)),
+            (ResultCategory::Git, |s, query| s.search_repos(query, services::DEFAULT_LIMIT)),
+        ]);
+        self.awaiting_index = (!self.services.is_ready()).then(|| query.to_string());
+        self.hero = rows.first().filter(|_| clear_winner).map(|best| best.id.clone());
+        // A recognized request outranks a guessed best match
+        self.unpicked = self.hero.is_none() || self.intent.is_some();
+        self.results = rows;
+        task
+    }
+
+    /// Show the calculator row alone, selected
+    fn show_calculation(&mut self, row: SearchResult) {
+        // Rows of a search still running are for the text typed before
+        self.search_generation += 1;
+        self.awaiting_index = None;
+        self.hero = None;
+        self.match_query.clear();
+        self.results = vec![row];
+        self.selected_index = 0;
+        self.mode = UIMode::Results;
+    }
+
+    /// Run searches off the UI thread. Each category's rows stream in as its
+    /// search finishes; until then it shows skeleton rows and a spinner.
+    fn search_in_background(&mut self, query: &str, searches: &[(ResultCategory, CategorySearch)]) -> Task<Message> {
+        self.search_generation += 1;
+        self.search_started = Instant::now();
+        self.pending = searches.iter().map(|(category, _)| *category).collect();
+        self.results.clear();
+        self.match_query = query.to_string();
+        self.empty = None;
+        self.selected_index = 0;
+        self.results_viewport.0 = 0.0;
+        self.mode = UIMode::Results;
+
+        let generation = self.search_generation;
+        Task::batch(searches.iter().map(|&(category, search)| {
+            let services = self.services.clone();
+            let query = query.to_string();
+            Task::perform(
+                async move {
+                    tokio::task::spawn_blocking(move || search(&services, &query))
+                        .await
+                        .unwrap_or_default()
+                },
+                move |results| Message::SearchComplete { generation, category, results },
+            )
+        }))
+    }
+
+    /// List clipboard history (newest first) as results
+    fn show_clipboard(&mut self) {
+        self.filter_clipboard("");
+    }
+
+    /// List clipboard history of one type and/or containing some text:
+    /// `filter` is `[type] [text]`, e.g. "urls github"
+    fn filter_clipboard(&mut self, filter: &str) {
+        let filter = filter.trim();
+        let (first, rest) = filter.split_once(char::is_whitespace).unwrap_or((filter, ""));
+        let (kind, query) = match ContentKind::parse(first) {
+            Some(kind) => (Some(kind), rest.trim()),
+            None => (None, filter),
+        };
+        self.results = self.clipboard_rows(usize::MAX, kind, query);
+        // History only holds text, so a copied image is offered for OCR
+        if filter.is_empty() && clipboard::has_image() {
+            let language = self.services.config().clipboard.ocr_language.clone();
+            self.results.insert(0, SearchResult {
+                id: "ocr\t".to_string(),
+                title: "Extract text (OCR) from the copied image".to_string(),
+                subtitle: format!("Adds the text to clipboard history · language {}", language),
+                icon: None,
+                category: ResultCategory::Clipboard,
+            });
+        }
+        self.match_query = query.to_string();
+        self.action_menu = None;
+        self.dashboard = false;
+        self.selected_index = 0;
+        self.mode = if self.results.is_empty() {
+            UIMode::Search
+        } else {
+            UIMode::Results
+        };
+    }
+
+    /// The newest `limit` clipboard history entries as rows, only those of
+    /// `kind` (if given) that contain `query`
+    fn clipboard_rows(&self, limit: usize, kind: Option<ContentKind>, query: &str) -> Vec<SearchResult> {
+        let query = query.to_lowercase();
+        self.services
+            .clipboard()
+            .get_history()
+            .into_iter()
+            .filter(|item| kind.is_none_or(|kind| item.kind == kind))
+            .filter(|item| query.is_empty() || item.content.to_lowercase().contains(&query))
+            .take(limit)
+            .map(|item| SearchResult {
+                id: item.id.to_string(),
+                title: item.content.lines().next().unwrap_or("").chars().take(80).collect(),
+                // The badge before the first " · " is drawn as a pill
+                subtitle: format!("{} · {} chars", item.badge(), item.content.chars().count()),
+                icon: None,
+                category: ResultCategory::Clipboard,
+            })
+            .collect()
+    }
+
+    /// The clipboard history entry a row's id names
+    fn clipboard_item(&self, id: &str) -> Option<ClipboardItem> {
+        let id = id.parse::<u64>().ok()?;
+        self.services.clipboard().get_history().into_iter().find(|item| item.id == id)
+    }
+
+    /// Ctrl+O on a clipboard entry: copying, plus actions that fit its type,
+    /// and merging or pasting the marked entries if there are any
+    fn clipboard_actions(&self, id: &str) -> Vec<SearchResult> {
+        let Some(item) = self.clipboard_item(id) else {
+            return Vec::new();
+        };
+        let marked = self.marked_clips.len();
+        let mut rows: Vec<SearchResult> = [
+            ("merge", format!("Merge & copy {} marked entries", marked)),
+            ("sequence", format!("Paste {} marked entries one at a time", marked)),
+        ]
+        .into_iter()
+        .filter(|_| marked > 0)
+        .map(|(action, title)| SearchResult {
+            id: format!("{}\t{}", action, id),
+            title,
+            subtitle: "Ctrl+Space marks or unmarks an entry".to_string(),
+            icon: None,
+            category: ResultCategory::Clipboard,
+        })
+        .collect();
+        let mut actions = vec![("copy", "Copy to clipboard")];
+        actions.extend_from_slice(match item.kind {
+            ContentKind::Url => &[("open", "Open in browser")],
+            ContentKind::Email => &[("open", "Write an email")],
+            ContentKind::Path => &[("open", "Open"), ("reveal", "Show in folder")],
+            ContentKind::Color if item.content.trim().starts_with('#') => &[("convert", "Copy as rgb()")],
+            ContentKind::Color => &[("convert", "Copy as hex")],
+            ContentKind::Json => &[("format", "Copy formatted"), ("minify", "Copy minified")],
+            ContentKind::Code => &[("explain", "Ask AI to explain")],
+            ContentKind::Text => &[],
+        });
+        let preview: String = item.content.lines().next().unwrap_or_default().chars().take(80).collect();
+        rows.extend(actions.into_iter().map(|(action, title)| SearchResult {
+            id: format!("{}\t{}", action, id),
+            title: title.to_string(),
+            subtitle: preview.clone(),
+            icon: None,
+            category: ResultCategory::Clipboard,
+        }));
+        rows
+    }
+
+    /// Run a clipboard entry action (see `clipboard_actions`)
+    fn run_clipboard_action(&mut self, action: &str, item: ClipboardItem) -> Task<Message> {
+        let content = item.content.trim();
+        let outcome = match action {
+            "open" => match item.kind {
+                ContentKind::Email => self.services.open_url(&format!("mailto:{}", content.trim_start_matches("mailto:"))),
+                ContentKind::Path => {
+                    let path = config::expand_home(content).to_string_lossy().to_string();
+                    self.services.launch(&path, ResultCategory::File)
+                }
+                _ if content.starts_with("www.") => self.services.open_url(&format!("https://{}", content)),
+                _ => self.services.open_url(content),
+            },
+            "reveal" => self.services.reveal(&config::expand_home(content).to_string_lossy()),
+            "convert" => content::convert_color(content)
+                .map(|converted| self.copy_to_clipboard(&converted, format!("Copied {}", converted)))
+                .ok_or_else(|| format!("Can't convert {}", content)),
+            "format" | "minify" => {
+                let (transform, toast) = match action {
+                    "format" => (Transform::JsonFormat, "Copied formatted JSON"),
+                    _ => (Transform::JsonMinify, "Copied minified JSON"),
+                };
+                transform.apply(content).map(|json| self.copy_to_clipboard(&json, toast.to_string()))
+            }
+            "merge" => {
+                let contents = self.take_marked_clips();
+                let separator = self.services.config().clipboard.merge_separator.clone();
+                self.copy_to_clipboard(&contents.join(&separator), format!("Merged {} entries", contents.len()));
+                Ok(())
+            }
+            "sequence" => {
+                let contents = self.take_marked_clips();
+                return self.paste_in_sequence(contents);
+            }
+            "explain" => {
+                let language = item.language.as_deref().unwrap_or_default();
+                let context = format!("### Clipboard\n```{}\n{}\n```", language.to_lowercase(), item.content);
+                self.action_menu = None;
+                return self.send_chat("Explain what this code does".to_string(), Some(context));
+            }
+            _ => {
+                self.copy_to_clipboard(&item.content, "Copied to clipboard".to_string());
+                Ok(())
+            }
+        };
+        if let Err(e) = outcome {
+            self.fail("Clipboard action failed", e, None);
+        }
+        Task::none()
+    }
+
+    /// Bare `/qr`: scan a code off the screen, or show the latest clipboard
+    /// entry as one
+    fn show_qr_menu(&mut self) {
+        let mut rows = vec![SearchResult {
+            id: "qr-scan\t".to_string(),
+            title: "Scan QR code from screen".to_string(),
+            subtitle: "Drag over the code; needs slurp and grim (Wayland) or maim".to_string(),
+            icon: None,
+            category: ResultCategory::Action,
+        }];
+        if let Some(latest) = self.services.clipboard().get_history().into_iter().next() {
+            rows.push(SearchResult {
+                title: "QR code of the clipboard".to_string(),
+                subtitle: latest.content.lines().next().unwrap_or_default().chars().take(80).collect(),
+                id: format!("qr-show\t{}", latest.content),
+                icon: None,
+                category: ResultCategory::Action,
+            });
+        }
+        self.results = rows;
+        self.dashboard = false;
+        self.match_query.clear();
+        self.selected_index = 0;
+        self.mode = UIMode::Results;
+    }
+
+    /// Rows to copy or save `text` as a QR code, which shows in the detail panel
+    fn show_qr(&mut self, text: &str) {
+        if let Err(e) = qr::render(text) {
+            self.fail("No QR code", e, None);
+            return;
+        }
+        let preview: String = text.lines().next().unwrap_or_default().chars().take(80).collect();
+        let row = |action: &str, title: &str, subtitle: String| SearchResult {
+            id: format!("{}\t{}", action, text),
+            title: title.to_string(),
+            subtitle,
+            icon: None,
+            category: ResultCategory::Action,
+        };
+        self.results = vec![
+            row("qr-copy", "Copy QR code image", preview),
+            row("qr-save", "Save QR code image", format!("As a PNG in {}", qr_dir().display())),
+        ];
+        self.dashboard = false;
+        self.match_query.clear();
+        self.selected_index = 0;
+        self.mode = UIMode::Results;
+        self.detail = Some(details::load(&self.services, &self.results[0]));
+    }
+
+    /// Run a `/qr` row (see `show_qr_menu` and `show_qr`)
+    fn run_qr_action(&mut self, action: &str, text: &str) -> Task<Message> {
+        let outcome = match action {
+            "qr-scan" => return self.scan_qr(),
+            "qr-show" => {
+                self.show_qr(text);
+                return Task::none();
+            }
+            "qr-copy" => qr::render(text)
+                .and_then(|png| clipboard::copy_image(&png))
+                .map(|()| "Copied QR code image".to_string()),
+            _ => qr::render(text).and_then(|png| {
+                let path = qr_dir().join(format!("qr-{}.png", unix_now()));
+                std::fs::write(&path, png).map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+                Ok(format!("Saved {}", path.display()))
+            }),
+        };
+        match outcome {
+            Ok(toast) => self.toasts.push(format!("{} ✓", toast), Instant::now()),
+            Err(e) => self.fail("QR code failed", e, None),
+        }
+        Task::none()
+    }
+
+    /// Open the scratchpad window, or close it if it's open
+    fn toggle_pad(&mut self) -> Task<Message> {
+        match self.pad.take() {
+            Some(pad) => pad.close(),
+            None => {
+                let (pad, opened) = Scratchpad::open();
+                self.pad = Some(pad);
+                opened.discard()
+            }
+        }
+    }
+
+    /// Hide the window so the user can drag over a QR code, then read it
+    fn scan_qr(&mut self) -> Task<Message> {
+        let scan = Task::perform(
+            async move {
+                tokio::task::spawn_blocking(|| {
+                    // Let the compositor unmap the launcher before the overlay
+                    std::thread::sleep(Duration::from_millis(200));
+                    match screenshot::region()? {
+                        Some(png) => qr::scan(&png).map(Some),
+                        None => Ok(None),
+                    }
+                })
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|result| result)
+            },
+            Message::QrScanned,
+        );
+        Task::batch([self.hide_window(), scan])
+    }
+
+    /// Read the text in the copied image off the UI thread (tesseract takes
+    /// a second or two)
+    fn extract_image_text(&mut self) -> Task<Message> {
+        let language = self.services.config().clipboard.ocr_language.clone();
+        self.results.clear();
+        self.action_menu = None;
+        self.loading = true;
+        self.ai_status = "Reading text in the image…".to_string();
+        self.mode = UIMode::Chat;
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let image = clipboard::image().ok_or("No image on the clipboard (copy a PNG or screenshot)")?;
+                    ocr::extract(&image, &language)
+                })
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|result| result)
+            },
+            Message::TextExtracted,
+        )
+    }
+
+    /// Contents of the marked clipboard entries in marking order, unmarking
+    /// them (entries deleted meanwhile are skipped)
+    fn take_marked_clips(&mut self) -> Vec<String> {
+        let history = self.services.clipboard().get_history();
+        std::mem::take(&mut self.marked_clips)
+            .into_iter()
+            .filter_map(|id| history.iter().find(|item| item.id == id))
+            .map(|item| item.content.clone())
+            .collect()
+    }
+
+    /// Offer `contents` for pasting one after another, hiding the window so
+    /// the first can be pasted right away
+    fn paste_in_sequence(&mut self, contents: Vec<String>) -> Task<Message> {
+        // Polling reads the clipboard, which would count as the paste
+        self.services.clipboard().set_paused(true);
+        match PasteQueue::start(contents, clipboard::copy_once, Instant::now()) {
+            Ok(queue) => {
+                self.toasts.push_action(
+                    format!("Paste {} entries one at a time", queue.total()),
+                    "Stop",
+                    Message::StopPasting,
+                    paste_queue::IDLE_TIMEOUT,
+                    Instant::now(),
+                );
+                self.paste_queue = Some(queue);
+                self.action_menu = None;
+                self.hide_window()
+            }
+            Err(e) => {
+                self.services.clipboard().set_paused(false);
+                self.fail("Couldn't start pasting", e, None);
+                Task::none()
+            }
+        }
+    }
+
+    /// Put the next marked entry on the clipboard once the last was pasted
+    fn check_paste_queue(&mut self) {
+        let Some(queue) = &mut self.paste_queue else {
+            return;
+        };
+        let now = Instant::now();
+        let toast = match queue.poll(now) {
+            Ok(paste_queue::Progress::Waiting) => return,
+            Ok(paste_queue::Progress::Next) => {
+                let (left, total) = (queue.left(), queue.total());
+                self.toasts.dismiss(|message| matches!(message, Message::StopPasting));
+                self.toasts.push_action(
+                    format!("Pasted {} of {}", total - left, total),
+                    "Stop",
+                    Message::StopPasting,
+                    paste_queue::IDLE_TIMEOUT,
+                    now,
+                );
+                return;
+            }
+            Ok(paste_queue::Progress::Done) => Ok(format!("Pasted all {} entries", queue.total())),
+            Ok(paste_queue::Progress::Abandoned) => Ok("Stopped pasting: nothing was pasted for a while".to_string()),
+            Err(e) => Err(e),
+        };
+        self.paste_queue = None;
+        self.services.clipboard().set_paused(false);
+        self.toasts.dismiss(|message| matches!(message, Message::StopPasting));
+        match toast {
+            Ok(toast) => self.toasts.push(toast, now),
+            Err(e) => self.fail("Sequential paste failed", e, None),
+        }
+    }
+
+    /// Whether the results mix categories, and so are grouped under headers
+    fn is_sectioned(&self) -> bool {
+        // The timeline is grouped by day instead
+        self.timeline.is_none()
+            && (self.is_dashboard()
+                || self.results.first().is_some_and(|first| {
+                    first.category == ResultCategory::Favorite
+                        || self.results.iter().any(|r| r.category != first.category)
+                }))
+    }
+
+    /// Section header text; the dashboard's apps and files are picked by usage
+    fn section_label(&self, category: ResultCategory) -> &'static str {
+        match category {
+            ResultCategory::App if self.is_dashboard() => "Frequent apps",
+            ResultCategory::File if self.is_dashboard() => "Recent files",
+            _ => category.label(),
+        }
+    }
+
+    /// Whether the row at `index` is in a collapsed section
+    fn is_hidden(&self, index: usize) -> bool {
+        self.is_sectioned()
+            && self
+                .results
+                .get(index)
+                .is_some_and(|result| self.collapsed.contains(&result.category))
+    }
+
+    /// Move the selection to the next (or previous) shown row, wrapping around
+    fn step_selection(&mut self, forward: bool) {
+        let len = self.results.len();
+        let mut index = self.selected_index;
+        // Nothing picked yet: start just outside the list
+        if std::mem::take(&mut self.unpicked) {
+            index = if forward { len.saturating_sub(1) } else { 0 };
+        }
+        for _ in 0..len {
+            index = if forward { (index + 1) % len } else { (index + len - 1) % len };
+            if !self.is_hidden(index) {
+                self.selected_index = index;
+                return;
+            }
+        }
+    }
+
+    /// Show a failed request as an error card
+    fn fail(&mut self, title: &'static str, detail: impl std::fmt::Display, retry: Option<String>) {
+        self.results.clear();
+        self.loading = false;
+        self.ai_status.clear();
+        self.ai_response.clear();
+        self.failure = Some(Failure { title, detail: detail.to_string(), retry });
+        self.mode = UIMode::Chat;
+    }
+
+    /// Report the outcome of running a result: its message, or an error card
+    fn show_outcome(&mut self, failed: &'static str, outcome: Result<String, String>) {
+        match outcome {
+            Ok(message) => {
+                self.results.clear();
+                self.ai_status.clear();
+                self.ai_response = message;
+                self.mode = UIMode::Chat;
+            }
+            Err(e) => self.fail(failed, e, None),
+        }
+    }
+
+    /// Send a chat message, with extra context for this message only
+    fn send_chat(&mut self, message: String, local_context: Option<String>) -> Task<Message> {
+        // Notes only ride along once the embedding model is there; ask once
+        let memory_on = !self.services.config().memory.folders.is_empty();
+        if memory_on && !self.memory_model_offered && embedding::needs_model() {
+            self.memory_model_offered = true;
+            let toast = format!("Recalling your notes needs the embedding model ({} MB)", embedding::MODEL_MB);
+            self.toasts.push_action(toast, "Download", Message::DownloadMemoryModel, CONFIRM_WINDOW, Instant::now());
+        }
+        // An edited resend of the last prompt replaces that exchange
+        let replace_last = std::mem::take(&mut self.editing_last) && self.conversation.pop_exchange().is_some();
+        self.request_chat(message, local_context, replace_last)
+    }
+
+    /// Send a chat request; `replace_last` has the backend drop the session's
+    /// last exchange first
+    fn request_chat(&mut self, message: String, local_context: Option<String>, replace_last: bool) -> Task<Message> {
+        self.failure = None;
+        self.pending.clear();
+        self.loading = true;
+        self.ai_status = "🤔 Thinking...".to_string();
+        self.ai_response.clear();
+        self.tools_used.clear();
+        self.mode = UIMode::Chat;
+        self.conversation.push_user(message.clone(), local_context.clone());
+
+        let backend = self.backend.clone();
+        let session_id = self.session_id.clone();
+        let services = self.services.clone();
+        let request = Task::perform(
+            async move {
+                // Relevant notes ride along with this prompt only
+                let notes = services.recall(&message);
+                let local_context = match (local_context, notes) {
+                    (Some(context), Some(notes)) => Some(format!("{}\n\n{}", context, notes)),
+                    (context, notes) => context.or(notes),
+                };
+                let request = ChatRequest {
+                    message,
+                    session_id,
+                    local_context,
+                    api_keys: None,
+                    replace_last,
+                    tool_approval: ToolApproval::Ask,
+                };
+                backend.chat(request).await
+            },
+            |result| match result {
+                Ok(resp) => Message::AIResponseWithTools {
+                    response: resp.response,
+                    tools: resp.tools_used,
+                },
+                Err(e) => Message::AIError(e),
+            }
+        ).chain(Task::done(Message::AIResponseComplete));
+        let (request, handle) = request.abortable();
+        self.chat_request = Some(handle);
+        Task::batch([self.follow_conversation(), request])
+    }
+
+    /// Ask whether the AI may make the first tool call waiting for an answer
+    fn show_tool_call(&mut self) {
+        let Some(call) = self.tool_calls.first() else {
+            return;
+        };
+        let (action, wants) = match call.tool.as_str() {
+            "run_shell" => (format!("run `{}`", call.argument), "The AI wants to run this command"),
+            "open_url" => (format!("open {}", call.argument), "The AI wants to open this link"),
+            tool => (format!("{} {}", tool, call.argument), "The AI wants to use this tool"),
+        };
+        let row = |answer: &str, title: String, subtitle: String| SearchResult {
+            id: format!("{}\t{}", answer, call.id),
+            title,
+            subtitle,
+            icon: None,
+            category: ResultCategory::Action,
+        };
+        self.results = vec![
+            row("tool-allow", format!("Allow the AI to {}", action), format!("{}; Enter allows it once", wants)),
+            row("tool-deny", "Don't allow it".to_string(), "The AI is told you said no".to_string()),
+        ];
+        self.dashboard = false;
+        self.empty = None;
+        self.prompt.clear();
+        self.match_query.clear();
+        self.selected_index = 0;
+        self.mode = UIMode::Results;
+    }
+
+    /// Send the answer to a proposed tool call, then ask about the next one
+    fn answer_tool_call(&mut self, id: &str, approved: bool) -> Task<Message> {
+        let Some(index) = self.tool_calls.iter().position(|call| call.id == id) else {
+            return Task::none();
+        };
+        let call = self.tool_calls.remove(index);
+        let answer = if approved { "allowed" } else { "denied" };
+        audit::record(audit::Kind::Tool, &format!("{} {}", call.tool, call.argument), answer);
+        if self.tool_calls.is_empty() {
+            self.results.clear();
+            self.mode = UIMode::Chat;
+        } else {
+            self.show_tool_call();
+        }
+        let backend = self.backend.clone();
+        Task::perform(async move { backend.decide_tool_call(&call.id, approved).await }, Message::ToolCallDecided)
+    }
+
+    /// Deny the tool calls still waiting, as the reply they were for stopped
+    fn deny_tool_calls(&mut self) -> Task<Message> {
+        let ids: Vec<String> = std::mem::take(&mut self.tool_calls)
+            .into_iter()
+            .map(|call| {
+                audit::record(audit::Kind::Tool, &format!("{} {}", call.tool, call.argument), "denied");
+                call.id
+            })
+            .collect();
+        if ids.is_empty() {
+            return Task::none();
+        }
+        if self.results.iter().any(|r| r.id.starts_with("tool-")) {
+            self.results.clear();
+            self.mode = UIMode::Chat;
+        }
+        let backend = self.backend.clone();
+        Task::batch(ids.into_iter().map(|id| {
+            let backend = backend.clone();
+            Task::perform(async move { backend.decide_tool_call(&id, false).await }, Message::ToolCallDecided)
+        }))
+    }
+
+    /// Scroll the chat view to the newest turn, unless the user scrolled up
+    fn follow_conversation(&self) -> Task<Message> {
+        if self.conversation.follows() {
+            scrollable::snap_to(chat_scroll_id(), scrollable::RelativeOffset::END)
+        } else {
+            Task::none()
+        }
+    }
+
+    fn execute_selected(&mut self) -> Task<Message> {
+        if self.is_hidden(self.selected_index) {
+            return Task::none();
+        }
+        let Some(result) = self.results.get(self.selected_index) else {
+            return Task::none();
+        };
+        self.failure = None;
+        match result.category {
+            ResultCategory::App => {
+                let id = result.id.clone();
+                if let Err(e) = self.launch_app(&id, false) {
+                    tracing::warn!("Launch failed: {}", e);
+                }
+            }
+            ResultCategory::File => match self.services.launch(&result.id, result.category) {
+                Ok(()) => {
+                    let (id, title) = (result.id.clone(), result.title.clone());
+                    self.record_usage(ResultCategory::File, &id, &title);
+                }
+                Err(e) => tracing::warn!("Launch failed: {}", e),
+            },
+            ResultCategory::Timer => {
+                if let Some(timer) = result.id.parse().ok().and_then(|id| self.timers.cancel(id)) {
+                    self.toasts.push(format!("Cancelled {}", timer.title()), Instant::now());
+                    let selected = self.selected_index;
+                    self.show_dashboard();
+                    self.selected_index = selected.min(self.results.len().saturating_sub(1));
+                }
+            }
+            // App, folder and /qr menu rows are `<action>\t<app id, path or text>`
+            ResultCategory::Action => match result.id.split_once('\t') {
+                Some((action, text)) if action.starts_with("qr-") => {
+                    let (action, text) = (action.to_string(), text.to_string());
+                    return self.run_qr_action(&action, &text);
+                }
+                Some((answer @ ("tool-allow" | "tool-deny"), id)) => {
+                    let (approved, id) = (answer == "tool-allow", id.to_string());
+                    return self.answer_tool_call(&id, approved);
+                }
+                Some((answer @ ("allow" | "allow-once" | "deny"), provider)) => {
+                    let (answer, provider) = (answer.to_string(), provider.to_string());
+                    return self.answer_permission(&answer, &provider);
+                }
+                Some(("revoke", provider)) => {
+                    let provider = provider.to_string();
+                    self.revoke_permission(&provider);
+                }
+                Some((action @ ("terminal-here" | "editor-here"), path)) => {
+                    let outcome = self.services.run_folder_action(action, Path::new(path));
+                    self.prompt.clear();
+                    self.browsing = None;
+                    self.show_outcome("Couldn't open folder", outcome);
+                }
+                Some((action, app_id)) => {
+                    let (action, app_id) = (action.to_string(), app_id.to_string());
+                    match action.as_str() {
+                        "launch-options" => self.edit_launch_options(&app_id),
+                        "toggle-hidden" => self.toggle_hidden_app(&app_id),
+                        new_instance => {
+                            if let Err(e) = self.launch_app(&app_id, new_instance == "launch-new") {
+                                self.fail("Launch failed", e, None);
+                            }
+                        }
+                    }
+                }
+                None => {
+                    if let Some(action) = Action::from_id(&result.id) {
+                        self.run_action(action);
+                    }
+                }
+            },
+            ResultCategory::Git => {
+                let id = result.id.clone();
+                self.run_repo_result(&id);
+            }
+            ResultCategory::Archive => {
+                let Some((action, path)) = result.id.split_once('\t') else {
+                    return Task::none();
+                };
+                let path = PathBuf::from(path);
+                match action {
+                    "extract" => {
+                        let dest = archives::default_destination(&path);
+                        return self.start_extraction(path, dest);
+                    }
+                    "extract-to" => {
+                        let parent = path.parent().map(|p| p.display().to_string()).unwrap_or_default();
+                        self.pending_archive = Some(path);
+                        self.results.clear();
+                        self.mode = UIMode::Search;
+                        self.prompt = format!("/extract-to {}/", parent);
+                        return text_input::move_cursor_to_end(prompt_input_id());
+                    }
+                    // "list": contents show in the detail panel
+                    _ => {
+                        if self.detail.is_none() {
+                            return self.update(Message::ToggleDetail);
+                        }
+                    }
+                }
+            }
+            ResultCategory::Memory => {
+                let id = result.id.clone();
+                return self.run_memory_result(&id);
+            }
+            ResultCategory::OpenWith => {
+                let outcome = self.services.run_open_with(&result.id);
+                self.prompt.clear();
+                self.show_outcome("Couldn't open file", outcome);
+            }
+            ResultCategory::Device => {
+                let outcome = self.services.run_device_action(&result.id);
+                self.prompt.clear();
+                self.show_outcome("Device action failed", outcome);
+            }
+            ResultCategory::Control => {
+                let id = result.id.clone();
+                let Some(ControlKind::Toggle(enabled)) = self.controls.get(&id).copied() else {
+                    return Task::none();
+                };
+                let Some(target) = Target::decode(&id) else {
+                    return Task::none();
+                };
+                match controls::toggle(&target, enabled) {
+                    Ok(now) => {
+                        self.controls.insert(id, ControlKind::Toggle(now));
+                        if let Some(row) = self.results.get_mut(self.selected_index) {
+                            row.subtitle = control_hint(ControlKind::Toggle(now));
+                        }
+                    }
+                    Err(e) => tracing::warn!("Toggle failed: {}", e),
+                }
+            }
+            ResultCategory::Network | ResultCategory::Hash | ResultCategory::Transform
+                if !result.id.is_empty() =>
+            {
+                let value = result.id.clone();
+                let toast = if value == result.title && value.chars().count() <= 40 {
+                    format!("Copied {}", value)
+                } else {
+                    format!("Copied {} chars", value.chars().count())
+                };
+                self.copy_to_clipboard(&value, toast);
+            }
+            ResultCategory::Saved => {
+                let name = result.id.clone();
+                return self.run_saved(&name);
+            }
+            ResultCategory::Bookmark => {
+                let folder = config::expand_home(&result.id);
+                return self.browse_folder(folder);
+            }
+            ResultCategory::Calculator => {
+                let Some(value) = self.services.record_calculation(&result.id) else {
+                    return Task::none();
+                };
+                self.copy_to_clipboard(&value, format!("Copied {}", value));
+                // Ready for the next calculation, which can go on from `ans`
+                self.prompt.clear();
+                self.show_dashboard();
+            }
+            // An older entry goes back on the clipboard; its Ctrl+O rows are
+            // `<action>\t<id>`
+            ResultCategory::Clipboard => {
+                let (action, id) = result.id.split_once('\t').unwrap_or(("copy", &result.id));
+                if action == "ocr" {
+                    return self.extract_image_text();
+                }
+                let action = action.to_string();
+                if let Some(item) = self.clipboard_item(id) {
+                    return self.run_clipboard_action(&action, item);
+                }
+            }
+            ResultCategory::Favorite => {
+                if let Some(index) = self.selected_favorite() {
+                    return self.open_favorite(self.favorites[index].clone());
+                }
+            }
+            // A contact lists its addresses and numbers; those rows are
+            // `<action>\t<email or phone>`
+            ResultCategory::Contact => match result.id.split_once('\t') {
+                Some(("compose", email)) => {
+                    if let Err(e) = self.services.open_url(&format!("mailto:{}", email)) {
+                        self.fail("Couldn't compose an email", e, None);
+                    }
+                }
+                Some((_, value)) => {
+                    let value = value.to_string();
+                    self.copy_to_clipboard(&value, format!("Copied {}", value));
+                }
+                None => return self.update(Message::FileActions),
+            },
+            // An event opens in the calendar; its Ctrl+O rows are `<action>\t<index>`
+            ResultCategory::Event => {
+                let (action, index) = result.id.split_once('\t').unwrap_or(("open", &result.id));
+                let action = action.to_string();
+                if let Some(event) = index.parse().ok().and_then(|i: usize| self.agenda.get(i)).cloned() {
+                    self.run_event_action(&action, &event);
+                }
+            }
+            // A past chat prompt goes back in the search bar, to ask again
+            ResultCategory::History => {
+                self.prompt = result.id.clone();
+                self.timeline = None;
+                self.results.clear();
+                self.mode = UIMode::Search;
+                return text_input::move_cursor_to_end(prompt_input_id());
+            }
+            // A command row (e.g. a template) completes the prompt; a whole
+            // command (no trailing space, e.g. `/dnd off`) runs right away
+            ResultCategory::Command => {
+                self.prompt = result.id.clone();
+                self.results.clear();
+                self.mode = UIMode::Search;
+                if !self.prompt.ends_with(' ') {
+                    return self.update(Message::PromptSubmit);
+                }
+                return text_input::move_cursor_to_end(prompt_input_id());
+            }
+            _ => {}
+        }
+        Task::none()
+    }
+
+    /// Network quick answers; the public IP row fills in when fetched
+    fn show_network(&mut self, query: NetQuery) -> Task<Message> {
+        let row = |id: String, title: String, subtitle: String| SearchResult {
+            id,
+            title,
+            subtitle,
+            icon: None,
+            category: ResultCategory::Network,
+        };
+        let mut results = Vec::new();
+
+        if query == NetQuery::Ip {
+            for addr in network::local_addresses() {
+                let addr_text = addr.addr.to_string();
+                results.push(row(addr_text.clone(), addr_text, addr.interface));
+            }
+        }
+        if query == NetQuery::Wifi {
+            results.push(match network::wifi_ssid() {
+                Some(ssid) => row(ssid.clone(), ssid, String::from("Wi-Fi network")),
+                None => row(String::new(), String::from("Not connected to Wi-Fi"), String::new()),
+            });
+        }
+        let fetch_public = matches!(query, NetQuery::Ip | NetQuery::PublicIp);
+        if fetch_public {
+            results.push(row(
+                String::new(),
+                String::from("Looking up public IP..."),
+                format!("{} · cached for 10 min", PUBLIC_IP_LABEL),
+            ));
+        }
+
+        self.results = results;
+        self.selected_index = 0;
+        self.mode = UIMode::Results;
+        if fetch_public {
+            Task::perform(network::public_ip(), Message::PublicIpFetched)
+        } else {
+            Task::none()
+        }
+    }
+
+    /// Start thumbnails for image/video rows in (or just below) the visible
+    /// part of the results list
+    fn load_visible_thumbnails(&mut self) -> Task<Message> {
+        if self.mode != UIMode::Results {
+            return Task::none();
+        }
+        let (offset, height) = self.results_viewport;
+        let first = (offset / RESULT_ROW_HEIGHT) as usize;
+        let count = (height / RESULT_ROW_HEIGHT).ceil() as usize + 2;
+
+        let mut tasks = Vec::new();
+        for result in self.results.iter().skip(first).take(count) {
+            if result.category != ResultCategory::File
+                || MediaKind::from_path(Path::new(&result.id)).is_none()
+                // Marks it recently used when already cached
+                || self.thumbnails.get(&result.id).is_some()
+            {
+                continue;
+            }
+            let path = result.id.clone();
+            self.thumbnails.insert(path.clone(), Thumbnail::Loading, path.len());
+            tasks.push(Task::perform(
+                async move {
+                    let source = path.clone();
+                    let thumb = tokio::task::spawn_blocking(move || {
+                        let png = thumbnails::thumbnail(Path::new(&source))?;
+                        std::fs::read(&png).map_err(|e| format!("Cannot read {}: {}", png.display(), e))
+                    })
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|result| result)
+                    .map(image::Handle::from_bytes);
+                    (path, thumb)
+                },
+                |(path, thumb)| Message::ThumbnailLoaded(path, thumb),
+            ));
+        }
+        Task::batch(tasks)
+    }
+
+    /// Checksum a file in the background (with progress), or text right away
+    fn hash(&mut self, algorithm: Algorithm, target: &str) -> Task<Message> {
+        let path = config::expand_home(target);
+        if !path.is_file() {
+            let digest = checksum::hash_text(algorithm, target);
+            self.show_hash(algorithm, &format!("\"{}\"", target), digest);
+            return Task::none();
+        }
+
+        let source = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let progress = Arc::new(Progress::new(size));
+        let label = format!("🔢 {} of {}", algorithm.name(), source);
+        self.ai_status = job_status(&label, &progress);
+        self.job = Some((label, progress.clone()));
+        self.results.clear();
+        self.ai_response.clear();
+        self.mode = UIMode::Chat;
+
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || checksum::hash_file(algorithm, &path, &progress))
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|result| result)
+            },
+            move |result| Message::HashComputed { algorithm, source: source.clone(), result },
+        )
+    }
+
+    /// Run a text transform on `text` (the latest clipboard entry if empty)
+    /// and show the output as a copyable row
+    fn transform(&mut self, transform: Transform, text: &str) {
+        let (input, source) = if transform.takes_input() && text.is_empty() {
+            match self.services.clipboard().get_history().into_iter().next() {
+                Some(item) => (item.content, " of clipboard"),
+                None => {
+                    self.results.clear();
+                    self.ai_response = "Clipboard history is empty".to_string();
+                    self.mode = UIMode::Chat;
+                    return;
+                }
+            }
+        } else {
+            (text.to_string(), "")
+        };
+
+        let output = match transform.apply(&input) {
+            Ok(output) => output,
+            Err(e) => {
+                self.fail("Transform failed", e, None);
+                return;
+            }
+        };
+        let title: String = output.lines().next().unwrap_or("").chars().take(80).collect();
+        let more = if title == output { "" } else { " · Tab for full text" };
+        self.results = vec![SearchResult {
+            subtitle: format!(
+                "{}{} · {} chars · ↵ to copy{}",
+                transform.name(),
+                source,
+                output.chars().count(),
+                more
+            ),
+            id: output,
+            title,
+            icon: None,
+            category: ResultCategory::Transform,
+        }];
+        self.selected_index = 0;
+        self.mode = UIMode::Results;
+    }
+
+    /// Enter the regex tester (the test text starts as the latest clipboard
+    /// entry) or recompile its pattern
+    fn show_regex(&mut self, pattern: &str) {
+        let compiled = regex_tester::compile(pattern);
+        match &mut self.regex {
+            Some((regex, _)) => *regex = compiled,
+            None => {
+                let seed = self
+                    .services
+                    .clipboard()
+                    .get_history()
+                    .into_iter()
+                    .next()
+                    .map(|item| item.content)
+                    .unwrap_or_default();
+                self.regex = Some((compiled, text_editor::Content::with_text(&seed)));
+            }
+        }
+        self.results.clear();
+        self.detail = None;
+        self.mode = UIMode::Regex;
+    }
+
+    /// Show a digest as a copyable row, comparing it with the clipboard
+    fn show_hash(&mut self, algorithm: Algorithm, source: &str, digest: String) {
+        let clipboard = self
+            .services
+            .clipboard()
+            .get_history()
+            .into_iter()
+            .next()
+            .map(|item| item.content.trim().to_lowercase());
+        let comparison = match clipboard {
+            Some(copied) if copied == digest => " · ✓ matches clipboard",
+            Some(copied) if copied.len() == digest.len() && copied.chars().all(|c| c.is_ascii_hexdigit()) => {
+                " · ✗ differs from clipboard"
+            }
+            _ => "",
+        };
+
+        self.results = vec![SearchResult {
+            id: digest.clone(),
+            title: digest,
+            subtitle: format!("{} of {} · ↵ to copy{}", algorithm.name(), source, comparison),
+            icon: None,
+            category: ResultCategory::Hash,
+        }];
+        self.selected_index = 0;
+        self.ai_status.clear();
+        self.mode = UIMode::Results;
+    }
+
+    /// Extract in the background; Tick shows progress in the status line
+    fn start_extraction(&mut self, archive: PathBuf, dest: PathBuf) -> Task<Message> {
+        let name = archive
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let progress = Arc::new(archives::progress_for(&archive));
+        let label = format!("📦 Extracting {}", name);
+        self.ai_status = job_status(&label, &progress);
+        self.job = Some((label, progress.clone()));
+        self.prompt.clear();
+        self.results.clear();
+        self.ai_response = format!("Extracting {} to {}", name, dest.display());
+        self.mode = UIMode::Chat;
+
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    archives::extract(&archive, &dest, &progress).map(|extracted| {
+                        let mut message = format!("Extracted {} files to {}", extracted.files, dest.display());
+                        if !extracted.skipped.is_empty() {
+                            message.push_str("\n\nSkipped:");
+                            for (name, reason) in &extracted.skipped {
+                                message.push_str(&format!("\n- {} ({})", name, reason));
+                            }
+                        }
+                        message
+                    })
+                })
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|result| result)
+            },
+            Message::ArchiveExtracted,
+        )
+    }
+
+    /// Read the requested controls off the UI thread (ddcutil can take a second)
+    fn show_controls(&mut self, query: ControlQuery) -> Task<Message> {
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || controls::list(query))
+                    .await
+                    .unwrap_or_default()
+            },
+            Message::ControlsListed,
+        )
+    }
+
+    /// Push a control's current level to the system
+    fn apply_control(&self, id: &str) -> Task<Message> {
+        let (Some(target), Some(ControlKind::Level(percent))) =
+            (Target::decode(id), self.controls.get(id).copied())
+        else {
+            return Task::none();
+        };
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || controls::set_level(&target, percent))
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|result| result)
+            },
+            Message::ControlApplied,
+        )
+    }
+
+    /// Template picker: the templates whose name starts with `filter`
+    fn show_templates(&mut self, filter: &str) {
+        let filter = filter.to_lowercase();
+        self.results = self
+            .services
+            .config()
+            .templates
+            .iter()
+            .filter(|(name, _)| name.to_lowercase().starts_with(&filter))
+            .map(|(name, template)| SearchResult {
+                id: format!("/tpl {} ", name),
+                title: name.clone(),
+                subtitle: templates::preview(template).to_string(),
+                icon: None,
+                category: ResultCategory::Command,
+            })
+            .collect();
+        self.selected_index = 0;
+        self.match_query = filter.clone();
+        self.empty = self.results.is_empty().then(|| EmptyState {
+            message: if filter.is_empty() {
+                tr("empty-templates")
+            } else {
+                tr_args("empty-templates-filtered", &[("filter", filter.as_str().into())])
+            },
+            hint: tr("empty-templates-hint"),
+        });
+        self.mode = if self.results.is_empty() { UIMode::Search } else { UIMode::Results };
+    }
+
+    /// Offer the `[flows]` whose names start with `filter`
+    fn show_flows(&mut self, filter: &str) {
+        let filter = filter.to_lowercase();
+        let config = self.services.config();
+        self.results = config
+            .flows
+            .iter()
+            .filter(|(name, _)| name.to_lowercase().starts_with(&filter))
+            .map(|(name, flow)| {
+                let summary = if flow.description.is_empty() {
+                    flow.steps.iter().map(|step| step.describe()).collect::<Vec<_>>().join(" → ")
+                } else {
+                    flow.description.clone()
+                };
+                // Shell steps without full isolation say so up front
+                let warning = flow
+                    .runs_shell()
+                    .then(|| sandbox::warning(flow.sandbox(&config.sandbox), doctor::has_program))
+                    .flatten();
+                SearchResult {
+                    id: format!("/flow {}", name),
+                    title: name.clone(),
+                    subtitle: match warning {
+                        Some(warning) => format!("{} · {}", warning, summary),
+                        None => summary,
+                    },
+                    icon: None,
+                    category: ResultCategory::Command,
+                }
+            })
+            .collect();
+        self.selected_index = 0;
+        self.match_query = filter.clone();
+        self.empty = self.results.is_empty().then(|| EmptyState {
+            message: if filter.is_empty() {
+                tr("empty-flows")
+            } else {
+                tr_args("empty-flows-filtered", &[("filter", filter.as_str().into())])
+            },
+            hint: tr("empty-flows-hint"),
+        });
+        self.mode = if self.results.is_empty() { UIMode::Search } else { UIMode::Results };
+    }
+
+    /// Run a `[flows]` entry in the background, once it's allowed what it
+    /// needs
+    fn run_flow(&mut self, name: String) -> Task<Message> {
+        let config = self.services.config();
+        if !config.flows.contains_key(&name) {
+            self.fail("Unknown flow", format!("No flow named '{}' under [flows] in config.toml", name), None);
+            return Task::none();
+        }
+        let provider = permissions::flow(&name);
+        let missing = permissions::missing(&config, &provider);
+        if !missing.is_empty() {
+            self.ask_permission(&provider, &missing);
+            return Task::none();
+        }
+        self.start_flow(name)
+    }
+
+    fn start_flow(&mut self, name: String) -> Task<Message> {
+        let Some(flow) = self.services.config().flows.get(&name).cloned() else {
+            return Task::none();
+        };
+        self.results.clear();
+        self.loading = true;
+        self.ai_status = format!("Running {}…", name);
+        self.mode = UIMode::Chat;
+        let services = self.services.clone();
+        Task::perform(flows::run(services, name.clone(), flow), move |result| {
+            Message::FlowFinished(name.clone(), result)
+        })
+    }
+
+    /// Ask before `provider` first does what it hasn't been allowed to
+    /// (`missing`): allow for good, just this once (flows) or not at all
+    fn ask_permission(&mut self, provider: &str, missing: &BTreeSet<Capability>) {
+        let label = permissions::label(provider);
+        let row = |answer: &str, title: String, subtitle: &str| SearchResult {
+            id: format!("{}\t{}", answer, provider),
+            title,
+            subtitle: subtitle.to_string(),
+            icon: None,
+            category: ResultCategory::Action,
+        };
+        let mut rows = vec![row(
+            "allow",
+            format!("Allow {} to {}", label, permissions::describe(missing)),
+            "Remembered under [permissions] in config.toml; /permissions revokes it",
+        )];
+        if provider != permissions::HOOKS {
+            rows.push(row("allow-once", "Allow this time".to_string(), "Asks again next time"));
+        }
+        let denied = if provider == permissions::HOOKS { "Hooks stay off until allowed" } else { "The flow doesn't run" };
+        rows.push(row("deny", format!("Don't allow {}", label), denied));
+        self.results = rows;
+        self.dashboard = false;
+        self.empty = None;
+        self.prompt.clear();
+        self.match_query.clear();
+        self.selected_index = 0;
+        self.mode = UIMode::Results;
+    }
+
+    /// Act on the row picked from `ask_permission`
+    fn answer_permission(&mut self, answer: &str, provider: &str) -> Task<Message> {
+        let label = permissions::label(provider);
+        let flow = provider.strip_prefix("flow:").map(String::from);
+        match (answer, flow) {
+            ("allow", flow) => {
+                let config = self.services.config();
+                let mut granted = config.permissions.get(provider).cloned().unwrap_or_default();
+                granted.extend(permissions::needs(&config, provider));
+                if let Err(e) = self.save_permission(provider, granted) {
+                    self.fail("Couldn't save the permission", e, None);
+                    return Task::none();
+                }
+                self.toasts.push(format!("Allowed {} ✓", label), Instant::now());
+                if let Some(name) = flow {
+                    return self.start_flow(name);
+                }
+            }
+            ("allow-once", Some(name)) => return self.start_flow(name),
+            _ => self.toasts.push(format!("{} not allowed", label), Instant::now()),
+        }
+        self.results.clear();
+        self.mode = UIMode::Search;
+        Task::none()
+    }
+
+    /// `/permissions`: one row per provider with grants; Enter revokes them
+    fn show_permissions(&mut self) {
+        let config = self.services.config();
+        self.results = config
+            .permissions
+            .iter()
+            .filter(|(_, granted)| !granted.is_empty())
+            .map(|(provider, granted)| SearchResult {
+                id: format!("revoke\t{}", provider),
+                title: permissions::label(provider),
+                subtitle: format!("May {} · Enter to revoke", permissions::describe(granted)),
+                icon: None,
+                category: ResultCategory::Action,
+            })
+            .collect();
+        if self.results.is_empty() {
+            self.ai_response = "No flow or hook has been allowed anything yet; they ask the first time they need to.".to_string();
+            self.ai_status.clear();
+            self.mode = UIMode::Chat;
+            return;
+        }
+        self.dashboard = false;
+        self.empty = None;
+        self.match_query.clear();
+        self.selected_index = 0;
+        self.mode = UIMode::Results;
+    }
+
+    /// Take back everything `provider` was allowed; it asks again next time
+    fn revoke_permission(&mut self, provider: &str) {
+        let Some(capabilities) = self.services.config().permissions.get(provider).cloned() else {
+            return;
+        };
+        match self.save_permission(provider, BTreeSet::new()) {
+            Ok(()) => {
+                let toast = format!("Revoked {}", permissions::label(provider));
+                self.offer_undo(toast, Inverse::Permission { provider: provider.to_string(), capabilities });
+                let selected = self.selected_index;
+                self.show_permissions();
+                self.selected_index = selected.min(self.results.len().saturating_sub(1));
+            }
+            Err(e) => self.fail("Couldn't revoke the permission", e, None),
+        }
+    }
+
+    /// Save what `provider` is allowed (nothing drops its entry) and apply
+    /// it straight away, so hooks see it too
+    fn save_permission(&self, provider: &str, granted: BTreeSet<Capability>) -> Result<(), String> {
+        Config::update(|config| {
+            if granted.is_empty() {
+                config.permissions.remove(provider);
+            } else {
+                config.permissions.insert(provider.to_string(), granted);
+            }
+        })?;
+        self.services.reload_config();
+        Ok(())
+    }
+
+    /// Toast about a provider held back for lack of a grant, with a button
+    /// to review what it asks for
+    fn remind_permission(&mut self) {
+        if let Some(provider) = self.permission_needed.take() {
+            let text = format!("Permission needed for {}", permissions::label(&provider));
+            let now = Instant::now();
+            self.toasts.push_action(text, "Review", Message::ReviewPermission(provider), undo::UNDO_WINDOW, now);
+        }
+    }
+
+    /// List matching devices and/or contacts. For plain prompts (`strict`)
+    /// nothing changes unless one matches, so chat replies stay visible while
+    /// typing.
+    fn show_devices(&mut self, query: &str, categories: &[ResultCategory], strict: bool) {
+        let mut matches = Vec::new();
+        // Short plain prompts would match nearly every device
+        if !strict || query.trim().len() >= 3 {
+            for category in categories {
+                matches.extend(match category {
+                    ResultCategory::Contact => self.services.search_contacts(query, services::DEFAULT_LIMIT, strict),
+                    _ => self.services.search_devices(query, services::DEFAULT_LIMIT, strict),
+                });
+            }
+        }
+
+        if !matches.is_empty() || !strict {
+            self.mode = if matches.is_empty() { UIMode::Search } else { UIMode::Results };
+            self.empty = (matches.is_empty() && !strict).then(|| no_matches(categories[0], query));
+            self.results = matches;
+            self.match_query = query.to_string();
+            self.selected_index = 0;
+        } else if self.results.iter().any(|r| categories.contains(&r.category)) {
+            self.results.clear();
+            self.mode = UIMode::Search;
+        }
+    }
+
+    /// Repo search (the first one walks the project dirs, in the background)
+    fn search_repos(&mut self, query: &str) -> Task<Message> {
+        self.search_in_background(query, &[(ResultCategory::Git, |s, query| {
+            s.search_repos(query, services::DEFAULT_LIMIT)
+        })])
+    }
+
+    /// A repo lists its actions; an action runs and reports back
+    fn run_repo_result(&mut self, id: &str) {
+        if !id.contains('\t') {
+            match self.services.repo_actions(std::path::Path::new(id)) {
+                Ok(actions) => {
+                    self.match_query.clear();
+                    self.open_menu(actions);
+                }
+                Err(e) => self.fail("Couldn't read repository", e, None),
+            }
+            return;
+        }
+
+        let outcome = self.services.run_repo_action(id);
+        self.prompt.clear();
+        self.show_outcome("Repository action failed", outcome);
+    }
+
+    /// `/memory [query]`: fetch stored memories (matching `query`, if any)
+    fn list_memories(&mut self, query: String) -> Task<Message> {
+        self.loading = true;
+        self.ai_status = "🧠 Loading memories...".to_string();
+        self.ai_response.clear();
+        self.results.clear();
+        self.mode = UIMode::Chat;
+        let backend = self.backend.clone();
+        Task::perform(
+            async move {
+                let query = (!query.is_empty()).then_some(query);
+                backend.list_memories(query.as_deref()).await
+            },
+            Message::MemoriesListed,
+        )
+    }
+
+    /// List the fetched memories as results
+    fn show_memories(&mut self) {
+        self.action_menu = None;
+        self.dashboard = false;
+        self.results = self
+            .memories
+            .iter()
+            .map(|memory| {
+                let preview = memory.content.lines().find(|l| !l.trim().is_empty()).unwrap_or_default();
+                let title = if memory.title.is_empty() { preview } else { memory.title.as_str() };
+                SearchResult {
+                    id: memory.id.clone(),
+                    title: title.chars().take(80).collect(),
+                    subtitle: preview.chars().take(120).collect(),
+                    icon: None,
+                    category: ResultCategory::Memory,
+                }
+            })
+            .collect();
+        self.match_query.clear();
+        self.selected_index = 0;
+        self.empty = self.results.is_empty().then(|| EmptyState {
+            message: tr("empty-memories"),
+            hint: tr("empty-memories-hint"),
+        });
+        self.mode = if self.results.is_empty() { UIMode::Search } else { UIMode::Results };
+    }
+
+    /// List apps with whether search shows them (`/apps`)
+    fn show_app_visibility(&mut self, query: &str) {
+        self.action_menu = None;
+        self.dashboard = false;
+        self.results = self.services.app_visibility(query);
+        self.match_query.clear();
+        self.selected_index = 0;
+        self.empty = self.results.is_empty().then(|| EmptyState {
+            message: tr_args("empty-apps", &[("query", query.into())]),
+            hint: tr("empty-apps-hint"),
+        });
+        self.mode = if self.results.is_empty() { UIMode::Search } else { UIMode::Results };
+    }
+
+    /// Hide an app from search or show it again, saving to config.toml
+    fn toggle_hidden_app(&mut self, app_id: &str) {
+        let (hidden, all_hidden) =
+            match Config::update(|config| (config.apps.toggle_hidden(app_id), config.apps.hidden.clone())) {
+                Ok(toggled) => toggled,
+                Err(e) => {
+                    self.fail("Couldn't save hidden apps", e, None);
+                    return;
+                }
+            };
+        self.services.set_hidden_apps(&all_hidden);
+        let Some(row) = self.results.get_mut(self.selected_index) else {
+            return;
+        };
+        row.subtitle = services::visibility_hint(hidden, app_id);
+        let toast = if hidden { format!("Hid {} from search", row.title) } else { format!("{} shows in search again", row.title) };
+        self.toasts.push(toast, Instant::now());
+    }
+
+    /// Copy to the clipboard, confirming with a toast
+    fn copy_to_clipboard(&mut self, content: &str, toast: String) {
+        match clipboard::copy(content) {
+            Ok(_) => self.toasts.push(format!("{} ✓", toast), Instant::now()),
+            Err(e) => self.fail("Couldn't copy", e, None),
+        }
+    }
+
+    /// List the favorites, or nothing if there are none
+    fn show_favorites(&mut self) {
+        self.results = self.favorite_rows();
+        self.dashboard = false;
+        self.match_query.clear();
+        self.selected_index = 0;
+        self.mode = if self.results.is_empty() { UIMode::Search } else { UIMode::Results };
+    }
+
+    /// Empty-query dashboard: favorites, frequent apps, recent files, the
+    /// latest clipboard entries and pending timers
+    fn show_dashboard(&mut self) {
+        self.browsing = None;
+        self.timeline = None;
+        let now = unix_now();
+        let starred = |kind: FavoriteKind, id: &str| self.favorites.iter().any(|f| f.kind == kind && f.target == id);
+        let apps: Vec<&str> = self
+            .usage
+            .frequent(usage::Kind::App, now)
+            .into_iter()
+            .map(|entry| entry.id.as_str())
+            .filter(|id| !starred(FavoriteKind::App, id))
+            .collect();
+        let files = self
+            .usage
+            .recent(usage::Kind::File)
+            .into_iter()
+            .filter(|entry| !starred(FavoriteKind::File, &entry.id) && Path::new(&entry.id).exists())
+            .take(DASHBOARD_ROWS)
+            .map(|entry| SearchResult {
+                id: entry.id.clone(),
+                title: entry.title.clone(),
+                subtitle: entry.id.clone(),
+                icon: None,
+                category: ResultCategory::File,
+            });
+
+        let mut results = self.favorite_rows();
+        results.extend(self.saved_rows());
+        results.extend(self.services.app_results(&apps).into_iter().take(DASHBOARD_ROWS));
+        results.extend(files);
+        results.extend(self.clipboard_rows(DASHBOARD_CLIPS, None, ""));
+        results.extend(self.timer_rows(Instant::now()));
+        self.results = results;
+        self.dashboard = true;
+        self.match_query.clear();
+        self.selected_index = 0;
+        self.mode = if self.results.is_empty() { UIMode::Search } else { UIMode::Results };
+    }
+
+    /// Whether the results are the dashboard (and nothing has replaced it)
+    fn is_dashboard(&self) -> bool {
+        self.dashboard && self.prompt.is_empty() && self.action_menu.is_none() && !self.history_search
+    }
+
+    /// Re-list favorites after starring or reordering, on the dashboard if
+    /// that's where they were
+    fn refresh_favorites(&mut self) {
+        if self.is_dashboard() {
+            self.show_dashboard();
+        } else {
+            self.show_favorites();
+        }
+    }
+
+    fn favorite_rows(&self) -> Vec<SearchResult> {
+        let config = self.services.config();
+        let quicklinks = &config.quicklinks;
+        self.favorites
+            .iter()
+            .enumerate()
+            .map(|(index, favorite)| SearchResult {
+                id: index.to_string(),
+                title: favorite.title.clone(),
+                subtitle: match favorite.kind {
+                    FavoriteKind::App => "Application".to_string(),
+                    FavoriteKind::File => favorite.target.clone(),
+                    FavoriteKind::Quicklink => quicklinks.get(&favorite.target).cloned().unwrap_or_default(),
+                },
+                icon: match favorite.kind {
+                    FavoriteKind::App => self.services.app_results(&[&favorite.target]).pop().and_then(|app| app.icon),
+                    FavoriteKind::File => None,
+                    FavoriteKind::Quicklink => config.icons.quicklinks.get(&favorite.target).cloned(),
+                },
+                category: ResultCategory::Favorite,
+            })
+            .collect()
+    }
+
+    fn saved_rows(&self) -> Vec<SearchResult> {
+        self.saved_searches
+            .iter()
+            .map(|(name, search)| SearchResult {
+                id: name.clone(),
+                title: name.clone(),
+                subtitle: search.prompt(),
+                icon: None,
+                category: ResultCategory::Saved,
+            })
+            .collect()
+    }
+
+    /// /saved: run, list, add or remove saved searches
+    fn manage_saved(&mut self, command: SavedCommand) -> Task<Message> {
+        let message = match command {
+            SavedCommand::Run { name } => return self.run_saved(&name),
+            SavedCommand::List if self.saved_searches.is_empty() => {
+                "No saved searches. Add one with /saved add <name> <search>, e.g. /saved add invoices /file kind:pdf invoice"
+                    .to_string()
+            }
+            SavedCommand::List => {
+                self.results = self.saved_rows();
+                self.match_query.clear();
+                self.selected_index = 0;
+                self.mode = UIMode::Results;
+                return Task::none();
+            }
+            SavedCommand::Add { name, search } => {
+                let prompt = search.prompt();
+                self.saved_searches.insert(name.clone(), search);
+                match self.save_saved_searches() {
+                    Ok(()) => format!("Saved search added: {} → {}", name, prompt),
+                    Err(e) => format!("Error: {}", e),
+                }
+            }
+            SavedCommand::Remove { name } => match self.saved_searches.remove(&name) {
+                None => format!("No saved search named '{}'", name),
+                Some(search) => match self.save_saved_searches() {
+                    Ok(()) => {
+                        let toast = format!("Saved search {} removed", name);
+                        self.offer_undo(toast, Inverse::SavedSearch { name: name.clone(), search });
+                        format!("Saved search removed: {}", name)
+                    }
+                    Err(e) => format!("Error: {}", e),
+                },
+            },
+        };
+        self.ai_response = message;
+        self.ai_status.clear();
+        self.mode = UIMode::Chat;
+        Task::none()
+    }
+
+    /// Put a saved search's prompt in the search bar and run it
+    fn run_saved(&mut self, name: &str) -> Task<Message> {
+        let Some(search) = self.saved_searches.get(name).cloned() else {
+            self.ai_response = format!("No saved search named '{}' (see /saved list)", name);
+            self.ai_status.clear();
+            self.mode = UIMode::Chat;
+            return Task::none();
+        };
+        self.prompt = search.prompt();
+        self.dashboard = false;
+        self.suggestions.clear();
+        let query = search.query.as_str();
+        let task = match search.category {
+            SearchCategory::All => self.live_search(query),
+            SearchCategory::Apps => {
+                self.search(query);
+                Task::none()
+            }
+            SearchCategory::Files => self.search_files(query),
+            SearchCategory::Repos => self.search_repos(query),
+            SearchCategory::Clipboard => {
+                self.filter_clipboard(query);
+                Task::none()
+            }
+            SearchCategory::Contacts => {
+                self.show_devices(query, &[ResultCategory::Contact], false);
+                Task::none()
+            }
+        };
+        Task::batch([task, text_input::move_cursor_to_end(prompt_input_id())])
+    }
+
+    /// Bookmarks whose folder name starts with a short `query`, or all of
+    /// them for an empty one
+    fn bookmark_rows(&self, query: &str) -> Vec<SearchResult> {
+        let query = query.trim().to_lowercase();
+        if query.chars().count() > BOOKMARK_QUERY_CHARS {
+            return Vec::new();
+        }
+        self.bookmarks
+            .iter()
+            .filter(|bookmark| query.is_empty() || fuzzy::score(bookmark.name(), &query) >= fuzzy::PREFIX)
+            .map(|bookmark| SearchResult {
+                id: bookmark.path.clone(),
+                title: bookmark.name().to_string(),
+                subtitle: match &bookmark.shortcut {
+                    Some(shortcut) => format!("{}  ·  ,{}", bookmark.path, shortcut),
+                    None => bookmark.path.clone(),
+                },
+                icon: None,
+                category: ResultCategory::Bookmark,
+            })
+            .collect()
+    }
+
+    /// /bm: list, add or remove folder bookmarks
+    fn manage_bookmark(&mut self, command: BookmarkCommand) {
+        let message = match command {
+            BookmarkCommand::List if self.bookmarks.is_empty() => {
+                "No bookmarks. Add one with /bm add <folder> [,shortcut], e.g. /bm add ~/work/src ,w".to_string()
+            }
+            BookmarkCommand::List => {
+                self.results = self.bookmark_rows("");
+                self.match_query.clear();
+                self.selected_index = 0;
+                self.mode = UIMode::Results;
+                return;
+            }
+            BookmarkCommand::Add { path, .. } if !config::expand_home(&path).is_dir() => {
+                format!("Not a folder: {}", path)
+            }
+            BookmarkCommand::Add { path, shortcut } => {
+                // A shortcut moves to the new bookmark; re-adding a folder updates it
+                if let Some(shortcut) = &shortcut {
+                    for bookmark in &mut self.bookmarks {
+                        if bookmark.shortcut.as_ref() == Some(shortcut) {
+                            bookmark.shortcut = None;
+                        }
+                    }
+                }
+                let added = match &shortcut {
+                    Some(shortcut) => format!("Bookmarked {} (type ,{} to browse it)", path, shortcut),
+                    None => format!("Bookmarked {}", path),
+                };
+                match self.bookmarks.iter_mut().find(|bookmark| bookmark.is(&path)) {
+                    Some(bookmark) => bookmark.shortcut = shortcut,
+                    None => self.bookmarks.push(Bookmark { path, shortcut }),
+                }
+                match self.save_bookmarks() {
+                    Ok(()) => added,
+                    Err(e) => format!("Error: {}", e),
+                }
+            }
+            BookmarkCommand::Remove { target } => match self.bookmarks.iter().position(|b| b.is(&target)) {
+                None => format!("No bookmark for '{}' (see /bm list)", target),
+                Some(index) => {
+                    let bookmark = self.bookmarks.remove(index);
+                    match self.save_bookmarks() {
+                        Ok(()) => {
+                            let message = format!("Bookmark removed: {}", bookmark.path);
+                            let toast = format!("Bookmark {} removed", bookmark.name());
+                            self.offer_undo(toast, Inverse::Bookmark { index, bookmark });
+                            message
+                        }
+                        Err(e) => format!("Error: {}", e),
+                    }
+                }
+            },
+        };
+        self.ai_response = message;
+        self.ai_status.clear();
+        self.mode = UIMode::Chat;
+    }
+
+    fn save_bookmarks(&self) -> Result<(), String> {
+        Config::update(|config| config.bookmarks = self.bookmarks.clone())
+    }
+
+    fn save_saved_searches(&self) -> Result<(), String> {
+        Config::update(|config| config.saved_searches = self.saved_searches.clone())
+    }
+
+    /// `/audit [since]`: the audit log, newest first under a header for each
+    /// day; everything since `since`, or the latest `AUDIT_SHOWN` entries
+    fn show_audit(&mut self, since: &str) {
+        let start = if since.trim().is_empty() { Ok(0) } else { audit::parse_since(since) };
+        self.ai_response = match start.and_then(audit::since) {
+            Err(e) => format!("Error: {}", e),
+            Ok(entries) if entries.is_empty() && since.trim().is_empty() => "Nothing in the audit log yet".to_string(),
+            Ok(entries) if entries.is_empty() => format!("Nothing in the audit log since {}", since.trim()),
+            Ok(entries) => {
+                let shown = if since.trim().is_empty() { AUDIT_SHOWN } else { entries.len() };
+                let mut lines = Vec::new();
+                let mut day = String::new();
+                for entry in entries.iter().rev().take(shown) {
+                    let label = timeline::day_label(entry.time);
+                    if label != day {
+                        lines.push(format!("{}:", label));
+                        day = label;
+                    }
+                    lines.push(format!("{}  {}", timeline::clock(entry.time), audit::summary(entry)));
+                }
+                if entries.len() > shown {
+                    lines.push(format!("… {} older in {}", entries.len() - shown, audit::path().display()));
+                }
+                lines.join("\n")
+            }
+        };
+        self.ai_status.clear();
+        self.mode = UIMode::Chat;
+    }
+
+    /// `/timeline`: launched apps, opened files, clipboard copies and
+    /// answered chats, newest first. Rows keep their usual category, so Enter
+    /// launches, opens or copies again (a chat goes back in the search bar).
+    fn show_timeline(&mut self) {
+        let mut rows: Vec<(u64, SearchResult)> = Vec::new();
+        for entry in self.usage.entries() {
+            let when = timeline::clock(entry.last_used);
+            let row = match entry.kind {
+                // Uninstalled apps and deleted files are left out
+                usage::Kind::App => match self.services.app_results(&[entry.id.as_str()]).pop() {
+                    Some(app) => SearchResult { subtitle: format!("{} · Launched", when), ..app },
+                    None => continue,
+                },
+                usage::Kind::File if Path::new(&entry.id).exists() => SearchResult {
+                    id: entry.id.clone(),
+                    title: entry.title.clone(),
+                    subtitle: format!("{} · Opened {}", when, entry.id),
+                    icon: None,
+                    category: ResultCategory::File,
+                },
+                usage::Kind::File => continue,
+                usage::Kind::Chat => SearchResult {
+                    id: entry.id.clone(),
+                    title: entry.title.lines().next().unwrap_or("").chars().take(80).collect(),
+                    subtitle: format!("{} · Asked AI", when),
+                    icon: None,
+                    category: ResultCategory::History,
+                },
+            };
+            rows.push((entry.last_used, row));
+        }
+        for item in self.services.clipboard().get_history() {
+            rows.push((item.timestamp, SearchResult {
+                id: item.id.to_string(),
+                title: item.content.lines().next().unwrap_or("").chars().take(80).collect(),
+                // The badge before the first " · " is drawn as a pill
+                subtitle: format!("{} · {} · Copied", item.badge(), timeline::clock(item.timestamp)),
+                icon: None,
+                category: ResultCategory::Clipboard,
+            }));
+        }
+        rows.sort_by(|(a, _), (b, _)| b.cmp(a));
+        rows.truncate(TIMELINE_ROWS);
+
+        self.timeline = Some(rows.iter().map(|(time, _)| timeline::day_label(*time)).collect());
+        self.results = rows.into_iter().map(|(_, row)| row).collect();
+        self.dashboard = false;
+        self.hero = None;
+        self.match_query.clear();
+        self.selected_index = 0;
+        self.empty = self.results.is_empty().then(|| EmptyState {
+            message: tr("empty-timeline"),
+            hint: tr("empty-timeline-hint"),
+        });
+        self.mode = if self.results.is_empty() { UIMode::Search } else { UIMode::Results };
+    }
+
+    /// The day header to show above row `index` of the timeline, if it
+    /// starts a day
+    fn timeline_day(&self, index: usize) -> Option<&str> {
+        // Rows removed since (Ctrl+Delete) leave the days unmatched
+        let days = self.timeline.as_ref().filter(|days| days.len() == self.results.len())?;
+        let day = days.get(index)?;
+        (index == 0 || days[index - 1] != *day).then_some(day.as_str())
+    }
+
+    /// Upcoming events from the calendars, read in the background (`days`
+    /// defaults to `[calendar] days`)
+    fn show_agenda(&mut self, days: Option<u32>) -> Task<Message> {
+        let config = &self.services.config().calendar;
+        let days = days.unwrap_or(config.days);
+        let files = calendar::sources(config.resolved_files());
+        let prompt = self.prompt.clone();
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || calendar::agenda(&files, days))
+                    .await
+                    .unwrap_or_default()
+            },
+            move |events| Message::AgendaListed(prompt.clone(), events),
+        )
+    }
+
+    /// Ctrl+O rows for an event: copy its meeting link, open it in the calendar
+    fn event_actions(&self, id: &str) -> Vec<SearchResult> {
+        let Some(event) = id.parse().ok().and_then(|i: usize| self.agenda.get(i)) else {
+            return Vec::new();
+        };
+        let row = |action: &str, title: &str, subtitle: String| SearchResult {
+            id: format!("{}\t{}", action, id),
+            title: title.to_string(),
+            subtitle,
+            icon: None,
+            category: ResultCategory::Event,
+        };
+        let mut rows = Vec::new();
+        if let Some(link) = &event.meeting_link {
+            rows.push(row("copy-link", "Copy meeting link", link.clone()));
+        }
+        rows.push(row("open", "Open in calendar", event.when()));
+        rows
+    }
+
+    fn run_event_action(&mut self, action: &str, event: &calendar::Event) {
+        match action {
+            "copy-link" => {
+                if let Some(link) = &event.meeting_link {
+                    self.copy_to_clipboard(link, "Copied meeting link".to_string());
+                }
+            }
+            _ => {
+                if let Err(e) = self.services.open_calendar(&event.day()) {
+                    self.fail("Couldn't open the calendar", e, None);
+                }
+            }
+        }
+    }
+
+    /// `/dnd`: list the state and ways to change it, or switch Do Not Disturb
+    fn run_dnd(&mut self, command: DndCommand) {
+        let now = Instant::now();
+        let outcome = match command {
+            DndCommand::Status => {
+                self.show_dnd(now);
+                return;
+            }
+            DndCommand::On => dnd::set(true).map(|()| "Do Not Disturb on".to_string()),
+            DndCommand::Off => dnd::set(false).map(|()| {
+                self.end_focus();
+                "Do Not Disturb off".to_string()
+            }),
+            DndCommand::Focus(duration) => dnd::set(true).map(|()| {
+                self.end_focus();
+                self.focus_timer = Some(self.timers.start(duration, "Focus", now));
+                format!("Do Not Disturb for {}", timers::format_remaining(duration))
+            }),
+        };
+        match outcome {
+            Ok(toast) => {
+                self.toasts.push(format!("{} ✓", toast), now);
+                self.show_dashboard();
+            }
+            Err(e) => self.fail("Do Not Disturb failed", e, None),
+        }
+    }
+
+    /// Do Not Disturb's state, with rows to flip it or start a focus session
+    fn show_dnd(&mut self, now: Instant) {
+        let Some(enabled) = dnd::enabled() else {
+            self.fail("Do Not Disturb unavailable", "It can only be switched on GNOME and KDE Plasma", None);
+            return;
+        };
+        let focus = self
+            .focus_timer
+            .and_then(|id| self.timers.pending().iter().find(|timer| timer.id == id))
+            .map(|timer| format!(" · focus session ends in {}", timers::format_remaining(timer.due.saturating_duration_since(now))));
+        let row = |id: &str, title: &str, subtitle: String| SearchResult {
+            id: id.to_string(),
+            title: title.to_string(),
+            subtitle,
+            icon: None,
+            category: ResultCategory::Command,
+        };
+        let state = if enabled {
+            row("/dnd off", "Do Not Disturb is on", format!("↵ to turn it off{}", focus.unwrap_or_default()))
+        } else {
+            row("/dnd on", "Do Not Disturb is off", "↵ to silence notifications".to_string())
+        };
+        self.results = vec![
+            state,
+            row("/dnd 25m", "Focus for 25 minutes", "Do Not Disturb until the timer is up".to_string()),
+            row("/dnd 50m", "Focus for 50 minutes", "Do Not Disturb until the timer is up".to_string()),
+        ];
+        self.dashboard = false;
+        self.match_query.clear();
+        self.selected_index = 0;
+        self.mode = UIMode::Results;
+    }
+
+    /// Cancel the focus session's timer, if one is running
+    fn end_focus(&mut self) {
+        if let Some(id) = self.focus_timer.take() {
+            self.timers.cancel(id);
+        }
+    }
+
+    /// Pending timers as rows, with the time left at `now`
+    fn timer_rows(&self, now: Instant) -> Vec<SearchResult> {
+        self.timers
+            .pending()
+            .iter()
+            .map(|timer| SearchResult {
+                id: timer.id.to_string(),
+                title: timer.title().to_string(),
+                subtitle: format!("{} left · Enter to cancel", timers::format_remaining(timer.due.saturating_duration_since(now))),
+                icon: None,
+                category: ResultCategory::Timer,
+            })
+            .collect()
+    }
+
+    /// Announce timers that are up, and keep the dashboard's countdowns current
+    fn check_timers(&mut self) {
+        let now = Instant::now();
+        for timer in self.timers.take_due(now) {
+            tracing::info!("Timer up: {}", timer.title());
+            // Notifications come back before the one saying so
+            if self.focus_timer == Some(timer.id) {
+                self.focus_timer = None;
+                if let Err(e) = dnd::set(false) {
+                    tracing::warn!("Couldn't end Do Not Disturb: {}", e);
+                }
+            }
+            timers::notify(&timer);
+            self.toasts.push(format!("⏰ {} is up", timer.title()), now);
+        }
+        if self.is_dashboard() && self.results.iter().any(|r| r.category == ResultCategory::Timer) {
+            // Timer rows come last, so the rows above keep their places
+            self.results.retain(|r| r.category != ResultCategory::Timer);
+            self.results.extend(self.timer_rows(now));
+            self.selected_index = self.selected_index.min(self.results.len().saturating_sub(1));
+            if self.results.is_empty() {
+                self.mode = UIMode::Search;
+            }
+        }
+    }
+
+    /// Switch to the app's open window, or launch it if it has none (or
+    /// `new_instance` asks for another)
+    fn launch_app(&mut self, id: &str, new_instance: bool) -> Result<(), String> {
+        let window = if new_instance { None } else { self.services.open_window(id) };
+        let title = self.services.app(id).map(|app| app.name).unwrap_or_else(|| id.to_string());
+        let focused = match window {
+            Some(window) => windows::focus(&window).map_err(|e| tracing::warn!("{}; launching instead", e)).is_ok(),
+            None => false,
+        };
+        if !focused {
+            let child = self.services.spawn_app(id)?;
+            let now = Instant::now();
+            self.launches.watch(&title, child, now);
+            self.toasts.push(format!("Launching {}…", title), now);
+        }
+        self.record_usage(ResultCategory::App, id, &title);
+        Ok(())
+    }
+
+    /// Report apps that exited with an error right after launching
+    fn check_launches(&mut self) {
+        let failures = self.launches.poll(Instant::now());
+        if !failures.is_empty() {
+            tracing::warn!("{}", failures.join("; "));
+            self.fail("Launch failed", failures.join("\n"), None);
+        }
+    }
+
+    /// Count a launch for the dashboard's frequent apps and recent files
+    fn record_usage(&mut self, category: ResultCategory, id: &str, title: &str) {
+        let kind = match category {
+            ResultCategory::App => usage::Kind::App,
+            ResultCategory::File => usage::Kind::File,
+            ResultCategory::History => usage::Kind::Chat,
+            _ => return,
+        };
+        self.usage.record(kind, id, title, unix_now());
+        if let Err(e) = self.usage.save() {
+            tracing::warn!("Failed to save usage: {}", e);
+        }
+    }
+
+    /// Index in `favorites` of the selected row, if it's a favorite
+    fn selected_favorite(&self) -> Option<usize> {
+        self.results
+            .get(self.selected_index)
+            .filter(|result| result.category == ResultCategory::Favorite)
+            .and_then(|result| result.id.parse::<usize>().ok())
+            .filter(|index| *index < self.favorites.len())
+    }
+
+    /// Star or unstar `favorite`, saving to config.toml
+    fn star(&mut self, favorite: Favorite) {
+        let title = favorite.title.clone();
+        let saved = Config::update(|config| {
+            config.favorites = self.favorites.clone();
+            (config.toggle_favorite(favorite), config.favorites.clone())
+        });
+        let starred = match saved {
+            Ok((starred, favorites)) => {
+                self.favorites = favorites;
+                starred
+            }
+            Err(e) => {
+                self.fail("Couldn't save favorites", e, None);
+                return;
+            }
+        };
+        let toast = if starred { format!("Starred {} ★", title) } else { format!("Unstarred {}", title) };
+        self.toasts.push(toast, Instant::now());
+    }
+
+    /// Open a favorite the way its own result would be
+    fn open_favorite(&mut self, favorite: Favorite) -> Task<Message> {
+        let outcome = match favorite.kind {
+            FavoriteKind::App => self.launch_app(&favorite.target, false),
+            FavoriteKind::File => self
+                .services
+                .launch(&favorite.target, ResultCategory::File)
+                .map(|()| self.record_usage(ResultCategory::File, &favorite.target, &favorite.title)),
+            FavoriteKind::Quicklink => match self.services.config().quicklinks.get(&favorite.target) {
+                // Takes a query: start typing it
+                Some(template) if template.contains("{query}") => {
+                    self.prompt = format!("{} ", favorite.target);
+                    self.results.clear();
+                    self.mode = UIMode::Search;
+                    return text_input::move_cursor_to_end(prompt_input_id());
+                }
+                Some(template) => self.services.open_url(template),
+                None => Err(format!("No quicklink named '{}'", favorite.target)),
+            },
+        };
+        if let Err(e) = outcome {
+            self.fail("Couldn't open favorite", e, None);
+        }
+        Task::none()
+    }
+
+    /// Toast offering to undo an action for `undo::UNDO_WINDOW`
+    fn offer_undo(&mut self, toast: String, inverse: Inverse) {
+        let now = Instant::now();
+        let id = self.undo.push(inverse, now);
+        self.toasts.push_action(toast, "Undo", Message::Undo(id), undo::UNDO_WINDOW, now);
+    }
+
+    /// Show the actions of the selected result in place of the results
+    fn open_menu(&mut self, actions: Vec<SearchResult>) {
+        let parent = std::mem::replace(&mut self.results, actions);
+        self.action_menu = Some((parent, self.selected_index));
+        self.selected_index = 0;
+        self.mode = UIMode::Results;
+    }
+
+    /// A memory lists its actions; copy and delete run
+    fn run_memory_result(&mut self, id: &str) -> Task<Message> {
+        let Some((action, id)) = id.split_once('\t') else {
+            let title = self.results.get(self.selected_index).map(|r| r.title.clone()).unwrap_or_default();
+            let actions = [("copy", "Copy to clipboard"), ("delete", "Delete memory")]
+                .into_iter()
+                .map(|(action, label)| SearchResult {
+                    id: format!("{}\t{}", action, id),
+                    title: label.to_string(),
+                    subtitle: title.clone(),
+                    icon: None,
+                    category: ResultCategory::Memory,
+                })
+                .collect();
+            self.open_menu(actions);
+            return Task::none();
+        };
+
+        match action {
+            "copy" => {
+                match self.memories.iter().find(|m| m.id == id).map(|m| m.content.clone()) {
+                    Some(content) => {
+                        self.copy_to_clipboard(&content, "Copied memory".to_string());
+                        // Back to the memories
+                        if let Some((results, selected)) = self.action_menu.take() {
+                            self.results = results;
+                            self.selected_index = selected;
+                        }
+                    }
+                    None => self.fail("Couldn't copy memory", format!("Memory {} is no longer listed", id), None),
+                }
+                Task::none()
+            }
+            _ => {
+                self.loading = true;
+                self.ai_status = "🗑 Deleting memory...".to_string();
+                let backend = self.backend.clone();
+                let id = id.to_string();
+                Task::perform(
+                    async move {
+                        let result = backend.delete_memory(&id).await;
+                        (id, result)
+                    },
+                    |(id, result)| Message::MemoryDeleted(id, result),
+                )
+            }
+        }
+    }
+
+    /// Start recording for voice input, or offer to download the speech
+    /// model first
+    fn start_voice(&mut self) {
+        self.failure = None;
+        if voice::needs_model() {
+            let toast = format!("Voice input needs the speech model ({} MB)", voice::MODEL_MB);
+            self.toasts.push_action(toast, "Download", Message::DownloadVoiceModel, CONFIRM_WINDOW, Instant::now());
+            return;
+        }
+        match voice::record() {
+            Ok(recording) => {
+                self.voice_levels = vec![0.0; WAVEFORM_BARS];
+                self.voice = Some(recording);
+            }
+            Err(e) => self.fail("Voice input unavailable", e, None),
+        }
+    }
+
+    /// Stop recording and transcribe in the background
+    fn stop_voice(&mut self) -> Task<Message> {
+        let Some(recording) = self.voice.take() else {
+            return Task::none();
+        };
+        self.voice_levels.clear();
+        match recording.stop() {
+            Ok(Some(audio)) => {
+                self.transcribing = true;
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || voice::transcribe(&audio))
+                            .await
+                            .map_err(|e| e.to_string())
+                            .and_then(|result| result)
+                    },
+                    Message::VoiceTranscribed,
+                )
+            }
+            // Too short to be speech
+            Ok(None) => Task::none(),
+            Err(e) => {
+                self.fail("Voice input failed", e, None);
+                Task::none()
+            }
+        }
+    }
+
+    /// `/launch`: launch the top app match without showing results
+    fn launch_best_match(&mut self, query: &str) {
+        let outcome = match self.services.search_apps(query, 1).into_iter().next() {
+            Some(app) => self.launch_app(&app.id, false),
+            None => Err(format!("No application matches '{}'", query)),
+        };
+        match outcome {
+            Ok(()) => {
+                self.prompt.clear();
+                self.results.clear();
+                self.mode = UIMode::Search;
+            }
+            Err(e) => self.fail("Launch failed", e, None),
+        }
+    }
+
+    /// Open config.toml to edit an app's `[apps.overrides]` entry, adding an
+    /// empty one to fill in if there's none yet
+    fn edit_launch_options(&mut self, app_id: &str) {
+        let added = Config::update(|config| {
+            config.apps.overrides.entry(app_id.to_string()).or_insert_with(LaunchOverride::default);
+        });
+        let path = Config::path().to_string_lossy().to_string();
+        let outcome = added.and_then(|()| self.services.launch(&path, ResultCategory::File)).map(|()| {
+            format!(
+                "Editing launch options for {} under [apps.overrides.\"{}\"] in {}\n\n\
+                 args = [\"--flag\"]              extra arguments\n\
+                 env = {{ NAME = \"value\" }}     environment variables\n\
+                 working_dir = \"~/projects\"     directory to start in\n\
+                 backend = \"x11\" | \"wayland\"   force a display backend\n\n\
+                 Changes apply the next time it's launched.",
+                app_id, app_id, path
+            )
+        });
+        self.prompt.clear();
+        self.show_outcome("Couldn't open launch options", outcome);
+    }
+
+    /// `/cache stats|clear`: budgets and usage, or drop what can be rebuilt
+    fn manage_cache(&mut self, command: CacheCommand) -> String {
+        match command {
+            CacheCommand::Stats => {
+                let mut lines = vec![format!("Thumbnails: {}", self.thumbnails.stats().summary())];
+                lines.extend(
+                    self.services
+                        .cache_stats()
+                        .into_iter()
+                        .map(|(name, stats)| format!("{}: {}", name, stats.summary())),
+                );
+                lines.push(format!(
+                    "Thumbnails on disk: {}",
+                    cache::format_mb(thumbnails::disk_usage() as usize)
+                ));
+                if let Some(rss) = cache::resident_bytes() {
+                    lines.push(format!("Process memory (RSS): {}", cache::format_mb(rss)));
+                }
+                lines.join("\n")
+            }
+            CacheCommand::Clear => {
+                self.thumbnails.clear();
+                self.services.clear_caches();
+                match thumbnails::clear_disk_cache() {
+                    Ok(freed) => format!(
+                        "Cleared icon, thumbnail and response caches ({} on disk)",
+                        cache::format_mb(freed as usize)
+                    ),
+                    Err(e) => format!("Cleared in-memory caches; {}", e),
+                }
+            }
+        }
+    }
+
+    /// Apply an /alias subcommand, persisting changes to config.toml
+    fn manage_alias(&mut self, command: AliasCommand) -> String {
+        match command {
+            AliasCommand::List => {
+                if self.aliases.is_empty() {
+                    return "No aliases defined. Add one with /alias add <name> <expansion>".to_string();
+                }
+                let lines: Vec<String> = self
+                    .aliases
+                    .iter()
+                    .map(|(name, expansion)| format!("{} → {}", name, expansion))
+                    .collect();
+                format!("Aliases:\n{}", lines.join("\n"))
+            }
+            AliasCommand::Add { name, expansion } => {
+                if let Err(e) = commands::validate_alias_name(&name) {
+                    return format!("Error: {}", e);
+                }
+                self.aliases.insert(name.clone(), expansion.clone());
+                match self.save_aliases() {
+                    Ok(()) => format!("Alias added: {} → {}", name, expansion),
+                    Err(e) => format!("Error: {}", e),
+                }
+            }
+            AliasCommand::Remove { name } => {
+                let Some(expansion) = self.aliases.remove(&name) else {
+                    return format!("No alias named '{}'", name);
+                };
+                match self.save_aliases() {
+                    Ok(()) => {
+                        let toast = format!("Alias {} removed", name);
+                        self.offer_undo(toast, Inverse::Alias { name: name.clone(), expansion });
+                        format!("Alias removed: {}", name)
+                    }
+                    Err(e) => format!("Error: {}", e),
+                }
+            }
+        }
+    }
 
-                container(item_content)
-                    .padding(Padding::from([10.0, 12.0]))
-                    .width(Length::Fill)
-                    .style(move |_theme| container::Style {
-                        background: Some(Background::Color(
-                            if is_selected { colors::SELECTION } else { Color::TRANSPARENT }
-                        )),
-                        border: Border::default().rounded(8),
-                        ..Default::default()
-                    })
-                    .into()
+    fn save_aliases(&self) -> Result<(), String> {
+        Config::update(|config| config.aliases = self.aliases.clone())
+    }
+
+    /// List palette actions matching `query`
+    fn show_actions(&mut self, query: &str) {
+        self.results = Action::search(query)
+            .into_iter()
+            .map(|action| SearchResult {
+                id: action.id().to_string(),
+                title: action.title().to_string(),
+                subtitle: action.description().to_string(),
+                icon: None,
+                category: ResultCategory::Action,
             })
             .collect();
+        self.selected_index = 0;
+        self.mode = UIMode::Results;
+    }
 
-        container(
-            scrollable(
-                column(items).spacing(4)
-            )
-            .height(Length::Fill)
-        )
-        .height(Length::FillPortion(1))
-        .into()
+    /// Run a palette action, or ask first if it can't be undone
+    fn run_action(&mut self, action: Action) {
+        if let Some(question) = action.confirmation() {
+            let now = Instant::now();
+            self.unconfirmed = Some((action, now + CONFIRM_WINDOW));
+            let toast = format!("{} Press Enter again to confirm", question);
+            self.toasts.push_action(toast, action.title(), Message::ConfirmAction(action), CONFIRM_WINDOW, now);
+            self.prompt.clear();
+            return;
+        }
+        self.perform_action(action);
     }
 
-    pub fn subscription(&self) -> Subscription<Message> {
-        Subscription::batch([
-            iced::event::listen().map(Message::IcedEvent),
-            hotkey::hotkey_tick_subscription().map(|_| Message::Tick),
-        ])
+    fn perform_action(&mut self, action: Action) {
+        tracing::info!("Running action: {}", action.id());
+        audit::record(audit::Kind::Action, action.title(), "");
+        let outcome = match action {
+            Action::RefreshApps => {
+                let count = self.services.refresh_apps();
+                Ok(format!("Refreshed app index ({} apps)", count))
+            }
+            Action::ClearClipboard => {
+                let history = self.services.clipboard().get_history();
+                self.services.clipboard().clear();
+                if !history.is_empty() {
+                    self.offer_undo("Clipboard history cleared".to_string(), Inverse::Clipboard(history));
+                }
+                Ok("Clipboard history cleared".to_string())
+            }
+            Action::SwitchTheme => {
+                self.theme = self.theme.toggled();
+                colors::set_light(self.theme == config::Theme::Light);
+                let theme = self.theme;
+                Config::update(|config| config.ui.theme = theme).map(|()| format!("Switched to {:?} theme", theme))
+            }
+            Action::RestartBackend => {
+                crate::backend::sidecar::restart().map(|()| "Backend restarted".to_string())
+            }
+            Action::EmptyTrash => trash::empty().map(|count| match count {
+                0 => String::from("The trash is already empty"),
+                count => format!("Emptied the trash ({} items)", count),
+            }),
+            Action::OpenConfig => Config::ensure_exists().and_then(|path| {
+                let path = path.to_string_lossy().to_string();
+                self.services
+                    .launch(&path, ResultCategory::File)
+                    .map(|()| format!("Opened {}", path))
+            }),
+        };
+
+        self.prompt.clear();
+        self.show_outcome("Action failed", outcome);
     }
 
-    pub fn theme(&self) -> Theme {
-        Theme::Dark
+    /// History used by Up/Down in the current mode
+    fn history_kind(&self) -> history::Kind {
+        if self.mode == UIMode::Chat {
+            history::Kind::Chat
+        } else {
+            history::Kind::Search
+        }
     }
 
-    // ========================================================================
-    // Business Logic
-    // ========================================================================
+    fn update_suggestions(&mut self) {
+        let apps = self.services.app_names();
+        let engine = SuggestionEngine {
+            history: &self.history,
+            aliases: &self.aliases,
+            quicklinks: &self.services.config().quicklinks,
+            apps: &apps,
+        };
+        self.suggestions = engine.suggest(&self.prompt);
+    }
 
-    fn handle_command(&mut self, input: &str) {
-        let parts: Vec<&str> = input.split_whitespace().collect();
-        let cmd = parts.first().copied().unwrap_or("");
-        let args = parts.get(1..).unwrap_or(&[]).join(" ");
+    fn record_history(&mut self, kind: history::Kind, prompt: &str) {
+        self.history_cursor = None;
+        self.history.push(kind, prompt);
+        if let Err(e) = self.history.save() {
+            tracing::warn!("Failed to save history: {}", e);
+        }
+    }
 
-        match cmd {
-            "/app" => self.search_apps(&args),
-            "/file" => self.search_files(&args),
-            "/clip" => self.show_clipboard(),
-            "/quit" => std::process::exit(0),
-            _ => {}
+    /// Put the entry `offset` steps back into the prompt
+    fn recall_history(&mut self, offset: usize) -> Task<Message> {
+        match self.history.recall(self.history_kind(), offset) {
+            Some(entry) => {
+                self.prompt = entry.to_string();
+                self.history_cursor = Some(offset);
+                self.suggestions.clear();
+                if self.dashboard {
+                    // The recalled prompt replaces the dashboard
+                    self.dashboard = false;
+                    self.results.clear();
+                    self.mode = UIMode::Search;
+                }
+                text_input::move_cursor_to_end(prompt_input_id())
+            }
+            None => Task::none(),
         }
     }
 
-    fn search(&mut self, query: &str) {
-        let app_results: Vec<SearchResult> = self
-            .app_indexer
-            .search(query)
+    fn show_history_matches(&mut self) {
+        self.results = self
+            .history
+            .search(&self.prompt, 20)
             .into_iter()
-            .take(8)
-            .map(|app| SearchResult {
-                id: app.id.clone(),
-                title: app.name.clone(),
-                subtitle: app.categories.first().cloned().unwrap_or_default(),
-                icon: app.icon_path().map(|p| p.to_string_lossy().to_string()),
-                category: ResultCategory::App,
+            .map(|entry| SearchResult {
+                id: entry.to_string(),
+                title: entry.to_string(),
+                subtitle: String::from("History"),
+                icon: None,
+                category: ResultCategory::History,
             })
             .collect();
-
-        self.results = app_results;
         self.selected_index = 0;
-        self.mode = if self.results.is_empty() {
-            UIMode::Search
-        } else {
-            UIMode::Results
-        };
+        self.mode = UIMode::Results;
     }
 
-    fn search_apps(&mut self, query: &str) {
-        self.search(query);
+    /// The selected row's path, if it's a folder that can be browsed
+    fn selected_folder(&self) -> Option<PathBuf> {
+        let result = self.results.get(self.selected_index)?;
+        let path = match result.category {
+            ResultCategory::File => PathBuf::from(&result.id),
+            ResultCategory::Bookmark => config::expand_home(&result.id),
+            _ => return None,
+        };
+        path.is_dir().then_some(path)
     }
 
-    fn search_files(&mut self, _query: &str) {
-        // TODO: Implement file search
+    /// List `folder`'s children, with an empty filter
+    fn browse_folder(&mut self, folder: PathBuf) -> Task<Message> {
+        self.browsing = Some(folder);
+        self.prompt.clear();
+        self.suggestions.clear();
+        self.show_browse(None);
+        text_input::focus(prompt_input_id())
     }
 
-    fn show_clipboard(&mut self) {
-        // TODO: Implement clipboard display
+    /// List the folder being browsed, filtered by the prompt; `select` is the
+    /// child to select (the folder just left when going up)
+    fn show_browse(&mut self, select: Option<&Path>) {
+        let Some(folder) = self.browsing.clone() else {
+            return;
+        };
+        // Rows of a search still running are for the text typed before
+        self.search_generation += 1;
+        self.pending.clear();
+        self.dashboard = false;
+        self.hero = None;
+        self.match_query = self.prompt.trim().to_string();
+        self.results = match self.services.browse(&folder, &self.prompt) {
+            Ok(rows) => rows,
+            Err(e) => {
+                self.browsing = None;
+                self.fail("Can't browse folder", e, None);
+                return;
+            }
+        };
+        self.empty = (self.results.is_empty() && !self.match_query.is_empty()).then(|| EmptyState {
+            message: tr_args("empty-browse", &[("query", self.match_query.as_str().into())]),
+            hint: tr("empty-browse-hint"),
+        });
+        self.selected_index = select
+            .and_then(|path| self.results.iter().position(|r| Path::new(&r.id) == path))
+            .unwrap_or(0);
+        self.mode = if self.results.is_empty() { UIMode::Search } else { UIMode::Results };
     }
 
-    fn execute_selected(&mut self) {
+    /// Leave history search with the selected entry in the prompt
+    fn accept_history_match(&mut self) -> Task<Message> {
+        self.history_search = false;
         if let Some(result) = self.results.get(self.selected_index) {
-            match result.category {
-                ResultCategory::App => {
-                    let _ = self.app_indexer.launch(&result.id);
-                }
-                _ => {}
+            self.prompt = result.title.clone();
+        }
+        self.results.clear();
+        self.mode = UIMode::Search;
+        text_input::move_cursor_to_end(prompt_input_id())
+    }
+
+    /// Load, list or unload context paths; the backend's list is authoritative
+    fn manage_context(&mut self, command: ContextCommand) -> Task<Message> {
+        let backend = self.backend.clone();
+        let session_id = self.session_id.clone();
+        self.loading = true;
+        self.ai_response.clear();
+        self.mode = UIMode::Chat;
+
+        match command {
+            ContextCommand::Load { path } => {
+                let document = DocumentKind::from_path(std::path::Path::new(&path)).is_some();
+                self.ai_status = if document {
+                    format!("📄 Extracting text from {}...", chip_label(&path))
+                } else {
+                    format!("📎 Loading {}...", chip_label(&path))
+                };
+                Task::perform(
+                    async move { backend.load_context(&session_id, &path).await },
+                    Message::ContextUpdated,
+                )
+            }
+            ContextCommand::List => Task::perform(
+                async move { backend.list_contexts(&session_id).await },
+                Message::ContextListed,
+            ),
+            ContextCommand::Remove { index } => {
+                let Some(path) = self.context_paths.get(index - 1).cloned() else {
+                    self.loading = false;
+                    self.ai_response = format!(
+                        "No context #{}.\n\n{}",
+                        index,
+                        format_context_list(&self.context_paths)
+                    );
+                    return Task::none();
+                };
+                Task::perform(
+                    async move { backend.remove_context(&session_id, &path).await },
+                    Message::ContextUpdated,
+                )
             }
         }
     }
 
+    /// Keep an open detail panel showing the selected result
+    fn sync_detail(&mut self) {
+        let Some(detail) = &self.detail else { return };
+        let selected = match self.mode {
+            UIMode::Results => self.results.get(self.selected_index),
+            _ => None,
+        };
+        match selected {
+            Some(result) if result.id == detail.id => {}
+            Some(result) => self.detail = Some(details::load(&self.services, result)),
+            None => self.detail = None,
+        }
+    }
+
     fn send_to_ai(&mut self) {
         self.mode = UIMode::Chat;
         self.loading = true;
@@ -766,3 +5803,400 @@ impl Ruty {
         // TODO: Async call to backend
     }
 }
+
+#[cfg(test)]
+impl Ruty {
+    /// Searching with a toast up, shown or hidden, before or after its first
+    /// frame (for `redraw`'s tests)
+    pub(crate) fn busy(visible: bool, interactive: bool) -> Self {
+        let mut ruty = Self { visible, interactive, pending: vec![ResultCategory::App], ..Self::default() };
+        ruty.toasts.push("Copied", Instant::now());
+        ruty
+    }
+}
+
+fn view_detail(detail: &Detail) -> Element<'_, Message> {
+    let mut items = column![text(&detail.title).size(fonts::size(16)).color(colors::text())].spacing(8);
+    for (label, value) in &detail.fields {
+        items = items.push(
+            column![
+                text(*label).size(fonts::size(11)).color(colors::text_muted()),
+                text(value).size(fonts::size(13)).color(colors::text()),
+            ]
+            .spacing(2),
+        );
+    }
+    if let Some(handle) = &detail.image {
+        items = items.push(image(handle.clone()).width(Length::Fill));
+    }
+    if let Some(body) = &detail.body {
+        items = items.push(text(body).size(fonts::size(13)).color(colors::text()));
+    }
+
+    container(scrollable(container(items).padding(12)).height(Length::Fill))
+        .width(Length::FillPortion(2))
+        .height(Length::Fill)
+        .style(|_theme| container::Style {
+            background: Some(Background::Color(colors::surface())),
+            border: Border::default().rounded(8),
+            ..Default::default()
+        })
+        .into()
+}
+
+/// Empty state for a search of `category` that found nothing
+fn no_matches(category: ResultCategory, query: &str) -> EmptyState {
+    let hint = match category {
+        ResultCategory::App => "empty-hint-app",
+        ResultCategory::File => "empty-hint-file",
+        ResultCategory::Git => "empty-hint-git",
+        ResultCategory::Device => "empty-hint-device",
+        ResultCategory::Contact => "empty-hint-contact",
+        _ => "empty-hint-other",
+    };
+    EmptyState {
+        message: tr_args(
+            "empty-no-matches",
+            &[("category", category.label().to_lowercase().into()), ("query", query.trim().into())],
+        ),
+        hint: tr(hint),
+    }
+}
+
+/// Chat turns as bubbles (mine on the right), following new turns to the
+/// bottom unless scrolled up. With `actions`, the last prompt can be edited
+/// and a reply to it regenerated.
+fn view_conversation(conversation: &Conversation, actions: bool) -> Element<'_, Message> {
+    let last_prompt = conversation.last_prompt().filter(|_| actions);
+    let bubbles = conversation.turns().iter().enumerate().map(|(i, turn)| {
+        let mut actions = Vec::new();
+        if turn.role == Role::Assistant {
+            actions.push(("⧉ Copy", Message::CopyTurn(i)));
+        }
+        match last_prompt {
+            Some(last) if i == last => actions.push(("✎ Edit", Message::EditLastPrompt)),
+            Some(last) if i > last && turn.role == Role::Assistant => actions.push(("↻ Regenerate", Message::Regenerate)),
+            _ => {}
+        }
+        view_turn(turn, actions)
+    });
+    scrollable(column(bubbles).spacing(12).padding(Padding::from([8.0, 12.0])))
+        .id(chat_scroll_id())
+        .height(Length::Fill)
+        .on_scroll(|viewport| Message::ChatScrolled(viewport.relative_offset().y))
+        .into()
+}
+
+fn view_turn<'a>(turn: &'a Turn, actions: Vec<(&'static str, Message)>) -> Element<'a, Message> {
+    let mine = turn.role == Role::User;
+    let mut bubble = column![text(&turn.text).size(fonts::size(15)).color(colors::text())].spacing(8);
+    if !turn.tools.is_empty() {
+        let chips = turn.tools.iter().map(|tool| {
+            container(text(chat::tool_label(tool)).size(fonts::size(11)).color(colors::text_muted()))
+                .padding(Padding::from([2.0, 8.0]))
+                .style(|_theme| container::Style {
+                    border: Border {
+                        color: colors::border(),
+                        width: 1.0,
+                        radius: 10.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .into()
+        });
+        bubble = bubble.push(row(chips).spacing(6));
+    }
+    let bubble = container(bubble)
+        .padding(Padding::from([10.0, 14.0]))
+        .max_width(520)
+        .style(move |_theme| container::Style {
+            background: Some(Background::Color(if mine { colors::selection() } else { colors::surface() })),
+            border: Border::default().rounded(12),
+            ..Default::default()
+        });
+    let mut footer = row![text(details::format_age(turn.at)).size(fonts::size(11)).color(colors::text_muted())]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+    for (label, message) in actions {
+        footer = footer.push(
+            button(text(label).size(fonts::size(11)).color(colors::primary()))
+                .on_press(message)
+                .padding(0)
+                .style(|_theme, _status| button::Style::default()),
+        );
+    }
+
+    column![bubble, footer]
+        .spacing(4)
+        .align_x(if mine { iced::Alignment::End } else { iced::Alignment::Start })
+        .width(Length::Fill)
+        .into()
+}
+
+/// Featured best match: 48px icon, larger title and its Enter action
+fn view_hero<'a>(
+    result: &'a SearchResult,
+    query: &str,
+    icon_types: &BTreeMap<String, String>,
+    is_selected: bool,
+) -> Element<'a, Message> {
+    let icon = ui::icons::view(result.icon.as_deref(), result.category.as_str(), icon_types, 48, colors::primary());
+    let action = match result.category {
+        ResultCategory::App => "Launch",
+        ResultCategory::File => "Open",
+        ResultCategory::Bookmark => "Browse",
+        _ => "Select",
+    };
+    let content = row![
+        container(icon).width(56).center_x(56),
+        column![
+            ui::highlight::highlighted(&result.title, query, 20, colors::text(), colors::primary()),
+            text(&result.subtitle).size(fonts::size(13)).color(colors::text_muted()),
+        ]
+        .spacing(4),
+        Space::with_width(Length::Fill),
+        text(format!("↵ {}", action)).size(fonts::size(13)).color(if is_selected { colors::primary() } else { colors::text_muted() }),
+    ]
+    .spacing(12)
+    .align_y(iced::Alignment::Center);
+
+    container(content)
+        .padding(Padding::from([14.0, 12.0]))
+        .width(Length::Fill)
+        .style(move |_theme| container::Style {
+            background: Some(Background::Color(if is_selected { colors::selection() } else { colors::surface() })),
+            border: Border {
+                color: colors::border(),
+                width: 1.0,
+                radius: 10.0.into(),
+            },
+            ..Default::default()
+        })
+        .into()
+}
+
+/// "▾ Applications — 4"; clicking it collapses or expands the section
+fn view_section_header<'a>(
+    category: ResultCategory,
+    label: &'static str,
+    count: usize,
+    collapsed: bool,
+) -> Element<'a, Message> {
+    button(
+        row![
+            text(if collapsed { "▸" } else { "▾" }).size(fonts::size(12)).color(colors::text_muted()),
+            text(format!("{} — {}", label, count)).size(fonts::size(12)).color(colors::text_muted()),
+        ]
+        .spacing(6),
+    )
+    .on_press(Message::ToggleSection(category))
+    .padding(Padding::from([4.0, 12.0]))
+    .style(|_theme, _status| button::Style::default())
+    .into()
+}
+
+/// Header above the first `/timeline` row of a day
+fn view_day_header<'a>(day: &'a str) -> Element<'a, Message> {
+    container(text(day).size(fonts::size(12)).color(colors::text_muted()))
+        .padding(Padding::from([4.0, 12.0]))
+        .into()
+}
+
+/// Small pill labelling a row, e.g. a clipboard entry's content type
+fn view_badge<'a>(label: &'a str) -> Element<'a, Message> {
+    container(text(label).size(fonts::size(10)).color(colors::primary()))
+        .padding(Padding::from([1.0, 6.0]))
+        .style(|_theme| container::Style {
+            background: Some(Background::Color(colors::surface())),
+            border: Border::default().rounded(6),
+            ..Default::default()
+        })
+        .into()
+}
+
+/// Placeholder shaped like a result row, while its search runs
+fn view_skeleton_row<'a>() -> Element<'a, Message> {
+    let bar = |width: f32, height: f32| {
+        container(Space::new(Length::Fixed(width), Length::Fixed(height))).style(|_theme| container::Style {
+            background: Some(Background::Color(colors::surface())),
+            border: Border::default().rounded(4),
+            ..Default::default()
+        })
+    };
+    container(
+        row![
+            container(bar(24.0, 24.0)).width(36).center_x(36),
+            column![bar(180.0, 14.0), bar(120.0, 10.0)].spacing(6),
+        ]
+        .spacing(12)
+        .align_y(iced::Alignment::Center),
+    )
+    .padding(Padding::from([10.0, 12.0]))
+    .width(Length::Fill)
+    .into()
+}
+
+/// A search that found nothing, centered below the prompt
+fn view_empty(empty: &EmptyState) -> Element<'_, Message> {
+    container(
+        column![
+            text(&empty.message).size(fonts::size(15)).color(colors::text()),
+            text(&empty.hint).size(fonts::size(13)).color(colors::text_muted()),
+        ]
+        .spacing(6)
+        .align_x(iced::Alignment::Center),
+    )
+    .width(Length::Fill)
+    .center_x(Length::Fill)
+    .into()
+}
+
+/// Error card: what failed, the error, and Retry where it makes sense
+fn view_failure(failure: &Failure) -> Element<'_, Message> {
+    let mut card = column![
+        text(format!("⚠ {}", failure.title)).size(fonts::size(15)).color(colors::error()),
+        text(&failure.detail).size(fonts::size(13)).color(colors::text_muted()),
+    ]
+    .spacing(8);
+    if failure.retry.is_some() {
+        let retry = button(text("Retry").size(fonts::size(13)).color(colors::text()))
+            .on_press(Message::Retry)
+            .padding(Padding::from([6.0, 14.0]))
+            .style(|_theme, status| button::Style {
+                background: Some(Background::Color(match status {
+                    button::Status::Hovered | button::Status::Pressed => colors::border(),
+                    _ => colors::selection(),
+                })),
+                border: Border::default().rounded(6),
+                ..Default::default()
+            });
+        card = card.push(Space::with_height(4)).push(retry);
+    }
+
+    container(card)
+        .padding(16)
+        .width(Length::Fill)
+        .style(|_theme| container::Style {
+            background: Some(Background::Color(colors::surface())),
+            border: Border {
+                color: colors::error(),
+                width: 1.0,
+                radius: 8.0.into(),
+            },
+            ..Default::default()
+        })
+        .into()
+}
+
+/// Subtitle marking the public IP row
+const PUBLIC_IP_LABEL: &str = "Public IP";
+
+/// Status line for a background job, e.g. "📦 Extracting a.zip ▰▰▰▱▱▱▱▱▱▱ 30%"
+fn job_status(label: &str, progress: &Progress) -> String {
+    match progress.fraction() {
+        Some(fraction) => {
+            let filled = (fraction * 10.0).round() as usize;
+            format!(
+                "{} {}{} {:.0}%",
+                label,
+                "▰".repeat(filled),
+                "▱".repeat(10 - filled),
+                fraction * 100.0
+            )
+        }
+        None => format!("{}...", label),
+    }
+}
+
+/// "today", "agenda" or "calendar" on their own list upcoming events
+fn is_agenda_query(prompt: &str) -> bool {
+    matches!(prompt.trim().to_lowercase().as_str(), "today" | "agenda" | "calendar")
+}
+
+/// Subtitle for a quick control row
+fn control_hint(kind: ControlKind) -> String {
+    match kind {
+        ControlKind::Level(_) => String::from("←/→ to adjust · Shift for finer steps"),
+        ControlKind::Toggle(true) => String::from("On · ↵ to turn off"),
+        ControlKind::Toggle(false) => String::from("Off · ↵ to turn on"),
+    }
+}
+
+fn format_context_list(paths: &[String]) -> String {
+    if paths.is_empty() {
+        return String::from("No context loaded. Use /context <path> or drop files here.");
+    }
+    let lines: Vec<String> = paths
+        .iter()
+        .enumerate()
+        .map(|(i, path)| format!("{}. {}", i + 1, path))
+        .collect();
+    format!("Loaded context:\n{}", lines.join("\n"))
+}
+
+/// Short name for a context path: its last component
+fn chip_label(path: &str) -> &str {
+    let trimmed = path.trim_end_matches('/');
+    trimmed.rsplit('/').next().filter(|name| !name.is_empty()).unwrap_or(path)
+}
+
+/// Where saved QR codes go: the pictures folder, else home
+fn qr_dir() -> PathBuf {
+    dirs::picture_dir().or_else(dirs::home_dir).unwrap_or_else(std::env::temp_dir)
+}
+
+/// Seconds since the Unix epoch
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+fn prompt_input_id() -> text_input::Id {
+    text_input::Id::new("prompt")
+}
+
+fn chat_scroll_id() -> scrollable::Id {
+    scrollable::Id::new("chat")
+}
+
+/// Forward unhandled events to the app; Ctrl+R, Ctrl+Shift+P, Ctrl+O, Ctrl+.,
+/// Ctrl+D, Ctrl+Space, Ctrl+Delete, Ctrl+Up/Down and Left/Right (for sliders and folders)
+/// are taken even while the prompt has focus
+fn handle_event(event: Event, status: iced::event::Status, window: window::Id) -> Option<Message> {
+    // The scratchpad's editor handles its own input
+    if scratchpad::is_pad(window) {
+        return None;
+    }
+    if let Event::Keyboard(keyboard::Event::KeyPressed { key: Key::Named(named), modifiers, .. }) = &event {
+        let step = if modifiers.shift() { 1 } else { controls::STEP };
+        match named {
+            keyboard::key::Named::ArrowLeft => return Some(Message::AdjustControl(-step)),
+            keyboard::key::Named::ArrowRight => return Some(Message::AdjustControl(step)),
+            keyboard::key::Named::Delete if modifiers.control() => return Some(Message::DeleteSelected),
+            keyboard::key::Named::Space if modifiers.control() => return Some(Message::MarkClip),
+            keyboard::key::Named::ArrowUp if modifiers.control() => return Some(Message::MoveFavorite(-1)),
+            keyboard::key::Named::ArrowDown if modifiers.control() => return Some(Message::MoveFavorite(1)),
+            _ => {}
+        }
+    }
+    if let Event::Keyboard(keyboard::Event::KeyPressed { key: Key::Character(c), modifiers, .. }) = &event {
+        if modifiers.control() && c.as_str() == "r" {
+            return Some(Message::HistorySearch);
+        }
+        if modifiers.control() && modifiers.shift() && c.eq_ignore_ascii_case("p") {
+            return Some(Message::CommandPalette);
+        }
+        if modifiers.control() && c.as_str() == "o" {
+            return Some(Message::FileActions);
+        }
+        if modifiers.control() && c.as_str() == "." {
+            return Some(Message::TogglePin);
+        }
+        if modifiers.control() && c.as_str() == "d" {
+            return Some(Message::ToggleFavorite);
+        }
+    }
+    match status {
+        iced::event::Status::Ignored => Some(Message::IcedEvent(event)),
+        iced::event::Status::Captured => None,
+    }
+}
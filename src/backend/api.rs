@@ -1,10 +1,20 @@
 //! HTTP client for Python backend API
 
-use reqwest::Client;
+use reqwest::header::AUTHORIZATION;
+use reqwest::{Client, RequestBuilder, Response};
+use tracing::Instrument;
 use serde::{Deserialize, Serialize};
+use ruty_core::config::expand_home;
+use ruty_core::documents::{self, DocumentKind};
 use std::collections::HashMap;
+use std::time::Duration;
+
+use iced::futures::stream;
+use iced::Subscription;
 
 use super::sidecar::backend_url;
+use super::trace;
+use crate::events::{self, Event};
 
 /// Client for communicating with Python FastAPI backend
 #[derive(Clone)]
@@ -21,8 +31,42 @@ pub struct ChatRequest {
     pub local_context: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_keys: Option<HashMap<String, String>>,
+    /// Replace the session's last exchange instead of appending to it
+    /// (edited resend, regenerate)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub replace_last: bool,
+    pub tool_approval: ToolApproval,
+}
+
+/// What the backend does when the AI wants to act on the system (run_shell,
+/// open_url)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolApproval {
+    /// Propose the call and wait for `decide_tool_call`
+    Ask,
+    /// Refuse it: nobody is there to ask
+    Deny,
+}
+
+/// A tool call the AI proposed, waiting for the user's answer
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    /// `run_shell` or `open_url`
+    pub tool: String,
+    /// The command or URL
+    pub argument: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct ToolCallsResponse {
+    calls: Vec<ToolCall>,
+}
+
+/// How long one poll for proposed tool calls waits for one to come in
+const TOOL_CALL_WAIT: Duration = Duration::from_secs(20);
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct ChatResponse {
     pub response: String,
@@ -68,13 +112,44 @@ pub struct ProviderUpdateRequest {
 pub struct ContextRequest {
     pub session_id: String,
     pub path: String,
+    /// Text extracted locally (documents the backend can't read itself)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ContextResponse {
     pub success: bool,
+    #[serde(default)]
     pub files_loaded: usize,
+    #[serde(default)]
     pub message: String,
+    /// All paths loaded for the session after this request
+    #[serde(default)]
+    pub contexts: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContextListResponse {
+    pub contexts: Vec<String>,
+}
+
+/// A memory the backend stored in Supermemory
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Memory {
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub content: String,
+    /// ISO 8601 timestamp, when Supermemory reports one
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MemoryListResponse {
+    pub memories: Vec<Memory>,
 }
 
 impl BackendClient {
@@ -92,12 +167,20 @@ impl BackendClient {
         }
     }
 
+    /// Send a request, logging it for `/debug net`
+    async fn send(&self, request: RequestBuilder) -> reqwest::Result<Response> {
+        let request = request.build()?;
+        let span = trace::span(&request);
+        async { trace::finish(self.client.execute(request).await).await }
+            .instrument(span)
+            .await
+    }
+
     /// Check if backend is healthy
     pub async fn health_check(&self) -> Result<HealthResponse, String> {
         let url = format!("{}/health", self.base_url);
-        self.client
-            .get(&url)
-            .send()
+        self
+            .send(self.client.get(&url))
             .await
             .map_err(|e| e.to_string())?
             .json()
@@ -108,28 +191,39 @@ impl BackendClient {
     /// Send a chat message to the AI (blocking, full response)
     pub async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, String> {
         let url = format!("{}/chat", self.base_url);
-        self.client
-            .post(&url)
-            .json(&request)
-            .send()
+        let response: ChatResponse = self
+            .send(self.client.post(&url).json(&request))
             .await
-            .map_err(|e| e.to_string())?
+            .map_err(|e| {
+                if e.is_connect() {
+                    events::publish(Event::BackendDown(e.to_string()));
+                }
+                e.to_string()
+            })?
             .json()
             .await
-            .map_err(|e| e.to_string())
+            .map_err(|e| e.to_string())?;
+        events::publish(Event::AiResponseDone {
+            session_id: request.session_id,
+            prompt: request.message,
+            response: response.response.clone(),
+            tools_used: response.tools_used.clone(),
+        });
+        Ok(response)
     }
     
     /// Load local files as context
+    ///
+    /// PDFs and office documents are converted to text here first.
     pub async fn load_context(&self, session_id: &str, path: &str) -> Result<ContextResponse, String> {
         let url = format!("{}/context/load", self.base_url);
         let request = ContextRequest {
             session_id: session_id.to_string(),
             path: path.to_string(),
+            content: extract_document(path).await?,
         };
-        self.client
-            .post(&url)
-            .json(&request)
-            .send()
+        self
+            .send(self.client.post(&url).json(&request))
             .await
             .map_err(|e| e.to_string())?
             .json()
@@ -137,23 +231,106 @@ impl BackendClient {
             .map_err(|e| e.to_string())
     }
     
+    /// Unload one previously loaded context path
+    pub async fn remove_context(&self, session_id: &str, path: &str) -> Result<ContextResponse, String> {
+        let url = format!("{}/context/remove", self.base_url);
+        let request = ContextRequest {
+            session_id: session_id.to_string(),
+            path: path.to_string(),
+            content: None,
+        };
+        self
+            .send(self.client.post(&url).json(&request))
+            .await
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Paths currently loaded as context for a session
+    pub async fn list_contexts(&self, session_id: &str) -> Result<Vec<String>, String> {
+        let url = format!("{}/context/list/{}", self.base_url, session_id);
+        let response: ContextListResponse = self
+            .send(self.client.get(&url))
+            .await
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(response.contexts)
+    }
+
     /// Clear context for session
     pub async fn clear_context(&self, session_id: &str) -> Result<(), String> {
         let url = format!("{}/context/clear/{}", self.base_url, session_id);
-        self.client
-            .delete(&url)
-            .send()
+        self
+            .send(self.client.delete(&url))
             .await
             .map_err(|e| e.to_string())?;
         Ok(())
     }
     
+    /// Tool calls of `session_id` not handed out yet, waiting up to `wait`
+    /// for one to come in
+    pub async fn proposed_tool_calls(&self, session_id: &str, wait: Duration) -> Result<Vec<ToolCall>, String> {
+        let url = format!("{}/tool-calls/proposed", self.base_url);
+        let query = [("session_id", session_id.to_string()), ("wait", wait.as_secs().to_string())];
+        let request = self.client.get(&url).query(&query).header(AUTHORIZATION, bearer()?);
+        let resp = self.send(request).await.map_err(|e| e.to_string())?;
+        if !resp.status().is_success() {
+            return Err(error_detail(resp).await);
+        }
+        let response: ToolCallsResponse = resp.json().await.map_err(|e| e.to_string())?;
+        Ok(response.calls)
+    }
+
+    /// Approve or deny a proposed tool call
+    pub async fn decide_tool_call(&self, id: &str, approved: bool) -> Result<(), String> {
+        let url = format!("{}/tool-calls/{}", self.base_url, id);
+        let body = serde_json::json!({ "approved": approved });
+        let request = self.client.post(&url).json(&body).header(AUTHORIZATION, bearer()?);
+        let resp = self.send(request).await.map_err(|e| e.to_string())?;
+        if !resp.status().is_success() {
+            return Err(error_detail(resp).await);
+        }
+        Ok(())
+    }
+
+    /// Stored memories, or those matching `query`
+    pub async fn list_memories(&self, query: Option<&str>) -> Result<Vec<Memory>, String> {
+        let url = format!("{}/memories", self.base_url);
+        let mut request = self.client.get(&url);
+        if let Some(query) = query {
+            request = request.query(&[("q", query)]);
+        }
+        let resp = self.send(request).await.map_err(|e| e.to_string())?;
+        if !resp.status().is_success() {
+            return Err(error_detail(resp).await);
+        }
+        let response: MemoryListResponse = resp.json().await.map_err(|e| e.to_string())?;
+        Ok(response.memories)
+    }
+
+    /// Delete a stored memory
+    pub async fn delete_memory(&self, id: &str) -> Result<(), String> {
+        let url = format!("{}/memories/{}", self.base_url, id);
+        let resp = self
+            .send(self.client.delete(&url))
+            .await
+            .map_err(|e| e.to_string())?;
+        if resp.status().is_success() {
+            Ok(())
+        } else {
+            Err(error_detail(resp).await)
+        }
+    }
+
     /// Get available providers
     pub async fn get_providers(&self) -> Result<ProvidersResponse, String> {
         let url = format!("{}/providers", self.base_url);
-        self.client
-            .get(&url)
-            .send()
+        self
+            .send(self.client.get(&url))
             .await
             .map_err(|e| e.to_string())?
             .json()
@@ -164,10 +341,8 @@ impl BackendClient {
     /// Update provider configuration
     pub async fn update_provider(&self, request: ProviderUpdateRequest) -> Result<(), String> {
         let url = format!("{}/providers/update", self.base_url);
-        let resp = self.client
-            .post(&url)
-            .json(&request)
-            .send()
+        let resp = self
+            .send(self.client.post(&url).json(&request))
             .await
             .map_err(|e| e.to_string())?;
         
@@ -184,3 +359,57 @@ impl Default for BackendClient {
         Self::new()
     }
 }
+
+/// Tool calls the AI proposes in `session_id`, as they come in; run it while
+/// a chat request with `ToolApproval::Ask` is waiting
+pub fn tool_calls(backend: BackendClient, session_id: String) -> Subscription<ToolCall> {
+    let state = (backend, session_id.clone(), Vec::new());
+    let calls = stream::unfold(state, |(backend, session_id, mut queued)| async move {
+        while queued.is_empty() {
+            match backend.proposed_tool_calls(&session_id, TOOL_CALL_WAIT).await {
+                Ok(calls) => queued = calls,
+                Err(e) => {
+                    tracing::debug!("Polling tool calls failed: {}", e);
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+        let call = queued.remove(0);
+        Some((call, (backend, session_id, queued)))
+    });
+    Subscription::run_with_id(("tool-calls", session_id), calls)
+}
+
+/// `Bearer <token>` for the endpoints that can make the backend act on the
+/// system, which want the user's token as the daemon's APIs do
+fn bearer() -> Result<String, String> {
+    ruty_core::token::get().map(|token| ruty_core::token::bearer(&token))
+}
+
+/// FastAPI's `detail` message of a failed request, or its status
+async fn error_detail(resp: reqwest::Response) -> String {
+    #[derive(Deserialize)]
+    struct ErrorBody {
+        detail: String,
+    }
+    let status = resp.status();
+    match resp.json::<ErrorBody>().await {
+        Ok(body) => body.detail,
+        Err(_) => format!("Request failed: {}", status),
+    }
+}
+
+/// Text of a PDF/docx/odt at `path`, or `None` for other files
+async fn extract_document(path: &str) -> Result<Option<String>, String> {
+    let path = expand_home(path);
+    if DocumentKind::from_path(&path).is_none() {
+        return Ok(None);
+    }
+    let extracted = tokio::task::spawn_blocking(move || documents::extract(&path))
+        .await
+        .map_err(|e| e.to_string())??;
+    if extracted.truncated {
+        tracing::info!("Document text truncated to {} chars", documents::MAX_TEXT_CHARS);
+    }
+    Ok(Some(extracted.text))
+}
@@ -2,4 +2,5 @@
 
 pub mod api;
 pub mod sidecar;
+pub mod trace;
 pub mod types;
@@ -4,14 +4,48 @@
 
 use std::process::{Child, Command, Stdio};
 use std::path::PathBuf;
+use std::sync::Mutex;
 use std::time::Duration;
 
 /// Backend server port
 pub const BACKEND_PORT: u16 = 3847;
 
 /// Backend server URL
+/// Environment variable pointing the daemon at an externally managed backend
+pub const BACKEND_URL_ENV: &str = "RUTY_BACKEND_URL";
+
+/// Backend server URL (`RUTY_BACKEND_URL` overrides the bundled sidecar)
 pub fn backend_url() -> String {
-    format!("http://127.0.0.1:{}", BACKEND_PORT)
+    external_backend_url().unwrap_or_else(|| format!("http://127.0.0.1:{}", BACKEND_PORT))
+}
+
+/// URL of an external backend, if one is configured
+pub fn external_backend_url() -> Option<String> {
+    std::env::var(BACKEND_URL_ENV)
+        .ok()
+        .filter(|url| !url.is_empty())
+        .map(|url| url.trim_end_matches('/').to_string())
+}
+
+/// Bundled backend owned by the daemon, kept around for restarts
+static BUNDLED: Mutex<Option<Sidecar>> = Mutex::new(None);
+
+/// Hand the started sidecar to the daemon for the rest of the process
+pub fn install(sidecar: Sidecar) {
+    if let Ok(mut bundled) = BUNDLED.lock() {
+        *bundled = Some(sidecar);
+    }
+}
+
+/// Stop and start the bundled backend again
+pub fn restart() -> Result<(), String> {
+    if external_backend_url().is_some() {
+        return Err("Backend is managed externally (RUTY_BACKEND_URL)".to_string());
+    }
+    let mut bundled = BUNDLED.lock().map_err(|e| e.to_string())?;
+    let sidecar = bundled.as_mut().ok_or("No bundled backend to restart")?;
+    sidecar.stop();
+    sidecar.start()
 }
 
 /// Manages the Python backend process
@@ -0,0 +1,292 @@
+//! Backend request log for `/debug net`
+//!
+//! Every request `BackendClient` sends runs in a `backend_request` span
+//! carrying its method, path, status and payloads. `layer` is a tracing
+//! layer that keeps the last `KEPT` of those spans once they close, along
+//! with how long they took, so a chat that fails without a word can be
+//! looked into afterwards. Payloads are cut short, and anything that looks
+//! like a secret (API keys, tokens) is blanked before it's kept.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use reqwest::{Request, Response};
+use serde_json::Value;
+use tracing::field::{self, Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Span, Subscriber};
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Target of the request spans
+pub const TARGET: &str = "ruty::backend";
+/// Requests kept for the debug view
+const KEPT: usize = 50;
+/// Payloads are cut to this many characters
+const PAYLOAD_CHARS: usize = 300;
+/// JSON keys whose values are blanked when they contain one of these
+const SECRET_KEYS: &[&str] = &["key", "token", "secret", "password", "authorization"];
+
+static RECENT: Mutex<VecDeque<Exchange>> = Mutex::new(VecDeque::new());
+
+/// One request to the backend and how it went
+#[derive(Debug, Clone)]
+pub struct Exchange {
+    pub method: String,
+    /// Path and query of the URL
+    pub path: String,
+    /// HTTP status, `None` if no response came
+    pub status: Option<u16>,
+    pub request: String,
+    pub response: String,
+    pub error: Option<String>,
+    pub started: Instant,
+    pub duration: Duration,
+}
+
+impl Exchange {
+    fn new() -> Self {
+        Self {
+            method: String::new(),
+            path: String::new(),
+            status: None,
+            request: String::new(),
+            response: String::new(),
+            error: None,
+            started: Instant::now(),
+            duration: Duration::ZERO,
+        }
+    }
+
+    fn set(&mut self, name: &str, value: String) {
+        match name {
+            "method" => self.method = value,
+            "path" => self.path = value,
+            "request" => self.request = value,
+            "response" => self.response = value,
+            "error" => self.error = Some(value),
+            _ => {}
+        }
+    }
+}
+
+impl Visit for Exchange {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "status" {
+            self.status = u16::try_from(value).ok();
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.set(field.name(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.set(field.name(), format!("{:?}", value));
+    }
+}
+
+/// Keeps closed request spans in `RECENT`
+struct RequestLog;
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for RequestLog {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut exchange = Exchange::new();
+        attrs.record(&mut exchange);
+        span.extensions_mut().insert(exchange);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(exchange) = extensions.get_mut::<Exchange>() {
+            values.record(exchange);
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(mut exchange) = ctx.span(&id).and_then(|span| span.extensions_mut().remove::<Exchange>()) else {
+            return;
+        };
+        exchange.duration = exchange.started.elapsed();
+        let mut recent = RECENT.lock().unwrap();
+        if recent.len() == KEPT {
+            recent.pop_front();
+        }
+        recent.push_back(exchange);
+    }
+}
+
+/// Layer that records the request spans (and nothing else)
+pub fn layer<S: Subscriber + for<'a> LookupSpan<'a>>() -> impl Layer<S> {
+    RequestLog.with_filter(filter_fn(|metadata| metadata.target() == TARGET && metadata.is_span()))
+}
+
+/// The span to send `request` in
+pub fn span(request: &Request) -> Span {
+    let url = request.url();
+    let path = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let payload = request
+        .body()
+        .and_then(|body| body.as_bytes())
+        .map(|bytes| redact(&String::from_utf8_lossy(bytes)))
+        .unwrap_or_default();
+    tracing::info_span!(
+        target: TARGET,
+        "backend_request",
+        method = %request.method(),
+        path = %path,
+        request = %payload,
+        status = field::Empty,
+        response = field::Empty,
+        error = field::Empty,
+    )
+}
+
+/// Record how a request went on the current span. The body has to be read
+/// for that, so the response is handed back rebuilt around it.
+pub async fn finish(result: reqwest::Result<Response>) -> reqwest::Result<Response> {
+    let span = Span::current();
+    let response = result.inspect_err(|e| {
+        span.record("error", field::display(e));
+    })?;
+    let status = response.status();
+    let version = response.version();
+    let headers = response.headers().clone();
+    let body = response.bytes().await.inspect_err(|e| {
+        span.record("error", field::display(e));
+    })?;
+    span.record("status", status.as_u16());
+    span.record("response", redact(&String::from_utf8_lossy(&body)).as_str());
+
+    let mut rebuilt = http::Response::new(body);
+    *rebuilt.status_mut() = status;
+    *rebuilt.version_mut() = version;
+    *rebuilt.headers_mut() = headers;
+    Ok(Response::from(rebuilt))
+}
+
+/// A payload as kept: secrets in JSON blanked, cut to `PAYLOAD_CHARS`
+fn redact(text: &str) -> String {
+    let text = match serde_json::from_str::<Value>(text) {
+        Ok(mut json) => {
+            blank_secrets(&mut json);
+            json.to_string()
+        }
+        Err(_) => text.to_string(),
+    };
+    match text.char_indices().nth(PAYLOAD_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+fn blank_secrets(json: &mut Value) {
+    match json {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if value.is_null() || value.is_boolean() {
+                    continue;
+                }
+                if SECRET_KEYS.iter().any(|secret| key.contains(secret)) {
+                    *value = Value::String("[redacted]".to_string());
+                } else {
+                    blank_secrets(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(blank_secrets),
+        _ => {}
+    }
+}
+
+/// The kept requests, newest first
+pub fn recent() -> Vec<Exchange> {
+    RECENT.lock().unwrap().iter().rev().cloned().collect()
+}
+
+/// The kept requests as text for `/debug net`
+pub fn report() -> String {
+    let recent = recent();
+    if recent.is_empty() {
+        return "No backend requests yet.".to_string();
+    }
+    let mut text = format!("Last {} backend requests, newest first:\n", recent.len());
+    for exchange in recent {
+        let status = exchange.status.map_or_else(|| "failed".to_string(), |status| status.to_string());
+        text.push_str(&format!(
+            "\n{} {} → {} in {} ms ({}s ago)\n",
+            exchange.method,
+            exchange.path,
+            status,
+            exchange.duration.as_millis(),
+            exchange.started.elapsed().as_secs()
+        ));
+        if !exchange.request.is_empty() {
+            text.push_str(&format!("  sent: {}\n", exchange.request));
+        }
+        if !exchange.response.is_empty() {
+            text.push_str(&format!("  got: {}\n", exchange.response));
+        }
+        if let Some(error) = &exchange.error {
+            text.push_str(&format!("  error: {}\n", error));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_redact() {
+        let payload = r#"{"message":"hi","api_keys":{"openai":"sk-123"},"nested":[{"token":"abc","keep":1}]}"#;
+        let redacted = redact(payload);
+        assert!(redacted.contains(r#""api_keys":"[redacted]""#), "{}", redacted);
+        assert!(redacted.contains(r#""token":"[redacted]""#) && redacted.contains(r#""keep":1"#), "{}", redacted);
+        assert!(!redacted.contains("sk-123") && redacted.contains(r#""message":"hi""#));
+        assert_eq!(redact("plain text"), "plain text");
+        let long = "x".repeat(PAYLOAD_CHARS + 10);
+        assert_eq!(redact(&long).chars().count(), PAYLOAD_CHARS + 1);
+    }
+
+    #[test]
+    fn test_layer_keeps_requests() {
+        let subscriber = tracing_subscriber::registry().with(layer());
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!(
+                target: TARGET,
+                "backend_request",
+                method = "POST",
+                path = "/test-layer",
+                request = "{}",
+                status = field::Empty,
+                response = field::Empty,
+                error = field::Empty,
+            );
+            span.record("status", 502u16);
+            span.record("response", "bad gateway");
+            tracing::info_span!("unrelated", path = "/elsewhere").in_scope(|| {});
+        });
+        let recent = recent();
+        let exchange = recent.iter().find(|e| e.path == "/test-layer").unwrap();
+        assert_eq!((exchange.method.as_str(), exchange.status), ("POST", Some(502)));
+        assert_eq!(exchange.response, "bad gateway");
+        assert!(!recent.iter().any(|e| e.path == "/elsewhere"));
+        assert!(report().contains("POST /test-layer → 502"));
+    }
+}
@@ -0,0 +1,141 @@
+//! Chat conversation state
+//!
+//! Every prompt sent to the AI and every reply is kept as a turn, so the chat
+//! view shows the whole conversation rather than just the latest answer. The
+//! view follows new turns to the bottom unless the user has scrolled up to
+//! read something, in which case it stays put until they scroll back down.
+//! The last exchange can be taken back to resend an edited prompt or
+//! regenerate the reply.
+
+use std::time::SystemTime;
+
+/// Scrolled this close to the bottom (as a fraction), the view follows new turns
+const BOTTOM: f32 = 0.99;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Turn {
+    pub role: Role,
+    pub text: String,
+    /// Backend tools the assistant used for this reply
+    pub tools: Vec<String>,
+    /// Extra context sent with this prompt only (e.g. a clipboard entry)
+    pub context: Option<String>,
+    pub at: SystemTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct Conversation {
+    turns: Vec<Turn>,
+    /// Whether the view is at the bottom and should follow new turns
+    follow: bool,
+}
+
+impl Default for Conversation {
+    fn default() -> Self {
+        Self { turns: Vec::new(), follow: true }
+    }
+}
+
+impl Conversation {
+    pub fn turns(&self) -> &[Turn] {
+        &self.turns
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.turns.is_empty()
+    }
+
+    /// Record a prompt; sending one always jumps back to the bottom
+    pub fn push_user(&mut self, text: String, context: Option<String>) {
+        self.follow = true;
+        self.turns.push(Turn { role: Role::User, text, tools: Vec::new(), context, at: SystemTime::now() });
+    }
+
+    pub fn push_assistant(&mut self, text: String, tools: Vec<String>) {
+        self.turns.push(Turn { role: Role::Assistant, text, tools, context: None, at: SystemTime::now() });
+    }
+
+    /// Index of the last prompt, the one that can be edited or regenerated
+    pub fn last_prompt(&self) -> Option<usize> {
+        self.turns.iter().rposition(|turn| turn.role == Role::User)
+    }
+
+    /// Take back the last prompt and any reply to it, returning the prompt
+    pub fn pop_exchange(&mut self) -> Option<Turn> {
+        let index = self.last_prompt()?;
+        self.turns.drain(index..).next()
+    }
+
+    /// Track the scroll position (0.0 top to 1.0 bottom) of the chat view
+    pub fn scrolled(&mut self, relative_y: f32) {
+        self.follow = relative_y >= BOTTOM;
+    }
+
+    /// Whether new turns should scroll the view to the bottom
+    pub fn follows(&self) -> bool {
+        self.follow
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Chip text for a backend tool
+pub fn tool_label(tool: &str) -> &'static str {
+    match tool {
+        "search_memory" | "query_supermemory" => "🔍 Searched memory",
+        "add_memory" => "💾 Saved to memory",
+        "open_url" | "open_browser" => "🌐 Opened browser",
+        "run_shell" | "run_command" => "⚙️ Ran command",
+        "get_system_info" => "💻 Got system info",
+        _ => "🔧 Used tool",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scroll_lock() {
+        let mut conversation = Conversation::default();
+        conversation.push_user("hi".to_string(), None);
+        conversation.push_assistant("hello".to_string(), vec!["search_memory".to_string()]);
+        assert!(conversation.follows());
+
+        // Scrolled up to read: new replies don't yank the view down
+        conversation.scrolled(0.4);
+        conversation.push_assistant("more".to_string(), Vec::new());
+        assert!(!conversation.follows());
+
+        // Sending a prompt jumps back to the bottom
+        conversation.push_user("again".to_string(), None);
+        assert!(conversation.follows());
+        let roles: Vec<Role> = conversation.turns().iter().map(|t| t.role).collect();
+        assert_eq!(roles, vec![Role::User, Role::Assistant, Role::Assistant, Role::User]);
+    }
+
+    #[test]
+    fn test_pop_exchange() {
+        let mut conversation = Conversation::default();
+        conversation.push_user("first".to_string(), None);
+        conversation.push_assistant("one".to_string(), Vec::new());
+        conversation.push_user("second".to_string(), Some("ctx".to_string()));
+        conversation.push_assistant("two".to_string(), Vec::new());
+        assert_eq!(conversation.last_prompt(), Some(2));
+
+        let prompt = conversation.pop_exchange().unwrap();
+        assert_eq!((prompt.text.as_str(), prompt.context.as_deref()), ("second", Some("ctx")));
+        assert_eq!(conversation.turns().len(), 2);
+
+        conversation.clear();
+        assert!(conversation.pop_exchange().is_none());
+    }
+}
@@ -1,14 +1,84 @@
 //! Slash command parsing and handling
 //!
 //! Parses commands like /context, /clear, /providers from user input.
+//! User aliases from config are expanded first (see `expand_alias`).
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::timers;
+use ruty_core::checksum::Algorithm;
+use ruty_core::config::SavedSearch;
+use ruty_core::i18n;
+use ruty_core::transforms::Transform;
 
 /// Parsed command from user input
 #[derive(Debug, Clone)]
 pub enum Command {
     /// Search and launch apps: /app <query>
     App { query: String },
-    /// Load local files as context: /context <path>
-    Context { path: String },
+    /// Checksum of a file or literal text: /hash [algo] <path|text>
+    Hash { algorithm: Algorithm, target: String },
+    /// Text transform on typed text (clipboard if empty): /b64, /url, /json, /uuid, /lorem
+    Transform { transform: Transform, text: String },
+    /// Regex tester with the clipboard as test text: /regex <pattern>
+    Regex { pattern: String },
+    /// Extract the archive picked with "Extract to…": /extract-to <dir>
+    ExtractTo { dest: String },
+    /// Search files in the configured directories: /file <query>
+    File { query: String },
+    /// Bluetooth and audio devices: /devices [query]
+    Devices { query: String },
+    /// Address book: /contacts [name]
+    Contacts { query: String },
+    /// Find git repos in the project dirs: /git <query>
+    Git { query: String },
+    /// Launch the best matching app directly: /launch <query>
+    Launch { query: String },
+    /// List apps to hide from or show in search: /apps [query]
+    Apps { query: String },
+    /// Manage aliases: /alias add <name> <expansion> | list | rm <name>
+    Alias(AliasCommand),
+    /// Saved searches: /saved <name> | list | add <name> <search> | rm <name>
+    Saved(SavedCommand),
+    /// Folder bookmarks: /bm list | add <path> [,shortcut] | rm <path|shortcut>
+    Bookmark(BookmarkCommand),
+    /// Manage local file context: /context <path> | list | remove <n>
+    Context(ContextCommand),
+    /// Cache usage or eviction: /cache stats|clear
+    Cache(CacheCommand),
+    /// Browse clipboard history, optionally of one type and containing
+    /// text: /clip [url|email|path|color|json|code|text] [text]
+    Clipboard { filter: String },
+    /// Read the text in the copied image into a new clipboard entry: /ocr
+    Ocr,
+    /// Open or close the scratchpad window: /pad
+    Pad,
+    /// Show text as a QR code, or (bare) offer to scan one: /qr [text|url]
+    Qr { text: String },
+    /// Ask about a clipboard entry (latest unless one is selected): /ask-clip <question>
+    AskClip { question: String },
+    /// Ask AI with a prompt template from config: /tpl <name> [text]; bare
+    /// /tpl lists the templates
+    Template { name: Option<String>, input: String },
+    /// Run a workflow from config: /flow <name>; bare /flow lists them
+    Flow { name: Option<String> },
+    /// What flows and hooks were allowed to do, to revoke: /permissions
+    Permissions,
+    /// Browse memories the AI stored, or search them: /memory [query]
+    Memory { query: String },
+    /// List favorites, or star/unstar a quicklink: /fav [keyword]
+    Favorite { keyword: Option<String> },
+    /// Start a countdown: /timer <duration> [label]
+    Timer { duration: Duration, label: String },
+    /// Do Not Disturb: /dnd [on|off|<focus duration>]
+    Dnd(DndCommand),
+    /// Upcoming calendar events: /agenda [days] (default from config)
+    Agenda { days: Option<u32> },
+    /// Recent launches, opened files, copies and chats by day: /timeline
+    Timeline,
+    /// What was launched, opened and run, newest first: /audit [since]
+    Audit { since: String },
     /// Clear conversation: /clear
     Clear,
     /// Show/switch providers: /providers [provider] [model]
@@ -16,6 +86,10 @@ pub enum Command {
         provider: Option<String>, 
         model: Option<String> 
     },
+    /// Check the setup and suggest fixes: /doctor
+    Doctor,
+    /// Developer views: /debug net
+    Debug(DebugCommand),
     /// Open settings: /settings
     Settings,
     /// Show help: /help
@@ -24,6 +98,121 @@ pub enum Command {
     Chat { message: String },
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum AliasCommand {
+    Add { name: String, expansion: String },
+    List,
+    Remove { name: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SavedCommand {
+    Run { name: String },
+    List,
+    /// Keep a search, e.g. `/file kind:pdf invoice`, under a name
+    Add { name: String, search: SavedSearch },
+    Remove { name: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BookmarkCommand {
+    List,
+    /// Bookmark a folder, with a shortcut typed as `,<shortcut>` to browse it
+    Add { path: String, shortcut: Option<String> },
+    /// Remove the bookmark with this path or shortcut
+    Remove { target: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContextCommand {
+    Load { path: String },
+    List,
+    /// 1-based position as shown by `/context list`
+    Remove { index: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheCommand {
+    Stats,
+    Clear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugCommand {
+    /// The last backend requests
+    Net,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DndCommand {
+    /// List the state and the ways to change it
+    Status,
+    On,
+    Off,
+    /// On until a timer this long is up
+    Focus(Duration),
+}
+
+/// A slash command as listed in help and suggestions
+#[derive(Debug, Clone, Copy)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    /// Argument hint, empty if the command takes none
+    pub args: &'static str,
+}
+
+impl CommandSpec {
+    /// What the command does, in the UI language
+    pub fn description(&self) -> String {
+        i18n::tr(&format!("command-{}", self.name.trim_start_matches('/')))
+    }
+}
+
+/// Registry of slash commands understood by `Command::parse`
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec { name: "/app", args: "<query>" },
+    CommandSpec { name: "/launch", args: "<app>" },
+    CommandSpec { name: "/apps", args: "[name]" },
+    CommandSpec { name: "/file", args: "<query>" },
+    CommandSpec { name: "/hash", args: "[md5|sha1|sha256|blake3] <path|text>" },
+    CommandSpec { name: "/b64", args: "encode|decode [text]" },
+    CommandSpec { name: "/url", args: "encode|decode [text]" },
+    CommandSpec { name: "/json", args: "fmt|min [json]" },
+    CommandSpec { name: "/uuid", args: "" },
+    CommandSpec { name: "/lorem", args: "[paragraphs]" },
+    CommandSpec { name: "/regex", args: "<pattern>" },
+    CommandSpec { name: "/extract-to", args: "<dir>" },
+    CommandSpec { name: "/devices", args: "[name]" },
+    CommandSpec { name: "/contacts", args: "[name]" },
+    CommandSpec { name: "/git", args: "<repo>" },
+    CommandSpec { name: "/context", args: "<path>|list|remove <n>" },
+    CommandSpec { name: "/clip", args: "[type] [text]" },
+    CommandSpec { name: "/ocr", args: "" },
+    CommandSpec { name: "/pad", args: "" },
+    CommandSpec { name: "/qr", args: "[text|url]" },
+    CommandSpec { name: "/ask-clip", args: "<question>" },
+    CommandSpec { name: "/tpl", args: "<template> [text]" },
+    CommandSpec { name: "/flow", args: "<name>" },
+    CommandSpec { name: "/permissions", args: "" },
+    CommandSpec { name: "/memory", args: "[query]" },
+    CommandSpec { name: "/fav", args: "[quicklink]" },
+    CommandSpec { name: "/timer", args: "<duration> [label]" },
+    CommandSpec { name: "/dnd", args: "[on|off|<duration>]" },
+    CommandSpec { name: "/agenda", args: "[days]" },
+    CommandSpec { name: "/timeline", args: "" },
+    CommandSpec { name: "/audit", args: "[since]" },
+    CommandSpec { name: "/cache", args: "stats|clear" },
+    CommandSpec { name: "/clear", args: "" },
+    CommandSpec { name: "/providers", args: "[provider] [model]" },
+    CommandSpec { name: "/alias", args: "add|list|rm" },
+    CommandSpec { name: "/saved", args: "<name>|add|list|rm" },
+    CommandSpec { name: "/bm", args: "add|list|rm" },
+    CommandSpec { name: "/doctor", args: "" },
+    CommandSpec { name: "/debug", args: "net" },
+    CommandSpec { name: "/settings", args: "" },
+    CommandSpec { name: "/help", args: "" },
+];
+
 impl Command {
     /// Parse user input into a command
     pub fn parse(input: &str) -> Self {
@@ -47,15 +236,119 @@ impl Command {
                     Command::App { query: args.to_string() }
                 }
             }
-            "/context" | "/ctx" | "/c" => {
+            "/apps" => Command::Apps { query: args.to_string() },
+            "/hash" | "/checksum" => Self::parse_hash(args),
+            "/b64" | "/base64" | "/url" | "/json" => Self::parse_transform(&cmd, args),
+            "/regex" | "/re" => Command::Regex { pattern: args.to_string() },
+            "/uuid" => Command::Transform { transform: Transform::Uuid, text: String::new() },
+            "/lorem" => match args {
+                "" => Command::Transform { transform: Transform::Lorem(1), text: String::new() },
+                n => match n.parse::<usize>() {
+                    Ok(paragraphs) if paragraphs > 0 => Command::Transform {
+                        transform: Transform::Lorem(paragraphs),
+                        text: String::new(),
+                    },
+                    _ => Command::Chat {
+                        message: "Usage: /lorem [paragraphs]".to_string()
+                    },
+                },
+            },
+            "/extract-to" => {
                 if args.is_empty() {
-                    Command::Chat { 
-                        message: "Usage: /context <path>".to_string() 
+                    Command::Chat {
+                        message: "Usage: /extract-to <dir>".to_string()
+                    }
+                } else {
+                    Command::ExtractTo { dest: args.to_string() }
+                }
+            }
+            "/file" | "/files" | "/f" => {
+                if args.is_empty() {
+                    Command::Chat {
+                        message: "Usage: /file <query>".to_string()
+                    }
+                } else {
+                    Command::File { query: args.to_string() }
+                }
+            }
+            "/devices" | "/device" | "/dev" => Command::Devices { query: args.to_string() },
+            "/contacts" | "/contact" => Command::Contacts { query: args.to_string() },
+            "/git" | "/repo" => {
+                if args.is_empty() {
+                    Command::Chat {
+                        message: "Usage: /git <repo>".to_string()
+                    }
+                } else {
+                    Command::Git { query: args.to_string() }
+                }
+            }
+            "/context" | "/ctx" | "/c" => Self::parse_context(args),
+            "/clip" | "/clipboard" => Command::Clipboard { filter: args.to_string() },
+            "/ocr" => Command::Ocr,
+            "/pad" | "/scratchpad" => Command::Pad,
+            "/qr" => Command::Qr { text: args.to_string() },
+            "/ask-clip" | "/ac" => {
+                if args.is_empty() {
+                    Command::Chat {
+                        message: "Usage: /ask-clip <question>".to_string()
                     }
                 } else {
-                    Command::Context { path: args.to_string() }
+                    Command::AskClip { question: args.to_string() }
+                }
+            }
+            "/tpl" | "/template" => {
+                let (name, input) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+                Command::Template {
+                    name: (!name.is_empty()).then(|| name.to_string()),
+                    input: input.trim().to_string(),
+                }
+            }
+            "/flow" | "/flows" => Command::Flow {
+                name: Some(args.to_string()).filter(|name| !name.is_empty()),
+            },
+            "/permissions" => Command::Permissions,
+            "/memory" | "/memories" | "/mem" => Command::Memory { query: args.to_string() },
+            "/fav" | "/favorites" | "/star" => Command::Favorite {
+                keyword: Some(args.to_string()).filter(|keyword| !keyword.is_empty()),
+            },
+            "/timer" => {
+                let (duration, label) = args.split_once(' ').unwrap_or((args, ""));
+                match timers::parse_duration(duration) {
+                    Some(duration) => Command::Timer { duration, label: label.trim().to_string() },
+                    None => Command::Chat {
+                        message: "Usage: /timer <duration> [label], e.g. /timer 25m tea".to_string()
+                    },
                 }
             }
+            "/dnd" | "/focus" => match args {
+                "" => Command::Dnd(DndCommand::Status),
+                "on" => Command::Dnd(DndCommand::On),
+                "off" => Command::Dnd(DndCommand::Off),
+                _ => match timers::parse_duration(args) {
+                    Some(duration) => Command::Dnd(DndCommand::Focus(duration)),
+                    None => Command::Chat {
+                        message: "Usage: /dnd [on|off|<duration>], e.g. /dnd 25m".to_string()
+                    },
+                },
+            },
+            "/timeline" => Command::Timeline,
+            "/audit" => Command::Audit { since: args.to_string() },
+            "/agenda" | "/today" => match args {
+                "" => Command::Agenda { days: None },
+                _ => match args.parse::<u32>() {
+                    Ok(days) if days > 0 => Command::Agenda { days: Some(days) },
+                    _ => Command::Chat {
+                        message: "Usage: /agenda [days], e.g. /agenda 14".to_string()
+                    },
+                },
+            },
+            "/cache" => match args {
+                "" | "stats" => Command::Cache(CacheCommand::Stats),
+                "clear" => Command::Cache(CacheCommand::Clear),
+                _ => Command::Chat {
+                    message: "Usage: /cache stats|clear".to_string()
+                },
+            },
             "/clear" | "/cl" => Command::Clear,
             "/providers" | "/provider" | "/p" => {
                 let provider_parts: Vec<&str> = args.split_whitespace().collect();
@@ -64,6 +357,25 @@ impl Command {
                     model: provider_parts.get(1).map(|s| s.to_string()),
                 }
             }
+            "/launch" | "/l" => {
+                if args.is_empty() {
+                    Command::Chat {
+                        message: "Usage: /launch <app>".to_string()
+                    }
+                } else {
+                    Command::Launch { query: args.to_string() }
+                }
+            }
+            "/alias" => Self::parse_alias(args),
+            "/saved" => Self::parse_saved(args),
+            "/bm" | "/bookmark" | "/bookmarks" => Self::parse_bookmark(args),
+            "/doctor" => Command::Doctor,
+            "/debug" => match args {
+                "net" => Command::Debug(DebugCommand::Net),
+                _ => Command::Chat {
+                    message: "Usage: /debug net".to_string()
+                },
+            },
             "/settings" | "/s" => Command::Settings,
             "/help" | "/h" | "/?" => Command::Help,
             _ => Command::Chat { 
@@ -72,18 +384,156 @@ impl Command {
         }
     }
     
+    fn parse_hash(args: &str) -> Self {
+        let (first, rest) = args.split_once(' ').unwrap_or((args, ""));
+        let (algorithm, target) = match Algorithm::parse(first) {
+            Some(algorithm) => (algorithm, rest.trim()),
+            None => (Algorithm::Sha256, args),
+        };
+        if target.is_empty() {
+            return Command::Chat {
+                message: "Usage: /hash [md5|sha1|sha256|blake3] <path|text>".to_string()
+            };
+        }
+        Command::Hash { algorithm, target: target.to_string() }
+    }
+
+    /// `/b64 encode|decode`, `/url encode|decode`, `/json fmt|min`, then optional text
+    fn parse_transform(cmd: &str, args: &str) -> Self {
+        let (op, text) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        match Transform::parse(cmd, op) {
+            Some(transform) => Command::Transform { transform, text: text.trim().to_string() },
+            None => {
+                let ops = if cmd == "/json" { "fmt|min" } else { "encode|decode" };
+                Command::Chat {
+                    message: format!("Usage: {} {} [text] (clipboard if no text)", cmd, ops)
+                }
+            }
+        }
+    }
+
+    fn parse_context(args: &str) -> Self {
+        let (sub, rest) = args.split_once(' ').unwrap_or((args, ""));
+        match sub {
+            "" | "list" | "ls" => Command::Context(ContextCommand::List),
+            "remove" | "rm" => match rest.trim().parse::<usize>() {
+                Ok(index) if index > 0 => Command::Context(ContextCommand::Remove { index }),
+                _ => Command::Chat {
+                    message: "Usage: /context remove <n> (see /context list)".to_string()
+                },
+            },
+            _ => Command::Context(ContextCommand::Load { path: args.to_string() }),
+        }
+    }
+
+    fn parse_alias(args: &str) -> Self {
+        let (sub, rest) = args.split_once(' ').unwrap_or((args, ""));
+        let rest = rest.trim();
+        match sub {
+            "" | "list" | "ls" => Command::Alias(AliasCommand::List),
+            "add" => match rest.split_once(' ') {
+                Some((name, expansion)) if !expansion.trim().is_empty() => {
+                    Command::Alias(AliasCommand::Add {
+                        name: name.to_string(),
+                        expansion: expansion.trim().to_string(),
+                    })
+                }
+                _ => Command::Chat {
+                    message: "Usage: /alias add <name> <expansion>".to_string()
+                },
+            },
+            "rm" | "remove" if !rest.is_empty() => {
+                Command::Alias(AliasCommand::Remove { name: rest.to_string() })
+            }
+            _ => Command::Chat {
+                message: "Usage: /alias add <name> <expansion> | list | rm <name>".to_string()
+            },
+        }
+    }
+
+    fn parse_saved(args: &str) -> Self {
+        let (sub, rest) = args.split_once(' ').unwrap_or((args, ""));
+        let rest = rest.trim();
+        match sub {
+            "" | "list" | "ls" => Command::Saved(SavedCommand::List),
+            "add" => {
+                let (name, prompt) = rest.split_once(' ').unwrap_or((rest, ""));
+                match SavedSearch::from_prompt(prompt).filter(|search| !search.query.is_empty()) {
+                    Some(search) if !name.is_empty() => {
+                        Command::Saved(SavedCommand::Add { name: name.to_string(), search })
+                    }
+                    _ => Command::Chat {
+                        message: "Usage: /saved add <name> <search>, where the search is text or \
+                                  /app, /file, /git, /clip or /contacts with a query"
+                            .to_string()
+                    },
+                }
+            }
+            "rm" | "remove" if !rest.is_empty() => {
+                Command::Saved(SavedCommand::Remove { name: rest.to_string() })
+            }
+            name if rest.is_empty() && !matches!(name, "rm" | "remove") => {
+                Command::Saved(SavedCommand::Run { name: name.to_string() })
+            }
+            _ => Command::Chat {
+                message: "Usage: /saved <name> | list | add <name> <search> | rm <name>".to_string()
+            },
+        }
+    }
+
+    fn parse_bookmark(args: &str) -> Self {
+        let (sub, rest) = args.split_once(' ').unwrap_or((args, ""));
+        let rest = rest.trim();
+        match sub {
+            "" | "list" | "ls" => Command::Bookmark(BookmarkCommand::List),
+            // A trailing ",w" is the shortcut; the path may contain spaces
+            "add" if !rest.is_empty() => {
+                let (path, shortcut) = match rest.rsplit_once(' ') {
+                    Some((path, shortcut)) if shortcut.len() > 1 && shortcut.starts_with(',') => {
+                        (path.trim(), Some(shortcut[1..].to_string()))
+                    }
+                    _ => (rest, None),
+                };
+                Command::Bookmark(BookmarkCommand::Add { path: path.to_string(), shortcut })
+            }
+            "rm" | "remove" if !rest.is_empty() => {
+                Command::Bookmark(BookmarkCommand::Remove { target: rest.to_string() })
+            }
+            _ => Command::Chat {
+                message: "Usage: /bm add <folder> [,shortcut] | list | rm <folder|shortcut>".to_string()
+            },
+        }
+    }
+
     /// Get help text for all commands
-    pub fn help_text() -> &'static str {
-        r#"Available Commands:
-/app <query>     - Search and launch applications (default: AI)
-/context <path>  - Load local files as context
-/clear           - Clear conversation history
-/providers       - Show available providers
-/settings        - Open settings
-/help            - Show this help
+    pub fn help_text() -> String {
+        i18n::tr("commands-help")
+    }
+}
 
-Tip: Just type your question to chat with AI!"#
+/// Expand a leading alias: `name rest...` becomes `expansion rest...`.
+/// Aliases aren't expanded recursively.
+pub fn expand_alias(input: &str, aliases: &BTreeMap<String, String>) -> Option<String> {
+    let input = input.trim();
+    let (name, rest) = input.split_once(' ').unwrap_or((input, ""));
+    let expansion = aliases.get(name)?;
+    let rest = rest.trim();
+    Some(if rest.is_empty() {
+        expansion.clone()
+    } else {
+        format!("{} {}", expansion, rest)
+    })
+}
+
+/// Check that an alias name can be typed and won't shadow commands
+pub fn validate_alias_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.chars().any(char::is_whitespace) {
+        return Err("Alias names can't be empty or contain spaces".to_string());
+    }
+    if name.starts_with('/') || name.starts_with('>') {
+        return Err("Alias names can't start with '/' or '>'".to_string());
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -93,11 +543,74 @@ mod tests {
     #[test]
     fn test_parse_context() {
         match Command::parse("/context ./src") {
-            Command::Context { path } => assert_eq!(path, "./src"),
+            Command::Context(ContextCommand::Load { path }) => assert_eq!(path, "./src"),
             _ => panic!("Expected Context command"),
         }
+        assert!(matches!(Command::parse("/context"), Command::Context(ContextCommand::List)));
+        assert!(matches!(
+            Command::parse("/ctx remove 2"),
+            Command::Context(ContextCommand::Remove { index: 2 })
+        ));
+        assert!(matches!(Command::parse("/context rm 0"), Command::Chat { .. }));
     }
     
+    #[test]
+    fn test_parse_template() {
+        match Command::parse("/tpl translate  hola mundo") {
+            Command::Template { name, input } => {
+                assert_eq!(name.as_deref(), Some("translate"));
+                assert_eq!(input, "hola mundo");
+            }
+            _ => panic!("Expected Template command"),
+        }
+        assert!(matches!(Command::parse("/tpl"), Command::Template { name: None, .. }));
+    }
+
+    #[test]
+    fn test_flow() {
+        assert!(matches!(Command::parse("/flow standup"), Command::Flow { name: Some(name) } if name == "standup"));
+        assert!(matches!(Command::parse("/flow"), Command::Flow { name: None }));
+        assert!(matches!(Command::parse("/permissions"), Command::Permissions));
+        assert!(matches!(Command::parse("/audit yesterday"), Command::Audit { since } if since == "yesterday"));
+    }
+
+    #[test]
+    fn test_parse_apps() {
+        assert!(matches!(Command::parse("/apps"), Command::Apps { query } if query.is_empty()));
+        assert!(matches!(Command::parse("/apps kde"), Command::Apps { query } if query == "kde"));
+        assert!(matches!(Command::parse("/app kde"), Command::App { .. }));
+    }
+
+    #[test]
+    fn test_parse_clipboard() {
+        assert!(matches!(Command::parse("/clip"), Command::Clipboard { filter } if filter.is_empty()));
+        assert!(matches!(Command::parse("/clip urls github"), Command::Clipboard { filter } if filter == "urls github"));
+        assert!(matches!(Command::parse("/ocr"), Command::Ocr));
+        assert!(matches!(Command::parse("/pad"), Command::Pad));
+        assert!(matches!(Command::parse("/qr https://example.com"), Command::Qr { text } if text == "https://example.com"));
+    }
+
+    #[test]
+    fn test_parse_favorite() {
+        assert!(matches!(Command::parse("/fav"), Command::Favorite { keyword: None }));
+        assert!(matches!(Command::parse("/star gh"), Command::Favorite { keyword: Some(k) } if k == "gh"));
+    }
+
+    #[test]
+    fn test_parse_timer() {
+        assert!(matches!(
+            Command::parse("/timer 25m tea time"),
+            Command::Timer { duration, label } if duration == Duration::from_secs(1500) && label == "tea time"
+        ));
+        assert!(matches!(Command::parse("/timer soon"), Command::Chat { .. }));
+    }
+
+    #[test]
+    fn test_parse_memory() {
+        assert!(matches!(Command::parse("/memory"), Command::Memory { query } if query.is_empty()));
+        assert!(matches!(Command::parse("/mem  wifi password"), Command::Memory { query } if query == "wifi password"));
+    }
+
     #[test]
     fn test_parse_clear() {
         match Command::parse("/clear") {
@@ -106,6 +619,121 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_parse_cache() {
+        assert!(matches!(Command::parse("/cache"), Command::Cache(CacheCommand::Stats)));
+        assert!(matches!(Command::parse("/cache clear"), Command::Cache(CacheCommand::Clear)));
+        assert!(matches!(Command::parse("/cache purge"), Command::Chat { .. }));
+    }
+
+    #[test]
+    fn test_parse_saved() {
+        let saved = |input: &str| match Command::parse(input) {
+            Command::Saved(saved) => Some(saved),
+            _ => None,
+        };
+        assert_eq!(saved("/saved invoices"), Some(SavedCommand::Run { name: "invoices".into() }));
+        assert_eq!(saved("/saved"), Some(SavedCommand::List));
+        let Some(SavedCommand::Add { name, search }) = saved("/saved add invoices /file kind:pdf invoice") else {
+            panic!("Expected /saved add");
+        };
+        assert_eq!((name.as_str(), search.prompt()), ("invoices", "/file kind:pdf invoice".to_string()));
+        assert_eq!(saved("/saved rm invoices"), Some(SavedCommand::Remove { name: "invoices".into() }));
+        assert_eq!(saved("/saved add invoices /timer 5m"), None);
+        assert_eq!(saved("/saved add invoices"), None);
+        assert_eq!(saved("/saved rm"), None);
+    }
+
+    #[test]
+    fn test_parse_bookmark() {
+        let bookmark = |input: &str| match Command::parse(input) {
+            Command::Bookmark(bookmark) => Some(bookmark),
+            _ => None,
+        };
+        assert_eq!(bookmark("/bm"), Some(BookmarkCommand::List));
+        assert_eq!(
+            bookmark("/bm add ~/work/src"),
+            Some(BookmarkCommand::Add { path: "~/work/src".into(), shortcut: None })
+        );
+        assert_eq!(
+            bookmark("/bm add ~/My Projects ,p"),
+            Some(BookmarkCommand::Add { path: "~/My Projects".into(), shortcut: Some("p".into()) })
+        );
+        assert_eq!(bookmark("/bookmarks rm ,p"), Some(BookmarkCommand::Remove { target: ",p".into() }));
+        assert_eq!(bookmark("/bm add"), None);
+        assert_eq!(bookmark("/bm rm"), None);
+    }
+
+    #[test]
+    fn test_parse_debug() {
+        assert!(matches!(Command::parse("/debug net"), Command::Debug(DebugCommand::Net)));
+        assert!(matches!(Command::parse("/debug"), Command::Chat { .. }));
+    }
+
+    #[test]
+    fn test_dnd() {
+        assert!(matches!(Command::parse("/dnd"), Command::Dnd(DndCommand::Status)));
+        assert!(matches!(Command::parse("/dnd off"), Command::Dnd(DndCommand::Off)));
+        assert!(matches!(Command::parse("/focus 25m"), Command::Dnd(DndCommand::Focus(d)) if d == Duration::from_secs(1500)));
+        assert!(matches!(Command::parse("/dnd later"), Command::Chat { .. }));
+    }
+
+    #[test]
+    fn test_agenda() {
+        assert!(matches!(Command::parse("/agenda"), Command::Agenda { days: None }));
+        assert!(matches!(Command::parse("/agenda 14"), Command::Agenda { days: Some(14) }));
+        assert!(matches!(Command::parse("/agenda 0"), Command::Chat { .. }));
+        assert!(matches!(Command::parse("/agenda soon"), Command::Chat { .. }));
+    }
+
+    #[test]
+    fn test_parse_hash() {
+        match Command::parse("/hash blake3 ~/Downloads/disk image.iso") {
+            Command::Hash { algorithm, target } => {
+                assert_eq!(algorithm, Algorithm::Blake3);
+                assert_eq!(target, "~/Downloads/disk image.iso");
+            }
+            other => panic!("Expected Hash, got {:?}", other),
+        }
+        // Algorithm defaults to SHA-256
+        assert!(matches!(
+            Command::parse("/hash hello"),
+            Command::Hash { algorithm: Algorithm::Sha256, .. }
+        ));
+        assert!(matches!(Command::parse("/hash md5"), Command::Chat { .. }));
+    }
+
+    #[test]
+    fn test_parse_transform() {
+        match Command::parse("/b64 decode aGk=") {
+            Command::Transform { transform, text } => {
+                assert_eq!(transform, Transform::Base64Decode);
+                assert_eq!(text, "aGk=");
+            }
+            other => panic!("Expected Transform, got {:?}", other),
+        }
+        // No text: the caller falls back to the clipboard
+        assert!(matches!(
+            Command::parse("/json fmt"),
+            Command::Transform { transform: Transform::JsonFormat, ref text } if text.is_empty()
+        ));
+        assert!(matches!(
+            Command::parse("/lorem 3"),
+            Command::Transform { transform: Transform::Lorem(3), .. }
+        ));
+        assert!(matches!(Command::parse("/lorem many"), Command::Chat { .. }));
+        assert!(matches!(Command::parse("/url"), Command::Chat { .. }));
+    }
+
+    #[test]
+    fn test_parse_ask_clip() {
+        match Command::parse("/ask-clip explain this error") {
+            Command::AskClip { question } => assert_eq!(question, "explain this error"),
+            other => panic!("Expected AskClip, got {:?}", other),
+        }
+        assert!(matches!(Command::parse("/ask-clip"), Command::Chat { .. }));
+    }
+
     #[test]
     fn test_parse_chat() {
         match Command::parse("Hello world") {
@@ -113,4 +741,50 @@ mod tests {
             _ => panic!("Expected Chat"),
         }
     }
+
+    #[test]
+    fn test_parse_alias() {
+        assert_eq!(
+            match Command::parse("/alias add ff /launch firefox") {
+                Command::Alias(cmd) => cmd,
+                other => panic!("Expected Alias, got {:?}", other),
+            },
+            AliasCommand::Add { name: "ff".into(), expansion: "/launch firefox".into() }
+        );
+        assert!(matches!(Command::parse("/alias"), Command::Alias(AliasCommand::List)));
+        assert!(matches!(Command::parse("/alias rm ff"), Command::Alias(AliasCommand::Remove { .. })));
+        assert!(matches!(Command::parse("/alias add ff"), Command::Chat { .. }));
+    }
+
+    #[test]
+    fn test_expand_alias() {
+        let aliases = BTreeMap::from([
+            ("ff".to_string(), "/launch firefox".to_string()),
+            ("gpt".to_string(), "/providers openai gpt-4o".to_string()),
+        ]);
+        assert_eq!(expand_alias("ff", &aliases).as_deref(), Some("/launch firefox"));
+        assert_eq!(expand_alias("gpt  ", &aliases).as_deref(), Some("/providers openai gpt-4o"));
+        assert_eq!(expand_alias("ff private", &aliases).as_deref(), Some("/launch firefox private"));
+        assert_eq!(expand_alias("ffx", &aliases), None);
+        assert!(matches!(
+            Command::parse(&expand_alias("gpt", &aliases).unwrap()),
+            Command::Providers { provider: Some(_), model: Some(_) }
+        ));
+    }
+
+    #[test]
+    fn test_validate_alias_name() {
+        assert!(validate_alias_name("ff").is_ok());
+        assert!(validate_alias_name("/ff").is_err());
+        assert!(validate_alias_name("f f").is_err());
+    }
+
+    #[test]
+    fn test_descriptions_are_translated() {
+        for spec in COMMANDS {
+            assert!(!spec.description().starts_with("command-"), "{} has no description", spec.name);
+        }
+        assert!(Command::help_text().starts_with("Available Commands:"));
+        assert!(Command::help_text().contains("({input}, {clipboard}, {selection})"));
+    }
 }
@@ -0,0 +1,176 @@
+//! Quick controls: volume, brightness and night light
+//!
+//! "volume", "brightness" and "night light" list controls that adjust in
+//! place: sliders move with Left/Right, the night light toggles with Enter.
+
+use crate::native::{audio, brightness};
+
+/// Percentage points moved by Left/Right (Shift+Left/Right moves 1)
+pub const STEP: i8 = 5;
+
+/// Which controls a query asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlQuery {
+    Volume,
+    Brightness,
+    NightLight,
+}
+
+impl ControlQuery {
+    /// Recognize a control query (whole prompt, case-insensitive)
+    pub fn parse(query: &str) -> Option<Self> {
+        match query.trim().to_lowercase().as_str() {
+            "volume" | "vol" | "sound" => Some(Self::Volume),
+            "brightness" | "backlight" | "screen brightness" => Some(Self::Brightness),
+            "night light" | "nightlight" | "night mode" => Some(Self::NightLight),
+            _ => None,
+        }
+    }
+}
+
+/// What a control adjusts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// Default audio output
+    Volume,
+    /// sysfs backlight device
+    Backlight(String),
+    /// First DDC/CI monitor
+    External,
+    NightLight,
+}
+
+impl Target {
+    /// Result id for this control
+    pub fn encode(&self) -> String {
+        match self {
+            Self::Volume => String::from("volume"),
+            Self::Backlight(name) => format!("backlight\t{}", name),
+            Self::External => String::from("ddc"),
+            Self::NightLight => String::from("nightlight"),
+        }
+    }
+
+    /// Inverse of `encode`
+    pub fn decode(id: &str) -> Option<Self> {
+        match id.split_once('\t') {
+            Some(("backlight", name)) => Some(Self::Backlight(name.to_string())),
+            Some(_) => None,
+            None => match id {
+                "volume" => Some(Self::Volume),
+                "ddc" => Some(Self::External),
+                "nightlight" => Some(Self::NightLight),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// A control and its current state
+#[derive(Debug, Clone, PartialEq)]
+pub struct Control {
+    pub target: Target,
+    pub label: String,
+    pub kind: ControlKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlKind {
+    /// Percentage shown as a slider
+    Level(u8),
+    Toggle(bool),
+}
+
+/// Controls matching `query` (blocking: runs wpctl/ddcutil/gsettings)
+pub fn list(query: ControlQuery) -> Vec<Control> {
+    let mut controls = Vec::new();
+    match query {
+        ControlQuery::Volume => match audio::volume() {
+            Ok((percent, muted)) => controls.push(Control {
+                target: Target::Volume,
+                label: String::from(if muted { "Volume (muted)" } else { "Volume" }),
+                kind: ControlKind::Level(percent),
+            }),
+            Err(e) => tracing::warn!("Volume unavailable: {}", e),
+        },
+        ControlQuery::Brightness => {
+            for light in brightness::backlights() {
+                controls.push(Control {
+                    label: format!("Brightness · {}", light.name),
+                    target: Target::Backlight(light.name),
+                    kind: ControlKind::Level(light.percent),
+                });
+            }
+            if let Some(percent) = brightness::external_brightness() {
+                controls.push(Control {
+                    target: Target::External,
+                    label: String::from("Brightness · External monitor"),
+                    kind: ControlKind::Level(percent),
+                });
+            }
+        }
+        ControlQuery::NightLight => {}
+    }
+    // Night light sits next to brightness since both are about the screen
+    if matches!(query, ControlQuery::Brightness | ControlQuery::NightLight) {
+        if let Some(enabled) = brightness::night_light() {
+            controls.push(Control {
+                target: Target::NightLight,
+                label: String::from("Night Light"),
+                kind: ControlKind::Toggle(enabled),
+            });
+        }
+    }
+    controls
+}
+
+/// Apply a slider level (blocking)
+pub fn set_level(target: &Target, percent: u8) -> Result<(), String> {
+    match target {
+        Target::Volume => audio::set_volume(percent),
+        Target::Backlight(name) => brightness::set_backlight(name, percent),
+        Target::External => brightness::set_external_brightness(percent),
+        Target::NightLight => Err(String::from("Night light is a toggle")),
+    }
+}
+
+/// Flip a toggle, returning its new state
+pub fn toggle(target: &Target, enabled: bool) -> Result<bool, String> {
+    match target {
+        Target::NightLight => brightness::set_night_light(!enabled).map(|_| !enabled),
+        _ => Err(String::from("Not a toggle")),
+    }
+}
+
+/// Move a level by `delta` percentage points, staying within 0-100
+pub fn step(percent: u8, delta: i8) -> u8 {
+    (percent as i16 + delta as i16).clamp(0, 100) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(ControlQuery::parse("Volume"), Some(ControlQuery::Volume));
+        assert_eq!(ControlQuery::parse(" brightness "), Some(ControlQuery::Brightness));
+        assert_eq!(ControlQuery::parse("night light"), Some(ControlQuery::NightLight));
+        assert_eq!(ControlQuery::parse("volume up"), None);
+    }
+
+    #[test]
+    fn test_target_roundtrip() {
+        for target in [Target::Volume, Target::Backlight("intel_backlight".into()), Target::External] {
+            assert_eq!(Target::decode(&target.encode()), Some(target));
+        }
+        assert_eq!(Target::decode("sink\tfoo"), None);
+    }
+
+    #[test]
+    fn test_step_clamps() {
+        assert_eq!(step(50, STEP), 55);
+        assert_eq!(step(98, STEP), 100);
+        assert_eq!(step(3, -STEP), 0);
+    }
+}
@@ -0,0 +1,94 @@
+//! Crash recovery
+//!
+//! Checkpoints volatile state (clipboard history, app cache) to disk every
+//! minute and from a panic hook. A clean exit deletes the snapshot, so one
+//! that's still there at the next start was left by a crash and is restored.
+//! Copies from ignored apps and password managers are never checkpointed.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use ruty_core::apps::Application;
+use ruty_core::clipboard::ClipboardItem;
+use ruty_core::snapshot::Snapshot;
+
+/// How often volatile state is written to disk
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Latest scanned app list (kept here so the panic hook can reach it)
+static APP_CACHE: Mutex<Vec<Application>> = Mutex::new(Vec::new());
+
+/// App list recovered from a crash, consumed by the first AppIndexer
+static RECOVERED_APPS: Mutex<Option<Vec<Application>>> = Mutex::new(None);
+
+/// Clipboard history recovered from a crash, consumed by the clipboard manager
+static RECOVERED_CLIPBOARD: Mutex<Option<Vec<ClipboardItem>>> = Mutex::new(None);
+
+/// Load state from a crash snapshot, then install the panic hook and
+/// start periodic checkpointing. Call before creating `Services`.
+pub fn init() {
+    if let Some(snapshot) = Snapshot::load() {
+        tracing::warn!(
+            "Recovered from crash: restoring {} clipboard items and {} apps from snapshot",
+            snapshot.clipboard.len(),
+            snapshot.apps.len()
+        );
+        *RECOVERED_CLIPBOARD.lock().unwrap() = Some(snapshot.clipboard);
+        if !snapshot.apps.is_empty() {
+            *RECOVERED_APPS.lock().unwrap() = Some(snapshot.apps);
+        }
+    }
+
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        match capture().save() {
+            Ok(()) => eprintln!("Ruty panicked; state saved to {:?}", Snapshot::path()),
+            Err(e) => eprintln!("Ruty panicked; failed to save state: {}", e),
+        }
+        default_hook(info);
+    }));
+
+    std::thread::spawn(|| loop {
+        std::thread::sleep(CHECKPOINT_INTERVAL);
+        if let Err(e) = capture().save() {
+            tracing::warn!("Checkpoint failed: {}", e);
+        }
+    });
+}
+
+/// Record the current app list after a scan
+pub fn record_apps(apps: &[Application]) {
+    if let Ok(mut cache) = APP_CACHE.lock() {
+        *cache = apps.to_vec();
+    }
+}
+
+/// Take the app list recovered from a crash snapshot, if any
+pub fn take_recovered_apps() -> Option<Vec<Application>> {
+    RECOVERED_APPS.lock().ok()?.take()
+}
+
+/// Take the clipboard history recovered from a crash snapshot, if any
+pub fn take_recovered_clipboard() -> Option<Vec<ClipboardItem>> {
+    RECOVERED_CLIPBOARD.lock().ok()?.take()
+}
+
+/// Delete the snapshot on a clean exit, so clipboard history isn't left on
+/// disk and the next start doesn't restore it
+pub fn mark_clean_shutdown() {
+    if let Err(e) = Snapshot::remove() {
+        tracing::warn!("{}", e);
+    }
+}
+
+/// Collect current state without blocking on locks a panicking thread may hold
+fn capture() -> Snapshot {
+    let clipboard = crate::get_services()
+        .and_then(|s| s.clipboard().try_history_to_save())
+        .unwrap_or_default();
+    let apps = APP_CACHE
+        .try_lock()
+        .map(|apps| apps.clone())
+        .unwrap_or_default();
+    Snapshot::new(clipboard, apps)
+}
@@ -0,0 +1,308 @@
+//! Detail panel content
+//!
+//! Tab on a selected result opens a side panel with metadata. Each
+//! `ResultCategory` has its own loader; categories without one fall back to
+//! the result's title and subtitle.
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::time::SystemTime;
+
+use iced::widget::image;
+use ruty_core::archives::{self, ArchiveKind, Entry};
+use ruty_core::calc;
+use ruty_core::qr;
+
+use crate::app::{ResultCategory, SearchResult};
+use crate::services::Services;
+
+/// Archive entries listed before "… and N more"
+const MAX_LISTED: usize = 200;
+/// Recent calculations listed under a calculator result
+const CALCULATIONS_LISTED: usize = 10;
+
+/// Metadata shown in the detail panel
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detail {
+    /// Id of the result this was loaded for
+    pub id: String,
+    pub title: String,
+    pub fields: Vec<(&'static str, String)>,
+    /// Long-form content (e.g. a full clipboard entry)
+    pub body: Option<String>,
+    /// Picture shown above the body (a `/qr` code)
+    pub image: Option<image::Handle>,
+}
+
+/// Load details for a result
+pub fn load(services: &Services, result: &SearchResult) -> Detail {
+    let loaded = match result.category {
+        ResultCategory::App => app_detail(services, &result.id),
+        ResultCategory::File => file_detail(&result.id),
+        ResultCategory::Clipboard => clipboard_detail(services, &result.id),
+        // Keyed by the row id so moving between the archive's rows doesn't relist it
+        ResultCategory::Archive => match result.id.split_once('\t') {
+            Some((_, path)) => file_detail(path).map(|detail| Detail {
+                id: result.id.clone(),
+                ..detail
+            }),
+            None => Ok(generic_detail(result)),
+        },
+        // /qr rows are `qr-<action>\t<text>`
+        ResultCategory::Action if result.id.starts_with("qr-") => match result.id.split_once('\t') {
+            Some((_, text)) if !text.is_empty() => qr_detail(&result.id, text),
+            _ => Ok(generic_detail(result)),
+        },
+        // The row only previews the first line
+        ResultCategory::Transform => Ok(Detail {
+            body: Some(result.id.clone()),
+            ..generic_detail(result)
+        }),
+        ResultCategory::Calculator => Ok(calculator_detail(services, result)),
+        _ => Ok(generic_detail(result)),
+    };
+    loaded.unwrap_or_else(|e| Detail {
+        fields: vec![("Error", e)],
+        ..generic_detail(result)
+    })
+}
+
+fn generic_detail(result: &SearchResult) -> Detail {
+    Detail {
+        id: result.id.clone(),
+        title: result.title.clone(),
+        fields: vec![("Type", result.category.as_str().to_string())],
+        body: (!result.subtitle.is_empty()).then(|| result.subtitle.clone()),
+        image: None,
+    }
+}
+
+fn app_detail(services: &Services, id: &str) -> Result<Detail, String> {
+    let app = services
+        .app(id)
+        .ok_or_else(|| format!("Application not found: {}", id))?;
+
+    let mut fields = vec![
+        ("Desktop file", app.desktop_file.display().to_string()),
+        ("Exec", app.exec.clone()),
+    ];
+    if !app.categories.is_empty() {
+        fields.push(("Categories", app.categories.join(", ")));
+    }
+    if !app.keywords.is_empty() {
+        fields.push(("Keywords", app.keywords.join(", ")));
+    }
+    if app.terminal {
+        fields.push(("Terminal", "yes".to_string()));
+    }
+
+    Ok(Detail {
+        id: id.to_string(),
+        title: app.name,
+        fields,
+        body: app.comment.or(app.generic_name),
+        image: None,
+    })
+}
+
+fn file_detail(path: &str) -> Result<Detail, String> {
+    let meta = fs::metadata(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    let name = Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string());
+
+    let mut fields = vec![
+        ("Path", path.to_string()),
+        ("Kind", if meta.is_dir() { "Folder" } else { "File" }.to_string()),
+    ];
+    if meta.is_file() {
+        fields.push(("Size", format_size(meta.len())));
+    }
+    if let Ok(modified) = meta.modified() {
+        fields.push(("Modified", format_age(modified)));
+    }
+    fields.push(("Permissions", format_mode(meta.permissions().mode())));
+
+    let mut body = None;
+    if ArchiveKind::from_path(Path::new(path)).is_some() {
+        match archives::list(Path::new(path)) {
+            Ok(entries) => {
+                let files = entries.iter().filter(|e| !e.is_dir).count();
+                fields.push(("Contents", format!("{} files", files)));
+                body = Some(format_listing(&entries));
+            }
+            Err(e) => fields.push(("Contents", e)),
+        }
+    }
+
+    Ok(Detail {
+        id: path.to_string(),
+        title: name,
+        fields,
+        body,
+        image: None,
+    })
+}
+
+/// Archive entries, one per line with sizes, cut at `MAX_LISTED`
+fn format_listing(entries: &[Entry]) -> String {
+    let mut lines: Vec<String> = entries
+        .iter()
+        .take(MAX_LISTED)
+        .map(|entry| {
+            if entry.is_dir || entry.size == 0 {
+                entry.path.clone()
+            } else {
+                format!("{}  ({})", entry.path, format_size(entry.size))
+            }
+        })
+        .collect();
+    if entries.len() > MAX_LISTED {
+        lines.push(format!("… and {} more", entries.len() - MAX_LISTED));
+    }
+    lines.join("\n")
+}
+
+/// Clipboard results use the entry's id as theirs
+fn clipboard_detail(services: &Services, id: &str) -> Result<Detail, String> {
+    let entry: u64 = id.parse().map_err(|_| format!("Invalid clipboard id: {}", id))?;
+    let item = services
+        .clipboard()
+        .get_history()
+        .into_iter()
+        .find(|item| item.id == entry)
+        .ok_or("Clipboard entry no longer in history")?;
+
+    let copied = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(item.timestamp);
+    Ok(Detail {
+        id: id.to_string(),
+        title: "Clipboard entry".to_string(),
+        fields: vec![
+            ("Copied", format_age(copied)),
+            ("Length", format!("{} chars", item.content.chars().count())),
+        ],
+        body: Some(item.content),
+        image: None,
+    })
+}
+
+/// The result with `ans`, the variables and the session's recent calculations
+fn calculator_detail(services: &Services, result: &SearchResult) -> Detail {
+    let calculator = services.calculator();
+    let mut fields = vec![("Expression", result.id.clone())];
+    if let Some(ans) = calculator.ans() {
+        fields.push(("ans", calc::format_value(ans)));
+    }
+    let mut body = String::new();
+    if !calculator.variables().is_empty() {
+        body.push_str("Variables\n");
+        for (name, value) in calculator.variables() {
+            body.push_str(&format!("  {} = {}\n", name, calc::format_value(*value)));
+        }
+    }
+    let history: Vec<_> = calculator.history().take(CALCULATIONS_LISTED).collect();
+    if !history.is_empty() {
+        body.push_str(if body.is_empty() { "History\n" } else { "\nHistory\n" });
+        for calculation in history {
+            body.push_str(&format!("  {} → {}\n", calculation.input(), calc::format_value(calculation.value)));
+        }
+    }
+    Detail {
+        id: result.id.clone(),
+        title: result.title.clone(),
+        fields,
+        body: (!body.is_empty()).then_some(body),
+        image: None,
+    }
+}
+
+/// The QR code for `text`
+fn qr_detail(id: &str, text: &str) -> Result<Detail, String> {
+    let png = qr::render(text)?;
+    Ok(Detail {
+        id: id.to_string(),
+        title: "QR code".to_string(),
+        fields: vec![("Length", format!("{} chars", text.chars().count()))],
+        body: Some(text.to_string()),
+        image: Some(image::Handle::from_bytes(png)),
+    })
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// "5 min ago" style age
+pub fn format_age(time: SystemTime) -> String {
+    let secs = match SystemTime::now().duration_since(time) {
+        Ok(age) => age.as_secs(),
+        Err(_) => return "just now".to_string(),
+    };
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", secs / 60),
+        3600..=86_399 => format!("{} h ago", secs / 3600),
+        _ => format!("{} days ago", secs / 86_400),
+    }
+}
+
+/// `rwxr-xr-x` style permission string
+fn format_mode(mode: u32) -> String {
+    let mut out = String::with_capacity(9);
+    for shift in [6, 3, 0] {
+        let bits = (mode >> shift) & 0o7;
+        out.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        out.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        out.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+    }
+
+    #[test]
+    fn test_format_mode() {
+        assert_eq!(format_mode(0o755), "rwxr-xr-x");
+        assert_eq!(format_mode(0o100644), "rw-r--r--");
+    }
+
+    #[test]
+    fn test_format_listing() {
+        let entries: Vec<Entry> = (0..MAX_LISTED + 5)
+            .map(|i| Entry { path: format!("f{}", i), size: 2048, is_dir: false })
+            .collect();
+        let listing = format_listing(&entries);
+        assert!(listing.starts_with("f0  (2.0 KB)\n"));
+        assert!(listing.ends_with("… and 5 more"));
+    }
+
+    #[test]
+    fn test_file_detail() {
+        let detail = file_detail(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")).unwrap();
+        assert_eq!(detail.title, "Cargo.toml");
+        assert!(detail.fields.iter().any(|(k, _)| *k == "Size"));
+        assert!(file_detail("/nonexistent/ruty").is_err());
+    }
+}
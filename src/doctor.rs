@@ -0,0 +1,123 @@
+//! `ruty doctor` and `/doctor`
+//!
+//! The environment checks of `ruty_core::doctor`, plus the ones that need
+//! the daemon's own pieces: how hotkeys reach it, the AI backend and the
+//! ports it listens on.
+
+use std::time::Duration;
+
+use ruty_core::autostart;
+use ruty_core::config::Config;
+use ruty_core::doctor::{self, Check, Session};
+
+use crate::backend::api::BackendClient;
+use crate::backend::sidecar::{self, BACKEND_PORT};
+use crate::hotkey;
+use crate::native::apps::AppIndexer;
+use crate::native::compositor::Compositor;
+use crate::rpc;
+
+/// How long the backend gets to answer its health check
+const BACKEND_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Run every check, in the order they're reported
+pub async fn run() -> Vec<Check> {
+    let session = Session::detect();
+    let compositor = Compositor::detect();
+    let desktop = match &compositor {
+        Compositor::Hyprland => "Hyprland".to_string(),
+        Compositor::Sway => "Sway".to_string(),
+        Compositor::Other(desktop) => desktop.clone(),
+    };
+    let mut checks = vec![doctor::session_check(session, &desktop)];
+    let hotkeys = tokio::task::spawn_blocking(move || hotkeys(session, &compositor)).await;
+    checks.extend(hotkeys.ok());
+    checks.extend(doctor::tools(session, doctor::has_program));
+    let (backend, backend_up) = backend().await;
+    checks.push(backend);
+    checks.extend(ports(backend_up).await);
+    checks.push(doctor::config_check(std::fs::read_to_string(Config::path()).ok().as_deref()));
+    checks.push(doctor::sandbox_check(&Config::load(), doctor::has_program));
+    let icons = tokio::task::spawn_blocking(|| doctor::icons_check(AppIndexer::new().all())).await;
+    checks.extend(icons.ok());
+    checks
+}
+
+/// How hotkeys reach the daemon in this session
+fn hotkeys(session: Session, compositor: &Compositor) -> Check {
+    match session {
+        Session::X11 => Check::ok("Hotkeys", "X11 key grabs"),
+        Session::Wayland => match hotkey::portal_version() {
+            Ok(version) => Check::ok(
+                "Hotkeys",
+                format!("GlobalShortcuts portal v{} (plain combos; chords and modifier taps need X11)", version),
+            ),
+            Err(e) => Check::warn(
+                "Hotkeys",
+                format!("No GlobalShortcuts portal ({}); only a compositor keybind can open Ruty", e),
+                match compositor {
+                    Compositor::Hyprland => "Add `bind = SUPER, SPACE, exec, ruty open` to hyprland.conf",
+                    Compositor::Sway => "Add `bindsym Mod4+space exec ruty open` to the sway config",
+                    Compositor::Other(_) => "Bind a key to `ruty open` in your desktop's keyboard settings",
+                },
+            ),
+        },
+        Session::None => Check::warn("Hotkeys", "No display to take keys from", "Start Ruty from the graphical session"),
+    }
+}
+
+/// Whether the AI backend answers, and the check saying so
+async fn backend() -> (Check, bool) {
+    let url = sidecar::backend_url();
+    let error = match tokio::time::timeout(BACKEND_TIMEOUT, BackendClient::new().health_check()).await {
+        Ok(Ok(health)) => {
+            let detail = format!("{} answers ({}, {})", url, health.provider, health.model);
+            return (Check::ok("Backend", detail), true);
+        }
+        Ok(Err(e)) => e,
+        Err(_) => format!("no answer within {:?}", BACKEND_TIMEOUT),
+    };
+    let fix = if sidecar::external_backend_url().is_some() {
+        format!("Start the backend {} points at, or unset it to use the bundled one", sidecar::BACKEND_URL_ENV)
+    } else {
+        "The daemon runs it as `python -m ruty.server`: check that works, then use the Restart backend action"
+            .to_string()
+    };
+    (Check::fail("Backend", format!("{} isn't answering: {}", url, error), fix), false)
+}
+
+/// Whether the ports the daemon and backend listen on are theirs
+async fn ports(backend_up: bool) -> Vec<Check> {
+    let daemon_up = rpc::client::is_daemon_running().await;
+    let daemon = if daemon_up {
+        Some("the Ruty daemon")
+    } else if autostart::status().socket {
+        Some("systemd, for socket activation")
+    } else {
+        None
+    };
+    let mut checks = vec![doctor::port_check(
+        "Daemon port",
+        &rpc::listen_addr(),
+        daemon,
+        &format!("Stop the program holding it, or move Ruty with {}=<port>", rpc::PORT_ENV),
+    )];
+    if sidecar::external_backend_url().is_none() {
+        checks.push(doctor::port_check(
+            "Backend port",
+            &format!("127.0.0.1:{}", BACKEND_PORT),
+            backend_up.then_some("the AI backend"),
+            "Stop the program holding it; the backend can't start until it's free",
+        ));
+    }
+    let api = crate::get_services().map_or_else(|| Config::load().api, |services| services.config().api.clone());
+    if api.enabled {
+        checks.push(doctor::port_check(
+            "HTTP API port",
+            &format!("127.0.0.1:{}", api.port),
+            daemon_up.then_some("the Ruty daemon"),
+            "Pick another `port` under [api] in config.toml",
+        ));
+    }
+    checks
+}
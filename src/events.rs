@@ -0,0 +1,209 @@
+//! Internal event bus
+//!
+//! Subsystems publish what happened (a copy was recorded, the app index was
+//! rebuilt, the backend stopped answering) to one broadcast channel, and
+//! whoever cares subscribes: the UI, user hooks and desktop notifications.
+//! Publishers don't know their consumers, and publishing never blocks;
+//! consumers that fall behind skip what they missed.
+
+use std::collections::BTreeSet;
+use std::process::Command;
+use std::sync::LazyLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use iced::futures::stream;
+use iced::Subscription;
+use ruty_core::audit;
+use ruty_core::hooks::{self, HookEvent};
+use ruty_core::permissions::{self, Capability};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::native::apps::AppChanges;
+use crate::native::clipboard::ClipboardItem;
+
+/// Events a consumer can fall behind by before it starts missing them
+const CAPACITY: usize = 64;
+/// Don't repeat the "backend is down" notification more often than this
+const BACKEND_DOWN_RENOTIFY: Duration = Duration::from_secs(300);
+
+static BUS: LazyLock<broadcast::Sender<Event>> = LazyLock::new(|| broadcast::channel(CAPACITY).0);
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// The launcher window was brought up
+    WindowShown,
+    AppLaunched { id: String, name: String },
+    /// The clipboard monitor recorded a copy
+    ClipboardCaptured(ClipboardItem),
+    /// The app index was (re)built
+    IndexRefreshed,
+    /// A rescan found apps installed or removed since the last one
+    AppsChanged(AppChanges),
+    /// A chat request couldn't reach the AI backend
+    BackendDown(String),
+    AiResponseDone { session_id: String, prompt: String, response: String, tools_used: Vec<String> },
+    /// config.toml was edited and applied; lists the changed settings that
+    /// only take effect on restart
+    ConfigReloaded(Vec<&'static str>),
+    /// config.toml was edited but couldn't be loaded, so the old config stays
+    ConfigInvalid(String),
+    /// A provider (see `permissions`) was held back for lack of a grant
+    PermissionNeeded(String),
+}
+
+impl Event {
+    /// The event as user hooks see it, for those they can run on
+    fn hook(&self) -> Option<HookEvent> {
+        Some(match self.clone() {
+            Self::WindowShown => HookEvent::WindowShown,
+            Self::AppLaunched { id, name } => HookEvent::AppLaunched { id, name },
+            Self::ClipboardCaptured(item) => HookEvent::ClipboardCaptured {
+                content: item.content,
+                kind: item.kind,
+                language: item.language,
+            },
+            Self::AiResponseDone { session_id, prompt, response, tools_used } => {
+                HookEvent::AiResponseDone { session_id, prompt, response, tools_used }
+            }
+            Self::IndexRefreshed
+            | Self::AppsChanged(_)
+            | Self::BackendDown(_)
+            | Self::ConfigReloaded(_)
+            | Self::ConfigInvalid(_)
+            | Self::PermissionNeeded(_) => return None,
+        })
+    }
+}
+
+/// Tell every subscriber about `event`
+pub fn publish(event: Event) {
+    // Err only means nobody is subscribed
+    let _ = BUS.send(event);
+}
+
+pub fn subscribe() -> broadcast::Receiver<Event> {
+    BUS.subscribe()
+}
+
+/// Events for the UI
+pub fn subscription() -> Subscription<Event> {
+    Subscription::run(|| {
+        stream::unfold(subscribe(), |mut events| async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => return Some((event, events)),
+                    Err(RecvError::Lagged(missed)) => tracing::debug!("UI missed {} events", missed),
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+    })
+}
+
+/// Clipboard monitor listener
+pub fn clipboard_captured(item: &ClipboardItem) {
+    publish(Event::ClipboardCaptured(item.clone()));
+}
+
+/// Run the `[hooks]` commands for each event from now on, as configured
+/// when the event happens. Until they're granted what they need, they don't
+/// run and the UI is asked (once for each set of missing capabilities) to
+/// prompt for it.
+pub fn start_hooks() {
+    let mut asked: BTreeSet<Capability> = BTreeSet::new();
+    consume("hooks", move |event| {
+        if let (Some(hook), Some(services)) = (event.hook(), crate::get_services()) {
+            let config = services.config();
+            let commands = config.hooks.commands(&hook);
+            if commands.is_empty() {
+                return;
+            }
+            let missing = permissions::missing(&config, permissions::HOOKS);
+            if !missing.is_empty() {
+                tracing::debug!("Hooks not run for {}: not allowed to {}", hook.name(), permissions::describe(&missing));
+                if missing != asked {
+                    publish(Event::PermissionNeeded(permissions::HOOKS.to_string()));
+                    asked = missing;
+                }
+                return;
+            }
+            // Revoked later, they ask again
+            asked.clear();
+            let sandbox = config.hooks.sandbox.as_ref().unwrap_or(&config.sandbox);
+            hooks::run(commands, &hook, sandbox);
+        }
+    });
+}
+
+/// Append launches and the AI's tool calls to the audit log (the rest is
+/// recorded where it runs)
+pub fn start_audit() {
+    consume("audit", |event| match event {
+        Event::AppLaunched { id, name } => audit::record(audit::Kind::Launch, name, id),
+        Event::AiResponseDone { prompt, tools_used, .. } => {
+            let prompt: String = prompt.lines().next().unwrap_or_default().chars().take(80).collect();
+            for tool in tools_used {
+                audit::record(audit::Kind::Tool, tool, &prompt);
+            }
+        }
+        _ => {}
+    });
+}
+
+/// Desktop notifications for events worth interrupting for, such as the
+/// backend going down while nobody may be looking at the window
+pub fn start_notifications() {
+    let mut last_down: Option<Instant> = None;
+    consume("notifications", move |event| {
+        if let Event::BackendDown(error) = event {
+            if last_down.is_some_and(|at| at.elapsed() < BACKEND_DOWN_RENOTIFY) {
+                return;
+            }
+            last_down = Some(Instant::now());
+            notify("AI backend is down", error);
+        }
+    });
+}
+
+/// Desktop notification (best effort)
+fn notify(summary: &str, body: &str) {
+    let spawned = Command::new("notify-send").args(["--app-name=Ruty", summary, body]).spawn();
+    if let Err(e) = spawned {
+        tracing::debug!("notify-send unavailable: {}", e);
+    }
+}
+
+/// Handle events on a thread of their own
+fn consume(name: &'static str, mut handle: impl FnMut(&Event) + Send + 'static) {
+    let mut events = subscribe();
+    thread::spawn(move || loop {
+        match events.blocking_recv() {
+            Ok(event) => handle(&event),
+            Err(RecvError::Lagged(missed)) => tracing::warn!("{} missed {} events", name, missed),
+            Err(RecvError::Closed) => return,
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_reaches_subscribers() {
+        let mut events = subscribe();
+        publish(Event::AppLaunched { id: "firefox".into(), name: "Firefox".into() });
+        publish(Event::IndexRefreshed);
+
+        // Other tests may publish on the same bus meanwhile
+        let received: Vec<Event> = std::iter::from_fn(|| events.try_recv().ok()).collect();
+        let launched = received.iter().find(|event| matches!(event, Event::AppLaunched { .. })).unwrap();
+        assert_eq!(
+            launched.hook(),
+            Some(HookEvent::AppLaunched { id: "firefox".into(), name: "Firefox".into() })
+        );
+        let refreshed = received.iter().find(|event| matches!(event, Event::IndexRefreshed)).unwrap();
+        assert_eq!(refreshed.hook(), None);
+    }
+}
@@ -0,0 +1,109 @@
+//! Running workflows
+//!
+//! The `[flows]` format lives in `ruty_core::flows`; this runs a flow's steps
+//! against the daemon's services, for both `/flow` and the RunFlow RPC.
+
+use std::sync::Arc;
+
+use ruty_core::audit;
+use ruty_core::flows::{self, Flow, Step};
+use ruty_core::sandbox::{self, Sandbox};
+
+use crate::app::SearchResult;
+use crate::backend::api::{ChatRequest, ToolApproval};
+use crate::native::clipboard;
+use crate::services::{self, Services};
+
+/// Run `flow`'s steps in order, stopping at the first that fails. Returns the
+/// last step's output when it's worth showing (a query, shell command or AI
+/// reply).
+pub async fn run(services: Arc<Services>, name: String, flow: Flow) -> Result<Option<String>, String> {
+    tracing::info!("Running flow {} ({} steps)", name, flow.steps.len());
+    audit::record(audit::Kind::Flow, &name, "");
+    let config = services.config();
+    let sandbox = flow.sandbox(&config.sandbox);
+    let mut output = String::new();
+    let mut results: Vec<SearchResult> = Vec::new();
+    let mut picked: Option<SearchResult> = None;
+
+    for (i, step) in flow.steps.iter().enumerate() {
+        tracing::debug!("Flow {} step {}: {}", name, i + 1, step.describe());
+        let done = match step {
+            Step::Query(query) => {
+                let query = flows::fill(query, &output);
+                let services = services.clone();
+                results = tokio::task::spawn_blocking(move || services.search(&query, services::DEFAULT_LIMIT))
+                    .await
+                    .unwrap_or_default();
+                output = results.iter().map(|result| result.title.as_str()).collect::<Vec<_>>().join("\n");
+                Ok(())
+            }
+            Step::PickFirst => match results.first() {
+                Some(result) => {
+                    output = result.id.clone();
+                    picked = Some(result.clone());
+                    Ok(())
+                }
+                None => Err("The last query found nothing".to_string()),
+            },
+            Step::Launch(Some(app)) => {
+                let app = flows::fill(app, &output);
+                let services = services.clone();
+                tokio::task::spawn_blocking(move || match services.search_apps(&app, 1).into_iter().next() {
+                    Some(found) => services.spawn_app(&found.id).map(drop),
+                    None => Err(format!("No application matches '{}'", app)),
+                })
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|launched| launched)
+            }
+            Step::Launch(None) => match &picked {
+                Some(result) => services.launch(&result.id, result.category),
+                None => Err("Nothing picked yet; add a query and pick-first before it".to_string()),
+            },
+            Step::Copy(text) => {
+                let text = text.as_deref().map_or_else(|| output.clone(), |text| flows::fill(text, &output));
+                clipboard::copy(&text).map(drop)
+            }
+            Step::Shell(command) => {
+                audit::record(audit::Kind::Shell, command, &format!("flow {}", name));
+                shell(command, &output, sandbox).await.map(|printed| output = printed)
+            }
+            Step::AiPrompt(prompt) => {
+                let message = flows::fill(prompt, &output);
+                let request = ChatRequest {
+                    local_context: services.recall(&message),
+                    message,
+                    session_id: format!("flow-{}", name),
+                    api_keys: None,
+                    replace_last: false,
+                    // Flows act through shell steps, which ask first
+                    tool_approval: ToolApproval::Deny,
+                };
+                services.backend().chat(request).await.map(|reply| output = reply.response)
+            }
+        };
+        done.map_err(|e| format!("Step {} ({}): {}", i + 1, step.describe(), e))?;
+    }
+
+    let shown = flow.steps.last().is_some_and(Step::shows_output) && !output.trim().is_empty();
+    Ok(shown.then_some(output))
+}
+
+/// Run `command` with `sh -c` in `sandbox`, returning what it printed
+async fn shell(command: &str, output: &str, sandbox: &Sandbox) -> Result<String, String> {
+    let mut command = tokio::process::Command::from(sandbox::shell_command(sandbox, command)?);
+    let result = command
+        .env("RUTY_OUTPUT", output)
+        .output()
+        .await
+        .map_err(|e| format!("Cannot run {}: {}", command.as_std().get_program().to_string_lossy(), e))?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(match stderr.trim() {
+            "" => format!("exited with {}", result.status),
+            stderr => stderr.to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&result.stdout).trim_end().to_string())
+}
@@ -1,85 +1,294 @@
 //! Global hotkey handling using `global-hotkey` crate + Unix signals
 //!
-//! On X11: Uses global-hotkey for Super+Space
-//! On Wayland: Uses SIGUSR1 signal for system keybind integration
+//! Each action has a binding in `[hotkeys]` of config.toml: a key combo, a
+//! chord ("Super+Space, C") or a modifier double-tap ("Ctrl, Ctrl"); see
+//! `chord`. A plain combo for voice input is push-to-talk on X11: it fires
+//! on press and reports the release.
+//!
+//! On X11: global-hotkey grabs the combos, XInput2 reports modifier taps
+//! On Wayland: Uses the GlobalShortcuts portal where the desktop offers it
+//! (GNOME, KDE; single combos only), and SIGUSR1 for a compositor keybind
+//! everywhere else
 
-use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState, hotkey::{Code, HotKey, Modifiers}};
-use iced::Subscription;
-use iced::time;
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState, hotkey::HotKey};
+use ruty_core::config::HotkeysConfig;
 use signal_hook::consts::SIGUSR1;
 use signal_hook::iterator::Signals;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::OnceLock;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+mod chord;
+mod portal;
+mod taps;
+
+pub use chord::Action;
+use chord::{Binding, Chords, Step};
 
 /// Static hotkey manager (must persist for lifetime of app)
 static HOTKEY_MANAGER: OnceLock<GlobalHotKeyManager> = OnceLock::new();
 
-/// Hotkey ID for Super+Space
-static HOTKEY_ID: OnceLock<u32> = OnceLock::new();
+/// Binding state, fed by the hotkey and tap listener threads
+static CHORDS: OnceLock<Mutex<Chords>> = OnceLock::new();
+
+/// Combos currently grabbed with the manager
+static GRABBED: Mutex<Vec<HotKey>> = Mutex::new(Vec::new());
+
+/// Set by SIGUSR1, cleared when polled
+static TOGGLE_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Action fired by a binding since the last poll
+static FIRED: Mutex<Option<Action>> = Mutex::new(None);
+
+/// The voice binding, when it's a plain combo (held for push-to-talk)
+static VOICE_COMBO: Mutex<Option<HotKey>> = Mutex::new(None);
+
+/// Whether the modifier tap listener is running
+static TAPS_LISTENING: AtomicBool = AtomicBool::new(false);
 
-/// Atomic flag for SIGUSR1 signal received
-static SIGNAL_RECEIVED: AtomicBool = AtomicBool::new(false);
+/// Whether the voice combo is down
+static VOICE_HELD: AtomicBool = AtomicBool::new(false);
+
+/// Presses of the voice combo so far, to tell a release from key repeat
+static VOICE_PRESSES: AtomicUsize = AtomicUsize::new(0);
+
+/// Set when the held voice combo is let go, cleared when polled
+static VOICE_RELEASED: AtomicBool = AtomicBool::new(false);
+
+/// Key repeat arrives as release/press pairs about this close together
+const REPEAT_GAP: Duration = Duration::from_millis(50);
+
+/// Bindings of the configured actions with their config text; invalid ones
+/// are logged and skipped
+fn bindings(config: &HotkeysConfig) -> Vec<(Binding, String)> {
+    [(Action::Toggle, &config.toggle), (Action::Clipboard, &config.clipboard), (Action::Voice, &config.voice)]
+        .into_iter()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .filter_map(|(action, text)| {
+            Binding::parse(action, text)
+                .map(|binding| (binding, text.clone()))
+                .map_err(|e| tracing::warn!("Ignoring hotkey for {:?}: {}", action, e))
+                .ok()
+        })
+        .collect()
+}
 
 /// Initialize the global hotkey system (X11) and signal handler (Wayland)
 pub fn init_hotkeys() -> Result<(), String> {
+    let config = crate::get_services()
+        .map(|services| services.config().hotkeys.clone())
+        .unwrap_or_default();
+    let bindings = bindings(&config);
+    let uses_taps = install(&bindings);
+
     // Try X11 global hotkey first
     match GlobalHotKeyManager::new() {
         Ok(manager) => {
-            let hotkey = HotKey::new(Some(Modifiers::SUPER), Code::Space);
-            if let Err(e) = manager.register(hotkey) {
-                tracing::warn!("Failed to register X11 hotkey: {}", e);
-            } else {
-                HOTKEY_MANAGER.set(manager).ok();
-                HOTKEY_ID.set(hotkey.id()).ok();
-                tracing::info!("Global hotkey registered: Super+Space (X11)");
-            }
+            HOTKEY_MANAGER.set(manager).ok();
+            GlobalHotKeyEvent::set_event_handler(Some(|event: GlobalHotKeyEvent| {
+                if VOICE_COMBO.lock().unwrap().is_some_and(|combo| combo.id() == event.id) {
+                    push_to_talk(event.state);
+                    return;
+                }
+                if event.state != HotKeyState::Pressed {
+                    return;
+                }
+                let hotkey = CHORDS.get().and_then(|chords| chords.lock().unwrap().combo(event.id));
+                if let Some(hotkey) = hotkey {
+                    feed(Step::Combo(hotkey), Instant::now());
+                }
+            }));
+            sync_grabs();
         }
         Err(e) => {
             tracing::warn!("X11 hotkey manager unavailable: {}", e);
         }
     }
-    
+
+    if uses_taps {
+        listen_taps();
+    }
+
+    // Wayland: ask the desktop for the shortcuts; SIGUSR1 below still works
+    // if the portal is missing or the user declines
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let shortcuts: Vec<(Action, String)> = bindings
+            .iter()
+            .filter(|(binding, _)| binding.single_combo().is_some())
+            .map(|(binding, text)| (binding.action, text.clone()))
+            .collect();
+        if shortcuts.len() < bindings.len() {
+            tracing::info!("Chords and modifier taps need X11; the portal only binds plain combos");
+        }
+        std::thread::spawn(move || {
+            if let Err(e) = portal::run(&shortcuts, fire) {
+                tracing::warn!(
+                    "GlobalShortcuts portal unavailable: {} (bind `ruty open` in your compositor instead)",
+                    e
+                );
+            }
+        });
+    }
+
     // Also set up SIGUSR1 handler for Wayland compatibility
     std::thread::spawn(|| {
         if let Ok(mut signals) = Signals::new([SIGUSR1]) {
             tracing::info!("SIGUSR1 signal handler ready (for Wayland keybind)");
             for _ in signals.forever() {
                 tracing::info!("SIGUSR1 received - toggling window");
-                SIGNAL_RECEIVED.store(true, Ordering::SeqCst);
+                TOGGLE_REQUESTED.store(true, Ordering::SeqCst);
+                crate::wake::wake();
             }
         }
     });
-    
+
     Ok(())
 }
 
-/// Check if hotkey was pressed (via X11 or SIGUSR1 signal)
-pub fn check_hotkey_pressed() -> bool {
-    // Check SIGUSR1 signal first (Wayland)
-    if SIGNAL_RECEIVED.swap(false, Ordering::SeqCst) {
-        return true;
+/// Make `bindings` the live ones, returning whether any uses modifier taps
+fn install(bindings: &[(Binding, String)]) -> bool {
+    let chords = Chords::new(bindings.iter().map(|(binding, _)| binding.clone()).collect());
+    let uses_taps = chords.uses_taps();
+    match CHORDS.get() {
+        Some(current) => *current.lock().unwrap() = chords,
+        None => {
+            CHORDS.set(Mutex::new(chords)).ok();
+        }
+    }
+    *VOICE_COMBO.lock().unwrap() =
+        bindings.iter().find(|(b, _)| b.action == Action::Voice).and_then(|(b, _)| b.single_combo());
+    uses_taps
+}
+
+/// Listen for modifier taps (X11), unless already listening
+fn listen_taps() {
+    if std::env::var_os("DISPLAY").is_none() || TAPS_LISTENING.swap(true, Ordering::SeqCst) {
+        return;
     }
-    
-    // Then check X11 global hotkey
-    let receiver = GlobalHotKeyEvent::receiver();
-    if let Ok(event) = receiver.try_recv() {
-        if event.state == HotKeyState::Pressed {
-            if let Some(id) = HOTKEY_ID.get() {
-                return event.id == *id;
+    std::thread::spawn(|| {
+        if let Err(e) = taps::listen(|modifier, at| feed(Step::Tap(modifier), at)) {
+            tracing::warn!("Modifier taps unavailable: {}", e);
+        }
+    });
+}
+
+/// Switch to edited `[hotkeys]` while running. X11 grabs follow at once;
+/// shortcuts bound through the Wayland portal stay as they were until the
+/// daemon restarts, since the desktop owns them.
+pub fn reload(config: &HotkeysConfig) {
+    let bindings = bindings(config);
+    if install(&bindings) {
+        listen_taps();
+    }
+    sync_grabs();
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        tracing::info!("Hotkeys bound through the GlobalShortcuts portal change on the next start");
+    }
+    tracing::info!("Reloaded {} hotkeys", bindings.len());
+}
+
+/// Version of the GlobalShortcuts portal hotkeys go through on Wayland
+pub fn portal_version() -> Result<u32, String> {
+    portal::version()
+}
+
+/// Advance the bindings by one step, regrabbing if the armed combos changed
+fn feed(step: Step, at: Instant) {
+    let Some(chords) = CHORDS.get() else {
+        return;
+    };
+    let mut chords = chords.lock().unwrap();
+    let armed = chords.armed();
+    if let Some(action) = chords.feed(step, at) {
+        tracing::info!("Hotkey fired: {:?}", action);
+        fire(action);
+    }
+    if chords.armed() != armed {
+        // Combo events arrive on global-hotkey's thread, which also serves
+        // (un)registration: wait for it elsewhere
+        std::thread::spawn(sync_grabs);
+    }
+    if let Some(deadline) = chords.deadline() {
+        std::thread::spawn(move || expire_at(deadline));
+    }
+}
+
+/// Release the grabs of a chord left open past `deadline`
+fn expire_at(deadline: Instant) {
+    std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+    let expired = CHORDS
+        .get()
+        .is_some_and(|chords| chords.lock().unwrap().expire(Instant::now()));
+    if expired {
+        sync_grabs();
+    }
+}
+
+/// Hand an action to the UI
+fn fire(action: Action) {
+    *FIRED.lock().unwrap() = Some(action);
+    crate::wake::wake();
+}
+
+/// The voice combo went down or up. Only the first press of a hold fires,
+/// and a release counts once no repeat press follows it.
+fn push_to_talk(state: HotKeyState) {
+    match state {
+        HotKeyState::Pressed => {
+            VOICE_PRESSES.fetch_add(1, Ordering::SeqCst);
+            if !VOICE_HELD.swap(true, Ordering::SeqCst) {
+                fire(Action::Voice);
             }
         }
+        HotKeyState::Released => {
+            let presses = VOICE_PRESSES.load(Ordering::SeqCst);
+            std::thread::spawn(move || {
+                std::thread::sleep(REPEAT_GAP);
+                if VOICE_PRESSES.load(Ordering::SeqCst) == presses && VOICE_HELD.swap(false, Ordering::SeqCst) {
+                    VOICE_RELEASED.store(true, Ordering::SeqCst);
+                    crate::wake::wake();
+                }
+            });
+        }
     }
-    
-    false
 }
 
-/// Time tick event for polling
-#[derive(Debug, Clone)]
-pub struct HotkeyTick;
+/// Whether the push-to-talk combo was let go since the last call
+pub fn voice_released() -> bool {
+    VOICE_RELEASED.swap(false, Ordering::SeqCst)
+}
+
+/// Grab exactly the combos that can advance a binding right now
+fn sync_grabs() {
+    let (Some(manager), Some(chords)) = (HOTKEY_MANAGER.get(), CHORDS.get()) else {
+        return;
+    };
+    let armed = chords.lock().unwrap().armed();
+    let mut grabbed = GRABBED.lock().unwrap();
+    grabbed.retain(|hotkey| {
+        if armed.contains(hotkey) {
+            return true;
+        }
+        if let Err(e) = manager.unregister(*hotkey) {
+            tracing::debug!("Failed to release hotkey {:?}: {}", hotkey, e);
+        }
+        false
+    });
+    for hotkey in armed {
+        if grabbed.contains(&hotkey) {
+            continue;
+        }
+        match manager.register(hotkey) {
+            Ok(()) => grabbed.push(hotkey),
+            Err(e) => tracing::warn!("Failed to register X11 hotkey {:?}: {}", hotkey, e),
+        }
+    }
+}
 
-/// Create a time-based subscription that fires tick events for hotkey polling
-pub fn hotkey_tick_subscription() -> Subscription<HotkeyTick> {
-    // 200ms interval for minimal CPU/disk activity while still feeling responsive
-    time::every(Duration::from_millis(200)).map(|_| HotkeyTick)
+/// Action requested since the last poll (X11 binding, portal or SIGUSR1)
+pub fn poll() -> Option<Action> {
+    if TOGGLE_REQUESTED.swap(false, Ordering::SeqCst) {
+        return Some(Action::Toggle);
+    }
+    FIRED.lock().unwrap().take()
 }
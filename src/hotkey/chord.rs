@@ -0,0 +1,240 @@
+//! Multi-step bindings: chords ("Super+Space, C") and modifier double-taps
+//! ("Ctrl, Ctrl")
+//!
+//! A binding is a comma-separated list of steps. A step is either a key combo
+//! (grabbed only while it can advance a binding, so a chord's second key
+//! isn't stolen from other apps) or a bare modifier, meaning "tap it on its
+//! own". `Chords` matches steps as they arrive: a completed binding fires at
+//! once, and when it's also the start of a longer one the sequence stays open
+//! until the next step or a timeout.
+
+use global_hotkey::hotkey::HotKey;
+use std::time::{Duration, Instant};
+
+/// Time allowed between the steps of a chord
+pub const CHORD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Time allowed between two taps of a double-tap
+pub const TAP_TIMEOUT: Duration = Duration::from_millis(400);
+
+/// What a binding does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Show or hide the launcher
+    Toggle,
+    /// Show the launcher on clipboard history
+    Clipboard,
+    /// Start voice input (stops when the key is released or pressed again)
+    Voice,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Modifier {
+    Ctrl,
+    Shift,
+    Alt,
+    Super,
+}
+
+impl Modifier {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "ctrl" | "control" => Some(Self::Ctrl),
+            "shift" => Some(Self::Shift),
+            "alt" => Some(Self::Alt),
+            "super" | "meta" | "cmd" | "win" => Some(Self::Super),
+            _ => None,
+        }
+    }
+}
+
+/// One keystroke of a binding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Combo(HotKey),
+    /// A modifier pressed and released without another key
+    Tap(Modifier),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binding {
+    pub action: Action,
+    pub steps: Vec<Step>,
+}
+
+impl Binding {
+    /// Parse config syntax, e.g. "Super+Space", "Super+Space, C" or "Ctrl, Ctrl"
+    pub fn parse(action: Action, text: &str) -> Result<Self, String> {
+        let steps = text
+            .split(',')
+            .map(str::trim)
+            .map(|step| match Modifier::parse(step) {
+                Some(modifier) => Ok(Step::Tap(modifier)),
+                None => step
+                    .parse::<HotKey>()
+                    .map(Step::Combo)
+                    .map_err(|e| format!("Invalid hotkey \"{}\": {}", step, e)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { action, steps })
+    }
+
+    /// Whether this is a plain key combo a desktop shortcut service can bind
+    pub fn single_combo(&self) -> Option<HotKey> {
+        match self.steps.as_slice() {
+            [Step::Combo(hotkey)] => Some(*hotkey),
+            _ => None,
+        }
+    }
+}
+
+/// Matches incoming steps against the bindings
+#[derive(Debug, Clone, Default)]
+pub struct Chords {
+    bindings: Vec<Binding>,
+    /// Steps typed so far of a sequence that can still complete a binding
+    typed: Vec<Step>,
+    deadline: Option<Instant>,
+}
+
+impl Chords {
+    pub fn new(bindings: Vec<Binding>) -> Self {
+        Self { bindings, ..Default::default() }
+    }
+
+    /// Handle a step at `now`; returns the action of a binding it completes
+    pub fn feed(&mut self, step: Step, now: Instant) -> Option<Action> {
+        self.expire(now);
+        self.typed.push(step);
+        if !self.bindings.iter().any(|b| b.steps.starts_with(&self.typed)) {
+            // Doesn't continue the open sequence; maybe it starts a new one
+            self.typed = vec![step];
+        }
+
+        let fired = self
+            .bindings
+            .iter()
+            .find(|b| b.steps == self.typed)
+            .map(|b| b.action);
+        let next = self
+            .bindings
+            .iter()
+            .filter(|b| b.steps.len() > self.typed.len() && b.steps.starts_with(&self.typed))
+            .map(|b| b.steps[self.typed.len()])
+            .collect::<Vec<_>>();
+
+        if next.is_empty() {
+            self.typed.clear();
+            self.deadline = None;
+        } else {
+            let timeout = if next.iter().all(|s| matches!(s, Step::Tap(_))) { TAP_TIMEOUT } else { CHORD_TIMEOUT };
+            self.deadline = Some(now + timeout);
+        }
+        fired
+    }
+
+    /// When the open sequence, if any, times out
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Drop an open sequence whose time ran out; true if one was dropped
+    pub fn expire(&mut self, now: Instant) -> bool {
+        if self.deadline.is_some_and(|deadline| now > deadline) {
+            self.typed.clear();
+            self.deadline = None;
+            return true;
+        }
+        false
+    }
+
+    /// Combos to grab right now: every binding's first combo, plus the next
+    /// combos of the open sequence
+    pub fn armed(&self) -> Vec<HotKey> {
+        let mut armed = Vec::new();
+        for binding in &self.bindings {
+            let mut candidates = vec![binding.steps.first()];
+            if !self.typed.is_empty() && binding.steps.starts_with(&self.typed) {
+                candidates.push(binding.steps.get(self.typed.len()));
+            }
+            for step in candidates.into_iter().flatten() {
+                if let Step::Combo(hotkey) = step {
+                    if !armed.contains(hotkey) {
+                        armed.push(*hotkey);
+                    }
+                }
+            }
+        }
+        armed
+    }
+
+    /// Combo of the bindings with the given global-hotkey id
+    pub fn combo(&self, id: u32) -> Option<HotKey> {
+        self.bindings.iter().flat_map(|b| &b.steps).find_map(|step| match step {
+            Step::Combo(hotkey) if hotkey.id() == id => Some(*hotkey),
+            _ => None,
+        })
+    }
+
+    /// Whether any binding uses modifier taps (they need a key event listener)
+    pub fn uses_taps(&self) -> bool {
+        self.bindings
+            .iter()
+            .any(|b| b.steps.iter().any(|s| matches!(s, Step::Tap(_))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn combo(text: &str) -> Step {
+        Step::Combo(text.parse().unwrap())
+    }
+
+    fn chords() -> Chords {
+        Chords::new(vec![
+            Binding::parse(Action::Toggle, "Super+Space").unwrap(),
+            Binding::parse(Action::Clipboard, "Super+Space, C").unwrap(),
+        ])
+    }
+
+    #[test]
+    fn test_parse() {
+        let binding = Binding::parse(Action::Toggle, "Ctrl, ctrl").unwrap();
+        assert_eq!(binding.steps, vec![Step::Tap(Modifier::Ctrl), Step::Tap(Modifier::Ctrl)]);
+        assert_eq!(binding.single_combo(), None);
+        assert!(Binding::parse(Action::Toggle, "Super+Space").unwrap().single_combo().is_some());
+        assert!(Binding::parse(Action::Toggle, "Super+Nope").is_err());
+    }
+
+    #[test]
+    fn test_chord() {
+        let mut chords = chords();
+        let start = Instant::now();
+        // The prefix is a binding of its own: it fires and the chord stays open
+        assert_eq!(chords.feed(combo("Super+Space"), start), Some(Action::Toggle));
+        assert!(chords.armed().contains(&"C".parse().unwrap()));
+        assert_eq!(chords.feed(combo("C"), start + Duration::from_millis(300)), Some(Action::Clipboard));
+        assert_eq!(chords.armed(), vec!["Super+Space".parse::<HotKey>().unwrap()]);
+
+        // Too slow: C no longer continues the chord
+        chords.feed(combo("Super+Space"), start);
+        assert_eq!(chords.feed(combo("C"), start + CHORD_TIMEOUT * 2), None);
+    }
+
+    #[test]
+    fn test_double_tap() {
+        let mut chords = Chords::new(vec![Binding::parse(Action::Toggle, "Ctrl, Ctrl").unwrap()]);
+        let start = Instant::now();
+        assert!(chords.uses_taps());
+        assert!(chords.armed().is_empty());
+        assert_eq!(chords.feed(Step::Tap(Modifier::Ctrl), start), None);
+        assert_eq!(chords.feed(Step::Tap(Modifier::Ctrl), start + Duration::from_millis(200)), Some(Action::Toggle));
+
+        // A third tap starts over rather than firing again
+        assert_eq!(chords.feed(Step::Tap(Modifier::Ctrl), start + Duration::from_millis(300)), None);
+        assert!(chords.expire(start + Duration::from_millis(300) + TAP_TIMEOUT * 2));
+        assert_eq!(chords.feed(Step::Tap(Modifier::Shift), start), None);
+    }
+}
@@ -0,0 +1,125 @@
+//! Global shortcuts through the xdg-desktop-portal GlobalShortcuts interface
+//!
+//! Wayland clients can't grab keys, but GNOME and KDE let them ask for
+//! shortcuts through the portal: the first run shows the desktop's prompt to
+//! confirm (or change) the triggers, later runs find the bindings in place and
+//! just listen for activations. ashpd speaks the portal's request/response
+//! protocol for us.
+
+use ashpd::desktop::global_shortcuts::{GlobalShortcuts, NewShortcut};
+use futures_util::StreamExt;
+
+use super::Action;
+
+/// Portal id of an action's shortcut
+fn shortcut_id(action: Action) -> &'static str {
+    match action {
+        Action::Toggle => "toggle",
+        Action::Clipboard => "clipboard",
+        Action::Voice => "voice",
+    }
+}
+
+fn description(action: Action) -> &'static str {
+    match action {
+        Action::Toggle => "Show or hide Ruty",
+        Action::Clipboard => "Open Ruty clipboard history",
+        Action::Voice => "Dictate into Ruty (push to talk)",
+    }
+}
+
+/// Bind a shortcut per action (config syntax, e.g. "Super+Space") and call `on_activate` on every activation.
+/// Blocks for as long as the session lives.
+pub fn run(shortcuts: &[(Action, String)], on_activate: impl Fn(Action)) -> Result<(), String> {
+    if shortcuts.is_empty() {
+        return Err("no plain key combos configured".to_string());
+    }
+    runtime()?.block_on(listen(shortcuts, on_activate))
+}
+
+async fn listen(shortcuts: &[(Action, String)], on_activate: impl Fn(Action)) -> Result<(), String> {
+    let portal = GlobalShortcuts::new().await.map_err(|e| e.to_string())?;
+    let session = portal.create_session().await.map_err(|e| format!("CreateSession failed: {}", e))?;
+
+    // Already bound on an earlier run: binding again would prompt every start
+    let listed = portal
+        .list_shortcuts(&session)
+        .await
+        .and_then(|request| request.response())
+        .map_err(|e| format!("ListShortcuts failed: {}", e))?;
+    let bound: Vec<&str> = listed.shortcuts().iter().map(|shortcut| shortcut.id()).collect();
+    if shortcuts.iter().any(|(action, _)| !bound.contains(&shortcut_id(*action))) {
+        let requested: Vec<NewShortcut> = shortcuts
+            .iter()
+            .map(|(action, trigger)| {
+                let preferred = portal_trigger(trigger);
+                tracing::info!("Requesting global shortcut {} from the portal", preferred);
+                NewShortcut::new(shortcut_id(*action), description(*action)).preferred_trigger(preferred.as_str())
+            })
+            .collect();
+        portal
+            .bind_shortcuts(&session, &requested, None)
+            .await
+            .and_then(|request| request.response())
+            .map_err(|e| format!("BindShortcuts failed: {}", e))?;
+    }
+    tracing::info!("Global shortcut ready (xdg-desktop-portal)");
+
+    // The portal only signals the connection that owns the session, and this
+    // connection owns just the one
+    let mut activations = portal.receive_activated().await.map_err(|e| e.to_string())?;
+    while let Some(activated) = activations.next().await {
+        let id = activated.shortcut_id();
+        if let Some((action, _)) = shortcuts.iter().find(|(action, _)| shortcut_id(*action) == id) {
+            on_activate(*action);
+        }
+    }
+    Err("Portal session closed".to_string())
+}
+
+/// Version of the desktop's GlobalShortcuts portal, an error if it has none
+pub fn version() -> Result<u32, String> {
+    runtime()?.block_on(async {
+        let portal = GlobalShortcuts::new().await.map_err(|e| e.to_string())?;
+        portal.get_property::<u32>("version").await.map_err(|e| e.to_string())
+    })
+}
+
+/// ashpd is async; the portal calls run on the hotkey thread
+fn runtime() -> Result<tokio::runtime::Runtime, String> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| format!("Cannot start the portal client: {}", e))
+}
+
+/// Config hotkey syntax ("Super+Space") to the portal's XDG shortcut syntax
+/// ("LOGO+space")
+fn portal_trigger(hotkey: &str) -> String {
+    hotkey
+        .split('+')
+        .map(str::trim)
+        .map(|part| match part.to_lowercase().as_str() {
+            "super" | "meta" | "logo" | "win" => "LOGO".to_string(),
+            "ctrl" | "control" => "CTRL".to_string(),
+            "alt" => "ALT".to_string(),
+            "shift" => "SHIFT".to_string(),
+            "space" => "space".to_string(),
+            key if key.chars().count() == 1 => key.to_string(),
+            _ => part.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portal_trigger() {
+        assert_eq!(portal_trigger("Super+Space"), "LOGO+space");
+        assert_eq!(portal_trigger("Ctrl + Alt+K"), "CTRL+ALT+k");
+        assert_eq!(portal_trigger("Super+Return"), "LOGO+Return");
+    }
+}
@@ -0,0 +1,105 @@
+//! Modifier taps on X11, from XInput2 raw key events
+//!
+//! Grabbing a bare modifier would take it away from every other app, so
+//! instead the root window listens for raw key events, which X delivers to
+//! any client that asks without affecting where keys go. A tap is a modifier
+//! pressed and released with no other key in between.
+
+use std::time::{Duration, Instant};
+
+use x11rb::connection::Connection;
+use x11rb::protocol::xinput::{self, ConnectionExt as _};
+use x11rb::protocol::Event;
+
+use super::chord::Modifier;
+
+/// Held longer than this, a modifier press is not a tap
+const MAX_TAP: Duration = Duration::from_millis(300);
+
+/// Modifier for an X keycode (evdev keycodes, as used by Xorg and XWayland)
+fn modifier(keycode: u32) -> Option<Modifier> {
+    match keycode {
+        37 | 105 => Some(Modifier::Ctrl),
+        50 | 62 => Some(Modifier::Shift),
+        64 | 108 => Some(Modifier::Alt),
+        133 | 134 => Some(Modifier::Super),
+        _ => None,
+    }
+}
+
+/// Turns raw press/release events into taps
+#[derive(Debug, Default)]
+struct TapDetector {
+    /// Last key pressed and when
+    pressed: Option<(u32, Instant)>,
+}
+
+impl TapDetector {
+    fn press(&mut self, keycode: u32, now: Instant) {
+        self.pressed = Some((keycode, now));
+    }
+
+    fn release(&mut self, keycode: u32, now: Instant) -> Option<Modifier> {
+        let (pressed, at) = self.pressed.take()?;
+        (pressed == keycode && now - at <= MAX_TAP)
+            .then(|| modifier(keycode))
+            .flatten()
+    }
+}
+
+/// Listen for modifier taps until the X connection closes (blocking)
+pub fn listen(on_tap: impl Fn(Modifier, Instant)) -> Result<(), String> {
+    let (conn, screen) = x11rb::connect(None).map_err(|e| format!("X11 unavailable: {}", e))?;
+    let root = conn.setup().roots[screen].root;
+    conn.xinput_xi_query_version(2, 0)
+        .map_err(|e| e.to_string())?
+        .reply()
+        .map_err(|e| format!("XInput2 unavailable: {}", e))?;
+    conn.xinput_xi_select_events(
+        root,
+        &[xinput::EventMask {
+            deviceid: xinput::Device::ALL_MASTER.into(),
+            mask: vec![xinput::XIEventMask::RAW_KEY_PRESS | xinput::XIEventMask::RAW_KEY_RELEASE],
+        }],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.flush().map_err(|e| e.to_string())?;
+
+    let mut detector = TapDetector::default();
+    loop {
+        let event = conn.wait_for_event().map_err(|e| e.to_string())?;
+        let now = Instant::now();
+        match event {
+            Event::XinputRawKeyPress(event) => detector.press(event.detail, now),
+            Event::XinputRawKeyRelease(event) => {
+                if let Some(modifier) = detector.release(event.detail, now) {
+                    on_tap(modifier, now);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tap_detector() {
+        let mut detector = TapDetector::default();
+        let start = Instant::now();
+        detector.press(37, start);
+        assert_eq!(detector.release(37, start + Duration::from_millis(80)), Some(Modifier::Ctrl));
+
+        // Ctrl+C is not a tap
+        detector.press(37, start);
+        detector.press(54, start);
+        assert_eq!(detector.release(54, start), None);
+        assert_eq!(detector.release(37, start), None);
+
+        // Held too long
+        detector.press(133, start);
+        assert_eq!(detector.release(133, start + Duration::from_secs(1)), None);
+    }
+}
@@ -0,0 +1,171 @@
+//! Plain-language requests that don't need AI
+//!
+//! "open spotify", "set a 5 minute timer" or "empty the trash" have a
+//! command or action that does exactly that. `classify` recognizes them with
+//! a handful of patterns; the launcher says what Enter will do, runs that
+//! instead of asking AI, and Tab sends the prompt to AI as typed. Anything
+//! the patterns don't cover still goes to AI.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use ruty_core::actions::Action;
+use ruty_core::fuzzy;
+
+/// Request-shaped prompts: "set a 5 minute timer for tea", "timer 10 min",
+/// "remind me in 2 hours to call mum"
+static TIMER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"(?i)^(?:(?:set|start) )?(?:an? )?(?:timer (?:for )?)?(?P<n>\d+) ?(?P<unit>s|secs?|seconds?|m|mins?|minutes?|h|hrs?|hours?)",
+        r"(?: timer)?(?: (?:for|to|called) (?P<label>.+))?$",
+    ))
+    .unwrap()
+});
+static REMINDER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^remind me in (?P<n>\d+) ?(?P<unit>s|secs?|seconds?|m|mins?|minutes?|h|hrs?|hours?)(?: to (?P<label>.+))?$")
+        .unwrap()
+});
+
+/// A recognized request
+#[derive(Debug, Clone, PartialEq)]
+pub struct Intent {
+    /// What Enter will do, e.g. "Set a 5m timer"
+    pub summary: String,
+    pub target: Target,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    /// A command to run as if it had been typed, e.g. "/timer 5m tea"
+    Prompt(String),
+    Action(Action),
+}
+
+impl Intent {
+    fn prompt(summary: String, prompt: String) -> Self {
+        Self { summary, target: Target::Prompt(prompt) }
+    }
+
+    fn action(action: Action) -> Self {
+        Self { summary: action.title().to_string(), target: Target::Action(action) }
+    }
+}
+
+/// The request a plain prompt makes, if it's one of the known kinds; `apps`
+/// are the installed apps' names, so "open <name>" only counts for an app
+pub fn classify<'a>(prompt: &'a str, apps: &[String]) -> Option<Intent> {
+    let text = prompt.trim().trim_end_matches(['.', '!']);
+    let text = match text.get(..7) {
+        Some(please) if please.eq_ignore_ascii_case("please ") => text[7..].trim(),
+        _ => text,
+    };
+    let lower = text.to_lowercase();
+
+    // A bare "5 minutes" isn't asking for anything
+    let timer = TIMER.captures(text).filter(|_| lower.contains("timer"));
+    if let Some(captures) = timer.or_else(|| REMINDER.captures(text)) {
+        let unit = match &captures["unit"][..1] {
+            "s" => "s",
+            "h" => "h",
+            _ => "m",
+        };
+        let duration = format!("{}{}", &captures["n"], unit);
+        let label = captures.name("label").map_or("", |label| label.as_str());
+        let summary = match label {
+            "" => format!("Set a {} timer", duration),
+            label => format!("Set a {} timer: {}", duration, label),
+        };
+        return Some(Intent::prompt(summary, format!("/timer {} {}", duration, label).trim_end().to_string()));
+    }
+
+    match lower.as_str() {
+        "empty trash" | "empty the trash" | "empty recycle bin" | "empty the recycle bin" => {
+            return Some(Intent::action(Action::EmptyTrash));
+        }
+        "clear clipboard" | "clear the clipboard" | "clear clipboard history" | "clear the clipboard history" => {
+            return Some(Intent::action(Action::ClearClipboard));
+        }
+        "switch theme" | "toggle theme" | "switch the theme" | "toggle the theme" => {
+            return Some(Intent::action(Action::SwitchTheme));
+        }
+        "turn on do not disturb" | "enable do not disturb" | "turn on dnd" | "enable dnd" | "do not disturb" => {
+            return Some(Intent::prompt(String::from("Turn on Do Not Disturb"), String::from("/dnd on")));
+        }
+        "turn off do not disturb" | "disable do not disturb" | "turn off dnd" | "disable dnd" => {
+            return Some(Intent::prompt(String::from("Turn off Do Not Disturb"), String::from("/dnd off")));
+        }
+        _ => {}
+    }
+
+    let (verb, rest) = text.split_once(' ')?;
+    let rest = rest.trim();
+    // Leading words are matched without case; what follows is kept as typed
+    let strip = |text: &'a str, prefix: &str| -> Option<&'a str> {
+        text.get(..prefix.len()).filter(|start| start.eq_ignore_ascii_case(prefix)).map(|_| &text[prefix.len()..])
+    };
+    match verb.to_lowercase().as_str() {
+        "open" | "launch" | "start" | "run" => {
+            let app = strip(rest, "the ").unwrap_or(rest);
+            let query = app.to_lowercase();
+            let name = apps.iter().find(|name| fuzzy::score(name, &query) >= fuzzy::CONTAINS)?;
+            Some(Intent::prompt(format!("Open {}", name), format!("/launch {}", app)))
+        }
+        "find" | "search" => {
+            let query = ["files named ", "file named ", "files for ", "files ", "file ", "for files "]
+                .iter()
+                .find_map(|prefix| strip(rest, prefix))?
+                .trim();
+            (!query.is_empty()).then(|| Intent::prompt(format!("Search files for \"{}\"", query), format!("/file {}", query)))
+        }
+        "connect" | "disconnect" => {
+            let device = strip(rest, "to ").unwrap_or(rest).trim();
+            let device = strip(device, "my ").unwrap_or(device);
+            Some(Intent::prompt(format!("Show devices matching \"{}\"", device), format!("/devices {}", device)))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(prompt: &str) -> Option<Target> {
+        let apps = [String::from("Spotify"), String::from("Firefox")];
+        classify(prompt, &apps).map(|intent| intent.target)
+    }
+
+    fn command(text: &str) -> Option<Target> {
+        Some(Target::Prompt(text.to_string()))
+    }
+
+    #[test]
+    fn test_timers() {
+        assert_eq!(target("set a 5 minute timer"), command("/timer 5m"));
+        assert_eq!(target("Set a 25 min timer for Tea."), command("/timer 25m Tea"));
+        assert_eq!(target("timer for 90 seconds"), command("/timer 90s"));
+        assert_eq!(target("remind me in 2 hours to call mum"), command("/timer 2h call mum"));
+        let apps: [String; 0] = [];
+        assert_eq!(classify("set a 5 minute timer for tea", &apps).unwrap().summary, "Set a 5m timer: tea");
+    }
+
+    #[test]
+    fn test_actions_and_commands() {
+        assert_eq!(target("empty the trash"), Some(Target::Action(Action::EmptyTrash)));
+        assert_eq!(target("please clear clipboard history"), Some(Target::Action(Action::ClearClipboard)));
+        assert_eq!(target("turn on do not disturb"), command("/dnd on"));
+        assert_eq!(target("Open Spotify"), command("/launch Spotify"));
+        assert_eq!(target("find files named report"), command("/file report"));
+        assert_eq!(target("connect to my airpods"), command("/devices airpods"));
+    }
+
+    #[test]
+    fn test_questions_go_to_ai() {
+        // Not an installed app
+        assert_eq!(target("open the pod bay doors"), None);
+        assert_eq!(target("find me a good restaurant"), None);
+        assert_eq!(target("how long is a 5 minute timer in seconds?"), None);
+        assert_eq!(target("5 minutes"), None);
+        assert_eq!(target("what is rust"), None);
+    }
+}
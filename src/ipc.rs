@@ -9,6 +9,8 @@ use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
+use ruty_core::paths;
+
 /// Flag to signal the main app that a toggle was requested
 pub static TOGGLE_REQUESTED: AtomicBool = AtomicBool::new(false);
 
@@ -17,9 +19,7 @@ pub static CLOSE_REQUESTED: AtomicBool = AtomicBool::new(false);
 
 /// Get the IPC socket path
 fn socket_path() -> PathBuf {
-    let runtime_dir = std::env::var("XDG_RUNTIME_DIR")
-        .unwrap_or_else(|_| "/tmp".to_string());
-    PathBuf::from(runtime_dir).join("ruty.sock")
+    paths::runtime_dir().join("ruty.sock")
 }
 
 /// Start the IPC server in a background thread
@@ -0,0 +1,94 @@
+//! Launch feedback
+//!
+//! Launching an app shows "Launching GIMP…" while it starts, and the process
+//! is watched for `LAUNCH_GRACE`. One that exits unsuccessfully within that
+//! window almost certainly failed to start (bad Exec line, missing library),
+//! so it's reported instead of the launch silently doing nothing. Processes
+//! that are still running, or exited cleanly after handing off to an existing
+//! instance, are let go.
+
+use std::process::{Child, ExitStatus};
+use std::time::{Duration, Instant};
+
+/// How long a launched process is watched for an early failure
+pub const LAUNCH_GRACE: Duration = Duration::from_secs(2);
+
+#[derive(Debug)]
+struct Launch {
+    name: String,
+    child: Child,
+    until: Instant,
+}
+
+/// Processes launched within the last `LAUNCH_GRACE`
+#[derive(Debug, Default)]
+pub struct Launches {
+    watched: Vec<Launch>,
+}
+
+impl Launches {
+    /// Watch an app launched at `now`
+    pub fn watch(&mut self, name: &str, child: Child, now: Instant) {
+        self.watched.push(Launch { name: name.to_string(), child, until: now + LAUNCH_GRACE });
+    }
+
+    /// Stop watching launches that exited or outlived the grace period,
+    /// returning an error message for each that failed
+    pub fn poll(&mut self, now: Instant) -> Vec<String> {
+        let mut failures = Vec::new();
+        self.watched.retain_mut(|launch| match launch.child.try_wait() {
+            Ok(Some(status)) => {
+                if !status.success() {
+                    failures.push(failure(&launch.name, status));
+                }
+                false
+            }
+            Ok(None) => launch.until > now,
+            Err(e) => {
+                tracing::debug!("Cannot watch {}: {}", launch.name, e);
+                false
+            }
+        });
+        failures
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.watched.is_empty()
+    }
+}
+
+fn failure(name: &str, status: ExitStatus) -> String {
+    match status.code() {
+        // The shell conventions for a missing or unrunnable command
+        Some(127) => format!("{} failed to start: command not found", name),
+        Some(126) => format!("{} failed to start: command not executable", name),
+        Some(code) => format!("{} exited right after launch (status {})", name, code),
+        None => format!("{} was killed right after launch ({})", name, status),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_poll() {
+        let start = Instant::now();
+        let mut launches = Launches::default();
+        let spawn = |script: &str| Command::new("sh").args(["-c", script]).spawn().unwrap();
+        let mut broken = spawn("exit 127");
+        broken.wait().unwrap();
+        let mut fine = spawn("exit 0");
+        fine.wait().unwrap();
+        launches.watch("Broken", broken, start);
+        launches.watch("Fine", fine, start);
+        launches.watch("Slow", spawn("sleep 5"), start);
+
+        assert_eq!(launches.poll(start), vec!["Broken failed to start: command not found".to_string()]);
+        assert!(!launches.is_empty());
+        // Still running after the grace period: it started
+        assert!(launches.poll(start + LAUNCH_GRACE).is_empty());
+        assert!(launches.is_empty());
+    }
+}
@@ -4,9 +4,20 @@
 //!
 //! Usage:
 //!   ruty           - Start daemon (or connect to existing)
+//!   ruty --headless - Start daemon without a window (RPC only)
+//!   ruty --timings - Start daemon and print how long startup took
 //!   ruty open      - Show window (toggle if visible)
+//!   ruty attach    - Open the UI on a headless daemon
 //!   ruty close     - Hide window
 //!   ruty quit      - Stop daemon
+//!   ruty status    - Show whether the daemon is running and indexed
+//!   ruty autostart - Manage login autostart (enable|disable|status)
+//!   ruty flow      - Run or list [flows] from config.toml (run <name>|list)
+//!   ruty geometry  - Show or set window size, anchor and monitor
+//!   ruty config    - Check config.toml or print it with defaults filled in
+//!   ruty audit     - Print the log of launched apps, opened files and what ran
+//!   ruty doctor    - Diagnose the setup (display, tools, backend, ports, ...)
+//!   ruty tui       - Search and clipboard history in the terminal
 //!   ruty help      - Show help
 
 mod app;
@@ -16,31 +27,61 @@ mod native;
 mod hotkey;
 mod ipc;
 mod rpc;
+mod chat;
 mod commands;
+mod controls;
+mod crash;
+mod details;
+mod doctor;
+mod events;
+mod flows;
+mod intents;
+mod launches;
+mod paste_queue;
+mod redraw;
+mod regex_tester;
+mod scratchpad;
+mod services;
+mod startup;
+mod suggest;
+mod timers;
+mod tui;
+mod undo;
+mod wake;
+mod window_commands;
 
 use std::sync::Arc;
 use app::Ruty;
 use iced::{window, Size};
-use rpc::server::WindowController;
+use window_commands::{WindowCommand, WindowController};
+use native::compositor;
+use services::Services;
 use std::env;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
 
 /// Global window controller shared between RPC server and Iced app
 static WINDOW_CONTROLLER: std::sync::OnceLock<Arc<WindowController>> = std::sync::OnceLock::new();
 
+/// Global services (apps, files, clipboard, backend) shared by RPC and UI
+static SERVICES: std::sync::OnceLock<Arc<Services>> = std::sync::OnceLock::new();
+
 fn main() -> iced::Result {
+    startup::begin();
+
     // Parse CLI arguments
     let args: Vec<String> = env::args().collect();
     
     if args.len() > 1 {
-        return handle_cli_command(&args[1]);
+        return handle_cli_command(&args[1..]);
     }
     
     // No args = start daemon mode
-    start_daemon()
+    start_daemon(false)
 }
 
-fn handle_cli_command(cmd: &str) -> iced::Result {
+fn handle_cli_command(args: &[String]) -> iced::Result {
+    let cmd = args[0].as_str();
+
     // Initialize minimal logging for CLI
     let _ = tracing_subscriber::registry()
         .with(tracing_subscriber::fmt::layer())
@@ -68,9 +109,22 @@ fn handle_cli_command(cmd: &str) -> iced::Result {
             } else {
                 println!("Daemon not running. Starting daemon...");
                 drop(rt);
-                start_daemon()
+                start_daemon(false)
             }
         }
+        "attach" => {
+            rt.block_on(async {
+                if rpc::client::is_daemon_running().await {
+                    match rpc::client::attach_window().await {
+                        Ok(_) => println!("Window attached"),
+                        Err(e) => eprintln!("Error: {}", e),
+                    }
+                } else {
+                    println!("Daemon is not running");
+                }
+            });
+            Ok(())
+        }
         "close" | "hide" => {
             rt.block_on(async {
                 if rpc::client::is_daemon_running().await {
@@ -99,23 +153,80 @@ fn handle_cli_command(cmd: &str) -> iced::Result {
         }
         "status" => {
             rt.block_on(async {
-                if rpc::client::is_daemon_running().await {
-                    println!("Daemon is running");
-                } else {
-                    println!("Daemon is not running");
+                match rpc::client::server_info().await {
+                    Ok(info) if info.ready => {
+                        println!("Daemon is running (v{}, {} apps indexed)", info.version, info.apps_indexed)
+                    }
+                    Ok(info) => println!("Daemon is running (v{}, indexing…)", info.version),
+                    Err(_) => println!("Daemon is not running"),
                 }
             });
             Ok(())
         }
+        "--headless" | "headless" => {
+            drop(rt);
+            start_daemon(true)
+        }
+        "--timings" => {
+            if rt.block_on(rpc::client::is_daemon_running()) {
+                eprintln!("Daemon is already running; stop it with 'ruty quit' to time a cold start");
+                return Ok(());
+            }
+            drop(rt);
+            startup::print_when_interactive();
+            start_daemon(false)
+        }
+        "autostart" => {
+            handle_autostart(&args[1..]);
+            Ok(())
+        }
+        "flow" | "flows" => {
+            rt.block_on(handle_flow(&args[1..]));
+            Ok(())
+        }
+        "geometry" => {
+            rt.block_on(handle_geometry(&args[1..]));
+            Ok(())
+        }
+        "config" => {
+            handle_config(&args[1..]);
+            Ok(())
+        }
+        "audit" => {
+            handle_audit(&args[1..]);
+            Ok(())
+        }
+        "doctor" => {
+            let checks = rt.block_on(doctor::run());
+            println!("{}", ruty_core::doctor::report(&checks));
+            Ok(())
+        }
+        "tui" => {
+            if let Err(e) = tui::run(rt.handle().clone(), &args[1..]) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+            Ok(())
+        }
         "help" | "--help" | "-h" => {
             println!("Ruty - AI-powered productivity launcher\n");
             println!("Usage: ruty [command]\n");
             println!("Commands:");
             println!("  (none)        Start daemon (or show window if already running)");
+            println!("  --headless    Start daemon without a window (attach later with AttachWindow RPC)");
+            println!("  --timings     Start daemon and print startup phase timings once interactive");
             println!("  open, toggle  Toggle window visibility");
+            println!("  attach        Open the UI on a headless daemon");
             println!("  close, hide   Hide window");
             println!("  quit, stop    Stop daemon");
-            println!("  status        Check if daemon is running");
+            println!("  status        Check if daemon is running and done indexing");
+            println!("  autostart     enable [--systemd|--socket] | disable | status | generate [dir]");
+            println!("  flow          run <name> | list   Run a [flows] workflow from config.toml");
+            println!("  geometry      [WIDTHxHEIGHT] [center|top] [--monitor NAME]   Show or set window placement");
+            println!("  config        validate [FILE] | show [--effective]   Check config.toml or print it");
+            println!("  audit         [--since WHEN]   Print what was launched, opened and run (today, yesterday, 3d, 2026-10-01)");
+            println!("  doctor        Check the setup and suggest fixes");
+            println!("  tui           [--clipboard]   Search, or pick from clipboard history, in the terminal");
             println!("  help          Show this help message");
             println!("\nSet Super+Space keybind to: ruty open");
             Ok(())
@@ -128,71 +239,364 @@ fn handle_cli_command(cmd: &str) -> iced::Result {
     }
 }
 
-fn start_daemon() -> iced::Result {
+/// `ruty flow run <name> | list`
+async fn handle_flow(args: &[String]) {
+    match (args.first().map(|s| s.as_str()).unwrap_or("list"), args.get(1)) {
+        ("run", Some(name)) => {
+            if !rpc::client::is_daemon_running().await {
+                eprintln!("Daemon is not running; start it with 'ruty' or 'ruty --headless'");
+                return;
+            }
+            match rpc::client::run_flow(name).await {
+                Ok(output) if output.is_empty() => {}
+                Ok(output) => println!("{}", output),
+                Err(e) => eprintln!("Error: {}", e),
+            }
+        }
+        ("list", _) => {
+            let config = ruty_core::config::Config::load();
+            if config.flows.is_empty() {
+                println!("No flows yet; add them under [flows] in {}", ruty_core::config::Config::path().display());
+            }
+            for (name, flow) in &config.flows {
+                println!("{:<16} {}", name, flow.description);
+            }
+        }
+        _ => eprintln!("Usage: ruty flow run <name> | list"),
+    }
+}
+
+/// `ruty geometry [WIDTHxHEIGHT] [center|top] [--monitor NAME]`
+async fn handle_geometry(args: &[String]) {
+    const USAGE: &str = "Usage: ruty geometry [WIDTHxHEIGHT] [center|top] [--monitor NAME]";
+    let mut changes = rpc::proto::WindowGeometry::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--monitor" {
+            match args.next() {
+                Some(name) => changes.monitor = name.clone(),
+                None => return eprintln!("{}", USAGE),
+            }
+        } else if let Some(anchor) = ruty_core::geometry::Anchor::parse(arg) {
+            changes.anchor = anchor.name().to_string();
+        } else if let Some((Ok(width), Ok(height))) = arg.split_once('x').map(|(w, h)| (w.parse(), h.parse())) {
+            (changes.width, changes.height) = (width, height);
+        } else {
+            return eprintln!("{}", USAGE);
+        }
+    }
+
+    if !rpc::client::is_daemon_running().await {
+        eprintln!("Daemon is not running");
+        return;
+    }
+    let changed = changes != rpc::proto::WindowGeometry::default();
+    match rpc::client::window_geometry(changed.then_some(changes)).await {
+        Ok(geometry) => {
+            let monitor = if geometry.monitor.is_empty() { "any monitor" } else { geometry.monitor.as_str() };
+            println!("{}x{}, {} on {}", geometry.width, geometry.height, geometry.anchor, monitor);
+        }
+        Err(e) => eprintln!("Error: {}", e),
+    }
+}
+
+/// `ruty config validate [file] | show [--effective]`
+fn handle_config(args: &[String]) {
+    use ruty_core::config::Config;
+    use ruty_core::validation;
+
+    match args.first().map(|s| s.as_str()).unwrap_or("validate") {
+        "validate" => {
+            let path = args.get(1).map(std::path::PathBuf::from).unwrap_or_else(Config::path);
+            let content = match std::fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    eprintln!("Error: cannot read {}: {}", path.display(), e);
+                    std::process::exit(1);
+                }
+            };
+            match validation::validate(&content) {
+                Ok(validated) => {
+                    for warning in &validated.warnings {
+                        println!("{}: warning: {}", path.display(), warning);
+                    }
+                    println!("{} is valid ({} warnings)", path.display(), validated.warnings.len());
+                }
+                Err(e) => {
+                    eprintln!("{}: error: {}", path.display(), e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        "show" if args.iter().any(|a| a == "--effective") => match Config::try_load() {
+            // Everything left out of the file, with its default filled in
+            Ok(config) => match config.to_toml() {
+                Ok(text) => print!("{}", text),
+                Err(e) => eprintln!("Error: {}", e),
+            },
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        "show" => match std::fs::read_to_string(Config::path()) {
+            Ok(content) => print!("{}", content),
+            Err(_) => println!("No {} (all defaults; see --effective)", Config::path().display()),
+        },
+        other => eprintln!("Unknown config command: {} (expected validate or show)", other),
+    }
+}
+
+/// `ruty audit [--since WHEN]`: the audit log, oldest first
+fn handle_audit(args: &[String]) {
+    use ruty_core::audit;
+
+    let since = match args {
+        [] => Ok(0),
+        [flag, when] if flag == "--since" => audit::parse_since(when),
+        _ => Err("Usage: ruty audit [--since today|yesterday|3d|2026-10-01]".to_string()),
+    };
+    match since.and_then(audit::since) {
+        Ok(entries) => {
+            for entry in &entries {
+                println!("{}", audit::line(entry));
+            }
+        }
+        Err(e) => eprintln!("Error: {}", e),
+    }
+}
+
+/// `ruty autostart enable [--systemd|--socket] | disable | status | generate [dir]`
+fn handle_autostart(args: &[String]) {
+    use ruty_core::autostart::{self, Method};
+
+    let exe = match env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            eprintln!("Error: could not determine ruty binary path: {}", e);
+            return;
+        }
+    };
+
+    match args.first().map(|s| s.as_str()).unwrap_or("status") {
+        "enable" if args.iter().any(|a| a == "--socket") => {
+            match autostart::enable_socket(&exe, &rpc::listen_addr()) {
+                Ok(paths) => {
+                    for path in paths {
+                        println!("Installed {}", path.display());
+                    }
+                    println!("Socket activation enabled: the daemon starts on first 'ruty open'");
+                }
+                Err(e) => eprintln!("Error: {}", e),
+            }
+        }
+        "enable" => {
+            let method = if args.iter().any(|a| a == "--systemd") {
+                Method::Systemd
+            } else {
+                Method::Desktop
+            };
+            match autostart::enable(method, &exe) {
+                Ok(path) => println!("Autostart enabled ({})", path.display()),
+                Err(e) => eprintln!("Error: {}", e),
+            }
+        }
+        "generate" => {
+            let dir = args.get(1).map(std::path::PathBuf::from).unwrap_or_else(|| ".".into());
+            match autostart::generate(&dir, &exe, &rpc::listen_addr()) {
+                Ok(paths) => {
+                    for path in paths {
+                        println!("Wrote {}", path.display());
+                    }
+                }
+                Err(e) => eprintln!("Error: {}", e),
+            }
+        }
+        "disable" => match autostart::disable() {
+            Ok(()) => println!("Autostart disabled"),
+            Err(e) => eprintln!("Error: {}", e),
+        },
+        "status" => {
+            let status = autostart::status();
+            if status.socket {
+                println!("Autostart enabled (systemd socket activation)");
+            } else if status.systemd {
+                println!("Autostart enabled (systemd user service)");
+            } else if status.desktop {
+                println!("Autostart enabled (XDG autostart entry)");
+            } else {
+                println!("Autostart disabled");
+            }
+        }
+        other => {
+            eprintln!("Unknown autostart command: {}", other);
+            eprintln!("Usage: ruty autostart enable [--systemd|--socket] | disable | status | generate [dir]");
+        }
+    }
+}
+
+fn start_daemon(headless: bool) -> iced::Result {
     // Initialize logging (use try_init to avoid panic if already initialized by CLI)
     let _ = tracing_subscriber::registry()
-        .with(tracing_subscriber::fmt::layer())
-        .with(tracing_subscriber::EnvFilter::from_default_env())
+        .with(tracing_subscriber::fmt::layer().with_filter(tracing_subscriber::EnvFilter::from_default_env()))
+        .with(backend::trace::layer())
         .try_init();
 
-    tracing::info!("Starting Ruty daemon...");
+    tracing::info!("Starting Ruty daemon{}...", if headless { " (headless)" } else { "" });
 
-    // Start Python backend sidecar
-    println!("🚀 Starting bundled Python backend...");
-    let mut sidecar = backend::sidecar::Sidecar::new()
-        .with_project_dir(std::env::current_dir().unwrap_or_default());
-    
-    match sidecar.start() {
-        Ok(()) => println!("🐍 Python backend started (Sidecar)"),
-        Err(e) => {
-            println!("⚠️  Backend start failed: {} (AI features may not work)", e);
-            tracing::warn!("Failed to start Python backend: {}", e);
+    // Claim the systemd socket before any threads exist
+    let activation_listener = rpc::server::take_activation_listener();
+    if activation_listener.is_some() {
+        tracing::info!("Socket-activated by systemd");
+    }
+
+    // Before anything (the backend included) opens files where they are now
+    startup::phase("migrate", ruty_core::paths::migrate);
+
+    // Start Python backend sidecar, unless an external backend is configured
+    startup::phase("backend", || {
+        if let Some(url) = backend::sidecar::external_backend_url() {
+            println!("🔗 Using external backend at {}", url);
+        } else {
+            println!("🚀 Starting bundled Python backend...");
+            let mut sidecar = backend::sidecar::Sidecar::new()
+                .with_project_dir(std::env::current_dir().unwrap_or_default());
+
+            match sidecar.start() {
+                Ok(()) => println!("🐍 Python backend started (Sidecar)"),
+                Err(e) => {
+                    println!("⚠️  Backend start failed: {} (AI features may not work)", e);
+                    tracing::warn!("Failed to start Python backend: {}", e);
+                }
+            }
+
+            // Keep sidecar alive (and restartable) for the rest of the process
+            backend::sidecar::install(sidecar);
         }
+    });
+
+    // Crash recovery must run before services pick up the recovered state
+    startup::phase("crash recovery", crash::init);
+    let services = startup::phase("services", || Arc::new(Services::new()));
+    startup::phase("i18n", || ruty_core::i18n::init(&services.config().ui.language));
+    events::start_hooks();
+    events::start_audit();
+    events::start_notifications();
+    // The clipboard poller and watchers wait for the first frame, unless
+    // there's no window to draw one
+    services.warm_up();
+    if headless {
+        services.start_background();
     }
-    
-    // Keep sidecar alive by leaking it (it will be cleaned up on process exit)
-    Box::leak(Box::new(sidecar));
+    let window_rules = services.config().ui.window_rules;
+    let ui_scale = services.config().ui.scale_factor();
+    startup::phase("fonts", || ui::fonts::install(&services.config().fonts));
+    let api = services.config().api.clone();
+    SERVICES.set(services).ok();
 
     // Create shared window controller
     let controller = Arc::new(WindowController::new());
+    if activation_listener.is_some() || headless {
+        // Socket activation: the connection that woke us is about to ask
+        // for a toggle, so start "hidden" and let it land on visible.
+        controller.set_visible(false);
+    }
+    controller.set_scale(ui_scale);
     WINDOW_CONTROLLER.set(controller.clone()).expect("Controller already set");
 
-    // Start gRPC server in background
+    // Start gRPC server (and the HTTP API, if enabled) in background
     let server_controller = controller.clone();
     std::thread::spawn(move || {
         let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
         rt.block_on(async {
-            if let Err(e) = rpc::server::start_server(server_controller).await {
+            if api.enabled {
+                tokio::spawn(async move {
+                    if let Err(e) = rpc::http::start_server(api.port).await {
+                        tracing::error!("HTTP API error: {}", e);
+                    }
+                });
+            }
+            if let Err(e) = rpc::server::start_server(server_controller, activation_listener).await {
                 tracing::error!("gRPC server error: {}", e);
             }
         });
     });
 
+    if headless {
+        tracing::info!("Ruty daemon running headless. Use the AttachWindow RPC to open the UI.");
+        wait_for_attach(&controller);
+        tracing::info!("Window attached");
+    }
+
     // Initialize global hotkey (works on X11)
-    if let Err(e) = hotkey::init_hotkeys() {
+    if let Err(e) = startup::phase("hotkeys", hotkey::init_hotkeys) {
         tracing::warn!("Could not register global hotkey: {} (use 'ruty open' instead)", e);
     }
 
     tracing::info!("Ruty daemon started. Use 'ruty open' to toggle window.");
 
-    // Start Iced application
-    iced::application("Ruty", Ruty::update, Ruty::view)
+    let geometry = controller.geometry().1.scaled(ui_scale);
+    // Tiling compositors would otherwise tile the launcher
+    if window_rules {
+        startup::phase("window rules", || compositor::apply_window_rules(geometry.width, geometry.height));
+    }
+
+    if controller.is_visible() {
+        events::publish(events::Event::WindowShown);
+    }
+
+    let launcher = window::Settings {
+        size: Size::new(geometry.width as f32, geometry.height as f32),
+        position: window::Position::Centered,
+        decorations: false,
+        transparent: true,
+        level: window::Level::AlwaysOnTop,
+        resizable: true,
+        platform_specific: window::settings::PlatformSpecific {
+            application_id: compositor::APP_ID.to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    // Start Iced as a daemon so it can open more windows than the launcher
+    // (the scratchpad); the launcher is the first, and closing it quits
+    iced::daemon(Ruty::title, Ruty::update, Ruty::view)
         .subscription(Ruty::subscription)
         .theme(Ruty::theme)
-        .window(window::Settings {
-            size: Size::new(700.0, 400.0),
-            position: window::Position::Centered,
-            decorations: false,
-            transparent: true,
-            level: window::Level::AlwaysOnTop,
-            resizable: true,
-            ..Default::default()
-        })
+        .scale_factor(move |_, _| ui_scale)
+        .settings(iced::Settings { default_text_size: ui::fonts::size(16).into(), ..Default::default() })
         .antialiasing(true)
-        .run()
+        .run_with(move || (startup::phase("ui state", Ruty::new), window::open(launcher).1.discard()))
+}
+
+/// Block the main thread of a headless daemon until a client attaches a
+/// window; exits if a quit arrives first
+fn wait_for_attach(controller: &WindowController) {
+    let mut commands = controller.take_commands().expect("Window commands already taken");
+    loop {
+        match commands.blocking_recv() {
+            Some(WindowCommand::Attach) => {
+                controller.set_visible(true);
+                controller.return_commands(commands);
+                return;
+            }
+            Some(WindowCommand::Quit) | None => {
+                tracing::info!("Quit requested via RPC");
+                crash::mark_clean_shutdown();
+                std::process::exit(0);
+            }
+            Some(command) => tracing::info!("No window attached; ignoring {:?}", command),
+        }
+    }
 }
 
 /// Get the global window controller
 pub fn get_window_controller() -> Option<Arc<WindowController>> {
     WINDOW_CONTROLLER.get().cloned()
 }
+
+/// Get the global services
+pub fn get_services() -> Option<Arc<Services>> {
+    SERVICES.get().cloned()
+}
@@ -0,0 +1,144 @@
+//! Audio output/input switching via pactl, volume via wpctl
+//!
+//! Works on PulseAudio and on PipeWire through pipewire-pulse; pactl is the
+//! one interface both provide. Volume uses WirePlumber's wpctl.
+
+use serde::Deserialize;
+use std::process::Command;
+
+/// wpctl's alias for the default output
+const DEFAULT_SINK: &str = "@DEFAULT_AUDIO_SINK@";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceKind {
+    /// Output (speakers, headphones)
+    Sink,
+    /// Input (microphones)
+    Source,
+}
+
+impl DeviceKind {
+    fn plural(self) -> &'static str {
+        match self {
+            Self::Sink => "sinks",
+            Self::Source => "sources",
+        }
+    }
+
+    fn noun(self) -> &'static str {
+        match self {
+            Self::Sink => "sink",
+            Self::Source => "source",
+        }
+    }
+}
+
+/// An audio sink or source
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioDevice {
+    pub kind: DeviceKind,
+    /// pactl name used to select the device
+    pub name: String,
+    pub description: String,
+    pub is_default: bool,
+}
+
+#[derive(Deserialize)]
+struct PactlDevice {
+    name: String,
+    #[serde(default)]
+    description: String,
+}
+
+/// Sinks or sources (monitor sources are skipped), default first
+pub fn devices(kind: DeviceKind) -> Result<Vec<AudioDevice>, String> {
+    let listing = pactl(&["--format=json", "list", kind.plural()])?;
+    let default = pactl(&[&format!("get-default-{}", kind.noun())])?;
+    parse_devices(kind, &listing, default.trim())
+}
+
+/// Make a device the default for its kind
+pub fn set_default(kind: DeviceKind, name: &str) -> Result<(), String> {
+    pactl(&[&format!("set-default-{}", kind.noun()), name]).map(|_| ())
+}
+
+/// Default sink volume as a percentage, and whether it's muted
+pub fn volume() -> Result<(u8, bool), String> {
+    let output = wpctl(&["get-volume", DEFAULT_SINK])?;
+    parse_volume(&output).ok_or_else(|| format!("Unexpected wpctl output: {}", output.trim()))
+}
+
+/// Set the default sink volume (capped at 100%)
+pub fn set_volume(percent: u8) -> Result<(), String> {
+    let level = format!("{:.2}", percent.min(100) as f64 / 100.0);
+    wpctl(&["set-volume", DEFAULT_SINK, &level]).map(|_| ())
+}
+
+/// Parse "Volume: 0.40" or "Volume: 0.40 [MUTED]"
+fn parse_volume(output: &str) -> Option<(u8, bool)> {
+    let rest = output.trim().strip_prefix("Volume:")?;
+    let level: f64 = rest.split_whitespace().next()?.parse().ok()?;
+    Some(((level * 100.0).round().clamp(0.0, 100.0) as u8, rest.contains("[MUTED]")))
+}
+
+fn parse_devices(kind: DeviceKind, json: &str, default: &str) -> Result<Vec<AudioDevice>, String> {
+    let listed: Vec<PactlDevice> =
+        serde_json::from_str(json).map_err(|e| format!("Unexpected pactl output: {}", e))?;
+    let mut devices: Vec<AudioDevice> = listed
+        .into_iter()
+        .filter(|d| !d.name.ends_with(".monitor"))
+        .map(|d| AudioDevice {
+            kind,
+            is_default: d.name == default,
+            description: if d.description.is_empty() { d.name.clone() } else { d.description },
+            name: d.name,
+        })
+        .collect();
+    devices.sort_by_key(|d| !d.is_default);
+    Ok(devices)
+}
+
+fn pactl(args: &[&str]) -> Result<String, String> {
+    run("pactl", args)
+}
+
+fn wpctl(args: &[&str]) -> Result<String, String> {
+    run("wpctl", args)
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("{} unavailable: {}", program, e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_devices() {
+        let json = r#"[
+            {"index": 1, "name": "alsa_output.pci.analog-stereo", "description": "Built-in Audio"},
+            {"index": 2, "name": "bluez_output.AA_BB.1", "description": "AirPods Pro"},
+            {"index": 3, "name": "alsa_output.pci.analog-stereo.monitor", "description": "Monitor"}
+        ]"#;
+        let devices = parse_devices(DeviceKind::Sink, json, "bluez_output.AA_BB.1").unwrap();
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].description, "AirPods Pro");
+        assert!(devices[0].is_default);
+        assert!(parse_devices(DeviceKind::Sink, "not json", "").is_err());
+    }
+
+    #[test]
+    fn test_parse_volume() {
+        assert_eq!(parse_volume("Volume: 0.40\n"), Some((40, false)));
+        assert_eq!(parse_volume("Volume: 1.50 [MUTED]"), Some((100, true)));
+        assert_eq!(parse_volume("error"), None);
+    }
+}
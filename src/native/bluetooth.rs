@@ -0,0 +1,74 @@
+//! Bluetooth devices via BlueZ over D-Bus
+//!
+//! Lists paired devices and connects or disconnects them, so typing a
+//! device name and pressing Enter toggles the connection.
+
+use std::collections::HashMap;
+
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+
+const BLUEZ: &str = "org.bluez";
+const DEVICE_IFACE: &str = "org.bluez.Device1";
+
+type ManagedObjects = HashMap<OwnedObjectPath, HashMap<String, HashMap<String, OwnedValue>>>;
+
+/// A paired Bluetooth device
+#[derive(Debug, Clone, PartialEq)]
+pub struct Device {
+    /// BlueZ object path, e.g. /org/bluez/hci0/dev_XX_XX
+    pub path: String,
+    pub name: String,
+    pub address: String,
+    pub connected: bool,
+}
+
+/// Paired devices, connected first
+pub fn devices() -> Result<Vec<Device>, String> {
+    let conn = Connection::system().map_err(|e| format!("D-Bus unavailable: {}", e))?;
+    let manager = Proxy::new(&conn, BLUEZ, "/", "org.freedesktop.DBus.ObjectManager")
+        .map_err(|e| e.to_string())?;
+    let objects: ManagedObjects = manager
+        .call("GetManagedObjects", &())
+        .map_err(|e| format!("BlueZ unavailable: {}", e))?;
+
+    let mut devices: Vec<Device> = objects
+        .into_iter()
+        .filter_map(|(path, interfaces)| {
+            let props = interfaces.get(DEVICE_IFACE)?;
+            if !flag(props, "Paired") {
+                return None;
+            }
+            let address = text(props, "Address").unwrap_or_default();
+            Some(Device {
+                path: path.to_string(),
+                name: text(props, "Alias")
+                    .or_else(|| text(props, "Name"))
+                    .unwrap_or_else(|| address.clone()),
+                address,
+                connected: flag(props, "Connected"),
+            })
+        })
+        .collect();
+    devices.sort_by(|a, b| b.connected.cmp(&a.connected).then_with(|| a.name.cmp(&b.name)));
+    Ok(devices)
+}
+
+/// Connect a device, or disconnect it if it's already connected
+pub fn toggle(path: &str, connected: bool) -> Result<(), String> {
+    let conn = Connection::system().map_err(|e| format!("D-Bus unavailable: {}", e))?;
+    let device = Proxy::new(&conn, BLUEZ, path, DEVICE_IFACE).map_err(|e| e.to_string())?;
+    let method = if connected { "Disconnect" } else { "Connect" };
+    device
+        .call_method(method, &())
+        .map(|_| ())
+        .map_err(|e| format!("{} failed: {}", method, e))
+}
+
+fn text(props: &HashMap<String, OwnedValue>, key: &str) -> Option<String> {
+    props.get(key).and_then(|v| String::try_from(v.clone()).ok())
+}
+
+fn flag(props: &HashMap<String, OwnedValue>, key: &str) -> bool {
+    props.get(key).and_then(|v| bool::try_from(v).ok()).unwrap_or(false)
+}
@@ -0,0 +1,168 @@
+//! Screen brightness and night light
+//!
+//! Laptop panels are read from /sys/class/backlight and set through logind
+//! (no root or udev rule needed); external monitors go through ddcutil (DDC/CI
+//! VCP code 0x10). Night light is GNOME's color plugin setting.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const BACKLIGHT_DIR: &str = "/sys/class/backlight";
+/// DDC/CI VCP feature code for luminance
+const VCP_BRIGHTNESS: &str = "10";
+const NIGHT_LIGHT_SCHEMA: &str = "org.gnome.settings-daemon.plugins.color";
+const NIGHT_LIGHT_KEY: &str = "night-light-enabled";
+
+/// A backlight device and its brightness as a percentage
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backlight {
+    /// sysfs device name, e.g. intel_backlight
+    pub name: String,
+    pub percent: u8,
+}
+
+/// Backlight devices under /sys/class/backlight
+pub fn backlights() -> Vec<Backlight> {
+    let Ok(entries) = fs::read_dir(BACKLIGHT_DIR) else {
+        return Vec::new();
+    };
+    let mut lights: Vec<Backlight> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let (current, max) = read_levels(&entry.path())?;
+            Some(Backlight {
+                name: entry.file_name().to_string_lossy().to_string(),
+                percent: to_percent(current, max),
+            })
+        })
+        .collect();
+    lights.sort_by(|a, b| a.name.cmp(&b.name));
+    lights
+}
+
+/// Set a backlight to `percent` via logind, falling back to writing sysfs
+pub fn set_backlight(name: &str, percent: u8) -> Result<(), String> {
+    let dir = Path::new(BACKLIGHT_DIR).join(name);
+    let (_, max) = read_levels(&dir).ok_or_else(|| format!("No backlight named {}", name))?;
+    let value = from_percent(percent, max);
+
+    match logind_set_brightness(name, value) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            tracing::debug!("logind SetBrightness failed ({}), writing sysfs", e);
+            fs::write(dir.join("brightness"), value.to_string())
+                .map_err(|e| format!("Cannot set brightness: {}", e))
+        }
+    }
+}
+
+fn logind_set_brightness(name: &str, value: u32) -> zbus::Result<()> {
+    use zbus::blocking::{Connection, Proxy};
+
+    let conn = Connection::system()?;
+    let session = Proxy::new(
+        &conn,
+        "org.freedesktop.login1",
+        "/org/freedesktop/login1/session/auto",
+        "org.freedesktop.login1.Session",
+    )?;
+    session.call_method("SetBrightness", &("backlight", name, value))?;
+    Ok(())
+}
+
+fn read_levels(dir: &Path) -> Option<(u32, u32)> {
+    let read = |file: &str| fs::read_to_string(dir.join(file)).ok()?.trim().parse::<u32>().ok();
+    let max = read("max_brightness").filter(|max| *max > 0)?;
+    Some((read("brightness")?, max))
+}
+
+fn to_percent(value: u32, max: u32) -> u8 {
+    ((value.min(max) as f64 / max as f64) * 100.0).round() as u8
+}
+
+fn from_percent(percent: u8, max: u32) -> u32 {
+    ((percent.min(100) as f64 / 100.0) * max as f64).round() as u32
+}
+
+/// Brightness of the first DDC/CI monitor, `None` without ddcutil or a monitor
+pub fn external_brightness() -> Option<u8> {
+    let output = ddcutil(&["getvcp", VCP_BRIGHTNESS, "--brief"]).ok()?;
+    parse_vcp(&output)
+}
+
+/// Set the first DDC/CI monitor's brightness
+pub fn set_external_brightness(percent: u8) -> Result<(), String> {
+    ddcutil(&["setvcp", VCP_BRIGHTNESS, &percent.min(100).to_string()]).map(|_| ())
+}
+
+/// Parse `getvcp --brief` output ("VCP 10 C 50 100") into a percentage
+fn parse_vcp(output: &str) -> Option<u8> {
+    let line = output.lines().find(|l| l.starts_with("VCP "))?;
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let [_, _, "C", current, max] = fields.as_slice() else {
+        return None;
+    };
+    let (current, max) = (current.parse::<u32>().ok()?, max.parse::<u32>().ok()?);
+    (max > 0).then(|| to_percent(current, max))
+}
+
+fn ddcutil(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("ddcutil")
+        .args(args)
+        .output()
+        .map_err(|e| format!("ddcutil unavailable: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Whether night light is on, `None` outside GNOME
+pub fn night_light() -> Option<bool> {
+    let output = Command::new("gsettings")
+        .args(["get", NIGHT_LIGHT_SCHEMA, NIGHT_LIGHT_KEY])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Turn night light on or off
+pub fn set_night_light(enabled: bool) -> Result<(), String> {
+    let status = Command::new("gsettings")
+        .args(["set", NIGHT_LIGHT_SCHEMA, NIGHT_LIGHT_KEY, if enabled { "true" } else { "false" }])
+        .status()
+        .map_err(|e| format!("gsettings unavailable: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(String::from("Failed to change night light"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_conversion() {
+        assert_eq!(to_percent(96000, 96000), 100);
+        assert_eq!(to_percent(48000, 96000), 50);
+        assert_eq!(from_percent(50, 96000), 48000);
+        assert_eq!(from_percent(120, 255), 255);
+    }
+
+    #[test]
+    fn test_parse_vcp() {
+        assert_eq!(parse_vcp("VCP 10 C 40 80\n"), Some(50));
+        assert_eq!(parse_vcp("VCP 10 ERR\n"), None);
+        assert_eq!(parse_vcp("No monitor detected"), None);
+    }
+}
@@ -0,0 +1,214 @@
+//! Window rules for tiling compositors
+//!
+//! Hyprland and Sway tile new windows unless a rule says otherwise, so at
+//! startup the launcher registers rules (over hyprctl/swaymsg IPC) matching
+//! its app id that make it float centered at its default size. Other
+//! desktops float undecorated windows already.
+//!
+//! The same IPC reads the monitor layout and moves the window to its saved
+//! geometry (see `ruty_core::geometry`) each time it's shown; on X11 the
+//! layout comes from `xrandr` and the UI moves the window itself.
+
+use std::process::Command;
+
+use ruty_core::geometry::{self, Geometry, Monitor};
+
+/// Wayland app_id and X11 WM_CLASS of the launcher window
+pub const APP_ID: &str = "ruty";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Compositor {
+    Hyprland,
+    Sway,
+    /// Anything else, by XDG_CURRENT_DESKTOP if set
+    Other(String),
+}
+
+impl Compositor {
+    /// Detect the running compositor from the environment it exports
+    pub fn detect() -> Self {
+        Self::from_env(|name| std::env::var(name).ok())
+    }
+
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        if var("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+            Self::Hyprland
+        } else if var("SWAYSOCK").is_some() {
+            Self::Sway
+        } else {
+            Self::Other(var("XDG_CURRENT_DESKTOP").unwrap_or_default())
+        }
+    }
+
+    /// IPC invocations (program, args) that make the launcher float centered
+    pub fn rule_commands(&self, width: u32, height: u32) -> Vec<(&'static str, Vec<String>)> {
+        match self {
+            Self::Hyprland => {
+                let class = format!("class:^({})$", APP_ID);
+                ["float".to_string(), "center".to_string(), format!("size {} {}", width, height)]
+                    .into_iter()
+                    .map(|rule| {
+                        let args = vec!["keyword".to_string(), "windowrulev2".to_string(), format!("{}, {}", rule, class)];
+                        ("hyprctl", args)
+                    })
+                    .collect()
+            }
+            Self::Sway => {
+                let actions = format!("floating enable, resize set {} {}, move position center", width, height);
+                // app_id for native Wayland, class for XWayland
+                [format!("app_id=\"^{}$\"", APP_ID), format!("class=\"^{}$\"", APP_ID)]
+                    .into_iter()
+                    .map(|criteria| ("swaymsg", vec![format!("for_window [{}] {}", criteria, actions)]))
+                    .collect()
+            }
+            Self::Other(_) => Vec::new(),
+        }
+    }
+
+    /// Whether the launcher is sized and moved over IPC; elsewhere the UI
+    /// moves it, if the display server lets clients do that
+    pub fn places_windows(&self) -> bool {
+        matches!(self, Self::Hyprland | Self::Sway)
+    }
+
+    /// Connected monitors; empty where the layout can't be read (Wayland
+    /// desktops other than Hyprland and Sway)
+    pub fn monitors(&self) -> Vec<Monitor> {
+        let read = |program: &str, args: &[&str]| -> Result<String, String> {
+            let output = Command::new(program).args(args).output().map_err(|e| e.to_string())?;
+            if !output.status.success() {
+                return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        };
+        let monitors = match self {
+            Self::Hyprland => read("hyprctl", &["monitors", "-j"]).and_then(|json| geometry::parse_hyprctl_monitors(&json)),
+            Self::Sway => read("swaymsg", &["-t", "get_outputs", "-r"]).and_then(|json| geometry::parse_sway_outputs(&json)),
+            // Under XWayland xrandr answers, but clients can't move windows
+            Self::Other(_) if std::env::var_os("WAYLAND_DISPLAY").is_none() && std::env::var_os("DISPLAY").is_some() => {
+                read("xrandr", &["--query"]).map(|output| geometry::parse_xrandr(&output))
+            }
+            Self::Other(_) => Ok(Vec::new()),
+        };
+        monitors.unwrap_or_else(|e| {
+            tracing::warn!("Couldn't read the monitor layout: {}", e);
+            Vec::new()
+        })
+    }
+
+    /// IPC invocations that give the launcher `geometry`'s size with its
+    /// top-left corner at `(x, y)`
+    pub fn place_commands(&self, geometry: &Geometry, (x, y): (i32, i32)) -> Vec<(&'static str, Vec<String>)> {
+        match self {
+            Self::Hyprland => {
+                let class = format!("class:^({})$", APP_ID);
+                [
+                    format!("resizewindowpixel exact {} {},{}", geometry.width, geometry.height, class),
+                    format!("movewindowpixel exact {} {},{}", x, y, class),
+                ]
+                .into_iter()
+                .map(|dispatch| ("hyprctl", vec!["dispatch".to_string(), dispatch]))
+                .collect()
+            }
+            Self::Sway => {
+                let actions = format!("resize set {} {}, move absolute position {} {}", geometry.width, geometry.height, x, y);
+                vec![("swaymsg", vec![format!("[app_id=\"^{}$\"] {}", APP_ID, actions)])]
+            }
+            Self::Other(_) => Vec::new(),
+        }
+    }
+}
+
+/// Size and move the launcher over compositor IPC; failures are logged
+pub fn place_window(compositor: &Compositor, geometry: &Geometry, position: (i32, i32)) {
+    for (program, args) in compositor.place_commands(geometry, position) {
+        if !run(program, &args) {
+            return;
+        }
+    }
+}
+
+/// Register floating/centering rules with the running compositor; failures
+/// are logged, the window then opens wherever the compositor puts it
+pub fn apply_window_rules(width: u32, height: u32) {
+    let compositor = Compositor::detect();
+    let commands = compositor.rule_commands(width, height);
+    if commands.is_empty() {
+        return;
+    }
+    tracing::info!("Registering window rules with {:?}", compositor);
+    for (program, args) in commands {
+        if !run(program, &args) {
+            return;
+        }
+    }
+}
+
+/// Run one IPC invocation, logging a rejection; false if the program is
+/// missing, so there's no point running more
+fn run(program: &str, args: &[String]) -> bool {
+    match Command::new(program).args(args).output() {
+        Ok(output) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            // hyprctl exits 0 even when it rejects a command; it answers "ok" otherwise
+            let accepted = output.status.success() && (program != "hyprctl" || stdout.trim() == "ok");
+            if !accepted {
+                tracing::warn!(
+                    "{} {} failed: {}{}",
+                    program,
+                    args.join(" "),
+                    stdout.trim(),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+            true
+        }
+        Err(e) => {
+            tracing::warn!("{} unavailable: {}", program, e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(vars: &[(&str, &str)]) -> Compositor {
+        Compositor::from_env(|name| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string()))
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect(&[("HYPRLAND_INSTANCE_SIGNATURE", "abc"), ("SWAYSOCK", "/s")]), Compositor::Hyprland);
+        assert_eq!(detect(&[("SWAYSOCK", "/run/user/1000/sway-ipc.sock")]), Compositor::Sway);
+        assert_eq!(detect(&[("XDG_CURRENT_DESKTOP", "GNOME")]), Compositor::Other("GNOME".into()));
+    }
+
+    #[test]
+    fn test_rule_commands() {
+        let hyprland = Compositor::Hyprland.rule_commands(700, 400);
+        assert_eq!(hyprland.len(), 3);
+        assert_eq!(hyprland[2].1, vec!["keyword", "windowrulev2", "size 700 400, class:^(ruty)$"]);
+
+        let sway = Compositor::Sway.rule_commands(700, 400);
+        assert_eq!(
+            sway[0].1,
+            vec!["for_window [app_id=\"^ruty$\"] floating enable, resize set 700 400, move position center"]
+        );
+        assert!(Compositor::Other("KDE".into()).rule_commands(700, 400).is_empty());
+    }
+
+    #[test]
+    fn test_place_commands() {
+        let geometry = Geometry { width: 800, height: 300, anchor: geometry::Anchor::Top };
+        let hyprland = Compositor::Hyprland.place_commands(&geometry, (1920, 216));
+        assert_eq!(hyprland[0].1, vec!["dispatch", "resizewindowpixel exact 800 300,class:^(ruty)$"]);
+        assert_eq!(hyprland[1].1, vec!["dispatch", "movewindowpixel exact 1920 216,class:^(ruty)$"]);
+
+        let sway = Compositor::Sway.place_commands(&geometry, (10, 20));
+        assert_eq!(sway[0].1, vec!["[app_id=\"^ruty$\"] resize set 800 300, move absolute position 10 20"]);
+        assert!(!Compositor::Other("GNOME".into()).places_windows());
+        assert!(Compositor::Other("GNOME".into()).place_commands(&geometry, (0, 0)).is_empty());
+    }
+}
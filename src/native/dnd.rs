@@ -0,0 +1,112 @@
+//! Do Not Disturb
+//!
+//! GNOME keeps it in the notifications `show-banners` setting, flipped with
+//! gsettings as night light is. Plasma's notification server takes an
+//! inhibition over D-Bus (`org.freedesktop.Notifications.Inhibit`) that lasts
+//! as long as the connection that asked for it, so the daemon holds on to
+//! that connection until Do Not Disturb is turned off again (so it can't lift
+//! Do Not Disturb switched on from Plasma's own applet).
+
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::Mutex;
+
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::Value;
+
+const GNOME_SCHEMA: &str = "org.gnome.desktop.notifications";
+const GNOME_KEY: &str = "show-banners";
+const NOTIFICATIONS: &str = "org.freedesktop.Notifications";
+const NOTIFICATIONS_PATH: &str = "/org/freedesktop/Notifications";
+
+/// The inhibition this daemon holds on Plasma: its connection and cookie
+static INHIBITION: Mutex<Option<(Connection, u32)>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Desktop {
+    Gnome,
+    Kde,
+}
+
+impl Desktop {
+    fn detect() -> Option<Self> {
+        Self::from_name(&std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default())
+    }
+
+    /// From XDG_CURRENT_DESKTOP, a colon-separated list such as "ubuntu:GNOME"
+    fn from_name(name: &str) -> Option<Self> {
+        name.split(':').find_map(|desktop| match desktop.to_uppercase().as_str() {
+            "GNOME" | "UNITY" | "BUDGIE" => Some(Self::Gnome),
+            "KDE" => Some(Self::Kde),
+            _ => None,
+        })
+    }
+}
+
+/// Whether Do Not Disturb is on (`None` on desktops it can't be told for)
+pub fn enabled() -> Option<bool> {
+    match Desktop::detect()? {
+        Desktop::Gnome => {
+            let output = Command::new("gsettings").args(["get", GNOME_SCHEMA, GNOME_KEY]).output().ok()?;
+            match String::from_utf8_lossy(&output.stdout).trim() {
+                "true" => Some(false),
+                "false" => Some(true),
+                _ => None,
+            }
+        }
+        Desktop::Kde => {
+            let conn = Connection::session().ok()?;
+            let proxy = Proxy::new(&conn, NOTIFICATIONS, NOTIFICATIONS_PATH, NOTIFICATIONS).ok()?;
+            proxy.get_property::<bool>("Inhibited").ok()
+        }
+    }
+}
+
+/// Turn Do Not Disturb on or off
+pub fn set(enabled: bool) -> Result<(), String> {
+    match Desktop::detect() {
+        Some(Desktop::Gnome) => {
+            let status = Command::new("gsettings")
+                .args(["set", GNOME_SCHEMA, GNOME_KEY, if enabled { "false" } else { "true" }])
+                .status()
+                .map_err(|e| format!("gsettings unavailable: {}", e))?;
+            if status.success() {
+                Ok(())
+            } else {
+                Err(String::from("Failed to change Do Not Disturb"))
+            }
+        }
+        Some(Desktop::Kde) => set_inhibited(enabled).map_err(|e| format!("Plasma notifications: {}", e)),
+        None => Err(String::from("Do Not Disturb can only be switched on GNOME and KDE Plasma")),
+    }
+}
+
+fn set_inhibited(enabled: bool) -> zbus::Result<()> {
+    let mut inhibition = INHIBITION.lock().unwrap();
+    if enabled {
+        if inhibition.is_none() {
+            let conn = Connection::session()?;
+            let proxy = Proxy::new(&conn, NOTIFICATIONS, NOTIFICATIONS_PATH, NOTIFICATIONS)?;
+            let hints: HashMap<&str, Value> = HashMap::new();
+            let cookie: u32 = proxy.call("Inhibit", &("ruty", "Do Not Disturb", hints))?;
+            *inhibition = Some((conn, cookie));
+        }
+    } else if let Some((conn, cookie)) = inhibition.take() {
+        let proxy = Proxy::new(&conn, NOTIFICATIONS, NOTIFICATIONS_PATH, NOTIFICATIONS)?;
+        proxy.call::<_, _, ()>("UnInhibit", &(cookie,))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_desktop() {
+        assert_eq!(Desktop::from_name("ubuntu:GNOME"), Some(Desktop::Gnome));
+        assert_eq!(Desktop::from_name("KDE"), Some(Desktop::Kde));
+        assert_eq!(Desktop::from_name("Hyprland"), None);
+        assert_eq!(Desktop::from_name(""), None);
+    }
+}
@@ -0,0 +1,62 @@
+//! Model downloads
+//!
+//! Local models are fetched into a `.part` file next to where they belong
+//! and only renamed into place once complete (and matching their pinned
+//! SHA-256, where there is one), so a cancelled download is never mistaken
+//! for the model.
+
+use std::fs;
+use std::path::Path;
+
+use tokio::io::AsyncWriteExt;
+
+use ruty_core::checksum::{self, Algorithm};
+use ruty_core::progress::Progress;
+
+/// Download `url` to `path`, counting bytes in `progress`; `what` names the
+/// file in errors
+pub async fn download(
+    url: &str,
+    path: &Path,
+    sha256: Option<&str>,
+    progress: &Progress,
+    what: &str,
+) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+    }
+    tracing::info!("Downloading {} to {}", what, path.display());
+    let partial = path.with_extension("part");
+    let result = fetch(url, &partial, progress, what)
+        .await
+        .and_then(|()| sha256.map_or(Ok(()), |sha256| verify(&partial, sha256, what)));
+    match result {
+        Ok(()) => fs::rename(&partial, path).map_err(|e| format!("Cannot save the {}: {}", what, e)),
+        Err(e) => {
+            fs::remove_file(&partial).ok();
+            Err(e)
+        }
+    }
+}
+
+async fn fetch(url: &str, partial: &Path, progress: &Progress, what: &str) -> Result<(), String> {
+    let failed = |e: reqwest::Error| format!("Downloading the {} failed: {}", what, e);
+    let mut response = reqwest::get(url).await.and_then(|r| r.error_for_status()).map_err(failed)?;
+    let mut file = tokio::fs::File::create(partial)
+        .await
+        .map_err(|e| format!("Cannot save the {}: {}", what, e))?;
+    while let Some(chunk) = response.chunk().await.map_err(failed)? {
+        file.write_all(&chunk).await.map_err(|e| format!("Cannot save the {}: {}", what, e))?;
+        progress.add(chunk.len() as u64);
+    }
+    file.flush().await.map_err(|e| format!("Cannot save the {}: {}", what, e))
+}
+
+/// Refuse a download that isn't the file we pinned
+fn verify(partial: &Path, sha256: &str, what: &str) -> Result<(), String> {
+    let digest = checksum::hash_file(Algorithm::Sha256, partial, &Progress::new(0))?;
+    if digest != sha256 {
+        return Err(format!("The downloaded {} isn't the expected file (SHA-256 {})", what, digest));
+    }
+    Ok(())
+}
@@ -0,0 +1,43 @@
+//! Downloading the sentence model local memory runs on
+//!
+//! The weights must match a pinned SHA-256. The tokenizer is small and
+//! isn't pinned; it's checked by loading the model with it, and dropped if
+//! that fails.
+
+use std::fs;
+use std::sync::Arc;
+
+use ruty_core::embedding::{self, Model};
+use ruty_core::progress::Progress;
+
+use super::download;
+
+pub use ruty_core::embedding::needs_model;
+
+/// Where the model files are downloaded from
+const MODEL_URL: &str = "https://huggingface.co/sentence-transformers/all-MiniLM-L6-v2/resolve/main";
+
+/// SHA-256 of the weights
+const WEIGHTS_SHA256: &str = "53aa51172d142c89d9012cce15ae4d6cc0ca6895895114379cacb4fab128d9db";
+
+/// Size of the download in MiB, to ask before starting it
+pub const MODEL_MB: u64 = 87;
+
+/// Download the embedding model, counting bytes in `progress`
+pub async fn download_model(progress: Arc<Progress>) -> Result<(), String> {
+    let dir = embedding::model_dir();
+    let weights = dir.join(embedding::WEIGHTS);
+    let tokenizer = dir.join(embedding::TOKENIZER);
+    let url = |file: &str| format!("{}/{}", MODEL_URL, file);
+    download::download(&url(embedding::TOKENIZER), &tokenizer, None, &progress, "embedding tokenizer").await?;
+    if !weights.exists() {
+        download::download(&url(embedding::WEIGHTS), &weights, Some(WEIGHTS_SHA256), &progress, "embedding model")
+            .await?;
+    }
+    let loaded = tokio::task::spawn_blocking(Model::load).await.map_err(|e| e.to_string())?;
+    if let Err(e) = loaded {
+        fs::remove_file(&tokenizer).ok();
+        return Err(e);
+    }
+    Ok(())
+}
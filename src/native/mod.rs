@@ -1,7 +1,17 @@
 //! Native platform modules
 //!
-//! These are migrated from src-tauri/src/
+//! The shared implementations live in the `ruty-core` crate.
 
-pub mod apps;
-pub mod files;
-pub mod clipboard;
+pub use ruty_core::{apps, clipboard, files};
+
+pub mod audio;
+pub mod bluetooth;
+pub mod brightness;
+pub mod compositor;
+pub mod dnd;
+pub mod download;
+pub mod embedding;
+pub mod network;
+pub mod screenshot;
+pub mod voice;
+pub mod windows;
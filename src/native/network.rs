@@ -0,0 +1,159 @@
+//! Network information quick answers
+//!
+//! Answers "ip", "public ip" and "wifi" queries: interface addresses come
+//! from getifaddrs, the SSID from NetworkManager over D-Bus and the public
+//! IP from a lookup service (cached, since it rarely changes).
+
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Service returning the caller's public IP as plain text
+const PUBLIC_IP_URL: &str = "https://api.ipify.org";
+/// How long a fetched public IP is reused
+const PUBLIC_IP_TTL: Duration = Duration::from_secs(600);
+
+static PUBLIC_IP: Mutex<Option<(Instant, String)>> = Mutex::new(None);
+
+/// Which quick answer a query asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetQuery {
+    /// Local addresses plus the public IP
+    Ip,
+    PublicIp,
+    Wifi,
+}
+
+impl NetQuery {
+    /// Recognize a network query (whole prompt, case-insensitive)
+    pub fn parse(query: &str) -> Option<Self> {
+        match query.trim().to_lowercase().as_str() {
+            "ip" | "ip address" | "local ip" => Some(Self::Ip),
+            "public ip" | "my ip" | "external ip" => Some(Self::PublicIp),
+            "wifi" | "wi-fi" | "ssid" => Some(Self::Wifi),
+            _ => None,
+        }
+    }
+}
+
+/// An address on a non-loopback interface
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceAddr {
+    pub interface: String,
+    pub addr: IpAddr,
+}
+
+/// Addresses of all non-loopback interfaces, IPv4 first
+pub fn local_addresses() -> Vec<InterfaceAddr> {
+    let mut addrs: Vec<InterfaceAddr> = match if_addrs::get_if_addrs() {
+        Ok(interfaces) => interfaces
+            .into_iter()
+            .filter(|iface| !iface.is_loopback())
+            .map(|iface| InterfaceAddr {
+                addr: iface.ip(),
+                interface: iface.name,
+            })
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Failed to list interfaces: {}", e);
+            Vec::new()
+        }
+    };
+    addrs.sort_by_key(|a| (a.addr.is_ipv6(), a.interface.clone()));
+    addrs
+}
+
+/// Public IP, fetched at most every `PUBLIC_IP_TTL`
+pub async fn public_ip() -> Result<String, String> {
+    if let Some((fetched, ip)) = PUBLIC_IP.lock().unwrap().as_ref() {
+        if fetched.elapsed() < PUBLIC_IP_TTL {
+            return Ok(ip.clone());
+        }
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let ip = client
+        .get(PUBLIC_IP_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Public IP lookup failed: {}", e))?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?
+        .trim()
+        .to_string();
+    ip.parse::<IpAddr>()
+        .map_err(|_| format!("Unexpected public IP response: {}", ip))?;
+
+    *PUBLIC_IP.lock().unwrap() = Some((Instant::now(), ip.clone()));
+    Ok(ip)
+}
+
+/// SSID of the active Wi-Fi connection, if any
+pub fn wifi_ssid() -> Option<String> {
+    match networkmanager_ssid() {
+        Ok(ssid) => ssid,
+        Err(e) => {
+            // No NetworkManager (e.g. iwd/wpa_supplicant only): ask iwgetid
+            tracing::debug!("NetworkManager unavailable ({}), trying iwgetid", e);
+            let output = std::process::Command::new("iwgetid").arg("-r").output().ok()?;
+            let ssid = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            (output.status.success() && !ssid.is_empty()).then_some(ssid)
+        }
+    }
+}
+
+fn networkmanager_ssid() -> zbus::Result<Option<String>> {
+    use zbus::blocking::{Connection, Proxy};
+    use zbus::zvariant::OwnedObjectPath;
+
+    const NM: &str = "org.freedesktop.NetworkManager";
+    /// NM_DEVICE_TYPE_WIFI
+    const DEVICE_TYPE_WIFI: u32 = 2;
+
+    let conn = Connection::system()?;
+    let manager = Proxy::new(&conn, NM, "/org/freedesktop/NetworkManager", NM)?;
+    let devices: Vec<OwnedObjectPath> = manager.get_property("Devices")?;
+
+    for device in devices {
+        let props = Proxy::new(&conn, NM, device.as_str(), "org.freedesktop.NetworkManager.Device")?;
+        if props.get_property::<u32>("DeviceType")? != DEVICE_TYPE_WIFI {
+            continue;
+        }
+        let wireless = Proxy::new(
+            &conn,
+            NM,
+            device.as_str(),
+            "org.freedesktop.NetworkManager.Device.Wireless",
+        )?;
+        let ap: OwnedObjectPath = wireless.get_property("ActiveAccessPoint")?;
+        if ap.as_str() == "/" {
+            continue;
+        }
+        let ap = Proxy::new(&conn, NM, ap.as_str(), "org.freedesktop.NetworkManager.AccessPoint")?;
+        let ssid: Vec<u8> = ap.get_property("Ssid")?;
+        return Ok(Some(String::from_utf8_lossy(&ssid).to_string()));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(NetQuery::parse("IP"), Some(NetQuery::Ip));
+        assert_eq!(NetQuery::parse(" public ip "), Some(NetQuery::PublicIp));
+        assert_eq!(NetQuery::parse("wifi"), Some(NetQuery::Wifi));
+        assert_eq!(NetQuery::parse("ip route"), None);
+    }
+
+    #[test]
+    fn test_local_addresses_skip_loopback() {
+        assert!(local_addresses().iter().all(|a| !a.addr.is_loopback()));
+    }
+}
@@ -0,0 +1,38 @@
+//! Region capture
+//!
+//! Lets the user drag out a screen region and returns it as a PNG, for
+//! reading QR codes off the screen. Wayland uses `slurp` to pick the region
+//! and `grim` to capture it (wlroots compositors); X11 uses `maim -s`.
+
+use std::process::{Command, Output};
+
+/// A user-selected region of the screen as PNG (`None` if the selection
+/// was cancelled)
+pub fn region() -> Result<Option<Vec<u8>>, String> {
+    let output = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let slurp = run("slurp", &[])?;
+        if !slurp.status.success() {
+            return Ok(None);
+        }
+        let geometry = String::from_utf8_lossy(&slurp.stdout).trim().to_string();
+        run("grim", &["-g", &geometry, "-"])?
+    } else {
+        run("maim", &["--select", "--hidecursor"])?
+    };
+    if !output.status.success() || output.stdout.is_empty() {
+        // maim says nothing when Escape cancels the selection
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return match stderr.trim() {
+            "" => Ok(None),
+            reason => Err(format!("Screen capture failed: {}", reason)),
+        };
+    }
+    Ok(Some(output.stdout))
+}
+
+fn run(program: &str, args: &[&str]) -> Result<Output, String> {
+    Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Cannot run {} (is it installed?): {}", program, e))
+}
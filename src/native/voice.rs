@@ -0,0 +1,262 @@
+//! Voice input: microphone recording and local speech-to-text
+//!
+//! Recording captures the default microphone with cpal into memory, mixed
+//! down to mono; the waveform indicator reads levels from the end of it while
+//! it grows. Transcription runs whisper.cpp (through whisper-rs) on it with
+//! the small multilingual model, which the user is asked to download on first
+//! use and which must match a pinned SHA-256. Nothing leaves the machine.
+//!
+//! cpal and whisper.cpp need ALSA headers and cmake to build, so they're
+//! behind the `voice` feature; without it, recording says voice input isn't
+//! built in.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ruty_core::paths;
+use ruty_core::progress::Progress;
+
+use super::download;
+
+/// Sample rate whisper expects
+const SAMPLE_RATE: u32 = 16_000;
+
+/// Recordings shorter than this are taken as a slipped key
+const MIN_DURATION: Duration = Duration::from_millis(300);
+
+/// Model file, where it's downloaded from and its SHA-256
+const MODEL: &str = "ggml-small.bin";
+const MODEL_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.bin";
+const MODEL_SHA256: &str = "1be3a9b2063867b937e64e2ec7483364a79917e157fa98c5d94b5c1fffea987b";
+
+/// Size of the model download in MiB, to ask before starting it
+pub const MODEL_MB: u64 = 466;
+
+/// Audio per waveform bar
+const BAR: Duration = Duration::from_millis(60);
+
+#[cfg(not(feature = "voice"))]
+const NOT_BUILT: &str = "This build of Ruty has no voice input (build it with --features voice)";
+
+/// A microphone recording in progress; dropping it stops the microphone
+#[cfg_attr(not(feature = "voice"), allow(dead_code))]
+pub struct Recording {
+    #[cfg(feature = "voice")]
+    _stream: cpal::Stream,
+    /// Mono audio at `rate`, appended from the audio thread
+    samples: Arc<Mutex<Vec<f32>>>,
+    rate: u32,
+    started: Instant,
+}
+
+/// Start recording from the default microphone
+#[cfg(feature = "voice")]
+pub fn record() -> Result<Recording, String> {
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use cpal::SampleFormat;
+
+    let device = cpal::default_host()
+        .default_input_device()
+        .ok_or_else(|| "No microphone found".to_string())?;
+    let config = device.default_input_config().map_err(|e| format!("Cannot use the microphone: {}", e))?;
+    let samples = Arc::new(Mutex::new(Vec::new()));
+    let stream = match config.sample_format() {
+        SampleFormat::F32 => capture::<f32>(&device, &config.config(), samples.clone()),
+        SampleFormat::I16 => capture::<i16>(&device, &config.config(), samples.clone()),
+        SampleFormat::U16 => capture::<u16>(&device, &config.config(), samples.clone()),
+        format => Err(format!("Unsupported microphone sample format: {}", format)),
+    }?;
+    stream.play().map_err(|e| format!("Cannot start recording: {}", e))?;
+    tracing::info!("Recording voice from {}", device.name().unwrap_or_default());
+    Ok(Recording { _stream: stream, samples, rate: config.sample_rate().0, started: Instant::now() })
+}
+
+#[cfg(not(feature = "voice"))]
+pub fn record() -> Result<Recording, String> {
+    Err(NOT_BUILT.to_string())
+}
+
+/// Input stream appending `device`'s audio to `samples`, mixed down to mono
+#[cfg(feature = "voice")]
+fn capture<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    samples: Arc<Mutex<Vec<f32>>>,
+) -> Result<cpal::Stream, String>
+where
+    T: cpal::SizedSample,
+    f32: cpal::FromSample<T>,
+{
+    use cpal::traits::DeviceTrait;
+    use cpal::Sample;
+
+    let channels = usize::from(config.channels.max(1));
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                let mono = data
+                    .chunks(channels)
+                    .map(|frame| frame.iter().map(|sample| f32::from_sample(*sample)).sum::<f32>() / channels as f32);
+                if let Ok(mut samples) = samples.lock() {
+                    samples.extend(mono);
+                }
+            },
+            |e| tracing::warn!("Microphone error: {}", e),
+            None,
+        )
+        .map_err(|e| format!("Cannot record from the microphone: {}", e))
+}
+
+impl Recording {
+    /// Loudness (0.0 to 1.0) of the last `bars` stretches of audio, oldest
+    /// first; silence before the recording started counts as zero
+    pub fn levels(&self, bars: usize) -> Vec<f32> {
+        let per_bar = (self.rate as u128 * BAR.as_millis() / 1000) as usize;
+        let samples = self.samples.lock().map(|samples| {
+            let start = samples.len().saturating_sub(bars * per_bar);
+            samples[start..].to_vec()
+        });
+        levels(&samples.unwrap_or_default(), bars, per_bar)
+    }
+
+    /// Stop recording; returns the audio at 16 kHz, or `None` for a slip of
+    /// the key
+    pub fn stop(self) -> Result<Option<Vec<f32>>, String> {
+        if self.started.elapsed() < MIN_DURATION {
+            return Ok(None);
+        }
+        let samples = self.samples.lock().map_err(|_| "Recording failed".to_string())?;
+        Ok(Some(resample(&samples, self.rate, SAMPLE_RATE)))
+    }
+}
+
+/// Transcribe 16 kHz audio with whisper.cpp (blocking)
+#[cfg(feature = "voice")]
+pub fn transcribe(audio: &[f32]) -> Result<String, String> {
+    use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+    let model = model_path();
+    if !model.exists() {
+        return Err("The speech model isn't downloaded".to_string());
+    }
+    let context = WhisperContext::new_with_params(&model.to_string_lossy(), WhisperContextParameters::default())
+        .map_err(|e| format!("Cannot load the speech model: {}", e))?;
+    let mut state = context.create_state().map_err(|e| format!("Cannot start whisper: {}", e))?;
+
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_language(Some("auto"));
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    state.full(params, audio).map_err(|e| format!("Transcription failed: {}", e))?;
+
+    let segments = state.full_n_segments().map_err(|e| format!("Transcription failed: {}", e))?;
+    let lines = (0..segments)
+        .map(|segment| state.full_get_segment_text(segment))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Transcription failed: {}", e))?;
+    Ok(clean_transcript(&lines.join("\n")))
+}
+
+#[cfg(not(feature = "voice"))]
+pub fn transcribe(_audio: &[f32]) -> Result<String, String> {
+    Err(NOT_BUILT.to_string())
+}
+
+/// Where the whisper model lives
+pub fn model_path() -> PathBuf {
+    paths::data_dir().join("models").join(MODEL)
+}
+
+/// Whether voice input has to download the model before it can be used
+pub fn needs_model() -> bool {
+    cfg!(feature = "voice") && !model_path().exists()
+}
+
+/// Download the whisper model, counting bytes in `progress`; it's only kept
+/// if it matches the pinned SHA-256
+pub async fn download_model(progress: Arc<Progress>) -> Result<(), String> {
+    download::download(MODEL_URL, &model_path(), Some(MODEL_SHA256), &progress, "speech model").await
+}
+
+/// Loudness of consecutive `per_bar` stretches of audio, right-aligned into
+/// `bars` values
+fn levels(samples: &[f32], bars: usize, per_bar: usize) -> Vec<f32> {
+    let mut levels: Vec<f32> = samples
+        .chunks(per_bar.max(1))
+        .map(|chunk| {
+            let power = chunk.iter().map(|sample| sample * sample).sum::<f32>() / chunk.len() as f32;
+            // Speech RMS sits well below full scale; stretch it to fill the bar
+            (power.sqrt() * 4.0).min(1.0)
+        })
+        .collect();
+    if levels.len() > bars {
+        levels.drain(..levels.len() - bars);
+    }
+    let mut padded = vec![0.0; bars - levels.len()];
+    padded.append(&mut levels);
+    padded
+}
+
+/// `samples` at `from` Hz, linearly interpolated to `to` Hz
+fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let step = from as f64 / to as f64;
+    let len = (samples.len() as f64 / step) as usize;
+    (0..len)
+        .map(|i| {
+            let position = i as f64 * step;
+            let index = position as usize;
+            let next = samples.get(index + 1).unwrap_or(&samples[index]);
+            samples[index] + (next - samples[index]) * (position - index as f64) as f32
+        })
+        .collect()
+}
+
+/// Transcript text without whisper's markers for silence and noise
+#[cfg_attr(not(feature = "voice"), allow(dead_code))]
+fn clean_transcript(output: &str) -> String {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter(|line| !(line.starts_with('[') && line.ends_with(']')))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels() {
+        let loud = vec![0.25; 960];
+        let quiet = vec![0.0; 960];
+        let levels = levels(&[quiet, loud].concat(), 4, 960);
+        assert_eq!(levels.len(), 4);
+        assert_eq!(&levels[..3], &[0.0, 0.0, 0.0]);
+        assert_eq!(levels[3], 1.0);
+    }
+
+    #[test]
+    fn test_resample() {
+        let samples: Vec<f32> = (0..48).map(|i| i as f32).collect();
+        let resampled = resample(&samples, 48_000, SAMPLE_RATE);
+        assert_eq!(resampled.len(), 16);
+        assert_eq!(resampled[..3], [0.0, 3.0, 6.0]);
+        assert_eq!(resample(&[0.0, 1.0], 8_000, SAMPLE_RATE), vec![0.0, 0.5, 1.0, 1.0]);
+        assert_eq!(resample(&samples, SAMPLE_RATE, SAMPLE_RATE), samples);
+    }
+
+    #[test]
+    fn test_clean_transcript() {
+        assert_eq!(clean_transcript("\n [BLANK_AUDIO]\n Open the pod bay doors.\n  please\n"), "Open the pod bay doors. please");
+        assert_eq!(clean_transcript("[Music]"), "");
+    }
+}
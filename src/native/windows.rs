@@ -0,0 +1,239 @@
+//! Open window list
+//!
+//! Lists top-level windows so launching an app that's already open can
+//! switch to it instead. Hyprland and Sway are asked over their IPC
+//! (`hyprctl clients`, `swaymsg -t get_tree`); elsewhere `wmctrl` lists X11
+//! windows. Windows are matched to apps by class: the Wayland app_id or X11
+//! WM_CLASS against the desktop file's `StartupWMClass` or desktop id.
+//! The focused window's class tells clipboard history where a copy came from.
+
+use std::process::Command;
+
+use serde_json::Value;
+
+use super::apps::Application;
+use super::compositor::{Compositor, APP_ID};
+
+/// A top-level window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Window {
+    /// Handle the window list provider focuses it by
+    pub id: String,
+    /// Wayland app_id or X11 WM_CLASS
+    pub class: String,
+    pub title: String,
+    /// Has keyboard focus (not known for X11 windows)
+    pub focused: bool,
+}
+
+/// Open windows, other than the launcher's own (empty if they can't be listed)
+pub fn list() -> Vec<Window> {
+    let compositor = Compositor::detect();
+    let (program, args): (&str, &[&str]) = match compositor {
+        Compositor::Hyprland => ("hyprctl", &["clients", "-j"]),
+        Compositor::Sway => ("swaymsg", &["-t", "get_tree", "-r"]),
+        Compositor::Other(_) => ("wmctrl", &["-lx"]),
+    };
+    let output = match Command::new(program).args(args).output() {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout).to_string(),
+        Ok(output) => {
+            tracing::debug!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim());
+            return Vec::new();
+        }
+        Err(e) => {
+            tracing::debug!("{} unavailable: {}", program, e);
+            return Vec::new();
+        }
+    };
+    let windows = match compositor {
+        Compositor::Hyprland => parse_hyprland(&output),
+        Compositor::Sway => parse_sway(&output),
+        Compositor::Other(_) => parse_wmctrl(&output),
+    };
+    windows.into_iter().filter(|w| !w.class.eq_ignore_ascii_case(APP_ID)).collect()
+}
+
+/// Class of the focused window, if it can be told
+pub fn active_class() -> Option<String> {
+    let compositor = Compositor::detect();
+    let (program, args): (&str, &[&str]) = match compositor {
+        Compositor::Hyprland => ("hyprctl", &["activewindow", "-j"]),
+        Compositor::Sway => ("swaymsg", &["-t", "get_tree", "-r"]),
+        Compositor::Other(_) => ("xdotool", &["getactivewindow", "getwindowclassname"]),
+    };
+    let output = Command::new(program).args(args).output().ok().filter(|output| output.status.success())?;
+    let output = String::from_utf8_lossy(&output.stdout);
+    let class = match compositor {
+        Compositor::Hyprland => serde_json::from_str::<Value>(&output).ok()?["class"].as_str()?.to_string(),
+        Compositor::Sway => parse_sway(&output).into_iter().find(|window| window.focused)?.class,
+        Compositor::Other(_) => output.trim().to_string(),
+    };
+    (!class.is_empty()).then_some(class)
+}
+
+/// The first open window of `app`
+pub fn find<'a>(app: &Application, windows: &'a [Window]) -> Option<&'a Window> {
+    let identity = app.identity();
+    let id = app.id.to_lowercase();
+    windows.iter().find(|window| {
+        let class = window.class.to_lowercase();
+        !class.is_empty() && (class == identity || class == id)
+    })
+}
+
+/// Raise and focus a window
+pub fn focus(window: &Window) -> Result<(), String> {
+    let (program, args) = match Compositor::detect() {
+        Compositor::Hyprland => ("hyprctl", vec!["dispatch".to_string(), "focuswindow".to_string(), format!("address:{}", window.id)]),
+        Compositor::Sway => ("swaymsg", vec![format!("[con_id={}]", window.id), "focus".to_string()]),
+        Compositor::Other(_) => ("wmctrl", vec!["-ia".to_string(), window.id.clone()]),
+    };
+    let output = Command::new(program)
+        .args(&args)
+        .output()
+        .map_err(|e| format!("Cannot run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} couldn't focus {}: {}",
+            program,
+            window.title,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+fn parse_hyprland(json: &str) -> Vec<Window> {
+    let Ok(Value::Array(clients)) = serde_json::from_str::<Value>(json) else {
+        return Vec::new();
+    };
+    clients
+        .iter()
+        .filter(|client| client["mapped"].as_bool() != Some(false))
+        .filter_map(|client| {
+            Some(Window {
+                id: client["address"].as_str()?.to_string(),
+                class: client["class"].as_str().unwrap_or_default().to_string(),
+                title: client["title"].as_str().unwrap_or_default().to_string(),
+                focused: client["focusHistoryID"].as_i64() == Some(0),
+            })
+        })
+        .collect()
+}
+
+fn parse_sway(json: &str) -> Vec<Window> {
+    fn walk(node: &Value, windows: &mut Vec<Window>) {
+        // Leaf containers with a pid are windows (native or XWayland)
+        if node["pid"].is_u64() {
+            let class = node["app_id"]
+                .as_str()
+                .or_else(|| node["window_properties"]["class"].as_str())
+                .unwrap_or_default();
+            if let Some(id) = node["id"].as_u64() {
+                windows.push(Window {
+                    id: id.to_string(),
+                    class: class.to_string(),
+                    title: node["name"].as_str().unwrap_or_default().to_string(),
+                    focused: node["focused"].as_bool() == Some(true),
+                });
+            }
+        }
+        for key in ["nodes", "floating_nodes"] {
+            for child in node[key].as_array().into_iter().flatten() {
+                walk(child, windows);
+            }
+        }
+    }
+    let mut windows = Vec::new();
+    if let Ok(tree) = serde_json::from_str::<Value>(json) {
+        walk(&tree, &mut windows);
+    }
+    windows
+}
+
+/// `wmctrl -lx` lines: id, desktop, instance.Class, host, title
+fn parse_wmctrl(output: &str) -> Vec<Window> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let id = fields.next()?;
+            let desktop = fields.next()?;
+            let wm_class = fields.next()?;
+            // Desktop -1 is sticky chrome such as panels
+            if desktop == "-1" {
+                return None;
+            }
+            fields.next()?;
+            let class = wm_class.rsplit('.').next().unwrap_or(wm_class);
+            Some(Window {
+                id: id.to_string(),
+                class: class.to_string(),
+                title: fields.collect::<Vec<_>>().join(" "),
+                focused: false,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(id: &str, wm_class: Option<&str>) -> Application {
+        Application {
+            id: id.to_string(),
+            name: id.to_string(),
+            generic_name: None,
+            comment: None,
+            exec: id.to_string(),
+            icon: None,
+            categories: Vec::new(),
+            keywords: Vec::new(),
+            terminal: false,
+            no_display: false,
+            desktop_file: Default::default(),
+            mime_types: Vec::new(),
+            startup_wm_class: wm_class.map(str::to_string),
+            source: Default::default(),
+            alternates: Vec::new(),
+            duplicate_of: None,
+            missing_binary: None,
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let hyprland = parse_hyprland(
+            r#"[{"address": "0x55d1", "mapped": true, "class": "firefox", "title": "Docs — Mozilla Firefox"},
+                {"address": "0x55d2", "mapped": false, "class": "ghost", "title": ""}]"#,
+        );
+        assert_eq!(hyprland, vec![Window { id: "0x55d1".into(), class: "firefox".into(), title: "Docs — Mozilla Firefox".into(), focused: false }]);
+
+        let sway = parse_sway(
+            r#"{"id": 1, "nodes": [{"id": 4, "nodes": [
+                {"id": 7, "pid": 42, "app_id": "foot", "name": "~", "focused": true},
+                {"id": 9, "pid": 43, "app_id": null, "window_properties": {"class": "Gimp"}, "name": "GIMP"}]}],
+              "floating_nodes": []}"#,
+        );
+        assert_eq!(sway.iter().map(|w| (w.id.as_str(), w.class.as_str())).collect::<Vec<_>>(), vec![("7", "foot"), ("9", "Gimp")]);
+        assert!(sway[0].focused && !sway[1].focused);
+
+        let wmctrl = parse_wmctrl(
+            "0x02400003 -1 xfce4-panel.Xfce4-panel  host xfce4-panel\n\
+             0x04000007  0 Navigator.firefox  host Inbox - Mozilla Firefox\n",
+        );
+        assert_eq!(wmctrl, vec![Window { id: "0x04000007".into(), class: "firefox".into(), title: "Inbox - Mozilla Firefox".into(), focused: false }]);
+    }
+
+    #[test]
+    fn test_find() {
+        let windows = vec![
+            Window { id: "1".into(), class: "org.gnome.Nautilus".into(), title: "Home".into(), focused: false },
+            Window { id: "2".into(), class: "Code".into(), title: "main.rs".into(), focused: false },
+        ];
+        assert_eq!(find(&app("org.gnome.Nautilus", None), &windows).map(|w| w.id.as_str()), Some("1"));
+        assert_eq!(find(&app("code", Some("Code")), &windows).map(|w| w.id.as_str()), Some("2"));
+        assert_eq!(find(&app("gimp", None), &windows), None);
+    }
+}
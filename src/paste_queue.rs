@@ -0,0 +1,121 @@
+//! Sequential paste
+//!
+//! Entries marked in clipboard history can be pasted one after another, which
+//! is handy for filling in a form field by field: each is offered for a single
+//! paste (`wl-copy --paste-once`), and once an app has pasted it the next one
+//! takes its place. Clipboard history polling is paused meanwhile, as reading
+//! the clipboard would use up the paste. A queue nobody pastes from for
+//! `IDLE_TIMEOUT` is given up on.
+
+use std::collections::VecDeque;
+use std::process::Child;
+use std::time::{Duration, Instant};
+
+/// How long the queue waits for a paste before giving up
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Offers text for a single paste; the process exits once it's pasted
+pub type Serve = fn(&str) -> Result<Child, String>;
+
+/// What happened since the last poll
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    /// The current entry hasn't been pasted yet
+    Waiting,
+    /// It was, and the next one is on the clipboard
+    Next,
+    /// Every entry was pasted
+    Done,
+    /// Nothing was pasted for `IDLE_TIMEOUT`
+    Abandoned,
+}
+
+#[derive(Debug)]
+pub struct PasteQueue {
+    pending: VecDeque<String>,
+    serving: Child,
+    serve: Serve,
+    total: usize,
+    idle_until: Instant,
+}
+
+impl PasteQueue {
+    /// Offer the first of `items` for pasting at `now`
+    pub fn start(items: Vec<String>, serve: Serve, now: Instant) -> Result<Self, String> {
+        let total = items.len();
+        let mut pending = VecDeque::from(items);
+        let first = pending.pop_front().ok_or("Nothing to paste")?;
+        Ok(Self { serving: serve(&first)?, pending, serve, total, idle_until: now + IDLE_TIMEOUT })
+    }
+
+    /// Move on to the next entry if the current one was pasted
+    pub fn poll(&mut self, now: Instant) -> Result<Progress, String> {
+        match self.serving.try_wait() {
+            Ok(None) if now >= self.idle_until => Ok(Progress::Abandoned),
+            Ok(None) => Ok(Progress::Waiting),
+            Ok(Some(status)) if !status.success() => Err(format!("wl-copy stopped ({})", status)),
+            Ok(Some(_)) => {
+                let Some(next) = self.pending.pop_front() else {
+                    return Ok(Progress::Done);
+                };
+                self.serving = (self.serve)(&next)?;
+                self.idle_until = now + IDLE_TIMEOUT;
+                Ok(Progress::Next)
+            }
+            Err(e) => Err(format!("Lost track of the clipboard: {}", e)),
+        }
+    }
+
+    /// Entries not pasted yet, counting the one on the clipboard
+    pub fn left(&self) -> usize {
+        self.pending.len() + 1
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+}
+
+impl Drop for PasteQueue {
+    /// Take the unpasted entry off the clipboard
+    fn drop(&mut self) {
+        if let Ok(None) = self.serving.try_wait() {
+            let _ = self.serving.kill();
+            let _ = self.serving.wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    /// Pasted as soon as it's offered
+    fn pasted(_: &str) -> Result<Child, String> {
+        let mut child = Command::new("true").spawn().map_err(|e| e.to_string())?;
+        child.wait().map_err(|e| e.to_string())?;
+        Ok(child)
+    }
+
+    /// Never pasted
+    fn unpasted(_: &str) -> Result<Child, String> {
+        Command::new("sleep").arg("5").spawn().map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_poll() {
+        let start = Instant::now();
+        let mut queue = PasteQueue::start(vec!["name".into(), "street".into()], pasted, start).unwrap();
+        assert_eq!((queue.left(), queue.total()), (2, 2));
+        assert_eq!(queue.poll(start), Ok(Progress::Next));
+        assert_eq!(queue.left(), 1);
+        assert_eq!(queue.poll(start), Ok(Progress::Done));
+
+        let mut queue = PasteQueue::start(vec!["name".into()], unpasted, start).unwrap();
+        assert_eq!(queue.poll(start), Ok(Progress::Waiting));
+        assert_eq!(queue.poll(start + IDLE_TIMEOUT), Ok(Progress::Abandoned));
+
+        assert!(PasteQueue::start(Vec::new(), pasted, start).is_err());
+    }
+}
@@ -0,0 +1,104 @@
+//! When the UI wakes up on its own
+//!
+//! Iced draws a frame after every message, so each tick of a clock
+//! subscription is a redraw. The launcher only runs a clock while something
+//! on screen moves (spinners, the voice waveform, toasts, timer countdowns).
+//! Hidden (shrunk to 1px), nothing on screen needs updating: only work that
+//! acts outside the window keeps a clock, and `Ruty::view` draws nothing until
+//! the window is shown again.
+
+/// What could need a clock right now
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Activity {
+    /// A job or search is running (spinner)
+    pub busy: bool,
+    /// Toasts are up, or wait to expire
+    pub toasts: bool,
+    /// Voice is recording (waveform; releasing the hotkey stops it)
+    pub voice: bool,
+    /// Launched apps are watched for an early failure
+    pub launches: bool,
+    /// A sequential paste is typing into another window
+    pub pasting: bool,
+    /// Countdowns from /timer are running
+    pub timers: bool,
+}
+
+/// How often the clock ticks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Clock {
+    /// Every `JOB_TICK`, for animation and short deadlines
+    Fast,
+    /// Once a second, for timers
+    Seconds,
+}
+
+/// The clock `activity` needs, if any, with the window shown or hidden
+pub fn clock(activity: &Activity, visible: bool) -> Option<Clock> {
+    let background = activity.voice || activity.launches || activity.pasting;
+    let on_screen = activity.busy || activity.toasts;
+    if background || (visible && on_screen) {
+        Some(Clock::Fast)
+    } else if activity.timers {
+        // A timer going off notifies even while hidden
+        Some(Clock::Seconds)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::Hasher as _;
+
+    use iced::advanced::subscription::{self, Hasher};
+
+    use super::*;
+    use crate::app::Ruty;
+
+    /// Hashes of the subscriptions `ruty` runs, which identify them
+    fn running(ruty: &Ruty) -> Vec<u64> {
+        subscription::into_recipes(ruty.subscription())
+            .iter()
+            .map(|recipe| {
+                let mut hasher = Hasher::default();
+                recipe.hash(&mut hasher);
+                hasher.finish()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_no_frames_while_hidden() {
+        let busy = Activity { busy: true, toasts: true, ..Default::default() };
+        assert_eq!(clock(&busy, true), Some(Clock::Fast));
+        // Spinners and toasts are off screen: no ticks, so no redraws
+        assert_eq!(clock(&busy, false), None);
+        assert_eq!(clock(&Activity::default(), false), None);
+        assert_eq!(clock(&Activity::default(), true), None);
+
+        let pasting = Activity { pasting: true, ..busy };
+        assert_eq!(clock(&pasting, false), Some(Clock::Fast));
+        let timers = Activity { timers: true, ..busy };
+        assert_eq!(clock(&timers, false), Some(Clock::Seconds));
+        assert_eq!(clock(&timers, true), Some(Clock::Fast));
+    }
+
+    #[test]
+    fn test_hidden_subscription() {
+        let starting = running(&Ruty::busy(true, false));
+        let shown = running(&Ruty::busy(true, true));
+        let hidden = running(&Ruty::busy(false, true));
+
+        // Shown, the spinner and toast need a clock; until the first frame,
+        // so does its one-off frame subscription
+        let frames: Vec<u64> = starting.iter().copied().filter(|hash| !shown.contains(hash)).collect();
+        let ticks: Vec<u64> = shown.iter().copied().filter(|hash| !hidden.contains(hash)).collect();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(ticks.len(), 1);
+
+        // Hidden, only event listeners are left
+        assert_eq!(hidden.len(), shown.len() - 1);
+        assert!(hidden.iter().all(|hash| !frames.contains(hash) && !ticks.contains(hash)));
+    }
+}
@@ -0,0 +1,121 @@
+//! Regex tester for `/regex <pattern>`
+//!
+//! The prompt holds the pattern (recompiled on every keystroke so errors show
+//! live) and a text area, seeded from the clipboard, holds the test text.
+//! Matches are highlighted in a preview and their capture groups listed.
+
+use regex::Regex;
+use std::ops::Range;
+
+/// Matches whose groups are listed; the rest are only counted
+pub const MAX_LISTED: usize = 50;
+
+/// One match of the pattern in the test text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    /// Byte range in the test text
+    pub range: Range<usize>,
+    pub text: String,
+    /// Capture groups 1.., with their names; `None` if the group didn't take part
+    pub groups: Vec<(Option<String>, Option<String>)>,
+}
+
+/// The pattern typed after `/regex ` (or `/re `), spaces kept
+pub fn pattern(prompt: &str) -> Option<&str> {
+    ["/regex", "/re"]
+        .iter()
+        .find_map(|command| prompt.strip_prefix(command)?.strip_prefix(' '))
+}
+
+/// Compile `pattern`, with the regex crate's error (it points at the offending
+/// part) on failure
+pub fn compile(pattern: &str) -> Result<Regex, String> {
+    Regex::new(pattern).map_err(|e| match e {
+        regex::Error::Syntax(message) => message,
+        other => other.to_string(),
+    })
+}
+
+/// All non-overlapping matches of `regex` in `text`
+pub fn matches(regex: &Regex, text: &str) -> Vec<Match> {
+    let names: Vec<Option<String>> = regex
+        .capture_names()
+        .skip(1)
+        .map(|name| name.map(String::from))
+        .collect();
+    regex
+        .captures_iter(text)
+        .filter_map(|captures| {
+            let whole = captures.get(0)?;
+            let groups = names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (name.clone(), captures.get(i + 1).map(|g| g.as_str().to_string())))
+                .collect();
+            Some(Match {
+                range: whole.range(),
+                text: whole.as_str().to_string(),
+                groups,
+            })
+        })
+        .collect()
+}
+
+/// `text` split into (piece, matched) runs for highlighting; empty matches
+/// don't produce a run
+pub fn segments<'a>(text: &'a str, matches: &[Match]) -> Vec<(&'a str, bool)> {
+    let mut segments = Vec::new();
+    let mut last = 0;
+    for m in matches.iter().filter(|m| !m.range.is_empty()) {
+        if m.range.start > last {
+            segments.push((&text[last..m.range.start], false));
+        }
+        segments.push((&text[m.range.clone()], true));
+        last = m.range.end;
+    }
+    if last < text.len() {
+        segments.push((&text[last..], false));
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern() {
+        assert_eq!(pattern("/regex a b"), Some("a b"));
+        assert_eq!(pattern("/re \\d+ "), Some("\\d+ "));
+        assert_eq!(pattern("/regex "), Some(""));
+        assert_eq!(pattern("/regex"), None);
+        assert_eq!(pattern("/regexp"), None);
+        assert_eq!(pattern("/reload"), None);
+    }
+
+    #[test]
+    fn test_compile_error() {
+        assert!(compile("(?P<year>\\d{4})").is_ok());
+        let error = compile("a(b").unwrap_err();
+        assert!(error.contains("unclosed group"), "{}", error);
+    }
+
+    #[test]
+    fn test_matches_and_segments() {
+        let regex = compile(r"(?P<key>\w+)=(\d+)?").unwrap();
+        let text = "a=1, b=, c=30";
+        let found = matches(&regex, text);
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[0].groups, vec![(Some("key".into()), Some("a".into())), (None, Some("1".into()))]);
+        assert_eq!(found[1].groups[1], (None, None));
+        assert_eq!(found[2].text, "c=30");
+
+        assert_eq!(
+            segments(text, &found),
+            vec![("a=1", true), (", ", false), ("b=", true), (", ", false), ("c=30", true)]
+        );
+        // Empty matches highlight nothing
+        let empty = matches(&compile("x*").unwrap(), "ab");
+        assert_eq!(segments("ab", &empty), vec![("ab", false)]);
+    }
+}
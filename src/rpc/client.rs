@@ -3,22 +3,45 @@
 //! Sends commands to the running Ruty daemon.
 
 use super::proto::ruty_service_client::RutyServiceClient;
-use super::proto::Empty;
-use super::daemon_addr;
+use super::proto::{Empty, FlowRequest, ServerInfo, WindowGeometry};
+use super::{daemon_addr, Authorize};
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Channel;
+
+/// Connect to the daemon, authenticating with the user's token
+pub async fn connect() -> Result<RutyServiceClient<InterceptedService<Channel, Authorize>>, String> {
+    let authorize = Authorize::new()?;
+    let channel = Channel::from_shared(daemon_addr())
+        .map_err(|e| e.to_string())?
+        .connect()
+        .await
+        .map_err(|e| format!("Failed to connect to daemon: {}", e))?;
+    Ok(RutyServiceClient::with_interceptor(channel, authorize))
+}
 
 /// Check if daemon is running
 pub async fn is_daemon_running() -> bool {
-    match RutyServiceClient::connect(daemon_addr()).await {
+    match connect().await {
         Ok(mut client) => client.ping(Empty {}).await.is_ok(),
         Err(_) => false,
     }
 }
 
+/// Daemon version and index readiness
+pub async fn server_info() -> Result<ServerInfo, String> {
+    let mut client = connect().await?;
+
+    let response = client
+        .get_server_info(Empty {})
+        .await
+        .map_err(|e| format!("Server info failed: {}", e))?;
+
+    Ok(response.into_inner())
+}
+
 /// Toggle window visibility (main command for keybind)
 pub async fn toggle_window() -> Result<bool, String> {
-    let mut client = RutyServiceClient::connect(daemon_addr())
-        .await
-        .map_err(|e| format!("Failed to connect to daemon: {}", e))?;
+    let mut client = connect().await?;
 
     let response = client
         .toggle_window(Empty {})
@@ -30,9 +53,7 @@ pub async fn toggle_window() -> Result<bool, String> {
 
 /// Show window
 pub async fn show_window() -> Result<(), String> {
-    let mut client = RutyServiceClient::connect(daemon_addr())
-        .await
-        .map_err(|e| format!("Failed to connect to daemon: {}", e))?;
+    let mut client = connect().await?;
 
     client
         .show_window(Empty {})
@@ -44,9 +65,7 @@ pub async fn show_window() -> Result<(), String> {
 
 /// Hide window
 pub async fn hide_window() -> Result<(), String> {
-    let mut client = RutyServiceClient::connect(daemon_addr())
-        .await
-        .map_err(|e| format!("Failed to connect to daemon: {}", e))?;
+    let mut client = connect().await?;
 
     client
         .hide_window(Empty {})
@@ -58,9 +77,7 @@ pub async fn hide_window() -> Result<(), String> {
 
 /// Quit daemon
 pub async fn quit_daemon() -> Result<(), String> {
-    let mut client = RutyServiceClient::connect(daemon_addr())
-        .await
-        .map_err(|e| format!("Failed to connect to daemon: {}", e))?;
+    let mut client = connect().await?;
 
     client
         .quit(Empty {})
@@ -69,3 +86,40 @@ pub async fn quit_daemon() -> Result<(), String> {
 
     Ok(())
 }
+
+/// Open the UI on a (possibly headless) daemon
+pub async fn attach_window() -> Result<(), String> {
+    let mut client = connect().await?;
+
+    client
+        .attach_window(Empty {})
+        .await
+        .map_err(|e| format!("Attach failed: {}", e))?;
+
+    Ok(())
+}
+
+/// Run a `[flows]` entry, returning its output (empty unless the last step
+/// prints something)
+pub async fn run_flow(name: &str) -> Result<String, String> {
+    let mut client = connect().await?;
+
+    let response = client
+        .run_flow(FlowRequest { name: name.to_string() })
+        .await
+        .map_err(|e| e.message().to_string())?;
+
+    Ok(response.into_inner().output)
+}
+
+/// Change the window geometry (zero/empty fields stay as they are), or just
+/// read it with `None`; returns the geometry now saved
+pub async fn window_geometry(changes: Option<WindowGeometry>) -> Result<WindowGeometry, String> {
+    let mut client = connect().await?;
+
+    let response = match changes {
+        Some(changes) => client.set_window_geometry(changes).await,
+        None => client.get_window_geometry(Empty {}).await,
+    };
+    Ok(response.map_err(|e| e.message().to_string())?.into_inner())
+}
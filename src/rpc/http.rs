@@ -0,0 +1,163 @@
+//! HTTP API for other frontends
+//!
+//! A JSON mirror of the gRPC search and launch calls, for clients that can't
+//! easily speak gRPC (rofi scripts, browser extensions, a TUI):
+//!
+//! - `GET /api/search?q=<query>[&limit=<n>]` returns `{"results": [...]}`,
+//!   each with the `id` and `category` to execute it by
+//! - `POST /api/execute` with `{"id": ..., "category": ...}` launches an app
+//!   or opens a file
+//!
+//! Both need `Authorization: Bearer <token>` with the token the gRPC API
+//! uses (see `ruty_core::token`), so only the user's own programs can list
+//! their files or run things.
+//!
+//! It only listens on 127.0.0.1, and only when `[api] enabled` is set. No
+//! CORS headers are sent, so web pages can't read results or post JSON to it
+//! (browser extensions with host permissions can). Requests must name it as
+//! `127.0.0.1:<port>` or `localhost:<port>` in `Host`, so a site whose domain
+//! is rebound to 127.0.0.1 (DNS rebinding) can't reach it as same-origin.
+
+use std::sync::Arc;
+
+use axum::extract::{Query, Request, State};
+use axum::http::header::{AUTHORIZATION, HOST};
+use axum::http::{HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::app::{ResultCategory, SearchResult};
+use crate::services::{self, Services};
+
+#[derive(Debug, Deserialize)]
+struct SearchParams {
+    #[serde(default)]
+    q: String,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct SearchItem {
+    id: String,
+    title: String,
+    subtitle: String,
+    /// "app", "file", ...
+    category: &'static str,
+    icon: Option<String>,
+}
+
+impl From<SearchResult> for SearchItem {
+    fn from(result: SearchResult) -> Self {
+        Self {
+            id: result.id,
+            title: result.title,
+            subtitle: result.subtitle,
+            category: result.category.as_str(),
+            icon: result.icon,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SearchResponse {
+    results: Vec<SearchItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExecuteRequest {
+    id: String,
+    category: String,
+}
+
+/// What the handlers check requests against
+#[derive(Clone)]
+struct Api {
+    port: u16,
+    /// `Bearer <token>`
+    bearer: Arc<str>,
+}
+
+/// A failed request: its status with `{"error": "..."}`
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+fn services() -> Result<Arc<Services>, ApiError> {
+    crate::get_services().ok_or_else(|| ApiError(StatusCode::SERVICE_UNAVAILABLE, "Services not initialized".into()))
+}
+
+/// Refuse requests without the user's token
+fn authorize(api: &Api, headers: &HeaderMap) -> Result<(), ApiError> {
+    let sent = headers.get(AUTHORIZATION).and_then(|value| value.to_str().ok());
+    if !ruty_core::token::matches(sent, &api.bearer) {
+        return Err(ApiError(StatusCode::UNAUTHORIZED, "Missing or wrong token".into()));
+    }
+    Ok(())
+}
+
+async fn search(
+    State(api): State<Api>,
+    headers: HeaderMap,
+    Query(params): Query<SearchParams>,
+) -> Result<Json<SearchResponse>, ApiError> {
+    authorize(&api, &headers)?;
+    tracing::debug!("HTTP: search {:?}", params.q);
+    let services = services()?;
+    let limit = params.limit.filter(|n| *n > 0).unwrap_or(services::DEFAULT_LIMIT);
+    let results = tokio::task::spawn_blocking(move || services.search(&params.q, limit))
+        .await
+        .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(SearchResponse { results: results.into_iter().map(SearchItem::from).collect() }))
+}
+
+async fn execute(
+    State(api): State<Api>,
+    headers: HeaderMap,
+    Json(request): Json<ExecuteRequest>,
+) -> Result<StatusCode, ApiError> {
+    authorize(&api, &headers)?;
+    tracing::info!("HTTP: execute {} ({})", request.id, request.category);
+    let category = ResultCategory::parse(&request.category)
+        .ok_or_else(|| ApiError(StatusCode::BAD_REQUEST, format!("Unknown category: {}", request.category)))?;
+    let services = services()?;
+    tokio::task::spawn_blocking(move || services.launch(&request.id, category))
+        .await
+        .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| ApiError(StatusCode::UNPROCESSABLE_ENTITY, e))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Refuse requests addressed to any other host than us
+async fn check_host(State(api): State<Api>, request: Request, next: Next) -> Response {
+    let host = request.headers().get(HOST).and_then(|value| value.to_str().ok()).unwrap_or_default();
+    let ours = [format!("127.0.0.1:{}", api.port), format!("localhost:{}", api.port)];
+    if !ours.iter().any(|ours| ours.eq_ignore_ascii_case(host)) {
+        tracing::warn!("HTTP: refused request for host {:?}", host);
+        return ApiError(StatusCode::FORBIDDEN, format!("Unexpected host {:?}", host)).into_response();
+    }
+    next.run(request).await
+}
+
+fn router(api: Api) -> Router {
+    Router::new()
+        .route("/api/search", get(search))
+        .route("/api/execute", post(execute))
+        .layer(middleware::from_fn_with_state(api.clone(), check_host))
+        .with_state(api)
+}
+
+/// Serve the API on 127.0.0.1:`port` until the process exits
+pub async fn start_server(port: u16) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let bearer = ruty_core::token::bearer(&ruty_core::token::get()?).into();
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port)).await?;
+    tracing::info!("Starting HTTP API on {}", listener.local_addr()?);
+    axum::serve(listener, router(Api { port, bearer })).await?;
+    Ok(())
+}
@@ -1,9 +1,17 @@
 //! RPC module for Ruty daemon IPC
 //!
-//! Implements Gauntlet-style gRPC communication between CLI and daemon.
+//! Implements Gauntlet-style gRPC communication between CLI and daemon, plus
+//! an optional JSON-over-HTTP mirror of search and launch (see `http`).
+//! Every call carries the user's token (see `ruty_core::token`) in its
+//! `authorization` metadata; the daemon refuses calls without it.
+
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
 
 pub mod server;
 pub mod client;
+pub mod http;
 
 // Include generated protobuf code
 pub mod proto {
@@ -13,7 +21,42 @@ pub mod proto {
 /// Default port for Ruty daemon
 pub const DAEMON_PORT: u16 = 42321;
 
+/// Environment variable overriding the daemon port (used by tests)
+pub const PORT_ENV: &str = "RUTY_PORT";
+
+/// Port the daemon listens on (`RUTY_PORT` or the default)
+pub fn daemon_port() -> u16 {
+    std::env::var(PORT_ENV)
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(DAEMON_PORT)
+}
+
 /// Default address for Ruty daemon
 pub fn daemon_addr() -> String {
-    format!("http://127.0.0.1:{}", DAEMON_PORT)
+    format!("http://127.0.0.1:{}", daemon_port())
+}
+
+/// Socket address the daemon listens on (also used for the systemd socket unit)
+pub fn listen_addr() -> String {
+    format!("127.0.0.1:{}", daemon_port())
+}
+
+/// Adds `authorization: Bearer <token>` to each call
+#[derive(Clone)]
+pub struct Authorize(MetadataValue<Ascii>);
+
+impl Authorize {
+    /// With the user's token, created if there's none yet
+    pub fn new() -> Result<Self, String> {
+        let token = ruty_core::token::get()?;
+        ruty_core::token::bearer(&token).parse().map(Self).map_err(|_| "Invalid token".to_string())
+    }
+}
+
+impl Interceptor for Authorize {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        request.metadata_mut().insert("authorization", self.0.clone());
+        Ok(request)
+    }
 }
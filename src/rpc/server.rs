@@ -1,38 +1,31 @@
 //! gRPC server for Ruty daemon
 //!
-//! Handles IPC requests from CLI to control window visibility.
+//! Handles IPC requests from CLI to control window visibility, and exposes
+//! search, clipboard and AI features so headless daemons can be scripted.
 
-use std::sync::atomic::{AtomicBool, Ordering};
+use ruty_core::geometry::{Anchor, Geometry};
+use ruty_core::permissions;
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
 use super::proto::ruty_service_server::{RutyService, RutyServiceServer};
-use super::proto::{Empty, WindowState};
-use super::DAEMON_PORT;
-
-/// Shared state for window visibility
-#[derive(Debug)]
-pub struct WindowController {
-    pub visible: AtomicBool,
-    pub toggle_requested: AtomicBool,
-    pub quit_requested: AtomicBool,
-}
+use super::proto::{
+    ChatReply, ChatRequest, ClipboardEntry, ClipboardHistory, ContextReply, ContextRequest, Empty,
+    FlowReply, FlowRequest, LaunchRequest, SearchItem, SearchRequest, SearchResponse, ServerInfo, WindowGeometry,
+    WindowState,
+};
+use super::listen_addr;
+use crate::app::ResultCategory;
+use crate::backend::api;
+use crate::flows;
+use crate::services::{self, Services};
+use crate::window_commands::{WindowCommand, WindowController};
 
-impl WindowController {
-    pub fn new() -> Self {
-        Self {
-            visible: AtomicBool::new(true),
-            toggle_requested: AtomicBool::new(false),
-            quit_requested: AtomicBool::new(false),
-        }
-    }
-}
+/// Chat session used when an RPC client doesn't supply one
+const DEFAULT_RPC_SESSION: &str = "rpc";
 
-impl Default for WindowController {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+/// First file descriptor passed by systemd (SD_LISTEN_FDS_START)
+const SD_LISTEN_FDS_START: i32 = 3;
 
 /// gRPC service implementation
 pub struct RutyServiceImpl {
@@ -43,6 +36,11 @@ impl RutyServiceImpl {
     pub fn new(controller: Arc<WindowController>) -> Self {
         Self { controller }
     }
+
+    #[allow(clippy::result_large_err)] // Status is what tonic handlers fail with
+    fn services() -> Result<Arc<Services>, Status> {
+        crate::get_services().ok_or_else(|| Status::unavailable("Services not initialized"))
+    }
 }
 
 #[tonic::async_trait]
@@ -52,52 +50,286 @@ impl RutyService for RutyServiceImpl {
         Ok(Response::new(Empty {}))
     }
 
+    async fn get_server_info(&self, _request: Request<Empty>) -> Result<Response<ServerInfo>, Status> {
+        let services = Self::services()?;
+        Ok(Response::new(ServerInfo {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            ready: services.is_ready(),
+            apps_indexed: services.app_count() as u32,
+        }))
+    }
+
     async fn show_window(&self, _request: Request<Empty>) -> Result<Response<Empty>, Status> {
         tracing::info!("RPC: show_window");
-        self.controller.visible.store(true, Ordering::SeqCst);
-        self.controller.toggle_requested.store(true, Ordering::SeqCst);
+        self.controller.send(WindowCommand::Show);
         Ok(Response::new(Empty {}))
     }
 
     async fn hide_window(&self, _request: Request<Empty>) -> Result<Response<Empty>, Status> {
         tracing::info!("RPC: hide_window");
-        self.controller.visible.store(false, Ordering::SeqCst);
-        self.controller.toggle_requested.store(true, Ordering::SeqCst);
+        self.controller.send(WindowCommand::Hide);
         Ok(Response::new(Empty {}))
     }
 
+    /// Replies with the state the toggle leads to from the current one
     async fn toggle_window(&self, _request: Request<Empty>) -> Result<Response<WindowState>, Status> {
-        let current = self.controller.visible.load(Ordering::SeqCst);
-        let new_state = !current;
-        tracing::info!("RPC: toggle_window {} -> {}", current, new_state);
-        self.controller.visible.store(new_state, Ordering::SeqCst);
-        self.controller.toggle_requested.store(true, Ordering::SeqCst);
-        Ok(Response::new(WindowState { visible: new_state }))
+        let current = self.controller.is_visible();
+        let visible = WindowCommand::Toggle.visible_after(current);
+        tracing::info!("RPC: toggle_window {} -> {}", current, visible);
+        self.controller.send(WindowCommand::Toggle);
+        Ok(Response::new(WindowState { visible }))
     }
 
     async fn get_window_state(&self, _request: Request<Empty>) -> Result<Response<WindowState>, Status> {
-        let visible = self.controller.visible.load(Ordering::SeqCst);
-        Ok(Response::new(WindowState { visible }))
+        Ok(Response::new(WindowState { visible: self.controller.is_visible() }))
+    }
+
+    async fn attach_window(&self, _request: Request<Empty>) -> Result<Response<WindowState>, Status> {
+        tracing::info!("RPC: attach_window");
+        self.controller.send(WindowCommand::Attach);
+        Ok(Response::new(WindowState { visible: true }))
+    }
+
+    async fn set_window_geometry(&self, request: Request<WindowGeometry>) -> Result<Response<WindowGeometry>, Status> {
+        let req = request.into_inner();
+        tracing::info!("RPC: set_window_geometry {:?}", req);
+        let anchor = match req.anchor.as_str() {
+            "" => None,
+            name => Some(Anchor::parse(name).ok_or_else(|| {
+                Status::invalid_argument(format!("Unknown anchor '{}' (use center or top)", name))
+            })?),
+        };
+        let nonzero = |n: u32| (n > 0).then_some(n);
+        let monitor = (!req.monitor.is_empty()).then_some(req.monitor);
+        let controller = self.controller.clone();
+        let (monitor, geometry) = tokio::task::spawn_blocking(move || {
+            controller.set_geometry(monitor.as_deref(), (nonzero(req.width), nonzero(req.height)), anchor)
+        })
+        .await
+        .map_err(|e| Status::internal(e.to_string()))?
+        .map_err(Status::invalid_argument)?;
+        Ok(Response::new(window_geometry(monitor, geometry)))
+    }
+
+    async fn get_window_geometry(&self, _request: Request<Empty>) -> Result<Response<WindowGeometry>, Status> {
+        let controller = self.controller.clone();
+        let (monitor, geometry) = tokio::task::spawn_blocking(move || controller.geometry())
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(window_geometry(monitor, geometry)))
     }
 
     async fn quit(&self, _request: Request<Empty>) -> Result<Response<Empty>, Status> {
         tracing::info!("RPC: quit");
-        self.controller.quit_requested.store(true, Ordering::SeqCst);
+        self.controller.send(WindowCommand::Quit);
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn search(&self, request: Request<SearchRequest>) -> Result<Response<SearchResponse>, Status> {
+        let req = request.into_inner();
+        tracing::debug!("RPC: search {:?}", req.query);
+        let limit = match req.limit {
+            0 => services::DEFAULT_LIMIT,
+            n => n as usize,
+        };
+
+        let services = Self::services()?;
+        let results = tokio::task::spawn_blocking(move || services.search(&req.query, limit))
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        let results = results
+            .into_iter()
+            .map(|r| SearchItem {
+                id: r.id,
+                title: r.title,
+                subtitle: r.subtitle,
+                category: r.category.as_str().to_string(),
+                icon: r.icon.unwrap_or_default(),
+            })
+            .collect();
+        Ok(Response::new(SearchResponse { results }))
+    }
+
+    async fn launch(&self, request: Request<LaunchRequest>) -> Result<Response<Empty>, Status> {
+        let req = request.into_inner();
+        tracing::info!("RPC: launch {} ({})", req.id, req.category);
+        let category = ResultCategory::parse(&req.category)
+            .ok_or_else(|| Status::invalid_argument(format!("Unknown category: {}", req.category)))?;
+
+        let services = Self::services()?;
+        tokio::task::spawn_blocking(move || services.launch(&req.id, category))
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .map_err(Status::failed_precondition)?;
         Ok(Response::new(Empty {}))
     }
+
+    async fn get_clipboard_history(&self, _request: Request<Empty>) -> Result<Response<ClipboardHistory>, Status> {
+        let items = Self::services()?
+            .clipboard()
+            .get_history()
+            .into_iter()
+            .map(|item| ClipboardEntry {
+                content: item.content,
+                timestamp: item.timestamp,
+                kind: item.kind.id().to_string(),
+                language: item.language.unwrap_or_default(),
+            })
+            .collect();
+        Ok(Response::new(ClipboardHistory { items }))
+    }
+
+    async fn chat(&self, request: Request<ChatRequest>) -> Result<Response<ChatReply>, Status> {
+        let req = request.into_inner();
+        tracing::info!("RPC: chat");
+        let session_id = if req.session_id.is_empty() {
+            DEFAULT_RPC_SESSION.to_string()
+        } else {
+            req.session_id
+        };
+
+        let services = Self::services()?;
+        let local_context = services.recall(&req.message);
+        let response = services
+            .backend()
+            .chat(api::ChatRequest {
+                message: req.message,
+                session_id,
+                local_context,
+                api_keys: None,
+                replace_last: false,
+                // Nobody is looking at the launcher to approve tool calls
+                tool_approval: api::ToolApproval::Deny,
+            })
+            .await
+            .map_err(Status::unavailable)?;
+        Ok(Response::new(ChatReply {
+            response: response.response,
+            tools_used: response.tools_used,
+        }))
+    }
+
+    async fn load_context(&self, request: Request<ContextRequest>) -> Result<Response<ContextReply>, Status> {
+        let req = request.into_inner();
+        tracing::info!("RPC: load_context {}", req.path);
+        let session_id = if req.session_id.is_empty() {
+            DEFAULT_RPC_SESSION
+        } else {
+            &req.session_id
+        };
+
+        let response = Self::services()?
+            .backend()
+            .load_context(session_id, &req.path)
+            .await
+            .map_err(Status::unavailable)?;
+        Ok(Response::new(ContextReply {
+            success: response.success,
+            message: response.message,
+        }))
+    }
+
+    async fn run_flow(&self, request: Request<FlowRequest>) -> Result<Response<FlowReply>, Status> {
+        let req = request.into_inner();
+        tracing::info!("RPC: run_flow {}", req.name);
+        let services = Self::services()?;
+        let config = services.config();
+        let flow = config
+            .flows
+            .get(&req.name)
+            .cloned()
+            .ok_or_else(|| Status::not_found(format!("No flow named '{}' under [flows] in config.toml", req.name)))?;
+        // There's nobody to ask here; the launcher asks on the first /flow
+        let missing = permissions::missing(&config, &permissions::flow(&req.name));
+        if !missing.is_empty() {
+            return Err(Status::permission_denied(format!(
+                "Flow {} isn't allowed to {}; run /flow {} in the launcher once to allow it",
+                req.name,
+                permissions::describe(&missing),
+                req.name
+            )));
+        }
+        let output = flows::run(services, req.name, flow).await.map_err(Status::aborted)?;
+        Ok(Response::new(FlowReply { output: output.unwrap_or_default() }))
+    }
 }
 
-/// Start the gRPC server in a background task
-pub async fn start_server(controller: Arc<WindowController>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let addr = format!("127.0.0.1:{}", DAEMON_PORT).parse()?;
+fn window_geometry(monitor: String, geometry: Geometry) -> WindowGeometry {
+    WindowGeometry {
+        width: geometry.width,
+        height: geometry.height,
+        anchor: geometry.anchor.name().to_string(),
+        monitor,
+    }
+}
+
+/// Take the listening socket handed over by systemd socket activation.
+///
+/// Must be called before any threads are spawned, since it clears the
+/// LISTEN_* environment so child processes don't try to claim the socket.
+pub fn take_activation_listener() -> Option<std::net::TcpListener> {
+    use std::os::unix::io::FromRawFd;
+
+    let pid: u32 = std::env::var("LISTEN_PID").ok()?.parse().ok()?;
+    let fds: i32 = std::env::var("LISTEN_FDS").ok()?.parse().ok()?;
+
+    std::env::remove_var("LISTEN_PID");
+    std::env::remove_var("LISTEN_FDS");
+    std::env::remove_var("LISTEN_FDNAMES");
+
+    if pid != std::process::id() || fds < 1 {
+        return None;
+    }
+
+    // SAFETY: systemd guarantees fd 3 is an open listening socket when
+    // LISTEN_PID matches us, and nothing else has claimed it yet.
+    let inherited = unsafe { std::net::TcpListener::from_raw_fd(SD_LISTEN_FDS_START) };
+
+    // Re-open with CLOEXEC so the Python sidecar doesn't inherit it
+    let listener = inherited.try_clone().ok()?;
+    drop(inherited);
+
+    Some(listener)
+}
+
+/// Start the gRPC server in a background task.
+///
+/// Serves on `listener` when socket-activated, otherwise binds the default port.
+pub async fn start_server(
+    controller: Arc<WindowController>,
+    listener: Option<std::net::TcpListener>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let service = RutyServiceImpl::new(controller);
+    let expected = ruty_core::token::bearer(&ruty_core::token::get()?);
+    #[allow(clippy::result_large_err)] // The signature tonic interceptors have
+    let authorize = move |request: Request<()>| {
+        let sent = request.metadata().get("authorization").and_then(|value| value.to_str().ok());
+        if ruty_core::token::matches(sent, &expected) {
+            Ok(request)
+        } else {
+            Err(Status::unauthenticated("Missing or wrong token"))
+        }
+    };
+    let router = tonic::transport::Server::builder()
+        .add_service(RutyServiceServer::with_interceptor(service, authorize));
+
+    match listener {
+        Some(listener) => {
+            listener.set_nonblocking(true)?;
+            let listener = tokio::net::TcpListener::from_std(listener)?;
+            tracing::info!("Starting gRPC server on systemd socket {}", listener.local_addr()?);
 
-    tracing::info!("Starting gRPC server on {}", addr);
+            let incoming = tonic::transport::server::TcpIncoming::from_listener(listener, true, None)?;
+            router.serve_with_incoming(incoming).await?;
+        }
+        None => {
+            let addr = listen_addr().parse()?;
+            tracing::info!("Starting gRPC server on {}", addr);
 
-    tonic::transport::Server::builder()
-        .add_service(RutyServiceServer::new(service))
-        .serve(addr)
-        .await?;
+            router.serve(addr).await?;
+        }
+    }
 
     Ok(())
 }
@@ -0,0 +1,85 @@
+//! Scratchpad window
+//!
+//! `/pad` opens a small always-on-top window with a plain text editor for
+//! notes that should outlive a launcher session, and `/pad` again closes
+//! it. It's a window of its own: hiding the launcher leaves it up, and
+//! typing in it never reaches the launcher's key handling. Every edit is
+//! saved (see `ruty_core::scratchpad`), so there's nothing to lose when it
+//! closes.
+
+use std::sync::Mutex;
+
+use iced::widget::text_editor;
+use iced::{window, Size, Task};
+use ruty_core::scratchpad;
+
+/// Wayland app_id of the pad, distinct from the launcher's so its window
+/// rules don't apply
+pub const APP_ID: &str = "ruty-pad";
+
+const SIZE: Size = Size::new(360.0, 300.0);
+
+/// The open pad's window, for the event filter, which can't see app state
+static WINDOW: Mutex<Option<window::Id>> = Mutex::new(None);
+
+/// An open scratchpad
+pub struct Scratchpad {
+    window: window::Id,
+    pub content: text_editor::Content,
+}
+
+impl Scratchpad {
+    /// Open the pad window with the saved text
+    pub fn open() -> (Self, Task<window::Id>) {
+        let (window, opened) = window::open(window::Settings {
+            size: SIZE,
+            min_size: Some(Size::new(200.0, 120.0)),
+            level: window::Level::AlwaysOnTop,
+            platform_specific: window::settings::PlatformSpecific {
+                application_id: APP_ID.to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        *WINDOW.lock().unwrap() = Some(window);
+        tracing::info!("Scratchpad opened");
+        let content = text_editor::Content::with_text(&scratchpad::load());
+        (Self { window, content }, opened)
+    }
+
+    pub fn window(&self) -> window::Id {
+        self.window
+    }
+
+    /// Apply an editor action, saving the text if it changed
+    pub fn perform(&mut self, action: text_editor::Action) {
+        let edit = action.is_edit();
+        self.content.perform(action);
+        if edit {
+            if let Err(e) = scratchpad::save(&self.content.text()) {
+                tracing::warn!("{}", e);
+            }
+        }
+    }
+
+    /// Close the pad window
+    pub fn close<T>(self) -> Task<T> {
+        closed(self.window);
+        window::close(self.window)
+    }
+}
+
+/// Forget the pad window once it's gone (closed with `/pad` or by the
+/// window manager)
+pub fn closed(window: window::Id) {
+    let mut open = WINDOW.lock().unwrap();
+    if *open == Some(window) {
+        *open = None;
+        tracing::info!("Scratchpad closed");
+    }
+}
+
+/// Whether `window` is the pad
+pub fn is_pad(window: window::Id) -> bool {
+    *WINDOW.lock().unwrap() == Some(window)
+}
@@ -0,0 +1,32 @@
+/**
+ * API keys, kept in the system keyring
+ *
+ * Older versions saved them in plain text in settings.json; they move to
+ * the keyring the first time they're loaded.
+ */
+
+import { Store } from './tauri-store.js';
+
+/**
+ * Keys by provider ("groq", "supermemory"), moving any left in
+ * settings.json to the keyring first
+ */
+export async function loadApiKeys() {
+    const invoke = window.__TAURI__.core.invoke;
+    const store = new Store('settings.json');
+
+    const legacy = await store.get('api_keys');
+    if (legacy) {
+        for (const [provider, apiKey] of Object.entries(legacy)) {
+            if (apiKey) {
+                await invoke('set_api_key', { provider, apiKey });
+            }
+        }
+        // Only once every key is in the keyring
+        await store.delete('api_keys');
+        await store.save();
+        console.log('[Keys] Moved API keys from settings.json to the keyring');
+    }
+
+    return invoke('get_api_keys');
+}
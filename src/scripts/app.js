@@ -3,7 +3,7 @@
  * Handles Tauri IPC communication, command system, and UI interactions
  */
 
-import { Store } from './tauri-store.js';
+import { loadApiKeys } from './api-keys.js';
 import { commandRegistry } from './commands.js';
 import { resultList } from './results.js';
 
@@ -16,7 +16,7 @@ let sessionId = `session_${Date.now()}_${Math.random().toString(36).slice(2, 10)
 let ws = null;
 let isProcessing = false;
 let lastFocusTime = 0;
-let apiKeys = {}; // Store API keys loaded from store
+let apiKeys = {}; // API keys loaded from the keyring
 let inputDebounceTimer = null;
 
 // DOM Elements
@@ -83,12 +83,12 @@ function handleWSMessage(data) {
 /**
  * Send message via HTTP (fallback if WebSocket not available)
  */
-async function sendMessageHTTP(message) {
+async function sendMessageHTTP(message, localContext = null) {
     try {
         const res = await fetch(`${API_BASE}/chat`, {
             method: 'POST',
             headers: { 'Content-Type': 'application/json' },
-            body: JSON.stringify({ message, session_id: sessionId, api_keys: apiKeys })
+            body: JSON.stringify({ message, session_id: sessionId, api_keys: apiKeys, local_context: localContext })
         });
 
         const data = await res.json();
@@ -109,12 +109,12 @@ async function sendMessageHTTP(message) {
 /**
  * Send message via WebSocket for streaming
  */
-function sendMessageWS(message) {
+function sendMessageWS(message, localContext = null) {
     if (ws && ws.readyState === WebSocket.OPEN) {
-        ws.send(JSON.stringify({ message, api_keys: apiKeys }));
+        ws.send(JSON.stringify({ message, api_keys: apiKeys, local_context: localContext }));
     } else {
         // Fallback to HTTP
-        sendMessageHTTP(message);
+        sendMessageHTTP(message, localContext);
     }
 }
 
@@ -258,6 +258,16 @@ async function handleResultAction(result, item) {
             input.value = '';
             break;
 
+        case 'askClip': {
+            // Ask AI with the clipboard entry as context for this message only
+            resultList.hide();
+            isProcessing = true;
+            document.querySelector('.input-icon')?.classList.add('rotating');
+            sendMessageWS(result.question, `### Clipboard\n\`\`\`\n${result.content}\n\`\`\``);
+            input.value = '';
+            break;
+        }
+
         case 'insert':
             // Insert text into input
             input.value = result.value;
@@ -565,6 +575,22 @@ window.addEventListener('focus', () => {
 // Set up result list callback
 resultList.onSelect = handleResultAction;
 
+// Clipboard hotkey: jump straight into clipboard history
+if (window.__TAURI__?.event?.listen) {
+    window.__TAURI__.event.listen('open-clipboard', () => {
+        input.value = '/clip ';
+        input.focus();
+        handleInputChange();
+    });
+}
+
+// Theme: follow config.toml and live switches from the settings page
+const applyTheme = (theme) => document.documentElement.classList.toggle('light', theme === 'light');
+if (window.__TAURI__?.core?.invoke) {
+    window.__TAURI__.core.invoke('get_theme').then(applyTheme).catch(() => {});
+    window.__TAURI__.event?.listen('theme-changed', (event) => applyTheme(event.payload));
+}
+
 // ============== Initialize ==============
 document.addEventListener('DOMContentLoaded', () => {
     // Retry connecting to API with backoff
@@ -595,11 +621,9 @@ document.addEventListener('DOMContentLoaded', () => {
     // Force initial resize
     setTimeout(resizeWindow, 100);
 
-    // Initialize Store and load keys
+    // Load keys
     if (window.__TAURI__) {
-        const store = new Store('settings.json');
-
-        store.get('api_keys').then(keys => {
+        loadApiKeys().then(keys => {
             if (keys && (keys.groq || keys.supermemory)) {
                 console.log('✅ API keys loaded');
                 apiKeys = keys;
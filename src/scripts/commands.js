@@ -35,6 +35,7 @@ export class CommandResult {
         type = CommandType.ACTION,
         icon = null,
         action = null,
+        altAction = null,
         shortcut = null,
         data = null,
     }) {
@@ -44,6 +45,7 @@ export class CommandResult {
         this.type = type;
         this.icon = icon || TypeIcons[type];
         this.action = action;
+        this.altAction = altAction; // Ctrl+Enter
         this.shortcut = shortcut;
         this.data = data;
     }
@@ -516,10 +518,11 @@ class CommandRegistry {
                         return new CommandResult({
                             id: `clip_${item.timestamp}`,
                             title: title,
-                            subtitle: 'Press Enter to copy',
+                            subtitle: 'Press Enter to copy, Ctrl+Enter to ask AI about it',
                             type: CommandType.CLIPBOARD,
                             data: { content: item.content },
                             action: async () => ({ type: 'copyToClipboard', content: item.content }),
+                            altAction: async () => ({ type: 'insert', value: `/ask-clip #${item.timestamp} ` }),
                         });
                     });
                 } catch (e) {
@@ -534,6 +537,65 @@ class CommandRegistry {
             }
         });
 
+        // Ask AI about a clipboard entry (latest is selected first)
+        this.register('ask-clip', {
+            description: 'Ask AI about clipboard content',
+            type: CommandType.CLIPBOARD,
+            getResults: async (args) => {
+                // "#<timestamp> question" pins one entry (from Ctrl+Enter in /clip)
+                const pinned = args.match(/^#(\d+)\s*(.*)$/);
+                if (pinned) args = pinned[2];
+                if (!args) {
+                    return [new CommandResult({
+                        id: 'askclip_usage',
+                        title: 'Type a question about your clipboard',
+                        subtitle: 'e.g. /ask-clip explain this error',
+                        type: CommandType.CLIPBOARD,
+                    })];
+                }
+                if (!window.__TAURI__?.core?.invoke) {
+                    return [new CommandResult({
+                        id: 'askclip_no_tauri',
+                        title: 'Clipboard history unavailable',
+                        subtitle: 'Tauri API not found',
+                        type: CommandType.CLIPBOARD,
+                    })];
+                }
+
+                try {
+                    let history = await window.__TAURI__.core.invoke('get_clipboard_history');
+                    if (pinned) {
+                        history = history.filter(item => String(item.timestamp) === pinned[1]);
+                    }
+                    if (history.length === 0) {
+                        return [new CommandResult({
+                            id: 'askclip_empty',
+                            title: 'Clipboard history empty',
+                            subtitle: 'Copy some text to ask about it',
+                            type: CommandType.CLIPBOARD,
+                        })];
+                    }
+
+                    return history.map(item => new CommandResult({
+                        id: `askclip_${item.timestamp}`,
+                        title: item.content.slice(0, 50).replace(/\n/g, ' ') + (item.content.length > 50 ? '...' : ''),
+                        subtitle: `Ask: ${args}`,
+                        type: CommandType.CLIPBOARD,
+                        data: { content: item.content },
+                        action: async () => ({ type: 'askClip', question: args, content: item.content }),
+                    }));
+                } catch (e) {
+                    console.error('Clipboard error:', e);
+                    return [new CommandResult({
+                        id: 'askclip_error',
+                        title: 'Error loading history',
+                        subtitle: String(e),
+                        type: CommandType.CLIPBOARD,
+                    })];
+                }
+            }
+        });
+
         // Provider command
         this.register('provider', {
             description: 'Change AI provider',
@@ -150,7 +150,7 @@ class ResultList {
             case 'Enter':
                 if (!e.shiftKey) {
                     e.preventDefault();
-                    this.executeSelected();
+                    this.executeSelected(e.ctrlKey);
                     return true;
                 }
                 break;
@@ -188,14 +188,15 @@ class ResultList {
     }
 
     /**
-     * Execute the selected item
+     * Execute the selected item (its secondary action if `alt` and it has one)
      */
-    async executeSelected() {
+    async executeSelected(alt = false) {
         if (this.results.length === 0) return null;
 
         const item = this.results[this.selectedIndex];
-        if (item && item.action && this.onSelect) {
-            const result = await item.action();
+        const action = (alt && item?.altAction) || item?.action;
+        if (action && this.onSelect) {
+            const result = await action();
             this.onSelect(result, item);
             return result;
         }
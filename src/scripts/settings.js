@@ -1,9 +1,9 @@
 /**
  * Ruty Settings Page
- * Handles API key configuration using Tauri Store plugin
+ * Handles API key configuration, stored in the system keyring
  */
 
-import { Store } from './tauri-store.js';
+import { loadApiKeys } from './api-keys.js';
 
 document.addEventListener('DOMContentLoaded', async () => {
     // If not in Tauri, show warning
@@ -13,27 +13,133 @@ document.addEventListener('DOMContentLoaded', async () => {
         return;
     }
 
-    const store = new Store('settings.json');
+    const invoke = window.__TAURI__.core.invoke;
+
+    // Theme: follow config.toml and live switches
+    const applyTheme = (theme) => document.documentElement.classList.toggle('light', theme === 'light');
+    invoke('get_theme').then(applyTheme).catch(() => {});
+    window.__TAURI__.event?.listen('theme-changed', (event) => applyTheme(event.payload));
+
+    // Labels in the configured language; the English in the markup stays
+    // if the backend can't be reached
+    try {
+        const messages = await invoke('translations');
+        for (const element of document.querySelectorAll('[data-i18n]')) {
+            element.textContent = messages[element.dataset.i18n] ?? element.textContent;
+        }
+        for (const element of document.querySelectorAll('[data-i18n-placeholder]')) {
+            element.placeholder = messages[element.dataset.i18nPlaceholder] ?? element.placeholder;
+        }
+    } catch (e) {
+        console.error('[Settings] Failed to load translations:', e);
+    }
 
     const groqInput = document.getElementById('groq-key');
     const smInput = document.getElementById('supermemory-key');
+    const providerSelect = document.getElementById('provider-select');
+    const modelSelect = document.getElementById('model-select');
+    const providerStatus = document.getElementById('provider-status');
     const saveBtn = document.getElementById('save-btn');
     const cancelBtn = document.getElementById('cancel-btn');
 
     // Load existing keys
     try {
         console.log('[Settings] Loading existing keys...');
-        const keys = await store.get('api_keys');
-        console.log('[Settings] Loaded keys:', keys ? 'found' : 'none');
+        const keys = await loadApiKeys();
+        console.log('[Settings] Loaded keys:', Object.keys(keys).length ? 'found' : 'none');
 
-        if (keys) {
-            groqInput.value = keys.groq || '';
-            smInput.value = keys.supermemory || '';
-        }
+        groqInput.value = keys.groq || '';
+        smInput.value = keys.supermemory || '';
     } catch (e) {
         console.error('[Settings] Failed to load settings:', e);
     }
 
+    // Load providers and models from the backend
+    let providers = {};
+
+    function fillModels(providerId, selected) {
+        modelSelect.innerHTML = '';
+        for (const model of providers[providerId]?.models || []) {
+            const option = document.createElement('option');
+            option.value = model;
+            option.textContent = model;
+            option.selected = model === selected;
+            modelSelect.appendChild(option);
+        }
+    }
+
+    try {
+        const resp = await invoke('get_providers');
+        providers = resp.providers;
+
+        for (const [id, info] of Object.entries(providers)) {
+            const option = document.createElement('option');
+            option.value = id;
+            option.textContent = info.requires_key && !info.has_api_key
+                ? `${info.name} (no key)`
+                : info.name;
+            option.selected = id === resp.current.provider;
+            providerSelect.appendChild(option);
+        }
+        fillModels(resp.current.provider, resp.current.model);
+        providerStatus.textContent = `Current: ${resp.current.provider} / ${resp.current.model}`;
+    } catch (e) {
+        console.error('[Settings] Failed to load providers:', e);
+        providerStatus.textContent = 'Backend unavailable - provider settings disabled.';
+        providerSelect.disabled = true;
+        modelSelect.disabled = true;
+    }
+
+    providerSelect.addEventListener('change', () => {
+        const id = providerSelect.value;
+        fillModels(id, providers[id]?.default_model);
+    });
+
+    // Autostart toggle applies immediately
+    const autostartToggle = document.getElementById('autostart-toggle');
+    try {
+        const status = await invoke('get_autostart');
+        autostartToggle.checked = status.desktop || status.systemd;
+    } catch (e) {
+        console.error('[Settings] Failed to read autostart status:', e);
+    }
+
+    autostartToggle.addEventListener('change', async () => {
+        try {
+            await invoke('set_autostart', { enabled: autostartToggle.checked });
+        } catch (e) {
+            console.error('[Settings] Failed to update autostart:', e);
+            autostartToggle.checked = !autostartToggle.checked;
+            alert(`Failed to update autostart:\n${e}`);
+        }
+    });
+
+    // Built-in actions
+    const actionList = document.getElementById('action-list');
+    const actionStatus = document.getElementById('action-status');
+    try {
+        const actions = await invoke('list_actions');
+        for (const action of actions) {
+            const button = document.createElement('button');
+            button.textContent = action.title;
+            button.title = action.description;
+            button.style.cssText = 'background:var(--bg-secondary); color:var(--text-primary); border:1px solid var(--border-subtle); padding:8px 14px; border-radius:8px; cursor:pointer; font-size:0.85rem;';
+            button.addEventListener('click', async () => {
+                if (action.confirm && !confirm(action.confirm)) {
+                    return;
+                }
+                try {
+                    actionStatus.textContent = await invoke('run_action', { id: action.id });
+                } catch (e) {
+                    actionStatus.textContent = `Failed: ${e}`;
+                }
+            });
+            actionList.appendChild(button);
+        }
+    } catch (e) {
+        console.error('[Settings] Failed to load actions:', e);
+    }
+
     // Cancel handler - always go back to main page
     cancelBtn.addEventListener('click', () => {
         window.location.href = 'index.html';
@@ -57,14 +163,17 @@ document.addEventListener('DOMContentLoaded', async () => {
         try {
             console.log('[Settings] Saving keys...');
 
-            // Save the keys
-            await store.set('api_keys', {
-                groq: groqKey,
-                supermemory: smKey
-            });
-
-            // Persist to disk
-            await store.save();
+            // Save the keys to the keyring (an empty one is removed)
+            await invoke('set_api_key', { provider: 'groq', apiKey: groqKey });
+            await invoke('set_api_key', { provider: 'supermemory', apiKey: smKey });
+
+            // Apply provider/model selection
+            if (!providerSelect.disabled && providerSelect.value) {
+                await invoke('set_provider', {
+                    provider: providerSelect.value,
+                    model: modelSelect.value || null
+                });
+            }
 
             console.log('[Settings] Keys saved successfully!');
 
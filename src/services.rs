@@ -0,0 +1,1094 @@
+//! Shared daemon services
+//!
+//! Long-lived subsystems used by both the iced UI and the gRPC server, so a
+//! headless daemon offers the same search, clipboard and AI features.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use notify::{RecursiveMode, Watcher};
+use ruty_core::archives::{self, ArchiveKind};
+use ruty_core::audit;
+use ruty_core::cache::{CacheStats, LruCache, ResponseCache, MB};
+use ruty_core::calc::{self, Calculator};
+use ruty_core::config::Config;
+use ruty_core::contacts::{self, Contact};
+use ruty_core::embedding;
+use ruty_core::folders;
+use ruty_core::fuzzy;
+use ruty_core::git::{self, RepoAction};
+use ruty_core::memory::{self, MemoryIndex};
+use ruty_core::mime::{self, MimeApps};
+use ruty_core::query::FileQuery;
+
+use crate::app::{ResultCategory, SearchResult};
+use crate::backend::api::BackendClient;
+use crate::events::{self, Event};
+use crate::native::apps::{AppChanges, AppIndexer, Application, LaunchOverride};
+use crate::native::audio::{self, DeviceKind};
+use crate::native::bluetooth;
+use crate::native::clipboard::ClipboardManager;
+use crate::native::files::{self, FileSearcher};
+use crate::native::windows::{self, Window};
+
+/// Default number of results per search
+pub const DEFAULT_LIMIT: usize = 8;
+
+/// How long listed Bluetooth/audio devices are reused while typing
+const DEVICE_CACHE_TTL: Duration = Duration::from_secs(5);
+/// How long a file search's results are reused for the same query
+const FILE_SEARCH_TTL: Duration = Duration::from_secs(5);
+/// How long contacts are reused before the address books are read again
+const CONTACTS_CACHE_TTL: Duration = Duration::from_secs(300);
+/// Wait after a change before rescanning, so an install writing several
+/// desktop files is picked up in one go
+const APP_WATCH_SETTLE: Duration = Duration::from_secs(1);
+/// Wait after an edit before reloading, so an editor's save (often a write
+/// then a rename) is read whole
+const CONFIG_WATCH_SETTLE: Duration = Duration::from_millis(200);
+
+pub struct Services {
+    /// Replaced whenever config.toml is edited (see `watch_config`)
+    config: RwLock<Arc<Config>>,
+    /// Empty until `warm_up` has scanned (unless crash-recovered)
+    apps: RwLock<AppIndexer>,
+    /// Probes for fd on first use, and again after `[files]` changes
+    files: RwLock<Option<Arc<FileSearcher>>>,
+    /// Icon name -> resolved file (looking one up stats dozens of paths)
+    icons: Mutex<LruCache<String, Option<PathBuf>>>,
+    /// Recent results of the searches worth not repeating, by provider and query
+    responses: Mutex<ResponseCache<Vec<SearchResult>>>,
+    /// Whether the app index is built
+    ready: AtomicBool,
+    /// Whether `start_background` has run
+    background: AtomicBool,
+    clipboard: ClipboardManager,
+    backend: BackendClient,
+    /// Git repos in the project dirs, discovered on first use
+    repos: RwLock<Option<Vec<PathBuf>>>,
+    /// Bluetooth devices and audio sinks/sources, listed at most every `DEVICE_CACHE_TTL`
+    devices: Mutex<Option<(Instant, Vec<SearchResult>)>>,
+    /// vCard and Evolution contacts, read at most every `CONTACTS_CACHE_TTL`
+    contacts: Mutex<Option<(Instant, Vec<Contact>)>>,
+    /// Passages of the `[memory]` folders, once `start_background` has
+    /// indexed them
+    memory: RwLock<Option<MemoryIndex>>,
+    /// Variables, `ans` and recent calculations since the daemon started
+    calculator: Mutex<Calculator>,
+}
+
+impl Services {
+    /// Build services, restoring crash-recovered state if available. Indexes
+    /// start empty: call `warm_up` to build them in the background.
+    pub fn new() -> Self {
+        let config = Config::load();
+
+        let recovered = crate::crash::take_recovered_apps();
+        let ready = recovered.is_some();
+        let mut apps = AppIndexer::from_apps(recovered.unwrap_or_default());
+        apps.set_hidden(config.apps.hidden.iter().cloned());
+        if ready {
+            crate::crash::record_apps(apps.all());
+        }
+
+        let clipboard = ClipboardManager::new();
+        configure_clipboard(&clipboard, &config);
+        clipboard.on_capture(events::clipboard_captured);
+        if let Some(items) = crate::crash::take_recovered_clipboard() {
+            clipboard.restore(items);
+        }
+
+        Self {
+            apps: RwLock::new(apps),
+            files: RwLock::new(None),
+            icons: Mutex::new(LruCache::new(config.cache.icons_mb * MB)),
+            responses: Mutex::new(ResponseCache::new(config.cache.responses_mb * MB)),
+            ready: AtomicBool::new(ready),
+            background: AtomicBool::new(false),
+            clipboard,
+            backend: BackendClient::new(),
+            repos: RwLock::new(None),
+            devices: Mutex::new(None),
+            contacts: Mutex::new(None),
+            memory: RwLock::new(None),
+            calculator: Mutex::new(Calculator::new()),
+            config: RwLock::new(Arc::new(config)),
+        }
+    }
+
+    /// Build the indexes on a background thread so startup and the first
+    /// queries don't wait for thousands of desktop files to parse
+    pub fn warm_up(self: &Arc<Self>) {
+        let services = self.clone();
+        std::thread::spawn(move || {
+            let start = Instant::now();
+            if !services.is_ready() {
+                services.refresh_apps();
+            }
+            services.files();
+            services.ready.store(true, Ordering::SeqCst);
+            tracing::info!(
+                "Indexed {} applications in {:?}",
+                services.app_count(),
+                start.elapsed()
+            );
+            // Queries made meanwhile show a placeholder; the UI reruns them
+            events::publish(Event::IndexRefreshed);
+        });
+    }
+
+    /// Start what the first keystroke doesn't need: the clipboard poller,
+    /// the app and config watchers and the `[memory]` index. The launcher
+    /// calls this once it has drawn its first frame (see `startup`); later
+    /// calls do nothing.
+    pub fn start_background(self: &Arc<Self>) {
+        if self.background.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        self.clipboard.start();
+        self.watch_apps();
+        self.watch_config();
+        let services = self.clone();
+        std::thread::spawn(move || services.index_memory());
+    }
+
+    /// Index the `[memory]` folders (blocking), replacing any earlier index.
+    /// Needs the embedding model; until it's downloaded there's no index.
+    pub fn index_memory(&self) {
+        let config = self.config();
+        let config = &config.memory;
+        if config.folders.is_empty() {
+            return;
+        }
+        if embedding::needs_model() {
+            tracing::info!("Memory is off until the embedding model is downloaded");
+            return;
+        }
+        let start = Instant::now();
+        let index = embedding::Model::load().and_then(|model| {
+            let cache = memory::cache_path();
+            MemoryIndex::build(Box::new(model), &config.resolved_dirs(), config.max_depth, Some(&cache))
+        });
+        match index {
+            Ok(index) => {
+                tracing::info!(
+                    "Indexed {} passages from {} notes in {:?}",
+                    index.len(),
+                    index.files(),
+                    start.elapsed()
+                );
+                *self.memory.write().unwrap() = Some(index);
+            }
+            Err(e) => tracing::warn!("Cannot index memory: {}", e),
+        }
+    }
+
+    /// Local context for a chat prompt from the notes most relevant to it;
+    /// `None` while the index is building or when nothing relevant is found
+    pub fn recall(&self, prompt: &str) -> Option<String> {
+        let memory = self.memory.read().unwrap();
+        let hits = memory.as_ref()?.search(prompt, self.config().memory.passages);
+        memory::context(&hits)
+    }
+
+    /// Whether the indexes are built; searches before then find no apps
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::SeqCst)
+    }
+
+    /// Number of indexed applications
+    pub fn app_count(&self) -> usize {
+        self.apps.read().unwrap().all().len()
+    }
+
+    /// Icon of an app: its `[icons] apps` override, else its file from the
+    /// icon cache when possible
+    fn icon(&self, app: &Application) -> Option<String> {
+        if let Some(icon) = self.config().icons.apps.get(&app.id) {
+            return Some(icon.clone());
+        }
+        let name = app.icon.clone()?;
+        let mut icons = self.icons.lock().unwrap();
+        let path = match icons.get(&name) {
+            Some(path) => path.clone(),
+            None => {
+                let path = app.icon_path();
+                let size = name.len() + path.as_ref().map_or(0, |p| p.as_os_str().len());
+                icons.insert(name, path.clone(), size);
+                path
+            }
+        };
+        path.map(|p| p.to_string_lossy().to_string())
+    }
+
+    /// Stats of the daemon-wide caches, by name
+    pub fn cache_stats(&self) -> Vec<(&'static str, CacheStats)> {
+        let clipboard = CacheStats {
+            entries: self.clipboard.get_history().len(),
+            bytes: self.clipboard.bytes(),
+            budget: self.config().cache.clipboard_mb * MB,
+            ..Default::default()
+        };
+        vec![
+            ("Icons", self.icons.lock().unwrap().stats()),
+            ("Responses", self.responses.lock().unwrap().stats()),
+            ("Clipboard", clipboard),
+        ]
+    }
+
+    /// Drop cached lookups (clipboard history is user data and stays)
+    pub fn clear_caches(&self) {
+        self.icons.lock().unwrap().clear();
+        self.responses.lock().unwrap().clear();
+    }
+
+    /// `answer` to `query`, or what `provider` answered to it less than
+    /// `ttl` ago
+    fn cached(
+        &self,
+        provider: &'static str,
+        query: &str,
+        ttl: Duration,
+        answer: impl FnOnce() -> Vec<SearchResult>,
+    ) -> Vec<SearchResult> {
+        if let Some(results) = self.responses.lock().unwrap().get(provider, query, ttl) {
+            return results;
+        }
+        let results = answer();
+        let size = results
+            .iter()
+            .map(|r| r.id.len() + r.title.len() + r.subtitle.len() + r.icon.as_ref().map_or(0, String::len))
+            .sum();
+        self.responses.lock().unwrap().insert(provider, query, results.clone(), size);
+        results
+    }
+
+    fn files(&self) -> Arc<FileSearcher> {
+        if let Some(files) = self.files.read().unwrap().as_ref() {
+            return files.clone();
+        }
+        let files = Arc::new(FileSearcher::with_config(&self.config().files));
+        *self.files.write().unwrap() = Some(files.clone());
+        files
+    }
+
+    /// The current config
+    pub fn config(&self) -> Arc<Config> {
+        self.config.read().unwrap().clone()
+    }
+
+    /// Read config.toml again and apply what changed. Settings only read at
+    /// startup are left alone and announced in `Event::ConfigReloaded`; a
+    /// file that doesn't parse keeps the current config and is announced in
+    /// `Event::ConfigInvalid`.
+    pub fn reload_config(&self) {
+        let new = match Config::try_load() {
+            Ok(config) => Arc::new(config),
+            Err(e) => {
+                tracing::warn!("{}", e);
+                events::publish(Event::ConfigInvalid(e));
+                return;
+            }
+        };
+        let old = self.config();
+        if new == old {
+            return;
+        }
+        // The UI saving favorites or the theme isn't news to the user
+        let quiet = Config::is_own_save();
+        *self.config.write().unwrap() = new.clone();
+        tracing::info!("Reloaded {}", Config::path().display());
+
+        if new.apps.hidden != old.apps.hidden {
+            self.set_hidden_apps(&new.apps.hidden);
+        }
+        if new.apps.prefer != old.apps.prefer && self.is_ready() {
+            self.refresh_apps();
+        }
+        if new.clipboard != old.clipboard || new.cache.clipboard_mb != old.cache.clipboard_mb {
+            configure_clipboard(&self.clipboard, &new);
+        }
+        if new.cache.icons_mb != old.cache.icons_mb {
+            *self.icons.lock().unwrap() = LruCache::new(new.cache.icons_mb * MB);
+        }
+        if new.cache.responses_mb != old.cache.responses_mb {
+            *self.responses.lock().unwrap() = ResponseCache::new(new.cache.responses_mb * MB);
+        }
+        if new.files != old.files {
+            *self.files.write().unwrap() = None;
+            self.responses.lock().unwrap().clear();
+        }
+        if new.projects != old.projects {
+            *self.repos.write().unwrap() = None;
+        }
+        if new.contacts != old.contacts {
+            *self.contacts.lock().unwrap() = None;
+        }
+        if new.memory != old.memory {
+            *self.memory.write().unwrap() = None;
+            self.index_memory();
+        }
+        if new.hotkeys != old.hotkeys {
+            crate::hotkey::reload(&new.hotkeys);
+        }
+        if new.ui.language != old.ui.language {
+            ruty_core::i18n::init(&new.ui.language);
+        }
+        if !quiet {
+            events::publish(Event::ConfigReloaded(old.restart_needed(&new)));
+        }
+    }
+
+    /// Reload the config whenever config.toml changes, for as long as the
+    /// daemon runs. The config dir is watched rather than the file, so
+    /// editors that save by renaming a new file over it are seen too.
+    pub fn watch_config(self: &Arc<Self>) {
+        let path = Config::path();
+        let Some(dir) = path.parent().map(Path::to_path_buf) else {
+            return;
+        };
+        let (sender, changes) = mpsc::channel::<notify::Result<notify::Event>>();
+        let watched = std::fs::create_dir_all(&dir)
+            .map_err(|e| e.to_string())
+            .and_then(|()| notify::recommended_watcher(sender).map_err(|e| e.to_string()))
+            .and_then(|mut watcher| {
+                watcher.watch(&dir, RecursiveMode::NonRecursive).map_err(|e| e.to_string())?;
+                Ok(watcher)
+            });
+        let watcher = match watched {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                tracing::warn!("Not watching {} for edits: {}", path.display(), e);
+                return;
+            }
+        };
+        let services = self.clone();
+        std::thread::spawn(move || {
+            // Watches until the thread ends with the daemon
+            let _watcher = watcher;
+            while let Ok(change) = changes.recv() {
+                let Ok(change) = change else {
+                    continue;
+                };
+                if change.kind.is_access() || !change.paths.contains(&path) {
+                    continue;
+                }
+                std::thread::sleep(CONFIG_WATCH_SETTLE);
+                while changes.try_recv().is_ok() {}
+                services.reload_config();
+            }
+        });
+    }
+
+    pub fn clipboard(&self) -> &ClipboardManager {
+        &self.clipboard
+    }
+
+    pub fn backend(&self) -> &BackendClient {
+        &self.backend
+    }
+
+    /// Search installed applications
+    pub fn search_apps(&self, query: &str, limit: usize) -> Vec<SearchResult> {
+        self.rank_apps(query, limit).0
+    }
+
+    /// App search that also says whether the top result is clearly the best
+    /// match (see `fuzzy::is_clear_winner`), so the UI can feature it
+    pub fn rank_apps(&self, query: &str, limit: usize) -> (Vec<SearchResult>, bool) {
+        let apps = self.apps.read().unwrap();
+        let ranked = if query.is_empty() {
+            apps.search(query).into_iter().map(|app| (app, 0)).collect()
+        } else {
+            apps.search_scored(query)
+        };
+        let clear_winner = ranked
+            .first()
+            .is_some_and(|(_, best)| fuzzy::is_clear_winner(*best, ranked.get(1).map(|(_, score)| *score)));
+        let results = ranked.into_iter().take(limit).map(|(app, _)| self.app_result(app)).collect();
+        (results, clear_winner)
+    }
+
+    /// Rows for the apps with these ids that are still installed, in order
+    pub fn app_results(&self, ids: &[&str]) -> Vec<SearchResult> {
+        let apps = self.apps.read().unwrap();
+        ids.iter()
+            .filter_map(|id| apps.all().iter().find(|app| app.id == *id))
+            .map(|app| self.app_result(app))
+            .collect()
+    }
+
+    fn app_result(&self, app: &Application) -> SearchResult {
+        SearchResult {
+            id: app.id.clone(),
+            title: app.name.clone(),
+            subtitle: match &app.missing_binary {
+                Some(binary) => format!("⚠ binary missing: {}", binary),
+                None => app.categories.first().cloned().unwrap_or_default(),
+            },
+            icon: self.icon(app),
+            category: ResultCategory::App,
+        }
+    }
+
+    /// Look up an indexed application by id
+    pub fn app(&self, id: &str) -> Option<Application> {
+        let apps = self.apps.read().unwrap();
+        apps.all().iter().find(|app| app.id == id).cloned()
+    }
+
+    /// An open window of the app, if it has one
+    pub fn open_window(&self, id: &str) -> Option<Window> {
+        let app = self.app(id)?;
+        windows::find(&app, &windows::list()).cloned()
+    }
+
+    /// Every install of an app (the one search shows first) as App rows
+    pub fn app_installs(&self, id: &str) -> Vec<SearchResult> {
+        let apps = self.apps.read().unwrap();
+        apps.installs(id)
+            .into_iter()
+            .enumerate()
+            .map(|(i, app)| SearchResult {
+                id: app.id.clone(),
+                title: app.name.clone(),
+                subtitle: match i {
+                    0 => format!("{} (shown in search)", app.source.label()),
+                    _ => format!("{} · {}", app.source.label(), app.desktop_file.display()),
+                },
+                icon: self.icon(app),
+                category: ResultCategory::App,
+            })
+            .collect()
+    }
+
+    /// Rescan installed applications, returning how many were found
+    pub fn refresh_apps(&self) -> usize {
+        let mut indexer = AppIndexer::new();
+        indexer.dedupe(&self.config().apps.prefer);
+        crate::crash::record_apps(indexer.all());
+        let count = indexer.all().len();
+        let mut apps = self.apps.write().unwrap();
+        indexer.set_hidden(apps.all().iter().filter(|app| apps.is_hidden(&app.id)).map(|app| app.id.clone()));
+        let changes = AppChanges::between(apps.all(), indexer.all());
+        *apps = indexer;
+        drop(apps);
+        // The first scan is announced once files are indexed too (`warm_up`)
+        if self.is_ready() {
+            events::publish(Event::IndexRefreshed);
+            if !changes.is_empty() {
+                tracing::info!("Apps added: {:?}, removed: {:?}", changes.added, changes.removed);
+                events::publish(Event::AppsChanged(changes));
+            }
+        }
+        count
+    }
+
+    /// Rescan the apps whenever something changes in the applications dirs,
+    /// for as long as the daemon runs. A dir that doesn't exist yet is
+    /// watched for through its parent, so the first user app or Flatpak is
+    /// seen too.
+    pub fn watch_apps(self: &Arc<Self>) {
+        let dirs = AppIndexer::desktop_dirs();
+        let (sender, changes) = mpsc::channel::<notify::Result<notify::Event>>();
+        let mut watcher = match notify::recommended_watcher(sender) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                tracing::warn!("Not watching the applications dirs for new apps: {}", e);
+                return;
+            }
+        };
+        watch_app_dirs(&mut watcher, &dirs);
+        let services = self.clone();
+        std::thread::spawn(move || {
+            while let Ok(change) = changes.recv() {
+                let Ok(change) = change else {
+                    continue;
+                };
+                let concerns_apps = change
+                    .paths
+                    .iter()
+                    .any(|path| dirs.iter().any(|dir| path.starts_with(dir) || dir.starts_with(path)));
+                if change.kind.is_access() || !concerns_apps {
+                    continue;
+                }
+                std::thread::sleep(APP_WATCH_SETTLE);
+                while changes.try_recv().is_ok() {}
+                // Dirs created meanwhile are watched from now on
+                watch_app_dirs(&mut watcher, &dirs);
+                // Changes during the first scan wait for it to finish
+                while !services.is_ready() {
+                    std::thread::sleep(APP_WATCH_SETTLE);
+                }
+                services.refresh_apps();
+            }
+        });
+    }
+
+    /// Names of all visible applications
+    pub fn app_names(&self) -> Vec<String> {
+        let apps = self.apps.read().unwrap();
+        apps.all()
+            .iter()
+            .filter(|app| apps.is_listed(app))
+            .map(|app| app.name.clone())
+            .collect()
+    }
+
+    /// Hide these apps from search from now on (`[apps] hidden`)
+    pub fn set_hidden_apps(&self, hidden: &[String]) {
+        self.apps.write().unwrap().set_hidden(hidden.iter().cloned());
+    }
+
+    /// Rows of `/apps`: every app search can show, hidden ones included,
+    /// whose name contains `query`. Enter on a row hides or shows it.
+    pub fn app_visibility(&self, query: &str) -> Vec<SearchResult> {
+        let query = query.to_lowercase();
+        let apps = self.apps.read().unwrap();
+        apps.all()
+            .iter()
+            .filter(|app| !app.no_display && app.duplicate_of.is_none())
+            .filter(|app| app.name.to_lowercase().contains(&query))
+            .map(|app| SearchResult {
+                id: format!("toggle-hidden\t{}", app.id),
+                title: app.name.clone(),
+                subtitle: visibility_hint(apps.is_hidden(&app.id), &app.id),
+                icon: self.icon(app),
+                category: ResultCategory::Action,
+            })
+            .collect()
+    }
+
+    /// Search files in the configured directories (blocking: spawns fd/find,
+    /// unless the same search ran in the last `FILE_SEARCH_TTL`). Filters
+    /// like `kind:pdf` in the query narrow it; one that doesn't parse makes
+    /// the whole query a plain name search.
+    pub fn search_files(&self, query: &str, limit: usize) -> Vec<SearchResult> {
+        self.cached("files", &format!("{}\t{}", limit, query), FILE_SEARCH_TTL, || {
+            let parsed = FileQuery::parse(query).unwrap_or_else(|e| {
+                tracing::debug!("Searching file names for {:?}: {}", query, e);
+                FileQuery::name(query)
+            });
+            self.files()
+                .search_query(&parsed, limit)
+                .into_iter()
+                .map(|file| SearchResult {
+                    id: file.path.clone(),
+                    title: file.name,
+                    subtitle: file.path,
+                    icon: None,
+                    category: ResultCategory::File,
+                })
+                .collect()
+        })
+    }
+
+    /// Children of `dir` matching `filter`, as file rows for browsing it
+    pub fn browse(&self, dir: &Path, filter: &str) -> Result<Vec<SearchResult>, String> {
+        let files = files::list_dir(dir, filter)?;
+        Ok(files
+            .into_iter()
+            .map(|file| SearchResult {
+                id: file.path.clone(),
+                title: if file.is_dir { format!("{}/", file.name) } else { file.name },
+                subtitle: file.path,
+                icon: None,
+                category: ResultCategory::File,
+            })
+            .collect())
+    }
+
+    /// Search apps, then fill remaining slots with files
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchResult> {
+        let mut results = self.search_apps(query, limit);
+        if results.len() < limit {
+            results.extend(self.search_files(query, limit - results.len()));
+        }
+        results
+    }
+
+    /// Search git repositories in the project dirs by name
+    pub fn search_repos(&self, query: &str, limit: usize) -> Vec<SearchResult> {
+        if self.repos.read().unwrap().is_none() {
+            let config = self.config();
+            let projects = &config.projects;
+            let found = git::discover(&projects.resolved_dirs(), projects.max_depth);
+            tracing::info!("Found {} git repositories", found.len());
+            *self.repos.write().unwrap() = Some(found);
+        }
+        let repos = self.repos.read().unwrap();
+        git::search(repos.as_deref().unwrap_or_default(), query)
+            .into_iter()
+            .take(limit)
+            .map(|repo| SearchResult {
+                id: repo.display().to_string(),
+                title: git::repo_name(repo),
+                subtitle: repo.display().to_string(),
+                icon: None,
+                category: ResultCategory::Git,
+            })
+            .collect()
+    }
+
+    /// Quick actions for one repository, reflecting its current state
+    pub fn repo_actions(&self, path: &Path) -> Result<Vec<SearchResult>, String> {
+        let info = git::inspect(path)?;
+        let branch = info.branch.as_deref().unwrap_or("detached");
+        let action = |action: RepoAction, title: String, subtitle: String| SearchResult {
+            id: action.encode(path),
+            title,
+            subtitle,
+            icon: None,
+            category: ResultCategory::Git,
+        };
+
+        let mut actions = vec![action(
+            RepoAction::OpenInEditor,
+            format!("Open {} in editor", info.name),
+            self.config().projects.editor.clone(),
+        )];
+        let status = match info.changed {
+            0 => String::from("clean"),
+            n => format!("{} changed", n),
+        };
+        actions.push(action(
+            RepoAction::ShowStatus,
+            format!("Status: {} ({})", branch, status),
+            String::from("Show changed files"),
+        ));
+        if let Some(url) = info.remote_url {
+            actions.push(action(RepoAction::CopyRemoteUrl, String::from("Copy remote URL"), url));
+        }
+        for name in info.recent_branches {
+            actions.push(action(
+                RepoAction::Checkout(name.clone()),
+                format!("Checkout {}", name),
+                format!("from {}", branch),
+            ));
+        }
+        Ok(actions)
+    }
+
+    /// Run an encoded repo action, returning a message for the user
+    pub fn run_repo_action(&self, id: &str) -> Result<String, String> {
+        let (repo, action) = RepoAction::decode(id).ok_or("Unknown repository action")?;
+        match action {
+            RepoAction::OpenInEditor => {
+                let config = self.config();
+                let mut parts = config.projects.editor.split_whitespace();
+                let editor = parts.next().ok_or("No editor configured")?;
+                std::process::Command::new(editor)
+                    .args(parts)
+                    .arg(&repo)
+                    .spawn()
+                    .map_err(|e| format!("Failed to start {}: {}", editor, e))?;
+                Ok(format!("Opened {}", git::repo_name(&repo)))
+            }
+            RepoAction::CopyRemoteUrl => {
+                let url = git::inspect(&repo)?.remote_url.ok_or("No origin remote")?;
+                ruty_core::clipboard::copy(&url)?;
+                Ok(format!("Copied {}", url))
+            }
+            RepoAction::Checkout(branch) => {
+                git::checkout(&repo, &branch)?;
+                Ok(format!("Switched {} to {}", git::repo_name(&repo), branch))
+            }
+            RepoAction::ShowStatus => {
+                let lines = git::status(&repo)?;
+                Ok(if lines.is_empty() {
+                    String::from("Working tree clean")
+                } else {
+                    lines.join("\n")
+                })
+            }
+        }
+    }
+
+    /// Run a `folder_actions` row's action on `dir`, returning a message for
+    /// the user
+    pub fn run_folder_action(&self, action: &str, dir: &Path) -> Result<String, String> {
+        let config = self.config();
+        let name = dir.file_name().map_or_else(|| dir.display().to_string(), |name| name.to_string_lossy().to_string());
+        if action == "terminal-here" {
+            folders::open_terminal(&config, dir)?;
+            Ok(format!("Opened a terminal in {}", name))
+        } else {
+            folders::open_editor(&config, dir)?;
+            Ok(format!("Opened {} in the editor", name))
+        }
+    }
+
+    /// "Open With…" rows for a file: every registered handler (default
+    /// first), then "Always open with" rows for the non-default ones
+    pub fn open_with(&self, path: &str) -> Result<Vec<SearchResult>, String> {
+        let mime = mime::mime_type(Path::new(path))
+            .ok_or_else(|| format!("Unknown file type: {}", path))?;
+        let apps = self.apps.read().unwrap();
+        let mimeapps = MimeApps::load();
+        let handlers = mimeapps.handlers(&mime, apps.all());
+        if handlers.is_empty() {
+            return Err(format!("No applications registered for {}", mime));
+        }
+        let default = mimeapps.default_id(&mime, &handlers);
+
+        let row = |kind: &str, app: &Application, title: String, subtitle: String| SearchResult {
+            id: format!("{}\t{}\t{}\t{}", kind, app.id, mime, path),
+            title,
+            subtitle,
+            icon: self.icon(app),
+            category: ResultCategory::OpenWith,
+        };
+        let mut rows: Vec<SearchResult> = handlers
+            .iter()
+            .map(|app| {
+                let is_default = default.as_deref() == Some(mime::desktop_id(app).as_str());
+                let subtitle = if is_default { format!("Default for {}", mime) } else { mime.clone() };
+                row("open", app, format!("Open with {}", app.name), subtitle)
+            })
+            .collect();
+        rows.extend(
+            handlers
+                .iter()
+                .filter(|app| default.as_deref() != Some(mime::desktop_id(app).as_str()))
+                .map(|app| {
+                    row(
+                        "default",
+                        app,
+                        format!("Always open with {}", app.name),
+                        format!("Set as default for {} and open", mime),
+                    )
+                }),
+        );
+        Ok(rows)
+    }
+
+    /// Run an "Open With…" row, returning a message for the user
+    pub fn run_open_with(&self, id: &str) -> Result<String, String> {
+        let mut parts = id.splitn(4, '\t');
+        let (Some(kind), Some(app_id), Some(mime), Some(path)) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(String::from("Unknown Open With action"));
+        };
+        let app = self.app(app_id).ok_or_else(|| format!("{} is no longer installed", app_id))?;
+        if kind == "default" {
+            mime::set_default(mime, &app)?;
+        }
+        let launch = launch_overrides().remove(&app.id);
+        app.open_file(path, launch.as_ref())?;
+        Ok(if kind == "default" {
+            format!("{} is now the default for {}", app.name, mime)
+        } else {
+            format!("Opened with {}", app.name)
+        })
+    }
+
+    /// Search Bluetooth devices and audio sinks/sources by name.
+    /// `strict` only accepts prefix/substring matches (used for plain prompts).
+    pub fn search_devices(&self, query: &str, limit: usize, strict: bool) -> Vec<SearchResult> {
+        let mut cache = self.devices.lock().unwrap();
+        if cache.as_ref().is_none_or(|(listed, _)| listed.elapsed() > DEVICE_CACHE_TTL) {
+            *cache = Some((Instant::now(), list_devices()));
+        }
+        let devices = cache.as_ref().map(|(_, d)| d.as_slice()).unwrap_or_default();
+
+        let query = query.trim().to_lowercase();
+        let min_score = if strict { fuzzy::CONTAINS } else { 1 };
+        let mut matches: Vec<(&SearchResult, i32)> = devices
+            .iter()
+            .map(|d| (d, if query.is_empty() { 1 } else { fuzzy::score(&d.title, &query) }))
+            .filter(|(_, score)| *score >= min_score)
+            .collect();
+        matches.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        matches.into_iter().take(limit).map(|(d, _)| d.clone()).collect()
+    }
+
+    /// Connect/disconnect a Bluetooth device or switch the default audio device
+    pub fn run_device_action(&self, id: &str) -> Result<String, String> {
+        let mut parts = id.splitn(3, '\t');
+        let outcome = match (parts.next(), parts.next(), parts.next()) {
+            (Some("bt"), Some(connected), Some(path)) => {
+                let connected = connected == "1";
+                bluetooth::toggle(path, connected)
+                    .map(|()| if connected { "Disconnected" } else { "Connected" }.to_string())
+            }
+            (Some("sink"), Some(name), None) => audio::set_default(DeviceKind::Sink, name)
+                .map(|()| "Switched audio output".to_string()),
+            (Some("source"), Some(name), None) => audio::set_default(DeviceKind::Source, name)
+                .map(|()| "Switched audio input".to_string()),
+            _ => Err(format!("Unknown device action: {}", id)),
+        };
+        // State changed; list again next time
+        *self.devices.lock().unwrap() = None;
+        outcome
+    }
+
+    /// Search contacts by name or email address. `strict` only accepts
+    /// prefix/substring matches (used for plain prompts).
+    pub fn search_contacts(&self, query: &str, limit: usize, strict: bool) -> Vec<SearchResult> {
+        let mut cache = self.contacts.lock().unwrap();
+        if cache.as_ref().is_none_or(|(listed, _)| listed.elapsed() > CONTACTS_CACHE_TTL) {
+            let files = contacts::sources(self.config().contacts.resolved_paths());
+            *cache = Some((Instant::now(), contacts::load(&files)));
+        }
+        let contacts = cache.as_ref().map(|(_, c)| c.as_slice()).unwrap_or_default();
+
+        let query = query.trim().to_lowercase();
+        let min_score = if strict { fuzzy::CONTAINS } else { 1 };
+        let mut matches: Vec<(&Contact, i32)> = contacts
+            .iter()
+            .map(|c| {
+                let score = if query.is_empty() {
+                    1
+                } else if c.emails.iter().any(|email| email.to_lowercase().contains(&query)) {
+                    fuzzy::score(&c.name, &query).max(fuzzy::CONTAINS)
+                } else {
+                    fuzzy::score(&c.name, &query)
+                };
+                (c, score)
+            })
+            .filter(|(_, score)| *score >= min_score)
+            .collect();
+        matches.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        matches
+            .into_iter()
+            .take(limit)
+            .map(|(c, _)| SearchResult {
+                id: c.uid.clone(),
+                title: c.name.clone(),
+                subtitle: c.summary(),
+                icon: None,
+                category: ResultCategory::Contact,
+            })
+            .collect()
+    }
+
+    /// A contact's email addresses and phone numbers as `compose\t<email>` and
+    /// `copy\t<value>` rows
+    pub fn contact_actions(&self, uid: &str) -> Vec<SearchResult> {
+        let cache = self.contacts.lock().unwrap();
+        let Some(contact) = cache.as_ref().and_then(|(_, c)| c.iter().find(|c| c.uid == uid)) else {
+            return Vec::new();
+        };
+        let row = |action: &str, title: &str, value: &str| SearchResult {
+            id: format!("{}\t{}", action, value),
+            title: title.to_string(),
+            subtitle: value.to_string(),
+            icon: None,
+            category: ResultCategory::Contact,
+        };
+        let mut rows = Vec::new();
+        for email in &contact.emails {
+            rows.push(row("compose", "Compose email", email));
+            rows.push(row("copy", "Copy email address", email));
+        }
+        for phone in &contact.phones {
+            rows.push(row("copy", "Copy phone number", phone));
+        }
+        rows
+    }
+
+    /// The calculator row for a prompt that reads as math; its id is the
+    /// prompt, so `record_calculation` can keep it
+    pub fn calculate(&self, input: &str) -> Option<SearchResult> {
+        let calculation = self.calculator.lock().unwrap().calculate(input)?;
+        let subtitle = match &calculation.variable {
+            Some(name) => format!("{} · ↵ to copy and keep as {}", calculation.input(), name),
+            None => format!("{} · ↵ to copy", calculation.input()),
+        };
+        Some(SearchResult {
+            id: input.trim().to_string(),
+            title: format!("= {}", calc::format_value(calculation.value)),
+            subtitle,
+            icon: None,
+            category: ResultCategory::Calculator,
+        })
+    }
+
+    /// Keep the result of a calculator row (as `ans`, its variable and in
+    /// the history); returns the value as shown
+    pub fn record_calculation(&self, input: &str) -> Option<String> {
+        let mut calculator = self.calculator.lock().unwrap();
+        let calculation = calculator.calculate(input)?;
+        let value = calc::format_value(calculation.value);
+        calculator.record(calculation);
+        Some(value)
+    }
+
+    /// The session's calculator as it stands
+    pub fn calculator(&self) -> Calculator {
+        self.calculator.lock().unwrap().clone()
+    }
+
+    /// Open a URL in the default browser
+    pub fn open_url(&self, url: &str) -> Result<(), String> {
+        std::process::Command::new("xdg-open")
+            .arg(url)
+            .spawn()
+            .map_err(|e| format!("Failed to open {}: {}", url, e))?;
+        audit::record(audit::Kind::Open, url, "");
+        Ok(())
+    }
+
+    /// Open the calendar app on a day (YYYY-MM-DD) with `[calendar] open_command`
+    pub fn open_calendar(&self, date: &str) -> Result<(), String> {
+        let command = self.config().calendar.open_command.replace("{date}", date);
+        let mut parts = command.split_whitespace();
+        let program = parts.next().ok_or("No [calendar] open_command configured")?;
+        std::process::Command::new(program)
+            .args(parts)
+            .spawn()
+            .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+        Ok(())
+    }
+
+    /// Open the folder containing a file
+    pub fn reveal(&self, path: &str) -> Result<(), String> {
+        self.files().reveal(path)
+    }
+
+    /// Launch an app or open a file by result id
+    pub fn launch(&self, id: &str, category: ResultCategory) -> Result<(), String> {
+        match category {
+            ResultCategory::App => self.spawn_app(id).map(drop),
+            ResultCategory::File => {
+                self.files().open(id)?;
+                audit::record(audit::Kind::Open, id, "");
+                Ok(())
+            }
+            other => Err(format!("Cannot launch {} results", other.as_str())),
+        }
+    }
+
+    /// Launch an app, returning its process so the UI can tell whether it started
+    pub fn spawn_app(&self, id: &str) -> Result<Child, String> {
+        let overrides = launch_overrides();
+        let mut apps = self.apps.write().unwrap();
+        apps.set_overrides(overrides);
+        let child = apps.spawn(id)?;
+        let name = apps.all().iter().find(|app| app.id == id).map(|app| app.name.clone()).unwrap_or_default();
+        events::publish(Event::AppLaunched { id: id.to_string(), name });
+        Ok(child)
+    }
+}
+
+/// Apply `[clipboard]` and the clipboard's `[cache]` budget
+fn configure_clipboard(clipboard: &ClipboardManager, config: &Config) {
+    clipboard.set_budget(config.cache.clipboard_mb * MB);
+    clipboard.set_limit(config.clipboard.history_limit);
+    clipboard.set_poll_interval(Duration::from_millis(config.clipboard.poll_interval_ms));
+    clipboard.set_ignored(&config.clipboard.ignore, windows::active_class);
+}
+
+/// Watch each of `dirs` and everything under it; one that doesn't exist yet
+/// through its nearest existing parent, to see it appear
+fn watch_app_dirs(watcher: &mut impl Watcher, dirs: &[PathBuf]) {
+    for dir in dirs {
+        let watched = if dir.is_dir() {
+            watcher.watch(dir, RecursiveMode::Recursive)
+        } else {
+            match dir.ancestors().skip(1).find(|parent| parent.is_dir()) {
+                Some(parent) => watcher.watch(parent, RecursiveMode::NonRecursive),
+                None => continue,
+            }
+        };
+        if let Err(e) = watched {
+            tracing::debug!("Not watching {} for new apps: {}", dir.display(), e);
+        }
+    }
+}
+
+/// Subtitle of an `/apps` row
+pub fn visibility_hint(hidden: bool, id: &str) -> String {
+    format!("{} · {}", if hidden { "Hidden from search" } else { "Shown in search" }, id)
+}
+
+/// Launch overrides as config.toml has them now, so edits made with "Edit
+/// launch options" apply on the next launch without a restart
+fn launch_overrides() -> BTreeMap<String, LaunchOverride> {
+    Config::load().apps.overrides
+}
+
+/// List/extract rows for an archive file (empty for other files)
+pub fn archive_actions(path: &str) -> Vec<SearchResult> {
+    if ArchiveKind::from_path(Path::new(path)).is_none() {
+        return Vec::new();
+    }
+    let dest = archives::default_destination(Path::new(path));
+    let row = |action: &str, title: &str, subtitle: String| SearchResult {
+        id: format!("{}\t{}", action, path),
+        title: title.to_string(),
+        subtitle,
+        icon: None,
+        category: ResultCategory::Archive,
+    };
+    vec![
+        row("list", "List contents", String::from("Show the files in the detail panel")),
+        row("extract", "Extract here", dest.display().to_string()),
+        row("extract-to", "Extract to…", String::from("Choose a folder")),
+    ]
+}
+
+/// "Open terminal here" and "Open in editor" rows for a folder
+pub fn folder_actions(path: &str) -> Vec<SearchResult> {
+    let row = |action: &str, title: &str| SearchResult {
+        id: format!("{}\t{}", action, path),
+        title: title.to_string(),
+        subtitle: path.to_string(),
+        icon: None,
+        category: ResultCategory::Action,
+    };
+    vec![row("terminal-here", "Open terminal here"), row("editor-here", "Open in editor")]
+}
+
+/// Current Bluetooth and audio devices as results (missing services are skipped)
+fn list_devices() -> Vec<SearchResult> {
+    let mut results = Vec::new();
+    match bluetooth::devices() {
+        Ok(devices) => results.extend(devices.into_iter().map(|d| SearchResult {
+            id: format!("bt\t{}\t{}", if d.connected { 1 } else { 0 }, d.path),
+            subtitle: if d.connected {
+                "Bluetooth · connected · Enter to disconnect".to_string()
+            } else {
+                "Bluetooth · Enter to connect".to_string()
+            },
+            title: d.name,
+            icon: None,
+            category: ResultCategory::Device,
+        })),
+        Err(e) => tracing::debug!("Bluetooth devices unavailable: {}", e),
+    }
+    for kind in [DeviceKind::Sink, DeviceKind::Source] {
+        match audio::devices(kind) {
+            Ok(devices) => results.extend(devices.into_iter().map(|d| {
+                let (prefix, label) = match kind {
+                    DeviceKind::Sink => ("sink", "Output"),
+                    DeviceKind::Source => ("source", "Input"),
+                };
+                SearchResult {
+                    id: format!("{}\t{}", prefix, d.name),
+                    title: d.description,
+                    subtitle: if d.is_default {
+                        format!("{} · default", label)
+                    } else {
+                        format!("{} · Enter to make default", label)
+                    },
+                    icon: None,
+                    category: ResultCategory::Device,
+                }
+            })),
+            Err(e) => tracing::debug!("Audio devices unavailable: {}", e),
+        }
+    }
+    results
+}
+
+impl Default for Services {
+    fn default() -> Self {
+        Self::new()
+    }
+}
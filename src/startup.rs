@@ -0,0 +1,98 @@
+//! Startup timings
+//!
+//! A launcher is judged by how soon it takes the first keystroke. Each phase
+//! of the daemon's startup runs in a `startup` tracing span and its duration
+//! is kept here, until the window draws its first frame: from then on it's
+//! interactive. `ruty --timings` starts the daemon and prints the phases at
+//! that point.
+//!
+//! What the first keystroke doesn't need (the clipboard poller, the app and
+//! config watchers, the memory index) starts after that frame, see
+//! `Services::start_background`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Start-to-interactive time to stay under
+pub const TARGET: Duration = Duration::from_millis(150);
+
+static STARTED: OnceLock<Instant> = OnceLock::new();
+static PHASES: Mutex<Vec<(&'static str, Duration)>> = Mutex::new(Vec::new());
+static PRINT: AtomicBool = AtomicBool::new(false);
+static INTERACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Note the process start; call first thing in `main`
+pub fn begin() {
+    STARTED.get_or_init(Instant::now);
+}
+
+/// Print the timings once interactive (`ruty --timings`)
+pub fn print_when_interactive() {
+    PRINT.store(true, Ordering::SeqCst);
+}
+
+/// Run one phase of startup, timed
+pub fn phase<T>(name: &'static str, run: impl FnOnce() -> T) -> T {
+    let _span = tracing::info_span!("startup", phase = name).entered();
+    let start = Instant::now();
+    let result = run();
+    PHASES.lock().unwrap().push((name, start.elapsed()));
+    result
+}
+
+/// The first frame is drawn: log the timings (and print them for
+/// `--timings`). Returns false if this already happened.
+pub fn interactive() -> bool {
+    if INTERACTIVE.swap(true, Ordering::SeqCst) {
+        return false;
+    }
+    let total = STARTED.get().map_or(Duration::ZERO, Instant::elapsed);
+    let report = report(&PHASES.lock().unwrap(), total);
+    if total > TARGET {
+        tracing::warn!("Interactive after {:.1} ms, over the {} ms target", ms(total), TARGET.as_millis());
+    } else {
+        tracing::info!("Interactive after {:.1} ms", ms(total));
+    }
+    tracing::debug!("Startup phases:\n{}", report);
+    if PRINT.load(Ordering::SeqCst) {
+        println!("{}", report);
+    }
+    true
+}
+
+/// One line per phase, then the total and how it compares to `TARGET`
+pub fn report(phases: &[(&str, Duration)], total: Duration) -> String {
+    let width = phases.iter().map(|(name, _)| name.len()).max().unwrap_or(0).max("interactive".len());
+    let mut lines: Vec<String> =
+        phases.iter().map(|(name, took)| format!("{:<width$}  {:>7.1} ms", name, ms(*took), width = width)).collect();
+    let verdict = if total > TARGET { "over" } else { "within" };
+    lines.push(format!(
+        "{:<width$}  {:>7.1} ms ({} the {} ms target)",
+        "interactive",
+        ms(total),
+        verdict,
+        TARGET.as_millis(),
+        width = width
+    ));
+    lines.join("\n")
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let phases = [("backend", Duration::from_micros(12_340)), ("services", Duration::from_millis(4))];
+        assert_eq!(
+            report(&phases, Duration::from_millis(98)),
+            "backend         12.3 ms\nservices         4.0 ms\ninteractive     98.0 ms (within the 150 ms target)"
+        );
+        assert!(report(&[], Duration::from_millis(151)).ends_with("151.0 ms (over the 150 ms target)"));
+    }
+}
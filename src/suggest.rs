@@ -0,0 +1,198 @@
+//! Inline suggestions under the search bar
+//!
+//! Completes slash commands, aliases, quicklink keywords, file search filters,
+//! app names and previous queries from what has been typed so far. Tab
+//! accepts the top one.
+
+use std::collections::BTreeMap;
+
+use ruty_core::history::{History, Kind};
+use ruty_core::query;
+
+use crate::commands::COMMANDS;
+
+/// Suggestions shown at once
+pub const MAX_SUGGESTIONS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionKind {
+    Command,
+    Alias,
+    Quicklink,
+    History,
+    App,
+    /// File search filter key or value, e.g. `kind:` or `kind:image`
+    Filter,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    /// Prompt text after accepting the suggestion
+    pub completion: String,
+    /// Extra text shown next to the completion
+    pub hint: String,
+    pub kind: SuggestionKind,
+}
+
+/// Sources consulted for suggestions
+pub struct SuggestionEngine<'a> {
+    pub history: &'a History,
+    pub aliases: &'a BTreeMap<String, String>,
+    pub quicklinks: &'a BTreeMap<String, String>,
+    pub apps: &'a [String],
+}
+
+impl SuggestionEngine<'_> {
+    /// Suggestions for the current prompt, best first
+    pub fn suggest(&self, input: &str) -> Vec<Suggestion> {
+        if input.trim().is_empty() {
+            return Vec::new();
+        }
+        let lower = input.to_lowercase();
+        let mut suggestions = Vec::new();
+
+        // Still typing the first word: complete command / alias / quicklink keywords
+        if !input.contains(' ') {
+            if lower.starts_with('/') {
+                for spec in COMMANDS.iter().filter(|c| c.name.starts_with(&lower)) {
+                    let completion = if spec.args.is_empty() {
+                        spec.name.to_string()
+                    } else {
+                        format!("{} ", spec.name)
+                    };
+                    suggestions.push(Suggestion {
+                        completion,
+                        hint: format!("{} {}", spec.args, spec.description()).trim().to_string(),
+                        kind: SuggestionKind::Command,
+                    });
+                }
+            } else {
+                for (name, expansion) in self.aliases.iter().filter(|(k, _)| k.starts_with(&lower)) {
+                    suggestions.push(Suggestion {
+                        completion: name.clone(),
+                        hint: format!("→ {}", expansion),
+                        kind: SuggestionKind::Alias,
+                    });
+                }
+                for (keyword, url) in self.quicklinks.iter().filter(|(k, _)| k.starts_with(&lower)) {
+                    suggestions.push(Suggestion {
+                        completion: format!("{} ", keyword),
+                        hint: url.clone(),
+                        kind: SuggestionKind::Quicklink,
+                    });
+                }
+            }
+        }
+
+        // Filter keys complete in /file; values (after `key:`) in plain prompts too
+        let (head, word) = match input.rsplit_once(' ') {
+            Some((head, word)) => (Some(head), word),
+            None => (None, input),
+        };
+        let file_search = head.is_some_and(|head| {
+            matches!(head.split_whitespace().next(), Some("/file" | "/files" | "/f"))
+        });
+        if file_search || (word.contains(':') && !lower.starts_with('/')) {
+            for (completion, hint) in query::complete(word) {
+                suggestions.push(Suggestion {
+                    completion: match head {
+                        Some(head) => format!("{} {}", head, completion),
+                        None => completion,
+                    },
+                    hint: hint.to_string(),
+                    kind: SuggestionKind::Filter,
+                });
+            }
+        }
+
+        // Previous queries that extend what's typed; commands recall searches first
+        let kinds = if lower.starts_with('/') {
+            [Kind::Search, Kind::Chat]
+        } else {
+            [Kind::Chat, Kind::Search]
+        };
+        for kind in kinds {
+            for entry in self.history.entries(kind).iter().rev() {
+                if entry.len() > input.len() && entry.to_lowercase().starts_with(&lower) {
+                    suggestions.push(Suggestion {
+                        completion: entry.clone(),
+                        hint: String::from("history"),
+                        kind: SuggestionKind::History,
+                    });
+                }
+            }
+        }
+
+        // App names complete into an /app search
+        let app_query = lower.strip_prefix("/app ").unwrap_or(&lower).trim_start();
+        if !app_query.is_empty() && (!lower.starts_with('/') || lower.starts_with("/app ")) {
+            for name in self.apps.iter().filter(|n| n.to_lowercase().starts_with(app_query)) {
+                suggestions.push(Suggestion {
+                    completion: format!("/app {}", name),
+                    hint: String::from("app"),
+                    kind: SuggestionKind::App,
+                });
+            }
+        }
+
+        let mut seen = vec![lower];
+        suggestions.retain(|s| {
+            let key = s.completion.to_lowercase();
+            if seen.contains(&key) {
+                return false;
+            }
+            seen.push(key);
+            true
+        });
+        suggestions.truncate(MAX_SUGGESTIONS);
+        suggestions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completions(engine: &SuggestionEngine, input: &str) -> Vec<String> {
+        engine.suggest(input).into_iter().map(|s| s.completion).collect()
+    }
+
+    #[test]
+    fn test_commands_aliases_and_quicklinks() {
+        let history = History::default();
+        let aliases = BTreeMap::from([("gpt".to_string(), "/providers openai gpt-4o".to_string())]);
+        let quicklinks = BTreeMap::from([("gh".to_string(), "https://github.com/search?q={query}".to_string())]);
+        let engine = SuggestionEngine { history: &history, aliases: &aliases, quicklinks: &quicklinks, apps: &[] };
+
+        assert_eq!(completions(&engine, "/c"), vec!["/contacts ", "/context ", "/clip ", "/cache ", "/clear"]);
+        assert_eq!(completions(&engine, "/clear"), Vec::<String>::new());
+        assert_eq!(completions(&engine, "g"), vec!["gpt", "gh "]);
+        assert!(completions(&engine, "").is_empty());
+    }
+
+    #[test]
+    fn test_history_and_apps() {
+        let mut history = History::default();
+        history.push(Kind::Chat, "firefox vs chrome");
+        history.push(Kind::Search, "/app firefox");
+        let apps = vec!["Firefox".to_string(), "Files".to_string()];
+        let quicklinks = BTreeMap::new();
+        let engine = SuggestionEngine { history: &history, aliases: &BTreeMap::new(), quicklinks: &quicklinks, apps: &apps };
+
+        assert_eq!(completions(&engine, "fire"), vec!["firefox vs chrome", "/app Firefox"]);
+        assert_eq!(completions(&engine, "/app fi"), vec!["/app firefox", "/app Files"]);
+    }
+
+    #[test]
+    fn test_file_filters() {
+        let history = History::default();
+        let quicklinks = BTreeMap::new();
+        let engine = SuggestionEngine { history: &history, aliases: &BTreeMap::new(), quicklinks: &quicklinks, apps: &[] };
+
+        assert_eq!(completions(&engine, "/file report s"), vec!["/file report size:"]);
+        assert_eq!(completions(&engine, "/f kind:a"), vec!["/f kind:audio", "/f kind:archive"]);
+        assert_eq!(completions(&engine, "report modified:to"), vec!["report modified:today"]);
+        // Keys alone would catch every word of a plain prompt
+        assert!(completions(&engine, "report s").is_empty());
+    }
+}
@@ -0,0 +1,140 @@
+//! Countdown timers
+//!
+//! `/timer 25m tea` counts down in the background; when it's up the launcher
+//! shows a toast and sends a desktop notification. Pending timers are listed
+//! on the empty-query dashboard. Like the conversation, they live in memory
+//! only and don't survive a restart.
+
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timer {
+    pub id: u64,
+    pub label: String,
+    pub due: Instant,
+}
+
+impl Timer {
+    /// Label to show ("Timer" if none was given)
+    pub fn title(&self) -> &str {
+        if self.label.is_empty() { "Timer" } else { &self.label }
+    }
+}
+
+/// Pending timers, soonest first
+#[derive(Debug, Default)]
+pub struct Timers {
+    timers: Vec<Timer>,
+    next_id: u64,
+}
+
+impl Timers {
+    /// Start a timer running for `duration` from `now`
+    pub fn start(&mut self, duration: Duration, label: &str, now: Instant) -> u64 {
+        self.next_id += 1;
+        self.timers.push(Timer { id: self.next_id, label: label.to_string(), due: now + duration });
+        self.timers.sort_by_key(|timer| timer.due);
+        self.next_id
+    }
+
+    pub fn cancel(&mut self, id: u64) -> Option<Timer> {
+        let index = self.timers.iter().position(|timer| timer.id == id)?;
+        Some(self.timers.remove(index))
+    }
+
+    pub fn pending(&self) -> &[Timer] {
+        &self.timers
+    }
+
+    /// Remove and return the timers that are up at `now`
+    pub fn take_due(&mut self, now: Instant) -> Vec<Timer> {
+        let due = self.timers.iter().take_while(|timer| timer.due <= now).count();
+        self.timers.drain(..due).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+}
+
+/// Parse `90s`, `5m`, `1h30m` or a bare number of minutes
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim().to_lowercase();
+    if let Ok(minutes) = text.parse::<u64>() {
+        return (minutes > 0).then(|| Duration::from_secs(minutes * 60));
+    }
+    let mut total = 0;
+    let mut number = String::new();
+    for c in text.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'h' | 'm' | 's' if !number.is_empty() => {
+                let value: u64 = number.parse().ok()?;
+                total += value
+                    * match c {
+                        'h' => 3600,
+                        'm' => 60,
+                        _ => 1,
+                    };
+                number.clear();
+            }
+            _ => return None,
+        }
+    }
+    (number.is_empty() && total > 0).then(|| Duration::from_secs(total))
+}
+
+/// Time left as `m:ss`, or `h:mm:ss` from an hour up
+pub fn format_remaining(left: Duration) -> String {
+    // Round up so a timer never shows 0:00 while still pending
+    let secs = left.as_secs() + u64::from(left.subsec_nanos() > 0);
+    match secs / 3600 {
+        0 => format!("{}:{:02}", secs / 60, secs % 60),
+        hours => format!("{}:{:02}:{:02}", hours, secs % 3600 / 60, secs % 60),
+    }
+}
+
+/// Desktop notification that a timer is up (best effort)
+pub fn notify(timer: &Timer) {
+    let spawned = Command::new("notify-send")
+        .args(["--app-name=Ruty", "--urgency=critical", "⏰ Time's up", timer.title()])
+        .spawn();
+    if let Err(e) = spawned {
+        tracing::debug!("notify-send unavailable: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("25"), Some(Duration::from_secs(1500)));
+        assert_eq!(parse_duration("5M"), Some(Duration::from_secs(300)));
+        assert_eq!(parse_duration("0"), None);
+        assert_eq!(parse_duration("5x"), None);
+        assert_eq!(parse_duration("1h30"), None);
+        assert_eq!(parse_duration("tea"), None);
+    }
+
+    #[test]
+    fn test_timers() {
+        let start = Instant::now();
+        let mut timers = Timers::default();
+        let tea = timers.start(Duration::from_secs(300), "tea", start);
+        let pasta = timers.start(Duration::from_secs(60), "", start);
+        assert_eq!(timers.pending()[0].title(), "Timer");
+
+        let due = timers.take_due(start + Duration::from_secs(61));
+        assert_eq!(due.iter().map(|t| t.id).collect::<Vec<_>>(), vec![pasta]);
+        assert_eq!(timers.cancel(tea).map(|t| t.label), Some("tea".to_string()));
+        assert!(timers.is_empty());
+
+        assert_eq!(format_remaining(Duration::from_millis(59_500)), "1:00");
+        assert_eq!(format_remaining(Duration::from_secs(3723)), "1:02:03");
+    }
+}
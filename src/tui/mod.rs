@@ -0,0 +1,260 @@
+//! Ruty in a terminal
+//!
+//! A keyboard-only frontend for SSH sessions and tty-only setups. It talks
+//! to a running daemon over gRPC (start one with `ruty --headless`), so it
+//! searches the same indexes and clipboard history as the window.
+//!
+//! Usage:
+//!   ruty tui              - Search apps and files; Enter launches
+//!   ruty tui --clipboard  - Clipboard history; Enter copies the entry
+//!
+//! Tab switches between the two, Up/Down (or Ctrl+P/N) select, Ctrl+U clears
+//! the query and Esc quits. A picked clipboard entry goes to the terminal's
+//! clipboard (OSC 52, which reaches the local machine over SSH) and is
+//! printed once the screen is restored, so `$(ruty tui -c)` works too.
+
+mod term;
+
+use ratatui::layout::{Constraint, Layout, Position};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+use ruty_core::content::ContentKind;
+use ruty_core::transforms::Transform;
+use tokio::runtime::Handle;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Channel;
+use unicode_width::UnicodeWidthStr;
+
+use crate::rpc::proto::ruty_service_client::RutyServiceClient;
+use crate::rpc::proto::{ClipboardEntry, Empty, LaunchRequest, SearchItem, SearchRequest};
+use crate::rpc::Authorize;
+use term::{fit, Key, Terminal};
+
+const PROMPT: &str = "ruty › ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Search,
+    Clipboard,
+}
+
+/// What to do once the screen is restored
+enum Exit {
+    Quit,
+    Print(String),
+}
+
+struct Tui {
+    client: RutyServiceClient<InterceptedService<Channel, Authorize>>,
+    runtime: Handle,
+    mode: Mode,
+    query: String,
+    results: Vec<SearchItem>,
+    /// Clipboard history as fetched when the mode was entered
+    history: Vec<ClipboardEntry>,
+    /// Indices into `history` matching the query
+    matches: Vec<usize>,
+    selected: usize,
+    /// Last error, shown in place of the key hints
+    status: Option<String>,
+}
+
+/// `ruty tui [--clipboard]`
+pub fn run(runtime: Handle, args: &[String]) -> Result<(), String> {
+    let mode = match args {
+        [] => Mode::Search,
+        [flag] if flag == "-c" || flag == "--clipboard" => Mode::Clipboard,
+        _ => return Err("Usage: ruty tui [--clipboard]".to_string()),
+    };
+    let client = runtime
+        .block_on(crate::rpc::client::connect())
+        .map_err(|e| format!("{}; start the daemon with `ruty --headless`", e))?;
+
+    let mut tui = Tui {
+        client,
+        runtime,
+        mode: Mode::Search,
+        query: String::new(),
+        results: Vec::new(),
+        history: Vec::new(),
+        matches: Vec::new(),
+        selected: 0,
+        status: None,
+    };
+    tui.switch(mode);
+    // The terminal is restored before anything is printed
+    let exit = Terminal::open().and_then(|mut terminal| tui.run(&mut terminal))?;
+    if let Exit::Print(text) = exit {
+        println!("{}", text);
+    }
+    Ok(())
+}
+
+impl Tui {
+    fn run(&mut self, terminal: &mut Terminal) -> Result<Exit, String> {
+        loop {
+            terminal.draw(|frame| self.view(frame))?;
+            if let Some(key) = terminal.read_key()? {
+                match key {
+                    Key::Escape | Key::Interrupt => return Ok(Exit::Quit),
+                    Key::Enter => {
+                        if let Some(exit) = self.pick(terminal)? {
+                            return Ok(exit);
+                        }
+                    }
+                    Key::Tab => self.switch(match self.mode {
+                        Mode::Search => Mode::Clipboard,
+                        Mode::Clipboard => Mode::Search,
+                    }),
+                    Key::Up => self.selected = self.selected.saturating_sub(1),
+                    Key::Down => self.selected = (self.selected + 1).min(self.len().saturating_sub(1)),
+                    Key::Backspace => {
+                        self.query.pop();
+                        self.refresh();
+                    }
+                    Key::ClearLine => {
+                        self.query.clear();
+                        self.refresh();
+                    }
+                    Key::Char(c) => {
+                        self.query.push(c);
+                        self.refresh();
+                    }
+                }
+            }
+        }
+    }
+
+    fn switch(&mut self, mode: Mode) {
+        self.mode = mode;
+        if mode == Mode::Clipboard {
+            self.history = match self.runtime.block_on(self.client.get_clipboard_history(Empty {})) {
+                Ok(history) => history.into_inner().items,
+                Err(e) => {
+                    self.status = Some(format!("Clipboard history unavailable: {}", e.message()));
+                    Vec::new()
+                }
+            };
+        }
+        self.refresh();
+    }
+
+    /// Match the query again
+    fn refresh(&mut self) {
+        self.selected = 0;
+        match self.mode {
+            Mode::Search if self.query.trim().is_empty() => self.results.clear(),
+            Mode::Search => {
+                let request = SearchRequest { query: self.query.clone(), limit: 0 };
+                match self.runtime.block_on(self.client.search(request)) {
+                    Ok(response) => {
+                        self.results = response.into_inner().results;
+                        self.status = None;
+                    }
+                    Err(e) => self.status = Some(format!("Search failed: {}", e.message())),
+                }
+            }
+            Mode::Clipboard => {
+                let query = self.query.to_lowercase();
+                self.matches = (0..self.history.len())
+                    .filter(|&i| self.history[i].content.to_lowercase().contains(&query))
+                    .collect();
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self.mode {
+            Mode::Search => self.results.len(),
+            Mode::Clipboard => self.matches.len(),
+        }
+    }
+
+    /// Enter: launch the result or copy the entry (`None` keeps running)
+    fn pick(&mut self, terminal: &mut Terminal) -> Result<Option<Exit>, String> {
+        match self.mode {
+            Mode::Search => {
+                let Some(item) = self.results.get(self.selected) else {
+                    return Ok(None);
+                };
+                let request = LaunchRequest { id: item.id.clone(), category: item.category.clone() };
+                match self.runtime.block_on(self.client.launch(request)) {
+                    Ok(_) => Ok(Some(Exit::Quit)),
+                    Err(e) => {
+                        self.status = Some(format!("Couldn't open {}: {}", item.title, e.message()));
+                        Ok(None)
+                    }
+                }
+            }
+            Mode::Clipboard => {
+                let Some(entry) = self.matches.get(self.selected).map(|&i| &self.history[i]) else {
+                    return Ok(None);
+                };
+                let encoded = Transform::Base64Encode.apply(&entry.content)?;
+                terminal.write(&format!("\x1b]52;c;{}\x07", encoded))?;
+                Ok(Some(Exit::Print(entry.content.clone())))
+            }
+        }
+    }
+
+    fn view(&self, frame: &mut Frame) {
+        let (mode, hints) = match self.mode {
+            Mode::Search => ("Search", "↑↓ select · Enter open · Tab clipboard · Esc quit"),
+            Mode::Clipboard => ("Clipboard", "↑↓ select · Enter copy · Tab search · Esc quit"),
+        };
+        let [prompt, rule, body, status] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        frame.render_widget(Line::from(vec![PROMPT.bold(), Span::raw(self.query.as_str())]), prompt);
+        let cursor = (PROMPT.width() + self.query.width()).min(usize::from(prompt.width.saturating_sub(1)));
+        frame.set_cursor_position(Position::new(prompt.x + cursor as u16, prompt.y));
+        frame.render_widget(Block::new().borders(Borders::TOP).title(format!("─ {} ", mode)).dim(), rule);
+
+        let width = usize::from(body.width);
+        let items: Vec<ListItem> = match self.mode {
+            Mode::Search => self
+                .results
+                .iter()
+                .map(|item| {
+                    let title = fit(&format!(" {}  ", item.title), width);
+                    let subtitle = fit(&item.subtitle, width.saturating_sub(title.width()));
+                    ListItem::new(Line::from(vec![Span::raw(title), subtitle.dim()]))
+                })
+                .collect(),
+            Mode::Clipboard => self
+                .matches
+                .iter()
+                .map(|&i| {
+                    let entry = &self.history[i];
+                    let badge = fit(&format!(" {:<5} ", ContentKind::parse(&entry.kind).unwrap_or_default().label()), width);
+                    let content = fit(&entry.content, width.saturating_sub(badge.width()));
+                    ListItem::new(Line::from(vec![badge.dim(), Span::raw(content)]))
+                })
+                .collect(),
+        };
+        if items.is_empty() {
+            if !(self.mode == Mode::Search && self.query.trim().is_empty()) {
+                frame.render_widget(Paragraph::new(" No matches").dim(), body);
+            }
+        } else {
+            // The list scrolls to keep the selection in view
+            let mut state = ListState::default().with_selected(Some(self.selected));
+            frame.render_stateful_widget(List::new(items).highlight_style(Style::new().reversed()), body, &mut state);
+        }
+
+        frame.render_widget(
+            match &self.status {
+                Some(status) => Paragraph::new(status.as_str()).bold(),
+                None => Paragraph::new(hints).dim(),
+            },
+            status,
+        );
+    }
+}
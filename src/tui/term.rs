@@ -0,0 +1,165 @@
+//! Terminal handling for the TUI
+//!
+//! ratatui draws on /dev/tty through crossterm rather than on stdout, so the
+//! screen works while stdout is piped (`$(ruty tui --clipboard)`); crossterm
+//! takes raw mode and input from the controlling terminal too.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::Frame;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Backspace,
+    Enter,
+    Tab,
+    Up,
+    Down,
+    Escape,
+    /// Ctrl+C
+    Interrupt,
+    /// Ctrl+U
+    ClearLine,
+}
+
+/// The controlling terminal in raw mode on the alternate screen; restored
+/// on drop
+pub struct Terminal {
+    terminal: ratatui::Terminal<CrosstermBackend<File>>,
+}
+
+impl Terminal {
+    pub fn open() -> Result<Self, String> {
+        let tty = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/tty")
+            .map_err(|e| format!("No terminal: {}", e))?;
+        let terminal = ratatui::Terminal::new(CrosstermBackend::new(tty))
+            .map_err(|e| format!("Cannot use the terminal: {}", e))?;
+        // From here on, dropping it puts the terminal back
+        let mut terminal = Self { terminal };
+        terminal::enable_raw_mode().map_err(|e| format!("Cannot use the terminal: {}", e))?;
+        execute!(terminal.terminal.backend_mut(), EnterAlternateScreen)
+            .map_err(|e| format!("Cannot use the terminal: {}", e))?;
+        terminal.terminal.clear().map_err(|e| format!("Cannot draw: {}", e))?;
+        Ok(terminal)
+    }
+
+    /// Redraw the screen with `view`
+    pub fn draw(&mut self, view: impl FnOnce(&mut Frame)) -> Result<(), String> {
+        self.terminal.draw(view).map(|_| ()).map_err(|e| format!("Cannot draw: {}", e))
+    }
+
+    /// Wait for input; `None` for anything that isn't one of our keys (a
+    /// resize, a key release), after which the caller redraws
+    pub fn read_key(&mut self) -> Result<Option<Key>, String> {
+        match event::read().map_err(|e| format!("Cannot read the terminal: {}", e))? {
+            Event::Key(event) => Ok(key(event)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Send raw output, such as an OSC 52 clipboard request
+    pub fn write(&mut self, text: &str) -> Result<(), String> {
+        let tty = self.terminal.backend_mut();
+        tty.write_all(text.as_bytes())
+            .and_then(|()| tty.flush())
+            .map_err(|e| format!("Cannot write to the terminal: {}", e))
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
+        let _ = self.terminal.show_cursor();
+        if let Err(e) = terminal::disable_raw_mode() {
+            eprintln!("Couldn't restore the terminal ({}); run `reset`", e);
+        }
+    }
+}
+
+/// The key a key press means here
+pub fn key(event: KeyEvent) -> Option<Key> {
+    if event.kind == KeyEventKind::Release {
+        return None;
+    }
+    let ctrl = event.modifiers.contains(KeyModifiers::CONTROL);
+    Some(match event.code {
+        KeyCode::Char('c') if ctrl => Key::Interrupt,
+        KeyCode::Char('u') if ctrl => Key::ClearLine,
+        KeyCode::Char('p') if ctrl => Key::Up,
+        KeyCode::Char('n') if ctrl => Key::Down,
+        KeyCode::Char(_) if ctrl => return None,
+        KeyCode::Char(c) => Key::Char(c),
+        KeyCode::Enter => Key::Enter,
+        KeyCode::Tab => Key::Tab,
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::Up => Key::Up,
+        KeyCode::Down => Key::Down,
+        KeyCode::Esc => Key::Escape,
+        _ => return None,
+    })
+}
+
+/// `text` on one line, cut to `width` columns with an ellipsis. Columns are
+/// display width, so CJK and most emoji count two.
+pub fn fit(text: &str, width: usize) -> String {
+    let flat: String = text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect();
+    if flat.width() <= width {
+        return flat;
+    }
+    let mut cut = String::new();
+    let mut used = 0;
+    for c in flat.chars() {
+        let c_width = c.width().unwrap_or(0);
+        if used + c_width > width.saturating_sub(1) {
+            break;
+        }
+        used += c_width;
+        cut.push(c);
+    }
+    if width > 0 {
+        cut.push('…');
+    }
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key() {
+        let press = |code, modifiers| key(KeyEvent::new(code, modifiers));
+        assert_eq!(press(KeyCode::Char('f'), KeyModifiers::NONE), Some(Key::Char('f')));
+        assert_eq!(press(KeyCode::Char('F'), KeyModifiers::SHIFT), Some(Key::Char('F')));
+        assert_eq!(press(KeyCode::Char('p'), KeyModifiers::CONTROL), Some(Key::Up));
+        assert_eq!(press(KeyCode::Char('c'), KeyModifiers::CONTROL), Some(Key::Interrupt));
+        assert_eq!(press(KeyCode::Char('x'), KeyModifiers::CONTROL), None);
+        assert_eq!(press(KeyCode::F(5), KeyModifiers::NONE), None);
+
+        let mut release = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        release.kind = KeyEventKind::Release;
+        assert_eq!(key(release), None);
+    }
+
+    #[test]
+    fn test_fit() {
+        assert_eq!(fit("Fixture Editor", 20), "Fixture Editor");
+        assert_eq!(fit("Fixture Editor", 8), "Fixture…");
+        assert_eq!(fit("line one\nline two", 40), "line one line two");
+        assert_eq!(fit("abc", 0), "");
+        // Wide characters take two columns each
+        assert_eq!(fit("漢字テスト", 10), "漢字テスト");
+        assert_eq!(fit("漢字テスト", 7), "漢字テ…");
+        assert_eq!(fit("漢字テスト", 6), "漢字…");
+    }
+}
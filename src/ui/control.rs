@@ -0,0 +1,27 @@
+//! Interactive result widgets
+//!
+//! Result rows that adjust in place instead of launching something.
+
+use iced::widget::{row, slider, text};
+use iced::{Color, Element, Length};
+
+use super::fonts;
+
+/// Slider with a percentage readout, for level controls (volume, brightness).
+/// `on_change` fires while dragging; `on_release` once the drag ends.
+pub fn level<'a, Message: Clone + 'a>(
+    percent: u8,
+    on_change: impl Fn(u8) -> Message + 'a,
+    on_release: Message,
+    readout_color: Color,
+) -> Element<'a, Message> {
+    row![
+        slider(0..=100u8, percent, on_change)
+            .on_release(on_release)
+            .width(Length::Fixed(180.0)),
+        text(format!("{:>3}%", percent)).size(fonts::size(12)).color(readout_color),
+    ]
+    .spacing(10)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
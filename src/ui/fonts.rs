@@ -0,0 +1,81 @@
+//! Fonts and text sizes
+//!
+//! `install` runs before iced draws anything: it picks the UI and
+//! monospace families (see `ruty_core::fonts`) and reorders the font
+//! database so the fallback chain comes first. The text renderer falls back
+//! through every installed font for characters the UI font lacks, in
+//! database order, so without this CJK and emoji came out in whichever
+//! font happened to be loaded first, or as boxes. `size` scales text sizes
+//! by `[fonts] size`.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use iced::advanced::graphics::text::{cosmic_text, font_system};
+use ruty_core::config::FontsConfig;
+use ruty_core::fonts;
+
+use cosmic_text::fontdb::{Database, FaceInfo, Family};
+
+/// `[fonts] size`, as f32 bits
+static SIZE_FACTOR: AtomicU32 = AtomicU32::new(0x3f80_0000);
+
+/// A text size from the views, scaled by `[fonts] size`
+pub fn size(base: u16) -> f32 {
+    base as f32 * f32::from_bits(SIZE_FACTOR.load(Ordering::Relaxed))
+}
+
+/// Apply `[fonts]`; must run before the first frame
+pub fn install(config: &FontsConfig) {
+    SIZE_FACTOR.store(config.size_factor().to_bits(), Ordering::Relaxed);
+
+    let mut system = font_system().write().expect("Font system poisoned");
+    let raw = system.raw();
+    let db = raw.db();
+    let pick = |configured: &str, generic: &str| {
+        if !configured.is_empty() {
+            if has_family(db, configured) {
+                return Some(configured.to_string());
+            }
+            tracing::warn!("Font '{}' isn't installed, using the desktop's {}", configured, generic);
+        }
+        fonts::system_family(generic).filter(|family| has_family(db, family))
+    };
+    let sans = pick(&config.family, "sans-serif");
+    let monospace = pick(&config.monospace, "monospace");
+
+    let chain = fonts::chain(config, sans.as_deref().unwrap_or_default());
+    let rank = |face: &FaceInfo| {
+        chain.iter().position(|family| is_family(face, family)).unwrap_or(chain.len())
+    };
+    let mut faces: Vec<FaceInfo> = db.faces().cloned().collect();
+    faces.sort_by_key(rank);
+
+    let mut ordered = Database::new();
+    for face in faces {
+        ordered.push_face_info(face);
+    }
+    ordered.set_sans_serif_family(sans.as_deref().unwrap_or(db.family_name(&Family::SansSerif)));
+    ordered.set_monospace_family(monospace.as_deref().unwrap_or(db.family_name(&Family::Monospace)));
+    ordered.set_serif_family(db.family_name(&Family::Serif));
+
+    let missing = fonts::missing_coverage(|family| has_family(&ordered, family));
+    if !missing.is_empty() {
+        tracing::info!("No {} font installed; such characters may not render", missing.join(" or "));
+    }
+    tracing::info!(
+        "UI font: {} (monospace: {})",
+        ordered.family_name(&Family::SansSerif),
+        ordered.family_name(&Family::Monospace)
+    );
+
+    let locale = raw.locale().to_string();
+    *raw = cosmic_text::FontSystem::new_with_locale_and_db(locale, ordered);
+}
+
+fn is_family(face: &FaceInfo, family: &str) -> bool {
+    face.families.iter().any(|(name, _)| name.eq_ignore_ascii_case(family))
+}
+
+fn has_family(db: &Database, family: &str) -> bool {
+    db.faces().any(|face| is_family(face, family))
+}
@@ -0,0 +1,62 @@
+//! Query highlighting in result rows
+//!
+//! Shows why a result ranked: the characters the fuzzy matcher matched are
+//! drawn in the accent color.
+
+use iced::widget::{rich_text, span, text};
+use iced::{Color, Element, Font};
+use ruty_core::fuzzy;
+
+use super::fonts;
+
+/// Split `content` into runs of (text, matched) for the chars `query` matches
+pub fn segments<'a>(content: &'a str, query: &str) -> Vec<(&'a str, bool)> {
+    let matched = fuzzy::matched_chars(content, &query.trim().to_lowercase());
+    let mut segments: Vec<(&str, bool)> = Vec::new();
+    let mut start = 0;
+    let mut current = false;
+    for (i, (offset, _)) in content.char_indices().enumerate() {
+        let is_match = matched.binary_search(&i).is_ok();
+        if is_match != current && offset > start {
+            segments.push((&content[start..offset], current));
+            start = offset;
+        }
+        current = is_match;
+    }
+    if start < content.len() {
+        segments.push((&content[start..], current));
+    }
+    segments
+}
+
+/// `content` as text of `size`, with the chars matched by `query` in `accent`
+pub fn highlighted<'a, Message: Clone + 'static>(
+    content: &'a str,
+    query: &str,
+    size: u16,
+    color: Color,
+    accent: Color,
+) -> Element<'a, Message> {
+    let segments = segments(content, query);
+    if !segments.iter().any(|(_, matched)| *matched) {
+        return text(content).size(fonts::size(size)).color(color).into();
+    }
+    let spans: Vec<text::Span<'a, Message, Font>> = segments
+        .into_iter()
+        .map(|(piece, matched)| span(piece).color(if matched { accent } else { color }))
+        .collect();
+    rich_text(spans).size(fonts::size(size)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segments() {
+        assert_eq!(segments("Firefox", "ffx"), vec![("F", true), ("ire", false), ("f", true), ("o", false), ("x", true)]);
+        assert_eq!(segments("LibreOffice Writer", "Writer "), vec![("LibreOffice ", false), ("Writer", true)]);
+        assert_eq!(segments("Café", "fé"), vec![("Ca", false), ("fé", true)]);
+        assert_eq!(segments("Firefox", "zzz"), vec![("Firefox", false)]);
+    }
+}
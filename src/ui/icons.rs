@@ -0,0 +1,133 @@
+//! Result icons
+//!
+//! A row shows its own icon (an app's from the system icon theme or
+//! `[icons] apps`, a quicklink's from `[icons] quicklinks`), else the one
+//! `[icons] types` sets for its type, else a bundled vector icon. The bundled
+//! set is drawn in the accent color, so rows look alike even without an icon
+//! theme installed.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use iced::widget::{image, svg, text};
+use iced::{Color, Element};
+use ruty_core::config;
+
+use super::fonts;
+
+/// Stroke-drawn 24x24 icons, by result type as `ResultCategory::as_str`
+/// names them
+const BUNDLED: &[(&str, &str)] = &[
+    ("app", r#"<rect x="3" y="3" width="7" height="7" rx="1.5"/><rect x="14" y="3" width="7" height="7" rx="1.5"/><rect x="3" y="14" width="7" height="7" rx="1.5"/><rect x="14" y="14" width="7" height="7" rx="1.5"/>"#),
+    ("file", r#"<path d="M14 3H6a2 2 0 0 0-2 2v14a2 2 0 0 0 2 2h12a2 2 0 0 0 2-2V9z"/><path d="M14 3v6h6"/>"#),
+    ("command", r#"<path d="M4 17l6-6-6-6"/><path d="M12 19h8"/>"#),
+    ("ai", r#"<path d="M12 3l2 5 5 2-5 2-2 5-2-5-5-2 5-2z"/><path d="M19 16v4M17 18h4"/>"#),
+    ("clipboard", r#"<rect x="8" y="2" width="8" height="4" rx="1"/><path d="M16 4h2a2 2 0 0 1 2 2v14a2 2 0 0 1-2 2H6a2 2 0 0 1-2-2V6a2 2 0 0 1 2-2h2"/>"#),
+    ("history", r#"<path d="M3 12a9 9 0 1 0 3-6.7L3 8"/><path d="M3 3v5h5"/><path d="M12 7v5l3 3"/>"#),
+    ("action", r#"<path d="M4 21v-7M4 10V3M12 21v-9M12 8V3M20 21v-5M20 12V3M1 14h6M9 8h6M17 16h6"/>"#),
+    ("git", r#"<circle cx="6" cy="6" r="2"/><circle cx="6" cy="18" r="2"/><circle cx="18" cy="8" r="2"/><path d="M6 8v8M18 10c0 4-6 4-10 7"/>"#),
+    ("network", r#"<path d="M2 9a15 15 0 0 1 20 0M5 12.5a10 10 0 0 1 14 0M8.5 16a5 5 0 0 1 7 0"/><path d="M12 20h.01"/>"#),
+    ("device", r#"<path d="M3 18v-6a9 9 0 0 1 18 0v6"/><path d="M21 19a2 2 0 0 1-2 2h-1v-6h3zM3 19a2 2 0 0 0 2 2h1v-6H3z"/>"#),
+    ("control", r#"<circle cx="12" cy="12" r="9"/><path d="M12 3v18M12 8h5M12 12h7M12 16h5"/>"#),
+    ("open_with", r#"<path d="M18 13v6a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V8a2 2 0 0 1 2-2h6"/><path d="M15 3h6v6M10 14L21 3"/>"#),
+    ("archive", r#"<rect x="2" y="3" width="20" height="5" rx="1"/><path d="M4 8v11a2 2 0 0 0 2 2h12a2 2 0 0 0 2-2V8M10 12h4"/>"#),
+    ("hash", r#"<path d="M4 9h16M4 15h16M10 3L8 21M16 3l-2 18"/>"#),
+    ("transform", r#"<path d="M17 3l4 4-4 4M3 7h18M7 21l-4-4 4-4M21 17H3"/>"#),
+    ("memory", r#"<path d="M12 2L2 7l10 5 10-5z"/><path d="M2 17l10 5 10-5M2 12l10 5 10-5"/>"#),
+    ("favorite", r#"<path d="M12 2l3.1 6.3 6.9 1-5 4.9 1.2 6.8-6.2-3.2-6.2 3.2 1.2-6.8-5-4.9 6.9-1z"/>"#),
+    ("timer", r#"<circle cx="12" cy="13" r="8"/><path d="M12 9v4l2 2M9 2h6"/>"#),
+    ("contact", r#"<circle cx="12" cy="8" r="4"/><path d="M4 21a8 8 0 0 1 16 0"/>"#),
+    ("event", r#"<rect x="3" y="4" width="18" height="18" rx="2"/><path d="M16 2v4M8 2v4M3 10h18"/>"#),
+    ("calculator", r#"<rect x="4" y="2" width="16" height="20" rx="2"/><path d="M8 6h8M8 10h8M8 14h.01M12 14h.01M16 14h.01M8 18h.01M12 18h.01M16 18h.01"/>"#),
+    ("saved", r#"<circle cx="11" cy="11" r="7"/><path d="M21 21l-4.3-4.3"/>"#),
+    ("bookmark", r#"<path d="M19 21l-7-5-7 5V5a2 2 0 0 1 2-2h10a2 2 0 0 1 2 2z"/>"#),
+];
+
+/// Drawn for a type without a bundled icon
+const FALLBACK: &str = r#"<circle cx="12" cy="12" r="4"/>"#;
+
+/// Handles are made once, so iced rasterizes each icon once per size
+static HANDLES: LazyLock<BTreeMap<&'static str, svg::Handle>> = LazyLock::new(|| {
+    BUNDLED
+        .iter()
+        .chain([("", FALLBACK)].iter())
+        .map(|(kind, body)| (*kind, svg::Handle::from_memory(document(body).into_bytes())))
+        .collect()
+});
+
+fn document(body: &str) -> String {
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="none" stroke="black" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">{}</svg>"#,
+        body
+    )
+}
+
+/// How an icon value is drawn
+#[derive(Debug, PartialEq)]
+enum Source {
+    Svg(PathBuf),
+    Image(PathBuf),
+    /// An emoji or a few characters
+    Text(String),
+}
+
+/// A value with a path separator or an image extension is a file;
+/// anything else is drawn as text
+fn source(value: &str) -> Source {
+    let value = value.trim();
+    let extension = value.rsplit_once('.').map(|(_, extension)| extension.to_ascii_lowercase());
+    let is_image = extension
+        .as_deref()
+        .is_some_and(|extension| matches!(extension, "svg" | "svgz" | "png" | "jpg" | "jpeg" | "xpm" | "ico" | "gif" | "webp"));
+    if !value.contains('/') && !is_image {
+        return Source::Text(value.to_string());
+    }
+    let path = config::expand_home(value);
+    match extension.as_deref() {
+        Some("svg" | "svgz") => Source::Svg(path),
+        _ => Source::Image(path),
+    }
+}
+
+/// Icon of a row of type `kind`: its own `icon` if it has one, else the
+/// `types` override for the type, else the bundled one in `color`
+pub fn view<'a, Message: 'a>(
+    icon: Option<&str>,
+    kind: &str,
+    types: &BTreeMap<String, String>,
+    size: u16,
+    color: Color,
+) -> Element<'a, Message> {
+    let value = icon.or_else(|| types.get(kind).map(String::as_str));
+    match value.map(source) {
+        Some(Source::Svg(path)) => svg(svg::Handle::from_path(path)).width(size).height(size).into(),
+        Some(Source::Image(path)) => image(path).width(size).height(size).into(),
+        Some(Source::Text(symbol)) => text(symbol).size(fonts::size(size * 5 / 6)).color(color).into(),
+        None => {
+            let handle = HANDLES.get(kind).or_else(|| HANDLES.get("")).cloned().expect("fallback icon");
+            svg(handle)
+                .width(size)
+                .height(size)
+                .style(move |_theme, _status| svg::Style { color: Some(color) })
+                .into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source() {
+        assert_eq!(source("📄"), Source::Text("📄".into()));
+        assert_eq!(source(" >_ "), Source::Text(">_".into()));
+        assert_eq!(source("/usr/share/icons/firefox.PNG"), Source::Image("/usr/share/icons/firefox.PNG".into()));
+        assert_eq!(source("/opt/app/icon"), Source::Image("/opt/app/icon".into()));
+        assert!(matches!(source("~/.icons/gh.svg"), Source::Svg(path) if path.ends_with(".icons/gh.svg") && path.is_absolute()));
+        assert_eq!(source("logo.svg"), Source::Svg("logo.svg".into()));
+        // No type is bundled twice
+        assert_eq!(HANDLES.len(), BUNDLED.len() + 1);
+    }
+}
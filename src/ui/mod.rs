@@ -5,4 +5,10 @@
 pub mod search_bar;
 pub mod results_list;
 pub mod chat_view;
+pub mod control;
+pub mod fonts;
+pub mod highlight;
+pub mod icons;
 pub mod theme;
+pub mod toast;
+pub mod waveform;
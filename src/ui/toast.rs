@@ -0,0 +1,167 @@
+//! Toast notifications
+//!
+//! Short confirmations ("Copied path ✓") float over the bottom of the window
+//! and dismiss themselves, so quick actions give feedback without replacing
+//! the results or the chat. A toast can carry an action button, which is how
+//! destructive actions offer "Undo" for a while.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use iced::widget::{button, column, container, row, text};
+use iced::{Background, Border, Color, Element, Length, Padding};
+
+use super::fonts;
+
+/// How long a toast stays up
+pub const TOAST_DURATION: Duration = Duration::from_millis(1800);
+
+/// Toasts shown at once; older ones are dropped
+const MAX_TOASTS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Toast<Message> {
+    text: String,
+    /// Button label and the message it sends
+    action: Option<(String, Message)>,
+    until: Instant,
+}
+
+/// Queue of visible toasts, oldest first
+#[derive(Debug, Clone)]
+pub struct Toasts<Message> {
+    queue: VecDeque<Toast<Message>>,
+}
+
+impl<Message> Default for Toasts<Message> {
+    fn default() -> Self {
+        Self { queue: VecDeque::new() }
+    }
+}
+
+impl<Message: Clone> Toasts<Message> {
+    /// Show `text` for `TOAST_DURATION` from `now`
+    pub fn push(&mut self, text: impl Into<String>, now: Instant) {
+        self.insert(Toast { text: text.into(), action: None, until: now + TOAST_DURATION });
+    }
+
+    /// Show `text` with a button sending `message`, for `lasts` from `now`
+    pub fn push_action(
+        &mut self,
+        text: impl Into<String>,
+        label: impl Into<String>,
+        message: Message,
+        lasts: Duration,
+        now: Instant,
+    ) {
+        self.insert(Toast { text: text.into(), action: Some((label.into(), message)), until: now + lasts });
+    }
+
+    fn insert(&mut self, toast: Toast<Message>) {
+        // Repeating an action refreshes its toast instead of stacking copies
+        self.queue.retain(|queued| queued.text != toast.text);
+        self.queue.push_back(toast);
+        while self.queue.len() > MAX_TOASTS {
+            self.queue.pop_front();
+        }
+    }
+
+    /// Drop toasts whose button would send a message matching `sends`
+    pub fn dismiss(&mut self, sends: impl Fn(&Message) -> bool) {
+        self.queue
+            .retain(|toast| !toast.action.as_ref().is_some_and(|(_, message)| sends(message)));
+    }
+
+    /// Drop toasts whose time is up
+    pub fn expire(&mut self, now: Instant) {
+        self.queue.retain(|toast| toast.until > now);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// The toasts as a column of pills, bottom-centered in the space it's given
+    pub fn view<'a>(&'a self, background: Color, color: Color) -> Element<'a, Message>
+    where
+        Message: 'a,
+    {
+        let pills = self.queue.iter().map(|toast| {
+            let label = text(&toast.text).size(fonts::size(13)).color(color);
+            let content: Element<'a, Message> = match &toast.action {
+                Some((action, message)) => row![
+                    label,
+                    button(text(action).size(fonts::size(13)).color(color))
+                        .padding(Padding::from([0.0, 4.0]))
+                        .style(move |_theme, status| button::Style {
+                            text_color: color,
+                            border: Border::default().rounded(6).color(color).width(
+                                if status == button::Status::Hovered { 1.0 } else { 0.0 }
+                            ),
+                            ..button::Style::default()
+                        })
+                        .on_press(message.clone()),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+                .into(),
+                None => label.into(),
+            };
+            container(content)
+                .padding(Padding::from([6.0, 14.0]))
+                .style(move |_theme| container::Style {
+                    background: Some(Background::Color(background)),
+                    border: Border::default().rounded(14),
+                    ..Default::default()
+                })
+                .into()
+        });
+        container(column(pills).spacing(6).align_x(iced::Alignment::Center))
+            .padding(Padding { bottom: 20.0, ..Padding::ZERO })
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_bottom(Length::Fill)
+            .center_x(Length::Fill)
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue() {
+        let start = Instant::now();
+        let mut toasts: Toasts<()> = Toasts::default();
+        toasts.push("Copied path ✓", start);
+        toasts.push("Copied 12 chars ✓", start + Duration::from_millis(500));
+        toasts.push("Copied path ✓", start + Duration::from_millis(600));
+        let texts: Vec<&str> = toasts.queue.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["Copied 12 chars ✓", "Copied path ✓"]);
+
+        toasts.expire(start + TOAST_DURATION + Duration::from_millis(550));
+        assert_eq!(toasts.queue.len(), 1);
+        toasts.expire(start + TOAST_DURATION * 2);
+        assert!(toasts.is_empty());
+
+        for i in 0..5 {
+            toasts.push(format!("toast {}", i), start);
+        }
+        assert_eq!(toasts.queue.front().map(|t| t.text.as_str()), Some("toast 2"));
+    }
+
+    #[test]
+    fn test_action_toasts() {
+        let start = Instant::now();
+        let mut toasts: Toasts<u64> = Toasts::default();
+        toasts.push_action("Moved to trash", "Undo", 7, Duration::from_secs(10), start);
+        toasts.push("Copied path ✓", start);
+        // Outlives plain toasts
+        toasts.expire(start + TOAST_DURATION * 2);
+        assert_eq!(toasts.queue.len(), 1);
+
+        toasts.dismiss(|id| *id == 7);
+        assert!(toasts.is_empty());
+    }
+}
@@ -0,0 +1,30 @@
+//! Recording level indicator
+//!
+//! A row of bars, one per recent stretch of microphone audio, shown while
+//! voice input records.
+
+use iced::widget::{container, row, Space};
+use iced::{Background, Border, Color, Element, Length};
+
+/// Height of a silent bar and of a full-scale one
+const MIN_HEIGHT: f32 = 2.0;
+const MAX_HEIGHT: f32 = 22.0;
+
+/// Bars for `levels` (0.0 to 1.0), oldest on the left
+pub fn waveform<'a, Message: 'a>(levels: &[f32], color: Color) -> Element<'a, Message> {
+    let bars = levels.iter().map(|level| {
+        let height = MIN_HEIGHT + level.clamp(0.0, 1.0) * (MAX_HEIGHT - MIN_HEIGHT);
+        container(Space::new(Length::Fixed(3.0), Length::Fixed(height)))
+            .style(move |_| container::Style {
+                background: Some(Background::Color(color)),
+                border: Border { radius: 1.5.into(), ..Default::default() },
+                ..Default::default()
+            })
+            .into()
+    });
+    row(bars)
+        .spacing(2)
+        .height(Length::Fixed(MAX_HEIGHT))
+        .align_y(iced::Alignment::Center)
+        .into()
+}
@@ -0,0 +1,101 @@
+//! Undo for destructive actions
+//!
+//! Deleting a clipboard entry, trashing a file, removing an alias, a saved
+//! search or a bookmark, or revoking a permission records the operation that
+//! reverses it. The inverse
+//! stays on the stack for `UNDO_WINDOW`, as long as the toast offering
+//! "Undo" is up; after that the action is final.
+
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+use ruty_core::clipboard::ClipboardItem;
+use ruty_core::config::{Bookmark, SavedSearch};
+use ruty_core::permissions::Capability;
+use ruty_core::trash::Trashed;
+
+/// How long an action can be undone
+pub const UNDO_WINDOW: Duration = Duration::from_secs(10);
+
+/// The operation that reverses an action
+#[derive(Debug, Clone, PartialEq)]
+pub enum Inverse {
+    /// Put clipboard history entries back
+    Clipboard(Vec<ClipboardItem>),
+    /// Move a file back out of the trash
+    File(Trashed),
+    /// Re-add a removed alias
+    Alias { name: String, expansion: String },
+    /// Re-add a removed saved search
+    SavedSearch { name: String, search: SavedSearch },
+    /// Put a removed bookmark back where it was
+    Bookmark { index: usize, bookmark: Bookmark },
+    /// Grant a provider what was revoked from it
+    Permission { provider: String, capabilities: BTreeSet<Capability> },
+}
+
+#[derive(Debug)]
+struct Entry {
+    id: u64,
+    inverse: Inverse,
+    until: Instant,
+}
+
+/// Inverses of recent actions, newest last
+#[derive(Debug, Default)]
+pub struct UndoStack {
+    entries: Vec<Entry>,
+    next_id: u64,
+}
+
+impl UndoStack {
+    /// Record how to undo an action taken at `now`; the id is what the
+    /// toast's Undo button sends back
+    pub fn push(&mut self, inverse: Inverse, now: Instant) -> u64 {
+        self.next_id += 1;
+        self.entries.push(Entry { id: self.next_id, inverse, until: now + UNDO_WINDOW });
+        self.next_id
+    }
+
+    /// Take the inverse of action `id`, unless its window has passed
+    pub fn take(&mut self, id: u64, now: Instant) -> Option<Inverse> {
+        let index = self.entries.iter().position(|entry| entry.id == id)?;
+        let entry = self.entries.remove(index);
+        (entry.until > now).then_some(entry.inverse)
+    }
+
+    /// Forget actions that can no longer be undone
+    pub fn expire(&mut self, now: Instant) {
+        self.entries.retain(|entry| entry.until > now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alias(name: &str) -> Inverse {
+        Inverse::Alias { name: name.to_string(), expansion: "echo".to_string() }
+    }
+
+    #[test]
+    fn test_undo_window() {
+        let start = Instant::now();
+        let mut stack = UndoStack::default();
+        let first = stack.push(alias("a"), start);
+        let second = stack.push(alias("b"), start + Duration::from_secs(5));
+        assert_ne!(first, second);
+
+        // Undoing is one-shot
+        assert_eq!(stack.take(second, start + Duration::from_secs(6)), Some(alias("b")));
+        assert_eq!(stack.take(second, start + Duration::from_secs(6)), None);
+
+        // Too late for the first action
+        assert_eq!(stack.take(first, start + UNDO_WINDOW), None);
+        assert!(stack.entries.is_empty());
+
+        stack.push(alias("c"), start);
+        stack.expire(start + UNDO_WINDOW + Duration::from_secs(1));
+        assert!(stack.entries.is_empty());
+    }
+}
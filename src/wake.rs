@@ -0,0 +1,29 @@
+//! Wakeups for the UI loop
+//!
+//! Hotkey threads and the signal handler record what they want in the
+//! `hotkey` flags and then call `wake()`; the app checks the flags when the
+//! subscription delivers the wakeup, so it sits fully idle in between
+//! instead of polling. (Window commands from RPC have a channel of their
+//! own, see `window_commands`.)
+
+use iced::Subscription;
+use iced::futures::stream;
+use tokio::sync::Notify;
+
+static WAKE: Notify = Notify::const_new();
+
+/// Ask the UI to check the shared flags. Wakeups sent while the UI is busy
+/// coalesce into one.
+pub fn wake() {
+    WAKE.notify_one();
+}
+
+/// Wait for the next (coalesced) `wake()`
+pub async fn woken() {
+    WAKE.notified().await;
+}
+
+/// Fires once per (coalesced) `wake()`
+pub fn subscription() -> Subscription<()> {
+    Subscription::run(|| stream::unfold((), |()| async { Some((woken().await, ())) }))
+}
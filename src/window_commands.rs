@@ -0,0 +1,232 @@
+//! Window commands
+//!
+//! RPC handlers (and a headless daemon's attach) don't touch the window:
+//! they send a `WindowCommand` down one channel, and the UI applies commands
+//! in order as its subscription delivers them. Only the side applying
+//! commands writes `visible`, so two quick toggles always end where they
+//! started.
+//!
+//! The controller also keeps the saved window geometry, which RPC clients
+//! change and the UI applies whenever the window comes up.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use iced::futures::stream;
+use iced::Subscription;
+use ruty_core::geometry::{self, Anchor, Geometry, Placements};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use crate::native::compositor::{self, Compositor};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowCommand {
+    Show,
+    Hide,
+    Toggle,
+    /// Open the UI on a headless daemon; shows a window that's attached
+    Attach,
+    /// Apply the saved geometry again, since it changed
+    Place,
+    Quit,
+}
+
+impl WindowCommand {
+    /// Whether the window shows once this is applied to one that's
+    /// `visible` (Quit leaves it as is)
+    pub fn visible_after(self, visible: bool) -> bool {
+        match self {
+            Self::Show | Self::Attach => true,
+            Self::Hide => false,
+            Self::Toggle => !visible,
+            Self::Place | Self::Quit => visible,
+        }
+    }
+}
+
+/// Window state shared with the RPC server, and the command channel
+#[derive(Debug)]
+pub struct WindowController {
+    /// Whether the window is showing; written where commands are applied
+    visible: AtomicBool,
+    sender: UnboundedSender<WindowCommand>,
+    /// Receiving end, until whoever applies commands takes it
+    receiver: Mutex<Option<UnboundedReceiver<WindowCommand>>>,
+    placements: Mutex<Placements>,
+    /// `[ui] scale`, which the window's size grows with
+    scale: Mutex<f64>,
+}
+
+/// Where the shown window goes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Placement {
+    pub geometry: Geometry,
+    /// Top-left corner for the UI to move the window to; `None` where the
+    /// compositor placed it already, or the monitor layout is unknown
+    pub position: Option<(i32, i32)>,
+}
+
+impl WindowController {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Self {
+            visible: AtomicBool::new(true),
+            sender,
+            receiver: Mutex::new(Some(receiver)),
+            placements: Mutex::new(Placements::load()),
+            scale: Mutex::new(1.0),
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible.load(Ordering::SeqCst)
+    }
+
+    pub fn set_visible(&self, visible: bool) {
+        self.visible.store(visible, Ordering::SeqCst);
+    }
+
+    pub fn set_scale(&self, scale: f64) {
+        *self.scale.lock().unwrap() = scale;
+    }
+
+    /// Queue a command for the UI
+    pub fn send(&self, command: WindowCommand) {
+        tracing::debug!("Window command: {:?}", command);
+        // The receiver lives as long as the controller, which holds it
+        // until it's taken
+        let _ = self.sender.send(command);
+    }
+
+    /// The commands sent from now on; `None` if someone else has them
+    pub fn take_commands(&self) -> Option<UnboundedReceiver<WindowCommand>> {
+        self.receiver.lock().unwrap().take()
+    }
+
+    /// Hand the commands back for the next taker (the headless daemon does,
+    /// once a window is attached)
+    pub fn return_commands(&self, receiver: UnboundedReceiver<WindowCommand>) {
+        *self.receiver.lock().unwrap() = Some(receiver);
+    }
+
+    /// The monitor the window opens on and its saved geometry; the monitor
+    /// is empty if the layout is unknown. Asks the compositor, so it blocks.
+    pub fn geometry(&self) -> (String, Geometry) {
+        let monitors = Compositor::detect().monitors();
+        let placements = self.placements.lock().unwrap();
+        let monitor = placements.monitor(&monitors);
+        (monitor.map(|m| m.name.clone()).unwrap_or_default(), placements.geometry(monitor))
+    }
+
+    /// Change the geometry saved for `monitor` (the one the window opens on
+    /// if `None`, else it becomes that one) and reapply it. Returns the
+    /// monitor and its new geometry, as for `geometry()`.
+    pub fn set_geometry(
+        &self,
+        monitor: Option<&str>,
+        size: (Option<u32>, Option<u32>),
+        anchor: Option<Anchor>,
+    ) -> Result<(String, Geometry), String> {
+        let (width, height) = size;
+        if width.is_some_and(|w| w < geometry::MIN_WIDTH) || height.is_some_and(|h| h < geometry::MIN_HEIGHT) {
+            return Err(format!("The window must be at least {}x{}", geometry::MIN_WIDTH, geometry::MIN_HEIGHT));
+        }
+        let monitors = Compositor::detect().monitors();
+        let mut placements = self.placements.lock().unwrap();
+        let target = match monitor {
+            Some(name) if monitors.is_empty() => {
+                return Err(format!("Can't find monitor {}: this desktop doesn't report its monitors", name))
+            }
+            Some(name) => Some(monitors.iter().find(|m| m.name == name).ok_or_else(|| {
+                let names: Vec<&str> = monitors.iter().map(|m| m.name.as_str()).collect();
+                format!("No monitor named {} (connected: {})", name, names.join(", "))
+            })?),
+            None => placements.monitor(&monitors),
+        };
+
+        let mut geometry = placements.geometry(target);
+        geometry.width = width.unwrap_or(geometry.width);
+        geometry.height = height.unwrap_or(geometry.height);
+        geometry.anchor = anchor.unwrap_or(geometry.anchor);
+        placements.set(target, geometry, monitor.is_some());
+        placements.save()?;
+        tracing::info!("Window geometry on {:?}: {:?}", target.map(|m| &m.name), geometry);
+        self.send(WindowCommand::Place);
+        Ok((target.map(|m| m.name.clone()).unwrap_or_default(), geometry))
+    }
+
+    /// Work out where the window goes now. Tiling compositors are told to
+    /// put it there; elsewhere the caller moves it. Blocks on compositor IPC.
+    pub fn place(&self) -> Placement {
+        let compositor = Compositor::detect();
+        let monitors = compositor.monitors();
+        let placements = self.placements.lock().unwrap();
+        let monitor = placements.monitor(&monitors);
+        let geometry = placements.geometry(monitor).scaled(*self.scale.lock().unwrap());
+        let position = monitor.map(|m| geometry.position(m));
+        match position {
+            Some(position) if compositor.places_windows() => {
+                compositor::place_window(&compositor, &geometry, position);
+                Placement { geometry, position: None }
+            }
+            _ => Placement { geometry, position },
+        }
+    }
+}
+
+impl Default for WindowController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Commands for the UI, in the order they were sent
+pub fn subscription() -> Subscription<WindowCommand> {
+    Subscription::run(|| {
+        let commands = crate::get_window_controller().and_then(|controller| controller.take_commands());
+        stream::unfold(commands, |commands| async move {
+            let mut commands = commands?;
+            let command = commands.recv().await?;
+            Some((command, Some(commands)))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_after() {
+        assert!(WindowCommand::Toggle.visible_after(false));
+        assert!(!WindowCommand::Toggle.visible_after(true));
+        assert!(WindowCommand::Attach.visible_after(false));
+        assert!(!WindowCommand::Hide.visible_after(true));
+        assert!(WindowCommand::Quit.visible_after(true));
+        assert!(!WindowCommand::Place.visible_after(false));
+    }
+
+    #[test]
+    fn test_commands_arrive_in_order() {
+        let controller = WindowController::new();
+        controller.send(WindowCommand::Toggle);
+        controller.send(WindowCommand::Toggle);
+        controller.send(WindowCommand::Hide);
+
+        let mut commands = controller.take_commands().unwrap();
+        assert!(controller.take_commands().is_none());
+        let mut visible = controller.is_visible();
+        let mut seen = Vec::new();
+        while let Ok(command) = commands.try_recv() {
+            visible = command.visible_after(visible);
+            seen.push((command, visible));
+        }
+        assert_eq!(
+            seen,
+            vec![(WindowCommand::Toggle, false), (WindowCommand::Toggle, true), (WindowCommand::Hide, false)]
+        );
+
+        controller.return_commands(commands);
+        assert!(controller.take_commands().is_some());
+    }
+}
@@ -0,0 +1,223 @@
+//! Shared harness for end-to-end tests
+//!
+//! Spawns `ruty --headless` inside a throwaway XDG environment with fixture
+//! .desktop files, a fake clipboard tool and a mock backend, then talks to
+//! it over gRPC like any other client.
+
+#![allow(dead_code)]
+
+use std::fs;
+use std::net::TcpListener;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use tempfile::TempDir;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
+use tonic::transport::Channel;
+use tonic::{Request, Status};
+use wiremock::MockServer;
+
+pub mod proto {
+    tonic::include_proto!("ruty");
+}
+
+use proto::ruty_service_client::RutyServiceClient;
+use proto::Empty;
+
+/// How long to wait for the daemon to finish indexing
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Text served by the fake `wl-paste`
+pub const CLIPBOARD_TEXT: &str = "ruty fixture clipboard";
+
+/// `[flows]`, `[hooks]` and what they're allowed, for the RunFlow and hook
+/// tests
+const FIXTURE_CONFIG: &str = r#"
+[permissions]
+hooks = ["shell", "network"]
+"flow:fixture" = ["file-read", "shell", "network"]
+"flow:broken" = ["shell", "network"]
+
+[hooks]
+app_launched = ["cat > \"$HOME/../app_launched.json\""]
+
+[flows.fixture]
+steps = [{ query = "Fixture Editor" }, "pick-first", "launch", { shell = "echo opened $RUTY_OUTPUT" }]
+
+[flows.broken]
+steps = [{ shell = "echo no such thing >&2; exit 3" }]
+
+[flows.unasked]
+steps = [{ shell = "touch \"$HOME/../unasked\"" }]
+"#;
+
+/// Adds the daemon's token to each call
+#[derive(Clone)]
+pub struct Authorize(MetadataValue<Ascii>);
+
+impl Interceptor for Authorize {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        request.metadata_mut().insert("authorization", self.0.clone());
+        Ok(request)
+    }
+}
+
+pub type Client = RutyServiceClient<InterceptedService<Channel, Authorize>>;
+
+/// A headless daemon running against fixtures; killed on drop
+pub struct TestDaemon {
+    pub backend: MockServer,
+    pub root: TempDir,
+    port: u16,
+    /// Port of the HTTP API, which the fixture config enables
+    api_port: u16,
+    child: Child,
+}
+
+impl TestDaemon {
+    pub async fn start() -> Self {
+        let backend = MockServer::start().await;
+        let root = tempfile::tempdir().expect("Failed to create temp dir");
+        let home = root.path().join("home");
+        let bin = root.path().join("bin");
+
+        write_desktop_file(
+            &home.join(".local/share/applications"),
+            "ruty-fixture-editor",
+            "Fixture Editor",
+            &format!("touch {}", root.path().join("launched").display()),
+        );
+        fs::create_dir_all(home.join("Documents")).unwrap();
+        fs::write(home.join("Documents/fixture-notes.txt"), "notes").unwrap();
+        write_script(&bin, "wl-paste", &format!("#!/bin/sh\nprintf '{}'\n", CLIPBOARD_TEXT));
+
+        let port = free_port();
+        let api_port = free_port();
+        fs::create_dir_all(home.join(".config/ruty")).unwrap();
+        fs::write(
+            home.join(".config/ruty/config.toml"),
+            format!("[api]\nenabled = true\nport = {}\n{}", api_port, FIXTURE_CONFIG),
+        )
+        .unwrap();
+        let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap_or_default());
+        let child = Command::new(env!("CARGO_BIN_EXE_ruty"))
+            .arg("--headless")
+            .current_dir(root.path())
+            .env("HOME", &home)
+            .env("XDG_CONFIG_HOME", home.join(".config"))
+            .env("XDG_DATA_HOME", home.join(".local/share"))
+            .env("XDG_STATE_HOME", home.join(".local/state"))
+            .env("XDG_DATA_DIRS", root.path().join("share"))
+            .env("PATH", path)
+            .env("RUTY_PORT", port.to_string())
+            .env("RUTY_BACKEND_URL", backend.uri())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Failed to spawn ruty");
+
+        let daemon = Self { backend, root, port, api_port, child };
+        daemon.wait_ready().await;
+        daemon
+    }
+
+    /// Connect a fresh gRPC client
+    pub async fn client(&self) -> Client {
+        self.connect().await.expect("Failed to connect to daemon")
+    }
+
+    /// Connect a gRPC client that doesn't send the token
+    pub async fn unauthorized_client(&self) -> RutyServiceClient<Channel> {
+        RutyServiceClient::connect(format!("http://127.0.0.1:{}", self.port))
+            .await
+            .expect("Failed to connect to daemon")
+    }
+
+    /// The token the daemon created in its state dir
+    pub fn token(&self) -> Option<String> {
+        let token = fs::read_to_string(self.path("home/.local/state/ruty/rpc-token")).ok()?;
+        Some(token.trim().to_string())
+    }
+
+    async fn connect(&self) -> Option<Client> {
+        let bearer = format!("Bearer {}", self.token()?).parse().ok()?;
+        let channel = Channel::from_shared(format!("http://127.0.0.1:{}", self.port)).ok()?.connect().await.ok()?;
+        Some(RutyServiceClient::with_interceptor(channel, Authorize(bearer)))
+    }
+
+    /// URL of an HTTP API endpoint, e.g. `/api/search?q=notes`
+    pub fn api_url(&self, path: &str) -> String {
+        format!("http://127.0.0.1:{}{}", self.api_port, path)
+    }
+
+    /// Whether the daemon process exits within `timeout`
+    pub async fn exits_within(&mut self, timeout: Duration) -> bool {
+        wait_for(timeout, || self.child.try_wait().ok().flatten().is_some()).await
+    }
+
+    /// Path inside the temp root
+    pub fn path(&self, rel: &str) -> PathBuf {
+        self.root.path().join(rel)
+    }
+
+    async fn wait_ready(&self) {
+        let deadline = Instant::now() + STARTUP_TIMEOUT;
+        while Instant::now() < deadline {
+            if let Some(mut client) = self.connect().await {
+                let info = client.get_server_info(Empty {}).await;
+                if info.is_ok_and(|info| info.into_inner().ready) {
+                    return;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("Daemon did not start within {:?}", STARTUP_TIMEOUT);
+    }
+}
+
+impl Drop for TestDaemon {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Poll `check` until it returns true or `timeout` elapses
+pub async fn wait_for(timeout: Duration, mut check: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if check() {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    check()
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|l| l.local_addr())
+        .map(|addr| addr.port())
+        .expect("No free port")
+}
+
+/// Install a desktop file `<id>.desktop` in `dir`
+pub fn write_desktop_file(dir: &Path, id: &str, name: &str, exec: &str) {
+    fs::create_dir_all(dir).unwrap();
+    let content = format!(
+        "[Desktop Entry]\nType=Application\nName={}\nExec={}\nCategories=Development;\n",
+        name, exec
+    );
+    fs::write(dir.join(format!("{}.desktop", id)), content).unwrap();
+}
+
+fn write_script(dir: &Path, name: &str, content: &str) {
+    fs::create_dir_all(dir).unwrap();
+    let path = dir.join(name);
+    fs::write(&path, content).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+}
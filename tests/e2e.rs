@@ -0,0 +1,335 @@
+//! End-to-end tests: drive a headless daemon over gRPC
+
+mod common;
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::time::Duration;
+
+use common::proto::{ChatRequest, ContextRequest, Empty, FlowRequest, LaunchRequest, SearchRequest, WindowGeometry};
+use common::{wait_for, write_desktop_file, TestDaemon, CLIPBOARD_TEXT};
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn search_finds_fixture_app_and_file() {
+    let daemon = TestDaemon::start().await;
+    let mut client = daemon.client().await;
+
+    let apps = client
+        .search(SearchRequest { query: "Fixture Editor".into(), limit: 0 })
+        .await
+        .unwrap()
+        .into_inner()
+        .results;
+    let app = apps.first().expect("no results");
+    assert_eq!(app.id, "ruty-fixture-editor");
+    assert_eq!(app.category, "app");
+
+    let files = client
+        .search(SearchRequest { query: "fixture-notes".into(), limit: 5 })
+        .await
+        .unwrap()
+        .into_inner()
+        .results;
+    assert!(files
+        .iter()
+        .any(|r| r.category == "file" && r.subtitle.ends_with("Documents/fixture-notes.txt")));
+}
+
+#[tokio::test]
+async fn calls_without_the_token_are_refused() {
+    let daemon = TestDaemon::start().await;
+    let token = daemon.path("home/.local/state/ruty/rpc-token");
+    assert_eq!(fs::metadata(&token).unwrap().permissions().mode() & 0o777, 0o600);
+
+    let err = daemon.unauthorized_client().await.ping(Empty {}).await.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::Unauthenticated);
+    daemon.client().await.ping(Empty {}).await.unwrap();
+}
+
+#[tokio::test]
+async fn installed_apps_are_picked_up() {
+    let daemon = TestDaemon::start().await;
+    let mut client = daemon.client().await;
+    let applications = daemon.path("home/.local/share/applications");
+    write_desktop_file(&applications, "ruty-fixture-viewer", "Fixture Viewer", "true");
+    fs::remove_file(applications.join("ruty-fixture-editor.desktop")).unwrap();
+
+    let mut found = Vec::new();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(15);
+    while tokio::time::Instant::now() < deadline {
+        let request = SearchRequest { query: "Fixture".into(), limit: 0 };
+        let results = client.search(request).await.unwrap().into_inner().results;
+        found = results.into_iter().filter(|r| r.category == "app").map(|r| r.id).collect();
+        if found == ["ruty-fixture-viewer"] {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+    assert_eq!(found, ["ruty-fixture-viewer"]);
+}
+
+#[tokio::test]
+async fn config_edits_apply_live() {
+    let daemon = TestDaemon::start().await;
+    let mut client = daemon.client().await;
+    let config = daemon.path("home/.config/ruty/config.toml");
+    let mut content = fs::read_to_string(&config).unwrap();
+    content.push_str("\n[apps]\nhidden = [\"ruty-fixture-editor\"]\n");
+    fs::write(&config, content).unwrap();
+
+    let mut found = vec![String::new()];
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    while tokio::time::Instant::now() < deadline {
+        let request = SearchRequest { query: "Fixture Editor".into(), limit: 0 };
+        let results = client.search(request).await.unwrap().into_inner().results;
+        found = results.into_iter().filter(|r| r.category == "app").map(|r| r.id).collect();
+        if found.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+    assert!(found.is_empty(), "still listed: {:?}", found);
+}
+
+#[tokio::test]
+async fn launch_runs_desktop_exec() {
+    let daemon = TestDaemon::start().await;
+    let mut client = daemon.client().await;
+
+    client
+        .launch(LaunchRequest { id: "ruty-fixture-editor".into(), category: "app".into() })
+        .await
+        .unwrap();
+
+    let marker = daemon.path("launched");
+    assert!(wait_for(Duration::from_secs(5), || marker.exists()).await);
+
+    let err = client
+        .launch(LaunchRequest { id: "missing".into(), category: "app".into() })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+}
+
+#[tokio::test]
+async fn clipboard_history_is_captured() {
+    let daemon = TestDaemon::start().await;
+    let mut client = daemon.client().await;
+
+    let mut found = false;
+    for _ in 0..50 {
+        let history = client.get_clipboard_history(Empty {}).await.unwrap().into_inner();
+        if history.items.iter().any(|item| item.content == CLIPBOARD_TEXT) {
+            found = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(found, "clipboard text never showed up in history");
+}
+
+#[tokio::test]
+async fn chat_goes_through_backend() {
+    let daemon = TestDaemon::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat"))
+        // Nobody is at the launcher to approve run_shell or open_url
+        .and(body_partial_json(serde_json::json!({ "message": "hello", "session_id": "rpc", "tool_approval": "deny" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "response": "hi there",
+            "tools_used": ["web_search"],
+            "session_id": "rpc",
+        })))
+        .expect(1)
+        .mount(&daemon.backend)
+        .await;
+
+    let mut client = daemon.client().await;
+    let reply = client
+        .chat(ChatRequest { message: "hello".into(), session_id: String::new() })
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(reply.response, "hi there");
+    assert_eq!(reply.tools_used, vec!["web_search".to_string()]);
+}
+
+#[tokio::test]
+async fn context_load_goes_through_backend() {
+    let daemon = TestDaemon::start().await;
+    Mock::given(method("POST"))
+        .and(path("/context/load"))
+        .and(body_partial_json(serde_json::json!({ "session_id": "s1", "path": "/tmp/project" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "success": true,
+            "files_loaded": 3,
+            "message": "Loaded 3 files",
+        })))
+        .expect(1)
+        .mount(&daemon.backend)
+        .await;
+
+    let mut client = daemon.client().await;
+    let reply = client
+        .load_context(ContextRequest { path: "/tmp/project".into(), session_id: "s1".into() })
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert!(reply.success);
+    assert_eq!(reply.message, "Loaded 3 files");
+}
+
+#[tokio::test]
+async fn backend_down_is_reported() {
+    let daemon = TestDaemon::start().await;
+    let mut client = daemon.client().await;
+
+    // No mock mounted: wiremock answers 404, which isn't a valid chat reply
+    let err = client
+        .chat(ChatRequest { message: "hello".into(), session_id: String::new() })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::Unavailable);
+}
+
+#[tokio::test]
+async fn http_api_searches_and_launches() {
+    let daemon = TestDaemon::start().await;
+    let http = reqwest::Client::new();
+    let bearer = format!("Bearer {}", daemon.token().unwrap());
+
+    let search = || http.get(daemon.api_url("/api/search?q=Fixture%20Editor"));
+    assert_eq!(search().send().await.unwrap().status(), reqwest::StatusCode::UNAUTHORIZED);
+    let body: serde_json::Value = search()
+        .header("Authorization", &bearer)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let app = &body["results"][0];
+    assert_eq!((app["id"].as_str(), app["category"].as_str()), (Some("ruty-fixture-editor"), Some("app")));
+
+    let execute = |body: serde_json::Value| http.post(daemon.api_url("/api/execute")).json(&body);
+    let fixture = serde_json::json!({ "id": "ruty-fixture-editor", "category": "app" });
+
+    let anonymous = execute(fixture.clone()).send().await.unwrap();
+    assert_eq!(anonymous.status(), reqwest::StatusCode::UNAUTHORIZED);
+    // A rebound domain resolving to 127.0.0.1 still sends its own name
+    let rebound = execute(fixture.clone()).header("Host", "attacker.example").bearer_auth(&bearer).send().await;
+    assert_eq!(rebound.unwrap().status(), reqwest::StatusCode::FORBIDDEN);
+    let marker = daemon.path("launched");
+    assert!(!marker.exists());
+
+    let launched = execute(fixture).header("Authorization", &bearer).send().await.unwrap();
+    assert_eq!(launched.status(), reqwest::StatusCode::NO_CONTENT);
+    assert!(wait_for(Duration::from_secs(5), || marker.exists()).await);
+
+    let unknown = execute(serde_json::json!({ "id": "x", "category": "spaceship" }))
+        .header("Authorization", &bearer)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(unknown.status(), reqwest::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn flow_runs_its_steps() {
+    let daemon = TestDaemon::start().await;
+    let mut client = daemon.client().await;
+
+    let reply = client.run_flow(FlowRequest { name: "fixture".into() }).await.unwrap().into_inner();
+    assert_eq!(reply.output, "opened ruty-fixture-editor");
+    let marker = daemon.path("launched");
+    assert!(wait_for(Duration::from_secs(5), || marker.exists()).await);
+
+    // The flow, its launch and its shell step are in the audit log
+    let audit = daemon.path("home/.local/share/ruty/audit.jsonl");
+    let logged = |kind: &str| {
+        let kind = format!(r#""kind":"{}""#, kind);
+        std::fs::read_to_string(&audit).is_ok_and(|log| log.contains(&kind))
+    };
+    assert!(wait_for(Duration::from_secs(5), || logged("launch")).await);
+    assert!(logged("flow") && logged("shell"));
+
+    let err = client.run_flow(FlowRequest { name: "broken".into() }).await.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::Aborted);
+    assert!(err.message().contains("no such thing"), "{}", err.message());
+
+    let err = client.run_flow(FlowRequest { name: "missing".into() }).await.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::NotFound);
+
+    // Not allowed to run shell commands yet, so it doesn't
+    let err = client.run_flow(FlowRequest { name: "unasked".into() }).await.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::PermissionDenied);
+    assert!(err.message().contains("run shell commands"), "{}", err.message());
+    assert!(!daemon.path("unasked").exists());
+}
+
+#[tokio::test]
+async fn launch_runs_app_launched_hook() {
+    let daemon = TestDaemon::start().await;
+    let mut client = daemon.client().await;
+
+    client
+        .launch(LaunchRequest { id: "ruty-fixture-editor".into(), category: "app".into() })
+        .await
+        .unwrap();
+
+    // The hook is done once its JSON parses (cat may still be writing)
+    let hooked = daemon.path("app_launched.json");
+    let read = || {
+        std::fs::read_to_string(&hooked)
+            .ok()
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+    };
+    assert!(wait_for(Duration::from_secs(5), || read().is_some()).await);
+    let event = read().unwrap();
+    assert_eq!(event["event"], "app_launched");
+    assert_eq!(event["id"], "ruty-fixture-editor");
+    assert_eq!(event["name"], "Fixture Editor");
+}
+
+#[tokio::test]
+async fn window_geometry_is_saved() {
+    let daemon = TestDaemon::start().await;
+    let mut client = daemon.client().await;
+
+    let initial = client.get_window_geometry(Empty {}).await.unwrap().into_inner();
+    assert_eq!((initial.width, initial.height, initial.anchor.as_str()), (700, 400, "center"));
+
+    let request = WindowGeometry { width: 900, anchor: "top".into(), ..Default::default() };
+    let set = client.set_window_geometry(request).await.unwrap().into_inner();
+    assert_eq!((set.width, set.height, set.anchor.as_str()), (900, 400, "top"));
+    assert_eq!(client.get_window_geometry(Empty {}).await.unwrap().into_inner(), set);
+    assert!(daemon.path("home/.local/state/ruty/geometry.json").exists());
+
+    for bad in [
+        WindowGeometry { anchor: "left".into(), ..Default::default() },
+        WindowGeometry { width: 20, ..Default::default() },
+        WindowGeometry { monitor: "NO-SUCH-OUTPUT".into(), ..Default::default() },
+    ] {
+        let err = client.set_window_geometry(bad).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+}
+
+#[tokio::test]
+async fn headless_daemon_ignores_toggles_and_quits() {
+    let mut daemon = TestDaemon::start().await;
+    let mut client = daemon.client().await;
+
+    // No window to show until one is attached
+    client.toggle_window(Empty {}).await.unwrap();
+    client.show_window(Empty {}).await.unwrap();
+    assert!(!client.get_window_state(Empty {}).await.unwrap().into_inner().visible);
+
+    // The daemon may be gone before the reply makes it out
+    let _ = client.quit(Empty {}).await;
+    assert!(daemon.exits_within(Duration::from_secs(5)).await);
+}
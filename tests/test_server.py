@@ -0,0 +1,47 @@
+"""Backend API tests (python -m unittest discover tests)"""
+import os
+import tempfile
+import unittest
+from pathlib import Path
+from unittest import mock
+
+from fastapi.testclient import TestClient
+
+from ruty.server import app
+
+
+class ToolCallAuthTest(unittest.TestCase):
+    def setUp(self):
+        state = tempfile.TemporaryDirectory()
+        self.addCleanup(state.cleanup)
+        patched = mock.patch.dict(os.environ, {"XDG_STATE_HOME": state.name})
+        patched.start()
+        self.addCleanup(patched.stop)
+
+        token = Path(state.name) / "ruty" / "rpc-token"
+        token.parent.mkdir()
+        token.write_text("secret")
+        token.chmod(0o600)
+        self.client = TestClient(app)
+
+    def test_unauthenticated_calls_are_rejected(self):
+        decision = {"approved": True}
+        self.assertEqual(self.client.post("/tool-calls/abc", json=decision).status_code, 401)
+        wrong = {"Authorization": "Bearer guess"}
+        self.assertEqual(self.client.post("/tool-calls/abc", json=decision, headers=wrong).status_code, 401)
+        self.assertEqual(self.client.get("/tool-calls/proposed").status_code, 401)
+
+    def test_authenticated_calls_go_through(self):
+        headers = {"Authorization": "Bearer secret"}
+        self.assertEqual(self.client.get("/tool-calls/proposed", headers=headers).json(), {"calls": []})
+        # Authorized, but nothing is waiting under that id
+        self.assertEqual(self.client.post("/tool-calls/abc", json={"approved": True}, headers=headers).status_code, 404)
+
+    def test_token_readable_by_others_is_refused(self):
+        (Path(os.environ["XDG_STATE_HOME"]) / "ruty" / "rpc-token").chmod(0o644)
+        headers = {"Authorization": "Bearer secret"}
+        self.assertEqual(self.client.get("/tool-calls/proposed", headers=headers).status_code, 401)
+
+
+if __name__ == "__main__":
+    unittest.main()